@@ -1,18 +1,34 @@
 use crate::handle::AssetHandle;
-use crate::loader::{load_json, TextureData};
+use crate::import_settings::{import_settings_path, TextureImportSettings};
+use crate::loader::{
+    load_json, parse_tiled_map, parse_tiled_tileset, BitmapFont, FontData, RenderTextureDesc, TextureData, TiledMap,
+    TiledTileSetSource,
+};
 use crate::source::AssetSource;
 use crate::registry::AssetRegistry;
-use longhorn_core::AssetId;
+use longhorn_core::{
+    binary_scene_path, AnimationClip, AssetId, Material, NavMesh, ParticleEffect, Scene, SceneFormat, StateChart, TileSet, Tilemap,
+};
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 /// Manages loading and caching of assets
 pub struct AssetManager<S: AssetSource> {
     source: S,
     texture_cache: HashMap<String, (AssetId, TextureData)>,
+    texture_settings: HashMap<AssetId, TextureImportSettings>,
+    /// Bumped whenever a texture's pixels change after it was first loaded
+    texture_versions: HashMap<AssetId, u64>,
+    font_cache: HashMap<String, (AssetId, Arc<FontData>)>,
+    tileset_cache: HashMap<String, (AssetId, Arc<TileSet>)>,
+    material_cache: HashMap<String, (AssetId, Arc<Material>)>,
+    animation_cache: HashMap<String, (AssetId, Arc<AnimationClip>)>,
+    state_chart_cache: HashMap<String, (AssetId, Arc<StateChart>)>,
+    navmesh_cache: HashMap<String, (AssetId, Arc<NavMesh>)>,
     json_cache: HashMap<String, (AssetId, Vec<u8>)>,
     next_id: AtomicU64,
     registry: AssetRegistry,
@@ -35,6 +51,14 @@ impl<S: AssetSource> AssetManager<S> {
         Self {
             source,
             texture_cache: HashMap::new(),
+            texture_settings: HashMap::new(),
+            texture_versions: HashMap::new(),
+            font_cache: HashMap::new(),
+            tileset_cache: HashMap::new(),
+            material_cache: HashMap::new(),
+            animation_cache: HashMap::new(),
+            state_chart_cache: HashMap::new(),
+            navmesh_cache: HashMap::new(),
             json_cache: HashMap::new(),
             next_id: AtomicU64::new(initial_next_id),
             registry,
@@ -47,6 +71,11 @@ impl<S: AssetSource> AssetManager<S> {
         AssetId::new(self.next_id.fetch_add(1, Ordering::Relaxed))
     }
 
+    /// Reserve an asset ID for an asset generated at runtime (see `insert_texture`)
+    pub fn reserve_asset_id(&self) -> AssetId {
+        self.next_id()
+    }
+
     /// Load a texture from the given path (cached)
     pub fn load_texture(&mut self, path: &str) -> io::Result<AssetHandle<TextureData>> {
         // Check if already cached
@@ -55,12 +84,12 @@ impl<S: AssetSource> AssetManager<S> {
         }
 
         // Load from source
-        let bytes = self.source.load_bytes(path)?;
-        let texture_data = TextureData::from_bytes(&bytes)?;
+        let (texture_data, settings) = self.read_texture(path)?;
 
         // Cache it - reuse registry ID if it exists, otherwise generate new one
         let id = self.registry.get_id(path).unwrap_or_else(|| self.next_id());
         self.texture_cache.insert(path.to_string(), (id, texture_data));
+        self.texture_settings.insert(id, settings);
 
         Ok(AssetHandle::new(id))
     }
@@ -89,11 +118,12 @@ impl<S: AssetSource> AssetManager<S> {
         })?;
 
         // Load the texture from the path
-        let bytes = self.source.load_bytes(path)?;
-        let texture_data = TextureData::from_bytes(&bytes)?;
+        let path = path.to_string();
+        let (texture_data, settings) = self.read_texture(&path)?;
 
         // Cache it with the existing asset ID (not a new one)
-        self.texture_cache.insert(path.to_string(), (asset_id, texture_data));
+        self.texture_cache.insert(path, (asset_id, texture_data));
+        self.texture_settings.insert(asset_id, settings);
 
         Ok(AssetHandle::new(asset_id))
     }
@@ -108,6 +138,521 @@ impl<S: AssetSource> AssetManager<S> {
         self.texture_cache.get(path).map(|(_, data)| data)
     }
 
+    /// Decode a texture and apply its import settings
+    fn read_texture(&self, path: &str) -> io::Result<(TextureData, TextureImportSettings)> {
+        let settings = self.load_texture_settings(path)?;
+        let bytes = self.source.load_bytes(path)?;
+        let texture_data = if RenderTextureDesc::is_render_texture(path) {
+            load_json::<RenderTextureDesc>(&bytes)?.blank()
+        } else {
+            settings.apply(TextureData::from_bytes(&bytes)?)
+        };
+        Ok((texture_data, settings))
+    }
+
+    /// Read the import settings stored next to a texture
+    ///
+    /// Textures without a settings file use the defaults.
+    pub fn load_texture_settings(&self, path: &str) -> io::Result<TextureImportSettings> {
+        let settings_path = import_settings_path(path);
+        if !self.source.exists(&settings_path) {
+            return Ok(TextureImportSettings::default());
+        }
+        TextureImportSettings::from_json(&self.source.load_bytes(&settings_path)?)
+    }
+
+    /// Get the import settings a loaded texture was imported with
+    pub fn texture_settings(&self, asset_id: AssetId) -> TextureImportSettings {
+        self.texture_settings.get(&asset_id).copied().unwrap_or_default()
+    }
+
+    /// Save the import settings for a texture and re-import it if loaded
+    ///
+    /// Settings are written to `<path>.import.json` in the project.
+    /// Renderers holding an uploaded copy of the texture must re-upload it.
+    pub fn set_texture_settings(&mut self, path: &str, settings: TextureImportSettings) -> io::Result<()> {
+        let settings_path = self.project_root.join(import_settings_path(path));
+        if settings == TextureImportSettings::default() {
+            if settings_path.exists() {
+                std::fs::remove_file(&settings_path)?;
+            }
+        } else {
+            std::fs::write(&settings_path, settings.to_json()?)?;
+        }
+
+        if let Some(&(id, _)) = self.texture_cache.get(path) {
+            let (texture_data, settings) = self.read_texture(path)?;
+            self.texture_cache.insert(path.to_string(), (id, texture_data));
+            self.texture_settings.insert(id, settings);
+            *self.texture_versions.entry(id).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    /// Add or replace a texture generated at runtime, such as a glyph atlas
+    ///
+    /// `key` names the texture in the cache in place of a file path; use an ID
+    /// from `reserve_asset_id`. Replacing a texture bumps its version so
+    /// renderers upload the new pixels.
+    pub fn insert_texture(
+        &mut self,
+        key: &str,
+        asset_id: AssetId,
+        texture_data: TextureData,
+        settings: TextureImportSettings,
+    ) {
+        if self.texture_cache.insert(key.to_string(), (asset_id, texture_data)).is_some() {
+            *self.texture_versions.entry(asset_id).or_default() += 1;
+        }
+        self.texture_settings.insert(asset_id, settings);
+    }
+
+    /// How many times a texture's pixels changed since it was first loaded
+    ///
+    /// Renderers compare this against the version they uploaded.
+    pub fn texture_version(&self, asset_id: AssetId) -> u64 {
+        self.texture_versions.get(&asset_id).copied().unwrap_or(0)
+    }
+
+    /// Load a font from the given path (cached)
+    ///
+    /// `.fnt` files are BMFont text-format bitmap fonts whose page images are
+    /// loaded as textures from the font's folder; anything else is read as a
+    /// TrueType/OpenType font.
+    pub fn load_font(&mut self, path: &str) -> io::Result<AssetHandle<FontData>> {
+        if let Some((id, _)) = self.font_cache.get(path) {
+            return Ok(AssetHandle::new(*id));
+        }
+
+        let font = self.read_font(path)?;
+        let id = self.registry.get_id(path).unwrap_or_else(|| self.next_id());
+        self.font_cache.insert(path.to_string(), (id, Arc::new(font)));
+        Ok(AssetHandle::new(id))
+    }
+
+    /// Load a font by its AssetId (looks up path in registry)
+    pub fn load_font_by_id(&mut self, asset_id: AssetId) -> io::Result<AssetHandle<FontData>> {
+        if self.font_cache.values().any(|(id, _)| *id == asset_id) {
+            return Ok(AssetHandle::new(asset_id));
+        }
+
+        let path = self.registry.get_path(asset_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Asset ID {:?} not found in registry", asset_id),
+            )
+        })?;
+
+        let path = path.to_string();
+        let font = self.read_font(&path)?;
+        self.font_cache.insert(path, (asset_id, Arc::new(font)));
+        Ok(AssetHandle::new(asset_id))
+    }
+
+    /// Get a font by its handle
+    pub fn get_font(&self, handle: AssetHandle<FontData>) -> Option<Arc<FontData>> {
+        self.font_cache
+            .values()
+            .find(|(id, _)| *id == handle.id())
+            .map(|(_, font)| Arc::clone(font))
+    }
+
+    /// Decode a font, loading the page textures of bitmap fonts
+    fn read_font(&mut self, path: &str) -> io::Result<FontData> {
+        let bytes = self.source.load_bytes(path)?;
+        if !path.ends_with(".fnt") {
+            return FontData::from_ttf_bytes(bytes);
+        }
+
+        let source = String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut font = BitmapFont::parse(&source)?;
+        for page in &font.pages {
+            let handle = self.load_texture(&relative_path(path, page))?;
+            font.page_textures.push(handle.id());
+        }
+        Ok(FontData::Bitmap(font))
+    }
+
+    /// Load a tile set from the given path (cached)
+    ///
+    /// `.tsj` files are Tiled tile sets and a `.tmj` path loads the tile set
+    /// embedded in that Tiled map; anything else is read as a JSON `TileSet`.
+    /// The tile set image is loaded as a texture relative to the file.
+    pub fn load_tileset(&mut self, path: &str) -> io::Result<AssetHandle<TileSet>> {
+        if let Some((id, _)) = self.tileset_cache.get(path) {
+            return Ok(AssetHandle::new(*id));
+        }
+
+        let tileset = self.read_tileset(path)?;
+        let id = self.registry.get_id(path).unwrap_or_else(|| self.next_id());
+        self.tileset_cache.insert(path.to_string(), (id, Arc::new(tileset)));
+        Ok(AssetHandle::new(id))
+    }
+
+    /// Load a tile set by its AssetId (looks up path in registry)
+    pub fn load_tileset_by_id(&mut self, asset_id: AssetId) -> io::Result<AssetHandle<TileSet>> {
+        if self.tileset_cache.values().any(|(id, _)| *id == asset_id) {
+            return Ok(AssetHandle::new(asset_id));
+        }
+
+        let path = self.registry.get_path(asset_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Asset ID {:?} not found in registry", asset_id),
+            )
+        })?;
+
+        let path = path.to_string();
+        let tileset = self.read_tileset(&path)?;
+        self.tileset_cache.insert(path, (asset_id, Arc::new(tileset)));
+        Ok(AssetHandle::new(asset_id))
+    }
+
+    /// Get a tile set by its handle
+    pub fn get_tileset(&self, handle: AssetHandle<TileSet>) -> Option<Arc<TileSet>> {
+        self.tileset_cache
+            .values()
+            .find(|(id, _)| *id == handle.id())
+            .map(|(_, tileset)| Arc::clone(tileset))
+    }
+
+    /// Decode a tile set and slice its image
+    fn read_tileset(&mut self, path: &str) -> io::Result<TileSet> {
+        let bytes = self.source.load_bytes(path)?;
+        let mut tileset = if path.ends_with(".tsj") {
+            parse_tiled_tileset(&bytes)?
+        } else if path.ends_with(".tmj") {
+            match parse_tiled_map(&bytes)?.tileset {
+                TiledTileSetSource::Embedded(tileset) => tileset,
+                TiledTileSetSource::External(source) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{} has no embedded tile set (it uses {})", path, source),
+                    ))
+                }
+            }
+        } else {
+            load_json(&bytes)?
+        };
+
+        let handle = self.load_texture(&relative_path(path, &tileset.image))?;
+        let (width, height) = self
+            .get_texture(handle.clone())
+            .map(|texture| (texture.width, texture.height))
+            .unwrap_or_default();
+        tileset.texture = handle.id();
+        tileset.slice(width, height);
+        Ok(tileset)
+    }
+
+    /// Import a Tiled map (`.tmj`) as a `Tilemap`, loading its tile set
+    ///
+    /// External tile sets are loaded from their `.tsj` file; an embedded
+    /// tile set is loaded under the map's own path, so scenes saving the
+    /// tilemap refer to the map file.
+    pub fn import_tiled_map(&mut self, path: &str) -> io::Result<Tilemap> {
+        let TiledMap { mut tilemap, tileset } = parse_tiled_map(&self.source.load_bytes(path)?)?;
+        let tileset_path = match tileset {
+            TiledTileSetSource::External(source) => relative_path(path, &source),
+            TiledTileSetSource::Embedded(_) => path.to_string(),
+        };
+        tilemap.tileset = self.load_tileset(&tileset_path)?.id();
+        Ok(tilemap)
+    }
+
+    /// Load a material (`.material`) from the given path (cached)
+    ///
+    /// The shader file and texture images are read relative to the material.
+    /// The shader itself is compiled by the renderer when first drawn.
+    pub fn load_material(&mut self, path: &str) -> io::Result<AssetHandle<Material>> {
+        if let Some((id, _)) = self.material_cache.get(path) {
+            return Ok(AssetHandle::new(*id));
+        }
+
+        let material = self.read_material(path)?;
+        let id = self.registry.get_id(path).unwrap_or_else(|| self.next_id());
+        self.material_cache.insert(path.to_string(), (id, Arc::new(material)));
+        Ok(AssetHandle::new(id))
+    }
+
+    /// Load a material by its AssetId (looks up path in registry)
+    pub fn load_material_by_id(&mut self, asset_id: AssetId) -> io::Result<AssetHandle<Material>> {
+        if self.material_cache.values().any(|(id, _)| *id == asset_id) {
+            return Ok(AssetHandle::new(asset_id));
+        }
+
+        let path = self.registry.get_path(asset_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Asset ID {:?} not found in registry", asset_id),
+            )
+        })?;
+
+        let path = path.to_string();
+        let material = self.read_material(&path)?;
+        self.material_cache.insert(path, (asset_id, Arc::new(material)));
+        Ok(AssetHandle::new(asset_id))
+    }
+
+    /// Read a loaded material and its shader from disk again, keeping its ID
+    ///
+    /// Renderers rebuild the material's pipeline when its shader changed.
+    /// On error the previously loaded material stays in use.
+    pub fn reload_material(&mut self, path: &str) -> io::Result<AssetHandle<Material>> {
+        let Some((id, _)) = self.material_cache.get(path) else {
+            return self.load_material(path);
+        };
+        let id = *id;
+        let material = self.read_material(path)?;
+        self.material_cache.insert(path.to_string(), (id, Arc::new(material)));
+        Ok(AssetHandle::new(id))
+    }
+
+    /// Get a material by its handle
+    pub fn get_material(&self, handle: AssetHandle<Material>) -> Option<Arc<Material>> {
+        self.material_cache
+            .values()
+            .find(|(id, _)| *id == handle.id())
+            .map(|(_, material)| Arc::clone(material))
+    }
+
+    /// Get the path a material was loaded from
+    pub fn material_path(&self, id: AssetId) -> Option<&str> {
+        self.material_cache
+            .iter()
+            .find(|(_, (material_id, _))| *material_id == id)
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// Decode a material, reading its shader and loading its textures
+    fn read_material(&mut self, path: &str) -> io::Result<Material> {
+        let mut material: Material = load_json(&self.source.load_bytes(path)?)?;
+        if !material.shader.is_empty() {
+            let bytes = self.source.load_bytes(&relative_path(path, &material.shader))?;
+            material.source = String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        for texture in &mut material.textures {
+            texture.texture = self.load_texture(&relative_path(path, &texture.image))?.id();
+        }
+        Ok(material)
+    }
+
+    /// Load an animation clip (`.anim`) from the given path (cached)
+    pub fn load_animation_clip(&mut self, path: &str) -> io::Result<AssetHandle<AnimationClip>> {
+        if let Some((id, _)) = self.animation_cache.get(path) {
+            return Ok(AssetHandle::new(*id));
+        }
+
+        let clip: AnimationClip = load_json(&self.source.load_bytes(path)?)?;
+        let id = self.registry.get_id(path).unwrap_or_else(|| self.next_id());
+        self.animation_cache.insert(path.to_string(), (id, Arc::new(clip)));
+        Ok(AssetHandle::new(id))
+    }
+
+    /// Load an animation clip by its AssetId (looks up path in registry)
+    pub fn load_animation_clip_by_id(&mut self, asset_id: AssetId) -> io::Result<AssetHandle<AnimationClip>> {
+        if self.animation_cache.values().any(|(id, _)| *id == asset_id) {
+            return Ok(AssetHandle::new(asset_id));
+        }
+
+        let path = self.registry.get_path(asset_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Asset ID {:?} not found in registry", asset_id),
+            )
+        })?;
+
+        let path = path.to_string();
+        let clip: AnimationClip = load_json(&self.source.load_bytes(&path)?)?;
+        self.animation_cache.insert(path, (asset_id, Arc::new(clip)));
+        Ok(AssetHandle::new(asset_id))
+    }
+
+    /// Get an animation clip by its handle
+    pub fn get_animation_clip(&self, handle: AssetHandle<AnimationClip>) -> Option<Arc<AnimationClip>> {
+        self.animation_cache
+            .values()
+            .find(|(id, _)| *id == handle.id())
+            .map(|(_, clip)| Arc::clone(clip))
+    }
+
+    /// Get the path an animation clip was loaded from
+    pub fn animation_clip_path(&self, id: AssetId) -> Option<&str> {
+        self.animation_cache
+            .iter()
+            .find(|(_, (clip_id, _))| *clip_id == id)
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// Save an animation clip into the project and register it
+    ///
+    /// Replaces any cached copy, so players pick up the saved keys.
+    ///
+    /// # Returns
+    /// The AssetId players refer to the clip by
+    pub fn save_animation_clip(&mut self, path: &str, clip: &AnimationClip) -> io::Result<AssetId> {
+        let bytes = serde_json::to_vec_pretty(clip).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let dest_path = self.project_root.join(path);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest_path, &bytes)?;
+
+        let asset_id = match self.animation_cache.get(path) {
+            Some(&(id, _)) => id,
+            None => self.registry.register(path),
+        };
+        self.save_registry()?;
+        self.animation_cache.insert(path.to_string(), (asset_id, Arc::new(clip.clone())));
+        Ok(asset_id)
+    }
+
+    /// Load a state chart (`.fsm`) from the given path (cached)
+    ///
+    /// Charts whose transitions or initial states name missing states are
+    /// rejected as invalid data.
+    pub fn load_state_chart(&mut self, path: &str) -> io::Result<AssetHandle<StateChart>> {
+        if let Some((id, _)) = self.state_chart_cache.get(path) {
+            return Ok(AssetHandle::new(*id));
+        }
+
+        let chart = load_state_chart(&self.source.load_bytes(path)?)?;
+        let id = self.registry.get_id(path).unwrap_or_else(|| self.next_id());
+        self.state_chart_cache.insert(path.to_string(), (id, Arc::new(chart)));
+        Ok(AssetHandle::new(id))
+    }
+
+    /// Load a state chart by its AssetId (looks up path in registry)
+    pub fn load_state_chart_by_id(&mut self, asset_id: AssetId) -> io::Result<AssetHandle<StateChart>> {
+        if self.state_chart_cache.values().any(|(id, _)| *id == asset_id) {
+            return Ok(AssetHandle::new(asset_id));
+        }
+
+        let path = self.registry.get_path(asset_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Asset ID {:?} not found in registry", asset_id),
+            )
+        })?;
+
+        let path = path.to_string();
+        let chart = load_state_chart(&self.source.load_bytes(&path)?)?;
+        self.state_chart_cache.insert(path, (asset_id, Arc::new(chart)));
+        Ok(AssetHandle::new(asset_id))
+    }
+
+    /// Get a state chart by its handle
+    pub fn get_state_chart(&self, handle: AssetHandle<StateChart>) -> Option<Arc<StateChart>> {
+        self.state_chart_cache
+            .values()
+            .find(|(id, _)| *id == handle.id())
+            .map(|(_, chart)| Arc::clone(chart))
+    }
+
+    /// Get the path a state chart was loaded from
+    pub fn state_chart_path(&self, id: AssetId) -> Option<&str> {
+        self.state_chart_cache
+            .iter()
+            .find(|(_, (chart_id, _))| *chart_id == id)
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// Save a state chart into the project and register it
+    ///
+    /// Replaces any cached copy, so machines follow the saved chart.
+    ///
+    /// # Returns
+    /// The AssetId machines refer to the chart by
+    pub fn save_state_chart(&mut self, path: &str, chart: &StateChart) -> io::Result<AssetId> {
+        chart.validate().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let bytes = serde_json::to_vec_pretty(chart).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let dest_path = self.project_root.join(path);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest_path, &bytes)?;
+
+        let asset_id = match self.state_chart_cache.get(path) {
+            Some(&(id, _)) => id,
+            None => self.registry.register(path),
+        };
+        self.save_registry()?;
+        self.state_chart_cache.insert(path.to_string(), (asset_id, Arc::new(chart.clone())));
+        Ok(asset_id)
+    }
+
+    /// Load a baked navmesh (`.navmesh`) from the given path (cached)
+    ///
+    /// Meshes with polygons that aren't convex are rejected as invalid data.
+    pub fn load_navmesh(&mut self, path: &str) -> io::Result<AssetHandle<NavMesh>> {
+        if let Some((id, _)) = self.navmesh_cache.get(path) {
+            return Ok(AssetHandle::new(*id));
+        }
+
+        let mesh: NavMesh = load_json(&self.source.load_bytes(path)?)?;
+        let id = self.registry.get_id(path).unwrap_or_else(|| self.next_id());
+        self.navmesh_cache.insert(path.to_string(), (id, Arc::new(mesh)));
+        Ok(AssetHandle::new(id))
+    }
+
+    /// Load a navmesh by its AssetId (looks up path in registry)
+    pub fn load_navmesh_by_id(&mut self, asset_id: AssetId) -> io::Result<AssetHandle<NavMesh>> {
+        if self.navmesh_cache.values().any(|(id, _)| *id == asset_id) {
+            return Ok(AssetHandle::new(asset_id));
+        }
+
+        let path = self.registry.get_path(asset_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Asset ID {:?} not found in registry", asset_id),
+            )
+        })?;
+
+        let path = path.to_string();
+        let mesh: NavMesh = load_json(&self.source.load_bytes(&path)?)?;
+        self.navmesh_cache.insert(path, (asset_id, Arc::new(mesh)));
+        Ok(AssetHandle::new(asset_id))
+    }
+
+    /// Get a navmesh by its handle
+    pub fn get_navmesh(&self, handle: AssetHandle<NavMesh>) -> Option<Arc<NavMesh>> {
+        self.navmesh_cache
+            .values()
+            .find(|(id, _)| *id == handle.id())
+            .map(|(_, mesh)| Arc::clone(mesh))
+    }
+
+    /// Get the path a navmesh was loaded from
+    pub fn navmesh_path(&self, id: AssetId) -> Option<&str> {
+        self.navmesh_cache
+            .iter()
+            .find(|(_, (mesh_id, _))| *mesh_id == id)
+            .map(|(path, _)| path.as_str())
+    }
+
+    /// Save a baked navmesh into the project and register it
+    ///
+    /// Replaces any cached copy, so regions pick up the new bake.
+    ///
+    /// # Returns
+    /// The AssetId regions refer to the mesh by
+    pub fn save_navmesh(&mut self, path: &str, mesh: &NavMesh) -> io::Result<AssetId> {
+        let bytes = serde_json::to_vec_pretty(mesh).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let dest_path = self.project_root.join(path);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest_path, &bytes)?;
+
+        let asset_id = match self.navmesh_cache.get(path) {
+            Some(&(id, _)) => id,
+            None => self.registry.register(path),
+        };
+        self.save_registry()?;
+        self.navmesh_cache.insert(path.to_string(), (asset_id, Arc::new(mesh.clone())));
+        Ok(asset_id)
+    }
+
     /// Load and deserialize JSON data from the given path
     pub fn load_json<T: DeserializeOwned>(&mut self, path: &str) -> io::Result<T> {
         // Load bytes (check cache first)
@@ -124,6 +669,76 @@ impl<S: AssetSource> AssetManager<S> {
         load_json(&bytes)
     }
 
+    /// Load a particle preset (`.particles`)
+    pub fn load_particle_effect(&mut self, path: &str) -> io::Result<ParticleEffect> {
+        self.load_json(path)
+    }
+
+    /// Save a particle preset into the project
+    ///
+    /// Replaces any cached copy, so later loads see the saved effect.
+    pub fn save_particle_effect(&mut self, path: &str, effect: &ParticleEffect) -> io::Result<()> {
+        let bytes = serde_json::to_vec_pretty(effect).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let dest_path = self.project_root.join(path);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest_path, &bytes)?;
+
+        let id = match self.json_cache.get(path) {
+            Some((id, _)) => *id,
+            None => self.next_id(),
+        };
+        self.json_cache.insert(path.to_string(), (id, bytes));
+        Ok(())
+    }
+
+    /// Create (or resize) a render texture and register it
+    ///
+    /// Writes the `.rendertexture` file to the project; a loaded copy is
+    /// replaced with a blank texture of the new size.
+    ///
+    /// # Returns
+    /// The AssetId cameras and sprites refer to the texture by
+    pub fn create_render_texture(&mut self, path: &str, width: u32, height: u32) -> io::Result<AssetId> {
+        let desc = RenderTextureDesc { width, height };
+        let bytes = serde_json::to_vec_pretty(&desc).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let dest_path = self.project_root.join(path);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest_path, &bytes)?;
+
+        let asset_id = match self.texture_cache.get(path) {
+            Some(&(id, _)) => id,
+            None => self.registry.register(path),
+        };
+        self.save_registry()?;
+        if self.texture_cache.contains_key(path) {
+            self.texture_cache.insert(path.to_string(), (asset_id, desc.blank()));
+            *self.texture_versions.entry(asset_id).or_default() += 1;
+        }
+        Ok(asset_id)
+    }
+
+    /// Load a scene through the asset source
+    ///
+    /// If a baked binary version of the scene (`.scn.bin`) exists next to the
+    /// requested path, it is loaded instead, so shipping builds can keep
+    /// referring to the authoring path.
+    pub fn load_scene(&self, path: &str) -> io::Result<Scene> {
+        let baked_path = binary_scene_path(path);
+        let path = if baked_path != path && self.source.exists(&baked_path) {
+            baked_path.as_str()
+        } else {
+            path
+        };
+
+        let bytes = self.source.load_bytes(path)?;
+        Scene::from_bytes(&bytes, SceneFormat::from_path(path))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
     /// Check if an asset exists at the given path
     pub fn exists(&self, path: &str) -> bool {
         self.source.exists(path)
@@ -132,9 +747,25 @@ impl<S: AssetSource> AssetManager<S> {
     /// Preload an asset without returning it (useful for warming cache)
     pub fn preload(&mut self, path: &str) -> io::Result<()> {
         // Try to determine the asset type from extension
-        if path.ends_with(".png") || path.ends_with(".jpg") || path.ends_with(".jpeg") {
+        if path.ends_with(".png")
+            || path.ends_with(".jpg")
+            || path.ends_with(".jpeg")
+            || RenderTextureDesc::is_render_texture(path)
+        {
             self.load_texture(path)?;
-        } else if path.ends_with(".json") {
+        } else if path.ends_with(".ttf") || path.ends_with(".otf") || path.ends_with(".fnt") {
+            self.load_font(path)?;
+        } else if path.ends_with(".tileset") || path.ends_with(".tsj") {
+            self.load_tileset(path)?;
+        } else if path.ends_with(".material") {
+            self.load_material(path)?;
+        } else if path.ends_with(".anim") {
+            self.load_animation_clip(path)?;
+        } else if path.ends_with(".fsm") {
+            self.load_state_chart(path)?;
+        } else if path.ends_with(".navmesh") {
+            self.load_navmesh(path)?;
+        } else if path.ends_with(".json") || path.ends_with(".particles") {
             // Just load the bytes into cache
             let bytes = self.source.load_bytes(path)?;
             let id = self.next_id();
@@ -242,12 +873,96 @@ impl<S: AssetSource> longhorn_core::AssetLoader for AssetManager<S> {
         let handle = AssetManager::load_texture_by_id(self, id)?;
         Ok(handle.id())
     }
+
+    fn load_font(&mut self, path: &str) -> io::Result<AssetId> {
+        let handle = AssetManager::load_font(self, path)?;
+        Ok(handle.id())
+    }
+
+    fn load_font_by_id(&mut self, id: AssetId) -> io::Result<AssetId> {
+        let handle = AssetManager::load_font_by_id(self, id)?;
+        Ok(handle.id())
+    }
+
+    fn load_tileset(&mut self, path: &str) -> io::Result<AssetId> {
+        let handle = AssetManager::load_tileset(self, path)?;
+        Ok(handle.id())
+    }
+
+    fn load_tileset_by_id(&mut self, id: AssetId) -> io::Result<AssetId> {
+        let handle = AssetManager::load_tileset_by_id(self, id)?;
+        Ok(handle.id())
+    }
+
+    fn load_material(&mut self, path: &str) -> io::Result<AssetId> {
+        let handle = AssetManager::load_material(self, path)?;
+        Ok(handle.id())
+    }
+
+    fn load_material_by_id(&mut self, id: AssetId) -> io::Result<AssetId> {
+        let handle = AssetManager::load_material_by_id(self, id)?;
+        Ok(handle.id())
+    }
+
+    fn load_animation_clip(&mut self, path: &str) -> io::Result<AssetId> {
+        let handle = AssetManager::load_animation_clip(self, path)?;
+        Ok(handle.id())
+    }
+
+    fn load_animation_clip_by_id(&mut self, id: AssetId) -> io::Result<AssetId> {
+        let handle = AssetManager::load_animation_clip_by_id(self, id)?;
+        Ok(handle.id())
+    }
+
+    fn load_state_chart(&mut self, path: &str) -> io::Result<AssetId> {
+        let handle = AssetManager::load_state_chart(self, path)?;
+        Ok(handle.id())
+    }
+
+    fn load_state_chart_by_id(&mut self, id: AssetId) -> io::Result<AssetId> {
+        let handle = AssetManager::load_state_chart_by_id(self, id)?;
+        Ok(handle.id())
+    }
+
+    fn load_navmesh(&mut self, path: &str) -> io::Result<AssetId> {
+        let handle = AssetManager::load_navmesh(self, path)?;
+        Ok(handle.id())
+    }
+
+    fn load_navmesh_by_id(&mut self, id: AssetId) -> io::Result<AssetId> {
+        let handle = AssetManager::load_navmesh_by_id(self, id)?;
+        Ok(handle.id())
+    }
+}
+
+/// Parse a state chart and check its structure
+fn load_state_chart(bytes: &[u8]) -> io::Result<StateChart> {
+    let chart: StateChart = load_json(bytes)?;
+    chart.validate().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(chart)
+}
+
+/// Resolve a path relative to the folder of another asset, collapsing `..`
+fn relative_path(base: &str, relative: &str) -> String {
+    let mut parts: Vec<&str> = base.split('/').collect();
+    parts.pop();
+    for part in relative.split('/') {
+        match part {
+            "." | "" => {}
+            ".." if parts.last().is_some_and(|last| *last != "..") => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::source::FilesystemSource;
+    use longhorn_core::{NavGrid, Rect, State, Transition, Vec2};
     use serde::{Deserialize, Serialize};
     use std::fs;
 
@@ -372,6 +1087,119 @@ mod tests {
         fs::remove_dir_all(&temp_dir).unwrap();
     }
 
+    #[test]
+    fn test_particle_presets() {
+        let temp_dir = setup_test_dir();
+        let mut manager = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+
+        let effect = ParticleEffect::one_shot(30);
+        manager.save_particle_effect("fx/explosion.particles", &effect).unwrap();
+        assert_eq!(manager.load_particle_effect("fx/explosion.particles").unwrap(), effect);
+
+        // Saving over a loaded preset replaces the cached copy
+        let effect = ParticleEffect::continuous(5.0);
+        manager.save_particle_effect("fx/explosion.particles", &effect).unwrap();
+        assert_eq!(manager.load_particle_effect("fx/explosion.particles").unwrap(), effect);
+
+        let mut fresh = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        assert_eq!(fresh.load_particle_effect("fx/explosion.particles").unwrap(), effect);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_animation_clips() {
+        let temp_dir = setup_test_dir();
+        let mut manager = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+
+        let mut clip = AnimationClip::new(1.0);
+        clip.add_event(0.5, "hit");
+        let id = manager.save_animation_clip("anim/attack.anim", &clip).unwrap();
+        assert_eq!(manager.load_animation_clip("anim/attack.anim").unwrap().id(), id);
+        assert_eq!(*manager.get_animation_clip(AssetHandle::new(id)).unwrap(), clip);
+
+        // Saving again keeps the ID and replaces the cached clip
+        clip.looping = true;
+        assert_eq!(manager.save_animation_clip("anim/attack.anim", &clip).unwrap(), id);
+        assert!(manager.get_animation_clip(AssetHandle::new(id)).unwrap().looping);
+
+        let mut fresh = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        fresh.load_animation_clip_by_id(id).unwrap();
+        assert_eq!(*fresh.get_animation_clip(AssetHandle::new(id)).unwrap(), clip);
+        assert_eq!(fresh.animation_clip_path(id), Some("anim/attack.anim"));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_state_charts() {
+        let temp_dir = setup_test_dir();
+        let mut manager = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+
+        let chart = StateChart::new(vec![State::new("Idle"), State::new("Run").with_transition(Transition::new("Idle"))]);
+        let id = manager.save_state_chart("ai/guard.fsm", &chart).unwrap();
+        assert_eq!(manager.load_state_chart("ai/guard.fsm").unwrap().id(), id);
+        assert_eq!(*manager.get_state_chart(AssetHandle::new(id)).unwrap(), chart);
+
+        let mut fresh = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        fresh.load_state_chart_by_id(id).unwrap();
+        assert_eq!(*fresh.get_state_chart(AssetHandle::new(id)).unwrap(), chart);
+        assert_eq!(fresh.state_chart_path(id), Some("ai/guard.fsm"));
+
+        // Charts pointing at missing states are refused
+        let broken = StateChart::new(vec![State::new("Idle").with_transition(Transition::new("Flee"))]);
+        assert!(manager.save_state_chart("ai/broken.fsm", &broken).is_err());
+        fs::write(temp_dir.join("ai/broken.fsm"), serde_json::to_vec(&broken).unwrap()).unwrap();
+        let err = manager.load_state_chart("ai/broken.fsm").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_navmeshes() {
+        let temp_dir = setup_test_dir();
+        let mut manager = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+
+        let grid = NavGrid::from_colliders(Rect::new(Vec2::ZERO, Vec2::splat(64.0)), Vec2::splat(16.0), [
+            Rect::new(Vec2::new(16.0, 0.0), Vec2::new(32.0, 48.0)),
+        ]);
+        let mesh = NavMesh::from_grid(&grid);
+        let id = manager.save_navmesh("levels/cave.navmesh", &mesh).unwrap();
+        assert_eq!(manager.load_navmesh("levels/cave.navmesh").unwrap().id(), id);
+
+        let mut fresh = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        fresh.load_navmesh_by_id(id).unwrap();
+        assert_eq!(*fresh.get_navmesh(AssetHandle::new(id)).unwrap(), mesh);
+        assert_eq!(fresh.navmesh_path(id), Some("levels/cave.navmesh"));
+
+        // Polygons have to be convex
+        fs::write(temp_dir.join("levels/bent.navmesh"), r#"{"polygons":[{"vertices":[[0,0],[2,0],[1,1],[2,2],[0,2]]}]}"#).unwrap();
+        let err = manager.load_navmesh("levels/bent.navmesh").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_scene() {
+        let temp_dir = setup_test_dir();
+        Scene::new("Authoring").save(temp_dir.join("scenes/main.scn.ron")).unwrap();
+
+        let manager = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        let scene = manager.load_scene("scenes/main.scn.ron").unwrap();
+        assert_eq!(scene.name, "Authoring");
+
+        // A baked binary scene takes precedence over the authoring file
+        Scene::new("Baked").save(temp_dir.join("scenes/main.scn.bin")).unwrap();
+        let scene = manager.load_scene("scenes/main.scn.ron").unwrap();
+        assert_eq!(scene.name, "Baked");
+
+        assert!(manager.load_scene("scenes/missing.scn.ron").is_err());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
     #[test]
     fn test_exists() {
         let temp_dir = setup_test_dir();
@@ -541,4 +1369,186 @@ mod tests {
 
         fs::remove_dir_all(&temp_dir).unwrap();
     }
+
+    #[test]
+    fn test_texture_import_settings() {
+        let temp_dir = setup_test_dir();
+        let source = FilesystemSource::new(&temp_dir);
+        let mut manager = AssetManager::new(source, &temp_dir);
+
+        let handle = manager.load_texture("test.png").unwrap();
+        assert_eq!(manager.texture_settings(handle.id()), TextureImportSettings::default());
+
+        // Saving settings writes the sidecar file and re-imports the texture
+        let settings = TextureImportSettings {
+            filter: crate::TextureFilter::Linear,
+            max_size: Some(1),
+            ..Default::default()
+        };
+        manager.set_texture_settings("test.png", settings).unwrap();
+        assert!(temp_dir.join("test.png.import.json").exists());
+        assert_eq!(manager.texture_settings(handle.id()), settings);
+        assert_eq!(manager.texture_version(handle.id()), 1);
+        assert_eq!(manager.get_texture(handle).unwrap().width, 1);
+
+        // A fresh manager picks the settings up on load
+        let mut fresh = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        let handle = fresh.load_texture("test.png").unwrap();
+        assert_eq!(fresh.texture_settings(handle.id()), settings);
+
+        // Resetting to the defaults removes the file
+        manager.set_texture_settings("test.png", TextureImportSettings::default()).unwrap();
+        assert!(!temp_dir.join("test.png.import.json").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_fonts() {
+        let temp_dir = setup_test_dir();
+        fs::create_dir_all(temp_dir.join("fonts")).unwrap();
+        fs::write(temp_dir.join("fonts/ui.ttf"), epaint_default_fonts::UBUNTU_LIGHT).unwrap();
+        fs::copy(temp_dir.join("test.png"), temp_dir.join("fonts/pixel_0.png")).unwrap();
+        fs::write(
+            temp_dir.join("fonts/pixel.fnt"),
+            "info size=8\ncommon lineHeight=10 base=8 scaleW=2 scaleH=2 pages=1\npage id=0 file=\"pixel_0.png\"\nchar id=65 x=0 y=0 width=2 height=2 xoffset=0 yoffset=0 xadvance=3 page=0\n",
+        )
+        .unwrap();
+
+        let source = FilesystemSource::new(&temp_dir);
+        let mut manager = AssetManager::new(source, &temp_dir);
+
+        let ttf = manager.load_font("fonts/ui.ttf").unwrap();
+        assert_eq!(manager.load_font("fonts/ui.ttf").unwrap(), ttf);
+        assert!(matches!(*manager.get_font(ttf).unwrap(), FontData::TrueType(_)));
+
+        // Bitmap fonts load their pages as textures from the font's folder
+        let fnt = manager.load_font("fonts/pixel.fnt").unwrap();
+        let font = manager.get_font(fnt).unwrap();
+        let FontData::Bitmap(bitmap) = &*font else {
+            panic!("expected a bitmap font");
+        };
+        assert_eq!(bitmap.page_textures.len(), 1);
+        assert!(manager.is_texture_loaded(bitmap.page_textures[0]));
+        assert!(manager.load_font("fonts/missing.ttf").is_err());
+
+        // Generated textures are versioned when replaced
+        let id = manager.reserve_asset_id();
+        let texture = TextureData { width: 1, height: 1, pixels: vec![255; 4] };
+        manager.insert_texture("<glyphs>", id, texture.clone(), TextureImportSettings::default());
+        assert_eq!(manager.texture_version(id), 0);
+        manager.insert_texture("<glyphs>", id, texture, TextureImportSettings::default());
+        assert_eq!(manager.texture_version(id), 1);
+        assert!(manager.is_texture_loaded(id));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_tilesets_and_tiled_maps() {
+        let temp_dir = setup_test_dir();
+        fs::create_dir_all(temp_dir.join("tiles")).unwrap();
+        fs::create_dir_all(temp_dir.join("maps")).unwrap();
+        fs::write(
+            temp_dir.join("tiles/level.tileset"),
+            r#"{"image": "../test.png", "tile_width": 1, "tile_height": 1, "tiles": {"2": {"solid": true}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.join("tiles/level.tsj"),
+            r#"{"image": "../test.png", "imagewidth": 2, "imageheight": 2, "tilewidth": 1, "tileheight": 1}"#,
+        )
+        .unwrap();
+        let map = |tileset: &str| {
+            format!(
+                r#"{{"orientation": "orthogonal", "tilewidth": 1, "tileheight": 1, "tilesets": [{}],
+                    "layers": [{{"type": "tilelayer", "name": "Ground", "width": 2, "height": 1, "data": [4, 1]}}]}}"#,
+                tileset
+            )
+        };
+        fs::write(temp_dir.join("maps/external.tmj"), map(r#"{"firstgid": 1, "source": "../tiles/level.tsj"}"#)).unwrap();
+        fs::write(
+            temp_dir.join("maps/embedded.tmj"),
+            map(r#"{"firstgid": 1, "image": "../test.png", "imagewidth": 2, "imageheight": 2, "tilewidth": 1, "tileheight": 1}"#),
+        )
+        .unwrap();
+
+        let source = FilesystemSource::new(&temp_dir);
+        let mut manager = AssetManager::new(source, &temp_dir);
+
+        // The image is sliced by its actual size and loaded as a texture
+        let handle = manager.load_tileset("tiles/level.tileset").unwrap();
+        let tileset = manager.get_tileset(handle.clone()).unwrap();
+        assert_eq!((tileset.columns, tileset.rows), (2, 2));
+        assert!(tileset.flags(2).solid);
+        assert!(manager.is_texture_loaded(tileset.texture));
+        assert_eq!(manager.get_texture_by_path("test.png").map(|t| t.width), Some(2));
+        assert_eq!(manager.load_tileset("tiles/level.tileset").unwrap(), handle);
+
+        let external = manager.import_tiled_map("maps/external.tmj").unwrap();
+        assert_eq!(Some(external.tileset), manager.load_tileset("tiles/level.tsj").ok().map(|h| h.id()));
+        assert_eq!(external.get_tile(0, 0, 0), Some(3));
+
+        // Embedded tile sets load under the map's path
+        let embedded = manager.import_tiled_map("maps/embedded.tmj").unwrap();
+        assert_eq!(Some(embedded.tileset), manager.load_tileset("maps/embedded.tmj").ok().map(|h| h.id()));
+        assert_eq!(embedded.get_tile(0, 1, 0), Some(0));
+        assert!(manager.load_tileset("maps/external.tmj").is_err());
+
+        assert_eq!(relative_path("maps/level.tmj", "../tiles/a.tsj"), "tiles/a.tsj");
+        assert_eq!(relative_path("level.tmj", "./a.png"), "a.png");
+        assert_eq!(relative_path("a/b/c.fnt", "../../x/../y.png"), "y.png");
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_and_reload_materials() {
+        let temp_dir = setup_test_dir();
+        fs::create_dir_all(temp_dir.join("materials")).unwrap();
+        fs::write(
+            temp_dir.join("materials/dissolve.material"),
+            r#"{"shader": "dissolve.wgsl", "params": [{"name": "amount", "type": "float", "default": 0}],
+                "textures": [{"name": "noise", "image": "../test.png"}]}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.join("materials/dissolve.wgsl"), "// v1").unwrap();
+
+        let mut manager = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        let handle = manager.load_material("materials/dissolve.material").unwrap();
+        let material = manager.get_material(handle.clone()).unwrap();
+        assert_eq!(material.source, "// v1");
+        assert!(manager.is_texture_loaded(material.textures[0].texture));
+        assert_eq!(manager.load_material("materials/dissolve.material").unwrap(), handle);
+
+        fs::write(temp_dir.join("materials/dissolve.wgsl"), "// v2").unwrap();
+        assert_eq!(manager.reload_material("materials/dissolve.material").unwrap(), handle);
+        assert_eq!(manager.material_path(handle.id()), Some("materials/dissolve.material"));
+        assert_eq!(manager.get_material(handle).unwrap().source, "// v2");
+
+        fs::write(temp_dir.join("materials/broken.material"), r#"{"shader": "missing.wgsl"}"#).unwrap();
+        assert!(manager.load_material("materials/broken.material").is_err());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_textures() {
+        let temp_dir = setup_test_dir();
+        let mut manager = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+
+        let id = manager.create_render_texture("targets/minimap.rendertexture", 64, 32).unwrap();
+        assert_eq!(manager.get_asset_id("targets/minimap.rendertexture"), Some(id));
+        let handle = manager.load_texture_by_id(id).unwrap();
+        let texture = manager.get_texture(handle.clone()).unwrap();
+        assert_eq!((texture.width, texture.height), (64, 32));
+        assert!(texture.pixels.iter().all(|&p| p == 0));
+
+        // Resizing keeps the ID and replaces the loaded copy
+        assert_eq!(manager.create_render_texture("targets/minimap.rendertexture", 16, 16).unwrap(), id);
+        assert_eq!(manager.get_texture(handle).unwrap().width, 16);
+        assert_eq!(manager.texture_version(id), 1);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
 }
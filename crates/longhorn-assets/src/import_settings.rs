@@ -0,0 +1,222 @@
+use crate::loader::TextureData;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// Extension of the settings file stored next to an imported asset
+///
+/// `sprites/player.png` keeps its settings in `sprites/player.png.import.json`.
+pub const IMPORT_SETTINGS_EXTENSION: &str = ".import.json";
+
+/// Path of the import settings file for an asset path
+pub fn import_settings_path(asset_path: &str) -> String {
+    format!("{}{}", asset_path, IMPORT_SETTINGS_EXTENSION)
+}
+
+/// How texels are filtered when a texture is scaled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureFilter {
+    /// Sharp texels (pixel art)
+    #[default]
+    Nearest,
+    /// Bilinear blending between texels
+    Linear,
+}
+
+/// How texture coordinates outside 0..1 are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureWrap {
+    /// Repeat the edge texels
+    #[default]
+    Clamp,
+    /// Tile the texture
+    Repeat,
+    /// Tile the texture, mirroring every other copy
+    MirrorRepeat,
+}
+
+/// Per-texture import settings
+///
+/// The defaults match how textures were always imported: nearest filtering,
+/// clamped, no mipmaps, sRGB color and straight alpha.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextureImportSettings {
+    pub filter: TextureFilter,
+    pub wrap: TextureWrap,
+    /// Generate a mipmap chain (GPU rendering only)
+    pub mipmaps: bool,
+    /// Pixels are sRGB-encoded color; disable for data such as normal maps
+    pub srgb: bool,
+    /// Multiply color by alpha on import and blend as premultiplied, which
+    /// avoids dark fringes around filtered edges
+    pub premultiply_alpha: bool,
+    /// Downscale so neither side exceeds this many pixels
+    pub max_size: Option<u32>,
+}
+
+impl Default for TextureImportSettings {
+    fn default() -> Self {
+        Self {
+            filter: TextureFilter::Nearest,
+            wrap: TextureWrap::Clamp,
+            mipmaps: false,
+            srgb: true,
+            premultiply_alpha: false,
+            max_size: None,
+        }
+    }
+}
+
+impl TextureImportSettings {
+    /// Parse settings from a JSON settings file
+    pub fn from_json(bytes: &[u8]) -> io::Result<Self> {
+        serde_json::from_slice(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Encode settings as pretty-printed JSON
+    pub fn to_json(&self) -> io::Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Check if a texture with these settings can be packed into a shared
+    /// sprite atlas page (nearest, clamped, sRGB, straight alpha, no mipmaps)
+    pub fn is_atlas_compatible(&self) -> bool {
+        self.filter == TextureFilter::Nearest
+            && self.wrap == TextureWrap::Clamp
+            && !self.mipmaps
+            && self.srgb
+            && !self.premultiply_alpha
+    }
+
+    /// Apply the settings that change pixel data (max size and premultiplied alpha)
+    pub fn apply(&self, texture: TextureData) -> TextureData {
+        let mut texture = match self.max_size {
+            Some(max_size) if max_size > 0 && texture.width.max(texture.height) > max_size => {
+                downscale(texture, max_size, self.filter)
+            }
+            _ => texture,
+        };
+
+        if self.premultiply_alpha {
+            premultiply(&mut texture.pixels, self.srgb);
+        }
+        texture
+    }
+}
+
+/// Resize so the longer side is `max_size`, keeping the aspect ratio
+fn downscale(texture: TextureData, max_size: u32, filter: TextureFilter) -> TextureData {
+    let scale = max_size as f32 / texture.width.max(texture.height) as f32;
+    let width = ((texture.width as f32 * scale).round() as u32).max(1);
+    let height = ((texture.height as f32 * scale).round() as u32).max(1);
+
+    let Some(image) = image::RgbaImage::from_raw(texture.width, texture.height, texture.pixels.clone()) else {
+        return texture;
+    };
+    let filter = match filter {
+        TextureFilter::Nearest => image::imageops::FilterType::Nearest,
+        TextureFilter::Linear => image::imageops::FilterType::Triangle,
+    };
+    let resized = image::imageops::resize(&image, width, height, filter);
+    TextureData {
+        width,
+        height,
+        pixels: resized.into_raw(),
+    }
+}
+
+/// Multiply color channels by alpha, in linear space for sRGB pixels
+fn premultiply(pixels: &mut [u8], srgb: bool) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as f32 / 255.0;
+        for channel in &mut pixel[..3] {
+            let value = *channel as f32 / 255.0;
+            let value = if srgb {
+                linear_to_srgb(srgb_to_linear(value) * alpha)
+            } else {
+                value * alpha
+            };
+            *channel = (value * 255.0).round() as u8;
+        }
+    }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texture(width: u32, height: u32, pixel: [u8; 4]) -> TextureData {
+        TextureData {
+            width,
+            height,
+            pixels: pixel.repeat((width * height) as usize),
+        }
+    }
+
+    #[test]
+    fn test_defaults_and_partial_json() {
+        let settings = TextureImportSettings::from_json(br#"{"filter": "linear", "max_size": 64}"#).unwrap();
+        assert_eq!(settings.filter, TextureFilter::Linear);
+        assert_eq!(settings.max_size, Some(64));
+        assert_eq!(settings.wrap, TextureWrap::Clamp);
+        assert!(settings.srgb);
+        assert!(!settings.is_atlas_compatible());
+        assert!(TextureImportSettings::default().is_atlas_compatible());
+
+        let json = settings.to_json().unwrap();
+        assert_eq!(TextureImportSettings::from_json(json.as_bytes()).unwrap(), settings);
+        assert_eq!(import_settings_path("sprites/a.png"), "sprites/a.png.import.json");
+    }
+
+    #[test]
+    fn test_max_size_keeps_aspect_ratio() {
+        let settings = TextureImportSettings {
+            max_size: Some(16),
+            ..Default::default()
+        };
+        let result = settings.apply(texture(64, 32, [255, 0, 0, 255]));
+        assert_eq!((result.width, result.height), (16, 8));
+        assert_eq!(result.pixels.len(), 16 * 8 * 4);
+
+        let small = settings.apply(texture(8, 8, [255, 0, 0, 255]));
+        assert_eq!((small.width, small.height), (8, 8));
+    }
+
+    #[test]
+    fn test_premultiply_alpha() {
+        let linear = TextureImportSettings {
+            premultiply_alpha: true,
+            srgb: false,
+            ..Default::default()
+        };
+        assert_eq!(linear.apply(texture(1, 1, [200, 100, 0, 128])).pixels, vec![100, 50, 0, 128]);
+
+        // sRGB color is premultiplied in linear space
+        let srgb = TextureImportSettings {
+            premultiply_alpha: true,
+            ..Default::default()
+        };
+        let pixels = srgb.apply(texture(1, 1, [255, 255, 255, 128])).pixels;
+        assert_eq!(pixels[3], 128);
+        assert_eq!(pixels[0], 188);
+        assert_eq!(srgb.apply(texture(1, 1, [255, 255, 255, 0])).pixels, vec![0, 0, 0, 0]);
+    }
+}
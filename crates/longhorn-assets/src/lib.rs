@@ -3,9 +3,11 @@ mod source;
 mod loader;
 mod asset_manager;
 mod registry;
+mod import_settings;
 
 pub use handle::*;
 pub use source::*;
 pub use loader::*;
 pub use asset_manager::*;
 pub use registry::*;
+pub use import_settings::*;
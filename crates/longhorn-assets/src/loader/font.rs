@@ -0,0 +1,323 @@
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use longhorn_core::AssetId;
+use std::collections::HashMap;
+use std::io;
+
+/// Vertical metrics of a font at a given size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// Distance from the top of a line to its baseline
+    pub ascent: f32,
+    /// Distance between the baselines of consecutive lines
+    pub line_height: f32,
+}
+
+/// A glyph rasterized from a TrueType font
+#[derive(Debug, Clone)]
+pub struct RasterizedGlyph {
+    pub width: u32,
+    pub height: u32,
+    /// Position of the bitmap's top-left corner relative to the pen on the
+    /// baseline, in pixels (y grows downwards)
+    pub offset: [f32; 2],
+    /// One coverage byte per pixel, row by row
+    pub coverage: Vec<u8>,
+}
+
+/// One character of a bitmap font
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitmapGlyph {
+    /// Region of the page texture, in pixels
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Position of the region relative to the top of the line (y grows downwards)
+    pub x_offset: f32,
+    pub y_offset: f32,
+    pub x_advance: f32,
+    /// Index of the page texture
+    pub page: usize,
+}
+
+/// A bitmap font in the BMFont text format (`.fnt`)
+///
+/// Glyphs are regions of one or more page images drawn at the size the
+/// font was generated at and scaled for other sizes.
+#[derive(Debug, Clone, Default)]
+pub struct BitmapFont {
+    /// Size the font was generated at, in pixels
+    pub size: f32,
+    pub line_height: f32,
+    /// Distance from the top of a line to the baseline
+    pub base: f32,
+    /// Size of the page images
+    pub page_width: u32,
+    pub page_height: u32,
+    /// Page image files, relative to the font file
+    pub pages: Vec<String>,
+    /// Page texture IDs, filled in when loaded through an `AssetManager`
+    pub page_textures: Vec<AssetId>,
+    pub glyphs: HashMap<char, BitmapGlyph>,
+    /// Extra advance between character pairs
+    pub kerning: HashMap<(char, char), f32>,
+}
+
+impl BitmapFont {
+    /// Parse a font in the BMFont text format
+    ///
+    /// Only the `info`, `common`, `page`, `char` and `kerning` lines are read.
+    pub fn parse(source: &str) -> io::Result<Self> {
+        let mut font = Self::default();
+        let mut pages: Vec<(usize, String)> = Vec::new();
+
+        for (line_number, line) in source.lines().enumerate() {
+            let mut fields = split_fields(line);
+            let Some(tag) = fields.next() else {
+                continue;
+            };
+            let values: HashMap<&str, &str> = fields.filter_map(|field| field.split_once('=')).collect();
+            let int = |key: &str| -> io::Result<i64> {
+                let value = values.get(key).copied().unwrap_or("0");
+                value.trim_matches('"').parse().map_err(|_| {
+                    invalid_data(format!("line {}: invalid {} '{}'", line_number + 1, key, value))
+                })
+            };
+
+            match tag {
+                "info" => font.size = int("size")?.unsigned_abs() as f32,
+                "common" => {
+                    font.line_height = int("lineHeight")? as f32;
+                    font.base = int("base")? as f32;
+                    font.page_width = int("scaleW")? as u32;
+                    font.page_height = int("scaleH")? as u32;
+                }
+                "page" => {
+                    let file = values.get("file").copied().unwrap_or("").trim_matches('"');
+                    pages.push((int("id")? as usize, file.to_string()));
+                }
+                "char" => {
+                    let Some(c) = char::from_u32(int("id")? as u32) else {
+                        continue;
+                    };
+                    font.glyphs.insert(
+                        c,
+                        BitmapGlyph {
+                            x: int("x")? as u32,
+                            y: int("y")? as u32,
+                            width: int("width")? as u32,
+                            height: int("height")? as u32,
+                            x_offset: int("xoffset")? as f32,
+                            y_offset: int("yoffset")? as f32,
+                            x_advance: int("xadvance")? as f32,
+                            page: int("page")? as usize,
+                        },
+                    );
+                }
+                "kerning" => {
+                    let first = char::from_u32(int("first")? as u32);
+                    let second = char::from_u32(int("second")? as u32);
+                    if let (Some(first), Some(second)) = (first, second) {
+                        font.kerning.insert((first, second), int("amount")? as f32);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        pages.sort();
+        font.pages = pages.into_iter().map(|(_, file)| file).collect();
+        if font.size == 0.0 {
+            font.size = font.line_height;
+        }
+        if font.size <= 0.0 {
+            return Err(invalid_data("bitmap font has no size or line height".to_string()));
+        }
+        Ok(font)
+    }
+
+    /// Factor from the generated size to `size`
+    pub fn scale(&self, size: f32) -> f32 {
+        size / self.size
+    }
+}
+
+/// A loaded font
+#[derive(Debug, Clone)]
+pub enum FontData {
+    /// Vector font, rasterized on demand
+    TrueType(FontArc),
+    /// Pre-rendered glyphs on page textures
+    Bitmap(BitmapFont),
+}
+
+impl FontData {
+    /// Load a TrueType or OpenType font
+    pub fn from_ttf_bytes(bytes: Vec<u8>) -> io::Result<Self> {
+        FontArc::try_from_vec(bytes)
+            .map(FontData::TrueType)
+            .map_err(|e| invalid_data(e.to_string()))
+    }
+
+    /// Vertical metrics at `size` pixels
+    pub fn metrics(&self, size: f32) -> FontMetrics {
+        match self {
+            FontData::TrueType(font) => {
+                let font = font.as_scaled(PxScale::from(size));
+                FontMetrics {
+                    ascent: font.ascent(),
+                    line_height: font.height() + font.line_gap(),
+                }
+            }
+            FontData::Bitmap(font) => FontMetrics {
+                ascent: font.base * font.scale(size),
+                line_height: font.line_height * font.scale(size),
+            },
+        }
+    }
+
+    /// Horizontal advance of a character at `size` pixels
+    ///
+    /// Characters a bitmap font lacks have no advance.
+    pub fn advance(&self, c: char, size: f32) -> f32 {
+        match self {
+            FontData::TrueType(font) => {
+                let font = font.as_scaled(PxScale::from(size));
+                font.h_advance(font.glyph_id(c))
+            }
+            FontData::Bitmap(font) => font
+                .glyphs
+                .get(&c)
+                .map_or(0.0, |glyph| glyph.x_advance * font.scale(size)),
+        }
+    }
+
+    /// Kerning adjustment between two consecutive characters at `size` pixels
+    pub fn kerning(&self, first: char, second: char, size: f32) -> f32 {
+        match self {
+            FontData::TrueType(font) => {
+                let font = font.as_scaled(PxScale::from(size));
+                font.kern(font.glyph_id(first), font.glyph_id(second))
+            }
+            FontData::Bitmap(font) => font
+                .kerning
+                .get(&(first, second))
+                .map_or(0.0, |amount| amount * font.scale(size)),
+        }
+    }
+
+    /// Rasterize a character of a TrueType font at `size` pixels
+    ///
+    /// Returns `None` for bitmap fonts and characters without an outline
+    /// (such as spaces).
+    pub fn rasterize(&self, c: char, size: f32) -> Option<RasterizedGlyph> {
+        let FontData::TrueType(font) = self else {
+            return None;
+        };
+        let glyph = font.glyph_id(c).with_scale(PxScale::from(size));
+        let outlined = font.outline_glyph(glyph)?;
+        let bounds = outlined.px_bounds();
+        let width = bounds.width() as u32;
+        let height = bounds.height() as u32;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let mut coverage = vec![0; (width * height) as usize];
+        outlined.draw(|x, y, value| {
+            if x < width && y < height {
+                coverage[(y * width + x) as usize] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        });
+        Some(RasterizedGlyph {
+            width,
+            height,
+            offset: [bounds.min.x, bounds.min.y],
+            coverage,
+        })
+    }
+}
+
+/// Split a BMFont line into whitespace-separated fields, keeping quoted values whole
+fn split_fields(line: &str) -> impl Iterator<Item = &str> {
+    let mut rest = line.trim();
+    std::iter::from_fn(move || {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+        let mut in_quotes = false;
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                c.is_whitespace() && !in_quotes
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let (field, tail) = rest.split_at(end);
+        rest = tail;
+        Some(field)
+    })
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &str = r#"info face="Pixel Font" size=-16 bold=0 italic=0 padding=0,0,0,0 spacing=1,1
+common lineHeight=18 base=14 scaleW=128 scaleH=64 pages=2 packed=0
+page id=1 file="pixel_1.png"
+page id=0 file="pixel_0.png"
+chars count=2
+char id=65 x=0 y=0 width=10 height=12 xoffset=1 yoffset=2 xadvance=11 page=0 chnl=15
+char id=86 x=12 y=0 width=10 height=12 xoffset=0 yoffset=2 xadvance=10 page=1 chnl=15
+kernings count=1
+kerning first=65 second=86 amount=-2
+"#;
+
+    #[test]
+    fn test_parse_bitmap_font() {
+        let font = BitmapFont::parse(FONT).unwrap();
+        assert_eq!(font.size, 16.0);
+        assert_eq!(font.line_height, 18.0);
+        assert_eq!(font.base, 14.0);
+        assert_eq!((font.page_width, font.page_height), (128, 64));
+        assert_eq!(font.pages, vec!["pixel_0.png", "pixel_1.png"]);
+        assert_eq!(font.glyphs[&'V'].page, 1);
+        assert_eq!(font.glyphs[&'A'].x_offset, 1.0);
+
+        // Metrics scale from the generated size
+        let font = FontData::Bitmap(font);
+        assert_eq!(font.advance('A', 32.0), 22.0);
+        assert_eq!(font.kerning('A', 'V', 32.0), -4.0);
+        assert_eq!(font.kerning('V', 'A', 32.0), 0.0);
+        assert_eq!(font.metrics(16.0), FontMetrics { ascent: 14.0, line_height: 18.0 });
+        assert!(font.rasterize('A', 16.0).is_none());
+
+        assert!(BitmapFont::parse("char id=65 x=a").is_err());
+    }
+
+    #[test]
+    fn test_truetype_font() {
+        let font = FontData::from_ttf_bytes(epaint_default_fonts::UBUNTU_LIGHT.to_vec()).unwrap();
+        let metrics = font.metrics(32.0);
+        assert!(metrics.ascent > 0.0 && metrics.ascent < 32.0);
+        assert!(metrics.line_height >= 32.0);
+        assert!(font.advance('W', 32.0) > font.advance('i', 32.0));
+
+        let glyph = font.rasterize('H', 32.0).unwrap();
+        assert_eq!(glyph.coverage.len(), (glyph.width * glyph.height) as usize);
+        // The glyph sits above the baseline
+        assert!(glyph.offset[1] < 0.0);
+        assert!(glyph.coverage.contains(&255));
+        assert!(font.rasterize(' ', 32.0).is_none());
+
+        assert!(FontData::from_ttf_bytes(vec![0, 1, 2, 3]).is_err());
+    }
+}
@@ -1,5 +1,9 @@
 mod texture;
 mod json;
+mod font;
+mod tiled;
 
 pub use texture::*;
 pub use json::*;
+pub use font::*;
+pub use tiled::*;
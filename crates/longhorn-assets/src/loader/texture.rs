@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::io;
 
 /// Texture data loaded from an image file
@@ -35,6 +36,32 @@ impl TextureData {
     }
 }
 
+/// A texture cameras render into, stored as JSON (`.rendertexture`)
+///
+/// Loads as a transparent texture of the given size; renderers replace its
+/// pixels with what the camera targeting it sees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderTextureDesc {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl RenderTextureDesc {
+    /// Check if a path is a render texture
+    pub fn is_render_texture(path: &str) -> bool {
+        path.ends_with(".rendertexture")
+    }
+
+    /// Transparent texture data of this size
+    pub fn blank(&self) -> TextureData {
+        TextureData {
+            width: self.width,
+            height: self.height,
+            pixels: vec![0; (self.width * self.height * 4) as usize],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
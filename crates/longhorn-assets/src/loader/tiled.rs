@@ -0,0 +1,336 @@
+use longhorn_core::{AssetId, TileFlags, TileLayer, TileSet, Tilemap, Vec2};
+use serde::Deserialize;
+use std::io;
+
+/// Bits Tiled stores in a tile GID to flip or rotate the tile
+const GID_FLAGS: u32 = 0xE000_0000;
+
+/// Where the tile set of an imported Tiled map lives
+#[derive(Debug, Clone, PartialEq)]
+pub enum TiledTileSetSource {
+    /// A `.tsj` file, relative to the map
+    External(String),
+    /// Embedded in the map; its image is relative to the map
+    Embedded(TileSet),
+}
+
+/// A Tiled map converted to a `Tilemap`
+///
+/// The tilemap's `tileset` is unset until the tile set is loaded.
+#[derive(Debug, Clone)]
+pub struct TiledMap {
+    pub tilemap: Tilemap,
+    pub tileset: TiledTileSetSource,
+}
+
+#[derive(Deserialize)]
+struct MapFile {
+    orientation: String,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default)]
+    layers: Vec<LayerFile>,
+    #[serde(default)]
+    tilesets: Vec<TileSetFile>,
+}
+
+#[derive(Deserialize)]
+struct LayerFile {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default = "default_true")]
+    visible: bool,
+    #[serde(default)]
+    x: i32,
+    #[serde(default)]
+    y: i32,
+    #[serde(default)]
+    width: u32,
+    #[serde(default)]
+    data: Option<DataFile>,
+    /// Tile data of infinite maps
+    #[serde(default)]
+    chunks: Vec<ChunkFile>,
+    /// Children of group layers
+    #[serde(default)]
+    layers: Vec<LayerFile>,
+    #[serde(default)]
+    properties: Vec<PropertyFile>,
+}
+
+#[derive(Deserialize)]
+struct ChunkFile {
+    x: i32,
+    y: i32,
+    width: u32,
+    data: DataFile,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DataFile {
+    Gids(Vec<u32>),
+    /// Base64, possibly compressed
+    Encoded(serde::de::IgnoredAny),
+}
+
+#[derive(Deserialize)]
+struct PropertyFile {
+    name: String,
+    value: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct TileSetFile {
+    #[serde(default)]
+    firstgid: u32,
+    /// Set for external tile sets, which only list their file in the map
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    imagewidth: u32,
+    #[serde(default)]
+    imageheight: u32,
+    #[serde(default)]
+    tilewidth: u32,
+    #[serde(default)]
+    tileheight: u32,
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    tiles: Vec<TileFile>,
+}
+
+#[derive(Deserialize)]
+struct TileFile {
+    id: u32,
+    #[serde(default)]
+    properties: Vec<PropertyFile>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Parse a Tiled tile set (`.tsj`)
+///
+/// Tiles get their flags from the boolean custom properties `solid` and
+/// `one_way`. Image collection tile sets aren't supported.
+pub fn parse_tiled_tileset(bytes: &[u8]) -> io::Result<TileSet> {
+    let file: TileSetFile = serde_json::from_slice(bytes).map_err(invalid_data)?;
+    convert_tileset(file)
+}
+
+/// Parse a Tiled map (`.tmj`) with a single tile set
+///
+/// Orthogonal maps, finite or infinite, with layer data in the CSV format
+/// are supported; group layers are flattened and object and image layers
+/// are skipped. Flipped tiles are drawn unflipped. Layers draw below
+/// sprites in map order unless they have an integer `z_index` property.
+pub fn parse_tiled_map(bytes: &[u8]) -> io::Result<TiledMap> {
+    let file: MapFile = serde_json::from_slice(bytes).map_err(invalid_data)?;
+    if file.orientation != "orthogonal" {
+        return Err(invalid_data(format!("unsupported map orientation '{}'", file.orientation)));
+    }
+
+    let mut tilesets = file.tilesets.into_iter();
+    let tileset_file = match (tilesets.next(), tilesets.next()) {
+        (Some(tileset), None) => tileset,
+        (None, _) => return Err(invalid_data("map has no tile set")),
+        (Some(_), Some(_)) => return Err(invalid_data("maps with more than one tile set aren't supported")),
+    };
+    let first_gid = tileset_file.firstgid;
+    let tileset = match tileset_file.source.clone() {
+        Some(source) => TiledTileSetSource::External(source),
+        None => TiledTileSetSource::Embedded(convert_tileset(tileset_file)?),
+    };
+
+    let mut layers = Vec::new();
+    flatten_layers(file.layers, &mut layers);
+    let layer_count = layers.len() as i32;
+
+    let cell_size = Vec2::new(file.tilewidth as f32, file.tileheight as f32);
+    let mut tilemap = Tilemap::new(AssetId::new(0), cell_size);
+    for (index, layer_file) in layers.into_iter().enumerate() {
+        let z_index = layer_file
+            .properties
+            .iter()
+            .find(|property| property.name == "z_index")
+            .and_then(|property| property.value.as_i64())
+            .map_or(index as i32 - layer_count, |z| z as i32);
+        let mut layer = TileLayer::new(layer_file.name.clone()).with_z_index(z_index);
+        layer.visible = layer_file.visible;
+
+        let mut flipped = false;
+        let mut regions: Vec<(i32, i32, u32, &DataFile)> = layer_file
+            .chunks
+            .iter()
+            .map(|chunk| (chunk.x, chunk.y, chunk.width, &chunk.data))
+            .collect();
+        if let Some(data) = &layer_file.data {
+            regions.push((layer_file.x, layer_file.y, layer_file.width, data));
+        }
+        for (x0, y0, width, data) in regions {
+            let DataFile::Gids(gids) = data else {
+                return Err(invalid_data(format!(
+                    "layer '{}' isn't in the CSV layer format",
+                    layer_file.name
+                )));
+            };
+            for (i, &gid) in gids.iter().enumerate() {
+                flipped |= gid & GID_FLAGS != 0;
+                let gid = gid & !GID_FLAGS;
+                if gid == 0 {
+                    continue;
+                }
+                let Some(tile) = gid.checked_sub(first_gid) else {
+                    continue;
+                };
+                let (dx, dy) = (i as u32 % width.max(1), i as u32 / width.max(1));
+                layer.set(x0 + dx as i32, y0 + dy as i32, Some(tile));
+            }
+        }
+        if flipped {
+            log::warn!("Layer '{}' has flipped tiles, which are drawn unflipped", layer_file.name);
+        }
+        tilemap.layers.push(layer);
+    }
+
+    Ok(TiledMap { tilemap, tileset })
+}
+
+/// Collect tile layers in draw order, descending into groups
+fn flatten_layers(layers: Vec<LayerFile>, out: &mut Vec<LayerFile>) {
+    for mut layer in layers {
+        match layer.kind.as_str() {
+            "tilelayer" => out.push(layer),
+            "group" => {
+                let visible = layer.visible;
+                let start = out.len();
+                flatten_layers(std::mem::take(&mut layer.layers), out);
+                for child in &mut out[start..] {
+                    child.visible &= visible;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn convert_tileset(file: TileSetFile) -> io::Result<TileSet> {
+    if file.image.is_empty() {
+        return Err(invalid_data("tile sets without a single image aren't supported"));
+    }
+    if file.tilewidth == 0 || file.tileheight == 0 {
+        return Err(invalid_data("tile set has no tile size"));
+    }
+
+    let mut tileset = TileSet::new(AssetId::new(0), (0, 0), file.tilewidth, file.tileheight);
+    tileset.image = file.image;
+    tileset.margin = file.margin;
+    tileset.spacing = file.spacing;
+    tileset.slice(file.imagewidth, file.imageheight);
+    for tile in file.tiles {
+        let flag = |name: &str| {
+            tile.properties
+                .iter()
+                .any(|property| property.name == name && property.value.as_bool() == Some(true))
+        };
+        tileset.set_flags(
+            tile.id,
+            TileFlags {
+                solid: flag("solid"),
+                one_way: flag("one_way"),
+            },
+        );
+    }
+    Ok(tileset)
+}
+
+fn invalid_data(message: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TILESET: &str = r#"{
+        "columns": 4, "image": "../images/tiles.png", "imagewidth": 64, "imageheight": 32,
+        "margin": 0, "spacing": 0, "tilecount": 8, "tilewidth": 16, "tileheight": 16,
+        "tiles": [
+            {"id": 0, "properties": [{"name": "solid", "type": "bool", "value": true}]},
+            {"id": 3, "properties": [{"name": "one_way", "type": "bool", "value": true}]}
+        ],
+        "type": "tileset"
+    }"#;
+
+    #[test]
+    fn test_parse_tileset() {
+        let tileset = parse_tiled_tileset(TILESET.as_bytes()).unwrap();
+        assert_eq!(tileset.image, "../images/tiles.png");
+        assert_eq!((tileset.columns, tileset.rows), (4, 2));
+        assert_eq!(tileset.flags(0), TileFlags::SOLID);
+        assert_eq!(tileset.flags(3), TileFlags::ONE_WAY);
+        assert!(tileset.flags(1).is_empty());
+
+        assert!(parse_tiled_tileset(br#"{"tilewidth": 16, "tileheight": 16}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_map() {
+        // A finite 3x2 layer, an infinite-style chunk inside a hidden group and a flipped tile
+        let map = r#"{
+            "orientation": "orthogonal", "tilewidth": 16, "tileheight": 8,
+            "tilesets": [{"firstgid": 1, "source": "tiles.tsj"}],
+            "layers": [
+                {"type": "tilelayer", "name": "Ground", "x": 0, "y": 0, "width": 3, "height": 2,
+                 "data": [1, 0, 2, 0, 3, 2147483652]},
+                {"type": "objectgroup", "name": "Spawns", "objects": []},
+                {"type": "group", "name": "Back", "visible": false, "layers": [
+                    {"type": "tilelayer", "name": "Far", "visible": true,
+                     "properties": [{"name": "z_index", "type": "int", "value": -10}],
+                     "chunks": [{"x": -16, "y": 0, "width": 16, "height": 1,
+                                 "data": [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5]}]}
+                ]}
+            ]
+        }"#;
+
+        let TiledMap { tilemap, tileset } = parse_tiled_map(map.as_bytes()).unwrap();
+        assert_eq!(tileset, TiledTileSetSource::External("tiles.tsj".to_string()));
+        assert_eq!(tilemap.cell_size, Vec2::new(16.0, 8.0));
+        assert_eq!(tilemap.layers.len(), 2);
+
+        let ground = &tilemap.layers[0];
+        assert_eq!(ground.tiles().collect::<Vec<_>>(), vec![(0, 0, 0), (2, 0, 1), (1, 1, 2), (2, 1, 3)]);
+        assert_eq!(ground.z_index, -2);
+        assert!(ground.visible);
+
+        let far = &tilemap.layers[1];
+        assert_eq!(far.get(-1, 0), Some(4));
+        assert_eq!(far.z_index, -10);
+        assert!(!far.visible);
+
+        // Embedded tile sets come back sliced
+        let embedded = format!(
+            r#"{{"orientation": "orthogonal", "tilewidth": 16, "tileheight": 16,
+                "tilesets": [{}], "layers": []}}"#,
+            TILESET.replacen('{', r#"{"firstgid": 1, "#, 1)
+        );
+        let TiledTileSetSource::Embedded(tileset) = parse_tiled_map(embedded.as_bytes()).unwrap().tileset else {
+            panic!("expected an embedded tile set");
+        };
+        assert_eq!(tileset.tile_count(), 8);
+
+        let encoded = map.replace("[1, 0, 2, 0, 3, 2147483652]", r#""AQAAAA==""#);
+        assert!(parse_tiled_map(encoded.as_bytes()).is_err());
+        let isometric = map.replace("orthogonal", "isometric");
+        assert!(parse_tiled_map(isometric.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,523 @@
+use crate::ecs::{Children, EntityHandle, Name, Script, ScriptValue, TweenProperty};
+use crate::types::AssetId;
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Keys closer together than this (in seconds) are the same key
+pub const KEY_TIME_EPSILON: f32 = 1e-4;
+
+/// How a key's value moves towards the next key's
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    /// Hold the value until the next key
+    Step,
+    /// Timing curve through `(0, 0)`, control points `[x1, y1, x2, y2]` and
+    /// `(1, 1)`, like CSS `cubic-bezier`
+    Bezier([f32; 4]),
+}
+
+impl Interpolation {
+    /// Eased-in-and-out Bezier, a sensible default for smooth keys
+    pub const EASE: Interpolation = Interpolation::Bezier([0.42, 0.0, 0.58, 1.0]);
+
+    /// Progress towards the next key at linear progress `t` (0..1)
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Interpolation::Linear => t,
+            Interpolation::Step => 0.0,
+            Interpolation::Bezier([x1, y1, x2, y2]) => {
+                let s = solve_bezier(*x1, *x2, t.clamp(0.0, 1.0));
+                bezier(*y1, *y2, s)
+            }
+        }
+    }
+}
+
+/// One coordinate of a cubic Bezier from 0 to 1 with control coordinates `a` and `b`
+fn bezier(a: f32, b: f32, s: f32) -> f32 {
+    let r = 1.0 - s;
+    3.0 * r * r * s * a + 3.0 * r * s * s * b + s * s * s
+}
+
+/// Parameter where the curve's x coordinate reaches `x`
+fn solve_bezier(x1: f32, x2: f32, x: f32) -> f32 {
+    // Newton's method converges quickly on most curves...
+    let mut s = x;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, s) - x;
+        if error.abs() < 1e-6 {
+            return s;
+        }
+        let r = 1.0 - s;
+        let slope = 3.0 * r * r * x1 + 6.0 * r * s * (x2 - x1) + 3.0 * s * s * (1.0 - x2);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        s = (s - error / slope).clamp(0.0, 1.0);
+    }
+
+    // ...and bisection handles the flat ones
+    let (mut low, mut high) = (0.0, 1.0);
+    s = x;
+    for _ in 0..32 {
+        if bezier(x1, x2, s) < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    s
+}
+
+/// A value at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub time: f32,
+    pub value: f32,
+    /// How the value moves towards the next key
+    #[serde(default)]
+    pub interpolation: Interpolation,
+}
+
+impl Keyframe {
+    pub fn new(time: f32, value: f32) -> Self {
+        Self {
+            time,
+            value,
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+}
+
+/// Numeric field a clip animates
+///
+/// Serialized by name: the component fields tweens animate (`"position.x"`,
+/// `"alpha"`, ...) or `"script.<property>"` for a number property of the
+/// entity's Script.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum AnimationField {
+    Property(TweenProperty),
+    Script(String),
+}
+
+impl AnimationField {
+    /// Current value on an entity, or `None` if it lacks the field
+    pub fn get(&self, world: &World, entity: EntityHandle) -> Option<f32> {
+        match self {
+            AnimationField::Property(property) => property.get(world, entity),
+            AnimationField::Script(name) => world
+                .get::<Script>(entity)
+                .ok()
+                .and_then(|script| script.properties.get(name).and_then(ScriptValue::as_number))
+                .map(|value| value as f32),
+        }
+    }
+
+    /// Set the value on an entity, reporting the component as changed
+    ///
+    /// Script properties are added if missing; does nothing if the entity
+    /// lacks the component.
+    pub fn set(&self, world: &World, entity: EntityHandle, value: f32) {
+        match self {
+            AnimationField::Property(property) => property.set(world, entity, value),
+            AnimationField::Script(name) => {
+                if let Ok(mut script) = world.get_mut::<Script>(entity) {
+                    script.properties.insert(name.clone(), ScriptValue::Number(value as f64));
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for AnimationField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnimationField::Property(property) => f.write_str(property.name()),
+            AnimationField::Script(name) => write!(f, "script.{}", name),
+        }
+    }
+}
+
+impl From<AnimationField> for String {
+    fn from(field: AnimationField) -> Self {
+        field.to_string()
+    }
+}
+
+impl TryFrom<String> for AnimationField {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        if let Some(property) = name.strip_prefix("script.") {
+            return Ok(AnimationField::Script(property.to_string()));
+        }
+        TweenProperty::from_name(&name)
+            .map(AnimationField::Property)
+            .ok_or_else(|| format!("unknown animation field '{}'", name))
+    }
+}
+
+/// Keys of one field of one entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationTrack {
+    /// Names from the animated entity down to the target, separated by `/`;
+    /// empty for the animated entity itself
+    #[serde(default)]
+    pub path: String,
+    pub field: AnimationField,
+    /// Keys sorted by time
+    pub keys: Vec<Keyframe>,
+}
+
+impl AnimationTrack {
+    pub fn new(path: impl Into<String>, field: AnimationField) -> Self {
+        Self {
+            path: path.into(),
+            field,
+            keys: Vec::new(),
+        }
+    }
+
+    /// Value at `time`, holding the first and last keys' values outside
+    /// them; `None` without keys
+    pub fn sample(&self, time: f32) -> Option<f32> {
+        let next = self.keys.partition_point(|key| key.time <= time);
+        if next == 0 {
+            return self.keys.first().map(|key| key.value);
+        }
+        let key = &self.keys[next - 1];
+        let Some(after) = self.keys.get(next) else {
+            return Some(key.value);
+        };
+        let t = (time - key.time) / (after.time - key.time);
+        Some(key.value + (after.value - key.value) * key.interpolation.apply(t))
+    }
+
+    /// Add a key, replacing the value of one at the same time
+    ///
+    /// Returns the key's index.
+    pub fn set_key(&mut self, time: f32, value: f32) -> usize {
+        if let Some(index) = self.key_at(time) {
+            self.keys[index].value = value;
+            return index;
+        }
+        let index = self.keys.partition_point(|key| key.time < time);
+        self.keys.insert(index, Keyframe::new(time, value));
+        index
+    }
+
+    /// Index of the key at `time`, if any
+    pub fn key_at(&self, time: f32) -> Option<usize> {
+        self.keys.iter().position(|key| (key.time - time).abs() < KEY_TIME_EPSILON)
+    }
+
+    /// Move a key to another time, keeping keys sorted; returns its new index
+    pub fn move_key(&mut self, index: usize, time: f32) -> usize {
+        let mut key = self.keys.remove(index);
+        if let Some(existing) = self.key_at(time) {
+            self.keys.remove(existing);
+        }
+        key.time = time;
+        let index = self.keys.partition_point(|k| k.time < time);
+        self.keys.insert(index, key);
+        index
+    }
+}
+
+/// Named marker on a clip's timeline, sent when playback passes it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationEvent {
+    pub time: f32,
+    pub name: String,
+    /// Extra data sent with the event
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub data: serde_json::Value,
+}
+
+/// Keyframed animation of fields of an entity and its descendants (`.anim`)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AnimationClip {
+    /// Length in seconds; the last key or event if longer
+    #[serde(default)]
+    pub duration: f32,
+    /// Whether playback wraps around at the end
+    #[serde(default)]
+    pub looping: bool,
+    #[serde(default)]
+    pub tracks: Vec<AnimationTrack>,
+    /// Events sorted by time
+    #[serde(default)]
+    pub events: Vec<AnimationEvent>,
+}
+
+impl AnimationClip {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Length in seconds, covering every key and event
+    pub fn length(&self) -> f32 {
+        let keys = self.tracks.iter().filter_map(|track| track.keys.last()).map(|key| key.time);
+        let events = self.events.iter().map(|event| event.time);
+        keys.chain(events).fold(self.duration, f32::max)
+    }
+
+    pub fn track(&self, path: &str, field: &AnimationField) -> Option<&AnimationTrack> {
+        self.tracks.iter().find(|track| track.path == path && &track.field == field)
+    }
+
+    /// The track of a field, added if missing
+    pub fn track_mut(&mut self, path: &str, field: &AnimationField) -> &mut AnimationTrack {
+        let index = match self.tracks.iter().position(|track| track.path == path && &track.field == field) {
+            Some(index) => index,
+            None => {
+                self.tracks.push(AnimationTrack::new(path, field.clone()));
+                self.tracks.len() - 1
+            }
+        };
+        &mut self.tracks[index]
+    }
+
+    /// Key a field's value at `time`, adding its track if needed
+    pub fn set_key(&mut self, path: &str, field: AnimationField, time: f32, value: f32) {
+        self.track_mut(path, &field).set_key(time, value);
+    }
+
+    /// Add an event, keeping events sorted by time
+    pub fn add_event(&mut self, time: f32, name: impl Into<String>) -> &mut AnimationEvent {
+        let index = self.events.partition_point(|event| event.time <= time);
+        self.events.insert(
+            index,
+            AnimationEvent {
+                time,
+                name: name.into(),
+                data: serde_json::Value::Null,
+            },
+        );
+        &mut self.events[index]
+    }
+
+    /// Value of every track with keys at `time`
+    pub fn sample(&self, time: f32) -> impl Iterator<Item = (&AnimationTrack, f32)> {
+        self.tracks
+            .iter()
+            .filter_map(move |track| track.sample(time).map(|value| (track, value)))
+    }
+
+    /// Set the fields of `root` and its descendants to their values at `time`
+    ///
+    /// Tracks whose entity doesn't exist are skipped.
+    pub fn apply(&self, world: &World, root: EntityHandle, time: f32) {
+        for (track, value) in self.sample(time) {
+            if let Some(entity) = find_path(world, root, &track.path) {
+                track.field.set(world, entity, value);
+            }
+        }
+    }
+}
+
+/// Follow a track path of child names down from `root`
+pub fn find_path(world: &World, root: EntityHandle, path: &str) -> Option<EntityHandle> {
+    let mut entity = root;
+    for name in path.split('/').filter(|name| !name.is_empty()) {
+        let children = world.get::<Children>(entity).ok()?.0.clone();
+        entity = children
+            .into_iter()
+            .map(EntityHandle::new)
+            .find(|&child| world.get::<Name>(child).is_ok_and(|n| n.0 == name))?;
+    }
+    Some(entity)
+}
+
+/// Track path from `root` down to `entity`, or `None` if `entity` isn't
+/// `root` or one of its descendants
+pub fn entity_path(world: &World, root: EntityHandle, entity: EntityHandle) -> Option<String> {
+    let mut names = Vec::new();
+    let mut current = entity;
+    while current != root {
+        names.push(world.get::<Name>(current).ok()?.0.clone());
+        current = EntityHandle::new(world.get::<crate::ecs::Parent>(current).ok()?.0);
+    }
+    names.reverse();
+    Some(names.join("/"))
+}
+
+/// A clip fading out while the player's clip fades in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationBlend {
+    pub clip: AssetId,
+    /// Playback position in the fading clip
+    pub time: f32,
+    /// Seconds the crossfade lasts and has run
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+/// Plays an `AnimationClip` on the entity and its descendants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationPlayer {
+    pub clip: AssetId,
+    /// Playback rate; 1 is normal speed
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+    pub playing: bool,
+    /// Playback position in seconds
+    #[serde(skip)]
+    pub time: f32,
+    /// Clip being crossfaded from
+    #[serde(skip)]
+    pub blend: Option<AnimationBlend>,
+    /// Whether `update_animations` applied the clip since it started
+    #[serde(skip)]
+    pub(crate) started: bool,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+impl AnimationPlayer {
+    /// Create a player playing `clip` from the start
+    pub fn new(clip: AssetId) -> Self {
+        Self {
+            clip,
+            speed: 1.0,
+            playing: true,
+            time: 0.0,
+            blend: None,
+            started: false,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_playing(mut self, playing: bool) -> Self {
+        self.playing = playing;
+        self
+    }
+
+    /// Play a clip from the start, cutting off the current one
+    pub fn play(&mut self, clip: AssetId) {
+        self.clip = clip;
+        self.time = 0.0;
+        self.playing = true;
+        self.blend = None;
+        self.started = false;
+    }
+
+    /// Play a clip from the start, fading the current one out over
+    /// `duration` seconds
+    pub fn crossfade(&mut self, clip: AssetId, duration: f32) {
+        let blend = AnimationBlend {
+            clip: self.clip,
+            time: self.time,
+            duration,
+            elapsed: 0.0,
+        };
+        self.play(clip);
+        if duration > 0.0 {
+            self.blend = Some(blend);
+        }
+    }
+
+    /// Weight of the current clip against the one fading out (0..1)
+    pub fn blend_weight(&self) -> f32 {
+        self.blend
+            .map_or(1.0, |blend| (blend.elapsed / blend.duration).clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::add_child;
+    use crate::math::Transform;
+
+    #[test]
+    fn test_track_interpolation() {
+        let mut track = AnimationTrack::new("", AnimationField::Property(TweenProperty::PositionX));
+        assert_eq!(track.sample(1.0), None);
+        track.set_key(2.0, 10.0);
+        track.set_key(0.0, 0.0);
+        track.set_key(3.0, 20.0);
+        assert_eq!(track.keys.iter().map(|k| k.time).collect::<Vec<_>>(), vec![0.0, 2.0, 3.0]);
+
+        assert_eq!(track.sample(-1.0), Some(0.0));
+        assert_eq!(track.sample(1.0), Some(5.0));
+        assert_eq!(track.sample(5.0), Some(20.0));
+
+        track.keys[1].interpolation = Interpolation::Step;
+        assert_eq!(track.sample(2.9), Some(10.0));
+        track.keys[0].interpolation = Interpolation::EASE;
+        let eased = track.sample(0.5).unwrap();
+        assert!(eased < 2.5 && eased > 0.0, "eased to {}", eased);
+        assert!((track.sample(1.0).unwrap() - 5.0).abs() < 1e-3);
+
+        // Re-keying an existing time replaces its value
+        assert_eq!(track.set_key(2.0, 12.0), 1);
+        assert_eq!(track.keys.len(), 3);
+        assert_eq!(track.move_key(1, 4.0), 2);
+        assert_eq!(track.keys[2], Keyframe::new(4.0, 12.0).with_interpolation(Interpolation::Step));
+    }
+
+    #[test]
+    fn test_clip_json_and_paths() {
+        let json = r#"{
+            "duration": 1.0,
+            "tracks": [
+                { "field": "alpha", "keys": [{ "time": 0.0, "value": 1.0 }, { "time": 2.0, "value": 0.0, "interpolation": "step" }] },
+                { "path": "Arm/Hand", "field": "script.grip", "keys": [{ "time": 0.0, "value": 0.5, "interpolation": { "bezier": [0.4, 0.0, 0.6, 1.0] } }] }
+            ],
+            "events": [{ "time": 1.5, "name": "swing", "data": { "power": 2 } }]
+        }"#;
+        let clip: AnimationClip = serde_json::from_str(json).unwrap();
+        assert_eq!(clip.length(), 2.0);
+        assert_eq!(clip.tracks[1].field, AnimationField::Script("grip".to_string()));
+        assert_eq!(clip.events[0].data["power"], 2);
+        let roundtrip: AnimationClip = serde_json::from_str(&serde_json::to_string(&clip).unwrap()).unwrap();
+        assert_eq!(roundtrip, clip);
+        assert!(serde_json::from_str::<AnimationClip>(r#"{ "tracks": [{ "field": "size", "keys": [] }] }"#).is_err());
+
+        let mut world = World::new();
+        let root = world.spawn().with(Transform::new()).build();
+        let arm = world.spawn().with(Name::new("Arm")).build();
+        let hand = world
+            .spawn()
+            .with(Name::new("Hand"))
+            .with(Script::new("Hand.ts"))
+            .build();
+        add_child(&mut world, root, arm).unwrap();
+        add_child(&mut world, arm, hand).unwrap();
+        assert_eq!(find_path(&world, root, "Arm/Hand"), Some(hand));
+        assert_eq!(find_path(&world, root, ""), Some(root));
+        assert_eq!(find_path(&world, root, "Hand"), None);
+        assert_eq!(entity_path(&world, root, hand).as_deref(), Some("Arm/Hand"));
+        assert_eq!(entity_path(&world, arm, root), None);
+
+        clip.apply(&world, root, 0.0);
+        let script = world.get::<Script>(hand).unwrap();
+        assert_eq!(script.properties.get("grip"), Some(&ScriptValue::Number(0.5)));
+    }
+}
@@ -0,0 +1,625 @@
+use crate::ecs::{EntityHandle, EntityRef, MapEntities, PostEffect, World};
+use crate::math::Rect;
+use crate::types::AssetId;
+use glam::{Mat4, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 2D camera component with position, zoom, and viewport size
+///
+/// Several enabled cameras can render in the same frame, each to its own
+/// `viewport` area of the screen (split-screen, minimaps); they draw in
+/// ascending `order`.
+///
+/// A camera with a `target` renders into that render texture instead of
+/// the screen, so sprites using the texture show what it sees. Cameras
+/// rendering to textures draw before those rendering to the screen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Camera {
+    /// Camera position in world space
+    pub position: Vec2,
+    /// Camera zoom level (1.0 = normal, >1.0 = zoomed in, <1.0 = zoomed out)
+    pub zoom: f32,
+    /// Viewport size in pixels (set from the screen area when rendering)
+    pub viewport_size: Vec2,
+    /// Screen area the camera renders to, normalized to the screen size
+    pub viewport: ViewportRect,
+    /// Render order; cameras with a higher order draw on top
+    pub order: i32,
+    /// Disabled cameras don't render
+    pub enabled: bool,
+    /// Follow a target entity
+    pub follow: Option<CameraFollow>,
+    /// World area the visible region is kept inside
+    pub bounds: Option<Rect>,
+    /// Trauma-based screen shake
+    pub shake: CameraShake,
+    /// Render texture asset to render into instead of the screen
+    pub target: Option<AssetId>,
+    /// Effects applied to the rendered image, in order
+    pub post_process: Vec<PostEffect>,
+}
+
+/// Marker component indicating this camera is the main game camera
+/// Only one MainCamera should exist per scene
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct MainCamera;
+
+impl Camera {
+    /// Create a new camera with the given viewport dimensions
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            viewport_size: Vec2::new(width, height),
+            viewport: ViewportRect::FULL,
+            order: 0,
+            enabled: true,
+            follow: None,
+            bounds: None,
+            shake: CameraShake::default(),
+            target: None,
+            post_process: Vec::new(),
+        }
+    }
+
+    /// Render to part of the screen
+    pub fn with_viewport(mut self, viewport: ViewportRect) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Set the render order
+    pub fn with_order(mut self, order: i32) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Follow a target entity
+    pub fn with_follow(mut self, follow: CameraFollow) -> Self {
+        self.follow = Some(follow);
+        self
+    }
+
+    /// Render into a render texture instead of the screen
+    pub fn with_target(mut self, target: AssetId) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Add a post-processing effect after the existing ones
+    pub fn with_effect(mut self, effect: PostEffect) -> Self {
+        self.post_process.push(effect);
+        self
+    }
+
+    /// Keep the visible area inside world bounds
+    pub fn with_bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Position the view is rendered from (camera position plus shake)
+    pub fn view_position(&self) -> Vec2 {
+        self.position + self.shake.offset()
+    }
+
+    /// Half the visible area in world units
+    fn half_extents(&self) -> Vec2 {
+        self.viewport_size / 2.0 / self.zoom
+    }
+
+    /// Get the view-projection matrix for this camera
+    pub fn view_projection(&self) -> Mat4 {
+        // Calculate the orthographic projection based on viewport size and zoom
+        let half = self.half_extents();
+
+        // Create orthographic projection
+        let projection = Mat4::orthographic_rh(-half.x, half.x, -half.y, half.y, -1.0, 1.0);
+
+        // Create view matrix (translate to camera position)
+        let position = self.view_position();
+        let view = Mat4::from_translation(Vec3::new(-position.x, -position.y, 0.0));
+
+        projection * view
+    }
+
+    /// Get the visible bounds in world space [min_x, min_y, max_x, max_y]
+    pub fn visible_bounds(&self) -> [f32; 4] {
+        let rect = self.visible_rect();
+        [rect.min.x, rect.min.y, rect.max.x, rect.max.y]
+    }
+
+    /// Get the visible area in world space as a rectangle
+    pub fn visible_rect(&self) -> Rect {
+        Rect::from_center_size(self.view_position(), self.half_extents() * 2.0)
+    }
+
+    /// Convert screen coordinates to world coordinates
+    ///
+    /// Screen coordinates are relative to the camera's viewport.
+    pub fn screen_to_world(&self, screen_pos: Vec2) -> Vec2 {
+        // Convert from screen space (0,0 at top-left) to NDC space (-1 to 1)
+        let ndc_x = (screen_pos.x / self.viewport_size.x) * 2.0 - 1.0;
+        let ndc_y = 1.0 - (screen_pos.y / self.viewport_size.y) * 2.0;
+
+        // Apply zoom and camera position
+        let half = self.half_extents();
+        self.view_position() + Vec2::new(ndc_x * half.x, ndc_y * half.y)
+    }
+
+    /// Convert world coordinates to screen coordinates
+    pub fn world_to_screen(&self, world_pos: Vec2) -> Vec2 {
+        // Convert from world space to NDC space
+        let ndc = (world_pos - self.view_position()) / self.half_extents();
+
+        // Convert from NDC space to screen space
+        Vec2::new(
+            (ndc.x + 1.0) * self.viewport_size.x / 2.0,
+            (1.0 - ndc.y) * self.viewport_size.y / 2.0,
+        )
+    }
+
+    /// Add screen shake trauma (clamped to 0..1)
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.shake.add_trauma(amount);
+    }
+
+    /// Advance follow, bounds clamping and shake by `dt` seconds
+    ///
+    /// `target` is the world position of the follow target, if it exists.
+    pub fn update(&mut self, dt: f32, target: Option<Vec2>) {
+        if let (Some(follow), Some(target)) = (&self.follow, target) {
+            self.position = follow.step(self.position, target, dt);
+        }
+        self.clamp_to_bounds();
+        self.shake.update(dt);
+    }
+
+    /// Move the camera so the visible area stays inside `bounds`
+    ///
+    /// On an axis where the bounds are smaller than the view, the camera is
+    /// centered on the bounds instead.
+    pub fn clamp_to_bounds(&mut self) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+        let half = self.half_extents();
+        let clamp_axis = |value: f32, min: f32, max: f32, half: f32| {
+            if max - min <= half * 2.0 {
+                (min + max) / 2.0
+            } else {
+                value.clamp(min + half, max - half)
+            }
+        };
+        self.position = Vec2::new(
+            clamp_axis(self.position.x, bounds.min.x, bounds.max.x, half.x),
+            clamp_axis(self.position.y, bounds.min.y, bounds.max.y, half.y),
+        );
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(800.0, 600.0)
+    }
+}
+
+impl MapEntities for Camera {
+    fn map_entities(&mut self, world: &World, entity_map: &HashMap<u64, EntityHandle>) {
+        if let Some(follow) = &mut self.follow {
+            follow.target.map_entities(world, entity_map);
+        }
+    }
+}
+
+/// Normalized screen rectangle (0..1, origin at the top-left)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ViewportRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ViewportRect {
+    /// The whole screen
+    pub const FULL: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    /// Create a normalized viewport rectangle
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Convert to pixels for a screen of the given size
+    ///
+    /// Edges are rounded so adjacent viewports share their border pixels
+    /// without gaps or overlap.
+    pub fn to_pixels(&self, screen_width: u32, screen_height: u32) -> ScreenRect {
+        let to_pixel = |value: f32, size: u32| (value.clamp(0.0, 1.0) * size as f32).round() as u32;
+        let x = to_pixel(self.x, screen_width);
+        let y = to_pixel(self.y, screen_height);
+        let right = to_pixel(self.x + self.width, screen_width);
+        let bottom = to_pixel(self.y + self.height, screen_height);
+        ScreenRect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
+
+impl Default for ViewportRect {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// Pixel rectangle on the render target (origin at the top-left)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScreenRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenRect {
+    /// A rectangle covering a whole target of the given size
+    pub fn full(width: u32, height: u32) -> Self {
+        Self { x: 0, y: 0, width, height }
+    }
+
+    /// Check if the rectangle covers no pixels
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+/// Smoothly follow an entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraFollow {
+    /// Entity to follow
+    pub target: EntityRef,
+    /// Offset from the target's position
+    pub offset: Vec2,
+    /// Time in seconds to close most of the distance (0 = snap to target)
+    pub damping: f32,
+    /// Half-size of the area around the camera the target can move in
+    /// without the camera moving
+    pub dead_zone: Vec2,
+}
+
+impl CameraFollow {
+    /// Follow a target with no damping or dead zone
+    pub fn new(target: EntityRef) -> Self {
+        Self {
+            target,
+            offset: Vec2::ZERO,
+            damping: 0.0,
+            dead_zone: Vec2::ZERO,
+        }
+    }
+
+    /// Set the damping time
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Set the dead zone half-size
+    pub fn with_dead_zone(mut self, dead_zone: Vec2) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+
+    /// Set the offset from the target
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Compute the next camera position
+    pub fn step(&self, position: Vec2, target: Vec2, dt: f32) -> Vec2 {
+        let delta = target + self.offset - position;
+
+        // Only the part of the distance outside the dead zone is followed
+        let outside = |delta: f32, dead_zone: f32| {
+            let dead_zone = dead_zone.max(0.0);
+            if delta.abs() <= dead_zone {
+                0.0
+            } else {
+                delta - dead_zone * delta.signum()
+            }
+        };
+        let delta = Vec2::new(outside(delta.x, self.dead_zone.x), outside(delta.y, self.dead_zone.y));
+
+        if self.damping <= 0.0 {
+            return position + delta;
+        }
+        // Exponential smoothing, independent of frame rate
+        position + delta * (1.0 - (-dt / self.damping).exp())
+    }
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self::new(EntityRef::none())
+    }
+}
+
+/// Trauma-based screen shake
+///
+/// Trauma (0..1) is added by impacts and decays over time; the shake offset
+/// scales with trauma squared so small hits stay subtle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraShake {
+    /// Offset at full trauma in world units
+    pub max_offset: Vec2,
+    /// How fast the shake moves (noise samples per second)
+    pub frequency: f32,
+    /// Trauma lost per second
+    pub decay: f32,
+    #[serde(skip)]
+    trauma: f32,
+    #[serde(skip)]
+    time: f32,
+    #[serde(skip)]
+    offset: Vec2,
+}
+
+impl CameraShake {
+    /// Current trauma (0..1)
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Current shake offset
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    /// Add trauma (clamped to 0..1)
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Advance the shake by `dt` seconds
+    pub fn update(&mut self, dt: f32) {
+        self.trauma = (self.trauma - self.decay * dt).max(0.0);
+        if self.trauma == 0.0 {
+            self.time = 0.0;
+            self.offset = Vec2::ZERO;
+            return;
+        }
+
+        self.time += dt;
+        let t = self.time * self.frequency;
+        let strength = self.trauma * self.trauma;
+        self.offset = self.max_offset * strength * Vec2::new(noise(t, 0), noise(t, 1));
+    }
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            max_offset: Vec2::splat(16.0),
+            frequency: 25.0,
+            decay: 1.0,
+            trauma: 0.0,
+            time: 0.0,
+            offset: Vec2::ZERO,
+        }
+    }
+}
+
+/// Smooth 1D value noise in -1..1
+fn noise(t: f32, seed: u32) -> f32 {
+    let cell = t.floor();
+    let f = t - cell;
+    let a = hash(cell as i32, seed);
+    let b = hash(cell as i32 + 1, seed);
+    let s = f * f * (3.0 - 2.0 * f);
+    a + (b - a) * s
+}
+
+/// Integer hash mapped to -1..1
+fn hash(i: i32, seed: u32) -> f32 {
+    let mut x = (i as u32).wrapping_mul(0x9E37_79B1) ^ seed.wrapping_mul(0x85EB_CA6B);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x2C1B_3C6D);
+    x ^= x >> 12;
+    x = x.wrapping_mul(0x297A_2D39);
+    x ^= x >> 15;
+    (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camera_new() {
+        let camera = Camera::new(800.0, 600.0);
+        assert_eq!(camera.position, Vec2::ZERO);
+        assert_eq!(camera.zoom, 1.0);
+        assert_eq!(camera.viewport_size, Vec2::new(800.0, 600.0));
+        assert_eq!(camera.viewport, ViewportRect::FULL);
+        assert!(camera.enabled);
+    }
+
+    #[test]
+    fn test_visible_bounds() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.position = Vec2::new(100.0, 50.0);
+        camera.zoom = 1.0;
+
+        let bounds = camera.visible_bounds();
+        assert_eq!(bounds[0], 100.0 - 400.0); // min_x
+        assert_eq!(bounds[1], 50.0 - 300.0); // min_y
+        assert_eq!(bounds[2], 100.0 + 400.0); // max_x
+        assert_eq!(bounds[3], 50.0 + 300.0); // max_y
+    }
+
+    #[test]
+    fn test_visible_bounds_with_zoom() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.position = Vec2::ZERO;
+        camera.zoom = 2.0;
+
+        let bounds = camera.visible_bounds();
+        assert_eq!(bounds[0], -200.0); // min_x (half the width due to 2x zoom)
+        assert_eq!(bounds[1], -150.0); // min_y
+        assert_eq!(bounds[2], 200.0); // max_x
+        assert_eq!(bounds[3], 150.0); // max_y
+    }
+
+    #[test]
+    fn test_screen_to_world() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.position = Vec2::ZERO;
+        camera.zoom = 1.0;
+
+        // Center of screen should map to camera position
+        let center = camera.screen_to_world(Vec2::new(400.0, 300.0));
+        assert!((center.x - 0.0).abs() < 0.1);
+        assert!((center.y - 0.0).abs() < 0.1);
+
+        // Top-left corner
+        let top_left = camera.screen_to_world(Vec2::new(0.0, 0.0));
+        assert!((top_left.x - (-400.0)).abs() < 0.1);
+        assert!((top_left.y - 300.0).abs() < 0.1);
+
+        // Bottom-right corner
+        let bottom_right = camera.screen_to_world(Vec2::new(800.0, 600.0));
+        assert!((bottom_right.x - 400.0).abs() < 0.1);
+        assert!((bottom_right.y - (-300.0)).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_world_to_screen() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.position = Vec2::ZERO;
+        camera.zoom = 1.0;
+
+        // Camera position should map to center of screen
+        let center = camera.world_to_screen(Vec2::ZERO);
+        assert!((center.x - 400.0).abs() < 0.1);
+        assert!((center.y - 300.0).abs() < 0.1);
+
+        // Top-left of visible area
+        let top_left = camera.world_to_screen(Vec2::new(-400.0, 300.0));
+        assert!((top_left.x - 0.0).abs() < 0.1);
+        assert!((top_left.y - 0.0).abs() < 0.1);
+
+        // Bottom-right of visible area
+        let bottom_right = camera.world_to_screen(Vec2::new(400.0, -300.0));
+        assert!((bottom_right.x - 800.0).abs() < 0.1);
+        assert!((bottom_right.y - 600.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_coordinate_conversion_roundtrip() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.position = Vec2::new(100.0, 50.0);
+        camera.zoom = 1.5;
+
+        let screen_pos = Vec2::new(250.0, 180.0);
+        let world_pos = camera.screen_to_world(screen_pos);
+        let back_to_screen = camera.world_to_screen(world_pos);
+
+        assert!((back_to_screen.x - screen_pos.x).abs() < 0.1);
+        assert!((back_to_screen.y - screen_pos.y).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_main_camera_component_registration() {
+        let mut world = World::new();
+
+        let entity = world.spawn().build();
+        world.set(entity, MainCamera).unwrap();
+
+        assert!(world.has::<MainCamera>(entity));
+    }
+
+    #[test]
+    fn test_follow_dead_zone_and_damping() {
+        let follow = CameraFollow::new(EntityRef::none()).with_dead_zone(Vec2::new(10.0, 0.0));
+
+        // Inside the dead zone horizontally, followed vertically
+        assert_eq!(follow.step(Vec2::ZERO, Vec2::new(8.0, 5.0), 0.016), Vec2::new(0.0, 5.0));
+        // Only the distance past the dead zone edge is followed
+        assert_eq!(follow.step(Vec2::ZERO, Vec2::new(-25.0, 0.0), 0.016), Vec2::new(-15.0, 0.0));
+
+        // Damping closes 1 - e^-1 of the distance in `damping` seconds
+        let damped = CameraFollow::new(EntityRef::none()).with_damping(0.5);
+        let position = damped.step(Vec2::ZERO, Vec2::new(100.0, 0.0), 0.5);
+        assert!((position.x - 63.212).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_clamp_to_bounds() {
+        let bounds = Rect::new(Vec2::new(0.0, 0.0), Vec2::new(1000.0, 100.0));
+        let mut camera = Camera::new(200.0, 200.0).with_bounds(bounds);
+
+        camera.position = Vec2::new(-50.0, 500.0);
+        camera.clamp_to_bounds();
+        // Left edge clamps to 100; bounds are shorter than the view vertically, so centered
+        assert_eq!(camera.position, Vec2::new(100.0, 50.0));
+
+        camera.position = Vec2::new(2000.0, 0.0);
+        camera.update(0.016, None);
+        assert_eq!(camera.position.x, 900.0);
+    }
+
+    #[test]
+    fn test_shake_decays() {
+        let mut camera = Camera::new(100.0, 100.0);
+        camera.add_trauma(0.5);
+        camera.add_trauma(0.75);
+        assert_eq!(camera.shake.trauma(), 1.0);
+
+        camera.update(0.1, None);
+        let offset = camera.shake.offset();
+        assert!(offset != Vec2::ZERO);
+        assert!(offset.abs().cmple(camera.shake.max_offset).all());
+        assert_eq!(camera.view_position(), offset);
+
+        // Fully decays after a second
+        camera.update(1.0, None);
+        assert_eq!(camera.shake.trauma(), 0.0);
+        assert_eq!(camera.view_position(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_viewport_to_pixels() {
+        let left = ViewportRect::new(0.0, 0.0, 0.5, 1.0).to_pixels(801, 600);
+        let right = ViewportRect::new(0.5, 0.0, 0.5, 1.0).to_pixels(801, 600);
+        assert_eq!(left.x + left.width, right.x);
+        assert_eq!(right.x + right.width, 801);
+        assert_eq!(ViewportRect::FULL.to_pixels(80, 60), ScreenRect::full(80, 60));
+    }
+
+    #[test]
+    fn test_camera_serde_defaults() {
+        let camera: Camera = serde_json::from_str(r#"{"zoom": 2.0}"#).unwrap();
+        assert_eq!(camera.zoom, 2.0);
+        assert!(camera.enabled);
+        assert_eq!(camera.viewport, ViewportRect::FULL);
+        assert_eq!(camera.target, None);
+        assert!(camera.post_process.is_empty());
+
+        let follow = Camera::new(10.0, 10.0).with_follow(CameraFollow::new(EntityRef::none()).with_damping(0.2));
+        let json = serde_json::to_string(&follow).unwrap();
+        assert_eq!(serde_json::from_str::<Camera>(&json).unwrap(), follow);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::ecs::{EntityGuid, EntityHandle};
+use hecs::Entity;
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Identifies a component type in change records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentType {
+    /// Rust type ID of the component
+    pub id: TypeId,
+    /// Short type name (e.g. `"Transform"`), or `"unknown"` if never registered
+    pub name: &'static str,
+}
+
+impl ComponentType {
+    /// Get the component type for `T`
+    pub fn of<T: 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: short_type_name(std::any::type_name::<T>()),
+        }
+    }
+
+    /// Check if this is the component type `T`
+    pub fn is<T: 'static>(&self) -> bool {
+        self.id == TypeId::of::<T>()
+    }
+}
+
+/// A single structural or component change recorded by `World`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldChange {
+    /// An entity was spawned
+    Spawned { entity: EntityHandle },
+    /// An entity was despawned (its GUID is kept since the entity is gone)
+    Despawned { entity: EntityHandle, guid: Option<EntityGuid> },
+    /// A component was inserted on an entity that didn't have it
+    Added { entity: EntityHandle, component: ComponentType },
+    /// An existing component was replaced or mutably borrowed
+    Changed { entity: EntityHandle, component: ComponentType },
+    /// A component was removed from a live entity
+    Removed { entity: EntityHandle, component: ComponentType },
+}
+
+impl WorldChange {
+    /// The entity the change applies to
+    pub fn entity(&self) -> EntityHandle {
+        match *self {
+            WorldChange::Spawned { entity }
+            | WorldChange::Despawned { entity, .. }
+            | WorldChange::Added { entity, .. }
+            | WorldChange::Changed { entity, .. }
+            | WorldChange::Removed { entity, .. } => entity,
+        }
+    }
+
+    /// The component type, for component changes
+    pub fn component(&self) -> Option<ComponentType> {
+        match *self {
+            WorldChange::Added { component, .. }
+            | WorldChange::Changed { component, .. }
+            | WorldChange::Removed { component, .. } => Some(component),
+            _ => None,
+        }
+    }
+}
+
+/// Per-frame change log kept by `World` when tracking is enabled
+///
+/// Changes accumulate until `World::clear_changes` (the engine clears them
+/// once per frame after forwarding them to the event bus). Recording goes
+/// through a mutex because `World::get_mut` only borrows the world shared.
+#[derive(Default)]
+pub(crate) struct ChangeTracker {
+    enabled: bool,
+    inner: Mutex<TrackerState>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    changes: Vec<WorldChange>,
+    /// (entity, component) pairs already reported as added or changed this frame
+    touched: HashSet<(Entity, TypeId)>,
+    /// Names for component types, so bundle spawns can report readable names
+    names: HashMap<TypeId, &'static str>,
+}
+
+impl ChangeTracker {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    pub(crate) fn register<T: 'static>(&self) {
+        let component = ComponentType::of::<T>();
+        self.state().names.insert(component.id, component.name);
+    }
+
+    pub(crate) fn component_type(&self, id: TypeId) -> ComponentType {
+        let name = self.state().names.get(&id).copied().unwrap_or("unknown");
+        ComponentType { id, name }
+    }
+
+    pub(crate) fn spawned(&self, entity: Entity) {
+        if self.enabled {
+            self.push(WorldChange::Spawned { entity: EntityHandle::new(entity) });
+        }
+    }
+
+    pub(crate) fn despawned(&self, entity: Entity, guid: Option<EntityGuid>) {
+        if self.enabled {
+            self.push(WorldChange::Despawned { entity: EntityHandle::new(entity), guid });
+        }
+    }
+
+    pub(crate) fn added(&self, entity: Entity, component: ComponentType) {
+        if !self.enabled {
+            return;
+        }
+        let mut state = self.state();
+        state.names.insert(component.id, component.name);
+        state.touched.insert((entity, component.id));
+        state.changes.push(WorldChange::Added { entity: EntityHandle::new(entity), component });
+    }
+
+    pub(crate) fn changed(&self, entity: Entity, component: ComponentType) {
+        if !self.enabled {
+            return;
+        }
+        let mut state = self.state();
+        // Only the first add/change per frame is reported
+        if state.touched.insert((entity, component.id)) {
+            state.names.insert(component.id, component.name);
+            state.changes.push(WorldChange::Changed { entity: EntityHandle::new(entity), component });
+        }
+    }
+
+    pub(crate) fn removed(&self, entity: Entity, component: ComponentType) {
+        if !self.enabled {
+            return;
+        }
+        let mut state = self.state();
+        state.touched.remove(&(entity, component.id));
+        state.changes.push(WorldChange::Removed { entity: EntityHandle::new(entity), component });
+    }
+
+    pub(crate) fn changes(&self) -> Vec<WorldChange> {
+        self.state().changes.clone()
+    }
+
+    /// Visit the recorded changes in order without copying the log
+    pub(crate) fn for_each(&self, mut f: impl FnMut(&WorldChange)) {
+        self.state().changes.iter().for_each(&mut f);
+    }
+
+    pub(crate) fn take(&self) -> Vec<WorldChange> {
+        let mut state = self.state();
+        state.touched.clear();
+        std::mem::take(&mut state.changes)
+    }
+
+    pub(crate) fn clear(&self) {
+        let mut state = self.state();
+        state.changes.clear();
+        state.touched.clear();
+    }
+
+    fn push(&self, change: WorldChange) {
+        self.state().changes.push(change);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, TrackerState> {
+        // A panic while recording can't leave the log inconsistent, so ignore poisoning
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Strip the module path from a type name (`longhorn_core::ecs::Name` -> `Name`)
+fn short_type_name(name: &'static str) -> &'static str {
+    if name.contains('<') {
+        name
+    } else {
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Name;
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(ComponentType::of::<Name>().name, "Name");
+        assert_eq!(ComponentType::of::<u32>().name, "u32");
+        assert_eq!(short_type_name("Vec<alloc::string::String>"), "Vec<alloc::string::String>");
+    }
+}
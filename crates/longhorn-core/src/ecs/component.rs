@@ -69,6 +69,24 @@ pub struct Sprite {
     pub color: [f32; 4], // RGBA
     pub flip_x: bool,
     pub flip_y: bool,
+    /// `Material` drawing the sprite; 0 uses the built-in sprite shader
+    #[serde(default = "no_material")]
+    pub material: AssetId,
+    /// Tangent-space normal map lighting uses for the sprite; 0 lights it
+    /// as flat. Import normal maps with `srgb: false`.
+    #[serde(default = "no_normal_map")]
+    pub normal_map: AssetId,
+    /// How the texture fills `size`
+    #[serde(default)]
+    pub draw_mode: SpriteDrawMode,
+}
+
+fn no_material() -> AssetId {
+    AssetId::new(0)
+}
+
+fn no_normal_map() -> AssetId {
+    AssetId::new(0)
 }
 
 impl Sprite {
@@ -80,6 +98,9 @@ impl Sprite {
             color: [1.0, 1.0, 1.0, 1.0],
             flip_x: false,
             flip_y: false,
+            material: no_material(),
+            normal_map: no_normal_map(),
+            draw_mode: SpriteDrawMode::Simple,
         }
     }
 
@@ -91,6 +112,9 @@ impl Sprite {
             color,
             flip_x: false,
             flip_y: false,
+            material: no_material(),
+            normal_map: no_normal_map(),
+            draw_mode: SpriteDrawMode::Simple,
         }
     }
 
@@ -113,6 +137,81 @@ impl Sprite {
     pub fn flip_vertical(&mut self, flip: bool) {
         self.flip_y = flip;
     }
+
+    /// Draw the sprite with a material
+    pub fn with_material(mut self, material: AssetId) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Light the sprite using a normal map
+    pub fn with_normal_map(mut self, normal_map: AssetId) -> Self {
+        self.normal_map = normal_map;
+        self
+    }
+
+    /// Set how the texture fills the sprite's size
+    pub fn with_draw_mode(mut self, draw_mode: SpriteDrawMode) -> Self {
+        self.draw_mode = draw_mode;
+        self
+    }
+}
+
+/// Insets from the texture edges, in texture pixels, marking the corners
+/// and edges of a nine-slice sprite
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SliceBorder {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl SliceBorder {
+    /// The same inset on every side
+    pub fn uniform(inset: f32) -> Self {
+        Self {
+            left: inset,
+            right: inset,
+            top: inset,
+            bottom: inset,
+        }
+    }
+}
+
+/// How a sprite's texture fills its size
+///
+/// One texture pixel covers one world unit (times the entity scale) in the
+/// parts that aren't stretched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SpriteDrawMode {
+    /// The whole texture stretched over the sprite
+    #[default]
+    Simple,
+    /// Corners keep their size, edges stretch along their length and the
+    /// center stretches both ways
+    NineSlice { border: SliceBorder },
+    /// The texture repeated at its own size, cropped at the right and bottom
+    Tiled,
+    /// Like `NineSlice`, with the edges and center repeated instead of stretched
+    TiledNineSlice { border: SliceBorder },
+}
+
+impl SpriteDrawMode {
+    /// Check if this is the default `Simple` mode
+    pub fn is_simple(&self) -> bool {
+        matches!(self, SpriteDrawMode::Simple)
+    }
+
+    /// The slice border, for the nine-slice modes
+    pub fn border(&self) -> Option<SliceBorder> {
+        match self {
+            SpriteDrawMode::NineSlice { border } | SpriteDrawMode::TiledNineSlice { border } => Some(*border),
+            SpriteDrawMode::Simple | SpriteDrawMode::Tiled => None,
+        }
+    }
 }
 
 /// Parent component - stores reference to parent entity
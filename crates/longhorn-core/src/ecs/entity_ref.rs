@@ -0,0 +1,131 @@
+use crate::ecs::{EntityGuid, EntityHandle, Script, ScriptValue, World};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Serializable reference to another entity
+///
+/// Stores the target's `EntityGuid` rather than `hecs::Entity` bits, so the
+/// reference stays valid across save/load and play-mode restore. Serializes
+/// as the GUID number, or `null` when empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EntityRef(pub Option<EntityGuid>);
+
+impl EntityRef {
+    /// Create an empty reference
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    /// Create a reference to the entity with the given GUID
+    pub fn new(guid: EntityGuid) -> Self {
+        Self(Some(guid))
+    }
+
+    /// Create a reference to a live entity (empty if it has no GUID)
+    pub fn to_entity(world: &World, entity: EntityHandle) -> Self {
+        Self(world.guid(entity))
+    }
+
+    /// Get the referenced GUID
+    pub fn guid(&self) -> Option<EntityGuid> {
+        self.0
+    }
+
+    /// Check if the reference is empty
+    pub fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Resolve to a live entity, if the target still exists
+    pub fn resolve(&self, world: &World) -> Option<EntityHandle> {
+        self.0.and_then(|guid| world.entity_by_guid(guid))
+    }
+}
+
+impl From<EntityGuid> for EntityRef {
+    fn from(guid: EntityGuid) -> Self {
+        Self::new(guid)
+    }
+}
+
+/// Components holding entity references implement this so scene loading and
+/// prefab instantiation can point them at the newly spawned entities.
+pub trait MapEntities {
+    /// Remap references using a serialized-ID → spawned-entity map
+    ///
+    /// References to entities outside the map are left untouched.
+    fn map_entities(&mut self, world: &World, entity_map: &HashMap<u64, EntityHandle>);
+}
+
+impl MapEntities for EntityRef {
+    fn map_entities(&mut self, world: &World, entity_map: &HashMap<u64, EntityHandle>) {
+        if let Some(guid) = self.0 {
+            if let Some(new_guid) = entity_map.get(&guid.get()).and_then(|&e| world.guid(e)) {
+                self.0 = Some(new_guid);
+            }
+        }
+    }
+}
+
+impl MapEntities for ScriptValue {
+    fn map_entities(&mut self, world: &World, entity_map: &HashMap<u64, EntityHandle>) {
+        if let ScriptValue::Entity { entity } = self {
+            entity.map_entities(world, entity_map);
+        }
+    }
+}
+
+impl MapEntities for Script {
+    fn map_entities(&mut self, world: &World, entity_map: &HashMap<u64, EntityHandle>) {
+        for value in self.properties.values_mut() {
+            value.map_entities(world, entity_map);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Name;
+
+    #[test]
+    fn test_entity_ref_resolve() {
+        let mut world = World::new();
+        let target = world.spawn().with(Name::new("Target")).build();
+
+        let entity_ref = EntityRef::to_entity(&world, target);
+        assert_eq!(entity_ref.resolve(&world), Some(target));
+
+        world.despawn(target).unwrap();
+        assert_eq!(entity_ref.resolve(&world), None);
+        assert_eq!(EntityRef::none().resolve(&world), None);
+    }
+
+    #[test]
+    fn test_entity_ref_serialization() {
+        let json = serde_json::to_string(&EntityRef::new(EntityGuid(42))).unwrap();
+        assert_eq!(json, "42");
+        assert_eq!(serde_json::to_string(&EntityRef::none()).unwrap(), "null");
+
+        let parsed: EntityRef = serde_json::from_str("42").unwrap();
+        assert_eq!(parsed, EntityRef::new(EntityGuid(42)));
+    }
+
+    #[test]
+    fn test_map_entities() {
+        let mut world = World::new();
+        let spawned = world.spawn().with(EntityGuid(100)).build();
+
+        let mut entity_map = HashMap::new();
+        entity_map.insert(7, spawned);
+
+        let mut mapped = EntityRef::new(EntityGuid(7));
+        mapped.map_entities(&world, &entity_map);
+        assert_eq!(mapped, EntityRef::new(EntityGuid(100)));
+
+        let mut external = EntityRef::new(EntityGuid(9));
+        external.map_entities(&world, &entity_map);
+        assert_eq!(external, EntityRef::new(EntityGuid(9)));
+    }
+}
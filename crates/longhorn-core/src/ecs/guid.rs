@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+/// Persistent entity identifier
+///
+/// Unlike `hecs::Entity` bits, a GUID survives save/load and play-mode
+/// restore, so scenes, scripts and remote commands can refer to an entity
+/// across respawns. `World` assigns one to every entity it spawns.
+///
+/// Generated GUIDs fit in 53 bits so they round-trip exactly through
+/// JavaScript numbers and JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EntityGuid(pub u64);
+
+impl EntityGuid {
+    /// Largest value produced by `EntityGuid::new` (2^53 - 1)
+    pub const MAX: u64 = (1 << 53) - 1;
+
+    /// Generate a new, process-unique GUID
+    pub fn new() -> Self {
+        static SEED: OnceLock<u64> = OnceLock::new();
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let seed = *SEED.get_or_init(|| {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0);
+            nanos ^ ((std::process::id() as u64) << 32)
+        });
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        // Zero is reserved so it can be used as a "no entity" marker
+        match splitmix64(seed.wrapping_add(count.wrapping_mul(0x9E37_79B9_7F4A_7C15))) & Self::MAX {
+            0 => Self(1),
+            value => Self(value),
+        }
+    }
+
+    /// Get the raw GUID value
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for EntityGuid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<u64> for EntityGuid {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<EntityGuid> for u64 {
+    fn from(guid: EntityGuid) -> Self {
+        guid.0
+    }
+}
+
+impl std::fmt::Display for EntityGuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// SplitMix64 finalizer - spreads sequential inputs across the full u64 range
+fn splitmix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_guids_are_unique() {
+        let guids: HashSet<_> = (0..10_000).map(|_| EntityGuid::new()).collect();
+        assert_eq!(guids.len(), 10_000);
+        assert!(!guids.contains(&EntityGuid(0)));
+        assert!(guids.iter().all(|g| g.get() <= EntityGuid::MAX));
+    }
+
+    #[test]
+    fn test_guid_serializes_as_number() {
+        let guid = EntityGuid(42);
+        let json = serde_json::to_string(&guid).unwrap();
+        assert_eq!(json, "42");
+
+        let parsed: EntityGuid = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, guid);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::math::GlobalTransform;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Number of edges a circular occluder is approximated with
+pub const CIRCLE_OCCLUDER_SEGMENTS: usize = 16;
+
+/// Light radiating in every direction from the entity position
+///
+/// Lighting is only enabled in scenes with at least one light component;
+/// lit sprites are multiplied by the sum of the ambient (`GlobalLight2D`)
+/// and every light reaching them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PointLight2D {
+    /// Linear RGB color
+    pub color: [f32; 3],
+    /// Multiplier applied to the color
+    pub intensity: f32,
+    /// Distance in world units at which the light fades out completely
+    pub radius: f32,
+    /// Exponent of the fade towards the radius; 1 is linear
+    pub falloff: f32,
+    /// Height above the sprites, which tilts light hitting normal-mapped
+    /// sprites; lower lights graze surfaces at a flatter angle
+    pub height: f32,
+    /// Whether `LightOccluder2D` shapes block this light
+    pub cast_shadows: bool,
+}
+
+impl Default for PointLight2D {
+    fn default() -> Self {
+        Self {
+            color: [1.0; 3],
+            intensity: 1.0,
+            radius: 200.0,
+            falloff: 1.0,
+            height: 50.0,
+            cast_shadows: false,
+        }
+    }
+}
+
+/// Cone of light from the entity position along its rotation (0 points
+/// along +X)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpotLight2D {
+    /// Linear RGB color
+    pub color: [f32; 3],
+    /// Multiplier applied to the color
+    pub intensity: f32,
+    /// Distance in world units at which the light fades out completely
+    pub radius: f32,
+    /// Exponent of the fade towards the radius; 1 is linear
+    pub falloff: f32,
+    /// Height above the sprites (see `PointLight2D::height`)
+    pub height: f32,
+    /// Full opening angle of the cone in degrees
+    pub angle: f32,
+    /// Fraction of the cone (0..1) over which its edge fades out
+    pub softness: f32,
+    /// Whether `LightOccluder2D` shapes block this light
+    pub cast_shadows: bool,
+}
+
+impl Default for SpotLight2D {
+    fn default() -> Self {
+        Self {
+            color: [1.0; 3],
+            intensity: 1.0,
+            radius: 300.0,
+            falloff: 1.0,
+            height: 50.0,
+            angle: 45.0,
+            softness: 0.2,
+            cast_shadows: false,
+        }
+    }
+}
+
+/// Ambient light reaching every sprite; several add up
+///
+/// Without one, lit scenes are black wherever no other light reaches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlobalLight2D {
+    /// Linear RGB color
+    pub color: [f32; 3],
+    /// Multiplier applied to the color
+    pub intensity: f32,
+}
+
+impl Default for GlobalLight2D {
+    fn default() -> Self {
+        Self {
+            color: [1.0; 3],
+            intensity: 0.2,
+        }
+    }
+}
+
+/// Shape of a `LightOccluder2D`, centered on the entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OccluderShape {
+    Box { size: Vec2 },
+    Circle { radius: f32 },
+    /// Closed polygon through the points, in local coordinates
+    Polygon { points: Vec<Vec2> },
+}
+
+/// Blocks light from lights with `cast_shadows`
+///
+/// The shape itself stays lit; it shadows what lies behind it as seen from
+/// the light. Lights inside a shape aren't blocked by it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LightOccluder2D {
+    pub shape: OccluderShape,
+}
+
+impl LightOccluder2D {
+    pub fn new(shape: OccluderShape) -> Self {
+        Self { shape }
+    }
+
+    /// Edges of the shape in world space, placed by the entity transform
+    pub fn segments(&self, transform: &GlobalTransform) -> Vec<[Vec2; 2]> {
+        let points: Vec<Vec2> = match &self.shape {
+            OccluderShape::Box { size } => {
+                let half = *size / 2.0;
+                vec![
+                    Vec2::new(-half.x, -half.y),
+                    Vec2::new(half.x, -half.y),
+                    Vec2::new(half.x, half.y),
+                    Vec2::new(-half.x, half.y),
+                ]
+            }
+            OccluderShape::Circle { radius } => (0..CIRCLE_OCCLUDER_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f32 / CIRCLE_OCCLUDER_SEGMENTS as f32 * std::f32::consts::TAU;
+                    Vec2::from_angle(angle) * *radius
+                })
+                .collect(),
+            OccluderShape::Polygon { points } => points.clone(),
+        };
+        if points.len() < 2 {
+            return Vec::new();
+        }
+
+        let points: Vec<Vec2> = points.iter().map(|&p| transform.transform_point(p)).collect();
+        (0..points.len())
+            .map(|i| [points[i], points[(i + 1) % points.len()]])
+            .collect()
+    }
+}
+
+impl Default for LightOccluder2D {
+    fn default() -> Self {
+        Self::new(OccluderShape::Box {
+            size: Vec2::new(32.0, 32.0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occluder_segments_follow_transform() {
+        let occluder = LightOccluder2D::new(OccluderShape::Box { size: Vec2::new(2.0, 4.0) });
+        let transform = GlobalTransform {
+            position: Vec2::new(10.0, 0.0),
+            rotation: 0.0,
+            scale: Vec2::new(2.0, 1.0),
+        };
+
+        let segments = occluder.segments(&transform);
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[0], [Vec2::new(8.0, -2.0), Vec2::new(12.0, -2.0)]);
+        // Closed: the last edge returns to the first corner
+        assert_eq!(segments[3][1], segments[0][0]);
+
+        let circle = LightOccluder2D::new(OccluderShape::Circle { radius: 1.0 });
+        assert_eq!(circle.segments(&GlobalTransform::new()).len(), CIRCLE_OCCLUDER_SEGMENTS);
+
+        let line = LightOccluder2D::new(OccluderShape::Polygon { points: vec![Vec2::ZERO] });
+        assert!(line.segments(&GlobalTransform::new()).is_empty());
+    }
+
+    #[test]
+    fn test_lights_from_json() {
+        let light: PointLight2D = serde_json::from_str(r#"{"radius": 64.0, "cast_shadows": true}"#).unwrap();
+        assert_eq!(light.radius, 64.0);
+        assert!(light.cast_shadows);
+        assert_eq!(light.intensity, 1.0);
+
+        let occluder: LightOccluder2D =
+            serde_json::from_str(r#"{"shape": {"type": "circle", "radius": 8.0}}"#).unwrap();
+        assert_eq!(occluder.shape, OccluderShape::Circle { radius: 8.0 });
+    }
+}
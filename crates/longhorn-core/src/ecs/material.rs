@@ -0,0 +1,208 @@
+use crate::types::AssetId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Type of a material parameter, as declared in its WGSL uniform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaterialParamType {
+    /// `f32`
+    Float,
+    /// `vec2<f32>`
+    Vec2,
+    /// `vec4<f32>`
+    Vec4,
+    /// RGBA color, a `vec4<f32>` in the shader
+    Color,
+}
+
+impl MaterialParamType {
+    /// Number of floats in a value of this type
+    pub fn components(self) -> usize {
+        match self {
+            MaterialParamType::Float => 1,
+            MaterialParamType::Vec2 => 2,
+            MaterialParamType::Vec4 | MaterialParamType::Color => 4,
+        }
+    }
+
+    /// Type name in WGSL
+    pub fn wgsl_type(self) -> &'static str {
+        match self {
+            MaterialParamType::Float => "f32",
+            MaterialParamType::Vec2 => "vec2<f32>",
+            MaterialParamType::Vec4 | MaterialParamType::Color => "vec4<f32>",
+        }
+    }
+}
+
+/// Value of a material parameter
+///
+/// Serialized as a number or an array of two or four numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MaterialValue {
+    Float(f32),
+    Vec2([f32; 2]),
+    Vec4([f32; 4]),
+}
+
+impl MaterialValue {
+    /// Floats of the value, or `None` if it doesn't fit a parameter of `ty`
+    pub fn components(&self, ty: MaterialParamType) -> Option<&[f32]> {
+        let components: &[f32] = match self {
+            MaterialValue::Float(value) => std::slice::from_ref(value),
+            MaterialValue::Vec2(value) => value,
+            MaterialValue::Vec4(value) => value,
+        };
+        (components.len() == ty.components()).then_some(components)
+    }
+}
+
+/// A uniform parameter declared by a material
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialParam {
+    /// Field name in the shader's `material` uniform
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: MaterialParamType,
+    /// Value used when the entity doesn't override it
+    pub default: MaterialValue,
+}
+
+/// An extra texture a material samples
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialTexture {
+    /// Texture name in the shader; its sampler is `<name>_sampler`
+    pub name: String,
+    /// Image file, relative to the material file
+    pub image: String,
+    /// Texture of the image, filled in when loaded
+    #[serde(skip, default = "no_texture")]
+    pub texture: AssetId,
+}
+
+fn no_texture() -> AssetId {
+    AssetId::new(0)
+}
+
+/// A custom fragment shader for sprites, with its parameters and textures
+///
+/// Stored as JSON (`.material`). The WGSL source comes from `shader`, a
+/// file relative to the material, or is written inline in `source`. The
+/// renderer adds the sprite vertex stage, the `material` uniform holding
+/// `params` and the declared textures; the shader defines
+/// `@fragment fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Material {
+    /// WGSL file, relative to the material file
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub shader: String,
+    /// WGSL source; read from `shader` when that is set
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub source: String,
+    pub params: Vec<MaterialParam>,
+    pub textures: Vec<MaterialTexture>,
+}
+
+impl Material {
+    /// Create a material from inline WGSL source
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Declare a parameter
+    pub fn with_param(mut self, name: impl Into<String>, ty: MaterialParamType, default: MaterialValue) -> Self {
+        self.params.push(MaterialParam {
+            name: name.into(),
+            ty,
+            default,
+        });
+        self
+    }
+
+    /// Get a declared parameter by name
+    pub fn param(&self, name: &str) -> Option<&MaterialParam> {
+        self.params.iter().find(|param| param.name == name)
+    }
+}
+
+/// Per-entity values for the parameters of its sprite's material
+///
+/// Parameters without a value here, or with a value of the wrong type, use
+/// the material's default. Scripts animate effects such as a hit flash by
+/// writing these values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaterialParams(pub BTreeMap<String, MaterialValue>);
+
+impl MaterialParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a parameter value
+    pub fn with(mut self, name: impl Into<String>, value: MaterialValue) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<MaterialValue> {
+        self.0.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: MaterialValue) {
+        self.0.insert(name.into(), value);
+    }
+
+    /// Value of a parameter of `material`: this entity's if it has a
+    /// matching one, the default otherwise
+    pub fn value<'a>(&'a self, param: &'a MaterialParam) -> &'a [f32] {
+        self.0
+            .get(&param.name)
+            .and_then(|value| value.components(param.ty))
+            .or_else(|| param.default.components(param.ty))
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_material_from_json() {
+        let material: Material = serde_json::from_str(
+            r#"{
+                "shader": "flash.wgsl",
+                "params": [
+                    {"name": "flash", "type": "float", "default": 0},
+                    {"name": "tint", "type": "color", "default": [1, 0, 0, 1]}
+                ],
+                "textures": [{"name": "noise", "image": "noise.png"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(material.shader, "flash.wgsl");
+        assert_eq!(material.param("flash").unwrap().default, MaterialValue::Float(0.0));
+        assert_eq!(material.param("tint").unwrap().ty, MaterialParamType::Color);
+        assert_eq!(material.textures[0].texture, AssetId::new(0));
+    }
+
+    #[test]
+    fn test_params_fall_back_to_defaults() {
+        let material = Material::new("")
+            .with_param("flash", MaterialParamType::Float, MaterialValue::Float(0.0))
+            .with_param("offset", MaterialParamType::Vec2, MaterialValue::Vec2([1.0, 2.0]));
+        let params = MaterialParams::new()
+            .with("flash", MaterialValue::Float(0.5))
+            .with("offset", MaterialValue::Float(3.0));
+
+        assert_eq!(params.value(material.param("flash").unwrap()), &[0.5]);
+        // Wrong type, so the default is used
+        assert_eq!(params.value(material.param("offset").unwrap()), &[1.0, 2.0]);
+    }
+}
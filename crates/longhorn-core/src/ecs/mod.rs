@@ -1,13 +1,45 @@
+pub mod animation;
+pub mod camera;
+pub mod change;
 pub mod component;
 pub mod entity;
+pub mod entity_ref;
+pub mod guid;
 pub mod hierarchy;
+pub mod light;
+pub mod material;
+pub mod navigation;
+pub mod particles;
+pub mod post_process;
 pub mod script;
+pub mod shape;
+pub mod state_machine;
+pub mod text;
+pub mod tilemap;
+pub mod tween;
+pub mod ui;
 pub mod world;
 
+pub use animation::*;
+pub use camera::*;
+pub use change::{ComponentType, WorldChange};
 pub use component::*;
 pub use entity::*;
+pub use entity_ref::*;
+pub use guid::*;
 pub use hierarchy::*;
+pub use light::*;
+pub use material::*;
+pub use navigation::*;
+pub use particles::*;
+pub use post_process::*;
 pub use script::*;
+pub use shape::*;
+pub use state_machine::*;
+pub use text::*;
+pub use tilemap::*;
+pub use tween::*;
+pub use ui::*;
 pub use world::*;
 
 // Re-export hecs types
@@ -0,0 +1,146 @@
+use crate::types::AssetId;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Where a NavAgent is on its way to its destination
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NavStatus {
+    /// No destination
+    #[default]
+    Idle,
+    /// Waiting for its path to be found
+    Pending,
+    /// Following its path
+    Moving,
+    /// Within stopping distance of the destination
+    Arrived,
+    /// No path leads to the destination
+    Unreachable,
+}
+
+impl NavStatus {
+    /// Name scripts see
+    pub fn name(&self) -> &'static str {
+        match self {
+            NavStatus::Idle => "idle",
+            NavStatus::Pending => "pending",
+            NavStatus::Moving => "moving",
+            NavStatus::Arrived => "arrived",
+            NavStatus::Unreachable => "unreachable",
+        }
+    }
+}
+
+/// Walks its entity to a destination around walls and obstacles
+///
+/// Setting a destination asks the engine's `Navigation` for a path; once
+/// it's found, the agent moves its Transform along the path at `speed`.
+/// Agents should be root entities (or children of unrotated, unscaled
+/// parents), since paths are in world space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NavAgent {
+    /// World units per second
+    pub speed: f32,
+    /// How close to the destination the agent stops
+    pub stopping_distance: f32,
+    /// Where the agent is going, in world space
+    #[serde(default)]
+    pub destination: Option<Vec2>,
+    /// Progress towards the destination
+    #[serde(skip)]
+    pub status: NavStatus,
+    /// Waypoints still ahead, ending at the destination
+    #[serde(skip)]
+    pub path: Vec<Vec2>,
+    /// Destination the current path or search is for
+    #[serde(skip)]
+    pub(crate) requested: Option<Vec2>,
+    /// Surface revision the path was last checked against
+    #[serde(skip)]
+    pub(crate) revision: u64,
+}
+
+impl NavAgent {
+    /// Create an agent without a destination
+    pub fn new(speed: f32) -> Self {
+        Self {
+            speed,
+            stopping_distance: 0.0,
+            destination: None,
+            status: NavStatus::Idle,
+            path: Vec::new(),
+            requested: None,
+            revision: 0,
+        }
+    }
+
+    /// Stop this far from the destination
+    pub fn with_stopping_distance(mut self, distance: f32) -> Self {
+        self.stopping_distance = distance;
+        self
+    }
+
+    /// Walk to a point, finding a new path even if it's the current destination
+    pub fn set_destination(&mut self, destination: Vec2) {
+        self.destination = Some(destination);
+        self.requested = None;
+    }
+
+    /// Stop where the agent is and forget the destination
+    pub fn stop(&mut self) {
+        self.destination = None;
+    }
+}
+
+impl Default for NavAgent {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+/// Box around its entity that NavAgents walk around
+///
+/// Covers the navigation grid cells it overlaps while it's there, and the
+/// agents whose paths it blocks find new ones.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NavObstacle {
+    /// Width and height in world units, centered on the entity
+    pub size: Vec2,
+}
+
+impl NavObstacle {
+    /// Create an obstacle of the given size
+    pub fn new(size: Vec2) -> Self {
+        Self { size }
+    }
+}
+
+impl Default for NavObstacle {
+    fn default() -> Self {
+        Self::new(Vec2::splat(32.0))
+    }
+}
+
+/// Area NavAgents walk on
+///
+/// Uses the baked `navmesh` if set; otherwise builds a navigation grid from
+/// the solid tiles of the entity's Tilemap. The engine navigates on the
+/// first enabled region.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NavRegion {
+    /// Baked `.navmesh` asset
+    pub navmesh: Option<AssetId>,
+}
+
+impl NavRegion {
+    /// Region built from the entity's Tilemap
+    pub fn from_tilemap() -> Self {
+        Self { navmesh: None }
+    }
+
+    /// Region using a baked navmesh
+    pub fn from_navmesh(navmesh: AssetId) -> Self {
+        Self { navmesh: Some(navmesh) }
+    }
+}
@@ -0,0 +1,362 @@
+use crate::types::AssetId;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Values a `Curve` can interpolate between
+pub trait Lerp: Copy {
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for [f32; 4] {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].lerp(other[i], t))
+    }
+}
+
+/// Piecewise linear curve over normalized time (0..1)
+///
+/// Serialized as a list of `[time, value]` keys; keys are kept sorted by
+/// time. Before the first key and after the last the curve holds their
+/// values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<(f32, T)>", into = "Vec<(f32, T)>")]
+#[serde(bound(serialize = "T: Clone + Serialize", deserialize = "T: Lerp + Deserialize<'de>"))]
+pub struct Curve<T> {
+    keys: Vec<(f32, T)>,
+}
+
+impl<T: Lerp> Curve<T> {
+    /// Create a curve from `(time, value)` keys in any order
+    pub fn new(mut keys: Vec<(f32, T)>) -> Self {
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keys }
+    }
+
+    /// A curve holding one value
+    pub fn constant(value: T) -> Self {
+        Self { keys: vec![(0.0, value)] }
+    }
+
+    /// A curve going from `from` at time 0 to `to` at time 1
+    pub fn linear(from: T, to: T) -> Self {
+        Self {
+            keys: vec![(0.0, from), (1.0, to)],
+        }
+    }
+
+    /// Keys sorted by time
+    pub fn keys(&self) -> &[(f32, T)] {
+        &self.keys
+    }
+
+    /// Value at time `t`, or `None` for a curve without keys
+    pub fn sample(&self, t: f32) -> Option<T> {
+        let next = self.keys.partition_point(|(time, _)| *time <= t);
+        match (next.checked_sub(1).map(|i| self.keys[i]), self.keys.get(next)) {
+            (Some((t0, v0)), Some(&(t1, v1))) => Some(v0.lerp(v1, (t - t0) / (t1 - t0))),
+            (Some((_, value)), None) | (None, Some(&(_, value))) => Some(value),
+            (None, None) => None,
+        }
+    }
+}
+
+impl<T: Lerp> From<Vec<(f32, T)>> for Curve<T> {
+    fn from(keys: Vec<(f32, T)>) -> Self {
+        Self::new(keys)
+    }
+}
+
+impl<T> From<Curve<T>> for Vec<(f32, T)> {
+    fn from(curve: Curve<T>) -> Self {
+        curve.keys
+    }
+}
+
+/// Space particles move in once emitted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationSpace {
+    /// Particles stay where they were emitted when the emitter moves
+    #[default]
+    World,
+    /// Particles move, rotate and scale with the emitter
+    Local,
+}
+
+/// Particles emitted at once at a point of the emission cycle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Burst {
+    /// Seconds into the cycle
+    pub time: f32,
+    pub count: u32,
+}
+
+/// How a `ParticleEmitter` emits and animates its particles
+///
+/// Saved on its own as a particle preset asset (`.particles`, JSON).
+/// Ranges are `[min, max]`, picked uniformly per particle. Angles are in
+/// degrees, counter-clockwise from +x and relative to the emitter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticleEffect {
+    /// Particles emitted per second while playing
+    pub rate: f32,
+    pub bursts: Vec<Burst>,
+    /// Length of one emission cycle in seconds
+    pub duration: f32,
+    /// Restart the cycle when it ends; otherwise the emitter stops
+    pub looping: bool,
+    /// Seconds each particle lives
+    pub lifetime: [f32; 2],
+    /// Initial speed in world units per second
+    pub speed: [f32; 2],
+    pub direction: f32,
+    /// Angle around `direction` particles are emitted in
+    pub spread: f32,
+    /// Radius of the circle around the emitter particles start in
+    pub radius: f32,
+    /// Acceleration in world units per second squared
+    pub gravity: Vec2,
+    /// Rotation speed in degrees per second
+    pub spin: [f32; 2],
+    /// Size in world units over the particle's lifetime
+    pub size: Curve<f32>,
+    /// RGBA color over the particle's lifetime, multiplied with the texture
+    pub color: Curve<[f32; 4]>,
+    /// Particles alive at once; emission pauses at the limit
+    pub max_particles: usize,
+    pub space: SimulationSpace,
+}
+
+impl ParticleEffect {
+    /// Emit `rate` particles per second forever
+    pub fn continuous(rate: f32) -> Self {
+        Self {
+            rate,
+            ..Default::default()
+        }
+    }
+
+    /// Emit `count` particles once at the start of a single cycle
+    pub fn one_shot(count: u32) -> Self {
+        Self {
+            rate: 0.0,
+            bursts: vec![Burst { time: 0.0, count }],
+            looping: false,
+            max_particles: (count as usize).max(Self::default().max_particles),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ParticleEffect {
+    fn default() -> Self {
+        Self {
+            rate: 10.0,
+            bursts: Vec::new(),
+            duration: 1.0,
+            looping: true,
+            lifetime: [1.0, 1.0],
+            speed: [50.0, 100.0],
+            direction: 90.0,
+            spread: 30.0,
+            radius: 0.0,
+            gravity: Vec2::ZERO,
+            spin: [0.0, 0.0],
+            size: Curve::constant(16.0),
+            color: Curve::linear([1.0, 1.0, 1.0, 1.0], [1.0, 1.0, 1.0, 0.0]),
+            max_particles: 100,
+            space: SimulationSpace::World,
+        }
+    }
+}
+
+/// Particle emitter component
+///
+/// Particles are simulated by `update_particles` into a `ParticleState`
+/// component and drawn as textured quads; an emitter without a texture
+/// simulates but doesn't draw.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticleEmitter {
+    pub effect: ParticleEffect,
+    pub texture: AssetId,
+    /// Draw order relative to sprites (0) and other layers
+    pub z_index: i32,
+    /// Whether the emission cycle runs; particles already emitted live on
+    /// when stopped
+    pub playing: bool,
+    /// Particles requested with `burst`, emitted by the next update
+    #[serde(skip)]
+    pub pending: u32,
+}
+
+impl ParticleEmitter {
+    /// Create a playing emitter
+    pub fn new(effect: ParticleEffect, texture: AssetId) -> Self {
+        Self {
+            effect,
+            texture,
+            ..Default::default()
+        }
+    }
+
+    /// Set the draw order
+    pub fn with_z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Emit `count` particles on the next update, playing or not
+    pub fn burst(&mut self, count: u32) {
+        self.pending = self.pending.saturating_add(count);
+    }
+
+    /// Start the emission cycle, from the beginning if it wasn't running
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    /// Stop emitting
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            effect: ParticleEffect::default(),
+            texture: AssetId::new(0),
+            z_index: 0,
+            playing: true,
+            pending: 0,
+        }
+    }
+}
+
+/// One live particle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    /// In world space or relative to the emitter, per the effect's `space`
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Radians, counter-clockwise
+    pub rotation: f32,
+    /// Radians per second
+    pub spin: f32,
+    /// Seconds since the particle was emitted
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// How far through its lifetime the particle is (0..1)
+    pub fn progress(&self) -> f32 {
+        if self.lifetime > 0.0 {
+            (self.age / self.lifetime).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Live particles and emission progress of a `ParticleEmitter`
+///
+/// Added and advanced by `update_particles`; not serialized.
+#[derive(Debug, Clone)]
+pub struct ParticleState {
+    pub(crate) particles: Vec<Particle>,
+    /// Seconds into the current emission cycle
+    pub(crate) time: f32,
+    /// Fraction of a particle owed by `rate` emission
+    pub(crate) carry: f32,
+    /// Whether the emitter was playing at the last update
+    pub(crate) playing: bool,
+    pub(crate) rng: u32,
+}
+
+impl ParticleState {
+    /// Create an empty state whose random sequence starts from `seed`
+    pub fn new(seed: u32) -> Self {
+        Self {
+            particles: Vec::new(),
+            time: 0.0,
+            carry: 0.0,
+            playing: false,
+            // Xorshift never leaves zero
+            rng: seed.max(1),
+        }
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Remove every particle and restart the emission cycle
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.time = 0.0;
+        self.carry = 0.0;
+        self.playing = false;
+    }
+
+    /// Next random number in 0..1
+    pub(crate) fn random(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        (self.rng >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Random value in a `[min, max]` range
+    pub(crate) fn random_range(&mut self, [min, max]: [f32; 2]) -> f32 {
+        min + (max - min) * self.random()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_sampling() {
+        let curve = Curve::new(vec![(1.0, 0.0), (0.0, 10.0), (0.5, 20.0)]);
+        assert_eq!(curve.keys()[0], (0.0, 10.0));
+        assert_eq!(curve.sample(-1.0), Some(10.0));
+        assert_eq!(curve.sample(0.25), Some(15.0));
+        assert_eq!(curve.sample(0.75), Some(10.0));
+        assert_eq!(curve.sample(2.0), Some(0.0));
+        assert_eq!(Curve::<f32>::new(Vec::new()).sample(0.5), None);
+
+        let color = Curve::linear([1.0, 1.0, 1.0, 1.0], [0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(color.sample(0.5), Some([0.5; 4]));
+    }
+
+    #[test]
+    fn test_effect_from_partial_json() {
+        let effect: ParticleEffect =
+            serde_json::from_str(r#"{"rate": 0, "size": [[1, 4], [0, 8]], "space": "local"}"#).unwrap();
+        assert_eq!(effect.rate, 0.0);
+        assert_eq!(effect.size.keys(), &[(0.0, 8.0), (1.0, 4.0)]);
+        assert_eq!(effect.space, SimulationSpace::Local);
+        assert_eq!(effect.max_particles, 100);
+
+        let json = serde_json::to_string(&effect).unwrap();
+        assert_eq!(serde_json::from_str::<ParticleEffect>(&json).unwrap(), effect);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::types::AssetId;
+use serde::{Deserialize, Serialize};
+
+/// A full-screen effect applied to what a camera rendered
+///
+/// Effects run in the order they are listed on the camera, each reading the
+/// previous one's output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostEffect {
+    ColorGrading(ColorGrading),
+    Vignette(Vignette),
+    Bloom(Bloom),
+    Crt(Crt),
+    Pixelate(Pixelate),
+}
+
+/// Remap colors through a lookup table texture
+///
+/// The LUT is a horizontal strip of `size` slices of `size`x`size` texels
+/// (e.g. 256x16): red increases along each slice, green downwards and blue
+/// from slice to slice. Colors are looked up by their sRGB values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorGrading {
+    /// LUT texture asset
+    pub lut: AssetId,
+    /// Blend between the original (0) and graded (1) colors
+    pub intensity: f32,
+}
+
+impl Default for ColorGrading {
+    fn default() -> Self {
+        Self {
+            lut: AssetId::new(0),
+            intensity: 1.0,
+        }
+    }
+}
+
+/// Darken (or tint) the image towards its corners
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Vignette {
+    /// Strength at the corners (0..1)
+    pub intensity: f32,
+    /// Distance from the center where the effect starts, with 1 at the corners
+    pub radius: f32,
+    /// Distance over which the effect fades in
+    pub softness: f32,
+    /// Color the corners fade to (linear RGB)
+    pub color: [f32; 3],
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self {
+            intensity: 0.5,
+            radius: 0.5,
+            softness: 0.5,
+            color: [0.0; 3],
+        }
+    }
+}
+
+/// Make bright areas glow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bloom {
+    /// Brightness (0..1, brightest channel) above which pixels glow
+    pub threshold: f32,
+    /// Strength of the glow added back to the image
+    pub intensity: f32,
+    /// Spacing of the blur taps in pixels; larger spreads the glow further
+    pub radius: f32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self {
+            threshold: 0.7,
+            intensity: 1.0,
+            radius: 2.0,
+        }
+    }
+}
+
+/// Curved old-monitor look with scanlines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Crt {
+    /// Barrel distortion (0 = flat); areas curved off the screen are black
+    pub curvature: f32,
+    /// How much every other pixel row is darkened (0..1)
+    pub scanlines: f32,
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self {
+            curvature: 0.1,
+            scanlines: 0.3,
+        }
+    }
+}
+
+/// Draw the image with large square pixels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pixelate {
+    /// Size of each block in pixels
+    pub pixel_size: u32,
+}
+
+impl Default for Pixelate {
+    fn default() -> Self {
+        Self { pixel_size: 4 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_from_json() {
+        let effects: Vec<PostEffect> = serde_json::from_str(
+            r#"[
+                {"type": "vignette", "intensity": 0.8},
+                {"type": "pixelate", "pixel_size": 3},
+                {"type": "color_grading", "lut": 7}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            effects[0],
+            PostEffect::Vignette(Vignette {
+                intensity: 0.8,
+                ..Default::default()
+            })
+        );
+        assert_eq!(effects[1], PostEffect::Pixelate(Pixelate { pixel_size: 3 }));
+        assert_eq!(
+            effects[2],
+            PostEffect::ColorGrading(ColorGrading {
+                lut: AssetId::new(7),
+                intensity: 1.0,
+            })
+        );
+
+        let json = serde_json::to_string(&effects).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<PostEffect>>(&json).unwrap(), effects);
+    }
+}
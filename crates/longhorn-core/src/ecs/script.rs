@@ -1,4 +1,5 @@
 // crates/longhorn-core/src/ecs/script.rs
+use crate::ecs::EntityRef;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,6 +11,8 @@ pub enum ScriptValue {
     String(String),
     Boolean(bool),
     Vec2 { x: f64, y: f64 },
+    /// Reference to another entity, serialized as `{ "entity": <guid> }`
+    Entity { entity: EntityRef },
 }
 
 impl ScriptValue {
@@ -33,6 +36,13 @@ impl ScriptValue {
             _ => None,
         }
     }
+
+    pub fn as_entity(&self) -> Option<EntityRef> {
+        match self {
+            ScriptValue::Entity { entity } => Some(*entity),
+            _ => None,
+        }
+    }
 }
 
 /// Script component - attached to entities to run TypeScript code
@@ -113,4 +123,19 @@ mod tests {
         let parsed: ScriptValue = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed, value);
     }
+
+    #[test]
+    fn test_script_value_entity_serialization() {
+        let value = ScriptValue::Entity {
+            entity: EntityRef::new(crate::ecs::EntityGuid(7)),
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"entity":7}"#);
+
+        let parsed: ScriptValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, value);
+
+        let empty: ScriptValue = serde_json::from_str(r#"{"entity":null}"#).unwrap();
+        assert_eq!(empty.as_entity(), Some(EntityRef::none()));
+    }
 }
@@ -0,0 +1,291 @@
+use crate::math::Rect;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Segments approximating a full circle of a `Shape`
+pub const SHAPE_CIRCLE_SEGMENTS: usize = 64;
+
+/// Segments approximating each rounded corner of a `Shape`
+pub const SHAPE_CORNER_SEGMENTS: usize = 12;
+
+/// Outline of a `Shape`, in local coordinates around the entity position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShapeGeometry {
+    Rectangle { size: Vec2 },
+    /// Rectangle with corners rounded by `radius`, which shrinks to fit
+    RoundedRectangle { size: Vec2, radius: f32 },
+    Circle { radius: f32 },
+    /// Closed polygon through the points; concave polygons are filled too,
+    /// as long as their edges don't cross
+    Polygon { points: Vec<Vec2> },
+    /// Open line through the points; only drawn by the stroke
+    Polyline { points: Vec<Vec2> },
+}
+
+impl ShapeGeometry {
+    /// Whether the outline closes on itself and can be filled
+    pub fn is_closed(&self) -> bool {
+        !matches!(self, ShapeGeometry::Polyline { .. })
+    }
+
+    /// Points along the outline, counter-clockwise for the built-in shapes
+    ///
+    /// Circles and rounded corners are approximated with
+    /// `SHAPE_CIRCLE_SEGMENTS` and `SHAPE_CORNER_SEGMENTS` segments.
+    pub fn outline(&self) -> Vec<Vec2> {
+        match self {
+            ShapeGeometry::Rectangle { size } => {
+                let half = *size / 2.0;
+                vec![
+                    Vec2::new(-half.x, -half.y),
+                    Vec2::new(half.x, -half.y),
+                    Vec2::new(half.x, half.y),
+                    Vec2::new(-half.x, half.y),
+                ]
+            }
+            ShapeGeometry::RoundedRectangle { size, radius } => {
+                let half = size.abs() / 2.0;
+                let radius = radius.clamp(0.0, half.min_element());
+                if radius <= 0.0 {
+                    return ShapeGeometry::Rectangle { size: *size }.outline();
+                }
+                let inner = half - Vec2::splat(radius);
+                let corners = [
+                    (Vec2::new(inner.x, -inner.y), -std::f32::consts::FRAC_PI_2),
+                    (Vec2::new(inner.x, inner.y), 0.0),
+                    (Vec2::new(-inner.x, inner.y), std::f32::consts::FRAC_PI_2),
+                    (Vec2::new(-inner.x, -inner.y), std::f32::consts::PI),
+                ];
+                corners
+                    .iter()
+                    .flat_map(|&(center, start)| {
+                        (0..=SHAPE_CORNER_SEGMENTS).map(move |i| {
+                            let angle = start + i as f32 / SHAPE_CORNER_SEGMENTS as f32 * std::f32::consts::FRAC_PI_2;
+                            center + Vec2::from_angle(angle) * radius
+                        })
+                    })
+                    .collect()
+            }
+            ShapeGeometry::Circle { radius } => (0..SHAPE_CIRCLE_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f32 / SHAPE_CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+                    Vec2::from_angle(angle) * *radius
+                })
+                .collect(),
+            ShapeGeometry::Polygon { points } | ShapeGeometry::Polyline { points } => points.clone(),
+        }
+    }
+
+    /// Local bounding box of the outline, or `None` without points
+    pub fn bounds(&self) -> Option<Rect> {
+        let points = match self {
+            ShapeGeometry::Rectangle { size } | ShapeGeometry::RoundedRectangle { size, .. } => {
+                return Some(Rect::from_center_size(Vec2::ZERO, size.abs()));
+            }
+            ShapeGeometry::Circle { radius } => {
+                return Some(Rect::from_center_size(Vec2::ZERO, Vec2::splat(radius.abs() * 2.0)));
+            }
+            ShapeGeometry::Polygon { points } | ShapeGeometry::Polyline { points } => points,
+        };
+        let first = *points.first()?;
+        let (min, max) = points.iter().fold((first, first), |(min, max), &p| (min.min(p), max.max(p)));
+        Some(Rect::new(min, max))
+    }
+}
+
+/// How the inside of a `Shape` is painted
+///
+/// Gradients span the local bounding box of the shape and are interpolated
+/// between the vertices it is tessellated into.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShapeFill {
+    Solid { color: [f32; 4] },
+    /// From `start` to `end` along `angle` (radians, 0 runs left to right)
+    LinearGradient { start: [f32; 4], end: [f32; 4], angle: f32 },
+    /// From `center` at the middle of the bounds to `edge` at the edge of
+    /// the largest circle that fits them
+    RadialGradient { center: [f32; 4], edge: [f32; 4] },
+}
+
+impl ShapeFill {
+    /// Fill color at a local point of a shape with the given bounds
+    pub fn color_at(&self, point: Vec2, bounds: Rect) -> [f32; 4] {
+        let (from, to, t) = match self {
+            ShapeFill::Solid { color } => return *color,
+            ShapeFill::LinearGradient { start, end, angle } => {
+                let direction = Vec2::from_angle(*angle);
+                // Distance along the direction from the first to the last corner reached
+                let half = bounds.size() / 2.0;
+                let reach = half.x * direction.x.abs() + half.y * direction.y.abs();
+                let along = (point - bounds.center()).dot(direction);
+                let t = if reach > 0.0 { (along / reach + 1.0) / 2.0 } else { 0.5 };
+                (start, end, t)
+            }
+            ShapeFill::RadialGradient { center, edge } => {
+                let radius = bounds.size().min_element() / 2.0;
+                let distance = point.distance(bounds.center());
+                let t = if radius > 0.0 { distance / radius } else { 1.0 };
+                (center, edge, t)
+            }
+        };
+        let t = t.clamp(0.0, 1.0);
+        std::array::from_fn(|i| from[i] + (to[i] - from[i]) * t)
+    }
+}
+
+impl Default for ShapeFill {
+    fn default() -> Self {
+        ShapeFill::Solid { color: [1.0, 1.0, 1.0, 1.0] }
+    }
+}
+
+/// Line drawn along the outline of a `Shape`, centered on it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShapeStroke {
+    pub color: [f32; 4],
+    /// Width in local units
+    pub width: f32,
+}
+
+impl ShapeStroke {
+    pub fn new(color: [f32; 4], width: f32) -> Self {
+        Self { color, width }
+    }
+}
+
+/// Vector shape drawn without a texture, for prototyping and simple art
+///
+/// Shapes are tessellated into triangles each frame and drawn in z-order
+/// with sprites, scaled and rotated by the entity transform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Shape {
+    pub geometry: ShapeGeometry,
+    /// Paint for the inside of closed outlines
+    pub fill: Option<ShapeFill>,
+    pub stroke: Option<ShapeStroke>,
+    /// Draw order relative to sprites (0) and other layers
+    pub z_index: i32,
+}
+
+impl Shape {
+    /// Create a white shape with the given outline; polylines get a white
+    /// stroke instead of a fill
+    pub fn new(geometry: ShapeGeometry) -> Self {
+        let closed = geometry.is_closed();
+        Self {
+            geometry,
+            fill: closed.then(ShapeFill::default),
+            stroke: (!closed).then(|| ShapeStroke::new([1.0, 1.0, 1.0, 1.0], 1.0)),
+            z_index: 0,
+        }
+    }
+
+    /// Create a filled rectangle
+    pub fn rectangle(size: Vec2) -> Self {
+        Self::new(ShapeGeometry::Rectangle { size })
+    }
+
+    /// Create a filled rectangle with rounded corners
+    pub fn rounded_rectangle(size: Vec2, radius: f32) -> Self {
+        Self::new(ShapeGeometry::RoundedRectangle { size, radius })
+    }
+
+    /// Create a filled circle
+    pub fn circle(radius: f32) -> Self {
+        Self::new(ShapeGeometry::Circle { radius })
+    }
+
+    /// Create a filled polygon
+    pub fn polygon(points: Vec<Vec2>) -> Self {
+        Self::new(ShapeGeometry::Polygon { points })
+    }
+
+    /// Create a stroked open line
+    pub fn polyline(points: Vec<Vec2>) -> Self {
+        Self::new(ShapeGeometry::Polyline { points })
+    }
+
+    /// Set the fill
+    pub fn with_fill(mut self, fill: ShapeFill) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+
+    /// Fill with one color
+    pub fn with_fill_color(self, color: [f32; 4]) -> Self {
+        self.with_fill(ShapeFill::Solid { color })
+    }
+
+    /// Leave the inside unpainted
+    pub fn without_fill(mut self) -> Self {
+        self.fill = None;
+        self
+    }
+
+    /// Outline the shape
+    pub fn with_stroke(mut self, color: [f32; 4], width: f32) -> Self {
+        self.stroke = Some(ShapeStroke::new(color, width));
+        self
+    }
+
+    /// Set the draw order
+    pub fn with_z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Self::rectangle(Vec2::splat(100.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outlines_and_bounds() {
+        let rounded = ShapeGeometry::RoundedRectangle { size: Vec2::new(40.0, 20.0), radius: 50.0 };
+        let outline = rounded.outline();
+        assert_eq!(outline.len(), 4 * (SHAPE_CORNER_SEGMENTS + 1));
+        // The radius shrinks to half the short side, so the ends are half circles
+        assert!(outline.iter().all(|p| p.x.abs() <= 20.0 + 1e-4 && p.y.abs() <= 10.0 + 1e-4));
+        assert!(outline.iter().any(|p| (p.y - 10.0).abs() < 1e-4));
+
+        let polyline = ShapeGeometry::Polyline { points: vec![Vec2::new(-1.0, 2.0), Vec2::new(3.0, -4.0)] };
+        assert!(!polyline.is_closed());
+        assert_eq!(polyline.bounds(), Some(Rect::new(Vec2::new(-1.0, -4.0), Vec2::new(3.0, 2.0))));
+        assert_eq!(ShapeGeometry::Polygon { points: Vec::new() }.bounds(), None);
+    }
+
+    #[test]
+    fn test_gradients_span_the_bounds() {
+        let bounds = Rect::from_center_size(Vec2::ZERO, Vec2::new(10.0, 4.0));
+        let linear = ShapeFill::LinearGradient { start: [0.0; 4], end: [1.0; 4], angle: 0.0 };
+        assert_eq!(linear.color_at(Vec2::new(-5.0, 2.0), bounds), [0.0; 4]);
+        assert_eq!(linear.color_at(Vec2::new(0.0, -2.0), bounds), [0.5; 4]);
+        assert_eq!(linear.color_at(Vec2::new(5.0, 0.0), bounds), [1.0; 4]);
+
+        let radial = ShapeFill::RadialGradient { center: [1.0; 4], edge: [0.0; 4] };
+        assert_eq!(radial.color_at(Vec2::ZERO, bounds), [1.0; 4]);
+        assert_eq!(radial.color_at(Vec2::new(1.0, 0.0), bounds), [0.5; 4]);
+        assert_eq!(radial.color_at(Vec2::new(5.0, 0.0), bounds), [0.0; 4]);
+    }
+
+    #[test]
+    fn test_shape_defaults_from_partial_json() {
+        let shape: Shape = serde_json::from_str(r#"{"geometry": {"kind": "circle", "radius": 8.0}}"#).unwrap();
+        assert_eq!(shape.geometry, ShapeGeometry::Circle { radius: 8.0 });
+        assert_eq!(shape.fill, Some(ShapeFill::default()));
+        assert_eq!(shape.stroke, None);
+
+        let line = Shape::polyline(vec![Vec2::ZERO, Vec2::X]);
+        assert_eq!(line.fill, None);
+        assert_eq!(line.stroke.map(|s| s.width), Some(1.0));
+    }
+}
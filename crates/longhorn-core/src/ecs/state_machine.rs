@@ -0,0 +1,503 @@
+use crate::types::AssetId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use thiserror::Error;
+
+/// Errors in a state chart's structure
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StateChartError {
+    #[error("State chart has no states")]
+    Empty,
+
+    #[error("Duplicate state name: {0}")]
+    DuplicateState(String),
+
+    #[error("Unknown initial state '{initial}' in {parent}")]
+    UnknownInitial { parent: String, initial: String },
+
+    #[error("Transition from '{from}' to unknown state '{to}'")]
+    UnknownTarget { from: String, to: String },
+}
+
+/// Value of a state machine parameter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParameterValue {
+    Bool(bool),
+    Number(f64),
+}
+
+impl ParameterValue {
+    /// The value as a number; `true` is 1 and `false` is 0
+    pub fn as_number(&self) -> f64 {
+        match self {
+            ParameterValue::Bool(b) => f64::from(u8::from(*b)),
+            ParameterValue::Number(n) => *n,
+        }
+    }
+}
+
+impl Default for ParameterValue {
+    fn default() -> Self {
+        ParameterValue::Number(0.0)
+    }
+}
+
+/// Comparison a condition makes between a parameter and a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Compare {
+    #[default]
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+}
+
+impl Compare {
+    pub const ALL: [Compare; 6] = [
+        Compare::Equal,
+        Compare::NotEqual,
+        Compare::Less,
+        Compare::LessOrEqual,
+        Compare::Greater,
+        Compare::GreaterOrEqual,
+    ];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Compare::Equal => "==",
+            Compare::NotEqual => "!=",
+            Compare::Less => "<",
+            Compare::LessOrEqual => "<=",
+            Compare::Greater => ">",
+            Compare::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+/// Test of a parameter's value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Condition {
+    pub parameter: String,
+    #[serde(default)]
+    pub compare: Compare,
+    pub value: ParameterValue,
+}
+
+impl Condition {
+    /// Whether the parameters pass the test; missing parameters count as 0
+    pub fn test(&self, parameters: &HashMap<String, ParameterValue>) -> bool {
+        let value = parameters.get(&self.parameter).copied().unwrap_or_default().as_number();
+        let expected = self.value.as_number();
+        match self.compare {
+            Compare::Equal => value == expected,
+            Compare::NotEqual => value != expected,
+            Compare::Less => value < expected,
+            Compare::LessOrEqual => value <= expected,
+            Compare::Greater => value > expected,
+            Compare::GreaterOrEqual => value >= expected,
+        }
+    }
+}
+
+/// Move from the state that has it to another state
+///
+/// Fires when every requirement given holds: its event was sent to the
+/// machine this frame, its conditions pass and the state has been active for
+/// `after` seconds. A transition without requirements fires immediately.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    /// Target state name
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+    /// Seconds the state must have been active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<f32>,
+}
+
+impl Transition {
+    pub fn new(to: impl Into<String>) -> Self {
+        Self {
+            to: to.into(),
+            event: None,
+            conditions: Vec::new(),
+            after: None,
+        }
+    }
+
+    pub fn on_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    pub fn when(mut self, parameter: impl Into<String>, compare: Compare, value: ParameterValue) -> Self {
+        self.conditions.push(Condition {
+            parameter: parameter.into(),
+            compare,
+            value,
+        });
+        self
+    }
+
+    pub fn after(mut self, seconds: f32) -> Self {
+        self.after = Some(seconds);
+        self
+    }
+}
+
+/// Something a state does when entered or exited
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StateAction {
+    /// Set a parameter of the machine
+    Set { parameter: String, value: ParameterValue },
+    /// Send an event targeted at the entity
+    Emit {
+        event: String,
+        #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+        data: serde_json::Value,
+    },
+    /// Play an animation clip (by path) on the entity's AnimationPlayer
+    PlayAnimation {
+        clip: String,
+        /// Seconds to crossfade from the current clip
+        #[serde(default)]
+        crossfade: f32,
+    },
+}
+
+/// State of a chart, possibly holding child states
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    /// Name, unique within the chart
+    pub name: String,
+    /// Child entered with this state; the first child if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<State>,
+    /// Checked in order, after the transitions of active child states
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<Transition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_enter: Vec<StateAction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_exit: Vec<StateAction>,
+    /// Node position in the editor's graph
+    #[serde(default)]
+    pub position: [f32; 2],
+}
+
+impl State {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            initial: None,
+            states: Vec::new(),
+            transitions: Vec::new(),
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+            position: [0.0, 0.0],
+        }
+    }
+
+    pub fn with_state(mut self, state: State) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    pub fn with_on_enter(mut self, action: StateAction) -> Self {
+        self.on_enter.push(action);
+        self
+    }
+
+    pub fn with_on_exit(mut self, action: StateAction) -> Self {
+        self.on_exit.push(action);
+        self
+    }
+
+    /// Child entered with this state
+    pub fn initial_state(&self) -> Option<&State> {
+        match &self.initial {
+            Some(name) => self.states.iter().find(|state| &state.name == name),
+            None => self.states.first(),
+        }
+    }
+}
+
+/// Hierarchical state machine definition (`.fsm`), played by `StateMachine`
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct StateChart {
+    /// Parameters and their starting values
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, ParameterValue>,
+    /// Top-level state entered first; the first state if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<String>,
+    pub states: Vec<State>,
+}
+
+impl StateChart {
+    pub fn new(states: Vec<State>) -> Self {
+        Self {
+            states,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_parameter(mut self, name: impl Into<String>, value: ParameterValue) -> Self {
+        self.parameters.insert(name.into(), value);
+        self
+    }
+
+    /// Check that state names are unique and refer to existing states
+    pub fn validate(&self) -> Result<(), StateChartError> {
+        if self.states.is_empty() {
+            return Err(StateChartError::Empty);
+        }
+        let mut names = HashSet::new();
+        for state in self.iter() {
+            if !names.insert(state.name.as_str()) {
+                return Err(StateChartError::DuplicateState(state.name.clone()));
+            }
+        }
+        let check_initial = |parent: &str, initial: &Option<String>, states: &[State]| match initial {
+            Some(initial) if !states.iter().any(|state| &state.name == initial) => {
+                Err(StateChartError::UnknownInitial {
+                    parent: parent.to_string(),
+                    initial: initial.clone(),
+                })
+            }
+            _ => Ok(()),
+        };
+        check_initial("the chart", &self.initial, &self.states)?;
+        for state in self.iter() {
+            check_initial(&state.name, &state.initial, &state.states)?;
+            if let Some(transition) = state.transitions.iter().find(|t| !names.contains(t.to.as_str())) {
+                return Err(StateChartError::UnknownTarget {
+                    from: state.name.clone(),
+                    to: transition.to.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Every state, parents before their children
+    pub fn iter(&self) -> impl Iterator<Item = &State> {
+        let mut stack: Vec<&State> = self.states.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let state = stack.pop()?;
+            stack.extend(state.states.iter().rev());
+            Some(state)
+        })
+    }
+
+    pub fn find(&self, name: &str) -> Option<&State> {
+        self.iter().find(|state| state.name == name)
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut State> {
+        fn find<'a>(states: &'a mut [State], name: &str) -> Option<&'a mut State> {
+            for state in states {
+                if state.name == name {
+                    return Some(state);
+                }
+                if let Some(found) = find(&mut state.states, name) {
+                    return Some(found);
+                }
+            }
+            None
+        }
+        find(&mut self.states, name)
+    }
+
+    /// States from the top level down to `name`
+    pub fn path_to(&self, name: &str) -> Option<Vec<&State>> {
+        fn search<'a>(states: &'a [State], name: &str, path: &mut Vec<&'a State>) -> bool {
+            for state in states {
+                path.push(state);
+                if state.name == name || search(&state.states, name, path) {
+                    return true;
+                }
+                path.pop();
+            }
+            false
+        }
+        let mut path = Vec::new();
+        search(&self.states, name, &mut path).then_some(path)
+    }
+
+    /// Top-level state entered first
+    pub fn initial_state(&self) -> Option<&State> {
+        match &self.initial {
+            Some(name) => self.states.iter().find(|state| &state.name == name),
+            None => self.states.first(),
+        }
+    }
+
+    /// Name of the state with `child` among its children
+    pub fn parent_of(&self, child: &str) -> Option<&str> {
+        let path = self.path_to(child)?;
+        path.len().checked_sub(2).map(|index| path[index].name.as_str())
+    }
+}
+
+/// Runs a `StateChart` on the entity
+///
+/// `update_state_machines` enters the chart's initial states on the first
+/// update, then follows the first enabled transition of the active states,
+/// innermost first, each frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateMachine {
+    pub chart: AssetId,
+    /// Parameter values; the chart's starting values fill in missing ones
+    #[serde(default)]
+    pub parameters: HashMap<String, ParameterValue>,
+    /// Active states from the top level down to the innermost
+    #[serde(skip)]
+    pub(crate) active: Vec<String>,
+    /// Seconds each active state has been active
+    #[serde(skip)]
+    pub(crate) elapsed: Vec<f32>,
+    /// Events sent since the last update
+    #[serde(skip)]
+    pub(crate) events: Vec<String>,
+}
+
+impl StateMachine {
+    pub fn new(chart: AssetId) -> Self {
+        Self {
+            chart,
+            parameters: HashMap::new(),
+            active: Vec::new(),
+            elapsed: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn with_parameter(mut self, name: impl Into<String>, value: ParameterValue) -> Self {
+        self.parameters.insert(name.into(), value);
+        self
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        self.parameters.get(name).copied()
+    }
+
+    pub fn set_parameter(&mut self, name: impl Into<String>, value: ParameterValue) {
+        self.parameters.insert(name.into(), value);
+    }
+
+    /// Send an event for transitions to react to on the next update
+    pub fn send(&mut self, event: impl Into<String>) {
+        self.events.push(event.into());
+    }
+
+    /// Events waiting for the next update
+    pub fn pending_events(&self) -> &[String] {
+        &self.events
+    }
+
+    /// Innermost active state, once started
+    pub fn state(&self) -> Option<&str> {
+        self.active.last().map(String::as_str)
+    }
+
+    /// Active states from the top level down to the innermost
+    pub fn active_states(&self) -> &[String] {
+        &self.active
+    }
+
+    /// Whether a state is active, directly or through an active child
+    pub fn is_in(&self, state: &str) -> bool {
+        self.active.iter().any(|active| active == state)
+    }
+
+    /// Leave every state; the next update starts from the initial states
+    /// without running exit actions
+    pub fn restart(&mut self) {
+        self.active.clear();
+        self.elapsed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart() -> StateChart {
+        StateChart::new(vec![
+            State::new("Idle").with_transition(Transition::new("Move").when("speed", Compare::Greater, ParameterValue::Number(0.0))),
+            State::new("Move")
+                .with_state(State::new("Walk"))
+                .with_state(State::new("Run"))
+                .with_transition(Transition::new("Idle").on_event("stop")),
+        ])
+    }
+
+    #[test]
+    fn test_chart_json_and_lookup() {
+        let json = r#"{
+            "parameters": { "grounded": true, "speed": 0 },
+            "initial": "Air",
+            "states": [
+                { "name": "Ground", "initial": "Run", "states": [{ "name": "Walk" }, { "name": "Run" }],
+                  "transitions": [{ "to": "Air", "conditions": [{ "parameter": "grounded", "value": false }] }] },
+                { "name": "Air", "on_enter": [{ "action": "emit", "event": "jumped" }, { "action": "play_animation", "clip": "anim/jump.anim", "crossfade": 0.1 }],
+                  "transitions": [{ "to": "Ground", "conditions": [{ "parameter": "speed", "compare": "<=", "value": 0.5 }], "after": 0.2 }] }
+            ]
+        }"#;
+        let chart: StateChart = serde_json::from_str(json).unwrap();
+        chart.validate().unwrap();
+        assert_eq!(chart.initial_state().unwrap().name, "Air");
+        assert_eq!(chart.find("Ground").unwrap().initial_state().unwrap().name, "Run");
+        assert_eq!(chart.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["Ground", "Walk", "Run", "Air"]);
+        assert_eq!(chart.parent_of("Run"), Some("Ground"));
+        assert_eq!(chart.parent_of("Air"), None);
+        assert_eq!(chart.parameters["grounded"], ParameterValue::Bool(true));
+        let roundtrip: StateChart = serde_json::from_str(&serde_json::to_string(&chart).unwrap()).unwrap();
+        assert_eq!(roundtrip, chart);
+
+        let condition = &chart.find("Air").unwrap().transitions[0].conditions[0];
+        let mut parameters = HashMap::new();
+        assert!(condition.test(&parameters));
+        parameters.insert("speed".to_string(), ParameterValue::Number(1.0));
+        assert!(!condition.test(&parameters));
+    }
+
+    #[test]
+    fn test_chart_validation() {
+        assert_eq!(StateChart::default().validate(), Err(StateChartError::Empty));
+        let mut chart = chart();
+        chart.validate().unwrap();
+
+        chart.find_mut("Walk").unwrap().transitions.push(Transition::new("Fly"));
+        assert_eq!(
+            chart.validate(),
+            Err(StateChartError::UnknownTarget { from: "Walk".to_string(), to: "Fly".to_string() })
+        );
+        chart.find_mut("Walk").unwrap().transitions.clear();
+        chart.find_mut("Move").unwrap().initial = Some("Idle".to_string());
+        assert!(matches!(chart.validate(), Err(StateChartError::UnknownInitial { .. })));
+        chart.find_mut("Move").unwrap().initial = None;
+        chart.states.push(State::new("Run"));
+        assert_eq!(chart.validate(), Err(StateChartError::DuplicateState("Run".to_string())));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::types::AssetId;
+use serde::{Deserialize, Serialize};
+
+/// Horizontal alignment of text lines around the entity position
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextAlign {
+    /// Lines start at the entity position
+    #[default]
+    Left,
+    /// Lines are centered on the entity position
+    Center,
+    /// Lines end at the entity position
+    Right,
+}
+
+/// Text component for drawing strings with a TrueType or bitmap font
+///
+/// The entity position is the top of the first line; `align` decides how
+/// each line sits horizontally around it. Lines break at `\n` and, when
+/// `wrap_width` is set, between words (or inside words longer than the
+/// wrap width).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Text {
+    /// The string to draw
+    pub content: String,
+    /// Font asset (`.ttf`/`.otf` or BMFont `.fnt`)
+    pub font: AssetId,
+    /// Line height in world units (the font's pixel size)
+    pub size: f32,
+    /// RGBA color
+    pub color: [f32; 4],
+    pub align: TextAlign,
+    /// Maximum line width in world units before wrapping
+    pub wrap_width: Option<f32>,
+}
+
+impl Text {
+    /// Create a left-aligned, white, unwrapped text
+    pub fn new(content: impl Into<String>, font: AssetId, size: f32) -> Self {
+        Self {
+            content: content.into(),
+            font,
+            size,
+            ..Default::default()
+        }
+    }
+
+    /// Replace the string
+    pub fn set_content(&mut self, content: impl Into<String>) {
+        self.content = content.into();
+    }
+
+    /// Set the alignment
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the color
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Wrap lines longer than `width`
+    pub fn with_wrap_width(mut self, width: f32) -> Self {
+        self.wrap_width = Some(width);
+        self
+    }
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self {
+            content: String::new(),
+            font: AssetId::new(0),
+            size: 32.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+            align: TextAlign::Left,
+            wrap_width: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_defaults_from_partial_json() {
+        let text: Text = serde_json::from_str(r#"{"content": "Score: 0", "align": "center"}"#).unwrap();
+        assert_eq!(text.content, "Score: 0");
+        assert_eq!(text.align, TextAlign::Center);
+        assert_eq!(text.size, 32.0);
+        assert_eq!(text.wrap_width, None);
+
+        let text = Text::new("Hi", AssetId::new(3), 16.0).with_wrap_width(100.0);
+        assert_eq!(text.font, AssetId::new(3));
+        assert_eq!(text.wrap_width, Some(100.0));
+    }
+}
@@ -0,0 +1,554 @@
+use crate::math::Rect;
+use crate::types::AssetId;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Width and height of a tile chunk, in cells
+pub const TILE_CHUNK_SIZE: i32 = 16;
+
+/// Number of cells in a tile chunk
+const CHUNK_CELLS: usize = (TILE_CHUNK_SIZE * TILE_CHUNK_SIZE) as usize;
+
+/// Source of chunk revisions, unique across all tilemaps
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Collision flags of a tile, read by physics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TileFlags {
+    /// Blocks movement from every side
+    pub solid: bool,
+    /// Blocks movement from above only (platforms that can be jumped through)
+    pub one_way: bool,
+}
+
+impl TileFlags {
+    pub const SOLID: TileFlags = TileFlags { solid: true, one_way: false };
+    pub const ONE_WAY: TileFlags = TileFlags { solid: false, one_way: true };
+
+    /// Check if the tile doesn't collide at all
+    pub fn is_empty(&self) -> bool {
+        !self.solid && !self.one_way
+    }
+
+    /// Flags of two tiles stacked in the same cell
+    pub fn union(self, other: TileFlags) -> TileFlags {
+        TileFlags {
+            solid: self.solid || other.solid,
+            one_way: self.one_way || other.one_way,
+        }
+    }
+}
+
+/// A texture sliced into a grid of equally sized tiles
+///
+/// Tiles are numbered row by row from the top-left, starting at 0. Tile
+/// sets are stored as JSON (`.tileset`) next to their image; `columns` and
+/// `rows` are computed from the image when the tile set is loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileSet {
+    /// Image file, relative to the tile set file
+    pub image: String,
+    /// Texture of the image, filled in when loaded
+    #[serde(skip, default = "no_texture")]
+    pub texture: AssetId,
+    /// Tile size in pixels
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Border around the tiles in pixels
+    #[serde(default)]
+    pub margin: u32,
+    /// Gap between neighbouring tiles in pixels
+    #[serde(default)]
+    pub spacing: u32,
+    /// Size of the image in pixels
+    #[serde(default)]
+    pub image_width: u32,
+    #[serde(default)]
+    pub image_height: u32,
+    #[serde(default)]
+    pub columns: u32,
+    #[serde(default)]
+    pub rows: u32,
+    /// Flags by tile index; tiles without an entry have none
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub tiles: HashMap<u32, TileFlags>,
+}
+
+fn no_texture() -> AssetId {
+    AssetId::new(0)
+}
+
+impl TileSet {
+    /// Slice a loaded texture into tiles of the given size
+    pub fn new(texture: AssetId, image_size: (u32, u32), tile_width: u32, tile_height: u32) -> Self {
+        let mut tileset = Self {
+            image: String::new(),
+            texture,
+            tile_width,
+            tile_height,
+            margin: 0,
+            spacing: 0,
+            image_width: 0,
+            image_height: 0,
+            columns: 0,
+            rows: 0,
+            tiles: HashMap::new(),
+        };
+        tileset.slice(image_size.0, image_size.1);
+        tileset
+    }
+
+    /// Set the image size and compute the tile grid from it
+    pub fn slice(&mut self, image_width: u32, image_height: u32) {
+        let count = |image: u32, tile: u32| {
+            let usable = image.saturating_sub(self.margin * 2) + self.spacing;
+            usable / (tile + self.spacing).max(1)
+        };
+        self.image_width = image_width;
+        self.image_height = image_height;
+        self.columns = count(image_width, self.tile_width);
+        self.rows = count(image_height, self.tile_height);
+    }
+
+    /// Number of tiles in the set
+    pub fn tile_count(&self) -> u32 {
+        self.columns * self.rows
+    }
+
+    /// Texture region of a tile as (u0, v0, u1, v1)
+    ///
+    /// Returns `None` for indices past the end of the set.
+    pub fn uv_rect(&self, index: u32) -> Option<[f32; 4]> {
+        if index >= self.tile_count() || self.image_width == 0 || self.image_height == 0 {
+            return None;
+        }
+        let x = self.margin + (index % self.columns) * (self.tile_width + self.spacing);
+        let y = self.margin + (index / self.columns) * (self.tile_height + self.spacing);
+        let (width, height) = (self.image_width as f32, self.image_height as f32);
+        Some([
+            x as f32 / width,
+            y as f32 / height,
+            (x + self.tile_width) as f32 / width,
+            (y + self.tile_height) as f32 / height,
+        ])
+    }
+
+    /// Collision flags of a tile
+    pub fn flags(&self, index: u32) -> TileFlags {
+        self.tiles.get(&index).copied().unwrap_or_default()
+    }
+
+    /// Set the collision flags of a tile
+    pub fn set_flags(&mut self, index: u32, flags: TileFlags) {
+        if flags.is_empty() {
+            self.tiles.remove(&index);
+        } else {
+            self.tiles.insert(index, flags);
+        }
+    }
+}
+
+/// A square block of `TILE_CHUNK_SIZE`² cells
+///
+/// Every change gives the chunk a new revision, so renderers can cache
+/// the chunk's geometry until it changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileChunk {
+    /// Raw cell values, row by row: 0 is empty, otherwise tile index + 1
+    tiles: Vec<u32>,
+    /// Number of non-empty cells
+    count: usize,
+    revision: u64,
+}
+
+impl TileChunk {
+    fn new() -> Self {
+        Self {
+            tiles: vec![0; CHUNK_CELLS],
+            count: 0,
+            revision: next_revision(),
+        }
+    }
+
+    /// Build a chunk from raw cell values (0 = empty, otherwise tile index + 1)
+    ///
+    /// Returns `None` unless there are exactly `TILE_CHUNK_SIZE`² values.
+    pub fn from_raw(tiles: Vec<u32>) -> Option<Self> {
+        if tiles.len() != CHUNK_CELLS {
+            return None;
+        }
+        let count = tiles.iter().filter(|&&tile| tile != 0).count();
+        Some(Self {
+            tiles,
+            count,
+            revision: next_revision(),
+        })
+    }
+
+    /// Raw cell values, row by row (0 = empty, otherwise tile index + 1)
+    pub fn raw(&self) -> &[u32] {
+        &self.tiles
+    }
+
+    /// Changes whenever a cell of the chunk changes
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Check if every cell is empty
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Tile index at a cell of the chunk
+    pub fn get(&self, x: i32, y: i32) -> Option<u32> {
+        self.tiles[(y * TILE_CHUNK_SIZE + x) as usize].checked_sub(1)
+    }
+
+    /// Non-empty cells as (x, y, tile index) relative to the chunk
+    pub fn tiles(&self) -> impl Iterator<Item = (i32, i32, u32)> + '_ {
+        self.tiles.iter().enumerate().filter_map(|(i, &tile)| {
+            let i = i as i32;
+            tile.checked_sub(1)
+                .map(|index| (i % TILE_CHUNK_SIZE, i / TILE_CHUNK_SIZE, index))
+        })
+    }
+
+    fn set(&mut self, x: i32, y: i32, tile: Option<u32>) -> Option<u32> {
+        let cell = &mut self.tiles[(y * TILE_CHUNK_SIZE + x) as usize];
+        let raw = tile.map_or(0, |index| index + 1);
+        let previous = cell.checked_sub(1);
+        if *cell != raw {
+            match (*cell, raw) {
+                (0, _) => self.count += 1,
+                (_, 0) => self.count -= 1,
+                _ => {}
+            }
+            *cell = raw;
+            self.revision = next_revision();
+        }
+        previous
+    }
+}
+
+/// One layer of a tilemap, stored in chunks so maps can grow in any direction
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileLayer {
+    pub name: String,
+    pub visible: bool,
+    /// Draw order of the layer relative to sprites
+    pub z_index: i32,
+    chunks: BTreeMap<(i32, i32), TileChunk>,
+}
+
+impl TileLayer {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            visible: true,
+            z_index: 0,
+            chunks: BTreeMap::new(),
+        }
+    }
+
+    /// Set the draw order
+    pub fn with_z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Tile index at a cell
+    pub fn get(&self, x: i32, y: i32) -> Option<u32> {
+        let (chunk, local) = split_cell(x, y);
+        self.chunks.get(&chunk)?.get(local.0, local.1)
+    }
+
+    /// Set or clear the tile at a cell, returning the previous tile
+    ///
+    /// Chunks are created on demand and dropped once empty.
+    pub fn set(&mut self, x: i32, y: i32, tile: Option<u32>) -> Option<u32> {
+        let (coord, local) = split_cell(x, y);
+        let Some(chunk) = self.chunks.get_mut(&coord) else {
+            if tile.is_some() {
+                self.chunks.entry(coord).or_insert_with(TileChunk::new).set(local.0, local.1, tile);
+            }
+            return None;
+        };
+        let previous = chunk.set(local.0, local.1, tile);
+        if chunk.is_empty() {
+            self.chunks.remove(&coord);
+        }
+        previous
+    }
+
+    /// Chunks by chunk coordinate
+    pub fn chunks(&self) -> impl Iterator<Item = ((i32, i32), &TileChunk)> {
+        self.chunks.iter().map(|(&coord, chunk)| (coord, chunk))
+    }
+
+    /// Add or replace a whole chunk (empty chunks are dropped)
+    pub fn insert_chunk(&mut self, coord: (i32, i32), chunk: TileChunk) {
+        if chunk.is_empty() {
+            self.chunks.remove(&coord);
+        } else {
+            self.chunks.insert(coord, chunk);
+        }
+    }
+
+    /// Non-empty cells as (x, y, tile index)
+    pub fn tiles(&self) -> impl Iterator<Item = (i32, i32, u32)> + '_ {
+        self.chunks.iter().flat_map(|(&(cx, cy), chunk)| {
+            chunk
+                .tiles()
+                .map(move |(x, y, index)| (cx * TILE_CHUNK_SIZE + x, cy * TILE_CHUNK_SIZE + y, index))
+        })
+    }
+
+    /// Number of non-empty cells
+    pub fn tile_count(&self) -> usize {
+        self.chunks.values().map(|chunk| chunk.count).sum()
+    }
+
+    /// Remove every tile
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+}
+
+/// Split a cell coordinate into its chunk and the cell within that chunk
+fn split_cell(x: i32, y: i32) -> ((i32, i32), (i32, i32)) {
+    (
+        (x.div_euclid(TILE_CHUNK_SIZE), y.div_euclid(TILE_CHUNK_SIZE)),
+        (x.rem_euclid(TILE_CHUNK_SIZE), y.rem_euclid(TILE_CHUNK_SIZE)),
+    )
+}
+
+/// A collision box built from a run of tiles, relative to the tilemap entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileCollider {
+    pub rect: Rect,
+    pub flags: TileFlags,
+}
+
+/// Grid of tiles from a `TileSet`, drawn in layers
+///
+/// Cell (0, 0) has its top-left corner at the entity position; x grows to
+/// the right and y grows downwards, like rows in an image or a Tiled map.
+/// Cells can be negative. Each layer draws every tile of the tile set as a
+/// `cell_size` quad.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tilemap {
+    /// Tile set asset (`.tileset` or Tiled `.tsj`)
+    pub tileset: AssetId,
+    /// Size of a cell in world units
+    pub cell_size: Vec2,
+    /// Layers in draw order
+    pub layers: Vec<TileLayer>,
+}
+
+impl Tilemap {
+    /// Create a tilemap without layers
+    pub fn new(tileset: AssetId, cell_size: Vec2) -> Self {
+        Self {
+            tileset,
+            cell_size,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Add an empty layer on top
+    pub fn with_layer(mut self, name: impl Into<String>) -> Self {
+        self.layers.push(TileLayer::new(name));
+        self
+    }
+
+    /// Index of the first layer with the given name
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    /// Tile index at a cell of a layer
+    pub fn get_tile(&self, layer: usize, x: i32, y: i32) -> Option<u32> {
+        self.layers.get(layer)?.get(x, y)
+    }
+
+    /// Set or clear the tile at a cell of a layer, returning the previous tile
+    ///
+    /// Does nothing if the layer doesn't exist.
+    pub fn set_tile(&mut self, layer: usize, x: i32, y: i32, tile: Option<u32>) -> Option<u32> {
+        self.layers.get_mut(layer)?.set(x, y, tile)
+    }
+
+    /// Cell containing a point relative to the entity (before its transform)
+    pub fn cell_at(&self, point: Vec2) -> (i32, i32) {
+        (
+            (point.x / self.cell_size.x).floor() as i32,
+            (-point.y / self.cell_size.y).floor() as i32,
+        )
+    }
+
+    /// Area of a cell relative to the entity (before its transform)
+    pub fn cell_rect(&self, x: i32, y: i32) -> Rect {
+        let min = Vec2::new(x as f32, -(y + 1) as f32) * self.cell_size;
+        Rect::new(min, min + self.cell_size)
+    }
+
+    /// Area of a chunk relative to the entity (before its transform)
+    pub fn chunk_rect(&self, cx: i32, cy: i32) -> Rect {
+        let first = self.cell_rect(cx * TILE_CHUNK_SIZE, cy * TILE_CHUNK_SIZE);
+        let last = self.cell_rect((cx + 1) * TILE_CHUNK_SIZE - 1, (cy + 1) * TILE_CHUNK_SIZE - 1);
+        first.union(&last)
+    }
+
+    /// Combined collision flags of the tiles of every layer at a cell
+    pub fn flags_at(&self, x: i32, y: i32, tileset: &TileSet) -> TileFlags {
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.get(x, y))
+            .fold(TileFlags::default(), |flags, tile| flags.union(tileset.flags(tile)))
+    }
+
+    /// Collision boxes for every colliding cell
+    ///
+    /// Neighbouring cells in a row with the same flags merge into one box,
+    /// so a floor is a single collider rather than one per tile.
+    pub fn colliders(&self, tileset: &TileSet) -> Vec<TileCollider> {
+        let mut cells: BTreeMap<(i32, i32), TileFlags> = BTreeMap::new();
+        for layer in &self.layers {
+            for (x, y, tile) in layer.tiles() {
+                let flags = tileset.flags(tile);
+                if !flags.is_empty() {
+                    let cell = cells.entry((y, x)).or_default();
+                    *cell = cell.union(flags);
+                }
+            }
+        }
+
+        let mut colliders: Vec<TileCollider> = Vec::new();
+        let mut last: Option<(i32, i32)> = None;
+        for ((y, x), flags) in cells {
+            let rect = self.cell_rect(x, y);
+            match colliders.last_mut() {
+                Some(run) if last == Some((y, x - 1)) && run.flags == flags => {
+                    run.rect = run.rect.union(&rect);
+                }
+                _ => colliders.push(TileCollider { rect, flags }),
+            }
+            last = Some((y, x));
+        }
+        colliders
+    }
+}
+
+impl Default for Tilemap {
+    fn default() -> Self {
+        Self::new(AssetId::new(0), Vec2::splat(32.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiles_across_chunks() {
+        let mut map = Tilemap::default().with_layer("Ground");
+        assert_eq!(map.set_tile(0, 3, 4, Some(7)), None);
+        assert_eq!(map.set_tile(0, -1, -20, Some(2)), None);
+        assert_eq!(map.set_tile(0, 3, 4, Some(8)), Some(7));
+        assert_eq!(map.get_tile(0, 3, 4), Some(8));
+        assert_eq!(map.get_tile(0, -1, -20), Some(2));
+        assert_eq!(map.get_tile(0, 4, 4), None);
+        assert_eq!(map.get_tile(1, 3, 4), None);
+        assert_eq!(map.set_tile(1, 3, 4, Some(1)), None);
+
+        let layer = &map.layers[0];
+        assert_eq!(layer.chunks().map(|(coord, _)| coord).collect::<Vec<_>>(), vec![(-1, -2), (0, 0)]);
+        let mut tiles: Vec<_> = layer.tiles().collect();
+        tiles.sort();
+        assert_eq!(tiles, vec![(-1, -20, 2), (3, 4, 8)]);
+
+        // Edits change the chunk revision; clearing the last tile drops the chunk
+        let revision = layer.chunks().last().unwrap().1.revision();
+        map.set_tile(0, 3, 5, Some(1));
+        assert_ne!(map.layers[0].chunks().last().unwrap().1.revision(), revision);
+        map.set_tile(0, -1, -20, None);
+        assert_eq!(map.layers[0].chunks().count(), 1);
+        assert_eq!(map.layers[0].tile_count(), 2);
+    }
+
+    #[test]
+    fn test_cell_geometry() {
+        let map = Tilemap::new(AssetId::new(1), Vec2::new(16.0, 8.0));
+        assert_eq!(map.cell_at(Vec2::new(20.0, -3.0)), (1, 0));
+        assert_eq!(map.cell_at(Vec2::new(-1.0, 1.0)), (-1, -1));
+        assert_eq!(map.cell_rect(1, 0), Rect::new(Vec2::new(16.0, -8.0), Vec2::new(32.0, 0.0)));
+        assert_eq!(
+            map.chunk_rect(0, 0),
+            Rect::new(Vec2::new(0.0, -128.0), Vec2::new(256.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_tileset_slicing() {
+        let mut tileset = TileSet::new(AssetId::new(1), (64, 32), 16, 16);
+        assert_eq!((tileset.columns, tileset.rows, tileset.tile_count()), (4, 2, 8));
+        assert_eq!(tileset.uv_rect(5), Some([0.25, 0.5, 0.5, 1.0]));
+        assert_eq!(tileset.uv_rect(8), None);
+
+        // 1px margin and 2px spacing: 1 + 3 * 16 + 2 * 2 + 1 = 54
+        tileset.margin = 1;
+        tileset.spacing = 2;
+        tileset.slice(54, 18);
+        assert_eq!((tileset.columns, tileset.rows), (3, 1));
+        assert_eq!(tileset.uv_rect(1).unwrap()[0], 19.0 / 54.0);
+    }
+
+    #[test]
+    fn test_colliders_merge_rows() {
+        let mut tileset = TileSet::new(AssetId::new(1), (32, 16), 16, 16);
+        tileset.set_flags(0, TileFlags::SOLID);
+        tileset.set_flags(1, TileFlags::ONE_WAY);
+
+        let mut map = Tilemap::new(AssetId::new(1), Vec2::splat(10.0))
+            .with_layer("Ground")
+            .with_layer("Decor");
+        for x in 0..3 {
+            map.set_tile(0, x, 2, Some(0));
+        }
+        map.set_tile(0, 5, 2, Some(0));
+        map.set_tile(0, 1, 0, Some(1));
+        // Decorations without flags don't collide
+        map.set_tile(1, 1, 1, Some(2));
+
+        assert_eq!(map.flags_at(2, 2, &tileset), TileFlags::SOLID);
+        assert!(map.flags_at(1, 1, &tileset).is_empty());
+
+        let colliders = map.colliders(&tileset);
+        assert_eq!(
+            colliders,
+            vec![
+                TileCollider {
+                    rect: Rect::new(Vec2::new(10.0, -10.0), Vec2::new(20.0, 0.0)),
+                    flags: TileFlags::ONE_WAY,
+                },
+                TileCollider {
+                    rect: Rect::new(Vec2::new(0.0, -30.0), Vec2::new(30.0, -20.0)),
+                    flags: TileFlags::SOLID,
+                },
+                TileCollider {
+                    rect: Rect::new(Vec2::new(50.0, -30.0), Vec2::new(60.0, -20.0)),
+                    flags: TileFlags::SOLID,
+                },
+            ]
+        );
+    }
+}
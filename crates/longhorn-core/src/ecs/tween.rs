@@ -0,0 +1,517 @@
+use crate::ecs::{Camera, EntityHandle, Sprite};
+use crate::math::Transform;
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Easing function shaping a tween's progress
+///
+/// Serialized by name, e.g. `"linear"` or `"easeOutBack"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Ease {
+    #[default]
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InSine,
+    OutSine,
+    InOutSine,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    InBack,
+    OutBack,
+    InOutBack,
+    InElastic,
+    OutElastic,
+    InOutElastic,
+    InBounce,
+    OutBounce,
+    InOutBounce,
+}
+
+const EASE_NAMES: [(&str, Ease); 22] = [
+    ("linear", Ease::Linear),
+    ("easeInQuad", Ease::InQuad),
+    ("easeOutQuad", Ease::OutQuad),
+    ("easeInOutQuad", Ease::InOutQuad),
+    ("easeInCubic", Ease::InCubic),
+    ("easeOutCubic", Ease::OutCubic),
+    ("easeInOutCubic", Ease::InOutCubic),
+    ("easeInSine", Ease::InSine),
+    ("easeOutSine", Ease::OutSine),
+    ("easeInOutSine", Ease::InOutSine),
+    ("easeInExpo", Ease::InExpo),
+    ("easeOutExpo", Ease::OutExpo),
+    ("easeInOutExpo", Ease::InOutExpo),
+    ("easeInBack", Ease::InBack),
+    ("easeOutBack", Ease::OutBack),
+    ("easeInOutBack", Ease::InOutBack),
+    ("easeInElastic", Ease::InElastic),
+    ("easeOutElastic", Ease::OutElastic),
+    ("easeInOutElastic", Ease::InOutElastic),
+    ("easeInBounce", Ease::InBounce),
+    ("easeOutBounce", Ease::OutBounce),
+    ("easeInOutBounce", Ease::InOutBounce),
+];
+
+impl Ease {
+    /// Look an easing function up by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        EASE_NAMES.iter().find(|(n, _)| *n == name).map(|(_, ease)| *ease)
+    }
+
+    pub fn name(&self) -> &'static str {
+        EASE_NAMES.iter().find(|(_, ease)| ease == self).map(|(n, _)| *n).unwrap_or("linear")
+    }
+
+    /// Eased progress for linear progress `t` (0..1)
+    ///
+    /// Back and elastic easing overshoot, going outside 0..1 mid-way.
+    pub fn apply(&self, t: f32) -> f32 {
+        const BACK: f32 = 1.70158;
+        const BACK_IN_OUT: f32 = BACK * 1.525;
+        const ELASTIC: f32 = 2.0 * PI / 3.0;
+        const ELASTIC_IN_OUT: f32 = 2.0 * PI / 4.5;
+
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::InQuad => t * t,
+            Ease::OutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Ease::InOutQuad => in_out(t, |t| t * t),
+            Ease::InCubic => t * t * t,
+            Ease::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Ease::InOutCubic => in_out(t, |t| t * t * t),
+            Ease::InSine => 1.0 - (t * PI / 2.0).cos(),
+            Ease::OutSine => (t * PI / 2.0).sin(),
+            Ease::InOutSine => -((t * PI).cos() - 1.0) / 2.0,
+            Ease::InExpo => expo(t),
+            Ease::OutExpo => 1.0 - expo(1.0 - t),
+            Ease::InOutExpo => in_out(t, expo),
+            Ease::InBack => (BACK + 1.0) * t * t * t - BACK * t * t,
+            Ease::OutBack => 1.0 + (BACK + 1.0) * (t - 1.0).powi(3) + BACK * (t - 1.0).powi(2),
+            Ease::InOutBack => in_out(t, |t| (BACK_IN_OUT + 1.0) * t * t * t - BACK_IN_OUT * t * t),
+            Ease::InElastic => elastic(t, ELASTIC),
+            Ease::OutElastic => 1.0 - elastic(1.0 - t, ELASTIC),
+            Ease::InOutElastic if t <= 0.0 || t >= 1.0 => t,
+            Ease::InOutElastic => {
+                let wave = ((20.0 * t - 11.125) * ELASTIC_IN_OUT).sin();
+                if t < 0.5 {
+                    -(2f32.powf(20.0 * t - 10.0) * wave) / 2.0
+                } else {
+                    2f32.powf(-20.0 * t + 10.0) * wave / 2.0 + 1.0
+                }
+            }
+            Ease::InBounce => 1.0 - bounce(1.0 - t),
+            Ease::OutBounce => bounce(t),
+            Ease::InOutBounce => in_out(t, |t| 1.0 - bounce(1.0 - t)),
+        }
+    }
+}
+
+/// Ease in with `ease_in` for the first half and out, mirrored, for the second
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+    }
+}
+
+fn expo(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else {
+        2f32.powf(10.0 * t - 10.0)
+    }
+}
+
+fn elastic(t: f32, period: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * period).sin()
+}
+
+fn bounce(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+impl From<Ease> for String {
+    fn from(ease: Ease) -> Self {
+        ease.name().to_string()
+    }
+}
+
+impl TryFrom<String> for Ease {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Ease::from_name(&name).ok_or_else(|| format!("unknown easing function '{}'", name))
+    }
+}
+
+/// Numeric component field a tween can animate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TweenProperty {
+    PositionX,
+    PositionY,
+    /// Transform rotation in radians
+    Rotation,
+    ScaleX,
+    ScaleY,
+    /// Sprite color channels
+    ColorR,
+    ColorG,
+    ColorB,
+    Alpha,
+    /// Camera zoom
+    Zoom,
+}
+
+const PROPERTY_NAMES: [(&str, TweenProperty); 10] = [
+    ("position.x", TweenProperty::PositionX),
+    ("position.y", TweenProperty::PositionY),
+    ("rotation", TweenProperty::Rotation),
+    ("scale.x", TweenProperty::ScaleX),
+    ("scale.y", TweenProperty::ScaleY),
+    ("color.r", TweenProperty::ColorR),
+    ("color.g", TweenProperty::ColorG),
+    ("color.b", TweenProperty::ColorB),
+    ("alpha", TweenProperty::Alpha),
+    ("zoom", TweenProperty::Zoom),
+];
+
+impl TweenProperty {
+    /// Look a property up by its script name, e.g. `"position.x"` or `"alpha"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        PROPERTY_NAMES.iter().find(|(n, _)| *n == name).map(|(_, property)| *property)
+    }
+
+    pub fn name(&self) -> &'static str {
+        PROPERTY_NAMES.iter().find(|(_, property)| property == self).map(|(n, _)| *n).unwrap_or_default()
+    }
+
+    /// Current value on an entity, or `None` if it lacks the component
+    pub fn get(&self, world: &World, entity: EntityHandle) -> Option<f32> {
+        match self {
+            TweenProperty::PositionX => world.get::<Transform>(entity).ok().map(|t| t.position.x),
+            TweenProperty::PositionY => world.get::<Transform>(entity).ok().map(|t| t.position.y),
+            TweenProperty::Rotation => world.get::<Transform>(entity).ok().map(|t| t.rotation),
+            TweenProperty::ScaleX => world.get::<Transform>(entity).ok().map(|t| t.scale.x),
+            TweenProperty::ScaleY => world.get::<Transform>(entity).ok().map(|t| t.scale.y),
+            TweenProperty::ColorR => world.get::<Sprite>(entity).ok().map(|s| s.color[0]),
+            TweenProperty::ColorG => world.get::<Sprite>(entity).ok().map(|s| s.color[1]),
+            TweenProperty::ColorB => world.get::<Sprite>(entity).ok().map(|s| s.color[2]),
+            TweenProperty::Alpha => world.get::<Sprite>(entity).ok().map(|s| s.color[3]),
+            TweenProperty::Zoom => world.get::<Camera>(entity).ok().map(|c| c.zoom),
+        }
+    }
+
+    /// Set the value on an entity, reporting the component as changed
+    ///
+    /// Does nothing if the entity lacks the component.
+    pub fn set(&self, world: &World, entity: EntityHandle, value: f32) {
+        match self {
+            TweenProperty::PositionX => {
+                if let Ok(mut t) = world.get_mut::<Transform>(entity) {
+                    t.position.x = value;
+                }
+            }
+            TweenProperty::PositionY => {
+                if let Ok(mut t) = world.get_mut::<Transform>(entity) {
+                    t.position.y = value;
+                }
+            }
+            TweenProperty::Rotation => {
+                if let Ok(mut t) = world.get_mut::<Transform>(entity) {
+                    t.rotation = value;
+                }
+            }
+            TweenProperty::ScaleX => {
+                if let Ok(mut t) = world.get_mut::<Transform>(entity) {
+                    t.scale.x = value;
+                }
+            }
+            TweenProperty::ScaleY => {
+                if let Ok(mut t) = world.get_mut::<Transform>(entity) {
+                    t.scale.y = value;
+                }
+            }
+            TweenProperty::ColorR | TweenProperty::ColorG | TweenProperty::ColorB | TweenProperty::Alpha => {
+                let channel = *self as usize - TweenProperty::ColorR as usize;
+                if let Ok(mut s) = world.get_mut::<Sprite>(entity) {
+                    s.color[channel] = value;
+                }
+            }
+            TweenProperty::Zoom => {
+                if let Ok(mut c) = world.get_mut::<Camera>(entity) {
+                    c.zoom = value;
+                }
+            }
+        }
+    }
+}
+
+/// How many times a tween plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TweenLoops {
+    Count(u32),
+    Forever,
+}
+
+impl Default for TweenLoops {
+    fn default() -> Self {
+        TweenLoops::Count(1)
+    }
+}
+
+/// A property animated towards a value
+#[derive(Debug, Clone, PartialEq)]
+pub struct TweenTarget {
+    pub property: TweenProperty,
+    pub value: f32,
+    /// Value when the step first started, captured by `update_tweens`
+    pub(crate) from: Option<f32>,
+}
+
+/// What a tween does
+#[derive(Debug, Clone, PartialEq)]
+pub enum TweenKind {
+    /// Animate properties from their values when the step starts; without
+    /// targets, just waits
+    To {
+        targets: Vec<TweenTarget>,
+        duration: f32,
+        ease: Ease,
+    },
+    /// Play tweens one after the other
+    Sequence(Vec<Tween>),
+    /// Play tweens at the same time, lasting as long as the longest
+    Parallel(Vec<Tween>),
+}
+
+/// Animation of an entity's numeric component fields
+///
+/// Tweens compose: sequences and parallel groups hold tweens with their own
+/// delays and loops. Played through the entity's `Tweens` component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween {
+    pub kind: TweenKind,
+    /// Seconds before the first play
+    pub delay: f32,
+    pub loops: TweenLoops,
+    /// Whether every other play runs backwards
+    pub yoyo: bool,
+    /// Name reported when the tween completes
+    pub name: Option<String>,
+}
+
+impl Tween {
+    /// Animate `targets` to their values over `duration` seconds
+    pub fn to(targets: impl IntoIterator<Item = (TweenProperty, f32)>, duration: f32, ease: Ease) -> Self {
+        let targets = targets
+            .into_iter()
+            .map(|(property, value)| TweenTarget { property, value, from: None })
+            .collect();
+        Self::new(TweenKind::To { targets, duration, ease })
+    }
+
+    /// Do nothing for `duration` seconds, e.g. between steps of a sequence
+    pub fn wait(duration: f32) -> Self {
+        Self::to([], duration, Ease::Linear)
+    }
+
+    pub fn sequence(tweens: Vec<Tween>) -> Self {
+        Self::new(TweenKind::Sequence(tweens))
+    }
+
+    pub fn parallel(tweens: Vec<Tween>) -> Self {
+        Self::new(TweenKind::Parallel(tweens))
+    }
+
+    fn new(kind: TweenKind) -> Self {
+        Self {
+            kind,
+            delay: 0.0,
+            loops: TweenLoops::default(),
+            yoyo: false,
+            name: None,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn with_loops(mut self, loops: TweenLoops) -> Self {
+        self.loops = loops;
+        self
+    }
+
+    pub fn with_yoyo(mut self, yoyo: bool) -> Self {
+        self.yoyo = yoyo;
+        self
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Length of one play in seconds
+    pub fn play_duration(&self) -> f32 {
+        match &self.kind {
+            TweenKind::To { duration, .. } => duration.max(0.0),
+            TweenKind::Sequence(tweens) => tweens.iter().map(Tween::duration).sum(),
+            TweenKind::Parallel(tweens) => tweens.iter().map(Tween::duration).fold(0.0, f32::max),
+        }
+    }
+
+    /// Length in seconds including the delay and every play; infinite for
+    /// tweens looping forever
+    pub fn duration(&self) -> f32 {
+        let play = self.play_duration();
+        match self.loops {
+            TweenLoops::Count(count) => self.delay + play * count.max(1) as f32,
+            // A zero-length tween can't loop
+            TweenLoops::Forever if play > 0.0 => f32::INFINITY,
+            TweenLoops::Forever => self.delay,
+        }
+    }
+}
+
+/// Identifies a tween played on an entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TweenId(pub u32);
+
+/// A tween in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveTween {
+    pub id: TweenId,
+    pub tween: Tween,
+    /// Seconds since the tween started
+    pub elapsed: f32,
+    /// Whether `update_tweens` applied it yet
+    pub(crate) started: bool,
+}
+
+/// Tweens playing on an entity
+///
+/// `update_tweens` advances them and drops them once complete. Removing the
+/// component or calling `clear` cancels them, leaving the fields where they
+/// are.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tweens {
+    active: Vec<ActiveTween>,
+    next_id: u32,
+}
+
+impl Tweens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start playing a tween from the entity's current values
+    pub fn play(&mut self, tween: Tween) -> TweenId {
+        let id = TweenId(self.next_id);
+        self.next_id += 1;
+        self.active.push(ActiveTween {
+            id,
+            tween,
+            elapsed: 0.0,
+            started: false,
+        });
+        id
+    }
+
+    /// Stop a tween; false if it isn't playing
+    pub fn cancel(&mut self, id: TweenId) -> bool {
+        let len = self.active.len();
+        self.active.retain(|active| active.id != id);
+        self.active.len() < len
+    }
+
+    /// Stop every tween
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn is_playing(&self, id: TweenId) -> bool {
+        self.active.iter().any(|active| active.id == id)
+    }
+
+    pub fn active(&self) -> &[ActiveTween] {
+        &self.active
+    }
+
+    pub(crate) fn active_mut(&mut self) -> &mut Vec<ActiveTween> {
+        &mut self.active
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+}
+
+/// Play a tween on an entity, adding a `Tweens` component if needed
+///
+/// Returns `None` if the entity doesn't exist.
+pub fn play_tween(world: &mut World, entity: EntityHandle, tween: Tween) -> Option<TweenId> {
+    if let Ok(mut tweens) = world.get_mut::<Tweens>(entity) {
+        return Some(tweens.play(tween));
+    }
+    let mut tweens = Tweens::new();
+    let id = tweens.play(tween);
+    world.set(entity, tweens).ok().map(|_| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eases_start_and_end_in_place() {
+        for (name, ease) in EASE_NAMES {
+            assert_eq!(Ease::from_name(name), Some(ease));
+            assert_eq!(ease.name(), name);
+            assert!(ease.apply(0.0).abs() < 1e-4, "{} starts at {}", name, ease.apply(0.0));
+            assert!((ease.apply(1.0) - 1.0).abs() < 1e-4, "{} ends at {}", name, ease.apply(1.0));
+        }
+        assert!(Ease::OutBack.apply(0.7) > 1.0);
+        assert!(Ease::InQuad.apply(0.5) < 0.5 && Ease::OutQuad.apply(0.5) > 0.5);
+        assert_eq!(Ease::InOutCubic.apply(0.5), 0.5);
+        assert_eq!(Ease::from_name("wobbly"), None);
+    }
+
+    #[test]
+    fn test_tween_durations() {
+        let step = Tween::to([(TweenProperty::Alpha, 0.0)], 0.5, Ease::Linear);
+        let sequence = Tween::sequence(vec![step.clone(), Tween::wait(0.25), step.clone().with_delay(0.25)]);
+        assert_eq!(sequence.duration(), 1.5);
+        let parallel = Tween::parallel(vec![step.clone(), sequence.clone()]).with_loops(TweenLoops::Count(2));
+        assert_eq!(parallel.duration(), 3.0);
+        assert_eq!(step.clone().with_loops(TweenLoops::Forever).duration(), f32::INFINITY);
+        assert_eq!(Tween::wait(0.0).with_delay(1.0).with_loops(TweenLoops::Forever).duration(), 1.0);
+    }
+}
@@ -0,0 +1,573 @@
+use crate::ecs::{ShapeStroke, SpriteDrawMode, TextAlign};
+use crate::math::Rect;
+use crate::types::AssetId;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// How canvas units relate to the screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CanvasScale {
+    /// One unit per game unit, so the UI scales with the design resolution
+    /// and the manifest's scale mode like the rest of the game
+    #[default]
+    Game,
+    /// One unit per screen pixel, so the UI keeps its size on any screen
+    Pixel,
+}
+
+/// Root of a tree of UI elements drawn in screen space over the game
+///
+/// The canvas covers the game area on screen; descendants with a
+/// `RectTransform` are laid out inside it. Canvases draw after every
+/// camera, in ascending `sort_order`, and the topmost gets touches first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Canvas {
+    pub sort_order: i32,
+    pub scale: CanvasScale,
+}
+
+impl Canvas {
+    /// Create a canvas that scales with the game
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the draw order among canvases
+    pub fn with_sort_order(mut self, sort_order: i32) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    /// Set how canvas units relate to the screen
+    pub fn with_scale(mut self, scale: CanvasScale) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+/// Placement of a UI element inside its parent's rect
+///
+/// Canvas space has its origin at the top-left with y growing downwards.
+/// Anchors are fractions of the parent rect (`(0, 0)` top-left, `(1, 1)`
+/// bottom-right): with both anchors equal the element keeps its `size` and
+/// sits at `position` from the anchor point; with anchors apart it stretches
+/// with the parent, `size` adding to the span between them. `pivot` is the
+/// point of the element, as a fraction of its size, that `position` places.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RectTransform {
+    pub anchor_min: Vec2,
+    pub anchor_max: Vec2,
+    pub pivot: Vec2,
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl RectTransform {
+    /// Create an element of `size` with its top-left corner at `position`
+    /// from the parent's top-left corner
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self {
+            anchor_min: Vec2::ZERO,
+            anchor_max: Vec2::ZERO,
+            pivot: Vec2::ZERO,
+            position,
+            size,
+        }
+    }
+
+    /// Create an element of `size` anchored and pivoted at the same point of
+    /// its parent, e.g. `(1, 0)` for the top-right corner or `(0.5, 0.5)`
+    /// for the center
+    pub fn anchored(anchor: Vec2, position: Vec2, size: Vec2) -> Self {
+        Self {
+            anchor_min: anchor,
+            anchor_max: anchor,
+            pivot: anchor,
+            position,
+            size,
+        }
+    }
+
+    /// Create an element filling its parent, inset by `margin` on every side
+    pub fn stretch(margin: f32) -> Self {
+        Self {
+            anchor_min: Vec2::ZERO,
+            anchor_max: Vec2::ONE,
+            pivot: Vec2::splat(0.5),
+            position: Vec2::ZERO,
+            size: Vec2::splat(-2.0 * margin),
+        }
+    }
+
+    /// The element's rect inside a parent rect
+    pub fn resolve(&self, parent: Rect) -> Rect {
+        let anchor_min = parent.min + parent.size() * self.anchor_min;
+        let anchor_max = parent.min + parent.size() * self.anchor_max;
+        let span = anchor_max - anchor_min;
+        let size = (span + self.size).max(Vec2::ZERO);
+        let reference = anchor_min + span * self.pivot + self.position;
+        Rect::from_pos_size(reference - size * self.pivot, size)
+    }
+}
+
+impl Default for RectTransform {
+    fn default() -> Self {
+        Self::new(Vec2::ZERO, Vec2::splat(100.0))
+    }
+}
+
+/// Axis a `UiLayout` places children along
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UiDirection {
+    /// Left to right
+    Row,
+    /// Top to bottom
+    #[default]
+    Column,
+}
+
+/// Placement of children along the main axis of a `UiLayout`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UiJustify {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// First and last child at the edges, the free space between the others
+    SpaceBetween,
+}
+
+/// Placement of children across the main axis of a `UiLayout`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UiAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Children fill the cross axis
+    Stretch,
+}
+
+/// Flex-like layout of a UI element's children
+///
+/// Children are placed one after the other along `direction` using the
+/// `size` of their `RectTransform`; their anchors and position are ignored.
+/// Space left over is shared between children with a `UiLayoutItem` in
+/// proportion to their `grow`, or distributed by `justify` if none grow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiLayout {
+    pub direction: UiDirection,
+    /// Space between consecutive children
+    pub gap: f32,
+    /// Space between the element's edges and its children
+    pub padding: f32,
+    pub justify: UiJustify,
+    pub align: UiAlign,
+}
+
+impl UiLayout {
+    /// Create a layout placing children left to right
+    pub fn row() -> Self {
+        Self {
+            direction: UiDirection::Row,
+            ..Default::default()
+        }
+    }
+
+    /// Create a layout placing children top to bottom
+    pub fn column() -> Self {
+        Self::default()
+    }
+
+    /// Set the space between children
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Set the space around the children
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the main axis placement
+    pub fn with_justify(mut self, justify: UiJustify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    /// Set the cross axis placement
+    pub fn with_align(mut self, align: UiAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// How a child of a `UiLayout` shares the space left over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiLayoutItem {
+    /// Share of the free space added to the child's size; 0 keeps its size
+    pub grow: f32,
+}
+
+impl UiLayoutItem {
+    pub fn grow(grow: f32) -> Self {
+        Self { grow }
+    }
+}
+
+/// Filled rectangle behind a UI element, optionally rounded and outlined
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiPanel {
+    /// RGBA color
+    pub color: [f32; 4],
+    pub corner_radius: f32,
+    pub border: Option<ShapeStroke>,
+}
+
+impl UiPanel {
+    /// Create a square-cornered panel without a border
+    pub fn new(color: [f32; 4]) -> Self {
+        Self {
+            color,
+            corner_radius: 0.0,
+            border: None,
+        }
+    }
+
+    /// Round the corners
+    pub fn with_corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Outline the panel
+    pub fn with_border(mut self, color: [f32; 4], width: f32) -> Self {
+        self.border = Some(ShapeStroke::new(color, width));
+        self
+    }
+}
+
+impl Default for UiPanel {
+    fn default() -> Self {
+        Self::new([1.0, 1.0, 1.0, 1.0])
+    }
+}
+
+/// Texture stretched over a UI element's rect
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiImage {
+    pub texture: AssetId,
+    /// RGBA tint
+    pub color: [f32; 4],
+    /// How the texture fills the rect; nine-slice keeps the borders of
+    /// framed panels and buttons crisp at any size
+    pub draw_mode: SpriteDrawMode,
+}
+
+impl UiImage {
+    /// Create an untinted image
+    pub fn new(texture: AssetId) -> Self {
+        Self {
+            texture,
+            ..Default::default()
+        }
+    }
+
+    /// Tint the image
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set how the texture fills the rect
+    pub fn with_draw_mode(mut self, draw_mode: SpriteDrawMode) -> Self {
+        self.draw_mode = draw_mode;
+        self
+    }
+}
+
+impl Default for UiImage {
+    fn default() -> Self {
+        Self {
+            texture: AssetId::new(0),
+            color: [1.0, 1.0, 1.0, 1.0],
+            draw_mode: SpriteDrawMode::Simple,
+        }
+    }
+}
+
+/// Vertical placement of a `UiLabel`'s lines inside its rect
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerticalAlign {
+    Top,
+    #[default]
+    Middle,
+    Bottom,
+}
+
+/// Text drawn inside a UI element's rect
+///
+/// Lines are placed horizontally by `align` and vertically by
+/// `vertical_align`; with `wrap` they break at the width of the rect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiLabel {
+    pub text: String,
+    /// Font asset (`.ttf`/`.otf` or BMFont `.fnt`)
+    pub font: AssetId,
+    /// Line height in canvas units
+    pub size: f32,
+    /// RGBA color
+    pub color: [f32; 4],
+    pub align: TextAlign,
+    pub vertical_align: VerticalAlign,
+    pub wrap: bool,
+}
+
+impl UiLabel {
+    /// Create a white, left-aligned, vertically centered label
+    pub fn new(text: impl Into<String>, font: AssetId, size: f32) -> Self {
+        Self {
+            text: text.into(),
+            font,
+            size,
+            ..Default::default()
+        }
+    }
+
+    /// Set the horizontal alignment
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the vertical alignment
+    pub fn with_vertical_align(mut self, vertical_align: VerticalAlign) -> Self {
+        self.vertical_align = vertical_align;
+        self
+    }
+
+    /// Set the color
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Break lines at the width of the rect
+    pub fn with_wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+}
+
+impl Default for UiLabel {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            font: AssetId::new(0),
+            size: 32.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+            align: TextAlign::Left,
+            vertical_align: VerticalAlign::Middle,
+            wrap: false,
+        }
+    }
+}
+
+/// Makes a UI element pressable
+///
+/// The element's `UiPanel` or `UiImage` is tinted by the color of the
+/// button's state. Releasing a press over the element clicks it, which
+/// scripts on it and its ancestors receive in `onClick`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiButton {
+    /// Tint while idle
+    pub normal_color: [f32; 4],
+    /// Tint while pressed
+    pub pressed_color: [f32; 4],
+    /// Tint while not interactable
+    pub disabled_color: [f32; 4],
+    pub interactable: bool,
+}
+
+impl UiButton {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for UiButton {
+    fn default() -> Self {
+        Self {
+            normal_color: [1.0, 1.0, 1.0, 1.0],
+            pressed_color: [0.75, 0.75, 0.75, 1.0],
+            disabled_color: [0.5, 0.5, 0.5, 0.5],
+            interactable: true,
+        }
+    }
+}
+
+/// Horizontal track with a handle dragged between `min` and `max`
+///
+/// Drawn as a rounded track filled up to a round handle, sized by the
+/// element's rect. Each change reaches scripts in `onValueChanged`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSlider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Values snap to multiples of `step` from `min`; 0 is continuous
+    pub step: f32,
+    pub track_color: [f32; 4],
+    pub fill_color: [f32; 4],
+    pub handle_color: [f32; 4],
+    pub interactable: bool,
+}
+
+impl UiSlider {
+    /// Create a continuous slider starting at `min`
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            value: min,
+            min,
+            max,
+            ..Default::default()
+        }
+    }
+
+    /// Set the value, clamped and snapped to the range
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = self.snap(value);
+        self
+    }
+
+    /// Snap values to multiples of `step`
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Clamp a value to the range and snap it to the step
+    pub fn snap(&self, value: f32) -> f32 {
+        let (low, high) = (self.min.min(self.max), self.min.max(self.max));
+        let value = if self.step > 0.0 {
+            self.min + ((value - self.min) / self.step).round() * self.step
+        } else {
+            value
+        };
+        value.clamp(low, high)
+    }
+
+    /// Position of the value in the range, from 0 at `min` to 1 at `max`
+    pub fn fraction(&self) -> f32 {
+        let range = self.max - self.min;
+        if range == 0.0 {
+            0.0
+        } else {
+            ((self.value - self.min) / range).clamp(0.0, 1.0)
+        }
+    }
+}
+
+impl Default for UiSlider {
+    fn default() -> Self {
+        Self {
+            value: 0.0,
+            min: 0.0,
+            max: 1.0,
+            step: 0.0,
+            track_color: [0.3, 0.3, 0.3, 1.0],
+            fill_color: [0.3, 0.6, 1.0, 1.0],
+            handle_color: [1.0, 1.0, 1.0, 1.0],
+            interactable: true,
+        }
+    }
+}
+
+/// Clips its children to its rect and scrolls them by dragging
+///
+/// Children are laid out as usual and then moved up and left by `offset`,
+/// which dragging keeps between zero and the extent of the children beyond
+/// the rect. Drags starting on a button inside scroll instead of clicking
+/// once they pass a small threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiScrollView {
+    pub offset: Vec2,
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+impl UiScrollView {
+    /// Create a vertically scrolling view
+    pub fn vertical() -> Self {
+        Self::default()
+    }
+
+    /// Create a horizontally scrolling view
+    pub fn horizontal() -> Self {
+        Self {
+            horizontal: true,
+            vertical: false,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for UiScrollView {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            horizontal: false,
+            vertical: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_transform_anchors() {
+        let parent = Rect::from_pos_size(Vec2::new(10.0, 20.0), Vec2::new(200.0, 100.0));
+
+        let fixed = RectTransform::new(Vec2::new(5.0, 5.0), Vec2::new(50.0, 20.0));
+        assert_eq!(fixed.resolve(parent), Rect::from_pos_size(Vec2::new(15.0, 25.0), Vec2::new(50.0, 20.0)));
+
+        // Anchored to the bottom-right corner, pivoted there too
+        let corner = RectTransform::anchored(Vec2::ONE, Vec2::new(-10.0, -10.0), Vec2::new(40.0, 30.0));
+        assert_eq!(corner.resolve(parent), Rect::new(Vec2::new(160.0, 80.0), Vec2::new(200.0, 110.0)));
+
+        let centered = RectTransform::anchored(Vec2::splat(0.5), Vec2::ZERO, Vec2::new(20.0, 20.0));
+        assert_eq!(centered.resolve(parent).center(), parent.center());
+
+        let stretched = RectTransform::stretch(10.0);
+        assert_eq!(stretched.resolve(parent), Rect::new(Vec2::new(20.0, 30.0), Vec2::new(200.0, 110.0)));
+    }
+
+    #[test]
+    fn test_slider_snaps_to_range_and_step() {
+        let slider = UiSlider::new(0.0, 10.0).with_step(2.5);
+        assert_eq!(slider.snap(3.5), 2.5);
+        assert_eq!(slider.snap(4.0), 5.0);
+        assert_eq!(slider.snap(-3.0), 0.0);
+        assert_eq!(slider.snap(12.0), 10.0);
+        assert_eq!(slider.with_value(7.4).fraction(), 0.75);
+    }
+}
@@ -1,28 +1,47 @@
-use crate::ecs::{EntityHandle, Name};
+use crate::ecs::change::ChangeTracker;
+use crate::ecs::{ComponentType, EntityGuid, EntityHandle, Name, WorldChange};
 use crate::types::{LonghornError, Result};
 use hecs::{Entity, World as HecsWorld};
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
 
 /// ECS World wrapper
 pub struct World {
     world: HecsWorld,
+    /// Lookup from persistent GUID to live entity
+    guids: HashMap<EntityGuid, Entity>,
+    /// Set when `inner_mut` handed out raw access since the index was built
+    guids_stale: bool,
+    /// Opt-in per-frame change log
+    changes: ChangeTracker,
 }
 
 impl World {
     /// Create a new empty world
     pub fn new() -> Self {
+        let changes = ChangeTracker::default();
+        changes.register::<EntityGuid>();
+
         Self {
             world: HecsWorld::new(),
+            guids: HashMap::new(),
+            guids_stale: false,
+            changes,
         }
     }
 
     /// Spawn a new entity and return a builder
     pub fn spawn(&mut self) -> EntityBuilder<'_> {
+        self.refresh_guid_index();
         EntityBuilder::new(self)
     }
 
     /// Spawn an entity with components
     pub fn spawn_with(&mut self, components: impl hecs::DynamicBundle) -> EntityHandle {
+        self.refresh_guid_index();
         let id = self.world.spawn(components);
+        self.register_guid(id);
+        self.record_spawn(id);
         EntityHandle::new(id)
     }
 
@@ -33,6 +52,7 @@ impl World {
         if !self.exists(entity) {
             return Err(LonghornError::EntityNotFound(entity.id));
         }
+        self.refresh_guid_index();
 
         // Collect all descendants before deleting
         let descendants = collect_descendants(self, entity);
@@ -41,6 +61,8 @@ impl World {
         clear_parent(self, entity).ok();
 
         // Despawn the entity
+        self.record_despawn(entity.id);
+        self.unregister_guid(entity.id);
         self.world.despawn(entity.id)
             .map_err(|_| LonghornError::EntityNotFound(entity.id))?;
 
@@ -48,6 +70,8 @@ impl World {
         for descendant in descendants {
             let descendant_handle = EntityHandle::new(descendant);
             if self.exists(descendant_handle) {
+                self.record_despawn(descendant);
+                self.unregister_guid(descendant);
                 self.world.despawn(descendant).ok();
             }
         }
@@ -70,6 +94,185 @@ impl World {
         None
     }
 
+    /// Find an entity by its persistent GUID
+    ///
+    /// Entities spawned or retagged through `inner_mut` are missed until the
+    /// GUID index is rebuilt by the next spawn, despawn, `set` or `remove`,
+    /// or by `rebuild_guid_index`; call that after raw edits to look them up
+    /// right away.
+    pub fn entity_by_guid(&self, guid: EntityGuid) -> Option<EntityHandle> {
+        let entity = *self.guids.get(&guid)?;
+        // Raw access may have retagged or removed the indexed GUID since
+        self.world
+            .get::<&EntityGuid>(entity)
+            .is_ok_and(|g| *g == guid)
+            .then(|| EntityHandle::new(entity))
+    }
+
+    /// Check if a live entity owns a GUID
+    pub(crate) fn contains_guid(&mut self, guid: EntityGuid) -> bool {
+        self.refresh_guid_index();
+        self.guids.contains_key(&guid)
+    }
+
+    /// Get the persistent GUID of an entity
+    pub fn guid(&self, entity: EntityHandle) -> Option<EntityGuid> {
+        self.world.get::<&EntityGuid>(entity.id).ok().map(|g| *g)
+    }
+
+    /// Rebuild the GUID lookup table from the `EntityGuid` components in the world
+    ///
+    /// Call this after spawning or editing entities through `inner_mut` to
+    /// look them up right away; otherwise the next spawn, despawn, `set` or
+    /// `remove` does.
+    pub fn rebuild_guid_index(&mut self) {
+        self.guids.clear();
+        self.guids
+            .extend(self.world.query::<&EntityGuid>().iter().map(|(entity, guid)| (*guid, entity)));
+        self.guids_stale = false;
+    }
+
+    /// Rebuild the GUID index if raw access may have changed it
+    fn refresh_guid_index(&mut self) {
+        if self.guids_stale {
+            self.rebuild_guid_index();
+        }
+    }
+
+    /// Ensure an entity has a GUID and index it
+    fn register_guid(&mut self, entity: Entity) {
+        let existing = self.world.get::<&EntityGuid>(entity).ok().map(|g| *g);
+        let guid = match existing {
+            Some(guid) => guid,
+            None => {
+                let guid = EntityGuid::new();
+                let _ = self.world.insert_one(entity, guid);
+                guid
+            }
+        };
+        self.guids.insert(guid, entity);
+    }
+
+    /// Drop an entity's GUID from the index
+    fn unregister_guid(&mut self, entity: Entity) {
+        if let Some(guid) = self.world.get::<&EntityGuid>(entity).ok().map(|g| *g) {
+            if self.guids.get(&guid) == Some(&entity) {
+                self.guids.remove(&guid);
+            }
+        }
+    }
+
+    /// Enable or disable change tracking
+    ///
+    /// While enabled, spawns, despawns and component additions, changes and
+    /// removals made through this API are recorded until `clear_changes` or
+    /// `take_changes` is called (the engine does this once per frame).
+    /// Disabling tracking discards any recorded changes.
+    pub fn set_change_tracking(&mut self, enabled: bool) {
+        self.changes.set_enabled(enabled);
+    }
+
+    /// Check if change tracking is enabled
+    pub fn is_change_tracking(&self) -> bool {
+        self.changes.is_enabled()
+    }
+
+    /// Get all changes recorded since the last clear, in order
+    pub fn changes(&self) -> Vec<WorldChange> {
+        self.changes.changes()
+    }
+
+    /// Take all recorded changes, clearing the log
+    pub fn take_changes(&mut self) -> Vec<WorldChange> {
+        self.changes.take()
+    }
+
+    /// Clear the change log (start of a new frame)
+    pub fn clear_changes(&mut self) {
+        self.changes.clear();
+    }
+
+    /// Entities spawned since the last clear
+    pub fn spawned(&self) -> Vec<EntityHandle> {
+        let mut entities = Vec::new();
+        self.changes.for_each(|change| {
+            if let WorldChange::Spawned { entity } = *change {
+                entities.push(entity);
+            }
+        });
+        entities
+    }
+
+    /// Entities despawned since the last clear, with their GUIDs
+    pub fn despawned(&self) -> Vec<(EntityHandle, Option<EntityGuid>)> {
+        let mut entities = Vec::new();
+        self.changes.for_each(|change| {
+            if let WorldChange::Despawned { entity, guid } = *change {
+                entities.push((entity, guid));
+            }
+        });
+        entities
+    }
+
+    /// Entities that gained a `T` component since the last clear
+    pub fn added<T: hecs::Component>(&self) -> Vec<EntityHandle> {
+        self.component_changes::<T>(|change| matches!(change, WorldChange::Added { .. }))
+    }
+
+    /// Entities whose existing `T` component was replaced or mutably borrowed
+    /// since the last clear (freshly added components are reported by `added`)
+    pub fn changed<T: hecs::Component>(&self) -> Vec<EntityHandle> {
+        self.component_changes::<T>(|change| matches!(change, WorldChange::Changed { .. }))
+    }
+
+    /// Entities that lost a `T` component since the last clear
+    pub fn removed<T: hecs::Component>(&self) -> Vec<EntityHandle> {
+        self.component_changes::<T>(|change| matches!(change, WorldChange::Removed { .. }))
+    }
+
+    /// Record a change to a component mutated outside of `set`/`get_mut`
+    /// (e.g. through `query_mut` or `inner_mut`)
+    pub fn mark_changed<T: hecs::Component>(&self, entity: EntityHandle) {
+        if self.has::<T>(entity) {
+            self.changes.changed(entity.id, ComponentType::of::<T>());
+        }
+    }
+
+    fn component_changes<T: hecs::Component>(&self, filter: impl Fn(&WorldChange) -> bool) -> Vec<EntityHandle> {
+        let mut entities = Vec::new();
+        let mut seen = HashSet::new();
+        self.changes.for_each(|change| {
+            if filter(change)
+                && change.component().is_some_and(|c| c.is::<T>())
+                && seen.insert(change.entity())
+            {
+                entities.push(change.entity());
+            }
+        });
+        entities
+    }
+
+    /// Record a spawned entity and its initial components
+    fn record_spawn(&self, entity: Entity) {
+        if !self.changes.is_enabled() {
+            return;
+        }
+        self.changes.spawned(entity);
+        if let Ok(entity_ref) = self.world.entity(entity) {
+            for id in entity_ref.component_types() {
+                self.changes.added(entity, self.changes.component_type(id));
+            }
+        }
+    }
+
+    /// Record a despawned entity (call before it is removed)
+    fn record_despawn(&self, entity: Entity) {
+        if self.changes.is_enabled() {
+            let guid = self.world.get::<&EntityGuid>(entity).ok().map(|g| *g);
+            self.changes.despawned(entity, guid);
+        }
+    }
+
     /// Get a component from an entity
     pub fn get<T: hecs::Component>(&self, entity: EntityHandle) -> Result<hecs::Ref<'_, T>> {
         self.world
@@ -78,10 +281,15 @@ impl World {
     }
 
     /// Get a mutable component from an entity
+    ///
+    /// With change tracking enabled, the component is reported as changed.
     pub fn get_mut<T: hecs::Component>(&self, entity: EntityHandle) -> Result<hecs::RefMut<'_, T>> {
-        self.world
+        let component = self
+            .world
             .get::<&mut T>(entity.id)
-            .map_err(|_| LonghornError::ComponentNotFound(entity.id))
+            .map_err(|_| LonghornError::ComponentNotFound(entity.id))?;
+        self.changes.changed(entity.id, ComponentType::of::<T>());
+        Ok(component)
     }
 
     /// Set a component on an entity (insert or replace)
@@ -89,16 +297,37 @@ impl World {
         if !self.exists(entity) {
             return Err(LonghornError::EntityNotFound(entity.id));
         }
+        self.refresh_guid_index();
+        if let Some(guid) = (&component as &dyn Any).downcast_ref::<EntityGuid>().copied() {
+            self.unregister_guid(entity.id);
+            self.guids.insert(guid, entity.id);
+        }
+        let existed = self.has::<T>(entity);
         self.world
             .insert_one(entity.id, component)
-            .map_err(|_| LonghornError::EntityNotFound(entity.id))
+            .map_err(|_| LonghornError::EntityNotFound(entity.id))?;
+        if existed {
+            self.changes.changed(entity.id, ComponentType::of::<T>());
+        } else {
+            self.changes.added(entity.id, ComponentType::of::<T>());
+        }
+        Ok(())
     }
 
     /// Remove a component from an entity
     pub fn remove<T: hecs::Component>(&mut self, entity: EntityHandle) -> Result<T> {
-        self.world
+        self.refresh_guid_index();
+        let component = self
+            .world
             .remove_one::<T>(entity.id)
-            .map_err(|_| LonghornError::ComponentNotFound(entity.id))
+            .map_err(|_| LonghornError::ComponentNotFound(entity.id))?;
+        if let Some(guid) = (&component as &dyn Any).downcast_ref::<EntityGuid>() {
+            if self.guids.get(guid) == Some(&entity.id) {
+                self.guids.remove(guid);
+            }
+        }
+        self.changes.removed(entity.id, ComponentType::of::<T>());
+        Ok(component)
     }
 
     /// Check if an entity has a component
@@ -112,10 +341,48 @@ impl World {
     }
 
     /// Query entities mutably
+    ///
+    /// Change `EntityGuid` through `set` instead; the GUID index doesn't see
+    /// edits made through queries.
     pub fn query_mut<Q: hecs::Query>(&mut self) -> hecs::QueryBorrow<'_, Q> {
         self.world.query::<Q>()
     }
 
+    /// Query one entity's components mutably
+    pub fn query_one_mut<Q: hecs::Query>(&mut self, entity: EntityHandle) -> Result<<Q as hecs::Query>::Item<'_>> {
+        self.world
+            .query_one_mut::<Q>(entity.id)
+            .map_err(|_| LonghornError::ComponentNotFound(entity.id))
+    }
+
+    /// Insert or replace a component without recording a change
+    ///
+    /// For runtime data derived from other components, such as laid out
+    /// glyphs or particle state. `EntityGuid` is refused; use `set`.
+    pub fn insert_untracked<T: hecs::Component>(&mut self, entity: EntityHandle, component: T) -> Result<()> {
+        Self::check_untracked::<T>()?;
+        self.world
+            .insert_one(entity.id, component)
+            .map_err(|_| LonghornError::EntityNotFound(entity.id))
+    }
+
+    /// Remove a component without recording a change; see `insert_untracked`
+    pub fn remove_untracked<T: hecs::Component>(&mut self, entity: EntityHandle) -> Result<T> {
+        Self::check_untracked::<T>()?;
+        self.world
+            .remove_one::<T>(entity.id)
+            .map_err(|_| LonghornError::ComponentNotFound(entity.id))
+    }
+
+    fn check_untracked<T: 'static>() -> Result<()> {
+        if TypeId::of::<T>() == TypeId::of::<EntityGuid>() {
+            return Err(LonghornError::InvalidOperation(
+                "EntityGuid must be changed through World::set".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Get the number of entities
     pub fn len(&self) -> usize {
         self.world.len() as usize
@@ -127,8 +394,18 @@ impl World {
     }
 
     /// Clear all entities
+    ///
+    /// With change tracking enabled, every entity is reported as despawned.
     pub fn clear(&mut self) {
+        if self.changes.is_enabled() {
+            let entities: Vec<Entity> = self.world.iter().map(|e| e.entity()).collect();
+            for entity in entities {
+                self.record_despawn(entity);
+            }
+        }
         self.world.clear();
+        self.guids.clear();
+        self.guids_stale = false;
     }
 
     /// Get access to the underlying hecs world
@@ -137,7 +414,14 @@ impl World {
     }
 
     /// Get mutable access to the underlying hecs world
+    ///
+    /// Entities spawned or given a new `EntityGuid` this way aren't found by
+    /// `entity_by_guid` until the GUID index is rebuilt, which happens on
+    /// the next spawn, despawn, `set` or `remove`, or `rebuild_guid_index`.
+    /// Since that rebuild walks the whole world, per-frame code should use
+    /// `query_mut`, `query_one_mut` and `insert_untracked` instead.
     pub fn inner_mut(&mut self) -> &mut HecsWorld {
+        self.guids_stale = true;
         &mut self.world
     }
 }
@@ -164,6 +448,10 @@ impl<'w> EntityBuilder<'w> {
 
     /// Add a component to the entity being built
     pub fn with<T: hecs::Component>(mut self, component: T) -> Self {
+        // Names are only needed to report the spawn, so skip the tracker's lock otherwise
+        if self.world.changes.is_enabled() {
+            self.world.changes.register::<T>();
+        }
         if let Some(entity) = self.entity {
             // Entity already exists, insert component
             let _ = self.world.world.insert_one(entity, component);
@@ -192,6 +480,8 @@ impl<'w> EntityBuilder<'w> {
             // No components were added, spawn empty entity
             self.world.world.spawn(())
         });
+        self.world.register_guid(entity);
+        self.world.record_spawn(entity);
         EntityHandle::new(entity)
     }
 }
@@ -400,4 +690,140 @@ mod tests {
         assert!(!world.exists(child1));
         assert!(!world.exists(grandchild));
     }
+
+    #[test]
+    fn test_spawn_assigns_guid() {
+        let mut world = World::new();
+        let built = world.spawn().with(Name::new("Built")).build();
+        let bundled = world.spawn_with((Name::new("Bundled"),));
+        let empty = world.spawn().build();
+
+        for entity in [built, bundled, empty] {
+            let guid = world.guid(entity).unwrap();
+            assert_eq!(world.entity_by_guid(guid), Some(entity));
+        }
+        assert_ne!(world.guid(built), world.guid(bundled));
+    }
+
+    #[test]
+    fn test_spawn_keeps_explicit_guid() {
+        let mut world = World::new();
+        let entity = world.spawn().with(EntityGuid(1234)).build();
+
+        assert_eq!(world.guid(entity), Some(EntityGuid(1234)));
+        assert_eq!(world.entity_by_guid(EntityGuid(1234)), Some(entity));
+    }
+
+    #[test]
+    fn test_guid_lookup_after_set_remove_and_despawn() {
+        let mut world = World::new();
+        let entity = world.spawn().build();
+        let old_guid = world.guid(entity).unwrap();
+
+        world.set(entity, EntityGuid(7)).unwrap();
+        assert_eq!(world.entity_by_guid(EntityGuid(7)), Some(entity));
+        assert_eq!(world.entity_by_guid(old_guid), None);
+
+        world.remove::<EntityGuid>(entity).unwrap();
+        assert_eq!(world.entity_by_guid(EntityGuid(7)), None);
+
+        world.set(entity, EntityGuid(8)).unwrap();
+        world.despawn(entity).unwrap();
+        assert_eq!(world.entity_by_guid(EntityGuid(8)), None);
+    }
+
+    #[test]
+    fn test_guid_index_catches_up_with_raw_access() {
+        let mut world = World::new();
+        let raw = world.inner_mut().spawn((EntityGuid(9),));
+        assert_eq!(world.entity_by_guid(EntityGuid(9)), None);
+
+        // The next spawn rebuilds the index
+        let spawned = world.spawn().build();
+        assert_eq!(world.entity_by_guid(EntityGuid(9)), Some(EntityHandle::new(raw)));
+        assert!(world.contains_guid(EntityGuid(9)));
+
+        let guid = world.guid(spawned).unwrap();
+        world.inner_mut().despawn(spawned.id).unwrap();
+        assert_eq!(world.entity_by_guid(guid), None);
+        assert!(!world.contains_guid(guid));
+    }
+
+    #[test]
+    fn test_untracked_access_keeps_guid_index() {
+        let mut world = World::new();
+        world.set_change_tracking(true);
+        let entity = world.spawn().with(Name::new("Player")).build();
+        world.clear_changes();
+
+        world.insert_untracked(entity, Transform::new()).unwrap();
+        *world.query_one_mut::<&mut Name>(entity).unwrap() = Name::new("Hero");
+        world.remove_untracked::<Transform>(entity).unwrap();
+
+        assert!(world.changes().is_empty());
+        assert!(!world.guids_stale);
+        assert!(world.insert_untracked(entity, EntityGuid(3)).is_err());
+        assert!(world.remove_untracked::<EntityGuid>(entity).is_err());
+
+        // Per-frame systems don't force a rebuild
+        crate::systems::update_tweens(&mut world, 0.1);
+        crate::systems::update_particles(&mut world, 0.1);
+        assert!(!world.guids_stale);
+    }
+
+    #[test]
+    fn test_change_tracking_disabled_by_default() {
+        let mut world = World::new();
+        let entity = world.spawn().with(Name::new("Test")).build();
+        world.set(entity, Transform::new()).unwrap();
+
+        assert!(!world.is_change_tracking());
+        assert!(world.changes().is_empty());
+    }
+
+    #[test]
+    fn test_change_tracking_spawn_and_despawn() {
+        let mut world = World::new();
+        world.set_change_tracking(true);
+
+        let entity = world.spawn().with(Name::new("Test")).build();
+        let guid = world.guid(entity);
+        assert_eq!(world.spawned(), vec![entity]);
+        assert_eq!(world.added::<Name>(), vec![entity]);
+        assert_eq!(world.added::<EntityGuid>(), vec![entity]);
+
+        world.clear_changes();
+        world.despawn(entity).unwrap();
+        assert_eq!(world.despawned(), vec![(entity, guid)]);
+        assert!(world.spawned().is_empty());
+    }
+
+    #[test]
+    fn test_change_tracking_components() {
+        let mut world = World::new();
+        let entity = world.spawn().with(Name::new("Test")).build();
+        world.set_change_tracking(true);
+
+        world.set(entity, Transform::new()).unwrap();
+        world.set(entity, Name::new("Renamed")).unwrap();
+        world.get_mut::<Name>(entity).unwrap().0.push('!');
+        world.remove::<Transform>(entity).unwrap();
+
+        assert_eq!(world.added::<Transform>(), vec![entity]);
+        assert_eq!(world.removed::<Transform>(), vec![entity]);
+        assert_eq!(world.changed::<Name>(), vec![entity]);
+
+        // Repeated changes to the same component are reported once per frame
+        let changes = world.take_changes();
+        let name_changes = changes
+            .iter()
+            .filter(|c| matches!(c, WorldChange::Changed { component, .. } if component.is::<Name>()))
+            .count();
+        assert_eq!(name_changes, 1);
+        assert_eq!(changes[0].component().unwrap().name, "Transform");
+
+        assert!(world.changes().is_empty());
+        world.mark_changed::<Name>(entity);
+        assert_eq!(world.changed::<Name>(), vec![entity]);
+    }
 }
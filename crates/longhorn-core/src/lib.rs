@@ -1,5 +1,6 @@
 pub mod ecs;
 pub mod math;
+pub mod navigation;
 pub mod scene;
 pub mod systems;
 pub mod time;
@@ -7,6 +8,7 @@ pub mod types;
 
 pub use ecs::*;
 pub use math::*;
+pub use navigation::*;
 pub use scene::*;
 pub use systems::*;
 pub use time::*;
@@ -103,7 +103,7 @@ impl GlobalTransform {
     }
 
     /// Transform a point by this global transform
-    fn transform_point(&self, point: Vec2) -> Vec2 {
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
         // Apply scale
         let scaled = point * self.scale;
 
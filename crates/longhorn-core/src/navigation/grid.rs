@@ -0,0 +1,392 @@
+use crate::ecs::{TileSet, Tilemap};
+use crate::math::Rect;
+use crate::navigation::SearchSpace;
+use glam::Vec2;
+
+/// How far a collider has to reach into a cell to block it, as a fraction
+/// of the cell, so colliders that only touch a cell's edge don't block it
+const OVERLAP_EPSILON: f32 = 1e-3;
+
+/// Grid of cells marking where agents can walk
+///
+/// Cell (0, 0) has its bottom-left corner at `origin`; x grows to the right
+/// and y grows upwards, like world space. A cell is walkable unless the
+/// level blocks it (`set_blocked`) or a dynamic obstacle covers it
+/// (`add_obstacle`). Every change bumps `revision`, so agents can tell when
+/// to check their paths again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavGrid {
+    origin: Vec2,
+    cell_size: Vec2,
+    width: u32,
+    height: u32,
+    /// Cells blocked by the level
+    blocked: Vec<bool>,
+    /// Number of dynamic obstacles covering each cell
+    obstacles: Vec<u16>,
+    revision: u64,
+}
+
+impl NavGrid {
+    /// Create a grid where every cell is walkable
+    pub fn new(origin: Vec2, cell_size: Vec2, width: u32, height: u32) -> Self {
+        let cells = width as usize * height as usize;
+        Self {
+            origin,
+            cell_size,
+            width,
+            height,
+            blocked: vec![false; cells],
+            obstacles: vec![0; cells],
+            revision: 0,
+        }
+    }
+
+    /// Create a grid covering `bounds`, blocking the cells that overlap any
+    /// of `colliders`
+    pub fn from_colliders(bounds: Rect, cell_size: Vec2, colliders: impl IntoIterator<Item = Rect>) -> Self {
+        let width = (bounds.width() / cell_size.x).ceil().max(1.0) as u32;
+        let height = (bounds.height() / cell_size.y).ceil().max(1.0) as u32;
+        let mut grid = Self::new(bounds.min, cell_size, width, height);
+        for collider in colliders {
+            for (x, y) in grid.cells_overlapping(collider) {
+                let index = grid.index(x, y);
+                grid.blocked[index] = true;
+            }
+        }
+        grid
+    }
+
+    /// Create a grid with the tilemap's cells, covering its tiles and blocking
+    /// its solid ones
+    ///
+    /// `position` is where the tilemap entity is in the world. One-way tiles
+    /// don't block: they're platforms, not walls.
+    pub fn from_tilemap(tilemap: &Tilemap, tileset: &TileSet, position: Vec2) -> Self {
+        let bounds = tilemap
+            .layers
+            .iter()
+            .flat_map(|layer| layer.tiles())
+            .map(|(x, y, _)| tilemap.cell_rect(x, y))
+            .reduce(|bounds, cell| bounds.union(&cell))
+            .unwrap_or_else(|| Rect::new(Vec2::ZERO, tilemap.cell_size));
+        let solid = tilemap
+            .colliders(tileset)
+            .into_iter()
+            .filter(|collider| collider.flags.solid)
+            .map(|collider| collider.rect.translate(position));
+        Self::from_colliders(bounds.translate(position), tilemap.cell_size, solid)
+    }
+
+    /// World position of the bottom-left corner of cell (0, 0)
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    /// Size of a cell in world units
+    pub fn cell_size(&self) -> Vec2 {
+        self.cell_size
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Number of rows
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Counter bumped by every change to the walkable cells
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Area the grid covers
+    pub fn bounds(&self) -> Rect {
+        Rect::from_pos_size(self.origin, self.cell_size * Vec2::new(self.width as f32, self.height as f32))
+    }
+
+    /// Cell containing a world position, if it's on the grid
+    pub fn cell_at(&self, point: Vec2) -> Option<(u32, u32)> {
+        let cell = ((point - self.origin) / self.cell_size).floor();
+        self.cell(cell.x as i64, cell.y as i64)
+    }
+
+    /// Center of a cell in world space
+    pub fn cell_center(&self, x: u32, y: u32) -> Vec2 {
+        self.origin + (Vec2::new(x as f32, y as f32) + 0.5) * self.cell_size
+    }
+
+    /// Area of a cell in world space
+    pub fn cell_rect(&self, x: u32, y: u32) -> Rect {
+        Rect::from_pos_size(self.origin + Vec2::new(x as f32, y as f32) * self.cell_size, self.cell_size)
+    }
+
+    /// Check if the level blocks a cell, ignoring dynamic obstacles
+    pub fn is_blocked(&self, x: u32, y: u32) -> bool {
+        self.cell(x as i64, y as i64).is_some_and(|(x, y)| self.blocked[self.index(x, y)])
+    }
+
+    /// Check if a dynamic obstacle covers a cell
+    pub fn is_obstructed(&self, x: u32, y: u32) -> bool {
+        self.cell(x as i64, y as i64).is_some_and(|(x, y)| self.obstacles[self.index(x, y)] > 0)
+    }
+
+    /// Check if agents can walk through a cell; cells off the grid aren't walkable
+    pub fn is_walkable(&self, x: u32, y: u32) -> bool {
+        self.cell(x as i64, y as i64).is_some_and(|(x, y)| {
+            let index = self.index(x, y);
+            !self.blocked[index] && self.obstacles[index] == 0
+        })
+    }
+
+    /// Block or unblock a cell for the level; does nothing off the grid
+    pub fn set_blocked(&mut self, x: u32, y: u32, blocked: bool) {
+        if let Some((x, y)) = self.cell(x as i64, y as i64) {
+            let index = self.index(x, y);
+            if self.blocked[index] != blocked {
+                self.blocked[index] = blocked;
+                self.revision += 1;
+            }
+        }
+    }
+
+    /// Cover the cells overlapping `area` with a dynamic obstacle
+    pub fn add_obstacle(&mut self, area: Rect) {
+        for (x, y) in self.cells_overlapping(area) {
+            let index = self.index(x, y);
+            self.obstacles[index] = self.obstacles[index].saturating_add(1);
+        }
+        self.revision += 1;
+    }
+
+    /// Undo an `add_obstacle` with the same area
+    pub fn remove_obstacle(&mut self, area: Rect) {
+        for (x, y) in self.cells_overlapping(area) {
+            let index = self.index(x, y);
+            self.obstacles[index] = self.obstacles[index].saturating_sub(1);
+        }
+        self.revision += 1;
+    }
+
+    /// Cells on the grid that `area` reaches into
+    pub fn cells_overlapping(&self, area: Rect) -> impl Iterator<Item = (u32, u32)> {
+        let min = ((area.min - self.origin) / self.cell_size + OVERLAP_EPSILON).floor();
+        let max = ((area.max - self.origin) / self.cell_size - OVERLAP_EPSILON).ceil();
+        let (x0, x1) = (min.x.max(0.0) as u32, max.x.clamp(0.0, self.width as f32) as u32);
+        let (y0, y1) = (min.y.max(0.0) as u32, max.y.clamp(0.0, self.height as f32) as u32);
+        (y0..y1).flat_map(move |y| (x0..x1).map(move |x| (x, y)))
+    }
+
+    /// Check if a straight line between two points only crosses walkable cells
+    ///
+    /// A line through the corner where two cells meet needs both of the
+    /// cells beside the corner to be walkable, like a diagonal step.
+    pub fn line_of_sight(&self, a: Vec2, b: Vec2) -> bool {
+        let from = (a - self.origin) / self.cell_size;
+        let to = (b - self.origin) / self.cell_size;
+        let delta = to - from;
+        let (mut x, mut y) = (from.x.floor() as i64, from.y.floor() as i64);
+        let (end_x, end_y) = (to.x.floor() as i64, to.y.floor() as i64);
+        let (step_x, step_y) = (if delta.x > 0.0 { 1 } else { -1 }, if delta.y > 0.0 { 1 } else { -1 });
+
+        // Fraction of the line between crossings of vertical and horizontal
+        // cell edges, and the fraction at the next crossing of each
+        let t_delta = Vec2::new(1.0 / delta.x.abs(), 1.0 / delta.y.abs());
+        let next_edge = |start: f32, cell: i64, step: i64| if step > 0 { cell as f32 + 1.0 - start } else { start - cell as f32 };
+        let mut t_max = Vec2::new(
+            if delta.x == 0.0 { f32::INFINITY } else { next_edge(from.x, x, step_x) * t_delta.x },
+            if delta.y == 0.0 { f32::INFINITY } else { next_edge(from.y, y, step_y) * t_delta.y },
+        );
+
+        let walkable = |x: i64, y: i64| self.cell(x, y).is_some_and(|(x, y)| self.is_walkable(x, y));
+        for _ in 0..=(end_x - x).abs() + (end_y - y).abs() {
+            if !walkable(x, y) {
+                return false;
+            }
+            if (x, y) == (end_x, end_y) {
+                return true;
+            }
+            if (t_max.x - t_max.y).abs() < 1e-6 {
+                if !walkable(x + step_x, y) || !walkable(x, y + step_y) {
+                    return false;
+                }
+                x += step_x;
+                y += step_y;
+                t_max += t_delta;
+            } else if t_max.x < t_max.y {
+                x += step_x;
+                t_max.x += t_delta.x;
+            } else {
+                y += step_y;
+                t_max.y += t_delta.y;
+            }
+        }
+        walkable(x, y)
+    }
+
+    /// Search node of the cell containing a world position
+    pub fn node_at(&self, point: Vec2) -> Option<usize> {
+        self.cell_at(point).map(|(x, y)| self.index(x, y))
+    }
+
+    /// Cell of a search node
+    pub fn node_cell(&self, node: usize) -> (u32, u32) {
+        (node as u32 % self.width, node as u32 / self.width)
+    }
+
+    /// Turn searched cells into waypoints from `start` to `goal`, skipping
+    /// the cells a straight line can cut past
+    pub fn smooth_path(&self, nodes: &[usize], start: Vec2, goal: Vec2) -> Vec<Vec2> {
+        let mut points = vec![start];
+        if nodes.len() > 2 {
+            points.extend(nodes[1..nodes.len() - 1].iter().map(|&node| {
+                let (x, y) = self.node_cell(node);
+                self.cell_center(x, y)
+            }));
+        }
+        points.push(goal);
+
+        let mut path = Vec::new();
+        let mut anchor = 0;
+        while anchor + 1 < points.len() {
+            let next = (anchor + 2..points.len())
+                .rev()
+                .find(|&next| self.line_of_sight(points[anchor], points[next]))
+                .unwrap_or(anchor + 1);
+            path.push(points[next]);
+            anchor = next;
+        }
+        path
+    }
+
+    /// Find a path between two world positions, as waypoints after `start`
+    /// ending at `goal`
+    ///
+    /// Both points have to be on the grid and the goal has to be walkable;
+    /// the start doesn't, so agents pushed into a wall can still walk out.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let (from, to) = (self.node_at(start)?, self.node_at(goal)?);
+        let (x, y) = self.node_cell(to);
+        if !self.is_walkable(x, y) {
+            return None;
+        }
+        match crate::navigation::Search::new(from, to).run(self) {
+            crate::navigation::SearchStatus::Found(nodes) => Some(self.smooth_path(&nodes, start, goal)),
+            _ => None,
+        }
+    }
+
+    /// Cell at signed coordinates, if it's on the grid
+    fn cell(&self, x: i64, y: i64) -> Option<(u32, u32)> {
+        ((0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y)).then_some((x as u32, y as u32))
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+}
+
+impl SearchSpace for NavGrid {
+    /// Walkable cells around `node`, moving diagonally only when both cells
+    /// beside the diagonal are walkable so paths don't cut wall corners
+    fn neighbours(&self, node: usize, out: &mut Vec<(usize, f32)>) {
+        let (x, y) = self.node_cell(node);
+        let walkable = |dx: i64, dy: i64| {
+            self.cell(x as i64 + dx, y as i64 + dy)
+                .filter(|&(x, y)| self.is_walkable(x, y))
+                .map(|(x, y)| self.index(x, y))
+        };
+        let diagonal = self.cell_size.length();
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            if let Some(next) = walkable(dx, dy) {
+                out.push((next, if dx != 0 { self.cell_size.x } else { self.cell_size.y }));
+            }
+        }
+        for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+            if walkable(dx, 0).is_some() && walkable(0, dy).is_some() {
+                out.extend(walkable(dx, dy).map(|next| (next, diagonal)));
+            }
+        }
+    }
+
+    /// Octile distance: diagonal steps while both axes are left, then straight ones
+    fn heuristic(&self, node: usize, goal: usize) -> f32 {
+        let ((x0, y0), (x1, y1)) = (self.node_cell(node), self.node_cell(goal));
+        let (dx, dy) = (x0.abs_diff(x1) as f32, y0.abs_diff(y1) as f32);
+        let diagonal = dx.min(dy);
+        diagonal * self.cell_size.length() + (dx - diagonal) * self.cell_size.x + (dy - diagonal) * self.cell_size.y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AssetId;
+
+    /// 5x5 grid of unit cells with a wall across x = 2, open at the top
+    fn walled() -> NavGrid {
+        let wall = Rect::new(Vec2::new(2.0, 0.0), Vec2::new(3.0, 4.0));
+        NavGrid::from_colliders(Rect::new(Vec2::ZERO, Vec2::splat(5.0)), Vec2::ONE, [wall])
+    }
+
+    #[test]
+    fn test_grid_paths_go_around_walls() {
+        let grid = walled();
+        assert_eq!((grid.width(), grid.height()), (5, 5));
+        assert!(!grid.is_walkable(2, 3));
+        assert!(grid.is_walkable(2, 4));
+        assert!(grid.is_walkable(1, 0), "touching a collider doesn't block a cell");
+
+        let path = grid.find_path(Vec2::new(0.5, 0.5), Vec2::new(4.5, 0.5)).unwrap();
+        assert_eq!(path.last(), Some(&Vec2::new(4.5, 0.5)));
+        // Smoothing leaves only the corners around the top of the wall
+        assert!(path.len() <= 3, "{:?}", path);
+        let mut from = Vec2::new(0.5, 0.5);
+        for &to in &path {
+            assert!(grid.line_of_sight(from, to), "{:?} to {:?}", from, to);
+            from = to;
+        }
+        assert!(path.iter().any(|point| point.y > 4.0));
+
+        assert!(!grid.line_of_sight(Vec2::new(0.5, 0.5), Vec2::new(4.5, 0.5)));
+        assert_eq!(grid.find_path(Vec2::new(0.5, 0.5), Vec2::new(2.5, 0.5)), None);
+        assert_eq!(grid.find_path(Vec2::new(0.5, 0.5), Vec2::new(9.0, 0.5)), None);
+    }
+
+    #[test]
+    fn test_obstacles_block_and_bump_the_revision() {
+        let mut grid = walled();
+        let gap = Rect::from_center_size(Vec2::new(2.5, 4.5), Vec2::splat(0.8));
+        grid.add_obstacle(gap);
+        assert!(grid.is_obstructed(2, 4));
+        assert_eq!(grid.revision(), 1);
+        assert_eq!(grid.find_path(Vec2::new(0.5, 0.5), Vec2::new(4.5, 0.5)), None);
+
+        grid.remove_obstacle(gap);
+        assert!(grid.is_walkable(2, 4));
+        assert_eq!(grid.revision(), 2);
+        assert!(grid.find_path(Vec2::new(0.5, 0.5), Vec2::new(4.5, 0.5)).is_some());
+    }
+
+    #[test]
+    fn test_grid_from_tilemap_solid_flags() {
+        let mut tileset = TileSet::new(AssetId::new(1), (64, 32), 32, 32);
+        tileset.set_flags(1, crate::ecs::TileFlags::SOLID);
+        let mut tilemap = Tilemap::new(AssetId::new(1), Vec2::splat(32.0)).with_layer("Ground");
+        for x in 0..4 {
+            tilemap.set_tile(0, x, 0, Some(0));
+        }
+        tilemap.set_tile(0, 1, 0, Some(1));
+
+        // Row 0 of the tilemap spans y -32..0, shifted up by the entity
+        let grid = NavGrid::from_tilemap(&tilemap, &tileset, Vec2::new(0.0, 32.0));
+        assert_eq!((grid.width(), grid.height()), (4, 1));
+        assert_eq!(grid.origin(), Vec2::ZERO);
+        assert_eq!(grid.cell_at(Vec2::new(40.0, 16.0)), Some((1, 0)));
+        assert!(!grid.is_walkable(1, 0));
+        assert!(grid.is_walkable(0, 0) && grid.is_walkable(2, 0));
+    }
+}
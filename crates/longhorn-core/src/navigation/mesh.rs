@@ -0,0 +1,373 @@
+use crate::math::Rect;
+use crate::navigation::{NavGrid, Search, SearchSpace, SearchStatus};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How close points and edges have to be to count as touching, in world units
+const MESH_EPSILON: f32 = 1e-3;
+
+/// Error building a `NavMesh`
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum NavMeshError {
+    #[error("Polygon {0} has fewer than 3 vertices")]
+    TooFewVertices(usize),
+    #[error("Polygon {0} isn't convex with counter-clockwise vertices")]
+    NotConvex(usize),
+}
+
+/// Convex walkable area of a `NavMesh`, with counter-clockwise vertices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NavPolygon {
+    pub vertices: Vec<Vec2>,
+}
+
+impl NavPolygon {
+    /// Create a polygon from counter-clockwise vertices
+    pub fn new(vertices: Vec<Vec2>) -> Self {
+        Self { vertices }
+    }
+
+    /// Polygon covering a rectangle
+    pub fn from_rect(rect: Rect) -> Self {
+        Self::new(vec![rect.min, Vec2::new(rect.max.x, rect.min.y), rect.max, Vec2::new(rect.min.x, rect.max.y)])
+    }
+
+    /// Average of the vertices
+    pub fn center(&self) -> Vec2 {
+        self.vertices.iter().sum::<Vec2>() / self.vertices.len().max(1) as f32
+    }
+
+    /// Edges as pairs of vertices, counter-clockwise
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    /// Check if a point is inside the polygon or on its edges
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.edges().all(|(a, b)| (b - a).perp_dot(point - a) >= -MESH_EPSILON)
+    }
+
+    /// Closest point of the polygon to `point`
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        if self.contains(point) {
+            return point;
+        }
+        self.edges()
+            .map(|(a, b)| {
+                let edge = b - a;
+                let t = ((point - a).dot(edge) / edge.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+                a + edge * t
+            })
+            .min_by(|p, q| p.distance_squared(point).total_cmp(&q.distance_squared(point)))
+            .unwrap_or(point)
+    }
+
+    fn is_convex(&self) -> bool {
+        let n = self.vertices.len();
+        (0..n).all(|i| {
+            let (a, b, c) = (self.vertices[i], self.vertices[(i + 1) % n], self.vertices[(i + 2) % n]);
+            (b - a).perp_dot(c - b) >= -MESH_EPSILON
+        })
+    }
+}
+
+/// Stretch of edge two polygons share, which agents cross to get from one
+/// to the other
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavPortal {
+    /// Polygon on the other side
+    pub to: usize,
+    /// End of the portal on the left when walking through it
+    pub left: Vec2,
+    /// End of the portal on the right when walking through it
+    pub right: Vec2,
+}
+
+/// Baked set of convex polygons agents can walk on (`.navmesh` assets)
+///
+/// Polygons are linked wherever an edge of one lies along an edge of
+/// another, so meshes can be drawn by hand or baked from a `NavGrid`
+/// without sharing vertices. Paths are searched over the polygons and
+/// pulled tight through the shared edges.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "NavMeshData", into = "NavMeshData")]
+pub struct NavMesh {
+    polygons: Vec<NavPolygon>,
+    portals: Vec<Vec<NavPortal>>,
+    centers: Vec<Vec2>,
+}
+
+/// What a `NavMesh` saves; the portals are found again on load
+#[derive(Serialize, Deserialize)]
+struct NavMeshData {
+    polygons: Vec<NavPolygon>,
+}
+
+impl TryFrom<NavMeshData> for NavMesh {
+    type Error = NavMeshError;
+
+    fn try_from(data: NavMeshData) -> Result<Self, Self::Error> {
+        NavMesh::new(data.polygons)
+    }
+}
+
+impl From<NavMesh> for NavMeshData {
+    fn from(mesh: NavMesh) -> Self {
+        Self { polygons: mesh.polygons }
+    }
+}
+
+impl NavMesh {
+    /// Build a mesh from convex polygons, linking the ones that share edges
+    pub fn new(polygons: Vec<NavPolygon>) -> Result<Self, NavMeshError> {
+        for (index, polygon) in polygons.iter().enumerate() {
+            if polygon.vertices.len() < 3 {
+                return Err(NavMeshError::TooFewVertices(index));
+            }
+            if !polygon.is_convex() {
+                return Err(NavMeshError::NotConvex(index));
+            }
+        }
+
+        let mut portals = vec![Vec::new(); polygons.len()];
+        for (i, polygon) in polygons.iter().enumerate() {
+            for (a, b) in polygon.edges() {
+                for (j, other) in polygons.iter().enumerate().filter(|(j, _)| *j != i) {
+                    for (c, d) in other.edges() {
+                        if let Some((from, to)) = shared_span(a, b, c, d) {
+                            // Walking out through a counter-clockwise edge, its
+                            // end is on the left
+                            portals[i].push(NavPortal { to: j, left: to, right: from });
+                        }
+                    }
+                }
+            }
+        }
+        let centers = polygons.iter().map(NavPolygon::center).collect();
+        Ok(Self {
+            polygons,
+            portals,
+            centers,
+        })
+    }
+
+    /// Bake the walkable cells of a grid into rectangles
+    ///
+    /// Runs of walkable cells grow right and then up into the largest
+    /// rectangles that fit, so open areas become a few large polygons.
+    /// Dynamic obstacles on the grid are baked in like walls.
+    pub fn from_grid(grid: &NavGrid) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let mut covered = vec![false; width as usize * height as usize];
+        let free = |covered: &[bool], x: u32, y: u32| grid.is_walkable(x, y) && !covered[(y * width + x) as usize];
+
+        let mut polygons = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if !free(&covered, x, y) {
+                    continue;
+                }
+                let right = (x..width).take_while(|&x| free(&covered, x, y)).last().unwrap_or(x);
+                let top = (y..height)
+                    .take_while(|&y| (x..=right).all(|x| free(&covered, x, y)))
+                    .last()
+                    .unwrap_or(y);
+                for cy in y..=top {
+                    for cx in x..=right {
+                        covered[(cy * width + cx) as usize] = true;
+                    }
+                }
+                let rect = grid.cell_rect(x, y).union(&grid.cell_rect(right, top));
+                polygons.push(NavPolygon::from_rect(rect));
+            }
+        }
+        Self::new(polygons).expect("rectangles are convex")
+    }
+
+    /// Polygons of the mesh
+    pub fn polygons(&self) -> &[NavPolygon] {
+        &self.polygons
+    }
+
+    /// Shared edges leading out of a polygon
+    pub fn portals(&self, polygon: usize) -> &[NavPortal] {
+        self.portals.get(polygon).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Polygon containing a point
+    pub fn find_polygon(&self, point: Vec2) -> Option<usize> {
+        self.polygons.iter().position(|polygon| polygon.contains(point))
+    }
+
+    /// Polygon containing a point, or else the one closest to it, with the
+    /// closest point on the mesh
+    pub fn closest_point(&self, point: Vec2) -> Option<(usize, Vec2)> {
+        if let Some(polygon) = self.find_polygon(point) {
+            return Some((polygon, point));
+        }
+        self.polygons
+            .iter()
+            .enumerate()
+            .map(|(index, polygon)| (index, polygon.closest_point(point)))
+            .min_by(|(_, p), (_, q)| p.distance_squared(point).total_cmp(&q.distance_squared(point)))
+    }
+
+    /// Pull a path tight through the portals between searched polygons,
+    /// returning waypoints after `start` ending at `goal`
+    pub fn smooth_path(&self, nodes: &[usize], start: Vec2, goal: Vec2) -> Vec<Vec2> {
+        let mut portals = vec![(start, start)];
+        for pair in nodes.windows(2) {
+            if let Some(portal) = self.portals(pair[0]).iter().find(|portal| portal.to == pair[1]) {
+                portals.push((portal.left, portal.right));
+            }
+        }
+        portals.push((goal, goal));
+        funnel(&portals)
+    }
+
+    /// Find a path between two points, as waypoints after `start` ending at
+    /// `goal`
+    ///
+    /// Points off the mesh are moved to the closest point on it.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let (from, start) = self.closest_point(start)?;
+        let (to, goal) = self.closest_point(goal)?;
+        match Search::new(from, to).run(self) {
+            SearchStatus::Found(nodes) => Some(self.smooth_path(&nodes, start, goal)),
+            _ => None,
+        }
+    }
+}
+
+impl SearchSpace for NavMesh {
+    fn neighbours(&self, node: usize, out: &mut Vec<(usize, f32)>) {
+        out.extend(
+            self.portals(node)
+                .iter()
+                .map(|portal| (portal.to, self.centers[node].distance(self.centers[portal.to]))),
+        );
+    }
+
+    fn heuristic(&self, node: usize, goal: usize) -> f32 {
+        self.centers[node].distance(self.centers[goal])
+    }
+}
+
+/// Part of edge `a`-`b` that edge `c`-`d` runs back along, if any
+fn shared_span(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> Option<(Vec2, Vec2)> {
+    let edge = b - a;
+    let length = edge.length();
+    if length < MESH_EPSILON || edge.dot(d - c) >= 0.0 {
+        return None;
+    }
+    let direction = edge / length;
+    if direction.perp_dot(c - a).abs() > MESH_EPSILON || direction.perp_dot(d - a).abs() > MESH_EPSILON {
+        return None;
+    }
+    let (tc, td) = (direction.dot(c - a), direction.dot(d - a));
+    let (from, to) = (tc.min(td).max(0.0), tc.max(td).min(length));
+    (to - from > MESH_EPSILON).then(|| (a + direction * from, a + direction * to))
+}
+
+/// Shortest path through a corridor of portals (the "simple stupid funnel
+/// algorithm"), where the first and last portals are the start and goal
+fn funnel(portals: &[(Vec2, Vec2)]) -> Vec<Vec2> {
+    let same = |p: Vec2, q: Vec2| p.distance_squared(q) < MESH_EPSILON * MESH_EPSILON;
+    let (start, goal) = (portals[0].0, portals[portals.len() - 1].0);
+    let mut path = Vec::new();
+    let (mut apex, mut left, mut right) = (start, start, start);
+    let (mut left_index, mut right_index) = (0, 0);
+
+    let mut i = 1;
+    while i < portals.len() {
+        let (next_left, next_right) = portals[i];
+
+        // Narrow the right side of the funnel...
+        if (right - apex).perp_dot(next_right - apex) >= 0.0 {
+            if same(apex, right) || (left - apex).perp_dot(next_right - apex) < 0.0 {
+                right = next_right;
+                right_index = i;
+            } else {
+                // ...unless it crosses the left side, which becomes a corner
+                apex = left;
+                path.push(apex);
+                (left, right, right_index) = (apex, apex, left_index);
+                i = left_index + 1;
+                continue;
+            }
+        }
+
+        // Same for the left side
+        if (left - apex).perp_dot(next_left - apex) <= 0.0 {
+            if same(apex, left) || (right - apex).perp_dot(next_left - apex) > 0.0 {
+                left = next_left;
+                left_index = i;
+            } else {
+                apex = right;
+                path.push(apex);
+                (left, right, left_index) = (apex, apex, right_index);
+                i = right_index + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    if path.last().is_none_or(|last| !same(*last, goal)) {
+        path.push(goal);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// U-shaped corridor: down the left, across the bottom, up the right
+    fn corridor() -> NavMesh {
+        NavMesh::new(vec![
+            NavPolygon::from_rect(Rect::new(Vec2::new(0.0, 2.0), Vec2::new(2.0, 10.0))),
+            NavPolygon::from_rect(Rect::new(Vec2::new(0.0, 0.0), Vec2::new(10.0, 2.0))),
+            NavPolygon::from_rect(Rect::new(Vec2::new(8.0, 2.0), Vec2::new(10.0, 10.0))),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_navmesh_paths_bend_at_corners() {
+        let mesh = corridor();
+        assert_eq!(mesh.portals(0).len(), 1);
+        assert_eq!(mesh.portals(1).len(), 2);
+        assert_eq!(mesh.find_polygon(Vec2::new(9.0, 9.0)), Some(2));
+
+        let path = mesh.find_path(Vec2::new(1.0, 9.0), Vec2::new(9.0, 9.0)).unwrap();
+        assert_eq!(path, vec![Vec2::new(2.0, 2.0), Vec2::new(8.0, 2.0), Vec2::new(9.0, 9.0)]);
+
+        // Straight through an open area needs no corners
+        let path = mesh.find_path(Vec2::new(1.0, 1.0), Vec2::new(9.0, 1.5)).unwrap();
+        assert_eq!(path, vec![Vec2::new(9.0, 1.5)]);
+
+        // Points off the mesh snap onto it
+        let path = mesh.find_path(Vec2::new(1.0, 9.0), Vec2::new(5.0, 4.5)).unwrap();
+        assert_eq!(path.last(), Some(&Vec2::new(5.0, 2.0)));
+    }
+
+    #[test]
+    fn test_navmesh_from_grid_and_serde() {
+        let wall = Rect::new(Vec2::new(2.0, 0.0), Vec2::new(3.0, 4.0));
+        let grid = NavGrid::from_colliders(Rect::new(Vec2::ZERO, Vec2::splat(5.0)), Vec2::ONE, [wall]);
+        let mesh = NavMesh::from_grid(&grid);
+        assert_eq!(mesh.polygons().len(), 3);
+        assert!(mesh.find_path(Vec2::new(0.5, 0.5), Vec2::new(4.5, 0.5)).is_some());
+
+        let json = serde_json::to_string(&mesh).unwrap();
+        assert_eq!(serde_json::from_str::<NavMesh>(&json).unwrap(), mesh);
+
+        let bowtie = r#"{"polygons":[{"vertices":[[0,0],[1,1],[1,0],[0,1]]}]}"#;
+        assert!(serde_json::from_str::<NavMesh>(bowtie).is_err());
+        assert_eq!(NavMesh::new(vec![NavPolygon::new(vec![Vec2::ZERO, Vec2::X])]), Err(NavMeshError::TooFewVertices(0)));
+    }
+}
@@ -0,0 +1,9 @@
+pub mod grid;
+pub mod mesh;
+pub mod query;
+pub mod search;
+
+pub use grid::*;
+pub use mesh::*;
+pub use query::*;
+pub use search::*;
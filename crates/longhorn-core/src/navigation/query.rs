@@ -0,0 +1,316 @@
+use crate::ecs::{EntityHandle, NavObstacle};
+use crate::math::{GlobalTransform, Rect, Transform};
+use crate::navigation::{NavGrid, NavMesh, Search, SearchSpace, SearchStatus};
+use crate::world::World;
+use glam::Vec2;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+/// Search nodes `Navigation::step` expands per call by default
+pub const DEFAULT_NAV_BUDGET: usize = 2048;
+
+/// What NavAgents walk on
+#[derive(Debug, Clone, PartialEq)]
+pub enum NavSurface {
+    Grid(NavGrid),
+    Mesh(Arc<NavMesh>),
+}
+
+impl NavSurface {
+    /// Counter bumped by every change to where agents can walk
+    pub fn revision(&self) -> u64 {
+        match self {
+            NavSurface::Grid(grid) => grid.revision(),
+            NavSurface::Mesh(_) => 0,
+        }
+    }
+
+    /// Check if an agent can walk straight from `a` to `b`
+    ///
+    /// Always true on meshes, whose paths only change when the mesh does.
+    pub fn is_clear(&self, a: Vec2, b: Vec2) -> bool {
+        match self {
+            NavSurface::Grid(grid) => grid.line_of_sight(a, b),
+            NavSurface::Mesh(_) => true,
+        }
+    }
+
+    /// Find a path between two points, as waypoints after `start` ending at
+    /// the goal
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        match self {
+            NavSurface::Grid(grid) => grid.find_path(start, goal),
+            NavSurface::Mesh(mesh) => mesh.find_path(start, goal),
+        }
+    }
+
+    /// Start and goal nodes of a search between two points, with the points
+    /// moved onto the surface
+    fn endpoints(&self, start: Vec2, goal: Vec2) -> Option<(Search, Vec2, Vec2)> {
+        match self {
+            NavSurface::Grid(grid) => {
+                let (from, to) = (grid.node_at(start)?, grid.node_at(goal)?);
+                let (x, y) = grid.node_cell(to);
+                grid.is_walkable(x, y).then(|| (Search::new(from, to), start, goal))
+            }
+            NavSurface::Mesh(mesh) => {
+                let (from, start) = mesh.closest_point(start)?;
+                let (to, goal) = mesh.closest_point(goal)?;
+                Some((Search::new(from, to), start, goal))
+            }
+        }
+    }
+
+    fn smooth_path(&self, nodes: &[usize], start: Vec2, goal: Vec2) -> Vec<Vec2> {
+        match self {
+            NavSurface::Grid(grid) => grid.smooth_path(nodes, start, goal),
+            NavSurface::Mesh(mesh) => mesh.smooth_path(nodes, start, goal),
+        }
+    }
+}
+
+impl SearchSpace for NavSurface {
+    fn neighbours(&self, node: usize, out: &mut Vec<(usize, f32)>) {
+        match self {
+            NavSurface::Grid(grid) => grid.neighbours(node, out),
+            NavSurface::Mesh(mesh) => mesh.neighbours(node, out),
+        }
+    }
+
+    fn heuristic(&self, node: usize, goal: usize) -> f32 {
+        match self {
+            NavSurface::Grid(grid) => grid.heuristic(node, goal),
+            NavSurface::Mesh(mesh) => mesh.heuristic(node, goal),
+        }
+    }
+}
+
+/// A path an entity asked for that's still being searched
+#[derive(Debug, Clone)]
+struct PathQuery {
+    entity: EntityHandle,
+    start: Vec2,
+    goal: Vec2,
+    search: Search,
+}
+
+/// The surface NavAgents walk on and the path searches they're waiting for
+///
+/// Searches share a budget of nodes per `step`, taking turns when the
+/// budget runs out, so a crowd asking for long paths in one frame spreads
+/// the work over the next few frames instead.
+#[derive(Debug, Clone)]
+pub struct Navigation {
+    surface: Option<NavSurface>,
+    budget: usize,
+    queries: VecDeque<PathQuery>,
+    /// Area each NavObstacle covers on the grid
+    obstacles: HashMap<EntityHandle, Rect>,
+}
+
+impl Navigation {
+    /// Create navigation without a surface
+    pub fn new() -> Self {
+        Self {
+            surface: None,
+            budget: DEFAULT_NAV_BUDGET,
+            queries: VecDeque::new(),
+            obstacles: HashMap::new(),
+        }
+    }
+
+    /// Surface agents walk on
+    pub fn surface(&self) -> Option<&NavSurface> {
+        self.surface.as_ref()
+    }
+
+    /// Replace the surface, dropping pending searches
+    ///
+    /// Obstacles are added to a new grid on the next `update_obstacles`.
+    pub fn set_surface(&mut self, surface: Option<NavSurface>) {
+        self.surface = surface;
+        self.queries.clear();
+        self.obstacles.clear();
+    }
+
+    /// Search nodes expanded per `step`
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Set the search nodes expanded per `step` (at least one)
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget.max(1);
+    }
+
+    /// Find a path right away, ignoring the budget
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        self.surface.as_ref()?.find_path(start, goal)
+    }
+
+    /// Queue a path search for an entity, replacing any it's waiting for
+    ///
+    /// Returns false if the points aren't on the surface, in which case
+    /// there's no path.
+    pub fn request(&mut self, entity: EntityHandle, start: Vec2, goal: Vec2) -> bool {
+        self.cancel(entity);
+        let Some((search, start, goal)) = self.surface.as_ref().and_then(|surface| surface.endpoints(start, goal)) else {
+            return false;
+        };
+        self.queries.push_back(PathQuery {
+            entity,
+            start,
+            goal,
+            search,
+        });
+        true
+    }
+
+    /// Drop an entity's pending search
+    pub fn cancel(&mut self, entity: EntityHandle) {
+        self.queries.retain(|query| query.entity != entity);
+    }
+
+    /// Check if an entity is waiting for a path
+    pub fn is_pending(&self, entity: EntityHandle) -> bool {
+        self.queries.iter().any(|query| query.entity == entity)
+    }
+
+    /// Number of searches waiting for their turn
+    pub fn pending(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Spend the budget on pending searches, oldest first
+    ///
+    /// Returns the finished searches: each entity's waypoints, or `None` if
+    /// there's no path. A search that runs out of budget goes to the back
+    /// of the queue.
+    pub fn step(&mut self) -> Vec<(EntityHandle, Option<Vec<Vec2>>)> {
+        let mut finished = Vec::new();
+        let Some(surface) = &self.surface else {
+            return finished;
+        };
+        let mut budget = self.budget;
+        let mut turns = self.queries.len();
+        while budget > 0 && turns > 0 {
+            let Some(mut query) = self.queries.pop_front() else {
+                break;
+            };
+            turns -= 1;
+            let (status, expanded) = query.search.step(surface, budget);
+            let status = status.clone();
+            budget -= expanded.min(budget);
+            match status {
+                SearchStatus::Searching => self.queries.push_back(query),
+                SearchStatus::Found(nodes) => {
+                    finished.push((query.entity, Some(surface.smooth_path(&nodes, query.start, query.goal))));
+                }
+                SearchStatus::NotFound => finished.push((query.entity, None)),
+            }
+        }
+        finished
+    }
+
+    /// Add NavObstacle entities to the grid where they are now, moving the
+    /// ones that moved and removing the ones that are gone
+    ///
+    /// Obstacles are placed at their GlobalTransform, or their Transform if
+    /// transforms haven't been propagated yet. Meshes ignore obstacles.
+    pub fn update_obstacles(&mut self, world: &World) {
+        let Some(NavSurface::Grid(grid)) = &mut self.surface else {
+            return;
+        };
+        let mut current = HashMap::new();
+        for (entity, (obstacle, global, local)) in world
+            .inner()
+            .query::<(&NavObstacle, Option<&GlobalTransform>, Option<&Transform>)>()
+            .iter()
+        {
+            let Some(position) = global.map(|global| global.position).or(local.map(|local| local.position)) else {
+                continue;
+            };
+            current.insert(EntityHandle::new(entity), Rect::from_center_size(position, obstacle.size));
+        }
+
+        for (entity, area) in &self.obstacles {
+            if current.get(entity) != Some(area) {
+                grid.remove_obstacle(*area);
+            }
+        }
+        for (entity, area) in &current {
+            if self.obstacles.get(entity) != Some(area) {
+                grid.add_obstacle(*area);
+            }
+        }
+        self.obstacles = current;
+    }
+}
+
+impl Default for Navigation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_searches_share_the_budget() {
+        let grid = NavGrid::new(Vec2::ZERO, Vec2::ONE, 64, 64);
+        let mut navigation = Navigation::new();
+        navigation.set_surface(Some(NavSurface::Grid(grid)));
+        navigation.set_budget(20);
+
+        let mut world = World::new();
+        let (a, b) = (world.spawn().build(), world.spawn().build());
+        assert!(navigation.request(a, Vec2::new(0.5, 0.5), Vec2::new(60.5, 0.5)));
+        assert!(navigation.request(b, Vec2::new(0.5, 0.5), Vec2::new(1.5, 0.5)));
+        assert!(!navigation.request(b, Vec2::new(0.5, 0.5), Vec2::new(-1.0, 0.5)));
+        assert!(navigation.request(b, Vec2::new(0.5, 0.5), Vec2::new(1.5, 0.5)));
+
+        // The long search uses up the first step, then lets the short one go
+        assert!(navigation.step().is_empty());
+        let finished = navigation.step();
+        assert_eq!(finished, vec![(b, Some(vec![Vec2::new(1.5, 0.5)]))]);
+        assert!(navigation.is_pending(a));
+
+        let mut steps = 0;
+        while navigation.pending() > 0 {
+            let finished = navigation.step();
+            steps += 1;
+            if let Some((entity, path)) = finished.first() {
+                assert_eq!(*entity, a);
+                assert_eq!(path.as_deref(), Some(&[Vec2::new(60.5, 0.5)][..]));
+            }
+        }
+        assert!(steps > 0);
+    }
+
+    #[test]
+    fn test_obstacles_follow_their_entities() {
+        let mut navigation = Navigation::new();
+        navigation.set_surface(Some(NavSurface::Grid(NavGrid::new(Vec2::ZERO, Vec2::ONE, 4, 4))));
+        let mut world = World::new();
+        let crate_entity = world
+            .spawn()
+            .with(Transform::from_position(Vec2::new(1.5, 1.5)))
+            .with(NavObstacle::new(Vec2::splat(0.8)))
+            .build();
+        let walkable = |navigation: &Navigation, x, y| match navigation.surface() {
+            Some(NavSurface::Grid(grid)) => grid.is_walkable(x, y),
+            _ => unreachable!(),
+        };
+
+        navigation.update_obstacles(&world);
+        assert!(!walkable(&navigation, 1, 1));
+        world.get_mut::<Transform>(crate_entity).unwrap().position = Vec2::new(2.5, 1.5);
+        navigation.update_obstacles(&world);
+        assert!(walkable(&navigation, 1, 1) && !walkable(&navigation, 2, 1));
+        world.despawn(crate_entity).unwrap();
+        navigation.update_obstacles(&world);
+        assert!(walkable(&navigation, 2, 1));
+    }
+}
@@ -0,0 +1,194 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// A graph A* can search, with nodes numbered from zero
+pub trait SearchSpace {
+    /// Push the nodes reachable from `node` and the cost of moving to each
+    fn neighbours(&self, node: usize, out: &mut Vec<(usize, f32)>);
+
+    /// Estimated cost from `node` to `goal`; never more than the real cost
+    fn heuristic(&self, node: usize, goal: usize) -> f32;
+}
+
+/// Where a search stands after a `Search::step`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchStatus {
+    /// Ran out of budget; call `step` again to continue
+    Searching,
+    /// Nodes from the start to the goal, both included
+    Found(Vec<usize>),
+    /// Every node reachable from the start was visited without reaching the goal
+    NotFound,
+}
+
+/// Open node, ordered so the heap pops the lowest estimated total cost first
+#[derive(Debug, Clone, Copy)]
+struct Open {
+    node: usize,
+    cost: f32,
+    estimate: f32,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Prefer nodes further along on ties, so searches across open areas
+        // head straight for the goal
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| self.cost.total_cmp(&other.cost))
+    }
+}
+
+/// An A* search that can be spread over several frames
+///
+/// Each `step` expands at most `budget` nodes, so many agents can request
+/// paths in one frame without stalling it.
+#[derive(Debug, Clone)]
+pub struct Search {
+    start: usize,
+    goal: usize,
+    open: BinaryHeap<Open>,
+    /// Best known cost to each visited node and the node it was reached from
+    visited: HashMap<usize, (f32, usize)>,
+    status: SearchStatus,
+}
+
+impl Search {
+    /// Start a search from `start` to `goal`
+    pub fn new(start: usize, goal: usize) -> Self {
+        let mut open = BinaryHeap::new();
+        open.push(Open {
+            node: start,
+            cost: 0.0,
+            estimate: 0.0,
+        });
+        Self {
+            start,
+            goal,
+            open,
+            visited: HashMap::from([(start, (0.0, start))]),
+            status: SearchStatus::Searching,
+        }
+    }
+
+    /// Node the search started from
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Node the search is looking for
+    pub fn goal(&self) -> usize {
+        self.goal
+    }
+
+    /// Current status, without searching further
+    pub fn status(&self) -> &SearchStatus {
+        &self.status
+    }
+
+    /// Expand up to `budget` nodes, returning the status and the number of
+    /// nodes expanded
+    pub fn step(&mut self, space: &impl SearchSpace, budget: usize) -> (&SearchStatus, usize) {
+        let mut expanded = 0;
+        let mut neighbours = Vec::new();
+        while self.status == SearchStatus::Searching && expanded < budget {
+            let Some(Open { node, cost, .. }) = self.open.pop() else {
+                self.status = SearchStatus::NotFound;
+                break;
+            };
+            if node == self.goal {
+                self.status = SearchStatus::Found(self.path());
+                break;
+            }
+            // Skip entries superseded by a cheaper route
+            if self.visited.get(&node).is_some_and(|(best, _)| cost > *best) {
+                continue;
+            }
+            expanded += 1;
+
+            neighbours.clear();
+            space.neighbours(node, &mut neighbours);
+            for &(next, step) in &neighbours {
+                let cost = cost + step;
+                if self.visited.get(&next).is_some_and(|(best, _)| cost >= *best) {
+                    continue;
+                }
+                self.visited.insert(next, (cost, node));
+                self.open.push(Open {
+                    node: next,
+                    cost,
+                    estimate: cost + space.heuristic(next, self.goal),
+                });
+            }
+        }
+        (&self.status, expanded)
+    }
+
+    /// Run the search to the end
+    pub fn run(mut self, space: &impl SearchSpace) -> SearchStatus {
+        self.step(space, usize::MAX);
+        self.status
+    }
+
+    /// Walk back from the goal to the start
+    fn path(&self) -> Vec<usize> {
+        let mut path = vec![self.goal];
+        let mut node = self.goal;
+        while node != self.start {
+            node = self.visited[&node].1;
+            path.push(node);
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Nodes on a line, each linked to the next two
+    struct Line(usize);
+
+    impl SearchSpace for Line {
+        fn neighbours(&self, node: usize, out: &mut Vec<(usize, f32)>) {
+            out.extend([node + 1, node + 2].into_iter().filter(|next| *next < self.0).map(|next| (next, (next - node) as f32)));
+        }
+
+        fn heuristic(&self, node: usize, goal: usize) -> f32 {
+            goal.abs_diff(node) as f32
+        }
+    }
+
+    #[test]
+    fn test_search_in_steps() {
+        let mut search = Search::new(0, 9);
+        let (status, expanded) = search.step(&Line(10), 2);
+        assert_eq!((status, expanded), (&SearchStatus::Searching, 2));
+        let (status, _) = search.step(&Line(10), usize::MAX);
+        let SearchStatus::Found(path) = status else {
+            panic!("expected a path, got {:?}", status);
+        };
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(path.last(), Some(&9));
+        assert!(path.windows(2).all(|pair| pair[1] > pair[0]));
+
+        assert_eq!(Search::new(0, 12).run(&Line(10)), SearchStatus::NotFound);
+        assert_eq!(Search::new(3, 3).run(&Line(10)), SearchStatus::Found(vec![3]));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::scene::{binary_scene_path, Scene, SceneFormat};
+use crate::types::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Check if a file name is an authoring (text) scene
+pub fn is_text_scene(filename: &str) -> bool {
+    filename.ends_with(".scn.ron") || filename.ends_with(".scn.json")
+}
+
+/// Convert every `.scn.ron` / `.scn.json` scene under `project_root` to a
+/// binary `.scn.bin` file under `output_root`, keeping relative paths
+///
+/// Used during export so shipping builds load compact binary scenes.
+/// Passing the same directory for both bakes the scenes in place.
+///
+/// # Returns
+/// The paths of the written binary scenes
+pub fn bake_scenes(project_root: impl AsRef<Path>, output_root: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let project_root = project_root.as_ref();
+    let output_root = output_root.as_ref();
+
+    let mut scene_paths = Vec::new();
+    collect_text_scenes(project_root, &mut scene_paths)?;
+    scene_paths.sort();
+
+    let mut baked = Vec::new();
+    for path in scene_paths {
+        let relative = path.strip_prefix(project_root).unwrap_or(&path);
+        let relative = binary_scene_path(&relative.to_string_lossy());
+        let out_path = output_root.join(relative);
+
+        let scene = Scene::load(&path)?;
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&out_path, scene.to_bytes(SceneFormat::Binary)?)?;
+        baked.push(out_path);
+    }
+
+    Ok(baked)
+}
+
+/// Recursively collect authoring scene files
+fn collect_text_scenes(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_text_scenes(&path, out)?;
+        } else if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(is_text_scene)
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bake_scenes() {
+        let root = std::env::temp_dir().join(format!(
+            "longhorn_bake_test_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let out = root.join("build");
+
+        Scene::new("Main").save(root.join("scenes/main.scn.ron")).unwrap();
+        Scene::new("Menu").save(root.join("menu.scn.json")).unwrap();
+        fs::write(root.join("game.json"), "{}").unwrap();
+
+        let baked = bake_scenes(&root, &out).unwrap();
+        assert_eq!(baked.len(), 2);
+        assert!(out.join("scenes/main.scn.bin").exists());
+        assert!(out.join("menu.scn.bin").exists());
+
+        let loaded = Scene::load(out.join("scenes/main.scn.bin")).unwrap();
+        assert_eq!(loaded.name, "Main");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
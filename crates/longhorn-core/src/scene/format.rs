@@ -0,0 +1,235 @@
+use crate::scene::Scene;
+use crate::types::{LonghornError, Result};
+use std::path::Path;
+
+/// Current scene schema version, shared by all scene formats
+pub const SCENE_FORMAT_VERSION: u32 = 1;
+
+/// Magic bytes at the start of a binary scene file
+pub const BINARY_SCENE_MAGIC: [u8; 4] = *b"LHSC";
+
+/// File extension used for baked binary scenes
+pub const BINARY_SCENE_EXTENSION: &str = ".scn.bin";
+
+/// On-disk encoding of a scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneFormat {
+    /// Pretty-printed JSON (default for unknown extensions)
+    Json,
+    /// Pretty-printed RON (`.ron`, `.scn.ron`)
+    Ron,
+    /// Compact binary for shipping builds (`.scn.bin`)
+    ///
+    /// Layout: `LHSC` magic, little-endian `u32` schema version, then the
+    /// scene encoded as MessagePack with named fields (same schema as JSON).
+    Binary,
+}
+
+impl SceneFormat {
+    /// Determine the format from a file path's extension
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let filename = path
+            .as_ref()
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("");
+
+        if filename.ends_with(BINARY_SCENE_EXTENSION) {
+            SceneFormat::Binary
+        } else if filename.ends_with(".scn.ron") || filename.ends_with(".ron") {
+            SceneFormat::Ron
+        } else {
+            SceneFormat::Json
+        }
+    }
+}
+
+/// Path of the baked binary scene for an authoring scene path
+///
+/// `scenes/main.scn.ron` becomes `scenes/main.scn.bin`.
+pub fn binary_scene_path(path: &str) -> String {
+    let stem = [".scn.ron", ".scn.json", BINARY_SCENE_EXTENSION, ".ron", ".json"]
+        .iter()
+        .find_map(|ext| path.strip_suffix(ext))
+        .unwrap_or(path);
+    format!("{}{}", stem, BINARY_SCENE_EXTENSION)
+}
+
+impl Scene {
+    /// Encode the scene in the given format
+    pub fn to_bytes(&self, format: SceneFormat) -> Result<Vec<u8>> {
+        match format {
+            SceneFormat::Json => serde_json::to_vec_pretty(self)
+                .map_err(|e| LonghornError::Serialization(e.to_string())),
+            SceneFormat::Ron => ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                .map(String::into_bytes)
+                .map_err(|e| LonghornError::Serialization(e.to_string())),
+            SceneFormat::Binary => {
+                let mut bytes = Vec::with_capacity(64);
+                bytes.extend_from_slice(&BINARY_SCENE_MAGIC);
+                bytes.extend_from_slice(&self.version.to_le_bytes());
+                rmp_serde::encode::write_named(&mut bytes, self)
+                    .map_err(|e| LonghornError::Serialization(e.to_string()))?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Decode a scene from bytes in the given format
+    pub fn from_bytes(bytes: &[u8], format: SceneFormat) -> Result<Self> {
+        let scene: Scene = match format {
+            SceneFormat::Json => serde_json::from_slice(bytes)
+                .map_err(|e| LonghornError::Serialization(e.to_string()))?,
+            SceneFormat::Ron => {
+                let contents = std::str::from_utf8(bytes)
+                    .map_err(|e| LonghornError::Serialization(e.to_string()))?;
+                ron::from_str(contents).map_err(|e| LonghornError::Serialization(e.to_string()))?
+            }
+            SceneFormat::Binary => {
+                if bytes.len() < 8 || bytes[..4] != BINARY_SCENE_MAGIC {
+                    return Err(LonghornError::Serialization(
+                        "Not a binary scene (missing LHSC header)".to_string(),
+                    ));
+                }
+                let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+                check_version(version)?;
+                rmp_serde::from_slice(&bytes[8..])
+                    .map_err(|e| LonghornError::Serialization(e.to_string()))?
+            }
+        };
+
+        check_version(scene.version)?;
+        Ok(scene)
+    }
+}
+
+/// Reject scenes written by a newer engine
+fn check_version(version: u32) -> Result<()> {
+    if version > SCENE_FORMAT_VERSION {
+        return Err(LonghornError::Serialization(format!(
+            "Scene format version {} is newer than supported version {}",
+            version, SCENE_FORMAT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{SerializedComponents, SerializedEntity, SerializedTransform};
+    use crate::ecs::{
+        EntityGuid, EntityRef, LightOccluder2D, MaterialParams, MaterialValue, OccluderShape, Script, ScriptValue, Shape,
+        ShapeFill,
+    };
+    use glam::Vec2;
+
+    fn sample_scene() -> Scene {
+        let mut script = Script::new("Player.ts");
+        script.set_property("speed", ScriptValue::Number(5.0));
+        script.set_property("spawn", ScriptValue::Vec2 { x: 1.0, y: 2.0 });
+        script.set_property("target", ScriptValue::Entity { entity: EntityRef::new(EntityGuid(7)) });
+        script.set_property("title", ScriptValue::String("Hero".to_string()));
+        script.set_property("invincible", ScriptValue::Boolean(true));
+
+        let mut scene = Scene::new("Binary Test");
+        scene.add_entity(SerializedEntity {
+            id: 42,
+            components: SerializedComponents {
+                name: Some("Player".to_string()),
+                transform: Some(SerializedTransform {
+                    position: [10.0, 20.0],
+                    rotation: 0.5,
+                    scale: [1.0, 1.0],
+                }),
+                script: Some(script),
+                enabled: Some(true),
+                material_params: Some(
+                    MaterialParams::new()
+                        .with("strength", MaterialValue::Float(0.5))
+                        .with("offset", MaterialValue::Vec2([1.0, 2.0]))
+                        .with("tint", MaterialValue::Vec4([1.0, 0.5, 0.25, 1.0])),
+                ),
+                light_occluder: Some(LightOccluder2D::new(OccluderShape::Polygon {
+                    points: vec![Vec2::ZERO, Vec2::X, Vec2::Y],
+                })),
+                shape: Some(Shape::rounded_rectangle(Vec2::new(4.0, 2.0), 0.5).with_fill(ShapeFill::LinearGradient {
+                    start: [1.0, 0.0, 0.0, 1.0],
+                    end: [0.0, 0.0, 1.0, 1.0],
+                    angle: 0.25,
+                })),
+                ..Default::default()
+            },
+            children: Vec::new(),
+        });
+        scene
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(SceneFormat::from_path("main.scn.bin"), SceneFormat::Binary);
+        assert_eq!(SceneFormat::from_path("main.scn.ron"), SceneFormat::Ron);
+        assert_eq!(SceneFormat::from_path("main.json"), SceneFormat::Json);
+        assert_eq!(SceneFormat::from_path("main"), SceneFormat::Json);
+    }
+
+    #[test]
+    fn test_binary_scene_path() {
+        assert_eq!(binary_scene_path("scenes/main.scn.ron"), "scenes/main.scn.bin");
+        assert_eq!(binary_scene_path("scenes/main.scn.json"), "scenes/main.scn.bin");
+        assert_eq!(binary_scene_path("scenes/main.scn.bin"), "scenes/main.scn.bin");
+    }
+
+    #[test]
+    fn test_binary_roundtrip_matches_json() {
+        let scene = sample_scene();
+
+        let binary = scene.to_bytes(SceneFormat::Binary).unwrap();
+        let json = scene.to_bytes(SceneFormat::Json).unwrap();
+        assert_eq!(&binary[..4], &BINARY_SCENE_MAGIC);
+        assert!(binary.len() < json.len());
+
+        let from_binary = Scene::from_bytes(&binary, SceneFormat::Binary).unwrap();
+        let from_json = Scene::from_bytes(&json, SceneFormat::Json).unwrap();
+        assert_eq!(
+            serde_json::to_value(&from_binary).unwrap(),
+            serde_json::to_value(&from_json).unwrap()
+        );
+        assert_eq!(from_binary.version, SCENE_FORMAT_VERSION);
+
+        // Tagged and untagged enums come back as the same variants
+        let expected = &scene.entities[0].components;
+        let components = &from_binary.entities[0].components;
+        assert_eq!(
+            components.script.as_ref().map(|s| &s.properties),
+            expected.script.as_ref().map(|s| &s.properties)
+        );
+        assert_eq!(components.material_params, expected.material_params);
+        assert_eq!(components.light_occluder, expected.light_occluder);
+        assert_eq!(components.shape, expected.shape);
+    }
+
+    #[test]
+    fn test_binary_rejects_bad_header() {
+        let result = Scene::from_bytes(b"{\"name\": \"x\"}", SceneFormat::Binary);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let mut scene = sample_scene();
+        scene.version = SCENE_FORMAT_VERSION + 1;
+
+        for format in [SceneFormat::Json, SceneFormat::Binary] {
+            let bytes = scene.to_bytes(format).unwrap();
+            assert!(Scene::from_bytes(&bytes, format).is_err());
+        }
+    }
+
+    #[test]
+    fn test_legacy_json_without_version() {
+        let json = r#"{"name": "Old", "entities": []}"#;
+        let scene = Scene::from_bytes(json.as_bytes(), SceneFormat::Json).unwrap();
+        assert_eq!(scene.version, SCENE_FORMAT_VERSION);
+    }
+}
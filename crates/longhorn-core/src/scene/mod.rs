@@ -1,3 +1,7 @@
+pub mod bake;
+pub mod format;
 pub mod scene;
 
+pub use bake::*;
+pub use format::*;
 pub use scene::*;
@@ -1,8 +1,14 @@
-use crate::ecs::{Enabled, EntityHandle, Name, Script, Sprite, World};
+use crate::ecs::{
+    AnimationPlayer, Camera, Canvas, Enabled, EntityBuilder, EntityGuid, EntityHandle, GlobalLight2D, LightOccluder2D, MainCamera,
+    MapEntities, MaterialParams, Name, NavAgent, NavObstacle, NavRegion, ParameterValue, ParticleEffect, ParticleEmitter, PointLight2D, RectTransform, Script, Shape,
+    SpotLight2D, Sprite, SpriteDrawMode, StateMachine, Text, TextAlign, TileChunk, TileLayer, Tilemap, UiButton, UiImage, UiLabel,
+    UiLayout, UiLayoutItem, UiPanel, UiScrollView, UiSlider, World,
+};
 use crate::math::Transform;
+use crate::scene::{SceneFormat, SCENE_FORMAT_VERSION};
 use crate::types::{AssetId, LonghornError, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
@@ -22,11 +28,108 @@ pub trait AssetLoader {
 
     /// Load a texture by ID and return its asset ID (for fallback when path loading fails)
     fn load_texture_by_id(&mut self, id: AssetId) -> std::io::Result<AssetId>;
+
+    /// Load a font by path and return its asset ID
+    fn load_font(&mut self, path: &str) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Font loading not supported: {}", path),
+        ))
+    }
+
+    /// Load a font by ID and return its asset ID (for fallback when path loading fails)
+    fn load_font_by_id(&mut self, id: AssetId) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Font loading not supported: {:?}", id),
+        ))
+    }
+
+    /// Load a tile set by path and return its asset ID
+    fn load_tileset(&mut self, path: &str) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Tile set loading not supported: {}", path),
+        ))
+    }
+
+    /// Load a tile set by ID and return its asset ID (for fallback when path loading fails)
+    fn load_tileset_by_id(&mut self, id: AssetId) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Tile set loading not supported: {:?}", id),
+        ))
+    }
+
+    /// Load a material by path and return its asset ID
+    fn load_material(&mut self, path: &str) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Material loading not supported: {}", path),
+        ))
+    }
+
+    /// Load a material by ID and return its asset ID (for fallback when path loading fails)
+    fn load_material_by_id(&mut self, id: AssetId) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Material loading not supported: {:?}", id),
+        ))
+    }
+
+    /// Load an animation clip by path and return its asset ID
+    fn load_animation_clip(&mut self, path: &str) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Animation clip loading not supported: {}", path),
+        ))
+    }
+
+    /// Load an animation clip by ID and return its asset ID (for fallback when path loading fails)
+    fn load_animation_clip_by_id(&mut self, id: AssetId) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Animation clip loading not supported: {:?}", id),
+        ))
+    }
+
+    /// Load a state chart by path and return its asset ID
+    fn load_state_chart(&mut self, path: &str) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("State chart loading not supported: {}", path),
+        ))
+    }
+
+    /// Load a state chart by ID and return its asset ID (for fallback when path loading fails)
+    fn load_state_chart_by_id(&mut self, id: AssetId) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("State chart loading not supported: {:?}", id),
+        ))
+    }
+
+    /// Load a navmesh by path and return its asset ID
+    fn load_navmesh(&mut self, path: &str) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Navmesh loading not supported: {}", path),
+        ))
+    }
+
+    /// Load a navmesh by ID and return its asset ID (for fallback when path loading fails)
+    fn load_navmesh_by_id(&mut self, id: AssetId) -> std::io::Result<AssetId> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Navmesh loading not supported: {:?}", id),
+        ))
+    }
 }
 
 /// Serialized entity data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializedEntity {
+    /// Persistent entity GUID (see `EntityGuid`)
     pub id: u64,
     pub components: SerializedComponents,
     #[serde(default)]
@@ -35,7 +138,7 @@ pub struct SerializedEntity {
 }
 
 /// Container for all component data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SerializedComponents {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "Name")]
@@ -56,6 +159,193 @@ pub struct SerializedComponents {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "Enabled")]
     pub enabled: Option<bool>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "Camera")]
+    pub camera: Option<Camera>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "MainCamera")]
+    pub main_camera: Option<bool>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "Text")]
+    pub text: Option<SerializedText>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "Tilemap")]
+    pub tilemap: Option<SerializedTilemap>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ParticleEmitter")]
+    pub particle_emitter: Option<SerializedParticleEmitter>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "AnimationPlayer")]
+    pub animation_player: Option<SerializedAnimationPlayer>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "StateMachine")]
+    pub state_machine: Option<SerializedStateMachine>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "NavAgent")]
+    pub nav_agent: Option<NavAgent>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "NavObstacle")]
+    pub nav_obstacle: Option<NavObstacle>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "NavRegion")]
+    pub nav_region: Option<SerializedNavRegion>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "MaterialParams")]
+    pub material_params: Option<MaterialParams>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "PointLight2D")]
+    pub point_light: Option<PointLight2D>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "SpotLight2D")]
+    pub spot_light: Option<SpotLight2D>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "GlobalLight2D")]
+    pub global_light: Option<GlobalLight2D>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "LightOccluder2D")]
+    pub light_occluder: Option<LightOccluder2D>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "Shape")]
+    pub shape: Option<Shape>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "Ui")]
+    pub ui: Option<SerializedUi>,
+}
+
+/// UI components of an entity
+///
+/// Kept together under one key since UI elements usually carry several.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SerializedUi {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canvas: Option<Canvas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rect_transform: Option<RectTransform>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<UiLayout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_item: Option<UiLayoutItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panel: Option<UiPanel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<UiImage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<UiLabel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<UiButton>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slider: Option<UiSlider>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_view: Option<UiScrollView>,
+}
+
+impl SerializedUi {
+    /// Read the UI components of an entity, or `None` if it has none
+    fn from_entity(world: &World, entity_id: hecs::Entity) -> Option<Self> {
+        fn get<T: hecs::Component + Clone>(world: &World, entity_id: hecs::Entity) -> Option<T> {
+            world.inner().get::<&T>(entity_id).ok().map(|component| (*component).clone())
+        }
+
+        let ui = Self {
+            canvas: get(world, entity_id),
+            rect_transform: get(world, entity_id),
+            layout: get(world, entity_id),
+            layout_item: get(world, entity_id),
+            panel: get(world, entity_id),
+            image: get(world, entity_id),
+            label: get(world, entity_id),
+            button: get(world, entity_id),
+            slider: get(world, entity_id),
+            scroll_view: get(world, entity_id),
+        };
+        (ui != Self::default()).then_some(ui)
+    }
+
+    /// Add the components to an entity being spawned
+    fn build<'w>(&self, mut builder: EntityBuilder<'w>) -> EntityBuilder<'w> {
+        if let Some(canvas) = self.canvas {
+            builder = builder.with(canvas);
+        }
+        if let Some(rect) = self.rect_transform {
+            builder = builder.with(rect);
+        }
+        if let Some(layout) = self.layout {
+            builder = builder.with(layout);
+        }
+        if let Some(item) = self.layout_item {
+            builder = builder.with(item);
+        }
+        if let Some(panel) = self.panel {
+            builder = builder.with(panel);
+        }
+        if let Some(image) = self.image {
+            builder = builder.with(image);
+        }
+        if let Some(ref label) = self.label {
+            builder = builder.with(label.clone());
+        }
+        if let Some(button) = self.button {
+            builder = builder.with(button);
+        }
+        if let Some(slider) = self.slider {
+            builder = builder.with(slider);
+        }
+        if let Some(scroll_view) = self.scroll_view {
+            builder = builder.with(scroll_view);
+        }
+        builder
+    }
+
+    /// Overwrite an entity's UI components with a snapshot's, removing those
+    /// the snapshot doesn't have
+    fn restore(ui: Option<&Self>, world: &mut World, entity_id: hecs::Entity) {
+        let ui = ui.cloned().unwrap_or_default();
+        restore_component(world, entity_id, &ui.canvas);
+        restore_component(world, entity_id, &ui.rect_transform);
+        restore_component(world, entity_id, &ui.layout);
+        restore_component(world, entity_id, &ui.layout_item);
+        restore_component(world, entity_id, &ui.panel);
+        restore_component(world, entity_id, &ui.image);
+        restore_component(world, entity_id, &ui.label);
+        restore_component(world, entity_id, &ui.button);
+        restore_component(world, entity_id, &ui.slider);
+        restore_component(world, entity_id, &ui.scroll_view);
+    }
 }
 
 /// Serialized transform component
@@ -96,19 +386,399 @@ pub struct SerializedSprite {
     pub color: [f32; 4],
     pub flip_x: bool,
     pub flip_y: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material_path: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub material_id: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normal_map_path: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normal_map_id: Option<u64>,
+    /// `None` for the default `Simple` mode
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draw_mode: Option<SpriteDrawMode>,
+}
+
+impl SerializedSprite {
+    /// Serialize a sprite, looking up its texture and material paths in the registry
+    fn from_sprite<R: AssetRegistry>(sprite: &Sprite, registry: &R) -> Self {
+        let has_material = sprite.material.0 != 0;
+        let has_normal_map = sprite.normal_map.0 != 0;
+        Self {
+            texture_path: registry.get_path(sprite.texture).unwrap_or("unknown").to_string(),
+            texture_id: sprite.texture.0,
+            size: [sprite.size.x, sprite.size.y],
+            color: sprite.color,
+            flip_x: sprite.flip_x,
+            flip_y: sprite.flip_y,
+            material_path: has_material
+                .then(|| registry.get_path(sprite.material).unwrap_or("unknown").to_string()),
+            material_id: has_material.then_some(sprite.material.0),
+            normal_map_path: has_normal_map
+                .then(|| registry.get_path(sprite.normal_map).unwrap_or("unknown").to_string()),
+            normal_map_id: has_normal_map.then_some(sprite.normal_map.0),
+            draw_mode: (!sprite.draw_mode.is_simple()).then_some(sprite.draw_mode),
+        }
+    }
+
+    /// Rebuild the sprite around an already loaded texture, loading its
+    /// material and normal map
+    ///
+    /// Like text, a sprite whose material or normal map can't be loaded keeps
+    /// the serialized ID.
+    fn to_sprite<L: AssetLoader>(&self, texture: AssetId, asset_loader: &mut L) -> Sprite {
+        let material = match (&self.material_path, self.material_id) {
+            (Some(path), id) => {
+                let id = AssetId::new(id.unwrap_or(0));
+                asset_loader
+                    .load_material(path)
+                    .or_else(|_| asset_loader.load_material_by_id(id))
+                    .unwrap_or(id)
+            }
+            (None, Some(id)) => asset_loader
+                .load_material_by_id(AssetId::new(id))
+                .unwrap_or(AssetId::new(id)),
+            (None, None) => AssetId::new(0),
+        };
+        let normal_map = match (&self.normal_map_path, self.normal_map_id) {
+            (Some(path), id) => {
+                let id = AssetId::new(id.unwrap_or(0));
+                asset_loader
+                    .load_texture(path)
+                    .or_else(|_| asset_loader.load_texture_by_id(id))
+                    .unwrap_or(id)
+            }
+            (None, Some(id)) => asset_loader
+                .load_texture_by_id(AssetId::new(id))
+                .unwrap_or(AssetId::new(id)),
+            (None, None) => AssetId::new(0),
+        };
+        Sprite {
+            texture,
+            size: glam::Vec2::new(self.size[0], self.size[1]),
+            color: self.color,
+            flip_x: self.flip_x,
+            flip_y: self.flip_y,
+            material,
+            normal_map,
+            draw_mode: self.draw_mode.unwrap_or_default(),
+        }
+    }
+}
+
+/// Serialized text component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedText {
+    pub content: String,
+    pub font_path: String,
+    pub font_id: u64,
+    pub size: f32,
+    pub color: [f32; 4],
+    #[serde(default)]
+    pub align: TextAlign,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap_width: Option<f32>,
+}
+
+impl SerializedText {
+    /// Serialize a text component, looking up its font path in the registry
+    fn from_text<R: AssetRegistry>(text: &Text, registry: &R) -> Self {
+        Self {
+            content: text.content.clone(),
+            font_path: registry.get_path(text.font).unwrap_or("unknown").to_string(),
+            font_id: text.font.0,
+            size: text.size,
+            color: text.color,
+            align: text.align,
+            wrap_width: text.wrap_width,
+        }
+    }
+
+    /// Rebuild the text component, loading its font
+    ///
+    /// Unlike sprites, text whose font can't be loaded is kept (with the
+    /// serialized font ID) so the string isn't lost; it just doesn't draw.
+    fn to_text<L: AssetLoader>(&self, asset_loader: &mut L) -> Text {
+        let font = asset_loader
+            .load_font(&self.font_path)
+            .or_else(|_| asset_loader.load_font_by_id(AssetId::new(self.font_id)))
+            .unwrap_or(AssetId::new(self.font_id));
+        Text {
+            content: self.content.clone(),
+            font,
+            size: self.size,
+            color: self.color,
+            align: self.align,
+            wrap_width: self.wrap_width,
+        }
+    }
+}
+
+/// Serialized tilemap component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedTilemap {
+    pub tileset_path: String,
+    pub tileset_id: u64,
+    pub cell_size: [f32; 2],
+    pub layers: Vec<SerializedTileLayer>,
+}
+
+/// Serialized tilemap layer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedTileLayer {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    #[serde(default)]
+    pub z_index: i32,
+    pub chunks: Vec<SerializedTileChunk>,
+}
+
+/// Serialized tile chunk: raw cell values row by row (0 = empty, otherwise tile index + 1)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedTileChunk {
+    pub x: i32,
+    pub y: i32,
+    pub tiles: Vec<u32>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl SerializedTilemap {
+    /// Serialize a tilemap component, looking up its tile set path in the registry
+    fn from_tilemap<R: AssetRegistry>(tilemap: &Tilemap, registry: &R) -> Self {
+        Self {
+            tileset_path: registry.get_path(tilemap.tileset).unwrap_or("unknown").to_string(),
+            tileset_id: tilemap.tileset.0,
+            cell_size: [tilemap.cell_size.x, tilemap.cell_size.y],
+            layers: tilemap
+                .layers
+                .iter()
+                .map(|layer| SerializedTileLayer {
+                    name: layer.name.clone(),
+                    visible: layer.visible,
+                    z_index: layer.z_index,
+                    chunks: layer
+                        .chunks()
+                        .map(|((x, y), chunk)| SerializedTileChunk {
+                            x,
+                            y,
+                            tiles: chunk.raw().to_vec(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Rebuild the tilemap component, loading its tile set
+    ///
+    /// Like text, a tilemap whose tile set can't be loaded keeps its tiles
+    /// and the serialized tile set ID. Chunks with the wrong number of
+    /// cells are skipped.
+    fn to_tilemap<L: AssetLoader>(&self, asset_loader: &mut L) -> Tilemap {
+        let tileset = asset_loader
+            .load_tileset(&self.tileset_path)
+            .or_else(|_| asset_loader.load_tileset_by_id(AssetId::new(self.tileset_id)))
+            .unwrap_or(AssetId::new(self.tileset_id));
+        let mut tilemap = Tilemap::new(tileset, glam::Vec2::new(self.cell_size[0], self.cell_size[1]));
+        for serialized in &self.layers {
+            let mut layer = TileLayer::new(serialized.name.clone()).with_z_index(serialized.z_index);
+            layer.visible = serialized.visible;
+            for chunk in &serialized.chunks {
+                match TileChunk::from_raw(chunk.tiles.clone()) {
+                    Some(tiles) => layer.insert_chunk((chunk.x, chunk.y), tiles),
+                    None => eprintln!(
+                        "Warning: Skipping tile chunk ({}, {}) of layer '{}' with {} cells",
+                        chunk.x,
+                        chunk.y,
+                        serialized.name,
+                        chunk.tiles.len()
+                    ),
+                }
+            }
+            tilemap.layers.push(layer);
+        }
+        tilemap
+    }
+}
+
+/// Serialized particle emitter component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedParticleEmitter {
+    pub texture_path: String,
+    pub texture_id: u64,
+    pub effect: ParticleEffect,
+    #[serde(default)]
+    pub z_index: i32,
+    pub playing: bool,
+}
+
+impl SerializedParticleEmitter {
+    /// Serialize an emitter, looking up its texture path in the registry
+    fn from_emitter<R: AssetRegistry>(emitter: &ParticleEmitter, registry: &R) -> Self {
+        Self {
+            texture_path: registry.get_path(emitter.texture).unwrap_or("unknown").to_string(),
+            texture_id: emitter.texture.0,
+            effect: emitter.effect.clone(),
+            z_index: emitter.z_index,
+            playing: emitter.playing,
+        }
+    }
+
+    /// Rebuild the emitter, loading its texture
+    ///
+    /// Like text, an emitter whose texture can't be loaded is kept with the
+    /// serialized texture ID.
+    fn to_emitter<L: AssetLoader>(&self, asset_loader: &mut L) -> ParticleEmitter {
+        let texture = asset_loader
+            .load_texture(&self.texture_path)
+            .or_else(|_| asset_loader.load_texture_by_id(AssetId::new(self.texture_id)))
+            .unwrap_or(AssetId::new(self.texture_id));
+        ParticleEmitter {
+            effect: self.effect.clone(),
+            texture,
+            z_index: self.z_index,
+            playing: self.playing,
+            pending: 0,
+        }
+    }
+}
+
+/// Serialized animation player component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedAnimationPlayer {
+    pub clip_path: String,
+    pub clip_id: u64,
+    pub speed: f32,
+    pub playing: bool,
+}
+
+impl SerializedAnimationPlayer {
+    /// Serialize a player, looking up its clip path in the registry
+    fn from_player<R: AssetRegistry>(player: &AnimationPlayer, registry: &R) -> Self {
+        Self {
+            clip_path: registry.get_path(player.clip).unwrap_or("unknown").to_string(),
+            clip_id: player.clip.0,
+            speed: player.speed,
+            playing: player.playing,
+        }
+    }
+
+    /// Rebuild the player, loading its clip
+    ///
+    /// Like particle emitters, a player whose clip can't be loaded is kept
+    /// with the serialized clip ID.
+    fn to_player<L: AssetLoader>(&self, asset_loader: &mut L) -> AnimationPlayer {
+        let clip = asset_loader
+            .load_animation_clip(&self.clip_path)
+            .or_else(|_| asset_loader.load_animation_clip_by_id(AssetId::new(self.clip_id)))
+            .unwrap_or(AssetId::new(self.clip_id));
+        AnimationPlayer::new(clip)
+            .with_speed(self.speed)
+            .with_playing(self.playing)
+    }
+}
+
+/// Serialized state machine component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedStateMachine {
+    pub chart_path: String,
+    pub chart_id: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, ParameterValue>,
+}
+
+impl SerializedStateMachine {
+    /// Serialize a machine, looking up its chart path in the registry
+    ///
+    /// Only parameters are kept; the machine starts from the chart's initial
+    /// states when loaded.
+    fn from_machine<R: AssetRegistry>(machine: &StateMachine, registry: &R) -> Self {
+        Self {
+            chart_path: registry.get_path(machine.chart).unwrap_or("unknown").to_string(),
+            chart_id: machine.chart.0,
+            parameters: machine.parameters.iter().map(|(name, value)| (name.clone(), *value)).collect(),
+        }
+    }
+
+    /// Rebuild the machine, loading its chart
+    fn to_machine<L: AssetLoader>(&self, asset_loader: &mut L) -> StateMachine {
+        let chart = asset_loader
+            .load_state_chart(&self.chart_path)
+            .or_else(|_| asset_loader.load_state_chart_by_id(AssetId::new(self.chart_id)))
+            .unwrap_or(AssetId::new(self.chart_id));
+        let mut machine = StateMachine::new(chart);
+        machine.parameters.extend(self.parameters.iter().map(|(name, value)| (name.clone(), *value)));
+        machine
+    }
+}
+
+/// Serialized navigation region component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedNavRegion {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub navmesh_path: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub navmesh_id: Option<u64>,
+}
+
+impl SerializedNavRegion {
+    /// Serialize a region, looking up its navmesh path in the registry
+    fn from_region<R: AssetRegistry>(region: &NavRegion, registry: &R) -> Self {
+        Self {
+            navmesh_path: region
+                .navmesh
+                .map(|id| registry.get_path(id).unwrap_or("unknown").to_string()),
+            navmesh_id: region.navmesh.map(|id| id.0),
+        }
+    }
+
+    /// Rebuild the region, loading its navmesh
+    fn to_region<L: AssetLoader>(&self, asset_loader: &mut L) -> NavRegion {
+        let Some(id) = self.navmesh_id.map(AssetId::new) else {
+            return NavRegion::from_tilemap();
+        };
+        let loaded = self.navmesh_path.as_deref().map(|path| asset_loader.load_navmesh(path));
+        let navmesh = match loaded {
+            Some(Ok(navmesh)) => navmesh,
+            _ => asset_loader.load_navmesh_by_id(id).unwrap_or(id),
+        };
+        NavRegion::from_navmesh(navmesh)
+    }
 }
 
 /// Scene data structure for serialization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Scene {
+    /// Schema version (see `SCENE_FORMAT_VERSION`)
+    #[serde(default = "legacy_scene_version")]
+    pub version: u32,
     pub name: String,
     pub entities: Vec<SerializedEntity>,
 }
 
+/// Scenes saved before versioning was introduced are version 1
+fn legacy_scene_version() -> u32 {
+    1
+}
+
 impl Scene {
     /// Create a new empty scene with the given name
     pub fn new(name: impl Into<String>) -> Self {
         Self {
+            version: SCENE_FORMAT_VERSION,
             name: name.into(),
             entities: Vec::new(),
         }
@@ -122,16 +792,13 @@ fn serialize_entity<R: AssetRegistry>(
         registry: &R,
         entity_id: hecs::Entity,
     ) -> SerializedEntity {
-        let entity_u64 = entity_id.to_bits().get();
         let entity_handle = EntityHandle::new(entity_id);
+        let entity_u64 = world
+            .guid(entity_handle)
+            .map(|guid| guid.get())
+            .unwrap_or_else(|| entity_id.to_bits().get());
 
-        let mut components = SerializedComponents {
-            name: None,
-            transform: None,
-            sprite: None,
-            script: None,
-            enabled: None,
-        };
+        let mut components = SerializedComponents::default();
 
         // Try to get Name component
         if let Ok(name) = world.inner().get::<&Name>(entity_id) {
@@ -145,21 +812,72 @@ fn serialize_entity<R: AssetRegistry>(
 
         // Try to get Sprite component
         if let Ok(sprite) = world.inner().get::<&Sprite>(entity_id) {
-            let texture_path = registry
-                .get_path(sprite.texture)
-                .unwrap_or("unknown")
-                .to_string();
+            components.sprite = Some(SerializedSprite::from_sprite(&sprite, registry));
+        }
+
+        // Try to get MaterialParams component
+        if let Ok(params) = world.inner().get::<&MaterialParams>(entity_id) {
+            components.material_params = Some((*params).clone());
+        }
+
+        // Try to get Text component
+        if let Ok(text) = world.inner().get::<&Text>(entity_id) {
+            components.text = Some(SerializedText::from_text(&text, registry));
+        }
+
+        // Try to get Tilemap component
+        if let Ok(tilemap) = world.inner().get::<&Tilemap>(entity_id) {
+            components.tilemap = Some(SerializedTilemap::from_tilemap(&tilemap, registry));
+        }
+
+        // Try to get ParticleEmitter component
+        if let Ok(emitter) = world.inner().get::<&ParticleEmitter>(entity_id) {
+            components.particle_emitter = Some(SerializedParticleEmitter::from_emitter(&emitter, registry));
+        }
+
+        // Try to get AnimationPlayer component
+        if let Ok(player) = world.inner().get::<&AnimationPlayer>(entity_id) {
+            components.animation_player = Some(SerializedAnimationPlayer::from_player(&player, registry));
+        }
 
-            components.sprite = Some(SerializedSprite {
-                texture_path,
-                texture_id: sprite.texture.0,
-                size: [sprite.size.x, sprite.size.y],
-                color: sprite.color,
-                flip_x: sprite.flip_x,
-                flip_y: sprite.flip_y,
-            });
+        // Try to get StateMachine component
+        if let Ok(machine) = world.inner().get::<&StateMachine>(entity_id) {
+            components.state_machine = Some(SerializedStateMachine::from_machine(&machine, registry));
         }
 
+        // Try to get navigation components
+        if let Ok(agent) = world.inner().get::<&NavAgent>(entity_id) {
+            components.nav_agent = Some((*agent).clone());
+        }
+        if let Ok(obstacle) = world.inner().get::<&NavObstacle>(entity_id) {
+            components.nav_obstacle = Some(*obstacle);
+        }
+        if let Ok(region) = world.inner().get::<&NavRegion>(entity_id) {
+            components.nav_region = Some(SerializedNavRegion::from_region(&region, registry));
+        }
+
+        // Try to get light components
+        if let Ok(light) = world.inner().get::<&PointLight2D>(entity_id) {
+            components.point_light = Some((*light).clone());
+        }
+        if let Ok(light) = world.inner().get::<&SpotLight2D>(entity_id) {
+            components.spot_light = Some((*light).clone());
+        }
+        if let Ok(light) = world.inner().get::<&GlobalLight2D>(entity_id) {
+            components.global_light = Some((*light).clone());
+        }
+        if let Ok(occluder) = world.inner().get::<&LightOccluder2D>(entity_id) {
+            components.light_occluder = Some((*occluder).clone());
+        }
+
+        // Try to get Shape component
+        if let Ok(shape) = world.inner().get::<&Shape>(entity_id) {
+            components.shape = Some((*shape).clone());
+        }
+
+        // Try to get UI components
+        components.ui = SerializedUi::from_entity(world, entity_id);
+
         // Try to get Script component
         if let Ok(script) = world.inner().get::<&Script>(entity_id) {
             components.script = Some((*script).clone());
@@ -170,6 +888,14 @@ fn serialize_entity<R: AssetRegistry>(
             components.enabled = Some(enabled.is_enabled());
         }
 
+        // Try to get Camera component
+        if let Ok(camera) = world.inner().get::<&Camera>(entity_id) {
+            components.camera = Some((*camera).clone());
+        }
+        if world.inner().get::<&MainCamera>(entity_id).is_ok() {
+            components.main_camera = Some(true);
+        }
+
         // Recursively serialize children
         let mut children = Vec::new();
         if let Ok(children_comp) = world.get::<crate::ecs::Children>(entity_handle) {
@@ -205,12 +931,13 @@ impl Scene {
         }
 
         Self {
+            version: SCENE_FORMAT_VERSION,
             name: "Scene".to_string(),
             entities,
         }
     }
 
-    /// Save the scene to a file (supports JSON and RON formats based on extension)
+    /// Save the scene to a file (JSON, RON or binary based on extension)
     pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = path.as_ref();
 
@@ -220,19 +947,7 @@ impl Scene {
         }
 
         // Determine format from file extension
-        let filename = path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
-
-        let contents = if filename.ends_with(".scn.ron") || filename.ends_with(".ron") {
-            // Serialize to RON
-            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
-                .map_err(|e| LonghornError::Serialization(e.to_string()))?
-        } else {
-            // Serialize to JSON (default)
-            serde_json::to_string_pretty(self)
-                .map_err(|e| LonghornError::Serialization(e.to_string()))?
-        };
+        let contents = self.to_bytes(SceneFormat::from_path(path))?;
 
         // Write to file
         fs::write(path, contents)?;
@@ -240,40 +955,38 @@ impl Scene {
         Ok(())
     }
 
-    /// Load a scene from a file (supports JSON and RON formats)
+    /// Load a scene from a file (JSON, RON or binary based on extension)
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
 
         // Read file contents
-        let contents = fs::read_to_string(path)?;
-
-        // Determine format from file extension
-        let filename = path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
-
-        let scene: Scene = if filename.ends_with(".scn.ron") || filename.ends_with(".ron") {
-            // Deserialize from RON
-            ron::from_str(&contents)
-                .map_err(|e| LonghornError::Serialization(e.to_string()))?
-        } else {
-            // Deserialize from JSON (default)
-            serde_json::from_str(&contents)
-                .map_err(|e| LonghornError::Serialization(e.to_string()))?
-        };
+        let contents = fs::read(path)?;
 
-        Ok(scene)
+        Self::from_bytes(&contents, SceneFormat::from_path(path))
     }
 }
 
 /// Recursively spawn an entity and its children
+///
+/// Every spawned entity is recorded in `entity_map` under its serialized ID.
+/// The serialized ID is reused as the entity's GUID unless `fresh_guids` is set
+/// or another live entity already owns it.
 fn spawn_entity<L: AssetLoader>(
     world: &mut World,
     asset_loader: &mut L,
     serialized: &SerializedEntity,
     parent: Option<EntityHandle>,
+    fresh_guids: bool,
+    entity_map: &mut HashMap<u64, EntityHandle>,
 ) -> Result<EntityHandle> {
-    let mut builder = world.spawn();
+    let guid = EntityGuid(serialized.id);
+    let guid = if fresh_guids || guid.get() == 0 || world.contains_guid(guid) {
+        EntityGuid::new()
+    } else {
+        guid
+    };
+
+    let mut builder = world.spawn().with(guid);
 
     // Add Name component if present
     if let Some(ref name) = serialized.components.name {
@@ -300,16 +1013,75 @@ fn spawn_entity<L: AssetLoader>(
 
         // Only add Sprite if we successfully loaded a texture
         if let Some(texture_id) = texture_id {
-            builder = builder.with(Sprite {
-                texture: texture_id,
-                size: glam::Vec2::new(sprite_data.size[0], sprite_data.size[1]),
-                color: sprite_data.color,
-                flip_x: sprite_data.flip_x,
-                flip_y: sprite_data.flip_y,
-            });
+            builder = builder.with(sprite_data.to_sprite(texture_id, asset_loader));
         }
     }
 
+    // Add MaterialParams component if present
+    if let Some(ref params) = serialized.components.material_params {
+        builder = builder.with(params.clone());
+    }
+
+    // Add Text component if present
+    if let Some(ref text) = serialized.components.text {
+        builder = builder.with(text.to_text(asset_loader));
+    }
+
+    // Add Tilemap component if present
+    if let Some(ref tilemap) = serialized.components.tilemap {
+        builder = builder.with(tilemap.to_tilemap(asset_loader));
+    }
+
+    // Add ParticleEmitter component if present
+    if let Some(ref emitter) = serialized.components.particle_emitter {
+        builder = builder.with(emitter.to_emitter(asset_loader));
+    }
+
+    // Add AnimationPlayer component if present
+    if let Some(ref player) = serialized.components.animation_player {
+        builder = builder.with(player.to_player(asset_loader));
+    }
+
+    // Add StateMachine component if present
+    if let Some(ref machine) = serialized.components.state_machine {
+        builder = builder.with(machine.to_machine(asset_loader));
+    }
+
+    // Add navigation components if present
+    if let Some(ref agent) = serialized.components.nav_agent {
+        builder = builder.with(agent.clone());
+    }
+    if let Some(obstacle) = serialized.components.nav_obstacle {
+        builder = builder.with(obstacle);
+    }
+    if let Some(ref region) = serialized.components.nav_region {
+        builder = builder.with(region.to_region(asset_loader));
+    }
+
+    // Add light components if present
+    if let Some(ref light) = serialized.components.point_light {
+        builder = builder.with(light.clone());
+    }
+    if let Some(ref light) = serialized.components.spot_light {
+        builder = builder.with(light.clone());
+    }
+    if let Some(ref light) = serialized.components.global_light {
+        builder = builder.with(light.clone());
+    }
+    if let Some(ref occluder) = serialized.components.light_occluder {
+        builder = builder.with(occluder.clone());
+    }
+
+    // Add Shape component if present
+    if let Some(ref shape) = serialized.components.shape {
+        builder = builder.with(shape.clone());
+    }
+
+    // Add UI components if present
+    if let Some(ref ui) = serialized.components.ui {
+        builder = ui.build(builder);
+    }
+
     // Add Script component if present
     if let Some(ref script) = serialized.components.script {
         builder = builder.with(script.clone());
@@ -320,8 +1092,17 @@ fn spawn_entity<L: AssetLoader>(
         builder = builder.with(Enabled::new(enabled));
     }
 
+    // Add Camera components if present
+    if let Some(ref camera) = serialized.components.camera {
+        builder = builder.with(camera.clone());
+    }
+    if serialized.components.main_camera == Some(true) {
+        builder = builder.with(MainCamera);
+    }
+
     // Build the entity
     let entity_handle = builder.build();
+    entity_map.insert(serialized.id, entity_handle);
 
     // Set parent if provided
     if let Some(parent_handle) = parent {
@@ -331,21 +1112,55 @@ fn spawn_entity<L: AssetLoader>(
 
     // Recursively spawn children
     for child in &serialized.children {
-        spawn_entity(world, asset_loader, child, Some(entity_handle))?;
+        spawn_entity(world, asset_loader, child, Some(entity_handle), fresh_guids, entity_map)?;
     }
 
-    Ok(entity_handle)
+    Ok(entity_handle)
+}
+
+/// Overwrite a plain-data component with its snapshot value, or remove it if
+/// the snapshot doesn't have one
+fn restore_component<T: hecs::Component + Clone>(world: &mut World, entity_id: hecs::Entity, component: &Option<T>) {
+    if let Some(component) = component {
+        let _ = world.inner_mut().insert_one(entity_id, component.clone());
+    } else if world.has::<T>(EntityHandle::new(entity_id)) {
+        let _ = world.inner_mut().remove_one::<T>(entity_id);
+    }
+}
+
+/// Point entity references in freshly spawned components at the spawned entities
+fn map_entity_refs(world: &mut World, entity_map: &HashMap<u64, EntityHandle>) {
+    for &entity in entity_map.values() {
+        map_component_refs::<Script>(world, entity, entity_map);
+        map_component_refs::<Camera>(world, entity, entity_map);
+    }
+}
+
+fn map_component_refs<T: MapEntities + Clone + Send + Sync + 'static>(
+    world: &mut World,
+    entity: EntityHandle,
+    entity_map: &HashMap<u64, EntityHandle>,
+) {
+    let Ok(mut component) = world.get::<T>(entity).map(|c| (*c).clone()) else {
+        return;
+    };
+    component.map_entities(world, entity_map);
+    let _ = world.set(entity, component);
 }
 
 impl Scene {
     /// Spawn all entities from this scene into a World
     ///
+    /// Entities keep the GUIDs they were saved with, so references to them
+    /// stay valid across save/load. Entity references inside the scene are
+    /// remapped through the returned id map.
+    ///
     /// # Arguments
     /// * `world` - The target ECS world to spawn entities into
     /// * `asset_loader` - Asset loader to load textures referenced in sprites
     ///
     /// # Returns
-    /// A mapping from serialized entity IDs to new entity handles
+    /// A mapping from serialized entity IDs (including children) to new entity handles
     pub fn spawn_into<L: AssetLoader>(
         &self,
         world: &mut World,
@@ -354,9 +1169,32 @@ impl Scene {
         let mut entity_map = HashMap::new();
 
         for serialized_entity in &self.entities {
-            let entity_handle = spawn_entity(world, asset_loader, serialized_entity, None)?;
-            entity_map.insert(serialized_entity.id, entity_handle);
+            spawn_entity(world, asset_loader, serialized_entity, None, false, &mut entity_map)?;
+        }
+        map_entity_refs(world, &entity_map);
+
+        Ok(entity_map)
+    }
+
+    /// Spawn a copy of this scene into a World as a prefab instance
+    ///
+    /// Unlike `spawn_into`, every entity gets a fresh GUID so the same scene
+    /// can be instantiated any number of times. Entity references between the
+    /// instance's entities point at the new copies.
+    ///
+    /// # Returns
+    /// A mapping from serialized entity IDs (including children) to new entity handles
+    pub fn instantiate_into<L: AssetLoader>(
+        &self,
+        world: &mut World,
+        asset_loader: &mut L,
+    ) -> Result<HashMap<u64, EntityHandle>> {
+        let mut entity_map = HashMap::new();
+
+        for serialized_entity in &self.entities {
+            spawn_entity(world, asset_loader, serialized_entity, None, true, &mut entity_map)?;
         }
+        map_entity_refs(world, &entity_map);
 
         Ok(entity_map)
     }
@@ -376,7 +1214,7 @@ impl Scene {
     ///
     /// This method updates existing entities to match the snapshot without
     /// destroying and recreating them, which preserves entity IDs and prevents
-    /// component scrambling.
+    /// component scrambling. Entities are matched by GUID.
     ///
     /// # Arguments
     /// * `world` - The target ECS world to restore entities into
@@ -387,7 +1225,6 @@ impl Scene {
         asset_loader: &mut L,
     ) -> Result<()> {
         use std::collections::HashMap;
-        use glam::Vec2;
 
         // Build a map of serialized entity ID -> SerializedEntity
         // This recursively collects ALL entities including children
@@ -404,11 +1241,13 @@ impl Scene {
 
         // Update existing entities in-place
         for entity_id in entity_ids {
-            let entity_bits = entity_id.to_bits().get();
+            let entity_guid = world
+                .guid(EntityHandle::new(entity_id))
+                .map(|guid| guid.get());
 
-            if let Some(serialized) = snapshot_entities.get(&entity_bits) {
+            if let Some(serialized) = entity_guid.and_then(|guid| snapshot_entities.get(&guid)) {
                 // This entity exists in the snapshot - update its components
-                processed_ids.insert(entity_bits);
+                processed_ids.insert(serialized.id);
 
                 // Remove all existing components by getting mutable access
                 // Note: We can't remove components in hecs, so we'll just overwrite them
@@ -427,6 +1266,75 @@ impl Scene {
                     let _ = world.inner_mut().remove_one::<Transform>(entity_id);
                 }
 
+                // Update/add Text
+                if let Some(ref text) = serialized.components.text {
+                    let text = text.to_text(asset_loader);
+                    let _ = world.inner_mut().insert_one(entity_id, text);
+                } else if world.has::<Text>(EntityHandle::new(entity_id)) {
+                    let _ = world.inner_mut().remove_one::<Text>(entity_id);
+                }
+
+                // Update/add Tilemap
+                if let Some(ref tilemap) = serialized.components.tilemap {
+                    let tilemap = tilemap.to_tilemap(asset_loader);
+                    let _ = world.inner_mut().insert_one(entity_id, tilemap);
+                } else if world.has::<Tilemap>(EntityHandle::new(entity_id)) {
+                    let _ = world.inner_mut().remove_one::<Tilemap>(entity_id);
+                }
+
+                // Update/add ParticleEmitter
+                if let Some(ref emitter) = serialized.components.particle_emitter {
+                    let emitter = emitter.to_emitter(asset_loader);
+                    let _ = world.inner_mut().insert_one(entity_id, emitter);
+                } else if world.has::<ParticleEmitter>(EntityHandle::new(entity_id)) {
+                    let _ = world.inner_mut().remove_one::<ParticleEmitter>(entity_id);
+                }
+
+                // Update/add AnimationPlayer
+                if let Some(ref player) = serialized.components.animation_player {
+                    let player = player.to_player(asset_loader);
+                    let _ = world.inner_mut().insert_one(entity_id, player);
+                } else if world.has::<AnimationPlayer>(EntityHandle::new(entity_id)) {
+                    let _ = world.inner_mut().remove_one::<AnimationPlayer>(entity_id);
+                }
+
+                // Update/add StateMachine
+                if let Some(ref machine) = serialized.components.state_machine {
+                    let machine = machine.to_machine(asset_loader);
+                    let _ = world.inner_mut().insert_one(entity_id, machine);
+                } else if world.has::<StateMachine>(EntityHandle::new(entity_id)) {
+                    let _ = world.inner_mut().remove_one::<StateMachine>(entity_id);
+                }
+
+                // Update/add navigation components
+                restore_component(world, entity_id, &serialized.components.nav_agent);
+                restore_component(world, entity_id, &serialized.components.nav_obstacle);
+                if let Some(ref region) = serialized.components.nav_region {
+                    let region = region.to_region(asset_loader);
+                    let _ = world.inner_mut().insert_one(entity_id, region);
+                } else if world.has::<NavRegion>(EntityHandle::new(entity_id)) {
+                    let _ = world.inner_mut().remove_one::<NavRegion>(entity_id);
+                }
+
+                // Update/add light components
+                restore_component(world, entity_id, &serialized.components.point_light);
+                restore_component(world, entity_id, &serialized.components.spot_light);
+                restore_component(world, entity_id, &serialized.components.global_light);
+                restore_component(world, entity_id, &serialized.components.light_occluder);
+
+                // Update/add Shape
+                restore_component(world, entity_id, &serialized.components.shape);
+
+                // Update/add UI components
+                SerializedUi::restore(serialized.components.ui.as_ref(), world, entity_id);
+
+                // Update/add MaterialParams
+                if let Some(ref params) = serialized.components.material_params {
+                    let _ = world.inner_mut().insert_one(entity_id, params.clone());
+                } else if world.has::<MaterialParams>(EntityHandle::new(entity_id)) {
+                    let _ = world.inner_mut().remove_one::<MaterialParams>(entity_id);
+                }
+
                 // Update/add Sprite
                 if let Some(ref sprite_data) = serialized.components.sprite {
                     // Try to load the texture
@@ -460,13 +1368,7 @@ impl Scene {
                         }
                     };
 
-                    let sprite = Sprite {
-                        texture: texture_id,
-                        size: Vec2::new(sprite_data.size[0], sprite_data.size[1]),
-                        color: sprite_data.color,
-                        flip_x: sprite_data.flip_x,
-                        flip_y: sprite_data.flip_y,
-                    };
+                    let sprite = sprite_data.to_sprite(texture_id, asset_loader);
                     let _ = world.inner_mut().insert_one(entity_id, sprite);
                 } else if world.has::<Sprite>(EntityHandle::new(entity_id)) {
                     let _ = world.inner_mut().remove_one::<Sprite>(entity_id);
@@ -496,13 +1398,13 @@ impl Scene {
         for serialized in &self.entities {
             if !processed_ids.contains(&serialized.id) {
                 // This entity only exists in the snapshot - spawn it
-                // Note: We can't control the entity ID in hecs, so this entity will get a new ID
+                // Note: We can't control the entity ID in hecs, but the GUID is kept
                 eprintln!(
                     "Warning: Entity {} from snapshot not found in world. Spawning with new ID.",
                     serialized.id
                 );
 
-                let mut builder = world.spawn();
+                let mut builder = world.spawn().with(EntityGuid(serialized.id));
 
                 if let Some(ref name) = serialized.components.name {
                     builder = builder.with(Name::new(name.clone()));
@@ -516,17 +1418,65 @@ impl Scene {
                     if let Ok(texture_id) = asset_loader.load_texture(&sprite_data.texture_path)
                         .or_else(|_| asset_loader.load_texture_by_id(AssetId::new(sprite_data.texture_id)))
                     {
-                        let sprite = Sprite {
-                            texture: texture_id,
-                            size: Vec2::new(sprite_data.size[0], sprite_data.size[1]),
-                            color: sprite_data.color,
-                            flip_x: sprite_data.flip_x,
-                            flip_y: sprite_data.flip_y,
-                        };
-                        builder = builder.with(sprite);
+                        builder = builder.with(sprite_data.to_sprite(texture_id, asset_loader));
                     }
                 }
 
+                if let Some(ref params) = serialized.components.material_params {
+                    builder = builder.with(params.clone());
+                }
+
+                if let Some(ref text) = serialized.components.text {
+                    builder = builder.with(text.to_text(asset_loader));
+                }
+
+                if let Some(ref tilemap) = serialized.components.tilemap {
+                    builder = builder.with(tilemap.to_tilemap(asset_loader));
+                }
+
+                if let Some(ref emitter) = serialized.components.particle_emitter {
+                    builder = builder.with(emitter.to_emitter(asset_loader));
+                }
+
+                if let Some(ref player) = serialized.components.animation_player {
+                    builder = builder.with(player.to_player(asset_loader));
+                }
+
+                if let Some(ref machine) = serialized.components.state_machine {
+                    builder = builder.with(machine.to_machine(asset_loader));
+                }
+
+                if let Some(ref agent) = serialized.components.nav_agent {
+                    builder = builder.with(agent.clone());
+                }
+                if let Some(obstacle) = serialized.components.nav_obstacle {
+                    builder = builder.with(obstacle);
+                }
+                if let Some(ref region) = serialized.components.nav_region {
+                    builder = builder.with(region.to_region(asset_loader));
+                }
+
+                if let Some(ref light) = serialized.components.point_light {
+                    builder = builder.with(light.clone());
+                }
+                if let Some(ref light) = serialized.components.spot_light {
+                    builder = builder.with(light.clone());
+                }
+                if let Some(ref light) = serialized.components.global_light {
+                    builder = builder.with(light.clone());
+                }
+                if let Some(ref occluder) = serialized.components.light_occluder {
+                    builder = builder.with(occluder.clone());
+                }
+
+                if let Some(ref shape) = serialized.components.shape {
+                    builder = builder.with(shape.clone());
+                }
+
+                if let Some(ref ui) = serialized.components.ui {
+                    builder = ui.build(builder);
+                }
+
                 if let Some(ref script) = serialized.components.script {
                     builder = builder.with(script.clone());
                 }
@@ -539,6 +1489,8 @@ impl Scene {
             }
         }
 
+        world.rebuild_guid_index();
+
         Ok(())
     }
 
@@ -629,6 +1581,18 @@ mod tests {
                 ))
             }
         }
+
+        fn load_font(&mut self, path: &str) -> std::io::Result<AssetId> {
+            self.load_texture(path)
+        }
+
+        fn load_tileset(&mut self, path: &str) -> std::io::Result<AssetId> {
+            self.load_texture(path)
+        }
+
+        fn load_material(&mut self, path: &str) -> std::io::Result<AssetId> {
+            self.load_texture(path)
+        }
     }
 
     fn temp_path(name: &str) -> std::path::PathBuf {
@@ -657,10 +1621,8 @@ mod tests {
             id: 1,
             components: SerializedComponents {
                 name: Some("Player".to_string()),
-                transform: None,
-                sprite: None,
-                script: None,
                 enabled: Some(true),
+                ..Default::default()
             },
             children: Vec::new(),
         };
@@ -692,9 +1654,14 @@ mod tests {
                     color: [1.0, 1.0, 1.0, 1.0],
                     flip_x: false,
                     flip_y: false,
+                    material_path: None,
+                    material_id: None,
+                    normal_map_path: None,
+                    normal_map_id: None,
+                    draw_mode: None,
                 }),
-                script: None,
                 enabled: Some(true),
+                ..Default::default()
             },
             children: Vec::new(),
         };
@@ -807,9 +1774,14 @@ mod tests {
                     color: [1.0, 0.5, 0.5, 1.0],
                     flip_x: true,
                     flip_y: false,
+                    material_path: None,
+                    material_id: None,
+                    normal_map_path: None,
+                    normal_map_id: None,
+                    draw_mode: None,
                 }),
-                script: None,
                 enabled: Some(false),
+                ..Default::default()
             },
             children: Vec::new(),
         };
@@ -944,9 +1916,14 @@ mod tests {
                     color: [1.0, 1.0, 1.0, 1.0],
                     flip_x: false,
                     flip_y: false,
+                    material_path: None,
+                    material_id: None,
+                    normal_map_path: None,
+                    normal_map_id: None,
+                    draw_mode: None,
                 }),
-                script: None,
                 enabled: Some(true),
+                ..Default::default()
             },
             children: Vec::new(),
         };
@@ -991,9 +1968,14 @@ mod tests {
                     color: [1.0, 1.0, 1.0, 1.0],
                     flip_x: false,
                     flip_y: false,
+                    material_path: None,
+                    material_id: None,
+                    normal_map_path: None,
+                    normal_map_id: None,
+                    draw_mode: None,
                 }),
-                script: None,
                 enabled: Some(true),
+                ..Default::default()
             },
             children: Vec::new(),
         };
@@ -1216,9 +2198,8 @@ mod tests {
                     rotation: 1.5,
                     scale: [2.0, 2.0],
                 }),
-                sprite: None,
-                script: None,
                 enabled: Some(true),
+                ..Default::default()
             },
             children: Vec::new(),
         };
@@ -1252,4 +2233,553 @@ mod tests {
         // Clean up
         fs::remove_file(&path).unwrap();
     }
+
+    #[test]
+    fn test_guids_survive_save_and_load() {
+        let path = temp_path("guids");
+        let registry = MockRegistry::new();
+
+        let mut world = World::new();
+        let parent = world.spawn().with(Name::new("Parent")).build();
+        let child = world.spawn().with(Name::new("Child")).build();
+        crate::ecs::hierarchy::add_child(&mut world, parent, child).unwrap();
+
+        let parent_guid = world.guid(parent).unwrap();
+        let child_guid = world.guid(child).unwrap();
+
+        Scene::from_world(&world, &registry).save(&path).unwrap();
+        let loaded = Scene::load(&path).unwrap();
+
+        let mut new_world = World::new();
+        let mut asset_loader = MockAssetLoader::new();
+        let entity_map = loaded.spawn_into(&mut new_world, &mut asset_loader).unwrap();
+
+        // Children are included in the id map
+        assert_eq!(entity_map.len(), 2);
+
+        let new_parent = new_world.entity_by_guid(parent_guid).unwrap();
+        let new_child = new_world.entity_by_guid(child_guid).unwrap();
+        assert_eq!(new_world.get::<Name>(new_parent).unwrap().as_str(), "Parent");
+        assert_eq!(new_world.get::<Name>(new_child).unwrap().as_str(), "Child");
+        assert_eq!(entity_map[&parent_guid.get()], new_parent);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_instantiate_assigns_fresh_guids() {
+        let registry = MockRegistry::new();
+
+        let mut world = World::new();
+        let original = world.spawn().with(Name::new("Crate")).build();
+        let original_guid = world.guid(original).unwrap();
+        let prefab = Scene::from_world(&world, &registry);
+
+        let mut asset_loader = MockAssetLoader::new();
+        let first = prefab.instantiate_into(&mut world, &mut asset_loader).unwrap();
+        let second = prefab.instantiate_into(&mut world, &mut asset_loader).unwrap();
+
+        let first_guid = world.guid(first[&original_guid.get()]).unwrap();
+        let second_guid = world.guid(second[&original_guid.get()]).unwrap();
+        assert_ne!(first_guid, original_guid);
+        assert_ne!(first_guid, second_guid);
+        assert_eq!(world.entity_by_guid(original_guid), Some(original));
+    }
+
+    #[test]
+    fn test_spawn_into_does_not_duplicate_live_guids() {
+        let registry = MockRegistry::new();
+
+        let mut world = World::new();
+        let original = world.spawn().with(Name::new("Player")).build();
+        let original_guid = world.guid(original).unwrap();
+        let scene = Scene::from_world(&world, &registry);
+
+        let mut asset_loader = MockAssetLoader::new();
+        let entity_map = scene.spawn_into(&mut world, &mut asset_loader).unwrap();
+
+        let copy = entity_map[&original_guid.get()];
+        assert_ne!(world.guid(copy), Some(original_guid));
+        assert_eq!(world.entity_by_guid(original_guid), Some(original));
+    }
+
+    #[test]
+    fn test_restore_into_matches_by_guid() {
+        let registry = MockRegistry::new();
+
+        let mut world = World::new();
+        let player = world
+            .spawn()
+            .with(Name::new("Player"))
+            .with(Transform::from_position(Vec2::new(1.0, 2.0)))
+            .build();
+        let player_guid = world.guid(player).unwrap();
+        let snapshot = Scene::from_world(&world, &registry);
+
+        // Simulate play mode: move the player and despawn it, then respawn with the same GUID
+        world.despawn(player).unwrap();
+        let respawned = world
+            .spawn()
+            .with(player_guid)
+            .with(Name::new("Player"))
+            .with(Transform::from_position(Vec2::new(50.0, 50.0)))
+            .build();
+        world.spawn().with(Name::new("Bullet")).build();
+
+        let mut asset_loader = MockAssetLoader::new();
+        snapshot.restore_into(&mut world, &mut asset_loader).unwrap();
+
+        assert_eq!(world.len(), 1);
+        assert_eq!(world.entity_by_guid(player_guid), Some(respawned));
+        let transform = world.get::<Transform>(respawned).unwrap();
+        assert_eq!(transform.position, Vec2::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn test_instantiate_remaps_entity_refs() {
+        use crate::ecs::{EntityRef, ScriptValue};
+
+        let registry = MockRegistry::new();
+
+        let mut world = World::new();
+        let switch = world.spawn().with(Name::new("Switch")).build();
+        let switch_ref = EntityRef::to_entity(&world, switch);
+        let mut script = Script::new("Door.ts");
+        script.set_property("switch", ScriptValue::Entity { entity: switch_ref });
+        script.set_property("external", ScriptValue::Entity { entity: EntityRef::new(EntityGuid(99)) });
+        let door = world.spawn().with(Name::new("Door")).with(script).build();
+        let prefab = Scene::from_world(&world, &registry);
+
+        let mut asset_loader = MockAssetLoader::new();
+        let entity_map = prefab.instantiate_into(&mut world, &mut asset_loader).unwrap();
+
+        let door_guid = world.guid(door).unwrap().get();
+        let new_door = entity_map[&door_guid];
+        let new_switch = entity_map[&switch_ref.guid().unwrap().get()];
+
+        let script = world.get::<Script>(new_door).unwrap();
+        let target = script.get_property("switch").unwrap().as_entity().unwrap();
+        assert_eq!(target.resolve(&world), Some(new_switch));
+
+        let external = script.get_property("external").unwrap().as_entity().unwrap();
+        assert_eq!(external, EntityRef::new(EntityGuid(99)));
+    }
+
+    #[test]
+    fn test_camera_roundtrip_remaps_follow_target() {
+        use crate::ecs::{CameraFollow, EntityRef, ViewportRect};
+        use crate::math::Rect;
+
+        let registry = MockRegistry::new();
+
+        let mut world = World::new();
+        let player = world.spawn().with(Name::new("Player")).build();
+        let camera = Camera::new(320.0, 240.0)
+            .with_viewport(ViewportRect::new(0.5, 0.0, 0.5, 1.0))
+            .with_order(2)
+            .with_bounds(Rect::new(Vec2::ZERO, Vec2::new(1000.0, 500.0)))
+            .with_follow(
+                CameraFollow::new(EntityRef::to_entity(&world, player))
+                    .with_damping(0.25)
+                    .with_dead_zone(Vec2::new(8.0, 4.0)),
+            );
+        world.spawn().with(Name::new("Camera")).with(camera.clone()).with(MainCamera).build();
+
+        let prefab = Scene::from_world(&world, &registry);
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let bytes = prefab.to_bytes(format).unwrap();
+            let scene = Scene::from_bytes(&bytes, format).unwrap();
+            let saved = scene.entities.iter().find_map(|e| e.components.camera.as_ref());
+            assert_eq!(saved, Some(&camera));
+        }
+
+        let mut asset_loader = MockAssetLoader::new();
+        let mut loaded = World::new();
+        prefab.instantiate_into(&mut loaded, &mut asset_loader).unwrap();
+
+        let (camera_entity, new_player) = {
+            let mut camera_entity = None;
+            let mut new_player = None;
+            for (entity, name) in loaded.query::<&Name>().iter() {
+                match name.as_str() {
+                    "Camera" => camera_entity = Some(EntityHandle::new(entity)),
+                    _ => new_player = Some(EntityHandle::new(entity)),
+                }
+            }
+            (camera_entity.unwrap(), new_player.unwrap())
+        };
+
+        assert!(loaded.has::<MainCamera>(camera_entity));
+        let loaded_camera = loaded.get::<Camera>(camera_entity).unwrap();
+        assert_eq!(loaded_camera.order, 2);
+        assert_eq!(loaded_camera.viewport, camera.viewport);
+        assert_eq!(loaded_camera.bounds, camera.bounds);
+        let follow = loaded_camera.follow.as_ref().unwrap();
+        assert_eq!(follow.damping, 0.25);
+        assert_eq!(follow.target.resolve(&loaded), Some(new_player));
+    }
+
+    #[test]
+    fn test_text_roundtrip_and_missing_font() {
+        let mut registry = MockRegistry::new();
+        registry.register("fonts/ui.ttf", 7);
+
+        let mut world = World::new();
+        let text = Text::new("Score: 10\nLives: 3", AssetId::new(7), 24.0)
+            .with_align(TextAlign::Center)
+            .with_color([1.0, 0.8, 0.0, 1.0])
+            .with_wrap_width(200.0);
+        let entity = world.spawn().with(Name::new("Score")).with(text.clone()).build();
+
+        let scene = Scene::from_world(&world, &registry);
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let saved = loaded.entities[0].components.text.as_ref().unwrap();
+            assert_eq!(saved.font_path, "fonts/ui.ttf");
+            assert_eq!(saved.content, text.content);
+            assert_eq!(saved.align, TextAlign::Center);
+        }
+
+        let mut asset_loader = MockAssetLoader::new();
+        asset_loader.registry.register("fonts/ui.ttf", 7);
+        let mut loaded = World::new();
+        let entity_map = scene.spawn_into(&mut loaded, &mut asset_loader).unwrap();
+        let guid = world.guid(entity).unwrap().get();
+        assert_eq!(*loaded.get::<Text>(entity_map[&guid]).unwrap(), text);
+
+        // Without the font the string survives with the saved font ID
+        let mut missing = World::new();
+        let entity_map = scene.spawn_into(&mut missing, &mut MockAssetLoader::new()).unwrap();
+        let restored = missing.get::<Text>(entity_map[&guid]).unwrap();
+        assert_eq!(restored.content, text.content);
+        assert_eq!(restored.font, AssetId::new(7));
+
+        // Restoring in place replaces edited text
+        world.get_mut::<Text>(entity).unwrap().set_content("Score: 99");
+        scene.restore_into(&mut world, &mut asset_loader).unwrap();
+        assert_eq!(world.get::<Text>(entity).unwrap().content, text.content);
+    }
+
+    #[test]
+    fn test_tilemap_roundtrip() {
+        let mut registry = MockRegistry::new();
+        registry.register("tiles/level.tileset", 4);
+
+        let mut tilemap = Tilemap::new(AssetId::new(4), glam::Vec2::new(16.0, 16.0))
+            .with_layer("Ground")
+            .with_layer("Decor");
+        tilemap.layers[1].visible = false;
+        tilemap.layers[1].z_index = 2;
+        for x in -16..20 {
+            tilemap.set_tile(0, x, 3, Some(1));
+        }
+        tilemap.set_tile(1, 5, -1, Some(9));
+
+        let mut world = World::new();
+        let entity = world.spawn().with(tilemap.clone()).build();
+        let guid = world.guid(entity).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        let tiles = |map: &Tilemap| -> Vec<Vec<(i32, i32, u32)>> {
+            map.layers.iter().map(|layer| layer.tiles().collect()).collect()
+        };
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let saved = loaded.entities[0].components.tilemap.as_ref().unwrap();
+            assert_eq!(saved.tileset_path, "tiles/level.tileset");
+            // A row from -16 to 19 spans three chunks
+            assert_eq!(saved.layers[0].chunks.len(), 3);
+
+            let mut asset_loader = MockAssetLoader::new();
+            asset_loader.registry.register("tiles/level.tileset", 4);
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut asset_loader).unwrap();
+            let restored = spawned.get::<Tilemap>(entity_map[&guid]).unwrap();
+            assert_eq!(restored.tileset, AssetId::new(4));
+            assert_eq!(restored.cell_size, tilemap.cell_size);
+            assert_eq!(tiles(&restored), tiles(&tilemap));
+            assert!(!restored.layers[1].visible);
+            assert_eq!(restored.layers[1].z_index, 2);
+        }
+
+        // Restoring in place replaces painted tiles
+        world.get_mut::<Tilemap>(entity).unwrap().set_tile(0, 0, 0, Some(3));
+        scene.restore_into(&mut world, &mut MockAssetLoader::new()).unwrap();
+        assert_eq!(tiles(&world.get::<Tilemap>(entity).unwrap()), tiles(&tilemap));
+    }
+
+    #[test]
+    fn test_particle_emitter_roundtrip() {
+        let mut registry = MockRegistry::new();
+        registry.register("fx/spark.png", 6);
+
+        let mut effect = ParticleEffect::one_shot(20);
+        effect.gravity = glam::Vec2::new(0.0, -98.0);
+        effect.space = crate::SimulationSpace::Local;
+        let mut emitter = ParticleEmitter::new(effect, AssetId::new(6)).with_z_index(3);
+        emitter.burst(5);
+
+        let mut world = World::new();
+        let entity = world.spawn().with(emitter.clone()).build();
+        let guid = world.guid(entity).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let saved = loaded.entities[0].components.particle_emitter.as_ref().unwrap();
+            assert_eq!(saved.texture_path, "fx/spark.png");
+
+            let mut asset_loader = MockAssetLoader::new();
+            asset_loader.registry.register("fx/spark.png", 6);
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut asset_loader).unwrap();
+            let restored = spawned.get::<ParticleEmitter>(entity_map[&guid]).unwrap();
+            // Pending bursts aren't saved
+            assert_eq!(*restored, ParticleEmitter { pending: 0, ..emitter.clone() });
+        }
+    }
+
+    #[test]
+    fn test_animation_player_roundtrip() {
+        let mut registry = MockRegistry::new();
+        registry.register("anim/idle.anim", 9);
+
+        let player = AnimationPlayer::new(AssetId::new(9)).with_speed(0.5);
+        let mut world = World::new();
+        let entity = world.spawn().with(player.clone()).build();
+        let guid = world.guid(entity).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let saved = loaded.entities[0].components.animation_player.as_ref().unwrap();
+            assert_eq!(saved.clip_path, "anim/idle.anim");
+
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut MockAssetLoader::new()).unwrap();
+            assert_eq!(*spawned.get::<AnimationPlayer>(entity_map[&guid]).unwrap(), player);
+        }
+    }
+
+    #[test]
+    fn test_state_machine_roundtrip() {
+        let mut registry = MockRegistry::new();
+        registry.register("ai/guard.fsm", 11);
+
+        let machine = StateMachine::new(AssetId::new(11))
+            .with_parameter("alert", ParameterValue::Bool(true))
+            .with_parameter("range", ParameterValue::Number(4.5));
+        let mut world = World::new();
+        let entity = world.spawn().with(machine.clone()).build();
+        let guid = world.guid(entity).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let saved = loaded.entities[0].components.state_machine.as_ref().unwrap();
+            assert_eq!(saved.chart_path, "ai/guard.fsm");
+
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut MockAssetLoader::new()).unwrap();
+            assert_eq!(*spawned.get::<StateMachine>(entity_map[&guid]).unwrap(), machine);
+        }
+    }
+
+    #[test]
+    fn test_navigation_roundtrip() {
+        let mut registry = MockRegistry::new();
+        registry.register("levels/cave.navmesh", 12);
+
+        let mut agent = NavAgent::new(80.0).with_stopping_distance(4.0);
+        agent.set_destination(glam::Vec2::new(100.0, -20.0));
+        let obstacle = NavObstacle::new(glam::Vec2::new(16.0, 48.0));
+        let mut world = World::new();
+        let guard = world.spawn().with(agent.clone()).with(obstacle).build();
+        let level = world.spawn().with(NavRegion::from_navmesh(AssetId::new(12))).build();
+        let (guard, level) = (world.guid(guard).unwrap().get(), world.guid(level).unwrap().get());
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut MockAssetLoader::new()).unwrap();
+            let mut expected = agent.clone();
+            expected.requested = None;
+            assert_eq!(*spawned.get::<NavAgent>(entity_map[&guard]).unwrap(), expected);
+            assert_eq!(*spawned.get::<NavObstacle>(entity_map[&guard]).unwrap(), obstacle);
+            assert_eq!(*spawned.get::<NavRegion>(entity_map[&level]).unwrap(), NavRegion::from_navmesh(AssetId::new(12)));
+        }
+    }
+
+    #[test]
+    fn test_sprite_material_roundtrip() {
+        let mut registry = MockRegistry::new();
+        registry.register("materials/flash.material", 7);
+
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), glam::Vec2::splat(32.0)).with_material(AssetId::new(7)))
+            .with(MaterialParams::new().with("flash", crate::MaterialValue::Float(0.5)))
+            .build();
+        let plain = world.spawn().with(Sprite::new(AssetId::new(2), glam::Vec2::splat(32.0))).build();
+        let guid = world.guid(entity).unwrap().get();
+        let plain_guid = world.guid(plain).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let mut asset_loader = MockAssetLoader::new();
+            // The material was renumbered since the scene was saved
+            asset_loader.registry.register("materials/flash.material", 9);
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut asset_loader).unwrap();
+
+            let sprite = *spawned.get::<Sprite>(entity_map[&guid]).unwrap();
+            assert_eq!(sprite.material, AssetId::new(9));
+            let params = spawned.get::<MaterialParams>(entity_map[&guid]).unwrap();
+            assert_eq!(params.get("flash"), Some(crate::MaterialValue::Float(0.5)));
+            let plain = *spawned.get::<Sprite>(entity_map[&plain_guid]).unwrap();
+            assert_eq!(plain.material, AssetId::new(0));
+        }
+    }
+
+    #[test]
+    fn test_lights_and_normal_map_roundtrip() {
+        let mut registry = MockRegistry::new();
+        registry.register("sprites/wall_n.png", 4);
+
+        let light = PointLight2D {
+            color: [1.0, 0.5, 0.2],
+            radius: 96.0,
+            cast_shadows: true,
+            ..Default::default()
+        };
+        let occluder = LightOccluder2D::new(crate::OccluderShape::Polygon {
+            points: vec![glam::Vec2::ZERO, glam::Vec2::X, glam::Vec2::Y],
+        });
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), glam::Vec2::splat(32.0)).with_normal_map(AssetId::new(4)))
+            .with(light.clone())
+            .with(occluder.clone())
+            .build();
+        let ambient = world.spawn().with(GlobalLight2D::default()).with(SpotLight2D::default()).build();
+        let guid = world.guid(entity).unwrap().get();
+        let ambient_guid = world.guid(ambient).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let mut asset_loader = MockAssetLoader::new();
+            asset_loader.registry.register("sprites/wall_n.png", 8);
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut asset_loader).unwrap();
+
+            let entity = entity_map[&guid];
+            assert_eq!(spawned.get::<Sprite>(entity).unwrap().normal_map, AssetId::new(8));
+            assert_eq!(*spawned.get::<PointLight2D>(entity).unwrap(), light);
+            assert_eq!(*spawned.get::<LightOccluder2D>(entity).unwrap(), occluder);
+            assert!(spawned.has::<GlobalLight2D>(entity_map[&ambient_guid]));
+            assert!(spawned.has::<SpotLight2D>(entity_map[&ambient_guid]));
+        }
+    }
+
+    #[test]
+    fn test_shape_roundtrip() {
+        let registry = MockRegistry::new();
+        let shape = Shape::polygon(vec![glam::Vec2::ZERO, glam::Vec2::new(10.0, 0.0), glam::Vec2::new(0.0, 10.0)])
+            .with_fill(crate::ShapeFill::RadialGradient { center: [1.0; 4], edge: [0.0, 0.0, 0.0, 1.0] })
+            .with_stroke([0.2, 0.4, 0.6, 1.0], 2.0)
+            .with_z_index(-2);
+        let mut world = World::new();
+        let entity = world.spawn().with(shape.clone()).with(Transform::new()).build();
+        let guid = world.guid(entity).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut MockAssetLoader::new()).unwrap();
+            assert_eq!(*spawned.get::<Shape>(entity_map[&guid]).unwrap(), shape);
+        }
+    }
+
+    #[test]
+    fn test_ui_roundtrip_and_restore() {
+        let registry = MockRegistry::new();
+        let mut world = World::new();
+        let canvas = world.spawn().with(Canvas::new().with_sort_order(2)).build();
+        let rect = RectTransform::anchored(glam::Vec2::new(0.5, 1.0), glam::Vec2::new(0.0, -20.0), glam::Vec2::new(160.0, 48.0));
+        let label = UiLabel::new("Play", AssetId::new(7), 24.0).with_align(TextAlign::Center);
+        let button = world
+            .spawn()
+            .with(rect)
+            .with(UiPanel::new([0.1, 0.2, 0.3, 1.0]).with_corner_radius(8.0))
+            .with(UiButton::new())
+            .with(label.clone())
+            .build();
+        crate::ecs::hierarchy::add_child(&mut world, canvas, button).unwrap();
+        let guid = world.guid(button).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut MockAssetLoader::new()).unwrap();
+            let entity = entity_map[&guid];
+            assert_eq!(*spawned.get::<RectTransform>(entity).unwrap(), rect);
+            assert_eq!(*spawned.get::<UiLabel>(entity).unwrap(), label);
+            assert!(spawned.has::<UiButton>(entity));
+            assert!(!spawned.has::<UiSlider>(entity));
+            let parent = spawned.get::<crate::ecs::Parent>(entity).unwrap().0;
+            assert_eq!(spawned.get::<Canvas>(EntityHandle::new(parent)).unwrap().sort_order, 2);
+        }
+
+        // Restoring a snapshot removes UI components added since
+        world.set(button, UiSlider::new(0.0, 1.0)).unwrap();
+        world.get_mut::<UiLabel>(button).unwrap().text = "Quit".to_string();
+        scene.restore_into(&mut world, &mut MockAssetLoader::new()).unwrap();
+        assert!(!world.has::<UiSlider>(button));
+        assert_eq!(world.get::<UiLabel>(button).unwrap().text, "Play");
+    }
+
+    #[test]
+    fn test_sprite_draw_mode_roundtrip() {
+        let registry = MockRegistry::new();
+        let nine_slice = SpriteDrawMode::NineSlice {
+            border: crate::SliceBorder {
+                left: 4.0,
+                right: 6.0,
+                top: 2.0,
+                bottom: 8.0,
+            },
+        };
+        let mut world = World::new();
+        let panel = world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), glam::Vec2::new(200.0, 80.0)).with_draw_mode(nine_slice))
+            .build();
+        let plain = world.spawn().with(Sprite::new(AssetId::new(1), glam::Vec2::splat(16.0))).build();
+        let panel_guid = world.guid(panel).unwrap().get();
+        let plain_guid = world.guid(plain).unwrap().get();
+        let scene = Scene::from_world(&world, &registry);
+
+        let plain_serialized = scene.entities.iter().find(|e| e.id == plain_guid).unwrap();
+        assert!(plain_serialized.components.sprite.as_ref().unwrap().draw_mode.is_none());
+
+        for format in [SceneFormat::Json, SceneFormat::Ron, SceneFormat::Binary] {
+            let loaded = Scene::from_bytes(&scene.to_bytes(format).unwrap(), format).unwrap();
+            let mut asset_loader = MockAssetLoader::new();
+            let mut spawned = World::new();
+            let entity_map = loaded.spawn_into(&mut spawned, &mut asset_loader).unwrap();
+
+            assert_eq!(spawned.get::<Sprite>(entity_map[&panel_guid]).unwrap().draw_mode, nine_slice);
+            assert_eq!(
+                spawned.get::<Sprite>(entity_map[&plain_guid]).unwrap().draw_mode,
+                SpriteDrawMode::Simple
+            );
+        }
+    }
 }
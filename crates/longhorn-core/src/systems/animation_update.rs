@@ -0,0 +1,229 @@
+use crate::ecs::{find_path, AnimationClip, AnimationEvent, AnimationField, AnimationPlayer, EntityHandle};
+use crate::types::AssetId;
+use crate::world::World;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An event on a clip's timeline that playback passed
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationEventFired {
+    /// Entity with the `AnimationPlayer`
+    pub entity: EntityHandle,
+    pub clip: AssetId,
+    pub event: AnimationEvent,
+}
+
+/// Where a player's clips are this frame
+struct PlayerFrame {
+    entity: EntityHandle,
+    clip: Arc<AnimationClip>,
+    time: f32,
+    /// Clip fading out, its time and the current clip's weight
+    blend: Option<(Arc<AnimationClip>, f32, f32)>,
+}
+
+/// Advance every playing `AnimationPlayer` by `dt` seconds and apply its clip
+///
+/// `clips` looks up loaded clips; players whose clip isn't loaded are left
+/// alone. While crossfading, fields of both clips are mixed by the blend
+/// weight; fields only the new clip animates fade in from their current
+/// values and fields only the old one animates keep theirs. Non-looping
+/// clips stop at their end. Returns the events playback passed going
+/// forwards, in order.
+pub fn update_animations(
+    world: &mut World,
+    dt: f32,
+    clips: impl Fn(AssetId) -> Option<Arc<AnimationClip>>,
+) -> Vec<AnimationEventFired> {
+    let mut frames = Vec::new();
+    let mut fired = Vec::new();
+    for (entity, player) in world.query_mut::<&mut AnimationPlayer>().iter() {
+        if !player.playing {
+            continue;
+        }
+        let Some(clip) = clips(player.clip) else {
+            continue;
+        };
+        let entity = EntityHandle::new(entity);
+
+        let prev = player.started.then_some(player.time);
+        let (time, finished) = advance_time(&clip, player.time, dt * player.speed);
+        if player.speed >= 0.0 {
+            passed_events(&clip, prev, time, dt * player.speed, |event| {
+                fired.push(AnimationEventFired {
+                    entity,
+                    clip: player.clip,
+                    event: event.clone(),
+                });
+            });
+        }
+        player.time = time;
+        player.started = true;
+        if finished {
+            player.playing = false;
+        }
+
+        let mut blend = None;
+        if let Some(fading) = &mut player.blend {
+            fading.elapsed += dt;
+            if let Some(from) = clips(fading.clip).filter(|_| fading.elapsed < fading.duration) {
+                fading.time = advance_time(&from, fading.time, dt * player.speed).0;
+                blend = Some((from, fading.time));
+            }
+        }
+        let weight = player.blend_weight();
+        if blend.is_none() {
+            player.blend = None;
+        }
+
+        frames.push(PlayerFrame {
+            entity,
+            clip,
+            time,
+            blend: blend.map(|(from, from_time)| (from, from_time, weight)),
+        });
+    }
+
+    for frame in frames {
+        let Some((from, from_time, weight)) = frame.blend else {
+            frame.clip.apply(world, frame.entity, frame.time);
+            continue;
+        };
+
+        let old: HashMap<(&str, &AnimationField), f32> = from
+            .sample(from_time)
+            .map(|(track, value)| ((track.path.as_str(), &track.field), value))
+            .collect();
+        for (track, value) in frame.clip.sample(frame.time) {
+            let Some(target) = find_path(world, frame.entity, &track.path) else {
+                continue;
+            };
+            let start = old
+                .get(&(track.path.as_str(), &track.field))
+                .copied()
+                .or_else(|| track.field.get(world, target));
+            if let Some(start) = start {
+                track.field.set(world, target, start + (value - start) * weight);
+            }
+        }
+    }
+    fired
+}
+
+/// Move a clip's playback position by `delta` seconds, wrapping looping
+/// clips and clamping others; also returns whether a clip reached its end
+fn advance_time(clip: &AnimationClip, time: f32, delta: f32) -> (f32, bool) {
+    let length = clip.length();
+    let time = time + delta;
+    if clip.looping && length > 0.0 {
+        (time.rem_euclid(length), false)
+    } else {
+        (time.clamp(0.0, length), (delta >= 0.0 && time >= length) || (delta < 0.0 && time <= 0.0))
+    }
+}
+
+/// Call `fire` for the events between playback positions `prev` and `time`,
+/// a forward move of `delta` seconds
+///
+/// `prev` is `None` on the first update, which includes events at the start.
+fn passed_events(clip: &AnimationClip, prev: Option<f32>, time: f32, delta: f32, mut fire: impl FnMut(&AnimationEvent)) {
+    let in_range = |event: &AnimationEvent, from: Option<f32>, to: f32| {
+        from.map_or(event.time >= 0.0, |from| event.time > from) && event.time <= to
+    };
+    let length = clip.length();
+    let start = prev.unwrap_or(0.0);
+    // Looping clips wrap at most once per frame's worth of events
+    let wrapped = clip.looping && length > 0.0 && start + delta >= length;
+    if !wrapped {
+        clip.events.iter().filter(|e| in_range(e, prev, time)).for_each(&mut fire);
+        return;
+    }
+    clip.events.iter().filter(|e| in_range(e, prev, length)).for_each(&mut fire);
+    clip.events.iter().filter(|e| in_range(e, None, time)).for_each(&mut fire);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{add_child, Name, TweenProperty};
+    use crate::math::Transform;
+
+    const X: AnimationField = AnimationField::Property(TweenProperty::PositionX);
+
+    fn clip(keys: &[(f32, f32)]) -> AnimationClip {
+        let mut clip = AnimationClip::new(0.0);
+        for &(time, value) in keys {
+            clip.set_key("", X, time, value);
+        }
+        clip
+    }
+
+    fn x(world: &World, entity: EntityHandle) -> f32 {
+        world.get::<Transform>(entity).unwrap().position.x
+    }
+
+    #[test]
+    fn test_playback_events_and_child_tracks() {
+        let mut clip = clip(&[(0.0, 0.0), (1.0, 10.0)]).with_looping(true);
+        clip.set_key("Arm", AnimationField::Property(TweenProperty::Rotation), 0.5, 2.0);
+        clip.add_event(0.0, "start");
+        clip.add_event(0.75, "step");
+        let clip = Arc::new(clip);
+        let clips = |id: AssetId| (id == AssetId::new(1)).then(|| clip.clone());
+
+        let mut world = World::new();
+        let root = world
+            .spawn()
+            .with(Transform::new())
+            .with(AnimationPlayer::new(AssetId::new(1)))
+            .build();
+        let arm = world.spawn().with(Name::new("Arm")).with(Transform::new()).build();
+        add_child(&mut world, root, arm).unwrap();
+
+        let names = |fired: Vec<AnimationEventFired>| fired.into_iter().map(|f| f.event.name).collect::<Vec<_>>();
+        assert_eq!(names(update_animations(&mut world, 0.5, clips)), vec!["start"]);
+        assert_eq!(x(&world, root), 5.0);
+        assert_eq!(world.get::<Transform>(arm).unwrap().rotation, 2.0);
+        assert!(update_animations(&mut world, 0.125, clips).is_empty());
+        // Wrapping around the end passes the rest of this loop and the start of the next
+        assert_eq!(names(update_animations(&mut world, 0.625, clips)), vec!["step", "start"]);
+        assert_eq!(x(&world, root), 2.5);
+
+        // Missing clips and stopped players are left alone
+        world.get_mut::<AnimationPlayer>(root).unwrap().clip = AssetId::new(2);
+        update_animations(&mut world, 0.25, clips);
+        assert_eq!(x(&world, root), 2.5);
+    }
+
+    #[test]
+    fn test_crossfade_and_clamping() {
+        let walk = Arc::new(clip(&[(0.0, 0.0), (2.0, 0.0)]));
+        let jump = Arc::new(clip(&[(0.0, 10.0), (1.0, 10.0)]));
+        let clips = |id: AssetId| match id.0 {
+            1 => Some(walk.clone()),
+            2 => Some(jump.clone()),
+            _ => None,
+        };
+
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(Transform::new())
+            .with(AnimationPlayer::new(AssetId::new(1)))
+            .build();
+        update_animations(&mut world, 0.5, clips);
+        world.get_mut::<AnimationPlayer>(entity).unwrap().crossfade(AssetId::new(2), 0.5);
+
+        update_animations(&mut world, 0.25, clips);
+        assert_eq!(x(&world, entity), 5.0);
+        update_animations(&mut world, 0.25, clips);
+        assert_eq!(x(&world, entity), 10.0);
+        assert_eq!(world.get::<AnimationPlayer>(entity).unwrap().blend, None);
+
+        // Non-looping clips hold their last frame and stop
+        update_animations(&mut world, 1.0, clips);
+        let player = world.get::<AnimationPlayer>(entity).unwrap();
+        assert!(!player.playing);
+        assert_eq!(player.time, 1.0);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::ecs::{Camera, EntityHandle};
+use crate::math::{GlobalTransform, Transform};
+use crate::world::World;
+use glam::Vec2;
+
+/// Advance every camera's follow, bounds clamping and shake by `dt` seconds
+///
+/// Each camera's `viewport_size` is first set from its viewport on a screen of
+/// `screen_size` pixels, so bounds clamping sees the area it will render.
+/// Follow targets are read from their GlobalTransform, or their Transform if
+/// transforms haven't been propagated yet.
+///
+/// Cameras are written through `query_mut`, so this doesn't report changes.
+pub fn update_cameras(world: &mut World, dt: f32, screen_size: Vec2) {
+    let targets: Vec<(hecs::Entity, Option<Vec2>)> = world
+        .query::<&Camera>()
+        .iter()
+        .map(|(entity, camera)| {
+            let target = camera
+                .follow
+                .as_ref()
+                .and_then(|follow| follow.target.resolve(world))
+                .and_then(|target| target_position(world, target));
+            (entity, target)
+        })
+        .collect();
+
+    for (entity, target) in targets {
+        let Ok(mut camera) = world.inner().get::<&mut Camera>(entity) else {
+            continue;
+        };
+        if screen_size.x > 0.0 && screen_size.y > 0.0 {
+            let pixels = camera.viewport.to_pixels(screen_size.x as u32, screen_size.y as u32);
+            if !pixels.is_empty() {
+                camera.viewport_size = Vec2::new(pixels.width as f32, pixels.height as f32);
+            }
+        }
+        camera.update(dt, target);
+    }
+}
+
+/// Enabled cameras in render order (ascending `order`, then entity)
+pub fn cameras_in_render_order(world: &World) -> Vec<(EntityHandle, Camera)> {
+    let mut cameras: Vec<(EntityHandle, Camera)> = world
+        .query::<&Camera>()
+        .iter()
+        .filter(|(_, camera)| camera.enabled)
+        .map(|(entity, camera)| (EntityHandle::new(entity), camera.clone()))
+        .collect();
+    cameras.sort_by_key(|(entity, camera)| (camera.order, entity.id.to_bits()));
+    cameras
+}
+
+/// World position of a follow target
+fn target_position(world: &World, target: EntityHandle) -> Option<Vec2> {
+    if let Ok(global) = world.get::<GlobalTransform>(target) {
+        return Some(global.position);
+    }
+    world.get::<Transform>(target).ok().map(|t| t.position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{CameraFollow, EntityRef, ViewportRect};
+
+    #[test]
+    fn test_camera_follows_target() {
+        let mut world = World::new();
+        let player = world
+            .spawn()
+            .with(Transform::from_position(Vec2::new(50.0, -20.0)))
+            .build();
+        let follow = CameraFollow::new(EntityRef::to_entity(&world, player));
+        let camera = world
+            .spawn()
+            .with(Camera::new(100.0, 100.0).with_follow(follow))
+            .build();
+
+        update_cameras(&mut world, 0.016, Vec2::new(800.0, 600.0));
+
+        let camera = world.get::<Camera>(camera).unwrap();
+        assert_eq!(camera.position, Vec2::new(50.0, -20.0));
+        assert_eq!(camera.viewport_size, Vec2::new(800.0, 600.0));
+    }
+
+    #[test]
+    fn test_render_order() {
+        let mut world = World::new();
+        let minimap = world
+            .spawn()
+            .with(Camera::new(1.0, 1.0).with_order(1).with_viewport(ViewportRect::new(0.75, 0.0, 0.25, 0.25)))
+            .build();
+        let main = world.spawn().with(Camera::new(1.0, 1.0)).build();
+        let mut disabled = Camera::new(1.0, 1.0);
+        disabled.enabled = false;
+        world.spawn().with(disabled).build();
+
+        let order: Vec<_> = cameras_in_render_order(&world).into_iter().map(|(e, _)| e).collect();
+        assert_eq!(order, vec![main, minimap]);
+    }
+}
@@ -1,3 +1,15 @@
+pub mod animation_update;
+pub mod camera_update;
+pub mod navigation_update;
+pub mod particle_update;
+pub mod state_machine_update;
 pub mod transform_propagation;
+pub mod tween_update;
 
+pub use animation_update::*;
+pub use camera_update::*;
+pub use navigation_update::*;
+pub use particle_update::*;
+pub use state_machine_update::*;
 pub use transform_propagation::*;
+pub use tween_update::*;
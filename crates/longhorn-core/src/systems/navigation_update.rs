@@ -0,0 +1,208 @@
+use crate::ecs::{EntityHandle, NavAgent, NavStatus};
+use crate::math::{GlobalTransform, Transform};
+use crate::navigation::{NavSurface, Navigation};
+use crate::world::World;
+use glam::Vec2;
+
+/// Something a NavAgent did that the rest of the engine reacts to
+#[derive(Debug, Clone, PartialEq)]
+pub enum NavigationEvent {
+    /// The agent got within stopping distance of its destination
+    Arrived { entity: EntityHandle, destination: Vec2 },
+    /// No path leads to the agent's destination
+    Unreachable { entity: EntityHandle, destination: Vec2 },
+}
+
+/// Move every NavAgent along its path by `dt` seconds
+///
+/// Updates the obstacles on the grid first. Agents with a new destination
+/// ask `navigation` for a path and wait for it, and moving agents whose
+/// path the surface has since blocked ask for a new one. Then the budget
+/// is spent on pending searches, and agents with paths move their
+/// Transform towards the next waypoint. Agents are placed at their
+/// GlobalTransform, or their Transform if transforms haven't been
+/// propagated yet. Returns the agents that arrived or found no path.
+///
+/// Agents are written through `query_mut`, so this doesn't report changes.
+pub fn update_navigation(world: &mut World, navigation: &mut Navigation, dt: f32) -> Vec<NavigationEvent> {
+    navigation.update_obstacles(world);
+    let revision = navigation.surface().map_or(0, NavSurface::revision);
+    let mut out = Vec::new();
+
+    for (entity, (agent, global, local)) in world
+        .query_mut::<(&mut NavAgent, Option<&GlobalTransform>, Option<&Transform>)>()
+        .iter()
+    {
+        let entity = EntityHandle::new(entity);
+        let Some(position) = global.map(|global| global.position).or(local.map(|local| local.position)) else {
+            continue;
+        };
+        let Some(destination) = agent.destination else {
+            if agent.status != NavStatus::Idle {
+                navigation.cancel(entity);
+                agent.path.clear();
+                agent.requested = None;
+                agent.status = NavStatus::Idle;
+            }
+            continue;
+        };
+
+        let blocked = agent.status == NavStatus::Moving
+            && agent.revision != revision
+            && !navigation.surface().is_some_and(|surface| is_clear(surface, position, &agent.path));
+        let lost = agent.status == NavStatus::Pending && !navigation.is_pending(entity);
+        agent.revision = revision;
+        if agent.requested == Some(destination) && !blocked && !lost {
+            continue;
+        }
+
+        agent.requested = Some(destination);
+        agent.path.clear();
+        if navigation.request(entity, position, destination) {
+            agent.status = NavStatus::Pending;
+        } else {
+            agent.status = NavStatus::Unreachable;
+            out.push(NavigationEvent::Unreachable { entity, destination });
+        }
+    }
+
+    for (entity, path) in navigation.step() {
+        let Ok(mut agent) = world.get_mut::<NavAgent>(entity) else {
+            continue;
+        };
+        match path {
+            Some(path) => {
+                agent.path = path;
+                agent.status = NavStatus::Moving;
+            }
+            None => {
+                agent.status = NavStatus::Unreachable;
+                out.extend(agent.destination.map(|destination| NavigationEvent::Unreachable { entity, destination }));
+            }
+        }
+    }
+
+    for (entity, (agent, transform, global)) in world
+        .query_mut::<(&mut NavAgent, &mut Transform, Option<&GlobalTransform>)>()
+        .iter()
+    {
+        if agent.status != NavStatus::Moving {
+            continue;
+        }
+        let start = global.map_or(transform.position, |global| global.position);
+        let position = steer(agent, start, agent.speed * dt);
+        transform.position += position - start;
+        if agent.path.is_empty() {
+            agent.status = NavStatus::Arrived;
+            out.extend(agent.destination.map(|destination| NavigationEvent::Arrived {
+                entity: EntityHandle::new(entity),
+                destination,
+            }));
+        }
+    }
+    out
+}
+
+/// Check if an agent can still follow its path from where it is
+fn is_clear(surface: &NavSurface, position: Vec2, path: &[Vec2]) -> bool {
+    std::iter::once(position)
+        .chain(path.iter().copied())
+        .zip(path.iter().copied())
+        .all(|(a, b)| surface.is_clear(a, b))
+}
+
+/// Walk up to `distance` along the agent's path from `position`, dropping
+/// the waypoints passed, and return where the agent ends up
+///
+/// The last waypoint is reached at stopping distance.
+fn steer(agent: &mut NavAgent, mut position: Vec2, mut distance: f32) -> Vec2 {
+    while let Some(&next) = agent.path.first() {
+        let last = agent.path.len() == 1;
+        let offset = next - position;
+        let length = offset.length();
+        let travel = if last { (length - agent.stopping_distance).max(0.0) } else { length };
+        if travel > distance {
+            return position + offset / length * distance;
+        }
+        position += offset.normalize_or_zero() * travel;
+        distance -= travel;
+        agent.path.remove(0);
+    }
+    position
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::NavObstacle;
+    use crate::math::Rect;
+    use crate::navigation::NavGrid;
+
+    /// 5x5 grid of unit cells with a wall across x = 2, open at the top
+    fn navigation() -> Navigation {
+        let wall = Rect::new(Vec2::new(2.0, 0.0), Vec2::new(3.0, 4.0));
+        let grid = NavGrid::from_colliders(Rect::new(Vec2::ZERO, Vec2::splat(5.0)), Vec2::ONE, [wall]);
+        let mut navigation = Navigation::new();
+        navigation.set_surface(Some(NavSurface::Grid(grid)));
+        navigation
+    }
+
+    #[test]
+    fn test_agents_walk_around_walls() {
+        let mut navigation = navigation();
+        let mut world = World::new();
+        let agent = world
+            .spawn()
+            .with(Transform::from_position(Vec2::new(0.5, 0.5)))
+            .with(NavAgent::new(4.0).with_stopping_distance(0.1))
+            .build();
+        world.get_mut::<NavAgent>(agent).unwrap().set_destination(Vec2::new(4.5, 0.5));
+
+        let mut events = Vec::new();
+        let mut highest: f32 = 0.0;
+        for _ in 0..20 {
+            events.extend(update_navigation(&mut world, &mut navigation, 0.25));
+            highest = highest.max(world.get::<Transform>(agent).unwrap().position.y);
+        }
+        let destination = Vec2::new(4.5, 0.5);
+        assert_eq!(events, vec![NavigationEvent::Arrived { entity: agent, destination }]);
+        assert!(highest > 4.0, "went over the wall");
+        let position = world.get::<Transform>(agent).unwrap().position;
+        assert!((position.distance(destination) - 0.1).abs() < 1e-4, "{:?}", position);
+        assert_eq!(world.get::<NavAgent>(agent).unwrap().status, NavStatus::Arrived);
+
+        // Walled-in destinations can't be reached
+        world.get_mut::<NavAgent>(agent).unwrap().set_destination(Vec2::new(2.5, 0.5));
+        let events = update_navigation(&mut world, &mut navigation, 0.25);
+        assert!(matches!(events[..], [NavigationEvent::Unreachable { .. }]));
+        world.get_mut::<NavAgent>(agent).unwrap().stop();
+        update_navigation(&mut world, &mut navigation, 0.25);
+        assert_eq!(world.get::<NavAgent>(agent).unwrap().status, NavStatus::Idle);
+    }
+
+    #[test]
+    fn test_agents_repath_around_new_obstacles() {
+        let mut navigation = navigation();
+        let mut world = World::new();
+        let agent = world
+            .spawn()
+            .with(Transform::from_position(Vec2::new(3.5, 0.5)))
+            .with(NavAgent::new(1.0))
+            .build();
+        world.get_mut::<NavAgent>(agent).unwrap().set_destination(Vec2::new(3.5, 3.5));
+        update_navigation(&mut world, &mut navigation, 0.1);
+        assert_eq!(world.get::<NavAgent>(agent).unwrap().path, vec![Vec2::new(3.5, 3.5)]);
+
+        // A crate dropped in the way sends the agent around it
+        world
+            .spawn()
+            .with(Transform::from_position(Vec2::new(3.5, 2.5)))
+            .with(NavObstacle::new(Vec2::splat(0.5)))
+            .build();
+        update_navigation(&mut world, &mut navigation, 0.1);
+        let agent = world.get::<NavAgent>(agent).unwrap();
+        assert_eq!(agent.status, NavStatus::Moving);
+        assert!(agent.path.len() > 1);
+        assert!(agent.path.iter().all(|point| point.x > 3.0), "{:?}", agent.path);
+    }
+}
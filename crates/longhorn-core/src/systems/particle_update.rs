@@ -0,0 +1,255 @@
+use crate::ecs::{EntityHandle, Particle, ParticleEffect, ParticleEmitter, ParticleState, SimulationSpace};
+use crate::math::{GlobalTransform, Transform};
+use crate::world::World;
+use glam::Vec2;
+
+/// Advance every particle emitter by `dt` seconds
+///
+/// Adds a `ParticleState` to entities with a `ParticleEmitter` (and removes
+/// it from entities that lost theirs), moves and ages live particles, then
+/// emits new ones from the emitter's GlobalTransform, or its Transform if
+/// transforms haven't been propagated yet. Emitters without either don't
+/// emit.
+///
+/// Emitters are written through `query_mut`, so this doesn't report changes.
+pub fn update_particles(world: &mut World, dt: f32) {
+    let added: Vec<_> = world
+        .inner()
+        .query::<&ParticleEmitter>()
+        .without::<&ParticleState>()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect();
+    let orphaned: Vec<_> = world
+        .inner()
+        .query::<&ParticleState>()
+        .without::<&ParticleEmitter>()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in added {
+        let _ = world.insert_untracked(EntityHandle::new(entity), ParticleState::new(entity.id()));
+    }
+    for entity in orphaned {
+        let _ = world.remove_untracked::<ParticleState>(EntityHandle::new(entity));
+    }
+
+    for (_, (emitter, state, global, local)) in world
+        .query_mut::<(&mut ParticleEmitter, &mut ParticleState, Option<&GlobalTransform>, Option<&Transform>)>()
+        .iter()
+    {
+        let transform = global.copied().or_else(|| local.map(GlobalTransform::from_transform));
+        simulate(emitter, state, transform, dt);
+    }
+}
+
+fn simulate(emitter: &mut ParticleEmitter, state: &mut ParticleState, transform: Option<GlobalTransform>, dt: f32) {
+    let effect = &emitter.effect;
+    state.particles.retain_mut(|particle| {
+        particle.age += dt;
+        particle.velocity += effect.gravity * dt;
+        particle.position += particle.velocity * dt;
+        particle.rotation += particle.spin * dt;
+        particle.age < particle.lifetime
+    });
+
+    // (Re)start the cycle when the emitter starts playing
+    if emitter.playing && !state.playing {
+        state.time = 0.0;
+        state.carry = 0.0;
+    }
+    let mut count = std::mem::take(&mut emitter.pending);
+    if emitter.playing {
+        count = count.saturating_add(advance_cycle(effect, state, dt));
+        if !effect.looping && state.time >= effect.duration {
+            emitter.playing = false;
+        }
+    }
+    state.playing = emitter.playing;
+
+    let Some(transform) = transform else {
+        return;
+    };
+    let room = effect.max_particles.saturating_sub(state.particles.len());
+    for _ in 0..(count as usize).min(room) {
+        let particle = spawn(effect, state, &transform);
+        state.particles.push(particle);
+    }
+}
+
+/// Move the emission cycle forward by `dt`
+///
+/// # Returns
+/// The number of particles due from `rate` and bursts in that time
+fn advance_cycle(effect: &ParticleEffect, state: &mut ParticleState, dt: f32) -> u32 {
+    let duration = effect.duration.max(f32::EPSILON);
+    let mut count = 0u32;
+    let mut remaining = dt;
+    loop {
+        let start = state.time;
+        let end = (start + remaining).min(duration).max(start);
+        let emitting = end - start;
+
+        let owed = effect.rate.max(0.0) * emitting + state.carry;
+        state.carry = owed.fract();
+        count = count.saturating_add(owed as u32);
+
+        for burst in &effect.bursts {
+            // A burst at 0 fires as the cycle starts, one at the end as it ends
+            let due = if start == 0.0 { burst.time >= start } else { burst.time > start };
+            if due && burst.time <= end {
+                count = count.saturating_add(burst.count);
+            }
+        }
+
+        state.time = start + remaining;
+        remaining -= emitting;
+        if state.time < duration || !effect.looping {
+            return count;
+        }
+        state.time = 0.0;
+        if remaining <= 0.0 {
+            return count;
+        }
+    }
+}
+
+/// Emit one particle
+fn spawn(effect: &ParticleEffect, state: &mut ParticleState, transform: &GlobalTransform) -> Particle {
+    let angle = (effect.direction + effect.spread * (state.random() - 0.5)).to_radians();
+    let speed = state.random_range(effect.speed);
+    let mut velocity = Vec2::from_angle(angle) * speed;
+
+    // Uniform over the circle's area
+    let offset_angle = state.random() * std::f32::consts::TAU;
+    let offset = Vec2::from_angle(offset_angle) * effect.radius * state.random().sqrt();
+
+    let mut rotation = 0.0;
+    let position = match effect.space {
+        SimulationSpace::Local => offset,
+        SimulationSpace::World => {
+            velocity = Vec2::from_angle(transform.rotation).rotate(velocity);
+            rotation = transform.rotation;
+            transform.transform_point(offset)
+        }
+    };
+
+    Particle {
+        position,
+        velocity,
+        rotation,
+        spin: state.random_range(effect.spin).to_radians(),
+        age: 0.0,
+        lifetime: state.random_range(effect.lifetime),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::Burst;
+
+    fn effect() -> ParticleEffect {
+        ParticleEffect {
+            rate: 10.0,
+            lifetime: [0.45, 0.45],
+            speed: [100.0, 100.0],
+            spread: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rate_lifetime_and_max_particles() {
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(ParticleEmitter::new(effect(), crate::AssetId::new(0)))
+            .with(Transform::from_position(Vec2::new(10.0, 0.0)))
+            .build();
+
+        // 10 per second for 0.3 seconds, in steps of 0.1
+        for _ in 0..3 {
+            update_particles(&mut world, 0.1);
+        }
+        let state = world.get::<ParticleState>(entity).unwrap();
+        assert_eq!(state.len(), 3);
+        // The oldest particle moved up for two steps at 100 units per second
+        let oldest = state.particles()[0];
+        assert!((oldest.position - Vec2::new(10.0, 20.0)).length() < 1e-3);
+        drop(state);
+
+        // Particles die before their sixth step, so the count levels off at 5
+        for _ in 0..10 {
+            update_particles(&mut world, 0.1);
+        }
+        assert_eq!(world.get::<ParticleState>(entity).unwrap().len(), 5);
+
+        world.get_mut::<ParticleEmitter>(entity).unwrap().effect.max_particles = 2;
+        for _ in 0..10 {
+            update_particles(&mut world, 0.1);
+        }
+        assert_eq!(world.get::<ParticleState>(entity).unwrap().len(), 2);
+
+        // Losing the emitter drops its state
+        world.remove::<ParticleEmitter>(entity).unwrap();
+        update_particles(&mut world, 0.1);
+        assert!(world.get::<ParticleState>(entity).is_err());
+    }
+
+    #[test]
+    fn test_bursts_and_one_shot_effects() {
+        let mut world = World::new();
+        let mut effect = ParticleEffect::one_shot(8);
+        effect.bursts.push(Burst { time: 0.5, count: 4 });
+        let entity = world
+            .spawn()
+            .with(ParticleEmitter::new(effect, crate::AssetId::new(0)))
+            .with(Transform::default())
+            .build();
+
+        update_particles(&mut world, 0.1);
+        assert_eq!(world.get::<ParticleState>(entity).unwrap().len(), 8);
+        for _ in 0..5 {
+            update_particles(&mut world, 0.1);
+        }
+        assert_eq!(world.get::<ParticleState>(entity).unwrap().len(), 12);
+
+        // The cycle ended, so the emitter stopped; bursts still emit
+        for _ in 0..12 {
+            update_particles(&mut world, 0.1);
+        }
+        assert!(!world.get::<ParticleEmitter>(entity).unwrap().playing);
+        assert!(world.get::<ParticleState>(entity).unwrap().is_empty());
+        world.get_mut::<ParticleEmitter>(entity).unwrap().burst(3);
+        update_particles(&mut world, 0.1);
+        assert_eq!(world.get::<ParticleState>(entity).unwrap().len(), 3);
+
+        // Playing again restarts the cycle
+        world.get_mut::<ParticleEmitter>(entity).unwrap().play();
+        update_particles(&mut world, 0.1);
+        assert_eq!(world.get::<ParticleState>(entity).unwrap().len(), 11);
+    }
+
+    #[test]
+    fn test_simulation_space() {
+        let mut world = World::new();
+        let mut local = effect();
+        local.space = SimulationSpace::Local;
+        let world_space = world
+            .spawn()
+            .with(ParticleEmitter::new(effect(), crate::AssetId::new(0)))
+            .with(Transform::from_position(Vec2::new(50.0, 0.0)))
+            .build();
+        let local_space = world
+            .spawn()
+            .with(ParticleEmitter::new(local, crate::AssetId::new(0)))
+            .with(Transform::from_position(Vec2::new(50.0, 0.0)))
+            .build();
+
+        update_particles(&mut world, 0.1);
+        let first = |entity| world.get::<ParticleState>(entity).unwrap().particles()[0].position;
+        assert_eq!(first(world_space), Vec2::new(50.0, 0.0));
+        assert_eq!(first(local_space), Vec2::ZERO);
+    }
+}
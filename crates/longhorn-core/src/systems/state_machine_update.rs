@@ -0,0 +1,271 @@
+use crate::ecs::{EntityHandle, State, StateAction, StateChart, StateMachine};
+use crate::types::AssetId;
+use crate::world::World;
+use std::sync::Arc;
+
+/// Most transitions a machine takes in one update, so transitions without
+/// requirements that lead back to each other can't hang the frame
+const MAX_TRANSITIONS_PER_UPDATE: usize = 8;
+
+/// Something a state machine did that the rest of the engine reacts to
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateMachineEvent {
+    /// A state was entered; parents are entered before their children
+    Entered { entity: EntityHandle, state: String },
+    /// A state was exited; children are exited before their parents
+    Exited { entity: EntityHandle, state: String },
+    /// An `emit` action ran
+    Emit {
+        entity: EntityHandle,
+        event: String,
+        data: serde_json::Value,
+    },
+    /// A `play_animation` action ran
+    PlayAnimation {
+        entity: EntityHandle,
+        clip: String,
+        crossfade: f32,
+    },
+}
+
+/// Advance every `StateMachine` by `dt` seconds
+///
+/// `charts` looks up loaded charts; machines whose chart isn't loaded are
+/// left alone. A machine's first update enters the chart's initial states.
+/// After that, the transitions of the active states are checked innermost
+/// state first, and the first enabled one is taken: states up to the one the
+/// source and target share are exited and the target and its initial
+/// children entered. Taking a transition to an active state exits and
+/// re-enters it. Each event sent to a machine can fire one transition and
+/// is dropped at the end of the update. Returns what the machines did, in
+/// order.
+pub fn update_state_machines(
+    world: &mut World,
+    dt: f32,
+    charts: impl Fn(AssetId) -> Option<Arc<StateChart>>,
+) -> Vec<StateMachineEvent> {
+    let mut out = Vec::new();
+    for (entity, machine) in world.query_mut::<&mut StateMachine>().iter() {
+        let Some(chart) = charts(machine.chart) else {
+            continue;
+        };
+        let entity = EntityHandle::new(entity);
+
+        // Drop states removed from the chart since they were entered
+        if machine.active.iter().any(|name| chart.find(name).is_none()) {
+            machine.restart();
+        }
+        if machine.active.is_empty() {
+            for (name, value) in &chart.parameters {
+                machine.parameters.entry(name.clone()).or_insert(*value);
+            }
+            if let Some(initial) = chart.initial_state() {
+                enter(machine, entity, 0, &[initial], &mut out);
+            }
+        } else {
+            machine.elapsed.iter_mut().for_each(|elapsed| *elapsed += dt);
+        }
+
+        for _ in 0..MAX_TRANSITIONS_PER_UPDATE {
+            let Some((depth, index)) = enabled_transition(&chart, machine) else {
+                break;
+            };
+            let source = chart.find(&machine.active[depth]).expect("active states are in the chart");
+            let transition = &source.transitions[index];
+            if let Some(event) = &transition.event {
+                let sent = machine.events.iter().position(|e| e == event).expect("enabled by an event");
+                machine.events.remove(sent);
+            }
+            let Some(target) = chart.path_to(&transition.to) else {
+                break;
+            };
+
+            // Keep the states the source and target share, but always leave
+            // the target itself so it's re-entered
+            let shared = machine
+                .active
+                .iter()
+                .zip(&target)
+                .take(depth + 1)
+                .take_while(|(active, state)| **active == state.name)
+                .count()
+                .min(target.len() - 1);
+            exit(&chart, machine, entity, shared, &mut out);
+            enter(machine, entity, shared, &target[shared..], &mut out);
+        }
+        machine.events.clear();
+    }
+    out
+}
+
+/// First enabled transition of the active states, innermost state first, as
+/// the depth of its state and its index there
+fn enabled_transition(chart: &StateChart, machine: &StateMachine) -> Option<(usize, usize)> {
+    (0..machine.active.len()).rev().find_map(|depth| {
+        let state = chart.find(&machine.active[depth])?;
+        let elapsed = machine.elapsed[depth];
+        state
+            .transitions
+            .iter()
+            .position(|transition| {
+                transition.event.as_ref().is_none_or(|event| machine.events.contains(event))
+                    && transition.after.is_none_or(|after| elapsed >= after)
+                    && transition.conditions.iter().all(|condition| condition.test(&machine.parameters))
+            })
+            .map(|index| (depth, index))
+    })
+}
+
+/// Exit the active states deeper than `depth`, innermost first
+fn exit(chart: &StateChart, machine: &mut StateMachine, entity: EntityHandle, depth: usize, out: &mut Vec<StateMachineEvent>) {
+    while machine.active.len() > depth {
+        let name = machine.active.pop().expect("deeper than depth");
+        machine.elapsed.pop();
+        if let Some(state) = chart.find(&name) {
+            run_actions(machine, entity, &state.on_exit, out);
+        }
+        out.push(StateMachineEvent::Exited { entity, state: name });
+    }
+}
+
+/// Enter `path`, starting at `depth`, then the initial children of its last state
+fn enter(
+    machine: &mut StateMachine,
+    entity: EntityHandle,
+    depth: usize,
+    path: &[&State],
+    out: &mut Vec<StateMachineEvent>,
+) {
+    machine.active.truncate(depth);
+    machine.elapsed.truncate(depth);
+    let initials = std::iter::successors(path.last().and_then(|state| state.initial_state()), |state| state.initial_state());
+    for state in path.iter().copied().chain(initials) {
+        machine.active.push(state.name.clone());
+        machine.elapsed.push(0.0);
+        out.push(StateMachineEvent::Entered {
+            entity,
+            state: state.name.clone(),
+        });
+        run_actions(machine, entity, &state.on_enter, out);
+    }
+}
+
+fn run_actions(machine: &mut StateMachine, entity: EntityHandle, actions: &[StateAction], out: &mut Vec<StateMachineEvent>) {
+    for action in actions {
+        match action {
+            StateAction::Set { parameter, value } => machine.set_parameter(parameter.clone(), *value),
+            StateAction::Emit { event, data } => out.push(StateMachineEvent::Emit {
+                entity,
+                event: event.clone(),
+                data: data.clone(),
+            }),
+            StateAction::PlayAnimation { clip, crossfade } => out.push(StateMachineEvent::PlayAnimation {
+                entity,
+                clip: clip.clone(),
+                crossfade: *crossfade,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Compare, ParameterValue, Transition};
+
+    fn entered_and_exited(out: &[StateMachineEvent]) -> Vec<String> {
+        out.iter()
+            .filter_map(|event| match event {
+                StateMachineEvent::Entered { state, .. } => Some(format!("+{state}")),
+                StateMachineEvent::Exited { state, .. } => Some(format!("-{state}")),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn chart() -> StateChart {
+        StateChart::new(vec![
+            State::new("Idle")
+                .with_transition(Transition::new("Move").when("speed", Compare::Greater, ParameterValue::Number(0.0))),
+            State::new("Move")
+                .with_state(
+                    State::new("Walk")
+                        .with_transition(Transition::new("Run").when("speed", Compare::GreaterOrEqual, ParameterValue::Number(5.0))),
+                )
+                .with_state(State::new("Run").with_on_exit(StateAction::Emit {
+                    event: "slowed".to_string(),
+                    data: serde_json::Value::Null,
+                }))
+                .with_transition(Transition::new("Idle").on_event("stop"))
+                .with_transition(Transition::new("Move").on_event("stumble"))
+                .with_on_enter(StateAction::PlayAnimation {
+                    clip: "anim/move.anim".to_string(),
+                    crossfade: 0.2,
+                }),
+        ])
+        .with_parameter("speed", ParameterValue::Number(0.0))
+    }
+
+    #[test]
+    fn test_hierarchical_transitions() {
+        let chart = Arc::new(chart());
+        let charts = |id: AssetId| (id == AssetId::new(1)).then(|| chart.clone());
+        let mut world = World::new();
+        let entity = world.spawn().with(StateMachine::new(AssetId::new(1))).build();
+        let machine = |world: &mut World| world.get_mut::<StateMachine>(entity).unwrap().clone();
+
+        assert_eq!(entered_and_exited(&update_state_machines(&mut world, 0.1, charts)), vec!["+Idle"]);
+        assert_eq!(machine(&mut world).parameter("speed"), Some(ParameterValue::Number(0.0)));
+
+        // Conditions can chain through several transitions in one update
+        world.get_mut::<StateMachine>(entity).unwrap().set_parameter("speed", ParameterValue::Number(6.0));
+        let out = update_state_machines(&mut world, 0.1, charts);
+        assert_eq!(entered_and_exited(&out), vec!["-Idle", "+Move", "+Walk", "-Walk", "+Run"]);
+        assert!(out.contains(&StateMachineEvent::PlayAnimation {
+            entity,
+            clip: "anim/move.anim".to_string(),
+            crossfade: 0.2
+        }));
+        assert_eq!(machine(&mut world).active_states(), ["Move", "Run"]);
+        assert!(machine(&mut world).is_in("Move"));
+
+        // A parent's transition exits its children first
+        world.get_mut::<StateMachine>(entity).unwrap().set_parameter("speed", ParameterValue::Number(0.0));
+        world.get_mut::<StateMachine>(entity).unwrap().send("stop");
+        let out = update_state_machines(&mut world, 0.1, charts);
+        assert_eq!(entered_and_exited(&out), vec!["-Run", "-Move", "+Idle"]);
+        // Exit actions run before the state is left
+        assert!(matches!(&out[0], StateMachineEvent::Emit { event, .. } if event == "slowed"));
+
+        // Unmatched events are dropped at the end of the update
+        world.get_mut::<StateMachine>(entity).unwrap().send("stop");
+        assert!(update_state_machines(&mut world, 0.1, charts).is_empty());
+        assert!(machine(&mut world).pending_events().is_empty());
+    }
+
+    #[test]
+    fn test_self_transition_timers_and_actions() {
+        let mut chart = chart();
+        chart.find_mut("Idle").unwrap().transitions.push(Transition::new("Move").after(1.0));
+        chart.find_mut("Walk").unwrap().on_enter.push(StateAction::Set {
+            parameter: "walked".to_string(),
+            value: ParameterValue::Bool(true),
+        });
+        let chart = Arc::new(chart);
+        let charts = |_: AssetId| Some(chart.clone());
+        let mut world = World::new();
+        let entity = world.spawn().with(StateMachine::new(AssetId::new(1))).build();
+
+        update_state_machines(&mut world, 0.5, charts);
+        assert!(update_state_machines(&mut world, 0.5, charts).is_empty());
+        let out = update_state_machines(&mut world, 0.5, charts);
+        assert_eq!(entered_and_exited(&out), vec!["-Idle", "+Move", "+Walk"]);
+        let walked = world.get::<StateMachine>(entity).unwrap().parameter("walked");
+        assert_eq!(walked, Some(ParameterValue::Bool(true)));
+
+        // Transitioning to an active ancestor re-enters it
+        world.get_mut::<StateMachine>(entity).unwrap().send("stumble");
+        let out = update_state_machines(&mut world, 0.1, charts);
+        assert_eq!(entered_and_exited(&out), vec!["-Walk", "-Move", "+Move", "+Walk"]);
+    }
+}
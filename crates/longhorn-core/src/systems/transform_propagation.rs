@@ -41,8 +41,7 @@ pub fn propagate_transforms(world: &mut World) {
 
         let global = GlobalTransform::from_transform(&transform_copy);
 
-        // Set or update GlobalTransform (set handles both insert and replace)
-        let _ = world.set(entity_handle, global);
+        set_global_transform(world, entity_handle, global);
 
         // Get children (copy the list to avoid borrow issues)
         let children_copy: Vec<hecs::Entity> = world
@@ -57,6 +56,17 @@ pub fn propagate_transforms(world: &mut World) {
     }
 }
 
+/// Insert or replace an entity's GlobalTransform
+///
+/// Unchanged values are left alone so change tracking only reports entities
+/// that actually moved.
+fn set_global_transform(world: &mut World, entity: crate::ecs::EntityHandle, global: GlobalTransform) {
+    if world.get::<GlobalTransform>(entity).is_ok_and(|current| *current == global) {
+        return;
+    }
+    let _ = world.set(entity, global);
+}
+
 /// Recursively propagate transform to a child and its descendants
 fn propagate_to_child(world: &mut World, parent_global: &GlobalTransform, child_id: hecs::Entity) {
     let child_handle = crate::ecs::EntityHandle::new(child_id);
@@ -70,8 +80,7 @@ fn propagate_to_child(world: &mut World, parent_global: &GlobalTransform, child_
     // Calculate child's GlobalTransform
     let child_global = parent_global.mul_transform(&child_transform);
 
-    // Set or update child's GlobalTransform (set handles both insert and replace)
-    let _ = world.set(child_handle, child_global);
+    set_global_transform(world, child_handle, child_global);
 
     // Get grandchildren (copy the list to avoid borrow issues)
     let grandchildren_copy: Vec<hecs::Entity> = world
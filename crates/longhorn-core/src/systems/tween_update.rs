@@ -0,0 +1,237 @@
+use crate::ecs::{EntityHandle, Tween, TweenId, TweenKind, TweenLoops, Tweens};
+use crate::world::World;
+
+/// A tween that finished playing
+#[derive(Debug, Clone, PartialEq)]
+pub struct TweenCompleted {
+    pub entity: EntityHandle,
+    pub id: TweenId,
+    pub name: Option<String>,
+}
+
+/// Advance every entity's tweens by `dt` seconds
+///
+/// Animated fields are written through `World::get_mut`, so they are
+/// reported as changed. Returns the tweens that completed, which are
+/// dropped from their `Tweens`; tweens looping forever never complete.
+pub fn update_tweens(world: &mut World, dt: f32) -> Vec<TweenCompleted> {
+    // Take the tweens out so they can write to the rest of the world
+    let playing: Vec<_> = world
+        .query_mut::<&mut Tweens>()
+        .iter()
+        .filter(|(_, tweens)| !tweens.is_empty())
+        .map(|(entity, tweens)| (EntityHandle::new(entity), std::mem::take(tweens.active_mut())))
+        .collect();
+
+    let mut completed = Vec::new();
+    for (entity, mut active) in playing {
+        active.retain_mut(|active| {
+            let prev = if active.started { active.elapsed } else { f32::NEG_INFINITY };
+            active.elapsed += dt;
+            active.started = true;
+            advance(&mut active.tween, world, entity, prev, active.elapsed);
+
+            let done = active.elapsed >= active.tween.duration();
+            if done {
+                completed.push(TweenCompleted {
+                    entity,
+                    id: active.id,
+                    name: active.tween.name.clone(),
+                });
+            }
+            !done
+        });
+
+        // Keep tweens the entity started while these were taken out
+        if let Ok(tweens) = world.query_one_mut::<&mut Tweens>(entity) {
+            let started = std::mem::replace(tweens.active_mut(), active);
+            tweens.active_mut().extend(started);
+        }
+    }
+    completed
+}
+
+/// Apply a tween for its time moving from `prev` to `time` seconds
+///
+/// Times can run backwards and be infinite. Tweens whose span the move
+/// doesn't touch are left alone, so finished steps don't keep overwriting
+/// their fields.
+fn advance(tween: &mut Tween, world: &World, entity: EntityHandle, prev: f32, time: f32) {
+    let total = tween.duration();
+    if (prev < 0.0 && time < 0.0) || (prev >= total && time >= total) {
+        return;
+    }
+
+    let play = tween.play_duration();
+    let count = match tween.loops {
+        TweenLoops::Count(count) => count.max(1),
+        TweenLoops::Forever => u32::MAX,
+    };
+    let (prev_index, prev_local) = play_time(tween.yoyo, play, count, prev - tween.delay);
+    let (index, local) = play_time(tween.yoyo, play, count, time - tween.delay);
+    let kind = &mut tween.kind;
+    if prev_index == index {
+        advance_play(kind, world, entity, prev_local, local);
+    } else if !tween.yoyo || index % 2 == 0 {
+        // A new play starts from the beginning
+        advance_play(kind, world, entity, f32::INFINITY, f32::NEG_INFINITY);
+        advance_play(kind, world, entity, f32::NEG_INFINITY, local);
+    } else {
+        advance_play(kind, world, entity, f32::NEG_INFINITY, f32::INFINITY);
+        advance_play(kind, world, entity, f32::INFINITY, local);
+    }
+}
+
+/// Which play `time` (after the delay) falls in and the time within it,
+/// counting backwards in the yoyo's reversed plays
+fn play_time(yoyo: bool, play: f32, count: u32, time: f32) -> (u32, f32) {
+    if time < 0.0 || play <= 0.0 {
+        return (0, time);
+    }
+    let index = ((time / play) as u32).min(count - 1);
+    let local = time - index as f32 * play;
+    if yoyo && index % 2 == 1 {
+        (index, play - local)
+    } else {
+        (index, local)
+    }
+}
+
+/// Apply one play of a tween, with times relative to its start
+fn advance_play(kind: &mut TweenKind, world: &World, entity: EntityHandle, prev: f32, time: f32) {
+    match kind {
+        TweenKind::To { targets, duration, ease } => {
+            let progress = if *duration > 0.0 {
+                time / *duration
+            } else if time >= 0.0 {
+                1.0
+            } else {
+                0.0
+            };
+            let eased = ease.apply(progress);
+            for target in targets {
+                // Steps that haven't started yet don't know where they start from
+                if target.from.is_none() && time < 0.0 {
+                    continue;
+                }
+                let from = *target
+                    .from
+                    .get_or_insert_with(|| target.property.get(world, entity).unwrap_or(target.value));
+                target.property.set(world, entity, from + (target.value - from) * eased);
+            }
+        }
+        TweenKind::Sequence(tweens) => {
+            let mut starts = Vec::with_capacity(tweens.len());
+            let mut start = 0.0;
+            for tween in tweens.iter() {
+                starts.push(start);
+                start += tween.duration();
+            }
+            let mut steps: Vec<_> = tweens.iter_mut().zip(starts).collect();
+            // Going backwards, later steps rewind first
+            if time < prev {
+                steps.reverse();
+            }
+            for (tween, start) in steps {
+                if start.is_finite() {
+                    advance(tween, world, entity, prev - start, time - start);
+                }
+            }
+        }
+        TweenKind::Parallel(tweens) => {
+            if time < prev {
+                tweens.iter_mut().rev().for_each(|tween| advance(tween, world, entity, prev, time));
+            } else {
+                tweens.iter_mut().for_each(|tween| advance(tween, world, entity, prev, time));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{play_tween, Ease, Sprite, TweenProperty};
+    use crate::math::Transform;
+    use crate::types::AssetId;
+    use glam::Vec2;
+
+    fn position(world: &World, entity: EntityHandle) -> Vec2 {
+        world.get::<Transform>(entity).unwrap().position
+    }
+
+    fn step(world: &mut World, seconds: f32) -> Vec<TweenCompleted> {
+        let mut completed = Vec::new();
+        // Exact in binary, so steps add up to whole seconds
+        for _ in 0..(seconds / 0.125).round() as u32 {
+            completed.extend(update_tweens(world, 0.125));
+        }
+        completed
+    }
+
+    #[test]
+    fn test_sequence_with_parallel_group() {
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(Transform::new())
+            .with(Sprite::new(AssetId::new(1), Vec2::ONE))
+            .build();
+
+        let tween = Tween::sequence(vec![
+            Tween::to([(TweenProperty::PositionX, 10.0)], 1.0, Ease::Linear),
+            Tween::parallel(vec![
+                Tween::to([(TweenProperty::PositionY, 4.0)], 0.5, Ease::Linear),
+                Tween::to([(TweenProperty::Alpha, 0.0)], 1.0, Ease::Linear).with_delay(1.0),
+            ]),
+        ])
+        .with_name("intro");
+        let id = play_tween(&mut world, entity, tween).unwrap();
+
+        step(&mut world, 0.5);
+        assert!((position(&world, entity) - Vec2::new(5.0, 0.0)).length() < 1e-4);
+
+        step(&mut world, 1.0);
+        assert!((position(&world, entity) - Vec2::new(10.0, 4.0)).length() < 1e-4);
+        assert_eq!(world.get::<Sprite>(entity).unwrap().color[3], 1.0);
+
+        let completed = step(&mut world, 1.5);
+        assert!((world.get::<Sprite>(entity).unwrap().color[3]).abs() < 1e-4);
+        assert_eq!(
+            completed,
+            vec![TweenCompleted {
+                entity,
+                id,
+                name: Some("intro".to_string())
+            }]
+        );
+        assert!(world.get::<Tweens>(entity).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_yoyo_loops_and_cancel() {
+        let mut world = World::new();
+        let entity = world.spawn().with(Transform::from_position(Vec2::new(2.0, 0.0))).build();
+
+        let tween = Tween::to([(TweenProperty::PositionX, 4.0)], 1.0, Ease::Linear)
+            .with_loops(TweenLoops::Count(2))
+            .with_yoyo(true);
+        play_tween(&mut world, entity, tween).unwrap();
+
+        step(&mut world, 1.5);
+        assert!((position(&world, entity).x - 3.0).abs() < 1e-4);
+        // A long frame still lands the yoyo back at the start
+        let completed = update_tweens(&mut world, 5.0);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(position(&world, entity).x, 2.0);
+
+        // Forever loops restart from the captured start, and cancelling leaves the value
+        let tween = Tween::to([(TweenProperty::PositionX, 3.0)], 1.0, Ease::Linear).with_loops(TweenLoops::Forever);
+        let id = play_tween(&mut world, entity, tween).unwrap();
+        assert!(step(&mut world, 2.5).is_empty());
+        assert!((position(&world, entity).x - 2.5).abs() < 1e-4);
+        assert!(world.get_mut::<Tweens>(entity).unwrap().cancel(id));
+        step(&mut world, 0.3);
+        assert!((position(&world, entity).x - 2.5).abs() < 1e-4);
+    }
+}
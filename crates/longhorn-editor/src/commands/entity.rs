@@ -2,7 +2,7 @@
 //!
 //! Handles entity creation, deletion, selection, property setting, and hierarchy management.
 
-use longhorn_core::{AssetId, EntityHandle, EntityId, Name, Sprite, Transform, Vec2};
+use longhorn_core::{AssetId, EntityGuid, EntityHandle, Name, Sprite, Transform, Vec2};
 use longhorn_engine::Engine;
 use longhorn_remote::{
     ComponentInfo, EntityDetails, EntityDump, EntityInfo, RemoteResponse, ResponseData,
@@ -11,10 +11,27 @@ use longhorn_remote::{
 
 use crate::Editor;
 
+/// Resolve a remote entity id (the entity's persistent GUID) to a live entity.
+pub(crate) fn resolve_entity(engine: &Engine, id: u64) -> Option<EntityHandle> {
+    engine.world().entity_by_guid(EntityGuid(id))
+}
+
+/// Get the remote id (persistent GUID) of an entity.
+pub(crate) fn remote_id(engine: &Engine, handle: EntityHandle) -> u64 {
+    engine
+        .world()
+        .guid(handle)
+        .map(|guid| guid.get())
+        .unwrap_or_else(|| handle.id().to_bits().get())
+}
+
 // --- State Query Handlers ---
 
 pub fn handle_get_state(editor: &Editor, engine: &Engine) -> RemoteResponse {
-    let selected = editor.state().selected_entity.map(|e| e.to_bits().get());
+    let selected = editor
+        .state()
+        .selected_entity
+        .map(|e| remote_id(engine, EntityHandle::new(e)));
     RemoteResponse::with_data(ResponseData::State {
         mode: format!("{:?}", editor.state().mode),
         paused: editor.state().paused,
@@ -38,7 +55,7 @@ pub fn handle_get_entities(engine: &Engine) -> RemoteResponse {
                 .map(|n| n.0.clone())
                 .unwrap_or_else(|| format!("Entity {}", entity.id()));
             EntityInfo {
-                id: entity.to_bits().get(),
+                id: remote_id(engine, handle),
                 name,
             }
         })
@@ -47,17 +64,8 @@ pub fn handle_get_entities(engine: &Engine) -> RemoteResponse {
 }
 
 pub fn handle_get_entity(engine: &Engine, id: u64) -> RemoteResponse {
-    let found = engine
-        .world()
-        .inner()
-        .iter()
-        .find(|e| e.entity().to_bits().get() == id);
-
-    match found {
-        Some(entity_ref) => {
-            let entity = entity_ref.entity();
-            let handle = EntityHandle::new(entity);
-
+    match resolve_entity(engine, id) {
+        Some(handle) => {
             let name = engine
                 .world()
                 .get::<Name>(handle)
@@ -90,16 +98,9 @@ pub fn handle_get_entity(engine: &Engine, id: u64) -> RemoteResponse {
 // --- Entity Manipulation Handlers ---
 
 pub fn handle_select_entity(editor: &mut Editor, engine: &Engine, id: u64) -> RemoteResponse {
-    let found = engine
-        .world()
-        .inner()
-        .iter()
-        .find(|e| e.entity().to_bits().get() == id)
-        .map(|e| e.entity());
-
-    match found {
-        Some(entity) => {
-            editor.state_mut().select(Some(entity));
+    match resolve_entity(engine, id) {
+        Some(handle) => {
+            editor.state_mut().select(Some(handle.id()));
             RemoteResponse::ok()
         }
         None => RemoteResponse::error(format!("Entity not found: {}", id)),
@@ -113,18 +114,17 @@ pub fn handle_create_entity(engine: &mut Engine, name: &str) -> RemoteResponse {
         .with(Name::new(name))
         .with(Transform::default())
         .build();
-    let id = entity.id().to_bits().get();
+    let id = remote_id(engine, entity);
     log::info!("Created entity '{}' with id {}", name, id);
     RemoteResponse::with_data(ResponseData::Created { id })
 }
 
 pub fn handle_delete_entity(editor: &mut Editor, engine: &mut Engine, id: u64) -> RemoteResponse {
-    match EntityId::from_bits(id) {
-        Some(entity_id) => {
-            let handle = EntityHandle::new(entity_id);
+    match resolve_entity(engine, id) {
+        Some(handle) => {
             if engine.world_mut().despawn(handle).is_ok() {
                 // Deselect if this was selected
-                if editor.state().selected_entity.map(|e| e.id() as u64) == Some(id) {
+                if editor.state().selected_entity == Some(handle.id()) {
                     editor.state_mut().select(None);
                 }
                 log::info!("Deleted entity {}", id);
@@ -133,7 +133,7 @@ pub fn handle_delete_entity(editor: &mut Editor, engine: &mut Engine, id: u64) -
                 RemoteResponse::error(format!("Entity not found: {}", id))
             }
         }
-        None => RemoteResponse::error(format!("Invalid entity id: {}", id)),
+        None => RemoteResponse::error(format!("Entity not found: {}", id)),
     }
 }
 
@@ -144,13 +144,13 @@ pub fn handle_set_entity_parent(
 ) -> RemoteResponse {
     use longhorn_core::ecs::hierarchy::set_parent;
 
-    let child_entity = match EntityId::from_bits(child_id) {
-        Some(id) => EntityHandle::new(id),
+    let child_entity = match resolve_entity(engine, child_id) {
+        Some(handle) => handle,
         None => return RemoteResponse::error(format!("Invalid child entity id: {}", child_id)),
     };
 
-    let parent_entity = match EntityId::from_bits(parent_id) {
-        Some(id) => EntityHandle::new(id),
+    let parent_entity = match resolve_entity(engine, parent_id) {
+        Some(handle) => handle,
         None => return RemoteResponse::error(format!("Invalid parent entity id: {}", parent_id)),
     };
 
@@ -166,8 +166,8 @@ pub fn handle_set_entity_parent(
 pub fn handle_clear_entity_parent(engine: &mut Engine, child_id: u64) -> RemoteResponse {
     use longhorn_core::ecs::hierarchy::clear_parent;
 
-    let child_entity = match EntityId::from_bits(child_id) {
-        Some(id) => EntityHandle::new(id),
+    let child_entity = match resolve_entity(engine, child_id) {
+        Some(handle) => handle,
         None => return RemoteResponse::error(format!("Invalid child entity id: {}", child_id)),
     };
 
@@ -187,11 +187,10 @@ pub fn set_entity_property(
     field: &str,
     value: serde_json::Value,
 ) -> RemoteResponse {
-    let entity_id = match EntityId::from_bits(entity_id) {
-        Some(id) => id,
+    let handle = match resolve_entity(engine, entity_id) {
+        Some(handle) => handle,
         None => return RemoteResponse::error(format!("Invalid entity id: {}", entity_id)),
     };
-    let handle = EntityHandle::new(entity_id);
 
     match component {
         "Transform" => set_transform_property(engine, handle, field, value),
@@ -352,11 +351,10 @@ fn set_sprite_property(
 // --- Debug/Inspection Handlers ---
 
 pub fn handle_get_entity_components(engine: &Engine, id: u64) -> RemoteResponse {
-    let entity_id = match EntityId::from_bits(id) {
-        Some(id) => id,
+    let handle = match resolve_entity(engine, id) {
+        Some(handle) => handle,
         None => return RemoteResponse::error(format!("Invalid entity id: {}", id)),
     };
-    let handle = EntityHandle::new(entity_id);
 
     let mut components = Vec::new();
 
@@ -402,11 +400,10 @@ pub fn handle_get_entity_components(engine: &Engine, id: u64) -> RemoteResponse
 }
 
 pub fn handle_dump_entity(engine: &Engine, id: u64) -> RemoteResponse {
-    let entity_id = match EntityId::from_bits(id) {
-        Some(id) => id,
+    let handle = match resolve_entity(engine, id) {
+        Some(handle) => handle,
         None => return RemoteResponse::error(format!("Invalid entity id: {}", id)),
     };
-    let handle = EntityHandle::new(entity_id);
 
     let name = engine.world().get::<Name>(handle).ok().map(|n| n.0.clone());
 
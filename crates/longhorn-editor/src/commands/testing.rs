@@ -3,13 +3,14 @@
 //! Handles screenshot capture, log tailing, frame waiting, gizmo simulation,
 //! and scene tree drag-drop simulation for E2E testing.
 
-use longhorn_core::{EntityHandle, EntityId, Name, Transform, Vec2};
+use longhorn_core::{Name, Transform, Vec2};
 use longhorn_engine::Engine;
 use longhorn_remote::{
     GizmoDragResult, GizmoStateData, LogEntry, LogTailResult, RemoteResponse, ResponseData,
     ScreenshotResult, WaitFramesResult,
 };
 
+use super::entity::resolve_entity;
 use crate::gizmo::{update_transform_from_drag, GizmoHandle, GizmoMode};
 use crate::Editor;
 
@@ -139,10 +140,9 @@ pub fn handle_simulate_gizmo_drag(
     delta_x: f32,
     delta_y: f32,
 ) -> RemoteResponse {
-    let Some(entity) = EntityId::from_bits(entity_id) else {
+    let Some(handle_entity) = resolve_entity(engine, entity_id) else {
         return RemoteResponse::error(format!("Invalid entity ID: {}", entity_id));
     };
-    let handle_entity = EntityHandle::new(entity);
 
     let old_transform = {
         let Ok(transform) = engine.world().get::<Transform>(handle_entity) else {
@@ -193,8 +193,8 @@ pub fn handle_simulate_scene_tree_drag(
         target_entity_id
     );
 
-    let dragged_entity = match EntityId::from_bits(dragged_entity_id) {
-        Some(id) => EntityHandle::new(id),
+    let dragged_entity = match resolve_entity(engine, dragged_entity_id) {
+        Some(handle) => handle,
         None => {
             return RemoteResponse::error(format!(
                 "Invalid dragged entity id: {}",
@@ -203,8 +203,8 @@ pub fn handle_simulate_scene_tree_drag(
         }
     };
 
-    let target_entity = match EntityId::from_bits(target_entity_id) {
-        Some(id) => EntityHandle::new(id),
+    let target_entity = match resolve_entity(engine, target_entity_id) {
+        Some(handle) => handle,
         None => {
             return RemoteResponse::error(format!(
                 "Invalid target entity id: {}",
@@ -248,8 +248,8 @@ pub fn handle_simulate_scene_tree_drag_to_root(
         entity_id
     );
 
-    let entity = match EntityId::from_bits(entity_id) {
-        Some(id) => EntityHandle::new(id),
+    let entity = match resolve_entity(engine, entity_id) {
+        Some(handle) => handle,
         None => return RemoteResponse::error(format!("Invalid entity id: {}", entity_id)),
     };
 
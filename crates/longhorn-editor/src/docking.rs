@@ -13,6 +13,8 @@ pub enum PanelType {
     Console,
     Project,
     ScriptEditor,
+    Timeline,
+    StateMachine,
 }
 
 impl PanelType {
@@ -25,6 +27,8 @@ impl PanelType {
             PanelType::Console => "Console",
             PanelType::Project => "Project",
             PanelType::ScriptEditor => "Script Editor",
+            PanelType::Timeline => "Timeline",
+            PanelType::StateMachine => "State Machine",
         }
     }
 }
@@ -73,11 +77,11 @@ pub fn create_default_dock_state() -> DockState<PanelType> {
         vec![PanelType::Inspector],
     );
 
-    // Add Console and Project tabbed at the bottom (70% for main, 30% for bottom)
+    // Add Console, Project, Timeline and State Machine tabbed at the bottom (70% for main, 30% for bottom)
     let [_main, _bottom] = dock_state.main_surface_mut().split_below(
         NodeIndex::root(),
         0.7,
-        vec![PanelType::Console, PanelType::Project],
+        vec![PanelType::Console, PanelType::Project, PanelType::Timeline, PanelType::StateMachine],
     );
 
     dock_state
@@ -8,7 +8,7 @@ use crate::{EditorState, SceneTreePanel, InspectorPanel, ViewportPanel, Toolbar,
 use crate::docking::{PanelType, PanelRenderer, create_default_dock_state, show_dock_area};
 use longhorn_remote::{RemoteCommand, RemoteResponse};
 use crate::ui_state::UiStateTracker;
-use crate::{ProjectPanelState, ProjectPanel, ProjectPanelAction, DirectoryNode, ContextAction};
+use crate::{ProjectPanelState, ProjectPanel, ProjectPanelAction, DirectoryNode, ContextAction, FileType, TextureImportPanel, TimelinePanel, StateMachinePanel};
 use crate::texture_picker::{TexturePickerState, TexturePickerAction};
 use crate::EditorCamera;
 use crate::{GizmoState, GizmoConfig, GizmoMode};
@@ -37,6 +37,14 @@ pub struct Editor {
     texture_picker_state: TexturePickerState,
     /// Pending screenshot request (path to save)
     pending_screenshot: Option<String>,
+    /// Import settings editor for the selected texture
+    texture_import: TextureImportPanel,
+    /// Dope sheet for the selected entity's animation clip
+    timeline: TimelinePanel,
+    /// Graph of the selected entity's state chart
+    state_machine: StateMachinePanel,
+    /// Textures whose import settings changed and must be re-uploaded
+    pending_texture_reloads: Vec<longhorn_core::AssetId>,
     /// Gizmo state for transform manipulation
     gizmo_state: GizmoState,
     /// Gizmo visual configuration
@@ -97,6 +105,10 @@ impl Editor {
             pending_show_script_editor: false,
             texture_picker_state: TexturePickerState::new(),
             pending_screenshot: None,
+            texture_import: TextureImportPanel::new(),
+            timeline: TimelinePanel::new(),
+            state_machine: StateMachinePanel::new(),
+            pending_texture_reloads: Vec::new(),
             gizmo_state: GizmoState::new(GizmoMode::Move),
             gizmo_config: GizmoConfig::default(),
             project: None,
@@ -148,6 +160,25 @@ impl Editor {
         self.pending_screenshot.take()
     }
 
+    /// Take and consume textures that must be re-uploaded after their
+    /// import settings changed
+    pub fn take_pending_texture_reloads(&mut self) -> Vec<longhorn_core::AssetId> {
+        std::mem::take(&mut self.pending_texture_reloads)
+    }
+
+    /// The texture selected in the project panel, relative to the project root
+    fn selected_texture_path(&self) -> Option<String> {
+        let selected = self.project_panel_state.selected_file.as_ref()?;
+        let name = selected.file_name()?.to_str()?;
+        let ext = selected.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+        if FileType::from_filename(name, ext.as_deref()) != FileType::Image {
+            return None;
+        }
+        let game_path = self.state.game_path.as_ref()?;
+        let relative = selected.strip_prefix(game_path).ok()?;
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+
     /// Request waiting for N frames (placeholder for future implementation)
     pub fn request_wait_frames(&self, _count: u32) {
         // TODO: Implement frame waiting
@@ -167,6 +198,10 @@ impl Editor {
         &self.editor_camera
     }
 
+    pub fn viewport(&self) -> &ViewportPanel {
+        &self.viewport
+    }
+
     pub fn viewport_mut(&mut self) -> &mut ViewportPanel {
         &mut self.viewport
     }
@@ -673,6 +708,18 @@ impl Editor {
 
                     ui.separator();
 
+                    // Copies the game with its scenes baked to the binary format
+                    if ui.add_enabled(self.project.is_some(), egui::Button::new("Export Game...")).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                            if let Err(e) = engine.export_game(&path) {
+                                self.console.error(format!("Failed to export game: {}", e));
+                            }
+                        }
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
                     if ui.button("Exit").clicked() {
                         if self.dirty_state.any_dirty() {
                             self.unsaved_changes_dialog.open(self.dirty_state.dirty_files());
@@ -729,6 +776,135 @@ impl Editor {
                 log::info!("EditorAction::OpenTexturePicker received for entity ID: {} (raw: {:?})", entity.id(), entity);
                 self.texture_picker_state.open_for_entity(entity);
             }
+            EditorAction::LoadParticlePreset { entity, path } => {
+                match engine.assets_mut().load_particle_effect(&path) {
+                    Ok(effect) => {
+                        let handle = longhorn_core::EntityHandle::new(entity);
+                        if let Ok(mut emitter) = engine.world_mut().get_mut::<longhorn_core::ParticleEmitter>(handle) {
+                            emitter.effect = effect;
+                            log::info!("Loaded particle preset: {}", path);
+                        }
+                    }
+                    Err(e) => log::error!("Failed to load particle preset {}: {}", path, e),
+                }
+            }
+            EditorAction::LoadSpriteMaterial { entity, path } => {
+                match engine.assets_mut().reload_material(&path) {
+                    Ok(handle) => {
+                        let material = engine.assets().get_material(handle.clone());
+                        let entity = longhorn_core::EntityHandle::new(entity);
+                        let world = engine.world_mut();
+                        if let Ok(mut sprite) = world.get_mut::<longhorn_core::Sprite>(entity) {
+                            sprite.material = handle.id();
+                        }
+                        // Expose the material's parameters in the inspector
+                        if let Some(material) = material {
+                            if world.get::<longhorn_core::MaterialParams>(entity).is_err() && !material.params.is_empty() {
+                                let params = material.params.iter().fold(longhorn_core::MaterialParams::new(), |params, param| {
+                                    params.with(param.name.clone(), param.default)
+                                });
+                                if let Err(e) = world.set(entity, params) {
+                                    log::error!("Failed to add material params: {:?}", e);
+                                }
+                            }
+                        }
+                        log::info!("Loaded material: {}", path);
+                    }
+                    Err(e) => self.console.error(format!("Failed to load material {}: {}", path, e)),
+                }
+            }
+            EditorAction::LoadSpriteNormalMap { entity, path } => {
+                // Normal maps hold directions, not colors: import them as linear data
+                let result = engine.assets().load_texture_settings(&path).and_then(|settings| {
+                    if settings.srgb {
+                        let settings = longhorn_assets::TextureImportSettings { srgb: false, ..settings };
+                        engine.assets_mut().set_texture_settings(&path, settings)?;
+                    }
+                    engine.assets_mut().load_texture(&path)
+                });
+                match result {
+                    Ok(handle) => {
+                        let entity = longhorn_core::EntityHandle::new(entity);
+                        if let Ok(mut sprite) = engine.world_mut().get_mut::<longhorn_core::Sprite>(entity) {
+                            sprite.normal_map = handle.id();
+                        }
+                        log::info!("Loaded normal map: {}", path);
+                    }
+                    Err(e) => self.console.error(format!("Failed to load normal map {}: {}", path, e)),
+                }
+            }
+            EditorAction::LoadAnimationClip { entity, path } => {
+                let result = if engine.assets().exists(&path) {
+                    engine.assets_mut().load_animation_clip(&path).map(|handle| handle.id())
+                } else {
+                    engine.assets_mut().save_animation_clip(&path, &longhorn_core::AnimationClip::new(1.0))
+                };
+                match result {
+                    Ok(clip) => {
+                        let handle = longhorn_core::EntityHandle::new(entity);
+                        if let Ok(mut player) = engine.world_mut().get_mut::<longhorn_core::AnimationPlayer>(handle) {
+                            player.clip = clip;
+                        }
+                        log::info!("Loaded animation clip: {}", path);
+                    }
+                    Err(e) => self.console.error(format!("Failed to load animation clip {}: {}", path, e)),
+                }
+            }
+            EditorAction::LoadStateChart { entity, path } => {
+                let result = if engine.assets().exists(&path) {
+                    engine.assets_mut().load_state_chart(&path).map(|handle| handle.id())
+                } else {
+                    let chart = longhorn_core::StateChart::new(vec![longhorn_core::State::new("Idle")]);
+                    engine.assets_mut().save_state_chart(&path, &chart)
+                };
+                match result {
+                    Ok(chart) => {
+                        let handle = longhorn_core::EntityHandle::new(entity);
+                        if let Ok(mut machine) = engine.world_mut().get_mut::<longhorn_core::StateMachine>(handle) {
+                            machine.chart = chart;
+                            machine.restart();
+                        }
+                        log::info!("Loaded state chart: {}", path);
+                    }
+                    Err(e) => self.console.error(format!("Failed to load state chart {}: {}", path, e)),
+                }
+            }
+            EditorAction::LoadNavMesh { entity, path } => {
+                match engine.assets_mut().load_navmesh(&path) {
+                    Ok(navmesh) => {
+                        let handle = longhorn_core::EntityHandle::new(entity);
+                        if let Err(e) = engine.world_mut().set(handle, longhorn_core::NavRegion::from_navmesh(navmesh.id())) {
+                            log::error!("Failed to update nav region: {:?}", e);
+                        }
+                        log::info!("Loaded navmesh: {}", path);
+                    }
+                    Err(e) => self.console.error(format!("Failed to load navmesh {}: {}", path, e)),
+                }
+            }
+            EditorAction::BakeNavMesh { entity, path } => {
+                match longhorn_engine::subsystems::navigation::bake_navmesh(
+                    engine,
+                    longhorn_core::EntityHandle::new(entity),
+                    &path,
+                ) {
+                    Ok(_) => log::info!("Baked navmesh: {}", path),
+                    Err(e) => self.console.error(format!("Failed to bake navmesh {}: {}", path, e)),
+                }
+            }
+            EditorAction::SaveParticlePreset { entity, path } => {
+                let handle = longhorn_core::EntityHandle::new(entity);
+                let effect = engine
+                    .world()
+                    .get::<longhorn_core::ParticleEmitter>(handle)
+                    .ok()
+                    .map(|emitter| emitter.effect.clone());
+                if let Some(effect) = effect {
+                    match engine.assets_mut().save_particle_effect(&path, &effect) {
+                        Ok(()) => log::info!("Saved particle preset: {}", path),
+                        Err(e) => log::error!("Failed to save particle preset {}: {}", path, e),
+                    }
+                }
+            }
             EditorAction::None => {}
         }
 
@@ -859,6 +1035,8 @@ impl<'a> PanelRenderer for EditorPanelWrapper<'a> {
             PanelType::Console => ("console", "Console"),
             PanelType::Project => ("project", "Project"),
             PanelType::ScriptEditor => ("script_editor", "Script Editor"),
+            PanelType::Timeline => ("timeline", "Timeline"),
+            PanelType::StateMachine => ("state_machine", "State Machine"),
         };
 
         // Register panel with UI state tracker
@@ -883,41 +1061,56 @@ impl<'a> PanelRenderer for EditorPanelWrapper<'a> {
 
                 // Handle scene tree actions
                 if let Some(action) = scene_action {
-                    match action {
-                        crate::SceneTreeAction::CreateEntity => {
-                            // Create new entity with Name and Transform
-                            let new_entity = world.spawn()
-                                .with(longhorn_core::Name::new("New Entity"))
-                                .with(longhorn_core::Transform::new())
-                                .build();
-
-                            // If an entity is selected, make the new entity a child
-                            if let Some(parent_entity) = self.editor.state.selected_entity {
-                                let parent_handle = longhorn_core::EntityHandle::new(parent_entity);
-                                if let Err(e) = longhorn_core::ecs::hierarchy::add_child(world, parent_handle, new_entity) {
-                                    log::error!("Failed to add child entity: {:?}", e);
-                                } else {
-                                    // Expand parent to show new child
-                                    self.editor.scene_tree.expanded_entities.insert(parent_entity.to_bits().get());
-                                }
-                            }
-
-                            // Select the new entity
-                            self.editor.state.select(Some(new_entity.id()));
-                            log::info!("Created new entity: {:?}", new_entity.id());
+                    // Create new entity with Name and Transform, plus the shape if any
+                    let new_entity = match action {
+                        crate::SceneTreeAction::CreateEntity => world.spawn()
+                            .with(longhorn_core::Name::new("New Entity"))
+                            .with(longhorn_core::Transform::new())
+                            .build(),
+                        crate::SceneTreeAction::CreateShape(shape) => world.spawn()
+                            .with(longhorn_core::Name::new("New Shape"))
+                            .with(longhorn_core::Transform::new())
+                            .with(shape)
+                            .build(),
+                    };
+
+                    // If an entity is selected, make the new entity a child
+                    if let Some(parent_entity) = self.editor.state.selected_entity {
+                        let parent_handle = longhorn_core::EntityHandle::new(parent_entity);
+                        if let Err(e) = longhorn_core::ecs::hierarchy::add_child(world, parent_handle, new_entity) {
+                            log::error!("Failed to add child entity: {:?}", e);
+                        } else {
+                            // Expand parent to show new child
+                            self.editor.scene_tree.expanded_entities.insert(parent_entity.to_bits().get());
                         }
                     }
+
+                    // Select the new entity
+                    self.editor.state.select(Some(new_entity.id()));
+                    log::info!("Created new entity: {:?}", new_entity.id());
                 }
             }
             PanelType::Inspector => {
+                // A texture selected in the project panel shows its import settings
+                if self.editor.state.selected_entity.is_none() {
+                    if let Some(path) = self.editor.selected_texture_path() {
+                        if let Some(asset_id) = self.editor.texture_import.show(ui, self.engine.assets_mut(), &path) {
+                            self.editor.pending_texture_reloads.push(asset_id);
+                        }
+                        return;
+                    }
+                }
+
                 // In play mode, show read-only indicator
                 if self.editor.state.is_playing() {
                     ui.label("(Read-only during play)");
                     ui.separator();
                 }
+                let (world, assets) = self.engine.world_and_assets_mut();
                 let action = self.editor.inspector.show(
                     ui,
-                    self.engine.world_mut(),
+                    world,
+                    &*assets,
                     &self.editor.state,
                 );
 
@@ -958,9 +1151,14 @@ impl<'a> PanelRenderer for EditorPanelWrapper<'a> {
                 if let Some(new_transform) = action.transform_update {
                     if let Some(selected) = self.editor.state.selected_entity {
                         let handle = longhorn_core::EntityHandle::new(selected);
+                        let old_transform = self.engine.world().get::<longhorn_core::Transform>(handle).ok().map(|t| *t);
                         if let Ok(mut transform) = self.engine.world_mut().get_mut::<longhorn_core::Transform>(handle) {
                             *transform = new_transform;
                         }
+                        // In record mode the edit also becomes keys of the animation clip
+                        if let Some(old_transform) = old_transform {
+                            self.editor.timeline.record_transform(self.engine.world(), handle, &old_transform, &new_transform);
+                        }
                     }
                 }
 
@@ -1016,12 +1214,22 @@ impl<'a> PanelRenderer for EditorPanelWrapper<'a> {
                 self.editor.console_panel.show(ui, &self.editor.console);
             }
             PanelType::Project => {
-                if let Some(action) = self.editor.project_panel.show(
+                let previous_file = self.editor.project_panel_state.selected_file.clone();
+                let action = self.editor.project_panel.show(
                     ui,
                     &mut self.editor.project_panel_state,
                     self.editor.project_tree.as_ref(),
                     &mut self.editor.ui_state,
-                ) {
+                );
+
+                // Selecting a texture shows its import settings in the inspector
+                if self.editor.project_panel_state.selected_file != previous_file
+                    && self.editor.selected_texture_path().is_some()
+                {
+                    self.editor.state.select(None);
+                }
+
+                if let Some(action) = action {
                     log::info!("=== EDITOR received ProjectPanelAction: {:?} ===", action);
                     match action {
                         ProjectPanelAction::OpenScript(path) => {
@@ -1169,6 +1377,14 @@ impl<'a> PanelRenderer for EditorPanelWrapper<'a> {
                     }
                 }
             }
+            PanelType::Timeline => {
+                let (world, assets) = self.engine.world_and_assets_mut();
+                self.editor.timeline.show(ui, world, assets, &self.editor.state);
+            }
+            PanelType::StateMachine => {
+                let (world, assets) = self.engine.world_and_assets_mut();
+                self.editor.state_machine.show(ui, world, assets, &self.editor.state);
+            }
             PanelType::ScriptEditor => {
                 let save_triggered = self.editor.script_editor_panel.show(ui, &mut self.editor.script_editor_state);
                 if save_triggered {
@@ -1,8 +1,9 @@
 use egui::Ui;
-use longhorn_core::{World, Name, Transform, Sprite, Enabled, EntityHandle, Script, ScriptValue};
+use longhorn_core::{World, Name, AnimationPlayer, StateMachine, ParameterValue, NavAgent, NavObstacle, NavRegion, Transform, Sprite, SpriteDrawMode, SliceBorder, MaterialParams, MaterialValue, AssetId, Text, TextAlign, Tilemap, ParticleEmitter, ParticleState, Curve, SimulationSpace, PointLight2D, SpotLight2D, GlobalLight2D, LightOccluder2D, OccluderShape, Shape, ShapeFill, ShapeGeometry, ShapeStroke, Enabled, EntityHandle, EntityId, EntityRef, Script, ScriptValue};
 use longhorn_engine::MainCamera;
-use longhorn_renderer::Camera;
+use longhorn_renderer::{Camera, TextureLookup};
 use crate::EditorState;
+use crate::ui::shape_presets;
 
 /// Actions that can be triggered from the Inspector panel
 #[derive(Debug, Clone)]
@@ -10,16 +11,48 @@ pub enum EditorAction {
     None,
     OpenScriptEditor { path: String },
     OpenTexturePicker { entity: hecs::Entity },
+    LoadParticlePreset { entity: hecs::Entity, path: String },
+    SaveParticlePreset { entity: hecs::Entity, path: String },
+    /// Load (or reload) a material and assign it to the entity's sprite
+    LoadSpriteMaterial { entity: hecs::Entity, path: String },
+    /// Load a texture as linear data and assign it as the sprite's normal map
+    LoadSpriteNormalMap { entity: hecs::Entity, path: String },
+    /// Load an animation clip, creating it if missing, and play it on the entity
+    LoadAnimationClip { entity: hecs::Entity, path: String },
+    /// Load a state chart, creating it if missing, and run it on the entity
+    LoadStateChart { entity: hecs::Entity, path: String },
+    /// Load a navmesh and walk the entity's navigation region on it
+    LoadNavMesh { entity: hecs::Entity, path: String },
+    /// Bake the region's Tilemap into a navmesh saved at the path
+    BakeNavMesh { entity: hecs::Entity, path: String },
 }
 
 pub struct InspectorPanel {
     pending_action: EditorAction,
+    /// Preset path typed into the Particle Emitter section
+    particle_preset_path: String,
+    /// Material path typed into the Sprite section
+    material_path: String,
+    /// Normal map path typed into the Sprite section
+    normal_map_path: String,
+    /// Clip path typed into the Animation Player section
+    animation_clip_path: String,
+    /// Chart path typed into the State Machine section
+    state_chart_path: String,
+    /// Navmesh path typed into the Nav Region section
+    navmesh_path: String,
 }
 
 impl InspectorPanel {
     pub fn new() -> Self {
         Self {
             pending_action: EditorAction::None,
+            particle_preset_path: "particles/effect.particles".to_string(),
+            material_path: "materials/sprite.material".to_string(),
+            normal_map_path: "sprites/normal.png".to_string(),
+            animation_clip_path: "animations/clip.anim".to_string(),
+            state_chart_path: "state_machines/behaviour.fsm".to_string(),
+            navmesh_path: "navigation/level.navmesh".to_string(),
         }
     }
 
@@ -27,6 +60,7 @@ impl InspectorPanel {
         &mut self,
         ui: &mut Ui,
         world: &mut World,
+        textures: &dyn TextureLookup,
         state: &EditorState,
     ) -> EditorAction {
         // Reset pending action at the start
@@ -91,7 +125,48 @@ impl InspectorPanel {
         ui.separator();
 
         // Sprite (editable)
-        self.show_sprite_component(ui, world, handle);
+        self.show_sprite_component(ui, world, textures, handle);
+        self.show_material_params_component(ui, world, handle);
+
+        ui.separator();
+
+        // Text (editable)
+        self.show_text_component(ui, world, handle);
+
+        ui.separator();
+
+        // Tilemap (editable settings; tiles are painted from scripts or imported)
+        self.show_tilemap_component(ui, world, handle);
+
+        ui.separator();
+
+        // Particle Emitter (editable; previews in the scene view)
+        self.show_particle_emitter_component(ui, world, handle);
+
+        ui.separator();
+
+        // Animation Player (clips are keyed in the Timeline panel)
+        self.show_animation_player_component(ui, world, handle);
+
+        ui.separator();
+
+        // State Machine (charts are edited in the State Machine panel)
+        self.show_state_machine_component(ui, world, handle);
+
+        ui.separator();
+
+        // Navigation agents, obstacles and regions (editable)
+        self.show_navigation_components(ui, world, handle);
+
+        ui.separator();
+
+        // 2D lights and occluders (editable)
+        self.show_light_components(ui, world, handle);
+
+        ui.separator();
+
+        // Shape (editable)
+        self.show_shape_component(ui, world, handle);
 
         ui.separator();
 
@@ -141,6 +216,125 @@ impl InspectorPanel {
                 ui.close_menu();
             }
 
+            // Text option
+            let has_text = world.get::<Text>(handle).is_ok();
+            if ui.add_enabled(!has_text, egui::Button::new("Text")).clicked() {
+                log::info!("Adding Text component to entity");
+                if let Err(e) = world.set(handle, Text::new("Text", longhorn_core::AssetId::new(0), 32.0)) {
+                    log::error!("Failed to add text: {:?}", e);
+                } else {
+                    log::info!("Added Text component to entity");
+                }
+                ui.close_menu();
+            }
+
+            // Tilemap option
+            let has_tilemap = world.get::<Tilemap>(handle).is_ok();
+            if ui.add_enabled(!has_tilemap, egui::Button::new("Tilemap")).clicked() {
+                log::info!("Adding Tilemap component to entity");
+                if let Err(e) = world.set(handle, Tilemap::default().with_layer("Ground")) {
+                    log::error!("Failed to add tilemap: {:?}", e);
+                } else {
+                    log::info!("Added Tilemap component to entity");
+                }
+                ui.close_menu();
+            }
+
+            // Particle Emitter option
+            let has_emitter = world.get::<ParticleEmitter>(handle).is_ok();
+            if ui.add_enabled(!has_emitter, egui::Button::new("Particle Emitter")).clicked() {
+                log::info!("Adding ParticleEmitter component to entity");
+                if let Err(e) = world.set(handle, ParticleEmitter::default()) {
+                    log::error!("Failed to add particle emitter: {:?}", e);
+                } else {
+                    log::info!("Added ParticleEmitter component to entity");
+                }
+                ui.close_menu();
+            }
+
+            // Animation Player option (stopped until a clip is loaded)
+            let has_player = world.get::<AnimationPlayer>(handle).is_ok();
+            if ui.add_enabled(!has_player, egui::Button::new("Animation Player")).clicked() {
+                if let Err(e) = world.set(handle, AnimationPlayer::new(AssetId::new(0)).with_playing(false)) {
+                    log::error!("Failed to add animation player: {:?}", e);
+                }
+                ui.close_menu();
+            }
+
+            // State Machine option (idle until a chart is loaded)
+            let has_machine = world.get::<StateMachine>(handle).is_ok();
+            if ui.add_enabled(!has_machine, egui::Button::new("State Machine")).clicked() {
+                if let Err(e) = world.set(handle, StateMachine::new(AssetId::new(0))) {
+                    log::error!("Failed to add state machine: {:?}", e);
+                }
+                ui.close_menu();
+            }
+
+            // Navigation options
+            let has_agent = world.get::<NavAgent>(handle).is_ok();
+            if ui.add_enabled(!has_agent, egui::Button::new("Nav Agent")).clicked() {
+                if let Err(e) = world.set(handle, NavAgent::default()) {
+                    log::error!("Failed to add nav agent: {:?}", e);
+                }
+                ui.close_menu();
+            }
+
+            let has_obstacle = world.get::<NavObstacle>(handle).is_ok();
+            if ui.add_enabled(!has_obstacle, egui::Button::new("Nav Obstacle")).clicked() {
+                if let Err(e) = world.set(handle, NavObstacle::default()) {
+                    log::error!("Failed to add nav obstacle: {:?}", e);
+                }
+                ui.close_menu();
+            }
+
+            // Nav Region option (walks the entity's Tilemap until a navmesh is baked)
+            let has_region = world.get::<NavRegion>(handle).is_ok();
+            if ui.add_enabled(!has_region, egui::Button::new("Nav Region")).clicked() {
+                if let Err(e) = world.set(handle, NavRegion::from_tilemap()) {
+                    log::error!("Failed to add nav region: {:?}", e);
+                }
+                ui.close_menu();
+            }
+
+            // Light options
+            let has_point_light = world.get::<PointLight2D>(handle).is_ok();
+            if ui.add_enabled(!has_point_light, egui::Button::new("Point Light 2D")).clicked() {
+                if let Err(e) = world.set(handle, PointLight2D::default()) {
+                    log::error!("Failed to add point light: {:?}", e);
+                }
+                ui.close_menu();
+            }
+            let has_spot_light = world.get::<SpotLight2D>(handle).is_ok();
+            if ui.add_enabled(!has_spot_light, egui::Button::new("Spot Light 2D")).clicked() {
+                if let Err(e) = world.set(handle, SpotLight2D::default()) {
+                    log::error!("Failed to add spot light: {:?}", e);
+                }
+                ui.close_menu();
+            }
+            let has_global_light = world.get::<GlobalLight2D>(handle).is_ok();
+            if ui.add_enabled(!has_global_light, egui::Button::new("Global Light 2D")).clicked() {
+                if let Err(e) = world.set(handle, GlobalLight2D::default()) {
+                    log::error!("Failed to add global light: {:?}", e);
+                }
+                ui.close_menu();
+            }
+            let has_occluder = world.get::<LightOccluder2D>(handle).is_ok();
+            if ui.add_enabled(!has_occluder, egui::Button::new("Light Occluder 2D")).clicked() {
+                if let Err(e) = world.set(handle, LightOccluder2D::default()) {
+                    log::error!("Failed to add light occluder: {:?}", e);
+                }
+                ui.close_menu();
+            }
+
+            // Shape option
+            let has_shape = world.get::<Shape>(handle).is_ok();
+            if ui.add_enabled(!has_shape, egui::Button::new("Shape")).clicked() {
+                if let Err(e) = world.set(handle, Shape::default()) {
+                    log::error!("Failed to add shape: {:?}", e);
+                }
+                ui.close_menu();
+            }
+
             // Script option
             if ui.button("Script").clicked() {
                 log::info!("Add Script button clicked (not yet implemented)");
@@ -189,7 +383,7 @@ impl InspectorPanel {
         self.pending_action.clone()
     }
 
-    fn show_sprite_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+    fn show_sprite_component(&mut self, ui: &mut Ui, world: &mut World, textures: &dyn TextureLookup, handle: EntityHandle) {
         if let Ok(mut sprite) = world.get_mut::<Sprite>(handle) {
             ui.group(|ui| {
                 ui.heading("Sprite");
@@ -246,10 +440,138 @@ impl InspectorPanel {
                     ui.checkbox(&mut sprite.flip_x, "Flip X");
                     ui.checkbox(&mut sprite.flip_y, "Flip Y");
                 });
+
+                ui.separator();
+
+                // Draw mode: switching between the nine-slice modes keeps the border
+                ui.horizontal(|ui| {
+                    ui.label("Draw Mode:");
+                    let border = sprite.draw_mode.border().unwrap_or_default();
+                    egui::ComboBox::from_id_salt("sprite_draw_mode")
+                        .selected_text(draw_mode_label(&sprite.draw_mode))
+                        .show_ui(ui, |ui| {
+                            for mode in [
+                                SpriteDrawMode::Simple,
+                                SpriteDrawMode::NineSlice { border },
+                                SpriteDrawMode::Tiled,
+                                SpriteDrawMode::TiledNineSlice { border },
+                            ] {
+                                let selected = std::mem::discriminant(&mode) == std::mem::discriminant(&sprite.draw_mode);
+                                if ui.selectable_label(selected, draw_mode_label(&mode)).clicked() && !selected {
+                                    sprite.draw_mode = mode;
+                                }
+                            }
+                        });
+                });
+                let texture_size = textures
+                    .texture(sprite.texture)
+                    .filter(|t| t.width > 0 && t.height > 0)
+                    .map(|t| glam::Vec2::new(t.width as f32, t.height as f32));
+                if let SpriteDrawMode::NineSlice { border } | SpriteDrawMode::TiledNineSlice { border } = &mut sprite.draw_mode {
+                    // Borders are in texels, so they only make sense against a loaded texture
+                    match texture_size {
+                        Some(texture_size) => slice_border_editor(ui, border, texture_size),
+                        None => {
+                            ui.label("Select a texture to edit the borders");
+                        }
+                    }
+                }
+
+                ui.separator();
+
+                // Material: loading an already loaded one recompiles its shader
+                ui.horizontal(|ui| {
+                    ui.label("Material:");
+                    if sprite.material.0 == 0 {
+                        ui.label("Default");
+                    } else {
+                        ui.label(format!("ID: {}", sprite.material.0));
+                        if ui.button("Clear").clicked() {
+                            sprite.material = AssetId::new(0);
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.material_path);
+                    if ui.button("Load").clicked() {
+                        self.pending_action = EditorAction::LoadSpriteMaterial {
+                            entity: handle.id(),
+                            path: self.material_path.clone(),
+                        };
+                    }
+                });
+
+                ui.separator();
+
+                // Normal map: only shades the sprite in scenes with lights
+                ui.horizontal(|ui| {
+                    ui.label("Normal Map:");
+                    if sprite.normal_map.0 == 0 {
+                        ui.label("None");
+                    } else {
+                        ui.label(format!("ID: {}", sprite.normal_map.0));
+                        if ui.button("Clear").clicked() {
+                            sprite.normal_map = AssetId::new(0);
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.normal_map_path);
+                    if ui.button("Load").clicked() {
+                        self.pending_action = EditorAction::LoadSpriteNormalMap {
+                            entity: handle.id(),
+                            path: self.normal_map_path.clone(),
+                        };
+                    }
+                });
             });
         }
     }
 
+    fn show_material_params_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        // Clone params to avoid borrow checker issues with UI
+        let Some(original) = world.get::<MaterialParams>(handle).ok().map(|p| (*p).clone()) else {
+            return;
+        };
+        let mut params = original.clone();
+
+        ui.group(|ui| {
+            ui.heading("Material Params");
+            ui.separator();
+
+            if params.0.is_empty() {
+                ui.label("No parameters");
+            }
+            for (name, value) in params.0.iter_mut() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", name));
+                    match value {
+                        MaterialValue::Float(x) => {
+                            ui.add(egui::DragValue::new(x).speed(0.01));
+                        }
+                        MaterialValue::Vec2(v) => {
+                            for x in v.iter_mut() {
+                                ui.add(egui::DragValue::new(x).speed(0.01));
+                            }
+                        }
+                        MaterialValue::Vec4(v) => {
+                            ui.color_edit_button_rgba_unmultiplied(v);
+                            for x in v.iter_mut() {
+                                ui.add(egui::DragValue::new(x).speed(0.01));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        if params != original {
+            if let Err(e) = world.set(handle, params) {
+                log::error!("Failed to update material params: {:?}", e);
+            }
+        }
+    }
+
     fn show_script_components(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
         // Query for all Script components on this entity
         // Clone script data to avoid borrow checker issues with UI
@@ -318,7 +640,7 @@ impl InspectorPanel {
 
                 for key in keys {
                     if let Some(value) = updated_properties.get(&key).cloned() {
-                        if let Some(new_value) = self.show_script_property_input(ui, &key, &value) {
+                        if let Some(new_value) = self.show_script_property_input(ui, world, &key, &value) {
                             updated_properties.insert(key, new_value);
                         }
                     }
@@ -347,7 +669,7 @@ impl InspectorPanel {
         }
     }
 
-    fn show_script_property_input(&mut self, ui: &mut Ui, key: &str, value: &ScriptValue) -> Option<ScriptValue> {
+    fn show_script_property_input(&mut self, ui: &mut Ui, world: &World, key: &str, value: &ScriptValue) -> Option<ScriptValue> {
         let mut result = None;
 
         ui.horizontal(|ui| {
@@ -388,6 +710,31 @@ impl InspectorPanel {
                         result = Some(ScriptValue::Vec2 { x: x_val, y: y_val });
                     }
                 }
+                ScriptValue::Entity { entity } => {
+                    let label = match entity.resolve(world) {
+                        Some(target) => world
+                            .get::<Name>(target)
+                            .map(|n| n.0.clone())
+                            .unwrap_or_else(|_| format!("Entity {}", target.id().id())),
+                        None if entity.is_none() => "None".to_string(),
+                        None => "Missing".to_string(),
+                    };
+
+                    // Drop slot accepting entities dragged from the scene tree (payload is entity bits)
+                    let (_, payload) = ui.dnd_drop_zone::<u64, ()>(egui::Frame::group(ui.style()), |ui| {
+                        ui.label(label);
+                    });
+                    if let Some(dropped) = payload.and_then(|bits| EntityId::from_bits(*bits)) {
+                        let dropped_ref = EntityRef::to_entity(world, EntityHandle::new(dropped));
+                        if !dropped_ref.is_none() {
+                            result = Some(ScriptValue::Entity { entity: dropped_ref });
+                        }
+                    }
+
+                    if !entity.is_none() && ui.small_button("x").on_hover_text("Clear reference").clicked() {
+                        result = Some(ScriptValue::Entity { entity: EntityRef::none() });
+                    }
+                }
             }
         });
 
@@ -396,52 +743,841 @@ impl InspectorPanel {
 
     fn show_camera_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
         // Clone camera data to avoid borrow checker issues with UI
-        let camera_data = if let Ok(camera) = world.get::<Camera>(handle) {
-            Some((camera.zoom, camera.viewport_size))
-        } else {
-            None
+        let Some(original) = world.get::<Camera>(handle).ok().map(|c| (*c).clone()) else {
+            return;
         };
+        let mut camera = original.clone();
+        let mut should_remove = false;
 
-        if let Some((mut zoom, viewport_size)) = camera_data {
-            let mut should_remove = false;
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Camera");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Remove").clicked() {
+                        should_remove = true;
+                    }
+                });
+            });
 
-            ui.group(|ui| {
+            ui.separator();
+
+            ui.checkbox(&mut camera.enabled, "Enabled");
+
+            // Zoom (editable)
+            ui.horizontal(|ui| {
+                ui.label("Zoom:");
+                ui.add(egui::DragValue::new(&mut camera.zoom).speed(0.01).range(0.1..=10.0));
+            });
+
+            // Render order (higher draws on top)
+            ui.horizontal(|ui| {
+                ui.label("Order:");
+                ui.add(egui::DragValue::new(&mut camera.order));
+            });
+
+            // Screen area (normalized)
+            ui.horizontal(|ui| {
+                ui.label("Rect:");
+                let viewport = &mut camera.viewport;
+                for (label, value) in [
+                    ("X", &mut viewport.x),
+                    ("Y", &mut viewport.y),
+                    ("W", &mut viewport.width),
+                    ("H", &mut viewport.height),
+                ] {
+                    ui.label(label);
+                    ui.add(egui::DragValue::new(value).speed(0.01).range(0.0..=1.0));
+                }
+            });
+
+            // Viewport size (read-only)
+            ui.horizontal(|ui| {
+                ui.label("Viewport:");
+                ui.label(format!("{}x{}", camera.viewport_size.x, camera.viewport_size.y));
+            });
+
+            if let Some(follow) = &mut camera.follow {
                 ui.horizontal(|ui| {
-                    ui.heading("Camera");
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.button("Remove").clicked() {
-                            should_remove = true;
-                        }
-                    });
+                    ui.label("Follow damping:");
+                    ui.add(egui::DragValue::new(&mut follow.damping).speed(0.01).range(0.0..=5.0));
                 });
+                ui.horizontal(|ui| {
+                    ui.label("Dead zone:");
+                    ui.add(egui::DragValue::new(&mut follow.dead_zone.x).speed(1.0).range(0.0..=f32::MAX));
+                    ui.add(egui::DragValue::new(&mut follow.dead_zone.y).speed(1.0).range(0.0..=f32::MAX));
+                });
+            }
 
-                ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Shake:");
+                ui.add(egui::DragValue::new(&mut camera.shake.max_offset.x).speed(0.5).prefix("x "));
+                ui.add(egui::DragValue::new(&mut camera.shake.max_offset.y).speed(0.5).prefix("y "));
+                ui.add(egui::DragValue::new(&mut camera.shake.decay).speed(0.05).prefix("decay "));
+            });
+        });
+
+        // Apply changes after UI rendering
+        if should_remove {
+            if let Err(e) = world.remove::<Camera>(handle) {
+                log::error!("Failed to remove camera: {:?}", e);
+            } else {
+                log::info!("Removed Camera component from entity");
+            }
+        } else if camera != original {
+            if let Err(e) = world.set(handle, camera) {
+                log::error!("Failed to update camera: {:?}", e);
+            }
+        }
+    }
+
+    fn show_tilemap_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        // Copy the settings only; tilemaps can hold a lot of tiles
+        let Some((tileset, cell_size, layers)) = world.get::<Tilemap>(handle).ok().map(|t| {
+            let layers: Vec<_> = t
+                .layers
+                .iter()
+                .map(|layer| (layer.name.clone(), layer.visible, layer.z_index, layer.tile_count()))
+                .collect();
+            (t.tileset, t.cell_size, layers)
+        }) else {
+            return;
+        };
+        let (mut new_tileset, mut new_cell_size, mut new_layers) = (tileset, cell_size, layers.clone());
+        let mut should_remove = false;
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Tilemap");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Remove").clicked() {
+                        should_remove = true;
+                    }
+                });
+            });
+
+            ui.separator();
 
-                // Zoom (editable)
+            ui.horizontal(|ui| {
+                ui.label("Tile Set ID:");
+                ui.add(egui::DragValue::new(&mut new_tileset.0));
+                if new_tileset.0 == 0 {
+                    ui.label("None");
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Cell Size:");
+                ui.add(egui::DragValue::new(&mut new_cell_size.x).speed(0.5).range(1.0..=f32::MAX).prefix("x: "));
+                ui.add(egui::DragValue::new(&mut new_cell_size.y).speed(0.5).range(1.0..=f32::MAX).prefix("y: "));
+            });
+
+            ui.label("Layers:");
+            for (name, visible, z_index, tile_count) in &mut new_layers {
                 ui.horizontal(|ui| {
-                    ui.label("Zoom:");
-                    ui.add(egui::DragValue::new(&mut zoom).speed(0.01).range(0.1..=10.0));
+                    ui.checkbox(visible, name.as_str());
+                    ui.add(egui::DragValue::new(z_index).prefix("z: "));
+                    ui.label(format!("{} tiles", tile_count));
+                });
+            }
+        });
+
+        // Apply changes after UI rendering
+        if should_remove {
+            if let Err(e) = world.remove::<Tilemap>(handle) {
+                log::error!("Failed to remove tilemap: {:?}", e);
+            } else {
+                log::info!("Removed Tilemap component from entity");
+            }
+        } else if new_tileset != tileset || new_cell_size != cell_size || new_layers != layers {
+            if let Ok(mut tilemap) = world.get_mut::<Tilemap>(handle) {
+                tilemap.tileset = new_tileset;
+                tilemap.cell_size = new_cell_size;
+                for (layer, (_, visible, z_index, _)) in tilemap.layers.iter_mut().zip(new_layers) {
+                    layer.visible = visible;
+                    layer.z_index = z_index;
+                }
+            }
+        }
+    }
+
+    fn show_text_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        // Clone text data to avoid borrow checker issues with UI
+        let Some(original) = world.get::<Text>(handle).ok().map(|t| (*t).clone()) else {
+            return;
+        };
+        let mut text = original.clone();
+        let mut should_remove = false;
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Text");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Remove").clicked() {
+                        should_remove = true;
+                    }
                 });
+            });
+
+            ui.separator();
+
+            ui.add(egui::TextEdit::multiline(&mut text.content).desired_rows(2));
+
+            // Font asset (None until one is assigned)
+            ui.horizontal(|ui| {
+                ui.label("Font ID:");
+                ui.add(egui::DragValue::new(&mut text.font.0));
+                if text.font.0 == 0 {
+                    ui.label("None");
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Size:");
+                ui.add(egui::DragValue::new(&mut text.size).speed(0.5).range(1.0..=512.0));
+            });
 
-                // Viewport size (read-only)
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                let mut color_rgb = [text.color[0], text.color[1], text.color[2]];
+                if ui.color_edit_button_rgb(&mut color_rgb).changed() {
+                    text.color[..3].copy_from_slice(&color_rgb);
+                }
+                ui.add(egui::Slider::new(&mut text.color[3], 0.0..=1.0).text("A").fixed_decimals(2));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Align:");
+                ui.selectable_value(&mut text.align, TextAlign::Left, "Left");
+                ui.selectable_value(&mut text.align, TextAlign::Center, "Center");
+                ui.selectable_value(&mut text.align, TextAlign::Right, "Right");
+            });
+
+            ui.horizontal(|ui| {
+                let mut wrap = text.wrap_width.is_some();
+                if ui.checkbox(&mut wrap, "Wrap").changed() {
+                    text.wrap_width = wrap.then_some(200.0);
+                }
+                if let Some(width) = &mut text.wrap_width {
+                    ui.add(egui::DragValue::new(width).speed(1.0).range(1.0..=f32::MAX).prefix("width "));
+                }
+            });
+        });
+
+        // Apply changes after UI rendering
+        if should_remove {
+            if let Err(e) = world.remove::<Text>(handle) {
+                log::error!("Failed to remove text: {:?}", e);
+            } else {
+                log::info!("Removed Text component from entity");
+            }
+        } else if text != original {
+            if let Err(e) = world.set(handle, text) {
+                log::error!("Failed to update text: {:?}", e);
+            }
+        }
+    }
+
+    fn show_particle_emitter_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        // Clone emitter data to avoid borrow checker issues with UI
+        let Some(original) = world.get::<ParticleEmitter>(handle).ok().map(|e| (*e).clone()) else {
+            return;
+        };
+        let particle_count = world.get::<ParticleState>(handle).map_or(0, |state| state.len());
+        let mut emitter = original.clone();
+        let mut should_remove = false;
+        let mut restart = false;
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Particle Emitter");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Remove").clicked() {
+                        should_remove = true;
+                    }
+                });
+            });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut emitter.playing, "Playing");
+                if ui.button("Burst 10").clicked() {
+                    emitter.burst(10);
+                }
+                if ui.button("Restart").clicked() {
+                    restart = true;
+                }
+                ui.label(format!("{} particles", particle_count));
+            });
+
+            // Texture asset (particles don't draw until one is assigned)
+            ui.horizontal(|ui| {
+                ui.label("Texture ID:");
+                ui.add(egui::DragValue::new(&mut emitter.texture.0));
+                if emitter.texture.0 == 0 {
+                    ui.label("None");
+                }
+                ui.label("Z:");
+                ui.add(egui::DragValue::new(&mut emitter.z_index));
+            });
+
+            let effect = &mut emitter.effect;
+            ui.horizontal(|ui| {
+                ui.label("Rate:");
+                ui.add(egui::DragValue::new(&mut effect.rate).speed(0.5).range(0.0..=10000.0).suffix("/s"));
+                ui.label("Max:");
+                ui.add(egui::DragValue::new(&mut effect.max_particles).range(0..=100000));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Duration:");
+                ui.add(egui::DragValue::new(&mut effect.duration).speed(0.05).range(0.01..=f32::MAX).suffix("s"));
+                ui.checkbox(&mut effect.looping, "Looping");
+            });
+
+            if !effect.bursts.is_empty() {
+                ui.label("Bursts:");
+                for burst in &mut effect.bursts {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut burst.count).prefix("count: "));
+                        ui.add(egui::DragValue::new(&mut burst.time).speed(0.05).range(0.0..=f32::MAX).prefix("at: ").suffix("s"));
+                    });
+                }
+            }
+
+            let range = |ui: &mut Ui, label: &str, range: &mut [f32; 2], speed: f32| {
                 ui.horizontal(|ui| {
-                    ui.label("Viewport:");
-                    ui.label(format!("{}x{}", viewport_size.x, viewport_size.y));
+                    ui.label(label);
+                    ui.add(egui::DragValue::new(&mut range[0]).speed(speed).prefix("min: "));
+                    ui.add(egui::DragValue::new(&mut range[1]).speed(speed).prefix("max: "));
                 });
+            };
+            range(ui, "Lifetime:", &mut effect.lifetime, 0.05);
+            range(ui, "Speed:", &mut effect.speed, 1.0);
+            range(ui, "Spin:", &mut effect.spin, 1.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Direction:");
+                ui.add(egui::DragValue::new(&mut effect.direction).speed(1.0).suffix("°"));
+                ui.label("Spread:");
+                ui.add(egui::DragValue::new(&mut effect.spread).speed(1.0).range(0.0..=360.0).suffix("°"));
             });
 
-            // Apply changes after UI rendering
-            if should_remove {
-                if let Err(e) = world.remove::<Camera>(handle) {
-                    log::error!("Failed to remove camera: {:?}", e);
-                } else {
-                    log::info!("Removed Camera component from entity");
+            ui.horizontal(|ui| {
+                ui.label("Radius:");
+                ui.add(egui::DragValue::new(&mut effect.radius).speed(0.5).range(0.0..=f32::MAX));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Gravity:");
+                ui.add(egui::DragValue::new(&mut effect.gravity.x).speed(1.0).prefix("x: "));
+                ui.add(egui::DragValue::new(&mut effect.gravity.y).speed(1.0).prefix("y: "));
+            });
+
+            // Curves are edited by their first and last keys
+            ui.horizontal(|ui| {
+                ui.label("Size:");
+                let mut keys = effect.size.keys().to_vec();
+                let last = keys.len().saturating_sub(1);
+                let mut changed = false;
+                if let Some((_, start)) = keys.first_mut() {
+                    changed |= ui.add(egui::DragValue::new(start).speed(0.5).range(0.0..=f32::MAX).prefix("start: ")).changed();
+                }
+                if last > 0 {
+                    changed |= ui.add(egui::DragValue::new(&mut keys[last].1).speed(0.5).range(0.0..=f32::MAX).prefix("end: ")).changed();
+                }
+                if changed {
+                    effect.size = Curve::new(keys);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                let mut keys = effect.color.keys().to_vec();
+                let last = keys.len().saturating_sub(1);
+                let mut changed = false;
+                if let Some((_, start)) = keys.first_mut() {
+                    changed |= ui.color_edit_button_rgba_unmultiplied(start).changed();
+                }
+                if last > 0 {
+                    ui.label("→");
+                    changed |= ui.color_edit_button_rgba_unmultiplied(&mut keys[last].1).changed();
+                }
+                if changed {
+                    effect.color = Curve::new(keys);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Space:");
+                ui.selectable_value(&mut effect.space, SimulationSpace::World, "World");
+                ui.selectable_value(&mut effect.space, SimulationSpace::Local, "Local");
+            });
+
+            ui.separator();
+
+            // Presets are loaded into and saved from the emitter by the editor
+            ui.horizontal(|ui| {
+                ui.label("Preset:");
+                ui.text_edit_singleline(&mut self.particle_preset_path);
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Load").clicked() {
+                    self.pending_action = EditorAction::LoadParticlePreset {
+                        entity: handle.id,
+                        path: self.particle_preset_path.clone(),
+                    };
                 }
+                if ui.button("Save").clicked() {
+                    self.pending_action = EditorAction::SaveParticlePreset {
+                        entity: handle.id,
+                        path: self.particle_preset_path.clone(),
+                    };
+                }
+            });
+        });
+
+        // Apply changes after UI rendering
+        if should_remove {
+            if let Err(e) = world.remove::<ParticleEmitter>(handle) {
+                log::error!("Failed to remove particle emitter: {:?}", e);
             } else {
-                // Update camera if zoom changed
-                if let Ok(mut camera) = world.get_mut::<Camera>(handle) {
-                    camera.zoom = zoom;
+                log::info!("Removed ParticleEmitter component from entity");
+            }
+            return;
+        }
+        if emitter != original {
+            if let Err(e) = world.set(handle, emitter) {
+                log::error!("Failed to update particle emitter: {:?}", e);
+            }
+        }
+        if restart {
+            // The state is runtime-only, so clearing it isn't a scene change
+            if let Ok(state) = world.query_one_mut::<&mut ParticleState>(handle) {
+                state.clear();
+            }
+        }
+    }
+
+    fn show_light_components(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        // Fields shared by point and spot lights
+        let light_fields = |ui: &mut Ui, color: &mut [f32; 3], intensity: &mut f32, radius: &mut f32, falloff: &mut f32, height: &mut f32, cast_shadows: &mut bool| {
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                ui.color_edit_button_rgb(color);
+                ui.label("Intensity:");
+                ui.add(egui::DragValue::new(intensity).speed(0.05).range(0.0..=f32::MAX));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Radius:");
+                ui.add(egui::DragValue::new(radius).speed(1.0).range(0.0..=f32::MAX));
+                ui.label("Falloff:");
+                ui.add(egui::DragValue::new(falloff).speed(0.05).range(0.0..=16.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Height:");
+                ui.add(egui::DragValue::new(height).speed(1.0).range(0.0..=f32::MAX));
+                ui.checkbox(cast_shadows, "Cast Shadows");
+            });
+        };
+
+        if let Some(original) = world.get::<PointLight2D>(handle).ok().map(|l| (*l).clone()) {
+            let mut light = original.clone();
+            let remove = Self::component_group(ui, "Point Light 2D", |ui| {
+                light_fields(ui, &mut light.color, &mut light.intensity, &mut light.radius, &mut light.falloff, &mut light.height, &mut light.cast_shadows);
+            });
+            Self::apply_component(world, handle, "point light", remove, original, light);
+        }
+
+        if let Some(original) = world.get::<SpotLight2D>(handle).ok().map(|l| (*l).clone()) {
+            let mut light = original.clone();
+            let remove = Self::component_group(ui, "Spot Light 2D", |ui| {
+                light_fields(ui, &mut light.color, &mut light.intensity, &mut light.radius, &mut light.falloff, &mut light.height, &mut light.cast_shadows);
+                ui.horizontal(|ui| {
+                    ui.label("Angle:");
+                    ui.add(egui::DragValue::new(&mut light.angle).speed(1.0).range(0.0..=360.0).suffix("°"));
+                    ui.label("Softness:");
+                    ui.add(egui::Slider::new(&mut light.softness, 0.0..=1.0).fixed_decimals(2));
+                });
+                ui.label("Points along the entity's rotation.");
+            });
+            Self::apply_component(world, handle, "spot light", remove, original, light);
+        }
+
+        if let Some(original) = world.get::<GlobalLight2D>(handle).ok().map(|l| (*l).clone()) {
+            let mut light = original.clone();
+            let remove = Self::component_group(ui, "Global Light 2D", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Color:");
+                    ui.color_edit_button_rgb(&mut light.color);
+                    ui.label("Intensity:");
+                    ui.add(egui::DragValue::new(&mut light.intensity).speed(0.01).range(0.0..=f32::MAX));
+                });
+            });
+            Self::apply_component(world, handle, "global light", remove, original, light);
+        }
+
+        if let Some(original) = world.get::<LightOccluder2D>(handle).ok().map(|o| (*o).clone()) {
+            let mut occluder = original.clone();
+            let remove = Self::component_group(ui, "Light Occluder 2D", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Shape:");
+                    let is_box = matches!(occluder.shape, OccluderShape::Box { .. });
+                    let is_circle = matches!(occluder.shape, OccluderShape::Circle { .. });
+                    if ui.selectable_label(is_box, "Box").clicked() && !is_box {
+                        occluder.shape = OccluderShape::Box { size: glam::Vec2::new(32.0, 32.0) };
+                    }
+                    if ui.selectable_label(is_circle, "Circle").clicked() && !is_circle {
+                        occluder.shape = OccluderShape::Circle { radius: 16.0 };
+                    }
+                    if let OccluderShape::Polygon { points } = &occluder.shape {
+                        ui.label(format!("Polygon ({} points)", points.len()));
+                    }
+                });
+                match &mut occluder.shape {
+                    OccluderShape::Box { size } => {
+                        ui.horizontal(|ui| {
+                            ui.label("Size:");
+                            ui.add(egui::DragValue::new(&mut size.x).prefix("W: ").speed(1.0).range(0.0..=f32::MAX));
+                            ui.add(egui::DragValue::new(&mut size.y).prefix("H: ").speed(1.0).range(0.0..=f32::MAX));
+                        });
+                    }
+                    OccluderShape::Circle { radius } => {
+                        ui.horizontal(|ui| {
+                            ui.label("Radius:");
+                            ui.add(egui::DragValue::new(radius).speed(0.5).range(0.0..=f32::MAX));
+                        });
+                    }
+                    OccluderShape::Polygon { points } => {
+                        for point in points.iter_mut() {
+                            ui.horizontal(|ui| {
+                                ui.add(egui::DragValue::new(&mut point.x).prefix("x: ").speed(0.5));
+                                ui.add(egui::DragValue::new(&mut point.y).prefix("y: ").speed(0.5));
+                            });
+                        }
+                    }
+                }
+            });
+            Self::apply_component(world, handle, "light occluder", remove, original, occluder);
+        }
+    }
+
+    fn show_shape_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        let Some(original) = world.get::<Shape>(handle).ok().map(|s| (*s).clone()) else {
+            return;
+        };
+        let mut shape = original.clone();
+        let remove = Self::component_group(ui, "Shape", |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Kind:");
+                for (name, preset) in shape_presets() {
+                    let selected = std::mem::discriminant(&shape.geometry) == std::mem::discriminant(&preset.geometry);
+                    if ui.selectable_label(selected, name).clicked() && !selected {
+                        shape.geometry = preset.geometry;
+                    }
+                }
+            });
+            match &mut shape.geometry {
+                ShapeGeometry::Rectangle { size } | ShapeGeometry::RoundedRectangle { size, .. } => {
+                    ui.horizontal(|ui| {
+                        ui.label("Size:");
+                        ui.add(egui::DragValue::new(&mut size.x).prefix("W: ").speed(1.0).range(0.0..=f32::MAX));
+                        ui.add(egui::DragValue::new(&mut size.y).prefix("H: ").speed(1.0).range(0.0..=f32::MAX));
+                    });
+                }
+                ShapeGeometry::Circle { radius } => {
+                    ui.horizontal(|ui| {
+                        ui.label("Radius:");
+                        ui.add(egui::DragValue::new(radius).speed(0.5).range(0.0..=f32::MAX));
+                    });
+                }
+                ShapeGeometry::Polygon { points } | ShapeGeometry::Polyline { points } => {
+                    let mut removed = None;
+                    for (i, point) in points.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::DragValue::new(&mut point.x).prefix("x: ").speed(0.5));
+                            ui.add(egui::DragValue::new(&mut point.y).prefix("y: ").speed(0.5));
+                            if ui.small_button("✖").clicked() {
+                                removed = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = removed {
+                        points.remove(i);
+                    }
+                    if ui.button("Add Point").clicked() {
+                        let next = points.last().map_or(glam::Vec2::ZERO, |p| *p + glam::Vec2::new(16.0, 0.0));
+                        points.push(next);
+                    }
+                }
+            }
+            if let ShapeGeometry::RoundedRectangle { radius, .. } = &mut shape.geometry {
+                ui.horizontal(|ui| {
+                    ui.label("Corner Radius:");
+                    ui.add(egui::DragValue::new(radius).speed(0.5).range(0.0..=f32::MAX));
+                });
+            }
+
+            if shape.geometry.is_closed() {
+                let mut filled = shape.fill.is_some();
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut filled, "Fill").changed() {
+                        shape.fill = filled.then(ShapeFill::default);
+                    }
+                    if let Some(fill) = &mut shape.fill {
+                        let color = first_fill_color(fill);
+                        let options = [
+                            ("Solid", ShapeFill::Solid { color }),
+                            ("Linear", ShapeFill::LinearGradient { start: color, end: [0.0, 0.0, 0.0, 1.0], angle: 0.0 }),
+                            ("Radial", ShapeFill::RadialGradient { center: color, edge: [0.0, 0.0, 0.0, 1.0] }),
+                        ];
+                        for (name, option) in options {
+                            let selected = std::mem::discriminant(fill) == std::mem::discriminant(&option);
+                            if ui.selectable_label(selected, name).clicked() && !selected {
+                                *fill = option;
+                            }
+                        }
+                    }
+                });
+                if let Some(fill) = &mut shape.fill {
+                    ui.horizontal(|ui| {
+                        match fill {
+                            ShapeFill::Solid { color } => {
+                                ui.label("Color:");
+                                ui.color_edit_button_rgba_unmultiplied(color);
+                            }
+                            ShapeFill::LinearGradient { start, end, angle } => {
+                                ui.label("From:");
+                                ui.color_edit_button_rgba_unmultiplied(start);
+                                ui.label("To:");
+                                ui.color_edit_button_rgba_unmultiplied(end);
+                                ui.label("Angle:");
+                                ui.drag_angle(angle);
+                            }
+                            ShapeFill::RadialGradient { center, edge } => {
+                                ui.label("Center:");
+                                ui.color_edit_button_rgba_unmultiplied(center);
+                                ui.label("Edge:");
+                                ui.color_edit_button_rgba_unmultiplied(edge);
+                            }
+                        }
+                    });
+                }
+            }
+
+            let mut stroked = shape.stroke.is_some();
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut stroked, "Stroke").changed() {
+                    shape.stroke = stroked.then(|| ShapeStroke::new([0.0, 0.0, 0.0, 1.0], 2.0));
+                }
+                if let Some(stroke) = &mut shape.stroke {
+                    ui.color_edit_button_rgba_unmultiplied(&mut stroke.color);
+                    ui.label("Width:");
+                    ui.add(egui::DragValue::new(&mut stroke.width).speed(0.1).range(0.0..=f32::MAX));
                 }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Z Index:");
+                ui.add(egui::DragValue::new(&mut shape.z_index));
+            });
+        });
+        Self::apply_component(world, handle, "shape", remove, original, shape);
+    }
+
+    fn show_animation_player_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        let Some(original) = world.get::<AnimationPlayer>(handle).ok().map(|p| (*p).clone()) else {
+            return;
+        };
+        let mut player = original.clone();
+        let remove = Self::component_group(ui, "Animation Player", |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut player.playing, "Play on start");
+                ui.label("Speed:");
+                ui.add(egui::DragValue::new(&mut player.speed).speed(0.05));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Clip ID:");
+                ui.add(egui::DragValue::new(&mut player.clip.0));
+                if player.clip.0 == 0 {
+                    ui.label("None");
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Clip:");
+                ui.text_edit_singleline(&mut self.animation_clip_path);
+                if ui.button("Load").clicked() {
+                    self.pending_action = EditorAction::LoadAnimationClip {
+                        entity: handle.id,
+                        path: self.animation_clip_path.clone(),
+                    };
+                }
+            });
+        });
+        Self::apply_component(world, handle, "animation player", remove, original, player);
+    }
+
+    fn show_state_machine_component(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        let Some(original) = world.get::<StateMachine>(handle).ok().map(|m| (*m).clone()) else {
+            return;
+        };
+        let mut machine = original.clone();
+        let remove = Self::component_group(ui, "State Machine", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Chart ID:");
+                ui.add(egui::DragValue::new(&mut machine.chart.0));
+                if machine.chart.0 == 0 {
+                    ui.label("None");
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Chart:");
+                ui.text_edit_singleline(&mut self.state_chart_path);
+                if ui.button("Load").clicked() {
+                    self.pending_action = EditorAction::LoadStateChart {
+                        entity: handle.id,
+                        path: self.state_chart_path.clone(),
+                    };
+                }
+            });
+            if !machine.active_states().is_empty() {
+                ui.label(format!("State: {}", machine.active_states().join(" / ")));
+            }
+            // Values set here override the chart's starting values
+            let mut names: Vec<String> = machine.parameters.keys().cloned().collect();
+            names.sort();
+            for name in names {
+                ui.horizontal(|ui| {
+                    ui.label(&name);
+                    match machine.parameters.get_mut(&name) {
+                        Some(ParameterValue::Bool(value)) => {
+                            ui.checkbox(value, "");
+                        }
+                        Some(ParameterValue::Number(value)) => {
+                            ui.add(egui::DragValue::new(value).speed(0.1));
+                        }
+                        None => {}
+                    }
+                    if ui.small_button("✖").clicked() {
+                        machine.parameters.remove(&name);
+                    }
+                });
+            }
+        });
+        Self::apply_component(world, handle, "state machine", remove, original, machine);
+    }
+
+    fn show_navigation_components(&mut self, ui: &mut Ui, world: &mut World, handle: EntityHandle) {
+        if let Some(original) = world.get::<NavAgent>(handle).ok().map(|a| (*a).clone()) {
+            let mut agent = original.clone();
+            let remove = Self::component_group(ui, "Nav Agent", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Speed:");
+                    ui.add(egui::DragValue::new(&mut agent.speed).speed(1.0).range(0.0..=f32::MAX));
+                    ui.label("Stopping Distance:");
+                    ui.add(egui::DragValue::new(&mut agent.stopping_distance).speed(0.5).range(0.0..=f32::MAX));
+                });
+                let mut has_destination = agent.destination.is_some();
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut has_destination, "Destination:");
+                    let mut destination = agent.destination.unwrap_or_default();
+                    ui.add_enabled_ui(has_destination, |ui| {
+                        ui.add(egui::DragValue::new(&mut destination.x).speed(1.0).prefix("x: "));
+                        ui.add(egui::DragValue::new(&mut destination.y).speed(1.0).prefix("y: "));
+                    });
+                    agent.destination = has_destination.then_some(destination);
+                });
+                ui.label(format!("Status: {} ({} waypoints)", agent.status.name(), agent.path.len()));
+            });
+            Self::apply_component(world, handle, "nav agent", remove, original, agent);
+        }
+
+        if let Some(original) = world.get::<NavObstacle>(handle).ok().map(|o| *o) {
+            let mut obstacle = original;
+            let remove = Self::component_group(ui, "Nav Obstacle", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Size:");
+                    ui.add(egui::DragValue::new(&mut obstacle.size.x).speed(1.0).range(0.0..=f32::MAX).prefix("w: "));
+                    ui.add(egui::DragValue::new(&mut obstacle.size.y).speed(1.0).range(0.0..=f32::MAX).prefix("h: "));
+                });
+            });
+            Self::apply_component(world, handle, "nav obstacle", remove, original, obstacle);
+        }
+
+        if let Some(original) = world.get::<NavRegion>(handle).ok().map(|r| *r) {
+            let mut region = original;
+            let remove = Self::component_group(ui, "Nav Region", |ui| {
+                ui.horizontal(|ui| {
+                    match region.navmesh {
+                        Some(navmesh) => {
+                            ui.label(format!("Navmesh ID: {}", navmesh.0));
+                            if ui.button("Use Tilemap").clicked() {
+                                region.navmesh = None;
+                            }
+                        }
+                        None => {
+                            ui.label("Walks the entity's Tilemap");
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Navmesh:");
+                    ui.text_edit_singleline(&mut self.navmesh_path);
+                    if ui.button("Load").clicked() {
+                        self.pending_action = EditorAction::LoadNavMesh {
+                            entity: handle.id,
+                            path: self.navmesh_path.clone(),
+                        };
+                    }
+                    if ui.button("Bake").clicked() {
+                        self.pending_action = EditorAction::BakeNavMesh {
+                            entity: handle.id,
+                            path: self.navmesh_path.clone(),
+                        };
+                    }
+                });
+            });
+            Self::apply_component(world, handle, "nav region", remove, original, region);
+        }
+    }
+
+    /// Show a component's section with a Remove button in its header
+    ///
+    /// # Returns
+    /// `true` if Remove was clicked
+    fn component_group(ui: &mut Ui, title: &str, contents: impl FnOnce(&mut Ui)) -> bool {
+        let mut remove = false;
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.heading(title);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Remove").clicked() {
+                        remove = true;
+                    }
+                });
+            });
+            ui.separator();
+            contents(ui);
+        });
+        remove
+    }
+
+    /// Write back a component edited in a `component_group`, or remove it
+    fn apply_component<T: hecs::Component + PartialEq>(
+        world: &mut World,
+        handle: EntityHandle,
+        name: &str,
+        remove: bool,
+        original: T,
+        edited: T,
+    ) {
+        if remove {
+            if let Err(e) = world.remove::<T>(handle) {
+                log::error!("Failed to remove {}: {:?}", name, e);
+            }
+        } else if edited != original {
+            if let Err(e) = world.set(handle, edited) {
+                log::error!("Failed to update {}: {:?}", name, e);
             }
         }
     }
@@ -475,3 +1611,71 @@ impl Default for InspectorPanel {
         Self::new()
     }
 }
+
+/// First color of a fill, kept when switching between fill types
+fn first_fill_color(fill: &ShapeFill) -> [f32; 4] {
+    match fill {
+        ShapeFill::Solid { color } => *color,
+        ShapeFill::LinearGradient { start, .. } => *start,
+        ShapeFill::RadialGradient { center, .. } => *center,
+    }
+}
+
+fn draw_mode_label(mode: &SpriteDrawMode) -> &'static str {
+    match mode {
+        SpriteDrawMode::Simple => "Simple",
+        SpriteDrawMode::NineSlice { .. } => "Nine Slice",
+        SpriteDrawMode::Tiled => "Tiled",
+        SpriteDrawMode::TiledNineSlice { .. } => "Tiled Nine Slice",
+    }
+}
+
+/// Border fields plus an outline of the texture with a draggable handle per
+/// border, all clamped so opposite borders don't cross
+fn slice_border_editor(ui: &mut Ui, border: &mut SliceBorder, texture_size: glam::Vec2) {
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(&mut border.left).prefix("L: ").speed(1.0).range(0.0..=texture_size.x));
+        ui.add(egui::DragValue::new(&mut border.right).prefix("R: ").speed(1.0).range(0.0..=texture_size.x));
+        ui.add(egui::DragValue::new(&mut border.top).prefix("T: ").speed(1.0).range(0.0..=texture_size.y));
+        ui.add(egui::DragValue::new(&mut border.bottom).prefix("B: ").speed(1.0).range(0.0..=texture_size.y));
+    });
+
+    const PREVIEW_SIZE: f32 = 160.0;
+    let scale = (PREVIEW_SIZE / texture_size.x).min(PREVIEW_SIZE / texture_size.y);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(texture_size.x * scale, texture_size.y * scale), egui::Sense::hover());
+    let painter = ui.painter_at(rect.expand(2.0));
+    painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, ui.visuals().weak_text_color()));
+
+    let stroke = egui::Stroke::new(1.0, ui.visuals().selection.stroke.color);
+    let hovered = egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
+    // (value, vertical line, measured from the far edge, id)
+    let handles: [(&mut f32, bool, bool, &str); 4] = [
+        (&mut border.left, true, false, "left"),
+        (&mut border.right, true, true, "right"),
+        (&mut border.top, false, false, "top"),
+        (&mut border.bottom, false, true, "bottom"),
+    ];
+    for (value, vertical, from_end, id) in handles {
+        let extent = if vertical { rect.width() } else { rect.height() };
+        let offset = if from_end { extent - *value * scale } else { *value * scale };
+        let line = if vertical {
+            let x = rect.left() + offset;
+            [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())]
+        } else {
+            let y = rect.top() + offset;
+            [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)]
+        };
+        let hit = egui::Rect::from_two_pos(line[0], line[1]).expand(3.0);
+        let response = ui.interact(hit, ui.id().with(("slice_border", id)), egui::Sense::drag());
+        if response.dragged() {
+            let delta = if vertical { response.drag_delta().x } else { response.drag_delta().y };
+            let delta = if from_end { -delta } else { delta } / scale;
+            *value = (*value + delta).clamp(0.0, if vertical { texture_size.x } else { texture_size.y });
+        }
+        painter.line_segment(line, if response.hovered() || response.dragged() { hovered } else { stroke });
+    }
+
+    // Keep opposite borders from overlapping
+    border.right = border.right.min(texture_size.x - border.left);
+    border.bottom = border.bottom.min(texture_size.y - border.top);
+}
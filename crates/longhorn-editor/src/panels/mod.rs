@@ -5,6 +5,9 @@ mod console;
 mod script_editor;
 mod project_panel;
 mod startup;
+mod texture_import;
+mod timeline;
+mod state_machine;
 
 pub use scene_tree::*;
 pub use inspector::*;
@@ -13,3 +16,6 @@ pub use console::*;
 pub use script_editor::*;
 pub use project_panel::*;
 pub use startup::{StartupPanel, StartupAction};
+pub use texture_import::TextureImportPanel;
+pub use timeline::TimelinePanel;
+pub use state_machine::StateMachinePanel;
@@ -0,0 +1,780 @@
+use egui::{pos2, vec2, Color32, Pos2, Rect, RichText, Sense, Stroke, Ui, Vec2};
+use longhorn_assets::{AssetManager, FilesystemSource};
+use longhorn_core::{
+    AssetId, Compare, Condition, EntityHandle, ParameterValue, State, StateAction, StateChart, StateMachine, Transition,
+    World,
+};
+use crate::EditorState;
+use crate::styling::Colors;
+
+/// Size of a state without children
+const NODE_SIZE: Vec2 = vec2(120.0, 36.0);
+/// Space between a parent state's border and its children
+const PADDING: f32 = 12.0;
+/// Height of a parent state's title bar
+const HEADER_HEIGHT: f32 = 22.0;
+/// Distance from a transition arrow that still selects it
+const ARROW_HIT_DISTANCE: f32 = 5.0;
+
+/// What is selected in the graph
+#[derive(Debug, Clone, PartialEq)]
+enum Selection {
+    State(String),
+    /// Transition of a state, by index
+    Transition(String, usize),
+}
+
+/// Graph editor for the state chart of the selected entity's `StateMachine`
+///
+/// Edits a copy of the chart; changes reach the asset (and running
+/// machines) when saved. During play the graph is read-only and highlights
+/// the machine's active states and current parameter values.
+pub struct StateMachinePanel {
+    /// Chart being edited and the ID it was loaded from
+    chart: Option<(AssetId, StateChart)>,
+    /// Whether the chart has unsaved changes
+    dirty: bool,
+    selection: Option<Selection>,
+    /// Graph scroll offset
+    pan: Vec2,
+    /// Name typed for the selected state, applied when editing ends
+    rename: String,
+    /// Name typed for new parameters
+    parameter_name: String,
+}
+
+impl StateMachinePanel {
+    pub fn new() -> Self {
+        Self {
+            chart: None,
+            dirty: false,
+            selection: None,
+            pan: Vec2::ZERO,
+            rename: String::new(),
+            parameter_name: "parameter".to_string(),
+        }
+    }
+
+    /// Edit a chart, discarding unsaved changes to another one
+    fn open(&mut self, chart_id: AssetId, assets: &mut AssetManager<FilesystemSource>) {
+        if self.dirty {
+            log::warn!("Discarding unsaved changes to state chart {:?}", self.chart.as_ref().map(|(id, _)| *id));
+        }
+        self.dirty = false;
+        self.selection = None;
+        self.pan = Vec2::ZERO;
+        self.chart = match assets.load_state_chart_by_id(chart_id) {
+            Ok(handle) => assets.get_state_chart(handle).map(|chart| (chart_id, (*chart).clone())),
+            Err(e) => {
+                log::debug!("State chart {:?} not loaded: {}", chart_id, e);
+                None
+            }
+        };
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        world: &mut World,
+        assets: &mut AssetManager<FilesystemSource>,
+        state: &EditorState,
+    ) {
+        ui.heading("State Machine");
+        ui.separator();
+
+        let Some(entity) = state.selected_entity.map(EntityHandle::new) else {
+            ui.label("Select an entity with a State Machine");
+            return;
+        };
+        let Some(machine) = world.get::<StateMachine>(entity).ok().map(|m| (*m).clone()) else {
+            ui.label("Select an entity with a State Machine");
+            return;
+        };
+        if self.chart.as_ref().map(|(id, _)| *id) != Some(machine.chart) {
+            self.open(machine.chart, assets);
+        }
+        let Some((chart_id, chart)) = &mut self.chart else {
+            ui.label(RichText::new(format!("Chart {} isn't loaded", machine.chart.0)).color(Colors::TEXT_MUTED));
+            return;
+        };
+        let chart_id = *chart_id;
+
+        let playing = state.is_playing();
+        let mut save = false;
+        ui.horizontal(|ui| {
+            if playing {
+                let active = machine.active_states().join(" / ");
+                ui.label(RichText::new(format!("Active: {}", if active.is_empty() { "-" } else { &active })).color(Colors::SUCCESS));
+                ui.label(RichText::new("(Read-only during play)").color(Colors::TEXT_MUTED));
+            } else {
+                if ui.button("Add State").clicked() {
+                    let name = unique_name(chart, "State");
+                    let position = [-self.pan.x + 20.0, -self.pan.y + 20.0];
+                    chart.states.push(State { position, ..State::new(name.clone()) });
+                    self.selection = Some(Selection::State(name));
+                    self.dirty = true;
+                }
+                let label = if self.dirty { "Save*" } else { "Save" };
+                save = ui.add_enabled(self.dirty, egui::Button::new(label)).clicked();
+            }
+        });
+
+        ui.add_enabled_ui(!playing, |ui| {
+            self.dirty |= parameters_editor(ui, chart, &machine, playing, &mut self.parameter_name);
+            if let Some(selection) = self.selection.clone() {
+                self.dirty |= selection_editor(ui, chart, &mut self.selection, &selection, &mut self.rename);
+            }
+        });
+        ui.separator();
+
+        self.dirty |= graph(ui, chart, &machine, playing, &mut self.selection, &mut self.pan);
+        if playing {
+            ui.ctx().request_repaint();
+        }
+
+        if save {
+            match assets.state_chart_path(chart_id).map(str::to_string) {
+                Some(path) => match assets.save_state_chart(&path, chart) {
+                    Ok(_) => {
+                        self.dirty = false;
+                        log::info!("Saved state chart: {}", path);
+                    }
+                    Err(e) => log::error!("Failed to save state chart {}: {}", path, e),
+                },
+                None => log::error!("State chart {:?} has no path", chart_id),
+            }
+        }
+    }
+}
+
+impl Default for StateMachinePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Edit the chart's parameters and starting values; during play, shows the
+/// machine's current values instead
+///
+/// # Returns
+/// `true` if the chart changed
+fn parameters_editor(ui: &mut Ui, chart: &mut StateChart, machine: &StateMachine, playing: bool, new_name: &mut String) -> bool {
+    let mut changed = false;
+    egui::CollapsingHeader::new("Parameters").default_open(true).show(ui, |ui| {
+        let mut remove = None;
+        for (name, value) in chart.parameters.iter_mut() {
+            ui.horizontal(|ui| {
+                ui.label(name);
+                if playing {
+                    let current = machine.parameter(name).unwrap_or(*value);
+                    ui.label(RichText::new(parameter_text(current)).color(Colors::ACCENT));
+                    return;
+                }
+                changed |= parameter_value_editor(ui, value);
+                if ui.small_button("✖").clicked() {
+                    remove = Some(name.clone());
+                }
+            });
+        }
+        if let Some(name) = remove {
+            chart.parameters.remove(&name);
+            changed = true;
+        }
+        if playing {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(new_name);
+            let valid = !new_name.is_empty() && !chart.parameters.contains_key(new_name.as_str());
+            if ui.add_enabled(valid, egui::Button::new("+ Bool")).clicked() {
+                chart.parameters.insert(new_name.clone(), ParameterValue::Bool(false));
+                changed = true;
+            }
+            if ui.add_enabled(valid, egui::Button::new("+ Number")).clicked() {
+                chart.parameters.insert(new_name.clone(), ParameterValue::Number(0.0));
+                changed = true;
+            }
+        });
+    });
+    changed
+}
+
+/// Edit the selected state or transition
+///
+/// # Returns
+/// `true` if the chart changed
+fn selection_editor(
+    ui: &mut Ui,
+    chart: &mut StateChart,
+    selection: &mut Option<Selection>,
+    selected: &Selection,
+    rename: &mut String,
+) -> bool {
+    let names: Vec<String> = chart.iter().map(|state| state.name.clone()).collect();
+    let parameters: Vec<(String, ParameterValue)> = chart.parameters.iter().map(|(n, v)| (n.clone(), *v)).collect();
+    let mut changed = false;
+    match selected {
+        Selection::State(name) => {
+            let is_initial = match chart.parent_of(name) {
+                Some(parent) => chart.find(parent).and_then(State::initial_state).is_some_and(|s| &s.name == name),
+                None => chart.initial_state().is_some_and(|s| &s.name == name),
+            };
+            let Some(state) = chart.find_mut(name) else {
+                *selection = None;
+                return false;
+            };
+            let mut new_name = None;
+            let mut delete = false;
+            let mut make_initial = false;
+            egui::CollapsingHeader::new(format!("State: {}", name)).default_open(true).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    if !ui.memory(|m| m.has_focus(ui.id().with("state_name"))) && rename.as_str() != name {
+                        *rename = name.clone();
+                    }
+                    let response = ui.add(egui::TextEdit::singleline(rename).id(ui.id().with("state_name")));
+                    if response.lost_focus() && rename.as_str() != name {
+                        new_name = Some(rename.clone());
+                    }
+                    make_initial = ui.add_enabled(!is_initial, egui::Button::new("Set Initial")).clicked();
+                    delete = ui.button("Delete").clicked();
+                });
+                ui.label("Transitions:");
+                let mut remove = None;
+                for (index, transition) in state.transitions.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let label = format!("→ {}", transition.to);
+                        if ui.selectable_label(false, label).on_hover_text(transition_text(transition)).clicked() {
+                            *selection = Some(Selection::Transition(name.clone(), index));
+                        }
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    state.transitions.remove(index);
+                    changed = true;
+                }
+                ui.menu_button("Add Transition", |ui| {
+                    for target in &names {
+                        if ui.button(target).clicked() {
+                            state.transitions.push(Transition::new(target.clone()));
+                            *selection = Some(Selection::Transition(name.clone(), state.transitions.len() - 1));
+                            changed = true;
+                            ui.close_menu();
+                        }
+                    }
+                });
+                changed |= actions_editor(ui, "On Enter", &mut state.on_enter, &parameters);
+                changed |= actions_editor(ui, "On Exit", &mut state.on_exit, &parameters);
+            });
+
+            if make_initial {
+                match chart.parent_of(name).map(str::to_string) {
+                    Some(parent) => chart.find_mut(&parent).expect("parent exists").initial = Some(name.clone()),
+                    None => chart.initial = Some(name.clone()),
+                }
+                changed = true;
+            }
+            if let Some(new_name) = new_name {
+                if new_name.is_empty() || chart.find(&new_name).is_some() {
+                    log::warn!("Can't rename state to '{}': the name is empty or taken", new_name);
+                } else {
+                    rename_state(chart, name, &new_name);
+                    *selection = Some(Selection::State(new_name));
+                    changed = true;
+                }
+            }
+            if delete {
+                delete_state(chart, name);
+                *selection = None;
+                changed = true;
+            }
+        }
+        Selection::Transition(from, index) => {
+            let Some(transition) = chart.find_mut(from).and_then(|state| state.transitions.get_mut(*index)) else {
+                *selection = None;
+                return false;
+            };
+            egui::CollapsingHeader::new(format!("Transition: {} → {}", from, transition.to))
+                .default_open(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("To:");
+                        egui::ComboBox::from_id_salt("transition_target")
+                            .selected_text(transition.to.clone())
+                            .show_ui(ui, |ui| {
+                                for target in &names {
+                                    changed |= ui.selectable_value(&mut transition.to, target.clone(), target).changed();
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        let mut has_event = transition.event.is_some();
+                        changed |= ui.checkbox(&mut has_event, "On event").changed();
+                        match (has_event, &mut transition.event) {
+                            (true, Some(event)) => changed |= ui.text_edit_singleline(event).changed(),
+                            (true, event @ None) => *event = Some("event".to_string()),
+                            (false, event) => *event = None,
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        let mut has_after = transition.after.is_some();
+                        changed |= ui.checkbox(&mut has_after, "After").changed();
+                        match (has_after, &mut transition.after) {
+                            (true, Some(after)) => {
+                                changed |= ui.add(egui::DragValue::new(after).speed(0.05).range(0.0..=f32::MAX).suffix("s")).changed();
+                            }
+                            (true, after @ None) => *after = Some(1.0),
+                            (false, after) => *after = None,
+                        }
+                    });
+                    ui.label("Conditions:");
+                    let mut remove = None;
+                    for (index, condition) in transition.conditions.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            changed |= condition_editor(ui, index, condition, &parameters);
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(index);
+                            }
+                        });
+                    }
+                    if let Some(index) = remove {
+                        transition.conditions.remove(index);
+                        changed = true;
+                    }
+                    let first = parameters.first().cloned();
+                    if ui.add_enabled(first.is_some(), egui::Button::new("Add Condition")).clicked() {
+                        if let Some((parameter, value)) = first {
+                            transition.conditions.push(Condition {
+                                parameter,
+                                compare: Compare::Equal,
+                                value,
+                            });
+                            changed = true;
+                        }
+                    }
+                });
+        }
+    }
+    changed
+}
+
+fn condition_editor(ui: &mut Ui, index: usize, condition: &mut Condition, parameters: &[(String, ParameterValue)]) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(("condition_parameter", index))
+        .selected_text(condition.parameter.clone())
+        .show_ui(ui, |ui| {
+            for (name, value) in parameters {
+                if ui.selectable_label(&condition.parameter == name, name).clicked() {
+                    condition.parameter = name.clone();
+                    condition.value = *value;
+                    changed = true;
+                }
+            }
+        });
+    egui::ComboBox::from_id_salt(("condition_compare", index))
+        .width(40.0)
+        .selected_text(condition.compare.symbol())
+        .show_ui(ui, |ui| {
+            for compare in Compare::ALL {
+                changed |= ui.selectable_value(&mut condition.compare, compare, compare.symbol()).changed();
+            }
+        });
+    changed |= parameter_value_editor(ui, &mut condition.value);
+    changed
+}
+
+/// Edit a list of state actions
+///
+/// # Returns
+/// `true` if the actions changed
+fn actions_editor(ui: &mut Ui, title: &str, actions: &mut Vec<StateAction>, parameters: &[(String, ParameterValue)]) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(format!("{}:", title));
+        ui.menu_button("+", |ui| {
+            if let Some((parameter, value)) = parameters.first().cloned() {
+                if ui.button("Set Parameter").clicked() {
+                    actions.push(StateAction::Set { parameter, value });
+                    changed = true;
+                    ui.close_menu();
+                }
+            }
+            if ui.button("Emit Event").clicked() {
+                actions.push(StateAction::Emit {
+                    event: "event".to_string(),
+                    data: serde_json::Value::Null,
+                });
+                changed = true;
+                ui.close_menu();
+            }
+            if ui.button("Play Animation").clicked() {
+                actions.push(StateAction::PlayAnimation {
+                    clip: "animations/clip.anim".to_string(),
+                    crossfade: 0.2,
+                });
+                changed = true;
+                ui.close_menu();
+            }
+        });
+    });
+    let mut remove = None;
+    for (index, action) in actions.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add_space(12.0);
+            match action {
+                StateAction::Set { parameter, value } => {
+                    ui.label("Set");
+                    egui::ComboBox::from_id_salt((title, "action_parameter", index))
+                        .selected_text(parameter.clone())
+                        .show_ui(ui, |ui| {
+                            for (name, default) in parameters {
+                                if ui.selectable_label(parameter == name, name).clicked() {
+                                    *parameter = name.clone();
+                                    *value = *default;
+                                    changed = true;
+                                }
+                            }
+                        });
+                    changed |= parameter_value_editor(ui, value);
+                }
+                StateAction::Emit { event, .. } => {
+                    ui.label("Emit");
+                    changed |= ui.text_edit_singleline(event).changed();
+                }
+                StateAction::PlayAnimation { clip, crossfade } => {
+                    ui.label("Play");
+                    changed |= ui.text_edit_singleline(clip).changed();
+                    changed |= ui
+                        .add(egui::DragValue::new(crossfade).speed(0.01).range(0.0..=f32::MAX).prefix("fade: ").suffix("s"))
+                        .changed();
+                }
+            }
+            if ui.small_button("✖").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        actions.remove(index);
+        changed = true;
+    }
+    changed
+}
+
+fn parameter_value_editor(ui: &mut Ui, value: &mut ParameterValue) -> bool {
+    match value {
+        ParameterValue::Bool(b) => ui.checkbox(b, "").changed(),
+        ParameterValue::Number(n) => ui.add(egui::DragValue::new(n).speed(0.1)).changed(),
+    }
+}
+
+fn parameter_text(value: ParameterValue) -> String {
+    match value {
+        ParameterValue::Bool(b) => b.to_string(),
+        ParameterValue::Number(n) => format!("{:.2}", n),
+    }
+}
+
+/// Requirements of a transition, e.g. `jump, speed > 1, after 0.5s`
+fn transition_text(transition: &Transition) -> String {
+    let mut parts: Vec<String> = transition.event.iter().cloned().collect();
+    parts.extend(transition.conditions.iter().map(|c| {
+        format!("{} {} {}", c.parameter, c.compare.symbol(), parameter_text(c.value))
+    }));
+    parts.extend(transition.after.map(|after| format!("after {:.2}s", after)));
+    parts.join(", ")
+}
+
+/// Draw and edit the graph: states as boxes with parents around their
+/// children, transitions as arrows
+///
+/// # Returns
+/// `true` if the chart changed
+fn graph(
+    ui: &mut Ui,
+    chart: &mut StateChart,
+    machine: &StateMachine,
+    playing: bool,
+    selection: &mut Option<Selection>,
+    pan: &mut Vec2,
+) -> bool {
+    let (canvas, background) = ui.allocate_exact_size(ui.available_size(), Sense::click_and_drag());
+    let painter = ui.painter_at(canvas);
+    painter.rect_filled(canvas, 0.0, Colors::BG_WIDGET_INACTIVE);
+    let origin = canvas.min.to_vec2() + *pan;
+
+    let mut rects = Vec::new();
+    for state in &chart.states {
+        layout(state, origin, &mut rects);
+    }
+    let rect_of = |name: &str| rects.iter().find(|(n, _)| n == name).map(|(_, rect)| *rect);
+    let initials: Vec<&str> = std::iter::once(chart.initial_state())
+        .chain(chart.iter().map(State::initial_state))
+        .flatten()
+        .map(|state| state.name.as_str())
+        .collect();
+
+    // States, parents first so children are drawn over them
+    let mut clicked = None;
+    let mut dragged = None;
+    let mut context_action = None;
+    for state in chart.iter() {
+        let Some(rect) = rect_of(&state.name) else {
+            continue;
+        };
+        let active = playing && machine.is_in(&state.name);
+        let selected = selection.as_ref() == Some(&Selection::State(state.name.clone()));
+        let fill = if active { Colors::SUCCESS.gamma_multiply(0.35) } else { Colors::BG_WIDGET_DEFAULT };
+        let stroke = match (selected, active) {
+            (true, _) => Stroke::new(2.0, Colors::ACCENT),
+            (false, true) => Stroke::new(2.0, Colors::SUCCESS),
+            _ => Stroke::new(1.0, Colors::STROKE_DARK),
+        };
+        painter.rect(rect, 4.0, fill, stroke);
+        let title = if initials.contains(&state.name.as_str()) {
+            format!("▶ {}", state.name)
+        } else {
+            state.name.clone()
+        };
+        let (anchor, position) = if state.states.is_empty() {
+            (egui::Align2::CENTER_CENTER, rect.center())
+        } else {
+            (egui::Align2::LEFT_CENTER, pos2(rect.left() + 6.0, rect.top() + HEADER_HEIGHT / 2.0))
+        };
+        painter.text(position, anchor, title, egui::FontId::proportional(12.0), Colors::TEXT_PRIMARY);
+
+        // Parents are only grabbed by their title bar
+        let grab = if state.states.is_empty() {
+            rect
+        } else {
+            Rect::from_min_size(rect.min, vec2(rect.width(), HEADER_HEIGHT))
+        };
+        let response = ui.interact(grab, ui.id().with(("state_node", &state.name)), Sense::click_and_drag());
+        if response.clicked() || response.drag_started() {
+            clicked = Some(Selection::State(state.name.clone()));
+        }
+        if response.dragged() && !playing {
+            dragged = Some((state.name.clone(), response.drag_delta()));
+        }
+        if !playing {
+            response.context_menu(|ui| {
+                if ui.button("Add Child State").clicked() {
+                    context_action = Some((state.name.clone(), true));
+                    ui.close_menu();
+                }
+                if ui.button("Delete State").clicked() {
+                    context_action = Some((state.name.clone(), false));
+                    ui.close_menu();
+                }
+            });
+        }
+    }
+
+    // Transitions, offset sideways so arrows both ways don't overlap
+    let pointer = ui.input(|i| i.pointer.interact_pos());
+    for state in chart.iter() {
+        let Some(from) = rect_of(&state.name) else {
+            continue;
+        };
+        for (index, transition) in state.transitions.iter().enumerate() {
+            let Some(to) = rect_of(&transition.to) else {
+                continue;
+            };
+            let selected = selection.as_ref() == Some(&Selection::Transition(state.name.clone(), index));
+            let color = if selected { Colors::ACCENT } else { Colors::TEXT_SECONDARY };
+            let (start, end) = if from == to {
+                // Loop over the top edge
+                (from.center_top() + vec2(-20.0, 0.0), from.center_top() + vec2(20.0, 0.0))
+            } else {
+                let direction = (to.center() - from.center()).normalized();
+                let offset = vec2(-direction.y, direction.x) * 5.0;
+                (
+                    edge_point(from, to.center()) + offset,
+                    edge_point(to, from.center()) + offset,
+                )
+            };
+            if from == to {
+                let top = from.top() - 18.0;
+                let points = [start, pos2(start.x, top), pos2(end.x, top), end];
+                painter.add(egui::Shape::line(points.to_vec(), Stroke::new(1.5, color)));
+                arrow_head(&painter, pos2(end.x, top), end, color);
+            } else {
+                painter.line_segment([start, end], Stroke::new(1.5, color));
+                arrow_head(&painter, start, end, color);
+            }
+            let label = transition_text(transition);
+            let middle = if from == to { pos2(from.center().x, from.top() - 18.0) } else { start + (end - start) / 2.0 };
+            if !label.is_empty() {
+                painter.text(middle, egui::Align2::CENTER_BOTTOM, label, egui::FontId::proportional(10.0), color);
+            }
+            let hit = pointer.is_some_and(|pointer| segment_distance(pointer, start, end) < ARROW_HIT_DISTANCE);
+            if hit && background.clicked() {
+                clicked = Some(Selection::Transition(state.name.clone(), index));
+            }
+        }
+    }
+
+    let mut changed = false;
+    if let Some(selected) = clicked {
+        *selection = Some(selected);
+    } else if background.clicked() {
+        *selection = None;
+    }
+    if background.dragged() {
+        *pan += background.drag_delta();
+    }
+    if background.secondary_clicked() && !playing {
+        if let Some(pointer) = pointer {
+            let name = unique_name(chart, "State");
+            let position = pointer - origin - NODE_SIZE / 2.0;
+            chart.states.push(State { position: [position.x, position.y], ..State::new(name.clone()) });
+            *selection = Some(Selection::State(name));
+            changed = true;
+        }
+    }
+    if let Some((name, delta)) = dragged {
+        if let Some(state) = chart.find_mut(&name) {
+            move_state(state, delta);
+            changed = true;
+        }
+    }
+    if let Some((name, add_child)) = context_action {
+        if add_child {
+            let child = unique_name(chart, "State");
+            let parent = chart.find_mut(&name).expect("state was drawn");
+            let position = match parent.states.last() {
+                Some(last) => [last.position[0], last.position[1] + NODE_SIZE.y + PADDING],
+                None => parent.position,
+            };
+            parent.states.push(State { position, ..State::new(child.clone()) });
+            *selection = Some(Selection::State(child));
+        } else {
+            delete_state(chart, &name);
+            *selection = None;
+        }
+        changed = true;
+    }
+    changed
+}
+
+/// Screen rectangles of a state and its descendants; parents enclose their children
+fn layout(state: &State, origin: Vec2, rects: &mut Vec<(String, Rect)>) -> Rect {
+    let rect = if state.states.is_empty() {
+        Rect::from_min_size(pos2(state.position[0], state.position[1]) + origin, NODE_SIZE)
+    } else {
+        let index = rects.len();
+        rects.push((state.name.clone(), Rect::NOTHING));
+        let children = state
+            .states
+            .iter()
+            .map(|child| layout(child, origin, rects))
+            .fold(Rect::NOTHING, |bounds, rect| bounds.union(rect));
+        let mut rect = children.expand(PADDING);
+        rect.min.y -= HEADER_HEIGHT;
+        rects[index].1 = rect;
+        return rect;
+    };
+    rects.push((state.name.clone(), rect));
+    rect
+}
+
+fn move_state(state: &mut State, delta: Vec2) {
+    state.position[0] += delta.x;
+    state.position[1] += delta.y;
+    for child in &mut state.states {
+        move_state(child, delta);
+    }
+}
+
+/// `base`, or `base` followed by the first number that makes it unused
+fn unique_name(chart: &StateChart, base: &str) -> String {
+    std::iter::once(base.to_string())
+        .chain((2..).map(|n| format!("{} {}", base, n)))
+        .find(|name| chart.find(name).is_none())
+        .expect("unbounded")
+}
+
+fn for_each_state_mut(states: &mut [State], f: &mut impl FnMut(&mut State)) {
+    for state in states {
+        f(state);
+        for_each_state_mut(&mut state.states, f);
+    }
+}
+
+/// Rename a state along with the transitions and initial states naming it
+fn rename_state(chart: &mut StateChart, old: &str, new: &str) {
+    if chart.initial.as_deref() == Some(old) {
+        chart.initial = Some(new.to_string());
+    }
+    for_each_state_mut(&mut chart.states, &mut |state| {
+        if state.name == old {
+            state.name = new.to_string();
+        }
+        if state.initial.as_deref() == Some(old) {
+            state.initial = Some(new.to_string());
+        }
+        for transition in &mut state.transitions {
+            if transition.to == old {
+                transition.to = new.to_string();
+            }
+        }
+    });
+}
+
+/// Remove a state with its children, and the transitions into them
+fn delete_state(chart: &mut StateChart, name: &str) {
+    let Some(removed) = chart.find(name) else {
+        return;
+    };
+    let mut gone = vec![removed.name.clone()];
+    let mut stack = vec![removed];
+    while let Some(state) = stack.pop() {
+        gone.extend(state.states.iter().map(|child| child.name.clone()));
+        stack.extend(state.states.iter());
+    }
+
+    match chart.parent_of(name).map(str::to_string) {
+        Some(parent) => chart.find_mut(&parent).expect("parent exists").states.retain(|s| s.name != name),
+        None => chart.states.retain(|s| s.name != name),
+    }
+    if chart.initial.as_deref() == Some(name) {
+        chart.initial = None;
+    }
+    for_each_state_mut(&mut chart.states, &mut |state| {
+        if state.initial.as_deref() == Some(name) {
+            state.initial = None;
+        }
+        state.transitions.retain(|transition| !gone.contains(&transition.to));
+    });
+}
+
+/// Where the line from a rectangle's center towards `target` leaves it
+fn edge_point(rect: Rect, target: Pos2) -> Pos2 {
+    let direction = target - rect.center();
+    if direction == Vec2::ZERO {
+        return rect.center();
+    }
+    let scale_x = if direction.x != 0.0 { rect.width() / 2.0 / direction.x.abs() } else { f32::INFINITY };
+    let scale_y = if direction.y != 0.0 { rect.height() / 2.0 / direction.y.abs() } else { f32::INFINITY };
+    rect.center() + direction * scale_x.min(scale_y).min(1.0)
+}
+
+fn segment_distance(point: Pos2, start: Pos2, end: Pos2) -> f32 {
+    let segment = end - start;
+    let length_sq = segment.length_sq();
+    if length_sq == 0.0 {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_sq).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
+
+fn arrow_head(painter: &egui::Painter, from: Pos2, to: Pos2, color: Color32) {
+    let direction = (to - from).normalized();
+    let side = vec2(-direction.y, direction.x);
+    let points = vec![to, to - direction * 8.0 + side * 4.0, to - direction * 8.0 - side * 4.0];
+    painter.add(egui::Shape::convex_polygon(points, color, Stroke::NONE));
+}
@@ -0,0 +1,104 @@
+use egui::Ui;
+use longhorn_assets::{AssetManager, FilesystemSource, TextureFilter, TextureImportSettings, TextureWrap};
+use longhorn_core::AssetId;
+
+/// Max size choices offered in the editor
+const MAX_SIZES: [u32; 9] = [32, 64, 128, 256, 512, 1024, 2048, 4096, 8192];
+
+/// Import settings editor for the texture selected in the project panel
+pub struct TextureImportPanel {
+    /// Path and settings of the texture being edited
+    current: Option<(String, TextureImportSettings)>,
+}
+
+impl TextureImportPanel {
+    pub fn new() -> Self {
+        Self { current: None }
+    }
+
+    /// Show the import settings for the texture at `path` (relative to the project)
+    ///
+    /// Changes are saved and the texture re-imported immediately.
+    /// Returns the texture's asset ID when it must be re-uploaded to the GPU.
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        assets: &mut AssetManager<FilesystemSource>,
+        path: &str,
+    ) -> Option<AssetId> {
+        if self.current.as_ref().map(|(p, _)| p.as_str()) != Some(path) {
+            let settings = assets.load_texture_settings(path).unwrap_or_else(|e| {
+                log::error!("Failed to read import settings for {}: {}", path, e);
+                TextureImportSettings::default()
+            });
+            self.current = Some((path.to_string(), settings));
+        }
+        let (_, settings) = self.current.as_mut()?;
+        let original = *settings;
+
+        ui.heading("Texture Import");
+        ui.label(path);
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            egui::ComboBox::from_id_salt("texture_filter")
+                .selected_text(match settings.filter {
+                    TextureFilter::Nearest => "Nearest",
+                    TextureFilter::Linear => "Linear",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.filter, TextureFilter::Nearest, "Nearest");
+                    ui.selectable_value(&mut settings.filter, TextureFilter::Linear, "Linear");
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Wrap:");
+            egui::ComboBox::from_id_salt("texture_wrap")
+                .selected_text(match settings.wrap {
+                    TextureWrap::Clamp => "Clamp",
+                    TextureWrap::Repeat => "Repeat",
+                    TextureWrap::MirrorRepeat => "Mirror Repeat",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.wrap, TextureWrap::Clamp, "Clamp");
+                    ui.selectable_value(&mut settings.wrap, TextureWrap::Repeat, "Repeat");
+                    ui.selectable_value(&mut settings.wrap, TextureWrap::MirrorRepeat, "Mirror Repeat");
+                });
+        });
+
+        ui.checkbox(&mut settings.mipmaps, "Generate mipmaps");
+        ui.checkbox(&mut settings.srgb, "sRGB color");
+        ui.checkbox(&mut settings.premultiply_alpha, "Premultiply alpha");
+
+        ui.horizontal(|ui| {
+            ui.label("Max size:");
+            egui::ComboBox::from_id_salt("texture_max_size")
+                .selected_text(settings.max_size.map_or("None".to_string(), |s| s.to_string()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut settings.max_size, None, "None");
+                    for size in MAX_SIZES {
+                        ui.selectable_value(&mut settings.max_size, Some(size), size.to_string());
+                    }
+                });
+        });
+
+        if *settings == original {
+            return None;
+        }
+
+        if let Err(e) = assets.set_texture_settings(path, *settings) {
+            log::error!("Failed to save import settings for {}: {}", path, e);
+            return None;
+        }
+        log::info!("Updated import settings for {}", path);
+        assets.get_asset_id(path)
+    }
+}
+
+impl Default for TextureImportPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
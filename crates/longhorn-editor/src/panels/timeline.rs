@@ -0,0 +1,465 @@
+use egui::{pos2, vec2, Color32, Rect, RichText, ScrollArea, Sense, Stroke, Ui};
+use longhorn_assets::{AssetManager, FilesystemSource};
+use longhorn_core::{
+    entity_path, AnimationClip, AnimationField, AnimationPlayer, AssetId, EntityHandle, Interpolation, Parent,
+    Transform, TweenProperty, World,
+};
+use crate::EditorState;
+use crate::styling::Colors;
+
+/// Width of the track name column
+const LABEL_WIDTH: f32 = 160.0;
+/// Height of the time ruler
+const RULER_HEIGHT: f32 = 20.0;
+/// Height of each track and of the event row
+const ROW_HEIGHT: f32 = 18.0;
+/// Size of a key diamond
+const KEY_SIZE: f32 = 9.0;
+
+/// Transform fields keyed by record mode and "Key Transform"
+const TRANSFORM_FIELDS: [TweenProperty; 5] = [
+    TweenProperty::PositionX,
+    TweenProperty::PositionY,
+    TweenProperty::Rotation,
+    TweenProperty::ScaleX,
+    TweenProperty::ScaleY,
+];
+
+/// Timeline and dope sheet for the animation clip of the selected entity
+///
+/// Edits a copy of the clip played by the nearest `AnimationPlayer` at or
+/// above the selection, previewing it on the scene; changes reach the asset
+/// (and running players) when saved.
+pub struct TimelinePanel {
+    /// Clip being edited and the ID it was loaded from
+    clip: Option<(AssetId, AnimationClip)>,
+    /// Entity playing the clip
+    root: Option<EntityHandle>,
+    /// Playhead position in seconds
+    time: f32,
+    /// Whether the preview is playing
+    previewing: bool,
+    /// Whether gizmo edits are recorded as keys
+    recording: bool,
+    /// Whether the clip has unsaved changes
+    dirty: bool,
+    /// Selected key (track index, key index)
+    selected_key: Option<(usize, usize)>,
+    /// Name typed for new events
+    event_name: String,
+}
+
+impl TimelinePanel {
+    pub fn new() -> Self {
+        Self {
+            clip: None,
+            root: None,
+            time: 0.0,
+            previewing: false,
+            recording: false,
+            dirty: false,
+            selected_key: None,
+            event_name: "event".to_string(),
+        }
+    }
+
+    /// Whether gizmo edits are recorded as keys
+    pub fn is_recording(&self) -> bool {
+        self.recording && self.clip.is_some()
+    }
+
+    /// Key the transform fields a gizmo edit changed, in record mode
+    ///
+    /// Keys go on the track of `entity`'s path under the entity playing the
+    /// clip, at the playhead.
+    pub fn record_transform(&mut self, world: &World, entity: EntityHandle, old: &Transform, new: &Transform) {
+        if !self.is_recording() {
+            return;
+        }
+        let fields = TRANSFORM_FIELDS.map(|property| {
+            (property, transform_field(old, property), transform_field(new, property))
+        });
+        let changed = fields.into_iter().filter(|(_, before, after)| before != after);
+        self.key_fields(world, entity, changed.map(|(property, _, after)| (property, after)));
+    }
+
+    /// Key fields of `entity` at the playhead
+    fn key_fields(&mut self, world: &World, entity: EntityHandle, fields: impl Iterator<Item = (TweenProperty, f32)>) {
+        let (Some(root), Some((_, clip))) = (self.root, &mut self.clip) else {
+            return;
+        };
+        let Some(path) = entity_path(world, root, entity) else {
+            log::warn!("Can't key an entity outside the animated hierarchy");
+            return;
+        };
+        for (property, value) in fields {
+            clip.set_key(&path, AnimationField::Property(property), self.time, value);
+            self.dirty = true;
+        }
+        self.selected_key = None;
+    }
+
+    /// Edit the clip a player plays (unsaved changes must be saved first)
+    fn open(&mut self, root: EntityHandle, clip_id: AssetId, assets: &mut AssetManager<FilesystemSource>) {
+        self.root = Some(root);
+        self.time = 0.0;
+        self.previewing = false;
+        self.dirty = false;
+        self.selected_key = None;
+        self.clip = match assets.load_animation_clip_by_id(clip_id) {
+            Ok(handle) => assets.get_animation_clip(handle).map(|clip| (clip_id, (*clip).clone())),
+            Err(e) => {
+                log::debug!("Animation clip {:?} not loaded: {}", clip_id, e);
+                None
+            }
+        };
+    }
+
+    /// Write the clip to its asset
+    ///
+    /// # Returns
+    /// `false` if the clip couldn't be saved
+    fn save(&mut self, assets: &mut AssetManager<FilesystemSource>) -> bool {
+        let Some((clip_id, clip)) = &self.clip else {
+            return true;
+        };
+        let Some(path) = assets.animation_clip_path(*clip_id).map(str::to_string) else {
+            log::error!("Animation clip {:?} has no path", clip_id);
+            return false;
+        };
+        match assets.save_animation_clip(&path, clip) {
+            Ok(_) => {
+                self.dirty = false;
+                log::info!("Saved animation clip: {}", path);
+                true
+            }
+            Err(e) => {
+                log::error!("Failed to save animation clip {}: {}", path, e);
+                false
+            }
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        world: &mut World,
+        assets: &mut AssetManager<FilesystemSource>,
+        state: &EditorState,
+    ) {
+        ui.heading("Timeline");
+        ui.separator();
+
+        if state.is_playing() {
+            ui.label("(Read-only during play)");
+            return;
+        }
+
+        let selected = state.selected_entity.map(EntityHandle::new);
+        let Some(root) = selected.and_then(|entity| animation_root(world, entity)) else {
+            ui.label("Select an entity with an Animation Player");
+            return;
+        };
+        let Ok(clip_id) = world.get::<AnimationPlayer>(root).map(|player| player.clip) else {
+            return;
+        };
+        if self.root != Some(root) || self.clip.as_ref().map(|(id, _)| *id) != Some(clip_id) {
+            // Recorded keys are saved before switching clips, never dropped
+            if self.dirty && !self.save(assets) {
+                ui.label(RichText::new("Unsaved changes to the previous clip couldn't be saved").color(Colors::ERROR));
+                ui.horizontal(|ui| {
+                    if ui.button("Retry Save").clicked() {
+                        self.save(assets);
+                    }
+                    if ui.button("Discard Changes").clicked() {
+                        self.dirty = false;
+                    }
+                });
+                return;
+            }
+            self.open(root, clip_id, assets);
+        }
+        if self.clip.is_none() {
+            ui.label(RichText::new(format!("Clip {} isn't loaded", clip_id.0)).color(Colors::TEXT_MUTED));
+            return;
+        }
+
+        let mut apply = false;
+        let mut key_selected = false;
+        let mut save = false;
+        let Some((_, clip)) = &mut self.clip else {
+            return;
+        };
+
+        // Transport, recording and clip settings
+        ui.horizontal(|ui| {
+            if ui.button(if self.previewing { "⏸" } else { "▶" }).clicked() {
+                self.previewing = !self.previewing;
+                if self.previewing && self.time >= clip.length() {
+                    self.time = 0.0;
+                }
+            }
+            let record_color = if self.recording { Colors::ERROR } else { Colors::TEXT_SECONDARY };
+            ui.toggle_value(&mut self.recording, RichText::new("⏺ Record").color(record_color));
+
+            ui.label("Time:");
+            apply |= ui
+                .add(egui::DragValue::new(&mut self.time).speed(0.01).range(0.0..=clip.length()).suffix("s"))
+                .changed();
+            ui.label("Length:");
+            self.dirty |= ui
+                .add(egui::DragValue::new(&mut clip.duration).speed(0.05).range(0.0..=f32::MAX).suffix("s"))
+                .changed();
+            self.dirty |= ui.checkbox(&mut clip.looping, "Looping").changed();
+
+            key_selected = ui.button("Key Transform").on_hover_text("Key the selected entity's transform").clicked();
+            let label = if self.dirty { "Save*" } else { "Save" };
+            save = ui.add_enabled(self.dirty, egui::Button::new(label)).clicked();
+        });
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.event_name);
+            if ui.button("Add Event").clicked() && !self.event_name.is_empty() {
+                clip.add_event(self.time, self.event_name.clone());
+                self.dirty = true;
+            }
+        });
+
+        // Selected key
+        let selected_key = self
+            .selected_key
+            .filter(|&(track, key)| clip.tracks.get(track).is_some_and(|track| key < track.keys.len()));
+        self.selected_key = selected_key;
+        if let Some((track_index, key_index)) = selected_key {
+            let track = &mut clip.tracks[track_index];
+            let key = &mut track.keys[key_index];
+            let mut edited = false;
+            let mut delete = false;
+            let mut moved = None;
+            ui.horizontal(|ui| {
+                ui.label("Key:");
+                let mut time = key.time;
+                if ui.add(egui::DragValue::new(&mut time).speed(0.01).range(0.0..=f32::MAX).prefix("at: ")).changed() {
+                    moved = Some(time);
+                }
+                edited |= ui.add(egui::DragValue::new(&mut key.value).speed(0.1).prefix("value: ")).changed();
+                let before = key.interpolation;
+                ui.selectable_value(&mut key.interpolation, Interpolation::Linear, "Linear");
+                ui.selectable_value(&mut key.interpolation, Interpolation::Step, "Step");
+                ui.selectable_value(&mut key.interpolation, Interpolation::EASE, "Ease");
+                edited |= key.interpolation != before;
+                delete = ui.button("Delete").clicked()
+                    || (ui.ui_contains_pointer() && ui.input(|i| i.key_pressed(egui::Key::Delete)));
+            });
+            if delete {
+                track.keys.remove(key_index);
+                if track.keys.is_empty() {
+                    clip.tracks.remove(track_index);
+                }
+                self.selected_key = None;
+                edited = true;
+            } else if let Some(time) = moved {
+                self.selected_key = Some((track_index, track.move_key(key_index, time)));
+                edited = true;
+            }
+            self.dirty |= edited;
+            apply |= edited;
+        }
+
+        ui.separator();
+
+        // Dope sheet: ruler, one row per track, events and the playhead
+        let clip_length = clip.length();
+        let length = clip_length.max(1.0);
+        let mut drag_key = None;
+        let mut remove_event = None;
+        ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            let width = ui.available_width();
+            let track_width = (width - LABEL_WIDTH).max(50.0);
+            let top = ui.cursor().top();
+            let (ruler, response) = ui.allocate_exact_size(vec2(width, RULER_HEIGHT), Sense::click_and_drag());
+            let left = ruler.left() + LABEL_WIDTH;
+            let to_x = |time: f32| left + time / length * track_width;
+            let to_time = |x: f32| ((x - left) / track_width * length).clamp(0.0, clip_length);
+
+            let painter = ui.painter();
+            painter.rect_filled(ruler, 0.0, Colors::BG_WIDGET_DEFAULT);
+            let step = [0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0]
+                .into_iter()
+                .find(|step| step / length * track_width >= 48.0)
+                .unwrap_or(60.0);
+            for tick in 0..=(length / step) as u32 {
+                let time = tick as f32 * step;
+                let x = to_x(time);
+                painter.line_segment([pos2(x, ruler.bottom() - 6.0), pos2(x, ruler.bottom())], Stroke::new(1.0, Colors::TEXT_MUTED));
+                painter.text(
+                    pos2(x + 2.0, ruler.top()),
+                    egui::Align2::LEFT_TOP,
+                    format!("{:.2}", time),
+                    egui::FontId::proportional(10.0),
+                    Colors::TEXT_SECONDARY,
+                );
+            }
+            if response.clicked() || response.dragged() {
+                if let Some(pointer) = response.interact_pointer_pos().filter(|pos| pos.x >= left) {
+                    self.time = to_time(pointer.x);
+                    self.previewing = false;
+                    apply = true;
+                }
+            }
+
+            for (track_index, track) in clip.tracks.iter().enumerate() {
+                let (row, _) = ui.allocate_exact_size(vec2(width, ROW_HEIGHT), Sense::hover());
+                let name = if track.path.is_empty() {
+                    track.field.to_string()
+                } else {
+                    format!("{}: {}", track.path, track.field)
+                };
+                let painter = ui.painter();
+                if track_index % 2 == 0 {
+                    painter.rect_filled(row, 0.0, Colors::BG_WIDGET_INACTIVE);
+                }
+                painter.text(
+                    pos2(row.left() + 4.0, row.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    name,
+                    egui::FontId::proportional(12.0),
+                    Colors::TEXT_PRIMARY,
+                );
+
+                for (key_index, key) in track.keys.iter().enumerate() {
+                    let center = pos2(to_x(key.time), row.center().y);
+                    let id = ui.id().with(("timeline_key", track_index, key_index));
+                    let response = ui.interact(Rect::from_center_size(center, vec2(KEY_SIZE, KEY_SIZE) * 1.5), id, Sense::click_and_drag());
+                    if response.clicked() || response.drag_started() {
+                        self.selected_key = Some((track_index, key_index));
+                        self.time = key.time;
+                        apply = true;
+                    }
+                    if response.dragged() {
+                        if let Some(pointer) = response.interact_pointer_pos() {
+                            drag_key = Some((track_index, key_index, to_time(pointer.x)));
+                        }
+                    }
+                    let selected = self.selected_key == Some((track_index, key_index));
+                    let color = match (selected, key.interpolation) {
+                        (true, _) => Colors::ACCENT,
+                        (false, Interpolation::Step) => Colors::WARNING,
+                        _ => Colors::TEXT_PRIMARY,
+                    };
+                    diamond(ui, center, color);
+                }
+            }
+
+            // Events
+            let (row, _) = ui.allocate_exact_size(vec2(width, ROW_HEIGHT), Sense::hover());
+            ui.painter().text(
+                pos2(row.left() + 4.0, row.center().y),
+                egui::Align2::LEFT_CENTER,
+                "Events",
+                egui::FontId::proportional(12.0),
+                Colors::TEXT_SECONDARY,
+            );
+            for (index, event) in clip.events.iter().enumerate() {
+                let x = to_x(event.time);
+                let marker = Rect::from_min_size(pos2(x - 2.0, row.top() + 2.0), vec2(4.0, ROW_HEIGHT - 4.0));
+                let response = ui
+                    .interact(marker.expand(3.0), ui.id().with(("timeline_event", index)), Sense::click())
+                    .on_hover_text(format!("{} at {:.2}s", event.name, event.time));
+                ui.painter().rect_filled(marker, 1.0, Colors::SUCCESS);
+                response.context_menu(|ui| {
+                    if ui.button("Delete Event").clicked() {
+                        remove_event = Some(index);
+                        ui.close_menu();
+                    }
+                });
+            }
+
+            // Playhead
+            let x = to_x(self.time);
+            let bottom = ui.cursor().top();
+            ui.painter().line_segment([pos2(x, top), pos2(x, bottom)], Stroke::new(1.5, Colors::ERROR));
+        });
+
+        if let Some((track_index, key_index, time)) = drag_key {
+            self.selected_key = Some((track_index, clip.tracks[track_index].move_key(key_index, time)));
+            self.time = time;
+            self.dirty = true;
+            apply = true;
+        }
+        if let Some(index) = remove_event {
+            clip.events.remove(index);
+            self.dirty = true;
+        }
+
+        // Preview playback
+        if self.previewing {
+            self.time += ui.input(|i| i.stable_dt);
+            if self.time >= clip_length {
+                if clip.looping && clip_length > 0.0 {
+                    self.time %= clip_length;
+                } else {
+                    self.time = clip_length;
+                    self.previewing = false;
+                }
+            }
+            apply = true;
+            ui.ctx().request_repaint();
+        }
+
+        if apply {
+            clip.apply(world, root, self.time);
+        }
+        if key_selected {
+            if let Some(entity) = selected {
+                let transform = world.get::<Transform>(entity).ok().map(|t| *t);
+                if let Some(transform) = transform {
+                    let fields = TRANSFORM_FIELDS.map(|property| (property, transform_field(&transform, property)));
+                    self.key_fields(world, entity, fields.into_iter());
+                }
+            }
+        }
+        if save {
+            self.save(assets);
+        }
+    }
+}
+
+impl Default for TimelinePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The entity itself or its nearest ancestor with an `AnimationPlayer`
+fn animation_root(world: &World, mut entity: EntityHandle) -> Option<EntityHandle> {
+    loop {
+        if world.get::<AnimationPlayer>(entity).is_ok() {
+            return Some(entity);
+        }
+        entity = EntityHandle::new(world.get::<Parent>(entity).ok()?.0);
+    }
+}
+
+fn transform_field(transform: &Transform, property: TweenProperty) -> f32 {
+    match property {
+        TweenProperty::PositionX => transform.position.x,
+        TweenProperty::PositionY => transform.position.y,
+        TweenProperty::Rotation => transform.rotation,
+        TweenProperty::ScaleX => transform.scale.x,
+        TweenProperty::ScaleY => transform.scale.y,
+        _ => 0.0,
+    }
+}
+
+fn diamond(ui: &Ui, center: egui::Pos2, color: Color32) {
+    let r = KEY_SIZE / 2.0;
+    let points = vec![
+        pos2(center.x, center.y - r),
+        pos2(center.x + r, center.y),
+        pos2(center.x, center.y + r),
+        pos2(center.x - r, center.y),
+    ];
+    ui.painter()
+        .add(egui::Shape::convex_polygon(points, color, Stroke::new(1.0, Colors::STROKE_DARK)));
+}
@@ -6,7 +6,10 @@ use crate::gizmo::{hit_test_gizmo, update_transform_from_drag, draw_gizmo};
 use glam::Vec2;
 use longhorn_core::{Transform, GlobalTransform, Sprite, World};
 
-pub struct ViewportPanel {}
+pub struct ViewportPanel {
+    /// Draw navigation surfaces and agent paths over the scene
+    pub show_navigation: bool,
+}
 
 /// Actions that can be triggered from the viewport
 #[derive(Debug, Clone, Default)]
@@ -18,7 +21,7 @@ pub struct ViewportAction {
 
 impl ViewportPanel {
     pub fn new() -> Self {
-        Self {}
+        Self { show_navigation: false }
     }
 
     pub fn show(
@@ -34,7 +37,10 @@ impl ViewportPanel {
         camera_zoom: f32,
         world: &World,
     ) -> (CameraInput, ViewportAction) {
-        ui.heading("Scene View (Editor Camera)");
+        ui.horizontal(|ui| {
+            ui.heading("Scene View (Editor Camera)");
+            ui.checkbox(&mut self.show_navigation, "Navigation");
+        });
         ui.separator();
 
         let available = ui.available_size();
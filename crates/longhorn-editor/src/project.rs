@@ -37,6 +37,7 @@ impl Project {
             viewport: longhorn_engine::ViewportConfig {
                 width: 1280,
                 height: 720,
+                scale_mode: longhorn_engine::ScaleMode::default(),
             },
             assets: longhorn_engine::AssetsConfig::default(),
         };
@@ -291,8 +291,8 @@ impl DirectoryNode {
                 .unwrap_or("")
                 .to_string();
 
-            // Skip hidden files
-            if entry_name.starts_with('.') {
+            // Skip hidden files and import settings (edited through the inspector)
+            if entry_name.starts_with('.') || entry_name.ends_with(longhorn_assets::IMPORT_SETTINGS_EXTENSION) {
                 continue;
             }
 
@@ -407,6 +407,7 @@ mod tests {
         fs::create_dir_all(&assets).unwrap();
         fs::write(assets.join("visible.ts"), "// visible").unwrap();
         fs::write(assets.join(".hidden"), "hidden").unwrap();
+        fs::write(assets.join("visible.ts.import.json"), "{}").unwrap();
         fs::create_dir_all(assets.join(".hidden_dir")).unwrap();
 
         let node = DirectoryNode::scan(&assets).unwrap();
@@ -1,9 +1,10 @@
-use longhorn_core::{World, Name, Transform, Sprite, Enabled, EntityHandle, Script};
+use longhorn_core::{World, Name, Transform, Sprite, Enabled, EntityGuid, EntityHandle, Script};
 use serde::{Deserialize, Serialize};
 
 /// Snapshot of an entity's components
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntitySnapshot {
+    pub guid: Option<EntityGuid>,
     pub name: Option<Name>,
     pub transform: Option<Transform>,
     pub sprite: Option<Sprite>,
@@ -30,6 +31,7 @@ impl SceneSnapshot {
         for entity in entity_ids {
             let handle = EntityHandle::new(entity);
             let snapshot = EntitySnapshot {
+                guid: world.guid(handle),
                 name: world.get::<Name>(handle).ok().map(|r| (*r).clone()),
                 transform: world.get::<Transform>(handle).ok().map(|r| (*r).clone()),
                 sprite: world.get::<Sprite>(handle).ok().map(|r| (*r).clone()),
@@ -51,6 +53,9 @@ impl SceneSnapshot {
         for entity_data in self.entities {
             let mut builder = world.spawn();
 
+            if let Some(guid) = entity_data.guid {
+                builder = builder.with(guid);
+            }
             if let Some(name) = entity_data.name {
                 builder = builder.with(name);
             }
@@ -93,6 +98,8 @@ mod tests {
         // Capture snapshot
         let snapshot = SceneSnapshot::capture(&world);
         assert_eq!(snapshot.entities.len(), 1);
+        let snapshot_guid = snapshot.entities[0].guid;
+        assert!(snapshot_guid.is_some());
 
         // Modify world
         world.spawn()
@@ -104,9 +111,10 @@ mod tests {
         snapshot.restore(&mut world);
         assert_eq!(world.len(), 1);
 
-        // Verify restored entity
+        // Verify restored entity keeps its GUID
         let entity = world.find("TestEntity");
         assert!(entity.is_some());
+        assert_eq!(world.guid(entity.unwrap()), snapshot_guid);
     }
 
     #[test]
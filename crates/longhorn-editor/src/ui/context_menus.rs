@@ -1,5 +1,7 @@
 use std::path::Path;
 use egui::Ui;
+use glam::Vec2;
+use longhorn_core::Shape;
 use crate::ContextAction;
 
 /// Actions that can be triggered from the scene tree context menu
@@ -7,6 +9,26 @@ use crate::ContextAction;
 pub enum SceneTreeAction {
     /// Create a new entity (as child of selected, or root if nothing selected)
     CreateEntity,
+    /// Create a new entity drawing a shape, placed like `CreateEntity`
+    CreateShape(Shape),
+}
+
+/// Shapes offered by the Create menu, with their names
+pub fn shape_presets() -> [(&'static str, Shape); 5] {
+    [
+        ("Rectangle", Shape::rectangle(Vec2::new(100.0, 100.0))),
+        ("Rounded Rectangle", Shape::rounded_rectangle(Vec2::new(100.0, 100.0), 16.0)),
+        ("Circle", Shape::circle(50.0)),
+        (
+            "Polygon",
+            Shape::polygon(vec![Vec2::new(-50.0, -43.3), Vec2::new(50.0, -43.3), Vec2::new(0.0, 43.3)]),
+        ),
+        (
+            "Polyline",
+            Shape::polyline(vec![Vec2::new(-50.0, 0.0), Vec2::new(0.0, 30.0), Vec2::new(50.0, 0.0)])
+                .with_stroke([1.0, 1.0, 1.0, 1.0], 4.0),
+        ),
+    ]
 }
 
 /// Renders a "Create" submenu for creating scenes, scripts, and folders.
@@ -108,6 +130,14 @@ pub fn show_scene_tree_create_menu(ui: &mut Ui) -> Option<SceneTreeAction> {
             action = Some(SceneTreeAction::CreateEntity);
             ui.close_menu();
         }
+        ui.menu_button("Shape", |ui| {
+            for (name, shape) in shape_presets() {
+                if ui.button(name).clicked() {
+                    action = Some(SceneTreeAction::CreateShape(shape));
+                    ui.close_menu();
+                }
+            }
+        });
     });
 
     action
@@ -1,26 +1,16 @@
 use wgpu;
-use wgpu::util::DeviceExt;
 use glam::Vec2;
 use std::collections::HashMap;
-use longhorn_assets::{AssetManager, AssetSource, AssetHandle, TextureData};
+use longhorn_assets::{AssetManager, AssetSource, TextureData, TextureImportSettings};
 use longhorn_core::{AssetId, GlobalTransform, Sprite, Transform, World};
 use longhorn_renderer::{
-    pipeline::{create_sprite_pipeline, CameraUniform},
-    Camera, Color, SpriteBatch, SpriteInstance, SpriteVertex,
+    pipeline::MaterialShaderError, Camera, Color, DebugDraw, RenderStats, RenderView, ScreenRect, SpriteBatch, SpriteInstance,
+    SpritePass, TextureLookup,
 };
 
 /// Embedded test sprite (32x32 white square)
 const TEST_SPRITE_BYTES: &[u8] = include_bytes!("../assets/test_sprite.png");
 
-/// GPU texture resource with bind group
-struct GpuTextureResource {
-    bind_group: wgpu::BindGroup,
-    #[allow(dead_code)]
-    width: u32,
-    #[allow(dead_code)]
-    height: u32,
-}
-
 /// Identifies which render target to use
 enum RenderTarget {
     Editor,
@@ -40,21 +30,11 @@ pub struct EditorViewportRenderer {
 
     size: (u32, u32),
 
-    // Sprite pipeline
-    pipeline: wgpu::RenderPipeline,
-    camera_buffer: wgpu::Buffer,
-    camera_bind_group: wgpu::BindGroup,
-    camera_uniform: CameraUniform,
-    vertex_buffer: wgpu::Buffer,
-
-    // Texture bind group layout (needed for creating new textures)
-    texture_bind_group_layout: wgpu::BindGroupLayout,
+    // Instanced sprite drawing, texture atlas and fallback texture
+    sprite_pass: SpritePass,
 
-    // Texture cache - maps AssetId to GPU texture resources
-    texture_cache: HashMap<AssetId, GpuTextureResource>,
-
-    // Fallback texture for sprites without a loaded texture
-    fallback_texture_bind_group: wgpu::BindGroup,
+    // Stats from the last rendered view
+    render_stats: RenderStats,
 
     // Camera
     camera: Camera,
@@ -80,85 +60,11 @@ impl EditorViewportRenderer {
         // Create render texture
         let (render_texture, render_view) = Self::create_render_texture(device, width, height);
 
-        // Create camera bind group layout
-        let camera_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-                label: Some("Editor Camera Bind Group Layout"),
-            });
-
-        // Create texture bind group layout
-        let texture_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Texture {
-                            multisampled: false,
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                        count: None,
-                    },
-                ],
-                label: Some("Editor Texture Bind Group Layout"),
-            });
-
-        // Create pipeline
-        let pipeline = create_sprite_pipeline(
-            device,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            &camera_bind_group_layout,
-            &texture_bind_group_layout,
-        );
+        // Sprite pass with the embedded test sprite as fallback texture
+        let mut sprite_pass = SpritePass::new(device, wgpu::TextureFormat::Rgba8UnormSrgb);
+        sprite_pass.set_fallback_texture(device, queue, &Self::test_texture_data());
 
-        // Create camera uniform and buffer
         let camera = Camera::new(width as f32, height as f32);
-        let mut camera_uniform = CameraUniform::new();
-        camera_uniform.update(camera.view_projection());
-
-        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Editor Camera Buffer"),
-            contents: bytemuck::cast_slice(&[camera_uniform]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
-
-        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
-            label: Some("Editor Camera Bind Group"),
-        });
-
-        // Create vertex buffer
-        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Editor Vertex Buffer"),
-            size: (1000 * 6 * std::mem::size_of::<SpriteVertex>()) as u64, // 1000 sprites
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        // Load and create fallback texture
-        let fallback_texture_bind_group =
-            Self::create_test_texture(device, queue, &texture_bind_group_layout);
 
         Self {
             editor_render_texture: render_texture,
@@ -166,14 +72,8 @@ impl EditorViewportRenderer {
             game_render_texture: None,
             game_render_view: None,
             size: (width, height),
-            pipeline,
-            camera_buffer,
-            camera_bind_group,
-            camera_uniform,
-            vertex_buffer,
-            texture_bind_group_layout,
-            texture_cache: HashMap::new(),
-            fallback_texture_bind_group,
+            sprite_pass,
+            render_stats: RenderStats::default(),
             camera,
             clear_color: Color::from_rgba8(40, 44, 52, 255), // Dark background
             egui_texture_id: None,
@@ -188,90 +88,34 @@ impl EditorViewportRenderer {
         queue: &wgpu::Queue,
         asset_id: AssetId,
         texture_data: &TextureData,
+        settings: &TextureImportSettings,
     ) {
-        if self.texture_cache.contains_key(&asset_id) {
-            return; // Already cached
+        if self.sprite_pass.upload_texture(device, queue, asset_id, texture_data, settings) {
+            log::debug!("Uploaded texture {:?} to GPU", asset_id);
         }
+    }
 
-        let size = wgpu::Extent3d {
-            width: texture_data.width,
-            height: texture_data.height,
-            depth_or_array_layers: 1,
-        };
-
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some(&format!("Editor Sprite Texture {:?}", asset_id)),
-            size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-
-        queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &texture_data.pixels,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * texture_data.width),
-                rows_per_image: Some(texture_data.height),
-            },
-            size,
-        );
-
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Nearest,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
-
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some(&format!("Editor Sprite Bind Group {:?}", asset_id)),
-            layout: &self.texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-            ],
-        });
-
-        self.texture_cache.insert(asset_id, GpuTextureResource {
-            bind_group,
-            width: texture_data.width,
-            height: texture_data.height,
-        });
+    /// Drop a texture from the GPU cache so it is re-uploaded with its
+    /// current data and import settings on next use
+    pub fn invalidate_texture(&mut self, asset_id: AssetId) {
+        if self.sprite_pass.invalidate_texture(asset_id) {
+            log::debug!("Invalidated texture {:?}", asset_id);
+        }
+    }
 
-        log::debug!("Uploaded texture {:?} to GPU cache", asset_id);
+    /// Take the material shaders that failed to compile since the last call
+    pub fn take_material_errors(&mut self) -> Vec<(AssetId, MaterialShaderError)> {
+        self.sprite_pass.take_material_errors()
     }
 
     /// Check if a texture is in the GPU cache
     pub fn has_texture(&self, asset_id: AssetId) -> bool {
-        self.texture_cache.contains_key(&asset_id)
+        self.sprite_pass.contains(asset_id)
     }
 
-    /// Get texture bind group, or fallback if not loaded
-    fn get_texture_bind_group(&self, asset_id: AssetId) -> &wgpu::BindGroup {
-        self.texture_cache
-            .get(&asset_id)
-            .map(|t| &t.bind_group)
-            .unwrap_or(&self.fallback_texture_bind_group)
+    /// Get draw call, instance and upload counts from the last rendered view
+    pub fn render_stats(&self) -> RenderStats {
+        self.render_stats
     }
 
     fn create_render_texture(
@@ -300,77 +144,17 @@ impl EditorViewportRenderer {
         (texture, view)
     }
 
-    fn create_test_texture(
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        bind_group_layout: &wgpu::BindGroupLayout,
-    ) -> wgpu::BindGroup {
+    fn test_texture_data() -> TextureData {
         // Decode embedded PNG
         let img = image::load_from_memory(TEST_SPRITE_BYTES)
             .expect("Failed to decode test sprite")
             .to_rgba8();
         let (width, height) = img.dimensions();
-        let pixels = img.into_raw();
-
-        // Create texture
-        let size = wgpu::Extent3d {
+        TextureData {
             width,
             height,
-            depth_or_array_layers: 1,
-        };
-
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Test Sprite Texture"),
-            size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-
-        queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &pixels,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * width),
-                rows_per_image: Some(height),
-            },
-            size,
-        );
-
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Nearest,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
-
-        device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Test Sprite Bind Group"),
-            layout: bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-            ],
-        })
+            pixels: img.into_raw(),
+        }
     }
 
     pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
@@ -510,110 +294,22 @@ impl EditorViewportRenderer {
     ) {
         // Temporary: use default camera for backwards compatibility
         let default_camera = crate::EditorCamera::default();
-        self.render_scene_view(device, queue, world, assets, &default_camera);
-    }
-
-    /// Render a batch of vertices with a specific texture
-    fn render_vertices_batch<'a>(
-        &'a self,
-        queue: &wgpu::Queue,
-        render_pass: &mut wgpu::RenderPass<'a>,
-        vertices: &[SpriteVertex],
-        texture_id: AssetId,
-    ) {
-        const MAX_VERTICES: usize = 1000 * 6;
-        let vertices = if vertices.len() > MAX_VERTICES {
-            log::warn!("Sprite count exceeds vertex buffer capacity ({} > {}). Truncating.",
-                       vertices.len(), MAX_VERTICES);
-            &vertices[..MAX_VERTICES]
-        } else {
-            vertices
-        };
-
-        // Upload vertices
-        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices));
-
-        // Get texture bind group (or fallback)
-        let bind_group = self.get_texture_bind_group(texture_id);
-        render_pass.set_bind_group(1, bind_group, &[]);
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.draw(0..vertices.len() as u32, 0..1);
+        self.render_scene_view(device, queue, world, assets, &default_camera, None);
     }
 
     /// Legacy render method for backwards compatibility (uses fallback texture)
     pub fn render(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, world: &World) {
-        // Update camera uniform
-        self.camera_uniform.update(self.camera.view_projection());
-        queue.write_buffer(
-            &self.camera_buffer,
-            0,
-            bytemuck::cast_slice(&[self.camera_uniform]),
-        );
-
         // Collect sprites from world
         let mut batch = SpriteBatch::new();
         for (_entity_id, (sprite, global_transform)) in world.query::<(&Sprite, &GlobalTransform)>().iter() {
-            batch.add(SpriteInstance {
-                position: global_transform.position,
-                size: sprite.size * global_transform.scale, // Apply transform scale to sprite size
-                color: Color::new(sprite.color[0], sprite.color[1], sprite.color[2], sprite.color[3]),
-                texture: sprite.texture,
-                z_index: 0,
-            });
+            batch.add(SpriteInstance::from_sprite(sprite, global_transform));
         }
 
         batch.sort();
 
-        // Generate vertices
-        let mut vertices = Vec::new();
-        for sprite in batch.iter() {
-            let sprite_verts = SpriteBatch::generate_vertices(sprite);
-            vertices.extend_from_slice(&sprite_verts);
-        }
-
-        // Create command encoder
-        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Editor Viewport Encoder"),
-        });
-
-        // Render pass
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Editor Viewport Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &self.editor_render_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(self.clear_color.to_wgpu()),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
-
-            if !vertices.is_empty() {
-                // Before writing to buffer, check capacity
-                const MAX_VERTICES: usize = 1000 * 6;
-                if vertices.len() > MAX_VERTICES {
-                    log::warn!("Sprite count exceeds vertex buffer capacity ({} > {}). Truncating.",
-                               vertices.len(), MAX_VERTICES);
-                    vertices.truncate(MAX_VERTICES);
-                }
-
-                // Upload vertices
-                queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
-
-                render_pass.set_pipeline(&self.pipeline);
-                render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
-                render_pass.set_bind_group(1, &self.fallback_texture_bind_group, &[]);
-                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-                render_pass.draw(0..vertices.len() as u32, 0..1);
-            }
-        }
-
-        queue.submit(std::iter::once(encoder.finish()));
+        // No texture lookup: sprites whose texture isn't uploaded yet use the fallback
+        let no_textures: HashMap<AssetId, TextureData> = HashMap::new();
+        self.draw_sprites(device, queue, &[], &batch, &no_textures, None, RenderTarget::Editor);
     }
 
     pub fn register_with_egui(
@@ -667,7 +363,7 @@ impl EditorViewportRenderer {
         })
     }
 
-    /// Render scene view using the editor camera
+    /// Render scene view using the editor camera, with `debug` shapes on top
     pub fn render_scene_view<S: AssetSource>(
         &mut self,
         device: &wgpu::Device,
@@ -675,22 +371,25 @@ impl EditorViewportRenderer {
         world: &World,
         assets: &AssetManager<S>,
         editor_camera: &crate::EditorCamera,
+        debug: Option<&DebugDraw>,
     ) {
         // Update camera from editor camera
         self.camera.position = editor_camera.transform.position;
         self.camera.zoom = editor_camera.zoom;
 
         // Render to editor texture
-        self.render_to_texture(device, queue, world, assets, RenderTarget::Editor);
+        self.render_to_texture(device, queue, world, assets, debug, RenderTarget::Editor);
     }
 
-    /// Render game view using the main camera from the scene
+    /// Render game view using the main camera from the scene, with `debug`
+    /// shapes on top
     pub fn render_game_view<S: AssetSource>(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         world: &World,
         assets: &AssetManager<S>,
+        debug: Option<&DebugDraw>,
         egui_renderer: Option<&mut egui_wgpu::Renderer>,
     ) {
         use longhorn_engine::MainCamera;
@@ -726,86 +425,103 @@ impl EditorViewportRenderer {
                 self.game_render_view = Some(game_view);
             }
 
-            // Update camera from main camera
+            // Update camera from main camera, including its effects
             let saved_position = self.camera.position;
             let saved_zoom = self.camera.zoom;
+            let saved_effects = std::mem::replace(&mut self.camera.post_process, camera.post_process.clone());
 
             self.camera.position = camera.position;
             self.camera.zoom = camera.zoom;
 
             // Render to game texture
-            self.render_to_texture(device, queue, world, assets, RenderTarget::Game);
+            self.render_to_texture(device, queue, world, assets, debug, RenderTarget::Game);
 
             // Restore camera
             self.camera.position = saved_position;
             self.camera.zoom = saved_zoom;
+            self.camera.post_process = saved_effects;
         }
     }
 
     /// Core rendering method that renders to a specific texture view
+    ///
+    /// Scene cameras with a render-texture target are rendered too, so
+    /// sprites showing those textures are up to date in both views.
     fn render_to_texture<S: AssetSource>(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         world: &World,
         assets: &AssetManager<S>,
+        debug: Option<&DebugDraw>,
         target: RenderTarget,
     ) {
-        // Update camera uniform
-        self.camera_uniform.update(self.camera.view_projection());
-        queue.write_buffer(
-            &self.camera_buffer,
-            0,
-            bytemuck::cast_slice(&[self.camera_uniform]),
-        );
-
-        // Collect sprites from world and upload textures as needed
-        let mut batch = SpriteBatch::new();
-        for (entity_id, (sprite, global_transform)) in world.query::<(&Sprite, &GlobalTransform)>().iter() {
-            // Upload texture to GPU if not already cached
-            if !self.texture_cache.contains_key(&sprite.texture) {
-                log::info!("Texture {} not in GPU cache, attempting to load from AssetManager", sprite.texture.0);
-                let handle = AssetHandle::<TextureData>::new(sprite.texture);
-                if let Some(texture_data) = assets.get_texture(handle) {
-                    log::info!("Loaded texture {} from AssetManager, uploading to GPU", sprite.texture.0);
-                    self.upload_texture(device, queue, sprite.texture, texture_data);
-                } else {
-                    log::warn!("Texture {} not found in AssetManager! Using fallback texture", sprite.texture.0);
-                }
-            }
-
-            batch.add(SpriteInstance {
-                position: global_transform.position,
-                size: sprite.size * global_transform.scale, // Apply transform scale to sprite size
-                color: Color::new(sprite.color[0], sprite.color[1], sprite.color[2], sprite.color[3]),
-                texture: sprite.texture,
-                z_index: 0,
-            });
-        }
-
+        let target_cameras: Vec<Camera> = longhorn_core::cameras_in_render_order(world)
+            .into_iter()
+            .filter_map(|(_, mut camera)| {
+                let texture = assets.texture(camera.target?)?;
+                camera.viewport_size = Vec2::new(texture.width as f32, texture.height as f32);
+                Some(camera)
+            })
+            .collect();
+        let target_batches: Vec<SpriteBatch> = target_cameras
+            .iter()
+            .map(|camera| {
+                let mut batch = SpriteBatch::collect_visible(world, camera);
+                batch.sort();
+                batch
+            })
+            .collect();
+        let target_views: Vec<RenderView<'_>> = target_cameras
+            .iter()
+            .zip(&target_batches)
+            .map(|(camera, sprites)| RenderView {
+                camera,
+                sprites,
+                viewport: ScreenRect::full(camera.viewport_size.x as u32, camera.viewport_size.y as u32),
+                debug: None,
+            })
+            .collect();
+
+        let mut batch = SpriteBatch::collect_visible(world, &self.camera);
         batch.sort();
 
-        // Debug: Log sprite batch info
-        log::info!("Sprite batch has {} sprites", batch.len());
-        for (i, sprite) in batch.iter().enumerate() {
-            log::info!("  Sprite {}: texture={}, z_index={}, position=({}, {}), size=({}, {})",
-                i, sprite.texture.0, sprite.z_index,
-                sprite.position.x, sprite.position.y,
-                sprite.size.x, sprite.size.y);
-        }
+        self.draw_sprites(device, queue, &target_views, &batch, assets, debug, target);
+    }
+
+    /// Upload instances (and missing textures) and draw them to a render target
+    ///
+    /// `target_views` are cameras rendering into render textures, drawn first.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_sprites(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target_views: &[RenderView<'_>],
+        batch: &SpriteBatch,
+        textures: &dyn TextureLookup,
+        debug: Option<&DebugDraw>,
+        target: RenderTarget,
+    ) {
+        // Get the appropriate texture based on the target
+        let target_texture = match target {
+            RenderTarget::Editor => &self.editor_render_texture,
+            // Fall back to editor view if game view not allocated
+            RenderTarget::Game => self.game_render_texture.as_ref().unwrap_or(&self.editor_render_texture),
+        };
+        let size = target_texture.size();
+        let mut views = target_views.to_vec();
+        views.push(RenderView {
+            camera: &self.camera,
+            sprites: batch,
+            viewport: ScreenRect::full(size.width, size.height),
+            debug,
+        });
+        self.render_stats = self.sprite_pass.prepare_render_views(device, queue, &views, textures);
 
-        // Get the appropriate texture view based on the target
-        // This is done AFTER all mutable borrows are complete
         let target_view = match target {
             RenderTarget::Editor => &self.editor_render_view,
-            RenderTarget::Game => {
-                if let Some(view) = &self.game_render_view {
-                    view
-                } else {
-                    // Fall back to editor view if game view not allocated
-                    &self.editor_render_view
-                }
-            }
+            RenderTarget::Game => self.game_render_view.as_ref().unwrap_or(&self.editor_render_view),
         };
 
         // Create command encoder
@@ -813,98 +529,8 @@ impl EditorViewportRenderer {
             label: Some("Editor Viewport Encoder"),
         });
 
-        // Render pass - render sprites grouped by texture
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Editor Viewport Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: target_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(self.clear_color.to_wgpu()),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
-
-            render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
-
-            // Render sprites grouped by texture
-            // Collect batches with their texture and vertex ranges
-            struct TextureBatch {
-                texture: AssetId,
-                start_vertex: u32,
-                vertex_count: u32,
-            }
-
-            let mut batches = Vec::new();
-            let mut all_vertices = Vec::new();
-            let mut current_texture: Option<AssetId> = None;
-            let mut batch_start = 0u32;
-
-            for sprite in batch.iter() {
-                // If texture changed, record the previous batch
-                if current_texture.is_some() && current_texture != Some(sprite.texture) {
-                    let vertex_count = all_vertices.len() as u32 - batch_start;
-                    if vertex_count > 0 {
-                        batches.push(TextureBatch {
-                            texture: current_texture.unwrap(),
-                            start_vertex: batch_start,
-                            vertex_count,
-                        });
-                        log::info!("Recorded batch: texture={}, start={}, count={}",
-                            current_texture.unwrap().0, batch_start, vertex_count);
-                    }
-                    batch_start = all_vertices.len() as u32;
-                }
-
-                current_texture = Some(sprite.texture);
-                let sprite_verts = SpriteBatch::generate_vertices(sprite);
-                all_vertices.extend_from_slice(&sprite_verts);
-            }
-
-            // Record the final batch
-            if current_texture.is_some() {
-                let vertex_count = all_vertices.len() as u32 - batch_start;
-                if vertex_count > 0 {
-                    batches.push(TextureBatch {
-                        texture: current_texture.unwrap(),
-                        start_vertex: batch_start,
-                        vertex_count,
-                    });
-                    log::info!("Recorded final batch: texture={}, start={}, count={}",
-                        current_texture.unwrap().0, batch_start, vertex_count);
-                }
-            }
-
-            // Upload all vertices to the buffer once
-            if !all_vertices.is_empty() {
-                const MAX_VERTICES: usize = 1000 * 6;
-                let vertices_to_upload = if all_vertices.len() > MAX_VERTICES {
-                    log::warn!("Total vertex count exceeds buffer capacity ({} > {}). Truncating.",
-                               all_vertices.len(), MAX_VERTICES);
-                    &all_vertices[..MAX_VERTICES]
-                } else {
-                    &all_vertices[..]
-                };
-
-                queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices_to_upload));
-                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-
-                // Render each batch with its texture
-                for batch in batches.iter() {
-                    let bind_group = self.get_texture_bind_group(batch.texture);
-                    render_pass.set_bind_group(1, bind_group, &[]);
-                    render_pass.draw(batch.start_vertex..(batch.start_vertex + batch.vertex_count), 0..1);
-                    log::info!("Drew batch: texture={}, vertices {}..{}",
-                        batch.texture.0, batch.start_vertex, batch.start_vertex + batch.vertex_count);
-                }
-            }
-        }
+        self.sprite_pass
+            .encode(&mut encoder, target_view, self.clear_color.to_wgpu());
 
         queue.submit(std::iter::once(encoder.finish()));
     }
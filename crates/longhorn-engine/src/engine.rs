@@ -1,28 +1,36 @@
-use crate::{EngineConfig, GameManifest};
+use crate::schedule::systems;
+use crate::{subsystems, EngineConfig, GameManifest, Resources, Schedule, ScheduleError, Stage, SystemConfig};
 use longhorn_assets::{AssetManager, FilesystemSource};
-use longhorn_core::{Time, World};
+use longhorn_core::{
+    EntityGuid, EntityHandle, FixedTimestep, MainCamera, Time, World, WorldChange,
+};
 use longhorn_events::EventBus;
 use longhorn_input::{InputState, TouchEvent};
-use longhorn_renderer::{Camera, Renderer};
-use longhorn_scripting::ScriptRuntime;
+use longhorn_renderer::{
+    Camera, Color, DebugDraw, DebugShape, FrameBuffer, RenderBackend, RenderStats, RenderView, Renderer, ScaleMode,
+    ScreenRect, SoftwareRenderer, SpriteBatch, SpriteIndex, ViewportScaling,
+};
+use longhorn_scripting::{JsDebugShape, JsVec2, ScriptRuntime};
+use std::collections::HashMap;
+use std::fs;
 use std::path::Path;
 
 /// Main game engine
 pub struct Engine {
     /// ECS world
-    world: World,
+    pub(crate) world: World,
     /// Renderer (optional for headless mode)
-    renderer: Option<Renderer>,
-    /// Camera
+    renderer: Option<Box<dyn RenderBackend>>,
+    /// Camera used when the world has no enabled Camera entities
     camera: Camera,
     /// Input state
-    input: InputState,
+    pub(crate) input: InputState,
     /// Asset manager
-    assets: AssetManager<FilesystemSource>,
+    pub(crate) assets: AssetManager<FilesystemSource>,
     /// Script runtime
-    scripting: ScriptRuntime,
+    pub(crate) scripting: ScriptRuntime,
     /// Time tracking
-    time: Time,
+    pub(crate) time: Time,
     /// Engine configuration
     config: EngineConfig,
     /// Game manifest
@@ -30,35 +38,86 @@ pub struct Engine {
     /// Game path
     game_path: Option<String>,
     /// Event bus
-    event_bus: EventBus,
+    pub(crate) event_bus: EventBus,
+    /// Systems run each frame by `update`
+    schedule: Schedule,
+    /// State of installed subsystems (see `install`)
+    pub(crate) resources: Resources,
+    /// Accumulator driving the FixedUpdate stage
+    fixed_timestep: FixedTimestep,
+    /// Stats from the last rendered frame
+    render_stats: RenderStats,
+    /// Whether recorded world changes are forwarded to the event bus
+    change_events: bool,
+    /// Spatial index used to cull sprites (optional, see `enable_sprite_index`)
+    sprite_index: Option<SpriteIndex>,
+    /// Design resolution and scale mode from the game manifest (none until a
+    /// game is loaded, so the game area follows the screen)
+    design: Option<(glam::Vec2, ScaleMode)>,
+    /// Mapping between screen pixels and game space for the current screen size
+    pub(crate) scaling: ViewportScaling,
+    /// Debug shapes drawn over the game this frame
+    debug_draw: DebugDraw,
+}
+
+/// Maximum FixedUpdate steps per frame, so a long frame can't spiral
+const MAX_FIXED_STEPS: u32 = 8;
+
+/// Convert a script vector
+fn vec2(v: JsVec2) -> glam::Vec2 {
+    glam::Vec2::new(v.x as f32, v.y as f32)
 }
 
 impl Engine {
     /// Create a new headless engine (for testing/editor)
     pub fn new_headless() -> Self {
-        let config = EngineConfig::default();
+        Self::with_backend(EngineConfig::default(), None)
+    }
+
+    /// Create a headless engine that renders frames on the CPU
+    ///
+    /// Each `update` rasterizes the world into an RGBA buffer available from
+    /// `frame`, so rendering can be tested without a GPU.
+    pub fn new_software(config: EngineConfig) -> Self {
+        let renderer = SoftwareRenderer::new(config.viewport_width, config.viewport_height);
+        Self::with_backend(config, Some(Box::new(renderer)))
+    }
+
+    /// Create an engine around an optional render backend
+    fn with_backend(config: EngineConfig, renderer: Option<Box<dyn RenderBackend>>) -> Self {
         let camera = Camera::new(
             config.viewport_width as f32,
             config.viewport_height as f32,
         );
 
-        // Use a temporary directory for assets
+        // Use a temporary directory for assets until a game is loaded
         let temp_dir = std::env::temp_dir();
         let temp_source = FilesystemSource::new(&temp_dir);
 
-        Self {
+        let mut engine = Self {
             world: World::new(),
-            renderer: None,
+            renderer,
             camera,
             input: InputState::new(),
             assets: AssetManager::new(temp_source, temp_dir),
             scripting: ScriptRuntime::new(),
             time: Time::new(),
+            fixed_timestep: FixedTimestep::from_fps(config.target_fps.max(1)),
+            render_stats: RenderStats::default(),
+            change_events: false,
+            sprite_index: None,
+            design: None,
+            scaling: ViewportScaling::identity(config.viewport_width, config.viewport_height),
+            debug_draw: DebugDraw::new(),
             config,
             game_manifest: None,
             game_path: None,
             event_bus: EventBus::new(),
-        }
+            schedule: Schedule::new(),
+            resources: Resources::new(),
+        };
+        engine.install(Self::install_builtins);
+        engine
     }
 
     /// Create a new engine with a renderer
@@ -73,28 +132,30 @@ impl Engine {
         )
         .await?;
 
-        let camera = Camera::new(
-            config.viewport_width as f32,
-            config.viewport_height as f32,
-        );
-
-        // Use a temporary directory for assets until a game is loaded
-        let temp_dir = std::env::temp_dir();
-        let temp_source = FilesystemSource::new(&temp_dir);
+        Ok(Self::with_backend(config, Some(Box::new(renderer))))
+    }
 
-        Ok(Self {
-            world: World::new(),
-            renderer: Some(renderer),
-            camera,
-            input: InputState::new(),
-            assets: AssetManager::new(temp_source, temp_dir),
-            scripting: ScriptRuntime::new(),
-            time: Time::new(),
-            config,
-            game_manifest: None,
-            game_path: None,
-            event_bus: EventBus::new(),
-        })
+    /// Install the engine's own systems and the built-in subsystems
+    fn install_builtins(schedule: &mut Schedule, resources: &mut Resources) {
+        schedule.add_system(Stage::Update, systems::SCRIPTS, Engine::run_scripts);
+        schedule.add_system(Stage::PostUpdate, systems::TRANSFORM_PROPAGATION, |engine| {
+            longhorn_core::propagate_transforms(&mut engine.world);
+            Ok(())
+        });
+        schedule
+            .add_system(Stage::PostUpdate, systems::WORLD_CHANGES, |engine| {
+                engine.flush_world_changes();
+                Ok(())
+            })
+            .after(systems::TRANSFORM_PROPAGATION);
+        schedule.add_system(Stage::PreRender, systems::CAMERAS, |engine| {
+            let screen_size = engine.scaling.visible_size;
+            longhorn_core::update_cameras(&mut engine.world, engine.time.delta(), screen_size);
+            engine.camera.update(engine.time.delta(), None);
+            Ok(())
+        });
+        schedule.add_system(Stage::Render, systems::RENDER, Engine::render_frame);
+        subsystems::install(schedule, resources);
     }
 
     /// Load a game from a directory
@@ -109,25 +170,26 @@ impl Engine {
         // Load manifest
         let manifest = GameManifest::load(path)?;
 
-        // Update viewport if needed
+        // Start at the design resolution until the platform reports its screen size
         if manifest.viewport.width != self.config.viewport_width
             || manifest.viewport.height != self.config.viewport_height
         {
             self.config.viewport_width = manifest.viewport.width;
             self.config.viewport_height = manifest.viewport.height;
-            self.camera.viewport_size = glam::Vec2::new(
-                manifest.viewport.width as f32,
-                manifest.viewport.height as f32,
-            );
 
             if let Some(renderer) = &mut self.renderer {
                 renderer.resize(manifest.viewport.width, manifest.viewport.height);
             }
         }
+        let design_size = glam::Vec2::new(manifest.viewport.width as f32, manifest.viewport.height as f32);
+        self.design = Some((design_size, manifest.viewport.scale_mode));
+        self.update_scaling();
 
         // Set up asset manager with game directory
         let game_source = FilesystemSource::new(path);
         self.assets = AssetManager::new(game_source, path);
+        // Subsystem caches of the previous game's assets start over
+        self.resources.reset_per_game();
 
         // Preload assets
         for asset_path in &manifest.assets.preload {
@@ -141,7 +203,10 @@ impl Engine {
         let texture_ids: Vec<(String, longhorn_core::AssetId)> = self.assets.registry()
             .iter()
             .filter(|(path, _)| {
-                path.ends_with(".png") || path.ends_with(".jpg") || path.ends_with(".jpeg")
+                path.ends_with(".png")
+                    || path.ends_with(".jpg")
+                    || path.ends_with(".jpeg")
+                    || longhorn_assets::RenderTextureDesc::is_render_texture(path)
             })
             .map(|(path, id)| {
                 log::info!("Found texture in registry: {} -> ID {:?}", path, id);
@@ -173,6 +238,37 @@ impl Engine {
         Ok(())
     }
 
+    /// Load a scene through the asset manager and spawn it into the world
+    ///
+    /// Baked binary scenes are preferred when present (see `AssetManager::load_scene`).
+    ///
+    /// # Returns
+    /// A mapping from serialized entity IDs to the spawned entity handles
+    pub fn load_scene(&mut self, path: &str) -> Result<HashMap<u64, EntityHandle>, EngineError> {
+        let scene = self.assets.load_scene(path)?;
+        let entity_map = scene.spawn_into(&mut self.world, &mut self.assets)?;
+        log::info!("Loaded scene '{}' ({} entities)", path, entity_map.len());
+        Ok(entity_map)
+    }
+
+    /// Export the loaded game to `output` for shipping
+    ///
+    /// Copies the game's files, baking `.scn.ron` / `.scn.json` scenes to
+    /// binary `.scn.bin` files instead of copying them. Scenes still load by
+    /// their authoring paths, since the asset manager prefers baked scenes.
+    ///
+    /// # Returns
+    /// The paths of the baked scenes
+    pub fn export_game(&self, output: impl AsRef<Path>) -> Result<Vec<std::path::PathBuf>, EngineError> {
+        let game_path = self.game_path().ok_or(EngineError::NoGameLoaded)?;
+        let output = output.as_ref();
+        fs::create_dir_all(output)?;
+        copy_game_files(game_path, output, &fs::canonicalize(output)?)?;
+        let baked = longhorn_core::bake_scenes(game_path, output)?;
+        log::info!("Exported game to {} ({} scenes baked)", output.display(), baked.len());
+        Ok(baked)
+    }
+
     /// Start the game (call onStart in scripting)
     pub fn start(&mut self) -> Result<(), EngineError> {
         if self.game_path.is_none() {
@@ -181,6 +277,7 @@ impl Engine {
 
         // Reset time so first frame has delta = 0
         self.time.reset();
+        self.fixed_timestep.reset();
 
         self.scripting.initialize(&mut self.world)?;
         log::info!("Game started");
@@ -195,7 +292,11 @@ impl Engine {
     }
 
     /// Handle a touch event
+    ///
+    /// Positions are in screen pixels and are mapped into game space (see
+    /// `screen_to_game`) before reaching input state, events and scripts.
     pub fn handle_touch(&mut self, event: TouchEvent) {
+        let event = event.with_position(self.scaling.screen_to_game(event.position()));
         self.input.handle_event(event);
 
         // Emit to event bus
@@ -223,6 +324,9 @@ impl Engine {
     }
 
     /// Update the engine (main frame update)
+    ///
+    /// Runs every stage of the schedule in order; FixedUpdate runs once per
+    /// elapsed fixed timestep.
     pub fn update(&mut self) -> Result<(), EngineError> {
         // Update time
         self.time.update();
@@ -236,7 +340,46 @@ impl Engine {
         // Process pending events
         let _events = self.event_bus.process();
 
-        // Update scripting
+        // Drop debug shapes drawn last frame, keeping those with time left
+        self.debug_draw.advance(self.time.delta());
+
+        // Systems may add and remove systems while running, so run a detached
+        // schedule and apply their changes to it afterwards
+        let mut schedule = std::mem::replace(&mut self.schedule, Schedule::detached());
+        let result = self.run_schedule(&mut schedule);
+        schedule.merge(std::mem::replace(&mut self.schedule, Schedule::new()));
+        self.schedule = schedule;
+
+        // End the frame even if a system failed
+        self.input.begin_frame();
+        self.event_bus.emit(longhorn_events::EventType::FrameEnd, serde_json::json!({}));
+
+        result
+    }
+
+    /// Run each stage of a schedule in order
+    fn run_schedule(&mut self, schedule: &mut Schedule) -> Result<(), EngineError> {
+        for stage in Stage::ALL {
+            let runs = if stage == Stage::FixedUpdate {
+                let steps = self.fixed_timestep.tick(self.time.delta_duration());
+                if steps > MAX_FIXED_STEPS {
+                    log::warn!("Dropping {} fixed update steps", steps - MAX_FIXED_STEPS);
+                    self.fixed_timestep.reset();
+                }
+                steps.min(MAX_FIXED_STEPS)
+            } else {
+                1
+            };
+
+            for _ in 0..runs {
+                schedule.run_stage(stage, self)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Built-in system: run scripts and forward the events they emitted
+    fn run_scripts(&mut self) -> Result<(), EngineError> {
         if self.scripting.is_initialized() {
             self.scripting.update(&mut self.world, self.time.delta())?;
         }
@@ -260,51 +403,354 @@ impl Engine {
             );
         }
 
-        // Render if renderer is available
-        if let Some(renderer) = &mut self.renderer {
-            // Set clear color from config
-            renderer.set_clear_color(self.config.clear_color());
-
-            // Determine which camera to use for rendering:
-            // 1. If MainCamera component exists in the scene, use it (for consistent editor/mobile view)
-            // 2. Otherwise, fall back to engine's default camera (for backward compatibility)
-            let rendering_camera = {
-                use longhorn_renderer::MainCamera;
-                let mut query = self.world.query::<(&Camera, &MainCamera)>();
-
-                // Check if MainCamera exists and copy its data
-                if let Some((_entity, (main_camera, _marker))) = query.iter().next() {
-                    // Clone MainCamera data
-                    main_camera.clone()
-                } else {
-                    // Fall back to engine's default camera
-                    self.camera.clone()
-                }
+        // Apply camera shakes requested by scripts
+        for (camera_id, trauma) in longhorn_scripting::take_pending_camera_shakes() {
+            self.shake_camera(camera_id.map(EntityGuid), trauma);
+        }
+
+        // Queue debug shapes drawn by scripts
+        for draw in longhorn_scripting::take_pending_debug_draws() {
+            let shape = match draw.shape {
+                JsDebugShape::Line { start, end } => DebugShape::Line {
+                    start: vec2(start),
+                    end: vec2(end),
+                },
+                JsDebugShape::Rect { center, size } => DebugShape::Rect {
+                    center: vec2(center),
+                    size: vec2(size),
+                },
+                JsDebugShape::Circle { center, radius } => DebugShape::Circle {
+                    center: vec2(center),
+                    radius: radius as f32,
+                },
+                JsDebugShape::Arrow { start, end } => DebugShape::Arrow {
+                    start: vec2(start),
+                    end: vec2(end),
+                },
+                JsDebugShape::Text { position, text } => DebugShape::Text {
+                    position: vec2(position),
+                    text,
+                },
             };
+            let [r, g, b, a] = draw.color.map(|c| c as f32);
+            self.debug_draw.add(shape, Color::new(r, g, b, a), draw.duration as f32);
+        }
+
+        Ok(())
+    }
 
-            // Render the world
-            renderer.render(&self.world, &self.assets, &rendering_camera)?;
+    /// Add screen shake trauma to a camera
+    ///
+    /// With no `camera`, shakes the MainCamera entity, falling back to the
+    /// first camera in render order and then the engine's own camera.
+    pub fn shake_camera(&mut self, camera: Option<EntityGuid>, trauma: f32) {
+        let entity = match camera {
+            Some(guid) => self.world.entity_by_guid(guid),
+            None => {
+                let main = self
+                    .world
+                    .query::<(&Camera, &MainCamera)>()
+                    .iter()
+                    .next()
+                    .map(|(entity, _)| EntityHandle::new(entity));
+                main.or_else(|| {
+                    longhorn_core::cameras_in_render_order(&self.world)
+                        .first()
+                        .map(|(entity, _)| *entity)
+                })
+            }
+        };
+
+        match entity {
+            Some(entity) => match self.world.get_mut::<Camera>(entity) {
+                Ok(mut camera) => camera.add_trauma(trauma),
+                Err(_) => log::warn!("Camera shake target {:?} has no Camera", entity),
+            },
+            None if camera.is_none() => self.camera.add_trauma(trauma),
+            None => log::warn!("Camera shake target {:?} not found", camera),
         }
+    }
 
-        // Reset per-frame input state
-        self.input.begin_frame();
+    /// Built-in system: render the world if a renderer is available
+    ///
+    /// Every enabled Camera entity renders to its viewport, in ascending
+    /// `order`. Without any, the engine's own camera renders the whole screen.
+    fn render_frame(&mut self) -> Result<(), EngineError> {
+        if self.renderer.is_none() {
+            return Ok(());
+        }
+        self.sync_sprite_index();
+
+        // Cameras split the scaled game area; anything outside it is letterboxed.
+        // Cameras with a render-texture target fill that texture instead.
+        let area = self.scaling.viewport;
+        let mut cameras: Vec<(Camera, ScreenRect)> = Vec::new();
+        for (_, mut camera) in longhorn_core::cameras_in_render_order(&self.world) {
+            if let Some(target) = camera.target {
+                let size = match self.assets.load_texture_by_id(target) {
+                    Ok(handle) => self.assets.get_texture(handle).map(|t| (t.width, t.height)),
+                    Err(e) => {
+                        log::debug!("Render texture {:?} for camera target not loaded: {}", target, e);
+                        None
+                    }
+                };
+                let Some((width, height)) = size else {
+                    continue;
+                };
+                camera.viewport_size = glam::Vec2::new(width as f32, height as f32);
+                cameras.push((camera, ScreenRect::full(width, height)));
+                continue;
+            }
 
-        // Emit frame end
-        self.event_bus.emit(longhorn_events::EventType::FrameEnd, serde_json::json!({}));
+            let mut viewport = camera.viewport.to_pixels(area.width, area.height);
+            viewport.x += area.x;
+            viewport.y += area.y;
+            let pixels = glam::Vec2::new(viewport.width as f32, viewport.height as f32);
+            camera.viewport_size = pixels / self.scaling.scale;
+            cameras.push((camera, viewport));
+        }
+        cameras.retain(|(_, viewport)| !viewport.is_empty());
+        if cameras.iter().all(|(camera, _)| camera.target.is_some()) && !area.is_empty() {
+            let mut camera = self.camera.clone();
+            camera.viewport_size = glam::Vec2::new(area.width as f32, area.height as f32) / self.scaling.scale;
+            cameras.push((camera, area));
+        }
+
+        // UI canvases draw over every camera
+        let ui_views = subsystems::ui::views(self);
+
+        let Some(renderer) = &mut self.renderer else {
+            return Ok(());
+        };
+
+        // Set clear color from config
+        renderer.set_clear_color(self.config.clear_color());
 
+        // Cull through the sprite index if enabled, otherwise test every sprite
+        let batches: Vec<SpriteBatch> = cameras
+            .iter()
+            .map(|(camera, _)| {
+                let mut sprites = match &self.sprite_index {
+                    Some(index) => index.collect_visible(&self.world, camera),
+                    None => SpriteBatch::collect_visible(&self.world, camera),
+                };
+                sprites.sort();
+                sprites
+            })
+            .collect();
+
+        // Render the world, then the UI
+        let views: Vec<RenderView<'_>> = cameras
+            .iter()
+            .zip(&batches)
+            .map(|((camera, viewport), sprites)| RenderView {
+                camera,
+                sprites,
+                viewport: *viewport,
+                debug: camera.target.is_none().then_some(&self.debug_draw),
+            })
+            .chain(ui_views.iter().map(|view| RenderView {
+                camera: &view.camera,
+                sprites: &view.sprites,
+                viewport: view.viewport,
+                debug: None,
+            }))
+            .collect();
+        self.render_stats = renderer.render_views(&views, &self.assets)?;
         Ok(())
     }
 
-    /// Resize the viewport
+    /// Add a system to the schedule (see `Schedule::add_system`)
+    pub fn add_system<F>(&mut self, stage: Stage, name: impl Into<String>, system: F) -> SystemConfig<'_>
+    where
+        F: FnMut(&mut Engine) -> Result<(), EngineError> + 'static,
+    {
+        self.schedule.add_system(stage, name, system)
+    }
+
+    /// Get a reference to the schedule
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Get a mutable reference to the schedule
+    pub fn schedule_mut(&mut self) -> &mut Schedule {
+        &mut self.schedule
+    }
+
+    /// Install a subsystem by registering its resources and systems
+    ///
+    /// The built-in subsystems (see `subsystems`) are installed by every
+    /// constructor.
+    pub fn install(&mut self, install: impl FnOnce(&mut Schedule, &mut Resources)) {
+        install(&mut self.schedule, &mut self.resources);
+    }
+
+    /// Get a subsystem's resource
+    pub fn resource<T: 'static>(&self) -> Option<&T> {
+        self.resources.get()
+    }
+
+    /// Get a subsystem's resource mutably
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut()
+    }
+
+    /// Run `f` with a resource taken out of the engine, so it can use the
+    /// engine alongside it
+    ///
+    /// # Returns
+    /// What `f` returned, or `None` if the resource isn't present
+    pub fn resource_scope<T: 'static, R>(&mut self, f: impl FnOnce(&mut Engine, &mut T) -> R) -> Option<R> {
+        let mut resource = self.resources.take::<T>()?;
+        let result = f(self, &mut resource);
+        self.resources.insert(resource);
+        Some(result)
+    }
+
+    /// Get the FixedUpdate timestep in seconds
+    pub fn fixed_delta(&self) -> f32 {
+        self.fixed_timestep.timestep_secs()
+    }
+
+    /// Enable or disable world change events
+    ///
+    /// While enabled, the world records spawns, despawns and component
+    /// additions, changes and removals, and `update` forwards them to the
+    /// event bus as `EntitySpawned`, `EntityDespawned`, `ComponentAdded`,
+    /// `ComponentChanged` and `ComponentRemoved` events.
+    pub fn set_change_events(&mut self, enabled: bool) {
+        self.change_events = enabled;
+        self.world
+            .set_change_tracking(enabled || self.sprite_index.is_some());
+    }
+
+    /// Cull sprites through a spatial index with the given grid cell size
+    ///
+    /// Worth enabling for large levels: instead of testing every sprite each
+    /// frame, the index is updated from world change tracking (which this
+    /// turns on) and only sprites near the camera are visited.
+    pub fn enable_sprite_index(&mut self, cell_size: f32) {
+        self.sprite_index = Some(SpriteIndex::new(cell_size));
+        self.world.set_change_tracking(true);
+    }
+
+    /// Go back to testing every sprite against the camera
+    pub fn disable_sprite_index(&mut self) {
+        self.sprite_index = None;
+        self.world.set_change_tracking(self.change_events);
+    }
+
+    /// Get the sprite index, if enabled
+    pub fn sprite_index(&self) -> Option<&SpriteIndex> {
+        self.sprite_index.as_ref()
+    }
+
+    /// Apply changes recorded since the WORLD_CHANGES flush to the sprite
+    /// index, right before rendering
+    ///
+    /// Systems after the flush (PARTICLES and the PreRender stage) still
+    /// move and resize sprites; their changes stay in the log for the next
+    /// flush's events, so applying them here again there is harmless.
+    fn sync_sprite_index(&mut self) {
+        let Some(index) = &mut self.sprite_index else {
+            return;
+        };
+        // A replaced world starts untracked; rebuild once and track it from now on
+        if !self.world.is_change_tracking() {
+            self.world.set_change_tracking(true);
+            index.invalidate();
+        }
+        index.sync(&self.world);
+    }
+
+    /// Emit recorded world changes to the event bus and clear the change log
+    ///
+    /// The changes also update the sprite index, if enabled; changes made
+    /// later in the frame reach it before rendering.
+    ///
+    /// Called by `update`; hosts that edit the world without running `update`
+    /// (e.g. the editor outside of play mode) can call it directly.
+    pub fn flush_world_changes(&mut self) {
+        use longhorn_events::EventType;
+
+        let changes = self.world.take_changes();
+        if let Some(index) = &mut self.sprite_index {
+            index.apply_changes(&self.world, &changes);
+        }
+        if !self.change_events {
+            return;
+        }
+
+        for change in changes {
+            let entity = match change {
+                WorldChange::Despawned { guid, .. } => guid,
+                _ => self.world.guid(change.entity()),
+            }
+            .map(|guid| guid.get())
+            .unwrap_or_default();
+
+            let (event_type, data) = match change {
+                WorldChange::Spawned { .. } => (
+                    EventType::EntitySpawned,
+                    serde_json::json!({ "entity": entity }),
+                ),
+                WorldChange::Despawned { .. } => (
+                    EventType::EntityDespawned,
+                    serde_json::json!({ "entity": entity }),
+                ),
+                WorldChange::Added { component, .. } => (
+                    EventType::ComponentAdded,
+                    serde_json::json!({ "entity": entity, "component": component.name }),
+                ),
+                WorldChange::Changed { component, .. } => (
+                    EventType::ComponentChanged,
+                    serde_json::json!({ "entity": entity, "component": component.name }),
+                ),
+                WorldChange::Removed { component, .. } => (
+                    EventType::ComponentRemoved,
+                    serde_json::json!({ "entity": entity, "component": component.name }),
+                ),
+            };
+            self.event_bus.emit(event_type, data);
+        }
+    }
+
+    /// Resize the screen
+    ///
+    /// With a game loaded, the game area is rescaled to the new size using the
+    /// manifest's scale mode; otherwise the game area is the whole screen.
     pub fn resize(&mut self, width: u32, height: u32) {
         self.config.viewport_width = width;
         self.config.viewport_height = height;
 
-        self.camera.viewport_size = glam::Vec2::new(width as f32, height as f32);
-
         if let Some(renderer) = &mut self.renderer {
             renderer.resize(width, height);
         }
+        self.update_scaling();
+    }
+
+    /// Recompute the screen to game space mapping for the current screen size
+    fn update_scaling(&mut self) {
+        let (width, height) = match &self.renderer {
+            Some(renderer) => renderer.size(),
+            None => (self.config.viewport_width, self.config.viewport_height),
+        };
+        self.scaling = match self.design {
+            Some((design_size, mode)) => ViewportScaling::new(mode, design_size, width, height),
+            None => ViewportScaling::identity(width, height),
+        };
+        self.camera.viewport_size = self.scaling.visible_size;
+    }
+
+    /// Get the current mapping between screen pixels and game space
+    pub fn scaling(&self) -> &ViewportScaling {
+        &self.scaling
+    }
+
+    /// Convert a screen pixel position to game space
+    pub fn screen_to_game(&self, position: glam::Vec2) -> glam::Vec2 {
+        self.scaling.screen_to_game(position)
     }
 
     /// Get a reference to the game manifest
@@ -322,7 +768,8 @@ impl Engine {
         &mut self.world
     }
 
-    /// Get a reference to the camera
+    /// Get a reference to the camera used when the world has no enabled
+    /// Camera entities
     pub fn camera(&self) -> &Camera {
         &self.camera
     }
@@ -333,8 +780,29 @@ impl Engine {
     }
 
     /// Get a reference to the renderer (if available)
-    pub fn renderer(&self) -> Option<&Renderer> {
-        self.renderer.as_ref()
+    pub fn renderer(&self) -> Option<&dyn RenderBackend> {
+        self.renderer.as_deref()
+    }
+
+    /// Get the last rendered frame, for CPU render backends
+    pub fn frame(&self) -> Option<&FrameBuffer> {
+        self.renderer.as_deref().and_then(|renderer| renderer.frame())
+    }
+
+    /// Get draw call, instance and upload counts from the last rendered frame
+    pub fn render_stats(&self) -> RenderStats {
+        self.render_stats
+    }
+
+    /// Get the debug shapes drawn over the game
+    pub fn debug_draw(&self) -> &DebugDraw {
+        &self.debug_draw
+    }
+
+    /// Get the debug draw queue, to draw lines, shapes and labels over the
+    /// game (see `DebugDraw`)
+    pub fn debug_draw_mut(&mut self) -> &mut DebugDraw {
+        &mut self.debug_draw
     }
 
     /// Get a reference to the input state
@@ -384,11 +852,16 @@ impl Engine {
     }
 
     /// Spawn an entity with a name and emit EntitySpawned event.
+    ///
+    /// With change events enabled, the event is emitted by `flush_world_changes` instead.
     pub fn spawn_entity(&mut self, name: &str) -> longhorn_core::EntityHandle {
         use longhorn_core::Name;
 
         let handle = self.world.spawn().with(Name::new(name)).build();
-        let id = handle.id().to_bits().get();
+        if self.change_events {
+            return handle;
+        }
+        let id = self.world.guid(handle).map(|guid| guid.get()).unwrap_or_default();
 
         self.event_bus.emit(
             longhorn_events::EventType::EntitySpawned,
@@ -402,21 +875,46 @@ impl Engine {
     }
 
     /// Despawn an entity and emit EntityDespawned event.
+    ///
+    /// With change events enabled, the event is emitted by `flush_world_changes` instead.
     pub fn despawn_entity(&mut self, handle: longhorn_core::EntityHandle) -> Result<(), EngineError> {
-        let id = handle.id().to_bits().get();
-
-        self.event_bus.emit(
-            longhorn_events::EventType::EntityDespawned,
-            serde_json::json!({
-                "entity": id,
-            }),
-        );
+        if !self.change_events {
+            let id = self.world.guid(handle).map(|guid| guid.get()).unwrap_or_default();
+            self.event_bus.emit(
+                longhorn_events::EventType::EntityDespawned,
+                serde_json::json!({
+                    "entity": id,
+                }),
+            );
+        }
 
         self.world.despawn(handle)?;
         Ok(())
     }
 }
 
+/// Recursively copy a game's files from `from` to `to`, leaving out
+/// authoring scenes and the export directory `skip` if it's inside the game
+fn copy_game_files(from: &Path, to: &Path, skip: &Path) -> std::io::Result<()> {
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        let dest = to.join(path.file_name().unwrap_or_default());
+        if path.is_dir() {
+            if fs::canonicalize(&path)? != skip {
+                fs::create_dir_all(&dest)?;
+                copy_game_files(&path, &dest, skip)?;
+            }
+        } else if !path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(longhorn_core::is_text_scene)
+        {
+            fs::copy(&path, &dest)?;
+        }
+    }
+    Ok(())
+}
+
 /// Engine errors
 #[derive(Debug, thiserror::Error)]
 pub enum EngineError {
@@ -429,6 +927,9 @@ pub enum EngineError {
     #[error("Core error: {0}")]
     Core(#[from] longhorn_core::LonghornError),
 
+    #[error("Schedule error: {0}")]
+    Schedule(#[from] ScheduleError),
+
     #[error("No game loaded")]
     NoGameLoaded,
 }
@@ -436,7 +937,7 @@ pub enum EngineError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use longhorn_core::Name;
+    use longhorn_core::{AnimationPlayer, Name, NavSurface, Navigation, StateMachine, Tilemap, Transform};
     use std::fs;
 
     fn setup_test_game() -> std::path::PathBuf {
@@ -456,6 +957,7 @@ mod tests {
             viewport: crate::game::ViewportConfig {
                 width: 800,
                 height: 600,
+                scale_mode: ScaleMode::Fit,
             },
             assets: crate::game::AssetsConfig {
                 preload: vec![],
@@ -489,6 +991,53 @@ mod tests {
         fs::remove_dir_all(&temp_dir).unwrap();
     }
 
+    #[test]
+    fn test_load_scene() {
+        let temp_dir = setup_test_game();
+        let mut scene = longhorn_core::Scene::new("Level");
+        scene.add_entity(longhorn_core::SerializedEntity {
+            id: 5,
+            components: longhorn_core::SerializedComponents {
+                name: Some("Player".to_string()),
+                ..Default::default()
+            },
+            children: Vec::new(),
+        });
+        scene.save(temp_dir.join("level.scn.bin")).unwrap();
+
+        let mut engine = Engine::new_headless();
+        engine.load_game(&temp_dir).unwrap();
+        let entity_map = engine.load_scene("level.scn.bin").unwrap();
+
+        let player = entity_map[&5];
+        assert_eq!(engine.world().get::<Name>(player).unwrap().as_str(), "Player");
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_game_bakes_scenes() {
+        let temp_dir = setup_test_game();
+        longhorn_core::Scene::new("Main").save(temp_dir.join("scenes/main.scn.ron")).unwrap();
+        fs::write(temp_dir.join("scenes/notes.txt"), "kept").unwrap();
+        let mut engine = Engine::new_headless();
+        assert!(matches!(engine.export_game(temp_dir.join("build")), Err(EngineError::NoGameLoaded)));
+        engine.load_game(&temp_dir).unwrap();
+
+        // Exporting inside the game leaves the export directory out of the copy
+        let build = temp_dir.join("build");
+        let baked = engine.export_game(&build).unwrap();
+        assert_eq!(baked, vec![build.join("scenes/main.scn.bin")]);
+        assert!(build.join("game.json").exists() && build.join("scenes/notes.txt").exists());
+        assert!(!build.join("scenes/main.scn.ron").exists() && !build.join("build").exists());
+
+        let mut exported = Engine::new_headless();
+        exported.load_game(&build).unwrap();
+        exported.load_scene("scenes/main.scn.ron").unwrap();
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
     #[test]
     fn test_start_without_game() {
         let mut engine = Engine::new_headless();
@@ -532,6 +1081,670 @@ mod tests {
         fs::remove_dir_all(&temp_dir).unwrap();
     }
 
+    #[test]
+    fn test_change_events() {
+        use longhorn_events::EventType;
+
+        let mut engine = Engine::new_headless();
+        engine.set_change_events(true);
+
+        let entity = engine.spawn_entity("Tracked");
+        let guid = engine.world().guid(entity).unwrap().get();
+        engine.world_mut().set(entity, Transform::new()).unwrap();
+        engine.flush_world_changes();
+
+        let events = engine.event_bus_mut().process();
+        let spawned: Vec<_> = events.iter().filter(|e| e.event_type == EventType::EntitySpawned).collect();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].data["entity"], guid);
+        assert!(events.iter().any(|e| e.event_type == EventType::ComponentAdded
+            && e.data["component"] == "Transform"));
+
+        engine.despawn_entity(entity).unwrap();
+        engine.flush_world_changes();
+        let events = engine.event_bus_mut().process();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::EntityDespawned);
+        assert_eq!(events[0].data["entity"], guid);
+    }
+
+    #[test]
+    fn test_sprite_index_culling() {
+        let mut engine = Engine::new_software(EngineConfig::new(100, 100, 60));
+        engine.enable_sprite_index(64.0);
+        let texture = longhorn_core::AssetId::new(1);
+        let spawn = |engine: &mut Engine, x: f32| {
+            engine
+                .world_mut()
+                .spawn()
+                .with(longhorn_core::Sprite::new(texture, glam::Vec2::new(10.0, 10.0)))
+                .with(Transform::from_position(glam::Vec2::new(x, 0.0)))
+                .build()
+        };
+        spawn(&mut engine, 0.0);
+        let far = spawn(&mut engine, 1000.0);
+
+        engine.update().unwrap();
+        let stats = engine.render_stats();
+        assert_eq!((stats.visible_sprites, stats.culled_sprites), (1, 1));
+        assert_eq!(engine.sprite_index().unwrap().len(), 2);
+
+        engine.world_mut().get_mut::<Transform>(far).unwrap().position = glam::Vec2::new(20.0, 0.0);
+        engine.update().unwrap();
+        let stats = engine.render_stats();
+        assert_eq!((stats.visible_sprites, stats.culled_sprites), (2, 0));
+
+        // Change tracking stays on for the index but no events are emitted
+        assert!(engine.world().is_change_tracking());
+        assert!(!engine
+            .event_bus_mut()
+            .process()
+            .iter()
+            .any(|e| e.event_type == longhorn_events::EventType::ComponentChanged));
+    }
+
+    #[test]
+    fn test_sprite_index_sees_changes_after_world_changes() {
+        let mut engine = Engine::new_software(EngineConfig::new(100, 100, 60));
+        engine.enable_sprite_index(64.0);
+        let texture = longhorn_core::AssetId::new(1);
+        let far = engine
+            .world_mut()
+            .spawn()
+            .with(longhorn_core::Sprite::new(texture, glam::Vec2::new(10.0, 10.0)))
+            .with(Transform::from_position(glam::Vec2::new(1000.0, 0.0)))
+            .build();
+        engine.update().unwrap();
+        assert_eq!(engine.render_stats().visible_sprites, 0);
+
+        // Grown to reach the camera after the PostUpdate flush, in the same frame
+        engine.add_system(Stage::PreRender, "grow", move |engine| {
+            if let Ok(mut sprite) = engine.world_mut().get_mut::<longhorn_core::Sprite>(far) {
+                sprite.size = glam::Vec2::new(2100.0, 10.0);
+            }
+            Ok(())
+        });
+        engine.update().unwrap();
+        assert_eq!(engine.render_stats().visible_sprites, 1);
+    }
+
+    #[test]
+    fn test_update_runs_schedule() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut engine = Engine::new_headless();
+        let entity = engine
+            .world_mut()
+            .spawn()
+            .with(Transform::from_position(glam::Vec2::new(3.0, 4.0)))
+            .build();
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let update_log = log.clone();
+        engine
+            .add_system(Stage::Update, "gameplay", move |_| {
+                update_log.borrow_mut().push("gameplay");
+                Ok(())
+            })
+            .before(systems::SCRIPTS);
+        let pre_log = log.clone();
+        engine.add_system(Stage::PreUpdate, "input", move |_| {
+            pre_log.borrow_mut().push("input");
+            Ok(())
+        });
+
+        assert_eq!(
+            engine.schedule_mut().system_names(Stage::Update).unwrap(),
+            vec!["gameplay", "scripts", "tweens", "state_machines", "animations", "navigation"]
+        );
+
+        engine.update().unwrap();
+        assert_eq!(*log.borrow(), vec!["input", "gameplay"]);
+
+        // Transform propagation is part of the default schedule
+        let global = engine.world().get::<longhorn_core::GlobalTransform>(entity).unwrap();
+        assert_eq!(global.position, glam::Vec2::new(3.0, 4.0));
+    }
+
+    #[test]
+    fn test_schedule_error_stops_update() {
+        let mut engine = Engine::new_headless();
+        engine.add_system(Stage::Update, "a", |_| Ok(())).after("b");
+        engine.add_system(Stage::Update, "b", |_| Ok(())).after("a");
+
+        assert!(matches!(engine.update(), Err(EngineError::Schedule(_))));
+        // The schedule is kept after a failed frame, and the frame still ends
+        assert!(engine.schedule().contains("a"));
+        let events = engine.event_bus_mut().process();
+        assert_eq!(events.last().unwrap().event_type, longhorn_events::EventType::FrameEnd);
+    }
+
+    #[test]
+    fn test_systems_can_remove_systems_while_running() {
+        let mut engine = Engine::new_headless();
+        engine.add_system(Stage::Update, "once", |engine| {
+            engine.schedule_mut().remove_system("once");
+            engine.schedule_mut().remove_system(systems::TWEENS);
+            engine.add_system(Stage::Update, "spawned", |_| Ok(()));
+            Ok(())
+        });
+
+        engine.update().unwrap();
+        assert!(!engine.schedule().contains("once"));
+        assert!(!engine.schedule().contains(systems::TWEENS));
+        assert!(engine.schedule().contains("spawned"));
+    }
+
+    #[test]
+    fn test_animation_players_send_clip_events() {
+        use longhorn_core::{AnimationClip, AnimationField, TweenProperty};
+        use longhorn_events::{EventTarget, EventType};
+
+        let temp_dir = setup_test_game();
+        let mut engine = Engine::new_headless();
+        engine.load_game(&temp_dir).unwrap();
+
+        let mut clip = AnimationClip::new(1.0);
+        clip.set_key("", AnimationField::Property(TweenProperty::PositionY), 0.0, 0.0);
+        clip.set_key("", AnimationField::Property(TweenProperty::PositionY), 1.0, 4.0);
+        clip.add_event(0.5, "footstep").data = serde_json::json!({ "foot": "left" });
+        let clip_id = engine.assets_mut().save_animation_clip("anim/walk.anim", &clip).unwrap();
+
+        let entity = engine.spawn_entity("Walker");
+        let guid = engine.world().guid(entity).unwrap().get();
+        engine.world_mut().set(entity, Transform::new()).unwrap();
+        engine.world_mut().set(entity, AnimationPlayer::new(clip_id)).unwrap();
+
+        subsystems::animations::update(&mut engine, 0.75);
+        assert_eq!(engine.world().get::<Transform>(entity).unwrap().position.y, 3.0);
+        let events: Vec<_> = engine
+            .event_bus_mut()
+            .process()
+            .into_iter()
+            .filter(|e| e.event_type == EventType::AnimationEvent)
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].target, EventTarget::Entity(guid));
+        assert_eq!(
+            events[0].data,
+            serde_json::json!({ "entity": guid, "clip": clip_id.0, "name": "footstep", "time": 0.5, "data": { "foot": "left" } })
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_state_machines_send_events_and_play_animations() {
+        use longhorn_core::{AnimationClip, State, StateAction, StateChart, Transition};
+        use longhorn_events::{EventTarget, EventType};
+
+        let temp_dir = setup_test_game();
+        let mut engine = Engine::new_headless();
+        engine.load_game(&temp_dir).unwrap();
+
+        let clip_id = engine.assets_mut().save_animation_clip("anim/swing.anim", &AnimationClip::new(0.5)).unwrap();
+        let chart = StateChart::new(vec![
+            State::new("Idle").with_transition(Transition::new("Attack").on_event("attack")),
+            State::new("Attack")
+                .with_on_enter(StateAction::PlayAnimation {
+                    clip: "anim/swing.anim".to_string(),
+                    crossfade: 0.0,
+                })
+                .with_on_enter(StateAction::Emit {
+                    event: "swing".to_string(),
+                    data: serde_json::json!({ "damage": 3 }),
+                }),
+        ]);
+        let chart_id = engine.assets_mut().save_state_chart("ai/fighter.fsm", &chart).unwrap();
+
+        let entity = engine.spawn_entity("Fighter");
+        let guid = engine.world().guid(entity).unwrap().get();
+        engine.world_mut().set(entity, StateMachine::new(chart_id)).unwrap();
+        subsystems::state_machines::update(&mut engine, 0.1).unwrap();
+        engine.world_mut().get_mut::<StateMachine>(entity).unwrap().send("attack");
+        subsystems::state_machines::update(&mut engine, 0.1).unwrap();
+
+        assert_eq!(engine.world().get::<StateMachine>(entity).unwrap().state(), Some("Attack"));
+        assert_eq!(engine.world().get::<AnimationPlayer>(entity).unwrap().clip, clip_id);
+        let events: Vec<_> = engine.event_bus_mut().process();
+        let states: Vec<_> = events
+            .iter()
+            .filter(|e| matches!(e.event_type, EventType::StateEntered | EventType::StateExited))
+            .map(|e| (e.event_type.clone(), e.data["state"].clone()))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (EventType::StateEntered, serde_json::json!("Idle")),
+                (EventType::StateExited, serde_json::json!("Idle")),
+                (EventType::StateEntered, serde_json::json!("Attack")),
+            ]
+        );
+        let swing = events.iter().find(|e| e.event_type == EventType::Custom("swing".to_string())).unwrap();
+        assert_eq!(swing.target, EventTarget::Entity(guid));
+        assert_eq!(swing.data, serde_json::json!({ "damage": 3 }));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_software_rendering() {
+        let temp_dir = setup_test_game();
+        let mut texture = FrameBuffer::new(1, 1);
+        texture.pixels = vec![0, 255, 0, 255];
+        texture.save_png(temp_dir.join("green.png")).unwrap();
+
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        engine.load_game(&temp_dir).unwrap();
+        let handle = engine.assets_mut().load_texture("green.png").unwrap();
+        engine
+            .world_mut()
+            .spawn()
+            .with(longhorn_core::Sprite::new(handle.id(), glam::Vec2::new(8.0, 8.0)))
+            .with(Transform::new())
+            .build();
+
+        assert!(engine.frame().unwrap().pixels.iter().all(|&p| p == 0));
+        engine.update().unwrap();
+
+        // The game manifest's viewport size wins over the initial config
+        let frame = engine.frame().unwrap();
+        assert_eq!((frame.width, frame.height), (800, 600));
+        assert_eq!(frame.pixel(400, 300), [0, 255, 0, 255]);
+        assert_ne!(frame.pixel(0, 0), [0, 255, 0, 255]);
+        assert_eq!(engine.renderer().unwrap().loaded_texture_ids(), vec![handle.id()]);
+        assert_eq!(engine.render_stats().instances, 1);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_text_rendering() {
+        let temp_dir = setup_test_game();
+        fs::write(temp_dir.join("ui.ttf"), epaint_default_fonts::UBUNTU_LIGHT).unwrap();
+
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        engine.load_game(&temp_dir).unwrap();
+        let font = engine.assets_mut().load_font("ui.ttf").unwrap().id();
+        engine
+            .world_mut()
+            .spawn()
+            .with(longhorn_core::Text::new("HI", font, 48.0).with_color([1.0, 0.0, 0.0, 1.0]))
+            .with(Transform::new())
+            .build();
+
+        engine.update().unwrap();
+
+        // One instance per glyph, hanging below and right of the entity at the screen center
+        assert_eq!(engine.render_stats().instances, 2);
+        let frame = engine.frame().unwrap();
+        let red = (400..460)
+            .flat_map(|x| (300..350).map(move |y| (x, y)))
+            .filter(|&(x, y)| frame.pixel(x, y) == [255, 0, 0, 255])
+            .count();
+        assert!(red > 50, "only {} red pixels", red);
+        assert_ne!(frame.pixel(390, 320), [255, 0, 0, 255]);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    /// Engine with a five by three tile NavRegion, a wall up the middle of
+    /// its bottom two rows, and a NavAgent walking across it
+    ///
+    /// # Returns
+    /// The engine, the region and the agent
+    fn nav_test_level(temp_dir: &std::path::Path) -> (Engine, EntityHandle, EntityHandle) {
+        use longhorn_core::{NavAgent, NavRegion};
+
+        FrameBuffer::new(2, 1).save_png(temp_dir.join("tiles.png")).unwrap();
+        fs::write(
+            temp_dir.join("tiles.tileset"),
+            r#"{"image": "tiles.png", "tile_width": 1, "tile_height": 1, "tiles": {"1": {"solid": true}}}"#,
+        )
+        .unwrap();
+        let mut engine = Engine::new_headless();
+        engine.load_game(temp_dir).unwrap();
+
+        let tileset = engine.assets_mut().load_tileset("tiles.tileset").unwrap().id();
+        let mut tilemap = Tilemap::new(tileset, glam::Vec2::splat(10.0)).with_layer("Ground");
+        for (x, y) in (0..5).flat_map(|x| (0..3).map(move |y| (x, y))) {
+            tilemap.set_tile(0, x, y, Some(if x == 2 && y > 0 { 1 } else { 0 }));
+        }
+        let level = engine.world_mut().spawn().with(tilemap).with(NavRegion::from_tilemap()).build();
+        engine.world_mut().set(level, Transform::from_position(glam::Vec2::new(0.0, 30.0))).unwrap();
+        let agent = engine.spawn_entity("Guard");
+        engine.world_mut().set(agent, Transform::from_position(glam::Vec2::new(5.0, 5.0))).unwrap();
+        let mut nav_agent = NavAgent::new(100.0);
+        nav_agent.set_destination(glam::Vec2::new(45.0, 5.0));
+        engine.world_mut().set(agent, nav_agent).unwrap();
+        (engine, level, agent)
+    }
+
+    #[test]
+    fn test_nav_agents_walk_around_solid_tiles() {
+        use longhorn_core::{NavAgent, NavRegion, NavStatus};
+        use longhorn_events::{EventTarget, EventType};
+
+        let temp_dir = setup_test_game();
+        let (mut engine, level, agent) = nav_test_level(&temp_dir);
+        let guid = engine.world().guid(agent).unwrap().get();
+
+        let walk = |engine: &mut Engine| {
+            for _ in 0..20 {
+                subsystems::navigation::update(engine, 0.05);
+            }
+            engine.world().get::<NavAgent>(agent).unwrap().status
+        };
+        assert_eq!(walk(&mut engine), NavStatus::Arrived);
+        assert!(matches!(engine.resource::<Navigation>().unwrap().surface(), Some(NavSurface::Grid(_))));
+        assert_eq!(engine.world().get::<Transform>(agent).unwrap().position, glam::Vec2::new(45.0, 5.0));
+        let events = engine.event_bus_mut().process();
+        let reached = events.iter().find(|e| e.event_type == EventType::DestinationReached).unwrap();
+        assert_eq!(reached.target, EventTarget::Entity(guid));
+        assert_eq!(reached.data["destination"], serde_json::json!([45.0, 5.0]));
+
+        // Baking swaps the grid for a navmesh of the same area
+        let navmesh = subsystems::navigation::bake_navmesh(&mut engine, level, "levels/cave.navmesh").unwrap();
+        assert_eq!(*engine.world().get::<NavRegion>(level).unwrap(), NavRegion::from_navmesh(navmesh));
+        engine.world_mut().get_mut::<NavAgent>(agent).unwrap().set_destination(glam::Vec2::new(5.0, 5.0));
+        assert_eq!(walk(&mut engine), NavStatus::Arrived);
+        assert!(matches!(engine.resource::<Navigation>().unwrap().surface(), Some(NavSurface::Mesh(_))));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "debug-draw")]
+    fn test_draw_navigation() {
+        let temp_dir = setup_test_game();
+        let (mut engine, _, _) = nav_test_level(&temp_dir);
+
+        let mut debug = DebugDraw::new();
+        subsystems::navigation::draw(&mut engine, &mut debug);
+        // Bounds, the two wall cells and the destination
+        assert_eq!(debug.len(), 4);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_tilemap_rendering() {
+        let temp_dir = setup_test_game();
+        let mut tiles = FrameBuffer::new(2, 1);
+        tiles.pixels = vec![0, 255, 0, 255, 0, 0, 255, 255];
+        tiles.save_png(temp_dir.join("tiles.png")).unwrap();
+        fs::write(
+            temp_dir.join("tiles.tileset"),
+            r#"{"image": "tiles.png", "tile_width": 1, "tile_height": 1}"#,
+        )
+        .unwrap();
+
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        engine.load_game(&temp_dir).unwrap();
+        let tileset = engine.assets_mut().load_tileset("tiles.tileset").unwrap().id();
+        let mut tilemap = longhorn_core::Tilemap::new(tileset, glam::Vec2::splat(20.0)).with_layer("Ground");
+        tilemap.set_tile(0, 0, 0, Some(1));
+        tilemap.set_tile(0, 1, 0, Some(0));
+        let entity = engine.world_mut().spawn().with(tilemap).with(Transform::new()).build();
+
+        engine.update().unwrap();
+
+        // Cell (0, 0) hangs below and right of the entity at the screen center
+        assert_eq!(engine.render_stats().instances, 2);
+        let frame = engine.frame().unwrap();
+        assert_eq!(frame.pixel(410, 310), [0, 0, 255, 255]);
+        assert_eq!(frame.pixel(430, 310), [0, 255, 0, 255]);
+
+        engine
+            .world_mut()
+            .get_mut::<longhorn_core::Tilemap>(entity)
+            .unwrap()
+            .set_tile(0, 0, 0, Some(0));
+        engine.update().unwrap();
+        assert_eq!(engine.frame().unwrap().pixel(410, 310), [0, 255, 0, 255]);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_particle_rendering() {
+        let temp_dir = setup_test_game();
+        let mut texture = FrameBuffer::new(1, 1);
+        texture.pixels = vec![0, 255, 0, 255];
+        texture.save_png(temp_dir.join("spark.png")).unwrap();
+
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        engine.load_game(&temp_dir).unwrap();
+        let spark = engine.assets_mut().load_texture("spark.png").unwrap().id();
+        let mut effect = longhorn_core::ParticleEffect::continuous(0.0);
+        effect.speed = [0.0, 0.0];
+        effect.color = longhorn_core::Curve::constant([1.0, 1.0, 1.0, 1.0]);
+        let mut emitter = longhorn_core::ParticleEmitter::new(effect, spark);
+        emitter.burst(5);
+        let entity = engine.world_mut().spawn().with(emitter).with(Transform::new()).build();
+
+        engine.update().unwrap();
+
+        // Bursts are simulated after scripts and drawn the same frame
+        let state = engine.world().get::<longhorn_core::ParticleState>(entity).unwrap();
+        assert_eq!(state.len(), 5);
+        drop(state);
+        assert_eq!(engine.render_stats().instances, 5);
+        assert_eq!(engine.frame().unwrap().pixel(400, 300), [0, 255, 0, 255]);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_split_screen_cameras() {
+        let temp_dir = setup_test_game();
+        let mut texture = FrameBuffer::new(1, 1);
+        texture.pixels = vec![0, 255, 0, 255];
+        texture.save_png(temp_dir.join("green.png")).unwrap();
+
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        engine.load_game(&temp_dir).unwrap();
+        let handle = engine.assets_mut().load_texture("green.png").unwrap();
+        let player = engine
+            .world_mut()
+            .spawn()
+            .with(longhorn_core::Sprite::new(handle.id(), glam::Vec2::new(8.0, 8.0)))
+            .with(Transform::from_position(glam::Vec2::new(500.0, 0.0)))
+            .build();
+
+        // Left half follows the player, right half stays at the origin
+        let follow = longhorn_core::CameraFollow::new(longhorn_core::EntityRef::to_entity(engine.world(), player));
+        let left = engine
+            .world_mut()
+            .spawn()
+            .with(Camera::default().with_follow(follow).with_viewport(longhorn_core::ViewportRect::new(0.0, 0.0, 0.5, 1.0)))
+            .build();
+        engine
+            .world_mut()
+            .spawn()
+            .with(Camera::default().with_order(1).with_viewport(longhorn_core::ViewportRect::new(0.5, 0.0, 0.5, 1.0)))
+            .build();
+
+        engine.update().unwrap();
+
+        let camera = engine.world().get::<Camera>(left).unwrap();
+        assert_eq!(camera.position, glam::Vec2::new(500.0, 0.0));
+        assert_eq!(camera.viewport_size, glam::Vec2::new(400.0, 600.0));
+        drop(camera);
+
+        let frame = engine.frame().unwrap();
+        assert_eq!(frame.pixel(200, 300), [0, 255, 0, 255]);
+        assert_ne!(frame.pixel(600, 300), [0, 255, 0, 255]);
+        let stats = engine.render_stats();
+        assert_eq!((stats.visible_sprites, stats.culled_sprites), (1, 1));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "debug-draw")]
+    fn test_debug_draw_lasts_one_frame_unless_timed() {
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        let mut frame = 0;
+        engine.add_system(Stage::Update, "debug", move |engine| {
+            if frame == 0 {
+                let red = Color::new(1.0, 0.0, 0.0, 1.0);
+                engine.debug_draw_mut().line(glam::Vec2::new(-7.5, 0.5), glam::Vec2::new(7.5, 0.5), red);
+                engine.debug_draw_mut().add(DebugShape::Circle { center: glam::Vec2::ZERO, radius: 4.0 }, red, 10.0);
+            }
+            frame += 1;
+            Ok(())
+        });
+
+        engine.update().unwrap();
+        assert_eq!(engine.debug_draw().len(), 2);
+        assert_eq!(engine.frame().unwrap().pixel(0, 7), [255, 0, 0, 255]);
+
+        // The line is dropped on the next frame, the circle has time left
+        engine.update().unwrap();
+        assert_eq!(engine.debug_draw().len(), 1);
+        assert_ne!(engine.frame().unwrap().pixel(0, 7), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "debug-draw"))]
+    fn test_debug_draw_compiled_out() {
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+        engine.debug_draw_mut().line(glam::Vec2::new(-7.5, 0.5), glam::Vec2::new(7.5, 0.5), red);
+        engine.debug_draw_mut().add(DebugShape::Circle { center: glam::Vec2::ZERO, radius: 4.0 }, red, 10.0);
+
+        engine.update().unwrap();
+        assert!(engine.debug_draw().is_empty());
+        assert_ne!(engine.frame().unwrap().pixel(0, 7), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_shake_camera() {
+        let mut engine = Engine::new_headless();
+        let main = engine.world_mut().spawn().with(Camera::default()).with(MainCamera).build();
+        let other = engine.world_mut().spawn().with(Camera::default()).build();
+        let other_guid = engine.world().guid(other).unwrap();
+
+        engine.shake_camera(None, 0.4);
+        engine.shake_camera(Some(other_guid), 0.7);
+
+        assert_eq!(engine.world().get::<Camera>(main).unwrap().shake.trauma(), 0.4);
+        assert_eq!(engine.world().get::<Camera>(other).unwrap().shake.trauma(), 0.7);
+    }
+
+    #[test]
+    fn test_scaling_letterboxes_and_maps_touches() {
+        let temp_dir = setup_test_game();
+        let mut texture = FrameBuffer::new(1, 1);
+        texture.pixels = vec![0, 255, 0, 255];
+        texture.save_png(temp_dir.join("green.png")).unwrap();
+
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        engine.load_game(&temp_dir).unwrap();
+        let handle = engine.assets_mut().load_texture("green.png").unwrap();
+        // Covers the whole 800x600 design area
+        engine
+            .world_mut()
+            .spawn()
+            .with(longhorn_core::Sprite::new(handle.id(), glam::Vec2::new(800.0, 600.0)))
+            .with(Transform::new())
+            .build();
+
+        // Twice as wide as the design: pillarboxed at 1x
+        engine.resize(1600, 600);
+        assert_eq!(engine.scaling().viewport, ScreenRect { x: 400, y: 0, width: 800, height: 600 });
+        assert_eq!(engine.camera().viewport_size, glam::Vec2::new(800.0, 600.0));
+
+        engine.update().unwrap();
+        let frame = engine.frame().unwrap();
+        assert_eq!(frame.pixel(800, 300), [0, 255, 0, 255]);
+        assert_ne!(frame.pixel(200, 300), [0, 255, 0, 255]);
+        assert_ne!(frame.pixel(1400, 300), [0, 255, 0, 255]);
+
+        // Touches are reported in design-resolution coordinates
+        engine.handle_touch(TouchEvent::Start { x: 800.0, y: 300.0 });
+        assert_eq!(engine.input.position(), glam::Vec2::new(400.0, 300.0));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_ui_draws_over_the_game_and_clicks() {
+        use longhorn_core::{Canvas, RectTransform, UiButton, UiPanel};
+
+        let temp_dir = setup_test_game();
+        let mut texture = FrameBuffer::new(1, 1);
+        texture.pixels = vec![0, 255, 0, 255];
+        texture.save_png(temp_dir.join("green.png")).unwrap();
+
+        let mut engine = Engine::new_software(EngineConfig::new(16, 16, 60));
+        engine.load_game(&temp_dir).unwrap();
+        let handle = engine.assets_mut().load_texture("green.png").unwrap();
+        engine
+            .world_mut()
+            .spawn()
+            .with(longhorn_core::Sprite::new(handle.id(), glam::Vec2::new(800.0, 600.0)))
+            .with(Transform::new())
+            .build();
+        let canvas = engine.world_mut().spawn().with(Canvas::new()).build();
+        let button = engine
+            .world_mut()
+            .spawn()
+            .with(RectTransform::anchored(glam::Vec2::new(1.0, 0.0), glam::Vec2::ZERO, glam::Vec2::new(100.0, 100.0)))
+            .with(UiPanel::new([1.0, 0.0, 0.0, 1.0]))
+            .with(UiButton::new())
+            .build();
+        longhorn_core::add_child(engine.world_mut(), canvas, button).unwrap();
+
+        // The canvas covers the pillarboxed game area, not the screen
+        engine.resize(1600, 600);
+        engine.update().unwrap();
+        let frame = engine.frame().unwrap();
+        assert_eq!(frame.pixel(1150, 50), [255, 0, 0, 255]);
+        assert_eq!(frame.pixel(1050, 50), [0, 255, 0, 255]);
+        assert_eq!(frame.pixel(1150, 150), [0, 255, 0, 255]);
+
+        engine.handle_touch(TouchEvent::Start { x: 1150.0, y: 50.0 });
+        engine.handle_touch(TouchEvent::End { x: 1150.0, y: 50.0 });
+        engine.update().unwrap();
+        let click = &engine.resource::<longhorn_ui::UiSystem>().unwrap().events()[0];
+        assert_eq!(click.path, vec![button, canvas]);
+        assert_eq!(click.position, glam::Vec2::new(750.0, 50.0));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_subsystem() {
+        #[derive(Default)]
+        struct Frames(u32);
+
+        let temp_dir = setup_test_game();
+        let mut engine = Engine::new_headless();
+        engine.install(|schedule, resources| {
+            resources.insert_per_game(Frames::default);
+            schedule.add_system(Stage::Update, "frames", |engine| {
+                engine.resource_scope(|engine, frames: &mut Frames| {
+                    frames.0 += 1;
+                    assert!(engine.resource::<Frames>().is_none());
+                });
+                Ok(())
+            });
+        });
+
+        engine.update().unwrap();
+        engine.update().unwrap();
+        assert_eq!(engine.resource::<Frames>().unwrap().0, 2);
+
+        // Per-game resources start over with the next game
+        engine.load_game(&temp_dir).unwrap();
+        assert_eq!(engine.resource::<Frames>().unwrap().0, 0);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
     #[test]
     fn test_resize() {
         let mut engine = Engine::new_headless();
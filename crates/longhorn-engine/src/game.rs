@@ -1,3 +1,4 @@
+use longhorn_renderer::ScaleMode;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -45,10 +46,14 @@ impl GameManifest {
 /// Viewport configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ViewportConfig {
-    /// Viewport width in pixels
+    /// Design resolution width in pixels
     pub width: u32,
-    /// Viewport height in pixels
+    /// Design resolution height in pixels
     pub height: u32,
+    /// How the design resolution is scaled to the screen
+    /// (`"fit"`, `"fill"`, `"expand"` or `"pixel_perfect"`)
+    #[serde(default)]
+    pub scale_mode: ScaleMode,
 }
 
 /// Assets configuration
@@ -81,6 +86,7 @@ mod tests {
             viewport: ViewportConfig {
                 width: 800,
                 height: 600,
+                scale_mode: ScaleMode::Expand,
             },
             assets: AssetsConfig {
                 preload: vec!["sprites/player.png".to_string()],
@@ -103,6 +109,7 @@ mod tests {
         assert_eq!(manifest.entry, "main.ts");
         assert_eq!(manifest.viewport.width, 800);
         assert_eq!(manifest.viewport.height, 600);
+        assert_eq!(manifest.viewport.scale_mode, ScaleMode::Expand);
         assert_eq!(manifest.assets.preload.len(), 1);
         assert_eq!(manifest.assets.preload[0], "sprites/player.png");
 
@@ -141,6 +148,7 @@ mod tests {
 
         let manifest = GameManifest::load(&temp_dir).unwrap();
         assert_eq!(manifest.name, "Simple Game");
+        assert_eq!(manifest.viewport.scale_mode, ScaleMode::Fit);
         assert_eq!(manifest.assets.preload.len(), 0);
 
         fs::remove_dir_all(&temp_dir).unwrap();
@@ -1,14 +1,19 @@
 mod config;
 mod game;
 mod engine;
+mod resources;
+mod schedule;
+pub mod subsystems;
 
 pub use config::*;
 pub use game::*;
 pub use engine::*;
+pub use resources::*;
+pub use schedule::*;
 
 // Re-export commonly used types
 pub use longhorn_core::{World, Transform, Sprite, Name, Enabled, EntityHandle, Script, ScriptValue};
-pub use longhorn_renderer::{Camera, MainCamera, Color};
+pub use longhorn_renderer::{Camera, MainCamera, Color, ScaleMode, ViewportScaling};
 pub use longhorn_input::{InputState, TouchEvent};
 pub use longhorn_assets::AssetManager;
 pub use longhorn_events::{EventBus, Event, EventType, EventTarget, SubscriptionId};
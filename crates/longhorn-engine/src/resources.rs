@@ -0,0 +1,118 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// Builds a fresh value of a per-game resource
+type MakeResource = Box<dyn Fn() -> Box<dyn Any>>;
+
+/// State of the engine's subsystems, one value per type
+///
+/// Subsystems insert their state when they are installed (see
+/// `Engine::install`) and their systems reach it through
+/// `Engine::resource_scope`:
+///
+/// ```ignore
+/// engine.install(|schedule, resources| {
+///     resources.insert(Score::default());
+///     schedule.add_system(Stage::Update, "score", |engine| {
+///         engine.resource_scope(|engine, score: &mut Score| score.update(engine.world()));
+///         Ok(())
+///     });
+/// });
+/// ```
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, Box<dyn Any>>,
+    /// Constructors of the resources recreated when a game is loaded
+    per_game: Vec<(TypeId, MakeResource)>,
+}
+
+impl Resources {
+    /// Create an empty set of resources
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a resource, returning the one it replaces
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    /// Insert a resource that is recreated with `make` whenever a game is
+    /// loaded, for state tied to the game's assets like glyph atlases
+    pub fn insert_per_game<T: 'static>(&mut self, make: impl Fn() -> T + 'static) {
+        self.insert(make());
+        self.per_game.retain(|(id, _)| *id != TypeId::of::<T>());
+        self.per_game
+            .push((TypeId::of::<T>(), Box::new(move || Box::new(make()) as Box<dyn Any>)));
+    }
+
+    /// Get a resource
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref())
+    }
+
+    /// Get a resource mutably
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>()).and_then(|value| value.downcast_mut())
+    }
+
+    /// Remove a resource
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.per_game.retain(|(id, _)| *id != TypeId::of::<T>());
+        self.take()
+    }
+
+    /// Take a resource out to be put back with `insert`, keeping it
+    /// recreated with each game if it was
+    pub(crate) fn take<T: 'static>(&mut self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    /// Check if a resource is present
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Recreate the resources inserted with `insert_per_game`
+    pub(crate) fn reset_per_game(&mut self) {
+        for (id, make) in &self.per_game {
+            self.values.insert(*id, make());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let mut resources = Resources::new();
+        assert_eq!(resources.insert(1u32), None);
+        assert_eq!(resources.insert(2u32), Some(1));
+        *resources.get_mut::<u32>().unwrap() += 1;
+
+        assert_eq!(resources.get::<u32>(), Some(&3));
+        assert!(!resources.contains::<i32>());
+        assert_eq!(resources.remove::<u32>(), Some(3));
+        assert!(!resources.contains::<u32>());
+    }
+
+    #[test]
+    fn test_per_game_resources_are_recreated() {
+        let mut resources = Resources::new();
+        resources.insert_per_game(Vec::<u32>::new);
+        resources.insert(String::from("kept"));
+        resources.get_mut::<Vec<u32>>().unwrap().push(7);
+
+        resources.reset_per_game();
+        assert!(resources.get::<Vec<u32>>().unwrap().is_empty());
+        assert_eq!(resources.get::<String>().unwrap(), "kept");
+    }
+}
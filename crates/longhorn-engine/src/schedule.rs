@@ -0,0 +1,373 @@
+use crate::{Engine, EngineError};
+use std::collections::HashMap;
+
+/// Frame stages, run in declaration order by `Engine::update`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// Before gameplay (input handling, spawning)
+    PreUpdate,
+    /// Zero or more times per frame at the fixed timestep (physics)
+    FixedUpdate,
+    /// Gameplay (scripts run here)
+    Update,
+    /// After gameplay (transform propagation, change events)
+    PostUpdate,
+    /// Preparing render data (camera follow, culling)
+    PreRender,
+    /// Drawing the frame
+    Render,
+}
+
+impl Stage {
+    /// All stages in execution order
+    pub const ALL: [Stage; 6] = [
+        Stage::PreUpdate,
+        Stage::FixedUpdate,
+        Stage::Update,
+        Stage::PostUpdate,
+        Stage::PreRender,
+        Stage::Render,
+    ];
+}
+
+/// Names of the systems the engine registers by default
+pub mod systems {
+    /// Handles touches on UI elements and sends their events to scripts (PreUpdate)
+    pub const UI: &str = "ui";
+    /// Runs script lifecycle and forwards script events (Update)
+    pub const SCRIPTS: &str = "scripts";
+    /// Advances tweens and sends their completion events (Update)
+    pub const TWEENS: &str = "tweens";
+    /// Advances StateMachine components and runs their state actions (Update)
+    pub const STATE_MACHINES: &str = "state_machines";
+    /// Plays AnimationPlayer clips and sends their timeline events (Update)
+    pub const ANIMATIONS: &str = "animations";
+    /// Moves NavAgent components along their paths (Update)
+    pub const NAVIGATION: &str = "navigation";
+    /// Updates GlobalTransform from the hierarchy (PostUpdate)
+    pub const TRANSFORM_PROPAGATION: &str = "transform_propagation";
+    /// Forwards world change events to the event bus (PostUpdate)
+    pub const WORLD_CHANGES: &str = "world_changes";
+    /// Simulates ParticleEmitter components (PostUpdate)
+    pub const PARTICLES: &str = "particles";
+    /// Advances camera follow, bounds and shake (PreRender)
+    pub const CAMERAS: &str = "cameras";
+    /// Lays out Text components into glyphs (PreRender)
+    pub const TEXT: &str = "text";
+    /// Builds the geometry of changed Tilemap chunks (PreRender)
+    pub const TILEMAPS: &str = "tilemaps";
+    /// Renders the world (Render)
+    pub const RENDER: &str = "render";
+}
+
+/// A system run by the schedule
+pub type SystemFn = Box<dyn FnMut(&mut Engine) -> Result<(), EngineError>>;
+
+struct SystemEntry {
+    name: String,
+    before: Vec<String>,
+    after: Vec<String>,
+    run: SystemFn,
+}
+
+#[derive(Default)]
+struct StageSystems {
+    systems: Vec<SystemEntry>,
+    /// Cached execution order (indices into `systems`), rebuilt when constraints change
+    order: Option<Vec<usize>>,
+}
+
+impl StageSystems {
+    /// Resolve before/after constraints into an execution order
+    ///
+    /// Systems without constraints between them keep registration order.
+    /// Constraints naming systems that aren't in this stage are ignored.
+    fn order(&mut self, stage: Stage) -> Result<&[usize], ScheduleError> {
+        if self.order.is_none() {
+            let index: HashMap<&str, usize> = self
+                .systems
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (s.name.as_str(), i))
+                .collect();
+
+            let count = self.systems.len();
+            let mut edges: Vec<Vec<usize>> = vec![Vec::new(); count];
+            let mut in_degree = vec![0usize; count];
+            for (i, system) in self.systems.iter().enumerate() {
+                for other in system.before.iter().filter_map(|n| index.get(n.as_str())) {
+                    edges[i].push(*other);
+                    in_degree[*other] += 1;
+                }
+                for other in system.after.iter().filter_map(|n| index.get(n.as_str())) {
+                    edges[*other].push(i);
+                    in_degree[i] += 1;
+                }
+            }
+
+            // Kahn's algorithm, always picking the earliest registered ready system
+            let mut order = Vec::with_capacity(count);
+            let mut ready: Vec<usize> = (0..count).filter(|&i| in_degree[i] == 0).collect();
+            while let Some(position) = ready.iter().enumerate().min_by_key(|(_, &i)| i).map(|(p, _)| p) {
+                let next = ready.swap_remove(position);
+                order.push(next);
+                for &other in &edges[next] {
+                    in_degree[other] -= 1;
+                    if in_degree[other] == 0 {
+                        ready.push(other);
+                    }
+                }
+            }
+
+            if order.len() < count {
+                let systems = (0..count)
+                    .filter(|i| !order.contains(i))
+                    .map(|i| self.systems[i].name.clone())
+                    .collect();
+                return Err(ScheduleError::Cycle { stage, systems });
+            }
+            self.order = Some(order);
+        }
+
+        Ok(self.order.as_deref().unwrap_or_default())
+    }
+}
+
+/// Ordered set of systems grouped into stages
+///
+/// Crates and games register systems with `add_system` and constrain their
+/// order within a stage by name:
+///
+/// ```ignore
+/// engine
+///     .schedule_mut()
+///     .add_system(Stage::Update, "physics", |engine| { /* ... */ Ok(()) })
+///     .before(systems::SCRIPTS);
+/// ```
+#[derive(Default)]
+pub struct Schedule {
+    stages: HashMap<Stage, StageSystems>,
+    /// Names passed to `remove_system` while standing in for a running
+    /// schedule, removed from it by `merge`
+    removed: Option<Vec<String>>,
+}
+
+impl Schedule {
+    /// Create an empty schedule
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty schedule that stands in for one being run, recording
+    /// removals as well as additions for `merge`
+    pub(crate) fn detached() -> Self {
+        Self {
+            stages: HashMap::new(),
+            removed: Some(Vec::new()),
+        }
+    }
+
+    /// Add a system to a stage
+    ///
+    /// A system with the same name is replaced (in any stage).
+    pub fn add_system<F>(&mut self, stage: Stage, name: impl Into<String>, system: F) -> SystemConfig<'_>
+    where
+        F: FnMut(&mut Engine) -> Result<(), EngineError> + 'static,
+    {
+        let name = name.into();
+        if self.remove_system(&name) {
+            log::debug!("Replacing system '{}'", name);
+        }
+
+        let stage_systems = self.stages.entry(stage).or_default();
+        stage_systems.order = None;
+        stage_systems.systems.push(SystemEntry {
+            name,
+            before: Vec::new(),
+            after: Vec::new(),
+            run: Box::new(system),
+        });
+
+        let StageSystems { systems, order } = stage_systems;
+        SystemConfig {
+            entry: systems.last_mut().expect("system was just added"),
+            order,
+        }
+    }
+
+    /// Remove a system by name
+    ///
+    /// # Returns
+    /// `true` if a system was removed
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        if let Some(removed) = &mut self.removed {
+            removed.push(name.to_string());
+        }
+        for stage_systems in self.stages.values_mut() {
+            if let Some(index) = stage_systems.systems.iter().position(|s| s.name == name) {
+                stage_systems.systems.remove(index);
+                stage_systems.order = None;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Check if a system is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.stage_of(name).is_some()
+    }
+
+    /// Get the stage a system is registered in
+    pub fn stage_of(&self, name: &str) -> Option<Stage> {
+        self.stages
+            .iter()
+            .find(|(_, s)| s.systems.iter().any(|system| system.name == name))
+            .map(|(stage, _)| *stage)
+    }
+
+    /// Get the names of a stage's systems in execution order
+    pub fn system_names(&mut self, stage: Stage) -> Result<Vec<String>, ScheduleError> {
+        let Some(stage_systems) = self.stages.get_mut(&stage) else {
+            return Ok(Vec::new());
+        };
+        let order = stage_systems.order(stage)?.to_vec();
+        Ok(order.into_iter().map(|i| stage_systems.systems[i].name.clone()).collect())
+    }
+
+    /// Run every system in a stage once, in order
+    ///
+    /// Stops at the first system that returns an error.
+    pub fn run_stage(&mut self, stage: Stage, engine: &mut Engine) -> Result<(), EngineError> {
+        let Some(stage_systems) = self.stages.get_mut(&stage) else {
+            return Ok(());
+        };
+        let order = stage_systems.order(stage)?.to_vec();
+        for index in order {
+            (stage_systems.systems[index].run)(engine)?;
+        }
+        Ok(())
+    }
+
+    /// Remove the systems `other` removed while detached, then move all
+    /// systems from `other` into this schedule
+    pub(crate) fn merge(&mut self, other: Schedule) {
+        for name in other.removed.into_iter().flatten() {
+            self.remove_system(&name);
+        }
+        for (stage, stage_systems) in other.stages {
+            for entry in stage_systems.systems {
+                self.remove_system(&entry.name);
+                let target = self.stages.entry(stage).or_default();
+                target.order = None;
+                target.systems.push(entry);
+            }
+        }
+    }
+}
+
+/// Ordering constraints for a newly added system
+pub struct SystemConfig<'a> {
+    entry: &'a mut SystemEntry,
+    order: &'a mut Option<Vec<usize>>,
+}
+
+impl SystemConfig<'_> {
+    /// Run this system before the named system (same stage)
+    pub fn before(self, name: impl Into<String>) -> Self {
+        self.entry.before.push(name.into());
+        *self.order = None;
+        self
+    }
+
+    /// Run this system after the named system (same stage)
+    pub fn after(self, name: impl Into<String>) -> Self {
+        self.entry.after.push(name.into());
+        *self.order = None;
+        self
+    }
+}
+
+/// Schedule errors
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("System ordering cycle in {stage:?} stage between: {systems:?}")]
+    Cycle { stage: Stage, systems: Vec<String> },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn recording_system(log: &Rc<RefCell<Vec<&'static str>>>, name: &'static str) -> impl FnMut(&mut Engine) -> Result<(), EngineError> {
+        let log = log.clone();
+        move |_| {
+            log.borrow_mut().push(name);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registration_order_without_constraints() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(Stage::Update, "a", |_| Ok(()));
+        schedule.add_system(Stage::Update, "b", |_| Ok(()));
+        schedule.add_system(Stage::Update, "c", |_| Ok(()));
+
+        assert_eq!(schedule.system_names(Stage::Update).unwrap(), vec!["a", "b", "c"]);
+        assert!(schedule.system_names(Stage::Render).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_before_after_constraints() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(Stage::Update, "scripts", |_| Ok(()));
+        schedule.add_system(Stage::Update, "animation", |_| Ok(())).after("scripts");
+        schedule.add_system(Stage::Update, "physics", |_| Ok(())).before("scripts");
+        schedule.add_system(Stage::Update, "audio", |_| Ok(())).after("missing");
+
+        assert_eq!(
+            schedule.system_names(Stage::Update).unwrap(),
+            vec!["physics", "scripts", "animation", "audio"]
+        );
+    }
+
+    #[test]
+    fn test_cycle_is_reported() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(Stage::Update, "a", |_| Ok(())).after("b");
+        schedule.add_system(Stage::Update, "b", |_| Ok(())).after("a");
+
+        assert!(matches!(
+            schedule.system_names(Stage::Update),
+            Err(ScheduleError::Cycle { stage: Stage::Update, .. })
+        ));
+    }
+
+    #[test]
+    fn test_replace_and_remove() {
+        let mut schedule = Schedule::new();
+        schedule.add_system(Stage::Update, "a", |_| Ok(()));
+        schedule.add_system(Stage::PostUpdate, "a", |_| Ok(()));
+
+        assert_eq!(schedule.stage_of("a"), Some(Stage::PostUpdate));
+        assert!(schedule.remove_system("a"));
+        assert!(!schedule.contains("a"));
+        assert!(!schedule.remove_system("a"));
+    }
+
+    #[test]
+    fn test_run_stage() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new_headless();
+        let mut schedule = Schedule::new();
+        schedule.add_system(Stage::Update, "second", recording_system(&log, "second"));
+        schedule.add_system(Stage::Update, "first", recording_system(&log, "first")).before("second");
+        schedule.add_system(Stage::Render, "other", recording_system(&log, "other"));
+
+        schedule.run_stage(Stage::Update, &mut engine).unwrap();
+        assert_eq!(*log.borrow(), vec!["first", "second"]);
+    }
+}
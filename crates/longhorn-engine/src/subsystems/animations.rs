@@ -0,0 +1,59 @@
+//! Playback of AnimationPlayer components
+
+use crate::schedule::systems;
+use crate::{Engine, Resources, Schedule, Stage};
+use longhorn_core::{AnimationPlayer, AssetId};
+use std::collections::{HashMap, HashSet};
+
+/// Register the animation system, after state machines so the clips they
+/// switch to play the same frame
+pub fn install(schedule: &mut Schedule, _resources: &mut Resources) {
+    schedule
+        .add_system(Stage::Update, systems::ANIMATIONS, |engine| {
+            let dt = engine.time.delta();
+            update(engine, dt);
+            Ok(())
+        })
+        .after(systems::STATE_MACHINES);
+}
+
+/// Advance AnimationPlayer components by `dt` seconds
+///
+/// Clips are loaded by asset ID on first use. Each clip event playback
+/// passes sends an `AnimationEvent` event targeted at the player entity's
+/// GUID, with the entity, clip, event name, time and data. Hosts can call
+/// it directly to preview clips without running `Engine::update`.
+pub fn update(engine: &mut Engine, dt: f32) {
+    let clip_ids: HashSet<AssetId> = engine
+        .world
+        .query::<&AnimationPlayer>()
+        .iter()
+        .filter(|(_, player)| player.playing)
+        .map(|(_, player)| player.clip)
+        .collect();
+    let mut clips = HashMap::new();
+    for id in clip_ids {
+        match engine.assets.load_animation_clip_by_id(id) {
+            Ok(handle) => clips.extend(engine.assets.get_animation_clip(handle).map(|clip| (id, clip))),
+            Err(e) => log::debug!("Animation clip {:?} not loaded: {}", id, e),
+        }
+    }
+
+    let fired = longhorn_core::update_animations(&mut engine.world, dt, |id| clips.get(&id).cloned());
+    for fired in fired {
+        let Some(guid) = engine.world.guid(fired.entity) else {
+            continue;
+        };
+        engine.event_bus.emit_targeted(
+            longhorn_events::EventType::AnimationEvent,
+            longhorn_events::EventTarget::Entity(guid.get()),
+            serde_json::json!({
+                "entity": guid.get(),
+                "clip": fired.clip.0,
+                "name": fired.event.name,
+                "time": fired.event.time,
+                "data": fired.event.data,
+            }),
+        );
+    }
+}
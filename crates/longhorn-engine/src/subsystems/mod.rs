@@ -0,0 +1,28 @@
+//! Built-in subsystems
+//!
+//! Each module installs its state and systems on the engine (see
+//! `Engine::install`) and holds the functions hosts call to run it outside
+//! of `Engine::update`.
+
+pub mod animations;
+pub mod navigation;
+pub mod particles;
+pub mod state_machines;
+pub mod text;
+pub mod tilemaps;
+pub mod tweens;
+pub mod ui;
+
+use crate::{Resources, Schedule};
+
+/// Install every built-in subsystem
+pub(crate) fn install(schedule: &mut Schedule, resources: &mut Resources) {
+    ui::install(schedule, resources);
+    tweens::install(schedule, resources);
+    state_machines::install(schedule, resources);
+    animations::install(schedule, resources);
+    navigation::install(schedule, resources);
+    particles::install(schedule, resources);
+    text::install(schedule, resources);
+    tilemaps::install(schedule, resources);
+}
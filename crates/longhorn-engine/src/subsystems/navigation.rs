@@ -0,0 +1,209 @@
+//! Pathfinding of NavAgent components over the enabled NavRegion
+
+use crate::schedule::systems;
+use crate::{Engine, EngineError, Resources, Schedule, Stage};
+use longhorn_core::{
+    AssetId, Enabled, EntityHandle, GlobalTransform, NavAgent, NavGrid, NavMesh, NavRegion, NavSurface, Navigation,
+    NavigationEvent, Tilemap, Transform,
+};
+use longhorn_renderer::{Color, DebugDraw};
+
+/// The NavRegion the navigation surface was built from, to rebuild it when
+/// the region, its navmesh or its tilemap changes
+#[derive(Debug, Clone, PartialEq)]
+struct NavRegionSource {
+    entity: EntityHandle,
+    navmesh: Option<AssetId>,
+    position: glam::Vec2,
+    /// Revisions of the tilemap's chunks
+    tiles: Vec<u64>,
+}
+
+/// What the navigation surface was built from (none if it wasn't built
+/// from a NavRegion)
+#[derive(Default)]
+struct BuiltFrom(Option<NavRegionSource>);
+
+/// Register the `Navigation` resource and the agent system, after scripts
+/// so destinations they set are searched the same frame
+pub fn install(schedule: &mut Schedule, resources: &mut Resources) {
+    resources.insert(Navigation::new());
+    resources.insert(BuiltFrom::default());
+    schedule
+        .add_system(Stage::Update, systems::NAVIGATION, |engine| {
+            let dt = engine.time.delta();
+            update(engine, dt);
+            Ok(())
+        })
+        .after(systems::SCRIPTS);
+}
+
+/// Move NavAgent components along their paths by `dt` seconds
+///
+/// Builds the navigation surface from the enabled NavRegion first (see
+/// `sync`). Each agent that arrives sends a `DestinationReached` event, and
+/// each that finds no path a `PathNotFound` event, targeted at the agent
+/// entity's GUID with the entity and destination.
+pub fn update(engine: &mut Engine, dt: f32) {
+    sync(engine);
+    let Some(navigation) = engine.resources.get_mut::<Navigation>() else {
+        return;
+    };
+    for event in longhorn_core::update_navigation(&mut engine.world, navigation, dt) {
+        let (event_type, entity, destination) = match event {
+            NavigationEvent::Arrived { entity, destination } => {
+                (longhorn_events::EventType::DestinationReached, entity, destination)
+            }
+            NavigationEvent::Unreachable { entity, destination } => {
+                (longhorn_events::EventType::PathNotFound, entity, destination)
+            }
+        };
+        let Some(guid) = engine.world.guid(entity) else {
+            continue;
+        };
+        engine.event_bus.emit_targeted(
+            event_type,
+            longhorn_events::EventTarget::Entity(guid.get()),
+            serde_json::json!({ "entity": guid.get(), "destination": [destination.x, destination.y] }),
+        );
+    }
+}
+
+/// Build the navigation surface from the enabled NavRegion, if it changed
+/// since the last call
+///
+/// Regions with a navmesh use it; others build a grid from the solid
+/// tiles of their entity's Tilemap. Without a region, a surface set on the
+/// `Navigation` resource is left alone.
+pub fn sync(engine: &mut Engine) {
+    let source = engine
+        .world
+        .query::<(&NavRegion, Option<&Tilemap>, Option<&GlobalTransform>, Option<&Transform>, Option<&Enabled>)>()
+        .iter()
+        .find(|(_, (.., enabled))| enabled.is_none_or(|enabled| enabled.is_enabled()))
+        .map(|(entity, (region, tilemap, global, local, _))| NavRegionSource {
+            entity: EntityHandle::new(entity),
+            navmesh: region.navmesh,
+            position: global
+                .map(|global| global.position)
+                .or(local.map(|local| local.position))
+                .unwrap_or_default(),
+            tiles: tilemap
+                .into_iter()
+                .flat_map(|tilemap| &tilemap.layers)
+                .flat_map(|layer| layer.chunks().map(|(_, chunk)| chunk.revision()))
+                .collect(),
+        });
+    if engine.resources.get::<BuiltFrom>().is_some_and(|built| built.0 == source) {
+        return;
+    }
+
+    let surface = source.as_ref().and_then(|source| match source.navmesh {
+        Some(id) => match engine.assets.load_navmesh_by_id(id) {
+            Ok(handle) => engine.assets.get_navmesh(handle).map(NavSurface::Mesh),
+            Err(e) => {
+                log::warn!("Navmesh {:?} not loaded: {}", id, e);
+                None
+            }
+        },
+        None => tilemap_nav_grid(engine, source.entity, source.position).map(NavSurface::Grid),
+    });
+    if let Some(navigation) = engine.resources.get_mut::<Navigation>() {
+        navigation.set_surface(surface);
+    }
+    engine.resources.insert(BuiltFrom(source));
+}
+
+/// Navigation grid of a tilemap entity at `position`, blocking its solid tiles
+fn tilemap_nav_grid(engine: &mut Engine, entity: EntityHandle, position: glam::Vec2) -> Option<NavGrid> {
+    let tileset_id = engine.world.get::<Tilemap>(entity).ok()?.tileset;
+    let tileset = match engine.assets.load_tileset_by_id(tileset_id) {
+        Ok(handle) => engine.assets.get_tileset(handle)?,
+        Err(e) => {
+            log::warn!("Tile set {:?} not loaded: {}", tileset_id, e);
+            return None;
+        }
+    };
+    let tilemap = engine.world.get::<Tilemap>(entity).ok()?;
+    Some(NavGrid::from_tilemap(&tilemap, &tileset, position))
+}
+
+/// Bake the walkable area of a NavRegion's Tilemap into a navmesh saved
+/// at `path`, and point the region at it
+///
+/// # Returns
+/// The AssetId of the navmesh
+pub fn bake_navmesh(engine: &mut Engine, entity: EntityHandle, path: &str) -> Result<AssetId, EngineError> {
+    let position = engine
+        .world
+        .get::<GlobalTransform>(entity)
+        .map(|global| global.position)
+        .or_else(|_| engine.world.get::<Transform>(entity).map(|local| local.position))
+        .unwrap_or_default();
+    let grid = tilemap_nav_grid(engine, entity, position).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "Navigation regions bake from their Tilemap and tile set")
+    })?;
+    let id = engine.assets.save_navmesh(path, &NavMesh::from_grid(&grid))?;
+    engine.world.set(entity, NavRegion::from_navmesh(id))?;
+    // Re-baking keeps the id, so the surface is rebuilt on the next sync
+    engine.resources.insert(BuiltFrom::default());
+    Ok(id)
+}
+
+/// Draw the navigation surface and the paths of NavAgents
+///
+/// Grids show their bounds, blocked cells and cells covered by
+/// obstacles; meshes show their polygons. Hosts draw into their own
+/// queue, like the editor's scene view, or into `Engine::debug_draw_mut`.
+pub fn draw(engine: &mut Engine, debug: &mut DebugDraw) {
+    sync(engine);
+    let Some(navigation) = engine.resources.get_mut::<Navigation>() else {
+        return;
+    };
+    navigation.update_obstacles(&engine.world);
+    match navigation.surface() {
+        Some(NavSurface::Grid(grid)) => {
+            let bounds = grid.bounds();
+            debug.rect(bounds.center(), bounds.size(), Color::new(0.5, 0.5, 0.5, 0.6));
+            for y in 0..grid.height() {
+                for x in 0..grid.width() {
+                    let color = if grid.is_blocked(x, y) {
+                        Color::new(1.0, 0.25, 0.25, 0.6)
+                    } else if grid.is_obstructed(x, y) {
+                        Color::new(1.0, 0.6, 0.1, 0.8)
+                    } else {
+                        continue;
+                    };
+                    let cell = grid.cell_rect(x, y);
+                    debug.rect(cell.center(), cell.size() * 0.9, color);
+                }
+            }
+        }
+        Some(NavSurface::Mesh(mesh)) => {
+            for polygon in mesh.polygons() {
+                for (a, b) in polygon.edges() {
+                    debug.line(a, b, Color::new(0.2, 0.8, 1.0, 0.8));
+                }
+            }
+        }
+        None => {}
+    }
+
+    let path_color = Color::new(1.0, 0.9, 0.2, 1.0);
+    for (_, (agent, global, local)) in engine
+        .world
+        .query::<(&NavAgent, Option<&GlobalTransform>, Option<&Transform>)>()
+        .iter()
+    {
+        let Some(mut from) = global.map(|global| global.position).or(local.map(|local| local.position)) else {
+            continue;
+        };
+        for &to in &agent.path {
+            debug.line(from, to, path_color);
+            from = to;
+        }
+        if let Some(destination) = agent.destination {
+            debug.circle(destination, agent.stopping_distance.max(4.0), path_color);
+        }
+    }
+}
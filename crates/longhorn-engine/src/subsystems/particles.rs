@@ -0,0 +1,23 @@
+//! Simulation of ParticleEmitter components
+
+use crate::schedule::systems;
+use crate::{Engine, Resources, Schedule, Stage};
+
+/// Register the particle system, after transform propagation
+pub fn install(schedule: &mut Schedule, _resources: &mut Resources) {
+    schedule
+        .add_system(Stage::PostUpdate, systems::PARTICLES, |engine| {
+            let dt = engine.time.delta();
+            update(engine, dt);
+            Ok(())
+        })
+        .after(systems::TRANSFORM_PROPAGATION);
+}
+
+/// Advance ParticleEmitter components by `dt` seconds
+///
+/// Runs in the PostUpdate stage; hosts can call it directly to preview
+/// effects without running `update`.
+pub fn update(engine: &mut Engine, dt: f32) {
+    longhorn_core::update_particles(&mut engine.world, dt);
+}
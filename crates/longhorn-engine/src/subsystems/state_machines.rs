@@ -0,0 +1,104 @@
+//! Playback of StateMachine components
+
+use crate::schedule::systems;
+use crate::{Engine, EngineError, Resources, Schedule, Stage};
+use longhorn_core::{AnimationPlayer, AssetId, EntityHandle, StateMachine, StateMachineEvent};
+use std::collections::{HashMap, HashSet};
+
+/// Register the state machine system, after scripts so events they send
+/// are handled the same frame
+pub fn install(schedule: &mut Schedule, _resources: &mut Resources) {
+    schedule
+        .add_system(Stage::Update, systems::STATE_MACHINES, |engine| {
+            let dt = engine.time.delta();
+            update(engine, dt)
+        })
+        .after(systems::SCRIPTS);
+}
+
+/// Advance StateMachine components by `dt` seconds
+///
+/// Charts are loaded by asset ID on first use. Each state entered or
+/// exited sends a `StateEntered` or `StateExited` event targeted at the
+/// machine entity's GUID, with the entity and state name, and calls
+/// `onStateEnter(self, state)` or `onStateExit(self, state)` on the
+/// entity's scripts. `emit` actions send targeted custom events and
+/// `play_animation` actions crossfade the entity's AnimationPlayer to the
+/// clip, adding a player if it has none.
+pub fn update(engine: &mut Engine, dt: f32) -> Result<(), EngineError> {
+    let chart_ids: HashSet<AssetId> = engine
+        .world
+        .query::<&StateMachine>()
+        .iter()
+        .map(|(_, machine)| machine.chart)
+        .collect();
+    let mut charts = HashMap::new();
+    for id in chart_ids {
+        match engine.assets.load_state_chart_by_id(id) {
+            Ok(handle) => charts.extend(engine.assets.get_state_chart(handle).map(|chart| (id, chart))),
+            Err(e) => log::debug!("State chart {:?} not loaded: {}", id, e),
+        }
+    }
+
+    let events = longhorn_core::update_state_machines(&mut engine.world, dt, |id| charts.get(&id).cloned());
+    for event in events {
+        match event {
+            StateMachineEvent::Entered { entity, state } => {
+                send_state_change(engine, entity, state, longhorn_events::EventType::StateEntered, "onStateEnter")?;
+            }
+            StateMachineEvent::Exited { entity, state } => {
+                send_state_change(engine, entity, state, longhorn_events::EventType::StateExited, "onStateExit")?;
+            }
+            StateMachineEvent::Emit { entity, event, data } => {
+                let Some(guid) = engine.world.guid(entity) else {
+                    continue;
+                };
+                engine.event_bus.emit_targeted(
+                    longhorn_events::EventType::Custom(event),
+                    longhorn_events::EventTarget::Entity(guid.get()),
+                    data,
+                );
+            }
+            StateMachineEvent::PlayAnimation { entity, clip, crossfade } => {
+                let clip = match engine.assets.load_animation_clip(&clip) {
+                    Ok(handle) => handle.id(),
+                    Err(e) => {
+                        log::warn!("State machine animation {} not loaded: {}", clip, e);
+                        continue;
+                    }
+                };
+                if let Ok(mut player) = engine.world.get_mut::<AnimationPlayer>(entity) {
+                    player.crossfade(clip, crossfade);
+                    continue;
+                }
+                if let Err(e) = engine.world.set(entity, AnimationPlayer::new(clip)) {
+                    log::warn!("Failed to add AnimationPlayer: {}", e);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Send a state machine's state change to the event bus and the entity's scripts
+fn send_state_change(
+    engine: &mut Engine,
+    entity: EntityHandle,
+    state: String,
+    event_type: longhorn_events::EventType,
+    method: &str,
+) -> Result<(), EngineError> {
+    let Some(guid) = engine.world.guid(entity) else {
+        return Ok(());
+    };
+    let arg = serde_json::Value::from(state.as_str()).to_string();
+    engine.event_bus.emit_targeted(
+        event_type,
+        longhorn_events::EventTarget::Entity(guid.get()),
+        serde_json::json!({ "entity": guid.get(), "state": state }),
+    );
+    if engine.scripting.is_initialized() {
+        engine.scripting.call_entity(&mut engine.world, guid.get(), method, &arg)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+//! Layout of Text components into glyphs
+
+use crate::schedule::systems;
+use crate::{Engine, Resources, Schedule, Stage};
+use longhorn_renderer::TextSystem;
+
+/// Register the glyph atlases and the PreRender layout system
+///
+/// The atlases live in the asset manager, so they start over with each game.
+pub fn install(schedule: &mut Schedule, resources: &mut Resources) {
+    resources.insert_per_game(TextSystem::new);
+    schedule.add_system(Stage::PreRender, systems::TEXT, |engine| {
+        update(engine);
+        Ok(())
+    });
+}
+
+/// Lay out Text components that changed since the last call
+///
+/// Runs in the PreRender stage; hosts that render without running
+/// `update` (e.g. the editor outside of play mode) can call it directly.
+pub fn update(engine: &mut Engine) {
+    if let Some(text) = engine.resources.get_mut::<TextSystem>() {
+        text.update(&mut engine.world, &mut engine.assets);
+    }
+}
@@ -0,0 +1,27 @@
+//! Chunk geometry of Tilemap components
+
+use crate::schedule::systems;
+use crate::{Engine, Resources, Schedule, Stage};
+use longhorn_renderer::TilemapSystem;
+
+/// Register the tile set cache and the PreRender geometry system
+///
+/// Tile sets are loaded through the asset manager, so the cache starts over
+/// with each game.
+pub fn install(schedule: &mut Schedule, resources: &mut Resources) {
+    resources.insert_per_game(TilemapSystem::new);
+    schedule.add_system(Stage::PreRender, systems::TILEMAPS, |engine| {
+        update(engine);
+        Ok(())
+    });
+}
+
+/// Rebuild the geometry of Tilemap chunks that changed since the last call
+///
+/// Runs in the PreRender stage; like `text::update`, hosts can call it
+/// directly.
+pub fn update(engine: &mut Engine) {
+    if let Some(tilemaps) = engine.resources.get_mut::<TilemapSystem>() {
+        tilemaps.update(&mut engine.world, &mut engine.assets);
+    }
+}
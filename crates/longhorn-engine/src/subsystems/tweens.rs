@@ -0,0 +1,136 @@
+//! Playback of Tweens components and the tweens started by scripts
+
+use crate::schedule::systems;
+use crate::{Engine, Resources, Schedule, Stage};
+use longhorn_core::{Ease, EntityGuid, Tween, TweenLoops, TweenProperty, Tweens};
+use longhorn_scripting::{JsTween, JsTweenCommand, JsTweenKind};
+
+/// Register the tween system, after scripts so tweens they start play the
+/// same frame
+pub fn install(schedule: &mut Schedule, _resources: &mut Resources) {
+    schedule
+        .add_system(Stage::Update, systems::TWEENS, |engine| {
+            apply_script_commands(engine);
+            let dt = engine.time.delta();
+            update(engine, dt);
+            Ok(())
+        })
+        .after(systems::SCRIPTS);
+}
+
+/// Start and cancel tweens requested by scripts
+fn apply_script_commands(engine: &mut Engine) {
+    for command in longhorn_scripting::take_pending_tweens() {
+        match command {
+            JsTweenCommand::Play { entity, tween: js } => match engine.world.entity_by_guid(EntityGuid(entity)) {
+                Some(handle) => {
+                    longhorn_core::play_tween(&mut engine.world, handle, tween(js));
+                }
+                None => log::warn!("Tween target {} not found", entity),
+            },
+            JsTweenCommand::Cancel { entity } => {
+                if let Some(mut tweens) = engine
+                    .world
+                    .entity_by_guid(EntityGuid(entity))
+                    .and_then(|handle| engine.world.get_mut::<Tweens>(handle).ok())
+                {
+                    tweens.clear();
+                }
+            }
+        }
+    }
+}
+
+/// Convert a script tween, skipping unknown fields and easing linearly
+/// with unknown easing functions
+fn tween(js: JsTween) -> Tween {
+    let tween = match js.kind {
+        JsTweenKind::To { values, duration, ease } => {
+            let targets = values.into_iter().filter_map(|(name, value)| {
+                let property = TweenProperty::from_name(&name);
+                if property.is_none() {
+                    log::warn!("Tween of unknown field '{}'", name);
+                }
+                property.map(|property| (property, value as f32))
+            });
+            let ease = Ease::from_name(&ease).unwrap_or_else(|| {
+                log::warn!("Unknown easing function '{}'", ease);
+                Ease::Linear
+            });
+            Tween::to(targets.collect::<Vec<_>>(), duration as f32, ease)
+        }
+        JsTweenKind::Sequence { steps } => Tween::sequence(steps.into_iter().map(tween).collect()),
+        JsTweenKind::Parallel { steps } => Tween::parallel(steps.into_iter().map(tween).collect()),
+    };
+    let loops = match js.loops {
+        0 => TweenLoops::Forever,
+        count => TweenLoops::Count(count),
+    };
+    let tween = tween.with_delay(js.delay as f32).with_loops(loops).with_yoyo(js.yoyo);
+    match js.name {
+        Some(name) => tween.with_name(name),
+        None => tween,
+    }
+}
+
+/// Advance tweens by `dt` seconds
+///
+/// Each tween that completes sends a `TweenCompleted` event targeted at
+/// its entity's GUID, with the entity, tween ID and name as data.
+pub fn update(engine: &mut Engine, dt: f32) {
+    for completed in longhorn_core::update_tweens(&mut engine.world, dt) {
+        let Some(guid) = engine.world.guid(completed.entity) else {
+            continue;
+        };
+        engine.event_bus.emit_targeted(
+            longhorn_events::EventType::TweenCompleted,
+            longhorn_events::EventTarget::Entity(guid.get()),
+            serde_json::json!({
+                "entity": guid.get(),
+                "tween": completed.id.0,
+                "name": completed.name,
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::Transform;
+    use longhorn_events::{EventTarget, EventType};
+
+    #[test]
+    fn test_tweens_send_completion_events() {
+        let mut engine = Engine::new_headless();
+        let entity = engine.spawn_entity("Mover");
+        let guid = engine.world().guid(entity).unwrap().get();
+        engine.world_mut().set(entity, Transform::new()).unwrap();
+
+        // Script tweens skip unknown fields and ease linearly with unknown easing
+        let js: JsTween = serde_json::from_value(serde_json::json!({
+            "kind": "to",
+            "values": [["position.x", 8.0], ["wobble", 1.0]],
+            "duration": 0.5,
+            "ease": "easeSideways",
+            "name": "slide",
+        }))
+        .unwrap();
+        let id = longhorn_core::play_tween(engine.world_mut(), entity, tween(js)).unwrap();
+
+        update(&mut engine, 0.25);
+        assert_eq!(engine.world().get::<Transform>(entity).unwrap().position.x, 4.0);
+        let completed = |engine: &mut Engine| {
+            let events = engine.event_bus_mut().process();
+            events.into_iter().filter(|e| e.event_type == EventType::TweenCompleted).collect::<Vec<_>>()
+        };
+        assert!(completed(&mut engine).is_empty());
+
+        update(&mut engine, 0.25);
+        assert_eq!(engine.world().get::<Transform>(entity).unwrap().position.x, 8.0);
+        let events = completed(&mut engine);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].target, EventTarget::Entity(guid));
+        assert_eq!(events[0].data, serde_json::json!({ "entity": guid, "tween": id.0, "name": "slide" }));
+    }
+}
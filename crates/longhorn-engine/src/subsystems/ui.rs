@@ -0,0 +1,64 @@
+//! Touch handling and drawing of UI canvases
+
+use crate::schedule::systems;
+use crate::{Engine, EngineError, Resources, Schedule, Stage};
+use longhorn_renderer::TextSystem;
+use longhorn_ui::{UiEventKind, UiSystem, UiView};
+
+/// Register the UI state and its PreUpdate touch system
+///
+/// Labels are laid out with the text subsystem's glyph atlases, which are
+/// added too if the text subsystem isn't installed.
+pub fn install(schedule: &mut Schedule, resources: &mut Resources) {
+    resources.insert_per_game(UiSystem::new);
+    if !resources.contains::<TextSystem>() {
+        resources.insert_per_game(TextSystem::new);
+    }
+    schedule.add_system(Stage::PreUpdate, systems::UI, update);
+}
+
+/// Handle touches on UI elements and call the scripts' `onClick` and
+/// `onValueChanged` handlers along each event's path
+///
+/// Hosts that run their own frame loop can call it directly, before
+/// the touches are cleared.
+pub fn update(engine: &mut Engine) -> Result<(), EngineError> {
+    let Some(ui) = engine.resources.get_mut::<UiSystem>() else {
+        return Ok(());
+    };
+    ui.update(&mut engine.world, &engine.input, &engine.scaling);
+    if !engine.scripting.is_initialized() {
+        return Ok(());
+    }
+
+    for event in ui.events() {
+        let guid = |entity| engine.world.guid(entity).map(|guid| guid.get());
+        let path: Vec<u64> = event.path.iter().filter_map(|&entity| guid(entity)).collect();
+        let mut data = serde_json::json!({
+            "type": event.kind.name(),
+            "target": guid(event.target),
+            "position": { "x": event.position.x, "y": event.position.y },
+        });
+        if let UiEventKind::ValueChanged(value) = event.kind {
+            data["value"] = value.into();
+        }
+        engine
+            .scripting
+            .dispatch_event(&mut engine.world, &path, event.kind.handler(), &data.to_string())?;
+    }
+    Ok(())
+}
+
+/// Lay out the UI canvases into views drawn over every camera
+pub(crate) fn views(engine: &mut Engine) -> Vec<UiView> {
+    let Some(mut ui) = engine.resources.take::<UiSystem>() else {
+        return Vec::new();
+    };
+    let views = engine
+        .resources
+        .get_mut::<TextSystem>()
+        .map(|text| ui.collect(&engine.world, text, &mut engine.assets, &engine.scaling))
+        .unwrap_or_default();
+    engine.resources.insert(ui);
+    views
+}
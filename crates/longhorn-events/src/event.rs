@@ -30,6 +30,20 @@ pub enum EventType {
     EntityDespawned,
     ComponentAdded,
     ComponentChanged,
+    ComponentRemoved,
+
+    // Animation events
+    TweenCompleted,
+    /// Playback passed an event on an animation clip's timeline
+    AnimationEvent,
+
+    // State machine events
+    StateEntered,
+    StateExited,
+
+    // Navigation events
+    DestinationReached,
+    PathNotFound,
 
     // Custom script event (name stored in event data)
     Custom(String),
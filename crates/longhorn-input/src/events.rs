@@ -18,6 +18,16 @@ impl TouchEvent {
         }
     }
 
+    /// The same event at another position (e.g. mapped from screen to game space)
+    pub fn with_position(&self, position: Vec2) -> Self {
+        let (x, y) = (position.x, position.y);
+        match self {
+            TouchEvent::Start { .. } => TouchEvent::Start { x, y },
+            TouchEvent::Move { .. } => TouchEvent::Move { x, y },
+            TouchEvent::End { .. } => TouchEvent::End { x, y },
+        }
+    }
+
     pub fn is_start(&self) -> bool {
         matches!(self, TouchEvent::Start { .. })
     }
@@ -0,0 +1,180 @@
+use longhorn_assets::TextureData;
+
+/// Size of each atlas page in pixels
+pub const ATLAS_PAGE_SIZE: u32 = 2048;
+
+/// Largest texture dimension packed into the atlas; bigger textures get
+/// their own GPU texture
+pub const MAX_ATLAS_ENTRY_SIZE: u32 = 512;
+
+/// Border added around each packed texture, filled with its edge pixels so
+/// nearest sampling at region edges never reads a neighbour
+const GUTTER: u32 = 1;
+
+/// Pixel rectangle of a texture inside an atlas page (without the gutter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRegion {
+    /// UV rect (u0, v0, u1, v1) of the region in a page of the given size
+    pub fn uv_rect(&self, page_size: u32) -> [f32; 4] {
+        let size = page_size as f32;
+        [
+            self.x as f32 / size,
+            self.y as f32 / size,
+            (self.x + self.width) as f32 / size,
+            (self.y + self.height) as f32 / size,
+        ]
+    }
+}
+
+/// Map a UV rect relative to a whole texture into an atlas region's UV rect
+///
+/// Flipped rects (u0 > u1) stay flipped.
+pub fn remap_uv_rect(uv_rect: [f32; 4], region_uv: [f32; 4]) -> [f32; 4] {
+    let [ru0, rv0, ru1, rv1] = region_uv;
+    let u = |t: f32| ru0 + (ru1 - ru0) * t;
+    let v = |t: f32| rv0 + (rv1 - rv0) * t;
+    [u(uv_rect[0]), v(uv_rect[1]), u(uv_rect[2]), v(uv_rect[3])]
+}
+
+/// Shelf packer for one atlas page
+///
+/// Textures are placed left to right on horizontal shelves; a new shelf
+/// starts below the tallest entry of the current one when a row is full.
+#[derive(Debug, Clone)]
+pub struct AtlasPacker {
+    size: u32,
+    shelf_y: u32,
+    shelf_height: u32,
+    cursor_x: u32,
+}
+
+impl AtlasPacker {
+    /// Create a packer for a square page
+    pub fn new(size: u32) -> Self {
+        Self {
+            size,
+            shelf_y: 0,
+            shelf_height: 0,
+            cursor_x: 0,
+        }
+    }
+
+    /// Page size in pixels
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Reserve space for a texture, returning its region (excluding gutter)
+    ///
+    /// # Returns
+    /// `None` if the page is full
+    pub fn allocate(&mut self, width: u32, height: u32) -> Option<AtlasRegion> {
+        let padded_width = width + GUTTER * 2;
+        let padded_height = height + GUTTER * 2;
+        if padded_width > self.size || padded_height > self.size {
+            return None;
+        }
+
+        if self.cursor_x + padded_width > self.size {
+            self.shelf_y += self.shelf_height;
+            self.shelf_height = 0;
+            self.cursor_x = 0;
+        }
+        if self.shelf_y + padded_height > self.size {
+            return None;
+        }
+
+        let region = AtlasRegion {
+            x: self.cursor_x + GUTTER,
+            y: self.shelf_y + GUTTER,
+            width,
+            height,
+        };
+        self.cursor_x += padded_width;
+        self.shelf_height = self.shelf_height.max(padded_height);
+        Some(region)
+    }
+}
+
+/// Check if a texture is small enough to be packed into the atlas
+pub fn fits_in_atlas(texture: &TextureData) -> bool {
+    texture.width > 0
+        && texture.height > 0
+        && texture.width <= MAX_ATLAS_ENTRY_SIZE
+        && texture.height <= MAX_ATLAS_ENTRY_SIZE
+}
+
+/// Copy texture pixels into a buffer with a gutter of repeated edge pixels
+///
+/// # Returns
+/// RGBA pixels of size `(width + 2) x (height + 2)`
+pub fn pad_with_gutter(texture: &TextureData) -> Vec<u8> {
+    let width = texture.width as usize;
+    let height = texture.height as usize;
+    let padded_width = width + GUTTER as usize * 2;
+    let padded_height = height + GUTTER as usize * 2;
+    let mut padded = vec![0u8; padded_width * padded_height * 4];
+
+    for y in 0..padded_height {
+        let source_y = y.saturating_sub(GUTTER as usize).min(height - 1);
+        for x in 0..padded_width {
+            let source_x = x.saturating_sub(GUTTER as usize).min(width - 1);
+            let source = (source_y * width + source_x) * 4;
+            let target = (y * padded_width + x) * 4;
+            padded[target..target + 4].copy_from_slice(&texture.pixels[source..source + 4]);
+        }
+    }
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packer_fills_shelves() {
+        let mut packer = AtlasPacker::new(16);
+
+        let a = packer.allocate(6, 4).unwrap();
+        let b = packer.allocate(6, 2).unwrap();
+        assert_eq!((a.x, a.y), (1, 1));
+        assert_eq!((b.x, b.y), (9, 1));
+
+        // Doesn't fit on the first shelf (16 - 16 = 0 left), starts a new one below a
+        let c = packer.allocate(4, 4).unwrap();
+        assert_eq!((c.x, c.y), (1, 7));
+
+        assert!(packer.allocate(20, 1).is_none());
+        assert!(packer.allocate(14, 10).is_none());
+    }
+
+    #[test]
+    fn test_remap_uv_rect_keeps_flip() {
+        let region = AtlasRegion { x: 2, y: 4, width: 4, height: 2 }.uv_rect(8);
+        assert_eq!(region, [0.25, 0.5, 0.75, 0.75]);
+
+        assert_eq!(remap_uv_rect([0.0, 0.0, 1.0, 1.0], region), region);
+        assert_eq!(remap_uv_rect([1.0, 0.0, 0.0, 1.0], region), [0.75, 0.5, 0.25, 0.75]);
+    }
+
+    #[test]
+    fn test_pad_with_gutter() {
+        let texture = TextureData {
+            width: 2,
+            height: 1,
+            pixels: vec![1, 1, 1, 1, 2, 2, 2, 2],
+        };
+        let padded = pad_with_gutter(&texture);
+
+        // 4x3 padded image; every row is [1, 1, 2, 2]
+        let reds: Vec<u8> = padded.chunks_exact(4).map(|p| p[0]).collect();
+        assert_eq!(reds, vec![1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2]);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{Camera, Color, DebugDraw, FrameBuffer, RenderStats, RendererError, ScreenRect, SpriteBatch};
+use longhorn_assets::{AssetHandle, AssetManager, AssetSource, TextureData, TextureImportSettings};
+use longhorn_core::{AssetId, Material, World};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Read access to decoded textures (and materials) by asset ID
+///
+/// Render backends take textures through this trait so they can be driven
+/// by an `AssetManager` or, in tests, a plain map.
+pub trait TextureLookup {
+    /// Get the texture for an asset ID, if it is loaded
+    fn texture(&self, id: AssetId) -> Option<&TextureData>;
+
+    /// Get the import settings for an asset ID (defaults if unknown)
+    fn texture_settings(&self, _id: AssetId) -> TextureImportSettings {
+        TextureImportSettings::default()
+    }
+
+    /// Version of a texture's pixels; backends holding an uploaded copy
+    /// upload it again when this changes
+    fn texture_version(&self, _id: AssetId) -> u64 {
+        0
+    }
+
+    /// Get a loaded material; sprites whose material isn't available draw
+    /// with the built-in sprite shader
+    fn material(&self, _id: AssetId) -> Option<Arc<Material>> {
+        None
+    }
+}
+
+impl<S: AssetSource> TextureLookup for AssetManager<S> {
+    fn texture(&self, id: AssetId) -> Option<&TextureData> {
+        self.get_texture(AssetHandle::new(id))
+    }
+
+    fn texture_settings(&self, id: AssetId) -> TextureImportSettings {
+        AssetManager::texture_settings(self, id)
+    }
+
+    fn texture_version(&self, id: AssetId) -> u64 {
+        AssetManager::texture_version(self, id)
+    }
+
+    fn material(&self, id: AssetId) -> Option<Arc<Material>> {
+        self.get_material(AssetHandle::new(id))
+    }
+}
+
+impl TextureLookup for HashMap<AssetId, TextureData> {
+    fn texture(&self, id: AssetId) -> Option<&TextureData> {
+        self.get(&id)
+    }
+}
+
+/// One camera's sprites and the screen area they render to
+#[derive(Clone, Copy)]
+pub struct RenderView<'a> {
+    /// Camera to render from; its `viewport_size` should match `viewport`
+    pub camera: &'a Camera,
+    /// Collected, sorted sprites
+    pub sprites: &'a SpriteBatch,
+    /// Pixel area of the render target; ignored for cameras with a
+    /// render-texture target, which always fill the texture
+    pub viewport: ScreenRect,
+    /// Debug shapes drawn over the finished view
+    pub debug: Option<&'a DebugDraw>,
+}
+
+/// A sprite renderer the engine can drive
+///
+/// Implemented by the wgpu `Renderer` and the CPU `SoftwareRenderer`. Both
+/// follow the same sprite semantics: sprites are collected with
+/// `SpriteBatch::collect_visible` (or a `SpriteIndex`), ordered with
+/// `SpriteBatch::sort`, turned into
+/// quads with the `SpriteBatch::generate_vertices` layout, sampled nearest/clamped,
+/// tinted by the sprite color and alpha blended over the clear color.
+/// Sprite materials are custom WGSL, so only the wgpu renderer draws them;
+/// the software renderer draws those sprites with the built-in shading.
+pub trait RenderBackend {
+    /// Resize the render target
+    fn resize(&mut self, width: u32, height: u32);
+
+    /// Current render target size in pixels
+    fn size(&self) -> (u32, u32);
+
+    /// Set the color the frame is cleared to
+    fn set_clear_color(&mut self, color: Color);
+
+    /// IDs of the textures the backend has uploaded or used
+    fn loaded_texture_ids(&self) -> Vec<AssetId>;
+
+    /// Render several views into one frame
+    ///
+    /// Views whose camera has a render-texture `target` are drawn first, into
+    /// that texture (cleared to transparent, sized like the texture asset),
+    /// so other views can show it. The frame is then cleared once and the
+    /// remaining views are drawn in order, each clipped to its viewport. A
+    /// view with `post_process` effects is drawn on its own over the clear
+    /// color and the processed result replaces its viewport area. Debug
+    /// shapes are drawn last, unlit and unprocessed.
+    ///
+    /// # Returns
+    /// Draw call, instance, upload and culling counts summed over the views
+    fn render_views(
+        &mut self,
+        views: &[RenderView<'_>],
+        textures: &dyn TextureLookup,
+    ) -> Result<RenderStats, RendererError>;
+
+    /// Render a collected, sorted batch of sprites from the camera's point of
+    /// view to the whole target
+    ///
+    /// # Returns
+    /// Draw call, instance, upload and culling counts for the frame
+    fn render_sprites(
+        &mut self,
+        sprites: &SpriteBatch,
+        textures: &dyn TextureLookup,
+        camera: &Camera,
+    ) -> Result<RenderStats, RendererError> {
+        let (width, height) = self.size();
+        let view = RenderView {
+            camera,
+            sprites,
+            viewport: ScreenRect::full(width, height),
+            debug: None,
+        };
+        self.render_views(&[view], textures)
+    }
+
+    /// Render the sprites in the world that the camera can see
+    ///
+    /// Culls by testing every sprite against the camera; see `SpriteIndex`
+    /// for large worlds.
+    fn render_world(
+        &mut self,
+        world: &World,
+        textures: &dyn TextureLookup,
+        camera: &Camera,
+    ) -> Result<RenderStats, RendererError> {
+        let mut sprites = SpriteBatch::collect_visible(world, camera);
+        sprites.sort();
+        self.render_sprites(&sprites, textures, camera)
+    }
+
+    /// The last rendered frame, for backends that render to CPU memory
+    fn frame(&self) -> Option<&FrameBuffer> {
+        None
+    }
+}
@@ -0,0 +1,400 @@
+use crate::{
+    sprite_batch::{world_transform, SpriteBatch, SpriteInstance},
+    Camera,
+};
+use glam::Vec2;
+use longhorn_core::{EntityHandle, GlobalTransform, MaterialParams, Rect, Sprite, Transform, World, WorldChange};
+use std::collections::{HashMap, HashSet};
+
+/// Default grid cell size in world units
+pub const DEFAULT_CELL_SIZE: f32 = 256.0;
+
+/// Entries spanning more cells than this are kept in a separate list that
+/// every query checks, instead of being inserted into each cell
+const MAX_CELLS_PER_ENTRY: i64 = 64;
+
+/// Uniform grid of entity bounding boxes
+///
+/// Each entity is stored in every cell its bounds overlap, so a query only
+/// visits the cells covering the query rectangle.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<EntityHandle>>,
+    bounds: HashMap<EntityHandle, Rect>,
+    oversized: HashSet<EntityHandle>,
+}
+
+impl SpatialGrid {
+    /// Create an empty grid with square cells of the given size
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::new(),
+            bounds: HashMap::new(),
+            oversized: HashSet::new(),
+        }
+    }
+
+    /// Cell size in world units
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Number of entities in the grid
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    /// Check if the grid is empty
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    /// Get the stored bounds of an entity
+    pub fn bounds(&self, entity: EntityHandle) -> Option<Rect> {
+        self.bounds.get(&entity).copied()
+    }
+
+    /// Insert an entity, or move it if it is already in the grid
+    pub fn insert(&mut self, entity: EntityHandle, bounds: Rect) {
+        if let Some(old) = self.bounds.get(&entity).copied() {
+            if old == bounds {
+                return;
+            }
+            self.remove(entity);
+        }
+
+        let (min, max) = self.cell_range(&bounds);
+        let cell_count = (max.0 as i64 - min.0 as i64 + 1) * (max.1 as i64 - min.1 as i64 + 1);
+        if cell_count > MAX_CELLS_PER_ENTRY {
+            self.oversized.insert(entity);
+        } else {
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    self.cells.entry((x, y)).or_default().push(entity);
+                }
+            }
+        }
+        self.bounds.insert(entity, bounds);
+    }
+
+    /// Remove an entity
+    ///
+    /// # Returns
+    /// `true` if the entity was in the grid
+    pub fn remove(&mut self, entity: EntityHandle) -> bool {
+        let Some(bounds) = self.bounds.remove(&entity) else {
+            return false;
+        };
+        if self.oversized.remove(&entity) {
+            return true;
+        }
+
+        let (min, max) = self.cell_range(&bounds);
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(cell) = self.cells.get_mut(&(x, y)) {
+                    cell.retain(|e| *e != entity);
+                    if cell.is_empty() {
+                        self.cells.remove(&(x, y));
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Remove every entity
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds.clear();
+        self.oversized.clear();
+    }
+
+    /// Find the entities whose bounds overlap a rectangle
+    ///
+    /// Results are in an unspecified order and contain each entity once.
+    pub fn query(&self, area: &Rect) -> Vec<EntityHandle> {
+        let mut found = HashSet::new();
+        let (min, max) = self.cell_range(area);
+        let cell_count = (max.0 as i64 - min.0 as i64 + 1) * (max.1 as i64 - min.1 as i64 + 1);
+
+        if cell_count as usize > self.cells.len() {
+            // Querying more cells than exist: walk the occupied cells instead
+            for entities in self.cells.values() {
+                found.extend(entities.iter().copied());
+            }
+        } else {
+            for x in min.0..=max.0 {
+                for y in min.1..=max.1 {
+                    if let Some(entities) = self.cells.get(&(x, y)) {
+                        found.extend(entities.iter().copied());
+                    }
+                }
+            }
+        }
+        found.extend(self.oversized.iter().copied());
+
+        found
+            .into_iter()
+            .filter(|entity| self.bounds[entity].intersects(area))
+            .collect()
+    }
+
+    fn cell_range(&self, rect: &Rect) -> ((i32, i32), (i32, i32)) {
+        let cell = |v: Vec2| {
+            (
+                (v.x / self.cell_size).floor() as i32,
+                (v.y / self.cell_size).floor() as i32,
+            )
+        };
+        (cell(rect.min), cell(rect.max))
+    }
+}
+
+/// Spatial index of the sprites in a world, for culling large levels
+///
+/// Kept up to date incrementally from the world's change log, so a frame
+/// only pays for the sprites that moved plus the ones near the camera.
+/// World change tracking must be enabled (`World::set_change_tracking`);
+/// while it is off the index is rebuilt on every `sync`.
+#[derive(Debug, Clone)]
+pub struct SpriteIndex {
+    grid: SpatialGrid,
+    built: bool,
+}
+
+impl SpriteIndex {
+    /// Create an index with the given grid cell size
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            grid: SpatialGrid::new(cell_size),
+            built: false,
+        }
+    }
+
+    /// The underlying grid
+    pub fn grid(&self) -> &SpatialGrid {
+        &self.grid
+    }
+
+    /// Number of indexed sprites
+    pub fn len(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// Check if no sprites are indexed
+    pub fn is_empty(&self) -> bool {
+        self.grid.is_empty()
+    }
+
+    /// Force a full rebuild on the next `sync` (e.g. after replacing the world)
+    pub fn invalidate(&mut self) {
+        self.built = false;
+    }
+
+    /// Bring the index up to date with the world
+    ///
+    /// Applies the changes currently in the world's change log. Applying the
+    /// same changes twice is harmless, so this can run more than once per frame.
+    pub fn sync(&mut self, world: &World) {
+        if !self.built || !world.is_change_tracking() {
+            self.rebuild(world);
+        } else {
+            self.apply_changes(world, &world.changes());
+        }
+    }
+
+    /// Update the index for a set of recorded changes
+    pub fn apply_changes(&mut self, world: &World, changes: &[WorldChange]) {
+        if !self.built {
+            self.rebuild(world);
+            return;
+        }
+
+        for change in changes {
+            match *change {
+                WorldChange::Despawned { entity, .. } => {
+                    self.grid.remove(entity);
+                }
+                WorldChange::Spawned { entity } => self.refresh(world, entity),
+                WorldChange::Added { entity, component }
+                | WorldChange::Changed { entity, component }
+                | WorldChange::Removed { entity, component } => {
+                    if component.is::<Sprite>()
+                        || component.is::<Transform>()
+                        || component.is::<GlobalTransform>()
+                    {
+                        self.refresh(world, entity);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Re-index every sprite in the world
+    pub fn rebuild(&mut self, world: &World) {
+        self.grid.clear();
+        for (entity, (sprite, global, local)) in world
+            .query::<(&Sprite, Option<&GlobalTransform>, Option<&Transform>)>()
+            .iter()
+        {
+            if let Some(transform) = world_transform(global, local) {
+                let bounds = SpriteInstance::from_sprite(sprite, &transform).bounds();
+                self.grid.insert(EntityHandle::new(entity), bounds);
+            }
+        }
+        self.built = true;
+    }
+
+    /// Collect the sprites overlapping the camera's visible area
+    ///
+    /// Call `sync` first. Sprites are returned in entity order so the draw
+    /// order of overlapping sprites on the same layer is stable.
+    pub fn collect_visible(&self, world: &World, camera: &Camera) -> SpriteBatch {
+        let mut entities = self.grid.query(&camera.visible_rect());
+        entities.sort_by_key(|entity| entity.id.to_bits());
+
+        let mut batch = SpriteBatch::new();
+        for entity in entities {
+            if let Some(instance) = sprite_instance(world, entity) {
+                batch.add(instance);
+            }
+        }
+        let culled_sprites = self.grid.len().saturating_sub(batch.len());
+        // Texts, tilemaps, particles and shapes aren't indexed; glyphs,
+        // particles and shapes are tested one by one and tiles a chunk at a time
+        let culled_glyphs = crate::text::collect_text(world, Some(camera.visible_rect()), &mut batch);
+        let culled_tiles = crate::tilemap::collect_tilemaps(world, Some(camera.visible_rect()), &mut batch);
+        let culled_particles = crate::particles::collect_particles(world, Some(camera.visible_rect()), &mut batch);
+        let culled_shapes = crate::shape::collect_shapes(world, Some(camera.visible_rect()), &mut batch);
+        batch.set_culled(culled_sprites + culled_glyphs + culled_tiles + culled_particles + culled_shapes);
+        batch.set_lighting(crate::Lighting::collect(world, Some(camera.visible_rect())));
+        batch
+    }
+
+    fn refresh(&mut self, world: &World, entity: EntityHandle) {
+        match sprite_instance(world, entity) {
+            Some(instance) => self.grid.insert(entity, instance.bounds()),
+            None => {
+                self.grid.remove(entity);
+            }
+        }
+    }
+}
+
+impl Default for SpriteIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+/// Build the render instance for one entity, if it is a sprite with a transform
+fn sprite_instance(world: &World, entity: EntityHandle) -> Option<SpriteInstance> {
+    let sprite = world.get::<Sprite>(entity).ok()?;
+    let global = world.get::<GlobalTransform>(entity).ok();
+    let local = world.get::<Transform>(entity).ok();
+    let transform = world_transform(global.as_deref(), local.as_deref())?;
+    let params = world.get::<MaterialParams>(entity).ok();
+    Some(SpriteInstance::from_sprite(&sprite, &transform).with_material_params(params.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::AssetId;
+
+    fn rect(x: f32, y: f32, size: f32) -> Rect {
+        Rect::new(Vec2::new(x, y), Vec2::new(x + size, y + size))
+    }
+
+    fn spawn_sprite(world: &mut World, x: f32) -> EntityHandle {
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(10.0, 10.0)))
+            .with(Transform::from_position(Vec2::new(x, 0.0)))
+            .build()
+    }
+
+    #[test]
+    fn test_grid_query_insert_move_remove() {
+        let mut world = World::new();
+        let a = world.spawn().build();
+        let b = world.spawn().build();
+
+        let mut grid = SpatialGrid::new(10.0);
+        grid.insert(a, rect(0.0, 0.0, 25.0));
+        grid.insert(b, rect(100.0, 100.0, 5.0));
+
+        // `a` spans several cells but is reported once
+        assert_eq!(grid.query(&rect(-5.0, -5.0, 30.0)), vec![a]);
+        assert_eq!(grid.query(&rect(102.0, 102.0, 1.0)), vec![b]);
+
+        grid.insert(b, rect(5.0, 5.0, 5.0));
+        let mut found = grid.query(&rect(0.0, 0.0, 10.0));
+        found.sort_by_key(|e| e.id.to_bits());
+        assert_eq!(found, vec![a, b]);
+        assert!(grid.query(&rect(102.0, 102.0, 1.0)).is_empty());
+
+        assert!(grid.remove(a));
+        assert!(!grid.remove(a));
+        assert_eq!(grid.query(&rect(0.0, 0.0, 10.0)), vec![b]);
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    fn test_grid_oversized_entries() {
+        let mut world = World::new();
+        let huge = world.spawn().build();
+
+        let mut grid = SpatialGrid::new(1.0);
+        grid.insert(huge, rect(-500.0, -500.0, 1000.0));
+        assert_eq!(grid.query(&rect(400.0, 400.0, 1.0)), vec![huge]);
+        assert!(grid.query(&rect(600.0, 600.0, 1.0)).is_empty());
+        assert!(grid.remove(huge));
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_index_follows_world_changes() {
+        let mut world = World::new();
+        world.set_change_tracking(true);
+        let near = spawn_sprite(&mut world, 0.0);
+        let far = spawn_sprite(&mut world, 1000.0);
+
+        let camera = Camera::new(100.0, 100.0);
+        let mut index = SpriteIndex::new(64.0);
+        index.sync(&world);
+        world.clear_changes();
+
+        let batch = index.collect_visible(&world, &camera);
+        assert_eq!((batch.len(), batch.culled()), (1, 1));
+
+        // Move the far sprite into view and remove the near one
+        world.get_mut::<Transform>(far).unwrap().position = Vec2::new(20.0, 0.0);
+        world.despawn(near).unwrap();
+        index.sync(&world);
+        world.clear_changes();
+
+        let batch = index.collect_visible(&world, &camera);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch.iter().next().unwrap().position, Vec2::new(20.0, 0.0));
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_index_rebuilds_without_change_tracking() {
+        let mut world = World::new();
+        let entity = spawn_sprite(&mut world, 1000.0);
+
+        let camera = Camera::new(100.0, 100.0);
+        let mut index = SpriteIndex::default();
+        index.sync(&world);
+        assert!(index.collect_visible(&world, &camera).is_empty());
+
+        world.get_mut::<Transform>(entity).unwrap().position = Vec2::ZERO;
+        index.sync(&world);
+        assert_eq!(index.collect_visible(&world, &camera).len(), 1);
+    }
+}
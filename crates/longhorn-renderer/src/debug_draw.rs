@@ -0,0 +1,511 @@
+//! Immediate-mode debug shapes drawn over the game and the editor's scene view
+
+use crate::pipeline::{create_shape_pipeline, ShapeVertex, SHAPE_SHADER};
+use crate::shape::ShapeBuffer;
+use crate::{Camera, Color, RenderStats, ScreenRect};
+use glam::Vec2;
+use std::ops::Range;
+
+/// Segments approximating a debug circle
+pub const DEBUG_CIRCLE_SEGMENTS: usize = 32;
+
+/// Length of an arrow head, in screen pixels
+pub const DEBUG_ARROW_HEAD_PIXELS: f32 = 8.0;
+
+/// Screen pixels per pixel of the debug label font
+pub const DEBUG_LABEL_SCALE: f32 = 2.0;
+
+/// A shape in world space
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugShape {
+    Line { start: Vec2, end: Vec2 },
+    /// Outline of an axis-aligned rectangle
+    Rect { center: Vec2, size: Vec2 },
+    /// Outline of a circle
+    Circle { center: Vec2, radius: f32 },
+    /// A line with a head at `end`
+    Arrow { start: Vec2, end: Vec2 },
+    /// A label with its top-left corner at `position`, the same size on
+    /// screen at any zoom
+    Text { position: Vec2, text: String },
+}
+
+/// A queued shape and how long it has left
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugCommand {
+    pub shape: DebugShape,
+    pub color: Color,
+    /// Seconds left; a shape is drawn at least once, even with none
+    pub remaining: f32,
+}
+
+/// Lines and triangles a `DebugDraw` tessellates to, in world space
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DebugGeometry {
+    /// Pairs of line end points
+    pub lines: Vec<ShapeVertex>,
+    /// Filled triangles (label pixels)
+    pub triangles: Vec<ShapeVertex>,
+}
+
+impl DebugGeometry {
+    /// Check if there is nothing to draw
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.triangles.is_empty()
+    }
+}
+
+/// Shapes pushed by systems and scripts, drawn over every screen view
+///
+/// Shapes last one frame unless given a duration: `advance` runs at the
+/// start of each engine update and drops shapes whose time ran out. Without
+/// the `debug-draw` cargo feature nothing is ever queued, so shipping builds
+/// pay nothing for calls left in game code; `set_enabled` turns drawing off
+/// at runtime.
+#[derive(Debug, Clone)]
+pub struct DebugDraw {
+    commands: Vec<DebugCommand>,
+    enabled: bool,
+}
+
+impl DebugDraw {
+    /// Create an empty, enabled debug draw queue
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Whether shapes are queued and drawn; always false without the
+    /// `debug-draw` feature
+    pub fn is_enabled(&self) -> bool {
+        cfg!(feature = "debug-draw") && self.enabled
+    }
+
+    /// Turn queuing and drawing on or off; turning it off drops queued shapes
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.commands.clear();
+        }
+    }
+
+    /// Queue a shape for `duration` seconds (0 for this frame only)
+    pub fn add(&mut self, shape: DebugShape, color: Color, duration: f32) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.commands.push(DebugCommand {
+            shape,
+            color,
+            remaining: duration.max(0.0),
+        });
+    }
+
+    /// Draw a line for this frame
+    pub fn line(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.add(DebugShape::Line { start, end }, color, 0.0);
+    }
+
+    /// Draw a rectangle outline for this frame
+    pub fn rect(&mut self, center: Vec2, size: Vec2, color: Color) {
+        self.add(DebugShape::Rect { center, size }, color, 0.0);
+    }
+
+    /// Draw a circle outline for this frame
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.add(DebugShape::Circle { center, radius }, color, 0.0);
+    }
+
+    /// Draw an arrow pointing at `end` for this frame
+    pub fn arrow(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.add(DebugShape::Arrow { start, end }, color, 0.0);
+    }
+
+    /// Draw a text label for this frame
+    ///
+    /// Labels use a built-in ASCII font; other characters show as `?`.
+    pub fn text(&mut self, position: Vec2, text: impl Into<String>, color: Color) {
+        self.add(DebugShape::Text { position, text: text.into() }, color, 0.0);
+    }
+
+    /// Advance time by `dt` seconds, dropping shapes that have been drawn
+    /// and whose duration ran out
+    pub fn advance(&mut self, dt: f32) {
+        self.commands.retain_mut(|command| {
+            command.remaining -= dt;
+            command.remaining > 0.0
+        });
+    }
+
+    /// Drop every queued shape
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Queued shapes
+    pub fn commands(&self) -> &[DebugCommand] {
+        &self.commands
+    }
+
+    /// Number of queued shapes
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Check if no shapes are queued
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Tessellate the queued shapes for a view where one screen pixel
+    /// covers `pixel_size` world units
+    ///
+    /// Arrow heads and labels are sized in screen pixels; everything else
+    /// is in world units.
+    pub fn geometry(&self, pixel_size: f32) -> DebugGeometry {
+        let mut geometry = DebugGeometry::default();
+        if !self.is_enabled() {
+            return geometry;
+        }
+        for command in &self.commands {
+            let color = command.color.to_array();
+            let mut line = |a: Vec2, b: Vec2| {
+                geometry.lines.push(ShapeVertex { position: a.to_array(), color });
+                geometry.lines.push(ShapeVertex { position: b.to_array(), color });
+            };
+            match &command.shape {
+                DebugShape::Line { start, end } => line(*start, *end),
+                DebugShape::Rect { center, size } => {
+                    let half = *size / 2.0;
+                    let corners = [
+                        *center + Vec2::new(-half.x, -half.y),
+                        *center + Vec2::new(half.x, -half.y),
+                        *center + Vec2::new(half.x, half.y),
+                        *center + Vec2::new(-half.x, half.y),
+                    ];
+                    for i in 0..4 {
+                        line(corners[i], corners[(i + 1) % 4]);
+                    }
+                }
+                DebugShape::Circle { center, radius } => {
+                    let point = |i: usize| {
+                        let angle = i as f32 / DEBUG_CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+                        *center + Vec2::from_angle(angle) * *radius
+                    };
+                    for i in 0..DEBUG_CIRCLE_SEGMENTS {
+                        line(point(i), point(i + 1));
+                    }
+                }
+                DebugShape::Arrow { start, end } => {
+                    line(*start, *end);
+                    let direction = (*end - *start).normalize_or_zero();
+                    if direction != Vec2::ZERO {
+                        let back = -direction * DEBUG_ARROW_HEAD_PIXELS * pixel_size;
+                        line(*end, *end + Vec2::from_angle(0.5).rotate(back));
+                        line(*end, *end + Vec2::from_angle(-0.5).rotate(back));
+                    }
+                }
+                DebugShape::Text { position, text } => {
+                    label_triangles(*position, text, pixel_size * DEBUG_LABEL_SCALE, color, &mut geometry.triangles);
+                }
+            }
+        }
+        geometry
+    }
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// World units covered by one screen pixel of a camera drawn into `viewport`
+pub fn debug_pixel_size(camera: &Camera, viewport: ScreenRect) -> f32 {
+    camera.visible_rect().width() / viewport.width.max(1) as f32
+}
+
+/// Width of a label glyph cell, in font pixels, including spacing
+const GLYPH_ADVANCE: f32 = 6.0;
+
+/// Height of a label line, in font pixels, including spacing
+const LINE_HEIGHT: f32 = 9.0;
+
+/// 5x7 glyphs for ASCII 32..=126, one byte per column, least significant
+/// bit at the top
+const GLYPHS: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Glyph columns for a character; characters outside the font show as `?`
+fn glyph(c: char) -> &'static [u8; 5] {
+    let index = match c {
+        ' '..='~' => c as usize - ' ' as usize,
+        _ => '?' as usize - ' ' as usize,
+    };
+    &GLYPHS[index]
+}
+
+/// Append two triangles per run of lit pixels in each glyph column of a label
+fn label_triangles(position: Vec2, text: &str, font_pixel: f32, color: [f32; 4], triangles: &mut Vec<ShapeVertex>) {
+    let mut quad = |min: Vec2, max: Vec2| {
+        let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+        for i in [0, 1, 2, 0, 2, 3] {
+            triangles.push(ShapeVertex {
+                position: corners[i].to_array(),
+                color,
+            });
+        }
+    };
+
+    for (line, text) in text.lines().enumerate() {
+        let top = position.y - line as f32 * LINE_HEIGHT * font_pixel;
+        for (index, c) in text.chars().enumerate() {
+            let left = position.x + index as f32 * GLYPH_ADVANCE * font_pixel;
+            for (column, bits) in glyph(c).iter().enumerate() {
+                let x = left + column as f32 * font_pixel;
+                let mut row = 0;
+                while row < 7 {
+                    if bits & (1 << row) == 0 {
+                        row += 1;
+                        continue;
+                    }
+                    let start = row;
+                    while row < 7 && bits & (1 << row) != 0 {
+                        row += 1;
+                    }
+                    // Rows count down from the top of the line
+                    quad(
+                        Vec2::new(x, top - row as f32 * font_pixel),
+                        Vec2::new(x + font_pixel, top - start as f32 * font_pixel),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Vertex ranges of one view's debug geometry in the `DebugPass` buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugRanges {
+    lines: Range<u32>,
+    triangles: Range<u32>,
+}
+
+/// GPU pass drawing `DebugDraw` geometry over finished views
+pub struct DebugPass {
+    line_pipeline: wgpu::RenderPipeline,
+    triangle_pipeline: wgpu::RenderPipeline,
+    buffer: ShapeBuffer,
+    /// Vertices of every view prepared this frame
+    vertices: Vec<ShapeVertex>,
+}
+
+impl DebugPass {
+    /// Create the debug pipelines for targets of the given format
+    pub fn new(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Debug Shader"),
+            source: wgpu::ShaderSource::Wgsl(SHAPE_SHADER.into()),
+        });
+        let pipeline = |topology| create_shape_pipeline(device, &shader, camera_bind_group_layout, target_format, topology);
+        Self {
+            line_pipeline: pipeline(wgpu::PrimitiveTopology::LineList),
+            triangle_pipeline: pipeline(wgpu::PrimitiveTopology::TriangleList),
+            buffer: ShapeBuffer::new("Debug Vertex Buffer"),
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Start a frame: forget the last frame's geometry
+    pub fn begin_frame(&mut self) {
+        self.vertices.clear();
+    }
+
+    /// Add a view's geometry; `None` when it has nothing to draw
+    pub fn prepare(&mut self, draw: &DebugDraw, camera: &Camera, viewport: ScreenRect) -> Option<DebugRanges> {
+        let geometry = draw.geometry(debug_pixel_size(camera, viewport));
+        if geometry.is_empty() {
+            return None;
+        }
+        let mut append = |vertices: &[ShapeVertex]| {
+            let start = self.vertices.len() as u32;
+            self.vertices.extend_from_slice(vertices);
+            start..self.vertices.len() as u32
+        };
+        Some(DebugRanges {
+            lines: append(&geometry.lines),
+            triangles: append(&geometry.triangles),
+        })
+    }
+
+    /// Upload the frame's geometry, growing the buffer if needed
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, stats: &mut RenderStats) {
+        self.buffer.write(device, queue, &self.vertices, stats);
+    }
+
+    /// Record a view's geometry; the caller binds the view's camera at group 0
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, ranges: &DebugRanges) {
+        let Some(buffer) = self.buffer.buffer() else {
+            return;
+        };
+        render_pass.set_vertex_buffer(0, buffer.slice(..));
+        if !ranges.triangles.is_empty() {
+            render_pass.set_pipeline(&self.triangle_pipeline);
+            render_pass.draw(ranges.triangles.clone(), 0..1);
+        }
+        if !ranges.lines.is_empty() {
+            render_pass.set_pipeline(&self.line_pipeline);
+            render_pass.draw(ranges.lines.clone(), 0..1);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "debug-draw"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shapes_expire_after_their_duration() {
+        let mut debug = DebugDraw::new();
+        debug.line(Vec2::ZERO, Vec2::X, Color::RED);
+        debug.add(DebugShape::Circle { center: Vec2::ZERO, radius: 1.0 }, Color::GREEN, 0.5);
+        assert_eq!(debug.len(), 2);
+
+        // One-frame shapes are gone at the next frame
+        debug.advance(0.016);
+        assert_eq!(debug.len(), 1);
+        debug.advance(0.5);
+        assert!(debug.is_empty());
+
+        debug.set_enabled(false);
+        debug.line(Vec2::ZERO, Vec2::X, Color::RED);
+        assert!(debug.is_empty());
+    }
+
+    #[test]
+    fn test_geometry_tessellates_outlines_and_labels() {
+        let mut debug = DebugDraw::new();
+        debug.rect(Vec2::ZERO, Vec2::new(4.0, 2.0), Color::WHITE);
+        debug.circle(Vec2::ZERO, 1.0, Color::WHITE);
+        debug.arrow(Vec2::ZERO, Vec2::new(10.0, 0.0), Color::WHITE);
+        let geometry = debug.geometry(0.5);
+        assert_eq!(geometry.lines.len(), 2 * (4 + DEBUG_CIRCLE_SEGMENTS + 3));
+        assert_eq!(geometry.lines[0].position, [-2.0, -1.0]);
+        // The head is sized in pixels, so it is half as long in world units
+        let head = Vec2::from(geometry.lines[geometry.lines.len() - 1].position);
+        assert!(((head - Vec2::new(10.0, 0.0)).length() - DEBUG_ARROW_HEAD_PIXELS * 0.5).abs() < 1e-4);
+        assert!(geometry.triangles.is_empty());
+
+        // "-" is one run per column, "|" a single run
+        let mut debug = DebugDraw::new();
+        debug.text(Vec2::new(0.0, 10.0), "-|", Color::WHITE);
+        let triangles = debug.geometry(1.0).triangles;
+        assert_eq!(triangles.len(), 6 * 6);
+        // The bar runs down the whole glyph from the top of the label
+        let bar = &triangles[5 * 6..];
+        let top = bar.iter().map(|v| v.position[1]).fold(f32::MIN, f32::max);
+        let bottom = bar.iter().map(|v| v.position[1]).fold(f32::MAX, f32::min);
+        assert_eq!((top, bottom), (10.0, 10.0 - 7.0 * DEBUG_LABEL_SCALE));
+    }
+}
@@ -0,0 +1,1580 @@
+use crate::{
+    atlas::{fits_in_atlas, pad_with_gutter, remap_uv_rect, AtlasPacker, AtlasRegion, ATLAS_PAGE_SIZE},
+    backend::{RenderView, TextureLookup},
+    debug_draw::{DebugPass, DebugRanges},
+    pipeline::{
+        compile_material_shader, create_instanced_sprite_pipeline, create_material_bind_group_layout,
+        create_material_pipeline, create_normal_sprite_pipeline, create_premultiplied_sprite_pipeline,
+        create_shape_pipeline, CameraUniform, MaterialLayout, MaterialShaderError, ShapeVertex, MATERIAL_BIND_GROUP,
+        NORMAL_BUFFER_FORMAT, SHAPE_SHADER,
+    },
+    lighting::{has_normal_map, normal_instance, LightingPass, LitView, FLAT_NORMAL},
+    post_process::{PostChain, PostProcessPass},
+    shape::ShapeBuffer,
+    sprite_batch::{SpriteBatch, SpriteInstance},
+    texture::{GpuTexture, TextureCache},
+    Camera, DebugDraw, ScreenRect,
+};
+use glam::Vec2;
+use bytemuck::{Pod, Zeroable};
+use longhorn_assets::{TextureData, TextureImportSettings};
+use longhorn_core::{AssetId, Material};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+use wgpu::{self, util::DeviceExt};
+
+/// Vertices drawn per sprite instance (two triangles)
+const VERTICES_PER_INSTANCE: u32 = 6;
+
+/// Initial instance buffer capacity (sprites)
+const INITIAL_INSTANCE_CAPACITY: usize = 1024;
+
+/// Maximum number of atlas pages before textures fall back to their own GPU texture
+const MAX_ATLAS_PAGES: usize = 4;
+
+/// Per-instance GPU data for one sprite
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct SpriteInstanceRaw {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub rotation: f32,
+    /// Texture region as (u0, v0, u1, v1) in the bound texture
+    pub uv_rect: [f32; 4],
+    pub color: [f32; 4],
+}
+
+impl SpriteInstanceRaw {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        0 => Float32x2,
+        1 => Float32x2,
+        2 => Float32,
+        3 => Float32x4,
+        4 => Float32x4,
+    ];
+
+    /// Build instance data for a sprite, sampling `uv_rect` of the bound texture
+    pub fn new(sprite: &SpriteInstance, uv_rect: [f32; 4]) -> Self {
+        Self {
+            position: sprite.position.to_array(),
+            size: sprite.size.to_array(),
+            rotation: sprite.rotation,
+            uv_rect,
+            color: sprite.color.to_array(),
+        }
+    }
+
+    /// Get the instance buffer layout descriptor
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SpriteInstanceRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Counters for one rendered frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Draw calls issued
+    pub draw_calls: u32,
+    /// Sprite instances drawn
+    pub instances: u32,
+    /// Sprites that passed culling
+    pub visible_sprites: u32,
+    /// Sprites outside the camera's view that were not submitted
+    pub culled_sprites: u32,
+    /// Writes to GPU buffers (camera uniform and instance data)
+    pub buffer_uploads: u32,
+    /// Times the instance buffer had to be reallocated to grow
+    pub buffer_allocations: u32,
+    /// Textures uploaded to the GPU this frame
+    pub texture_uploads: u32,
+    /// Sprites skipped because their texture wasn't available
+    pub skipped_sprites: u32,
+    /// Full-screen post-processing passes recorded
+    pub post_process_passes: u32,
+    /// Lights applied to lit views
+    pub lights: u32,
+}
+
+/// Where a sprite's texture is bound from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureSlot {
+    /// A shared atlas page (by index)
+    Atlas(usize),
+    /// A texture too large for the atlas, bound on its own
+    Standalone(AssetId),
+    /// The fallback texture for sprites whose texture isn't loaded
+    Fallback,
+    /// A texture a camera renders into
+    Render(AssetId),
+    /// No texture: shape meshes, drawn from the shape vertex buffer
+    Shape,
+}
+
+/// A material and one set of its parameter values, bound for drawing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaterialBinding {
+    pub material: AssetId,
+    /// Index of the parameter values among those the material uses this frame
+    pub params: usize,
+}
+
+/// A run of consecutive instances drawn with one texture binding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawBatch {
+    pub slot: TextureSlot,
+    /// Material drawing the batch; `None` uses the built-in sprite shader
+    pub material: Option<MaterialBinding>,
+    /// Instances drawn, or for `TextureSlot::Shape` the shape vertices
+    pub instances: Range<u32>,
+}
+
+/// Turn sorted sprites into instance data and draw batches
+///
+/// `resolve` maps a texture to its slot and the UV rect of the whole texture
+/// within that slot (the sprite's own UV rect is remapped into it). Sprites
+/// it returns `None` for are skipped. `material` gives the material binding
+/// of the sprite at an index of `sprites`. Consecutive sprites sharing a
+/// slot and material binding are merged into one batch, so sprites whose
+/// textures live in the same atlas page draw together. Sprites with a mesh
+/// append it to `shapes` instead, and consecutive ones share a batch.
+///
+/// Instances and batches are appended, so several views can share the
+/// buffers; batches never merge with ones from an earlier call.
+///
+/// # Returns
+/// The number of skipped sprites
+pub fn build_draw_batches(
+    sprites: &SpriteBatch,
+    resolve: impl FnMut(AssetId) -> Option<(TextureSlot, [f32; 4])>,
+    material: impl FnMut(usize) -> Option<MaterialBinding>,
+    instances: &mut Vec<SpriteInstanceRaw>,
+    shapes: &mut Vec<ShapeVertex>,
+    batches: &mut Vec<DrawBatch>,
+) -> u32 {
+    append_draw_batches(sprites.iter(), resolve, material, instances, shapes, batches)
+}
+
+/// `build_draw_batches` over any sequence of instances
+fn append_draw_batches<'a>(
+    sprites: impl Iterator<Item = &'a SpriteInstance>,
+    mut resolve: impl FnMut(AssetId) -> Option<(TextureSlot, [f32; 4])>,
+    mut material: impl FnMut(usize) -> Option<MaterialBinding>,
+    instances: &mut Vec<SpriteInstanceRaw>,
+    shapes: &mut Vec<ShapeVertex>,
+    batches: &mut Vec<DrawBatch>,
+) -> u32 {
+    let first_batch = batches.len();
+    let mut skipped = 0;
+
+    for (sprite_index, sprite) in sprites.enumerate() {
+        if let Some(mesh) = &sprite.mesh {
+            let start = shapes.len() as u32;
+            shapes.extend_from_slice(mesh);
+            let end = shapes.len() as u32;
+            let can_merge = batches.len() > first_batch;
+            match batches.last_mut() {
+                Some(batch) if can_merge && batch.slot == TextureSlot::Shape => batch.instances.end = end,
+                _ => batches.push(DrawBatch {
+                    slot: TextureSlot::Shape,
+                    material: None,
+                    instances: start..end,
+                }),
+            }
+            continue;
+        }
+        let Some((slot, region)) = resolve(sprite.texture) else {
+            skipped += 1;
+            continue;
+        };
+        let material = material(sprite_index);
+
+        let index = instances.len() as u32;
+        instances.push(SpriteInstanceRaw::new(sprite, remap_uv_rect(sprite.uv_rect, region)));
+
+        let can_merge = batches.len() > first_batch;
+        match batches.last_mut() {
+            Some(batch) if can_merge && batch.slot == slot && batch.material == material => {
+                batch.instances.end = index + 1
+            }
+            _ => batches.push(DrawBatch {
+                slot,
+                material,
+                instances: index..index + 1,
+            }),
+        }
+    }
+
+    skipped
+}
+
+/// Persistent GPU buffer for sprite instances
+///
+/// The buffer is reused every frame and only reallocated (to the next power
+/// of two) when a frame has more instances than it can hold.
+pub struct InstanceBuffer {
+    buffer: wgpu::Buffer,
+    capacity: usize,
+}
+
+impl InstanceBuffer {
+    /// Create a buffer that holds `capacity` instances
+    pub fn new(device: &wgpu::Device, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            buffer: Self::allocate(device, capacity),
+            capacity,
+        }
+    }
+
+    /// Number of instances the buffer can hold without growing
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The underlying GPU buffer
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// Upload this frame's instances, growing the buffer if needed
+    pub fn write(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        instances: &[SpriteInstanceRaw],
+        stats: &mut RenderStats,
+    ) {
+        if instances.is_empty() {
+            return;
+        }
+        if instances.len() > self.capacity {
+            self.capacity = instances.len().next_power_of_two();
+            self.buffer = Self::allocate(device, self.capacity);
+            stats.buffer_allocations += 1;
+        }
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(instances));
+        stats.buffer_uploads += 1;
+    }
+
+    fn allocate(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Sprite Instance Buffer"),
+            size: (capacity * std::mem::size_of::<SpriteInstanceRaw>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+/// Camera uniform buffer and bind group for one view
+struct ViewCamera {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Batches prepared for one view
+struct PreparedView {
+    /// Screen area to draw to, or the whole target
+    viewport: Option<ScreenRect>,
+    /// Render texture drawn into instead of the frame
+    target: Option<AssetId>,
+    /// Effects the view is drawn through
+    post: Option<PostChain>,
+    /// Buffers of a view drawn with lights
+    lighting: Option<LitView>,
+    batches: Range<usize>,
+    /// Batches drawing the view's normal buffer, if it is lit
+    normal_batches: Range<usize>,
+    /// Debug geometry drawn over the finished view
+    debug: Option<DebugRanges>,
+}
+
+impl PreparedView {
+    /// Whether the view is drawn straight to the screen, in a shared pass
+    fn is_plain(&self) -> bool {
+        self.target.is_none() && self.post.is_none() && self.lighting.is_none()
+    }
+}
+
+/// Uniform buffer and bind group for one set of a material's parameter values
+struct MaterialBindGroup {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// A material's pipeline and the parameter values it draws with this frame
+struct CompiledMaterial {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    layout: MaterialLayout,
+    /// Distinct uniform data used this frame, indexed by `MaterialBinding::params`
+    params: Vec<Vec<f32>>,
+    /// Reused across frames; one per entry of `params` once uploaded
+    bind_groups: Vec<MaterialBindGroup>,
+    /// Whether every texture was uploaded when the bind groups were created
+    textures_bound: bool,
+}
+
+/// Pipeline cache entry for one material asset
+struct GpuMaterial {
+    /// The material the entry was built from; rebuilt when it changes
+    material: Arc<Material>,
+    /// `None` if the shader failed to compile
+    compiled: Option<CompiledMaterial>,
+}
+
+/// Which pipeline the render pass has bound
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BoundPipeline {
+    Sprite,
+    Premultiplied,
+    Material(AssetId),
+    /// Shape meshes, with the shape vertex buffer bound
+    Shape,
+}
+
+/// One GPU atlas page and its packer
+struct AtlasPage {
+    packer: AtlasPacker,
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Instanced sprite drawing shared by the game renderer and editor viewports
+///
+/// Small textures with default sampling settings are packed into shared
+/// atlas pages so sprites with different textures can be drawn in one call;
+/// larger ones, and those needing their own sampler, mipmaps or
+/// premultiplied blending, keep their own texture. Each frame `prepare` builds the instance data and batches and
+/// uploads them in a single buffer write, and `draw` records one instanced
+/// draw call per batch.
+///
+/// Several views (cameras with their own viewport) can be prepared for the
+/// same frame; each gets its own camera uniform and is drawn in order.
+/// Cameras with a render-texture target draw into a GPU texture the size of
+/// that asset instead, before any other view, so sprites showing the texture
+/// see this frame's contents. Views with post-processing effects are drawn
+/// into a scratch texture and run through a `PostProcessPass`. Views whose
+/// sprites were collected with lights are drawn unlit, then again into a
+/// normal buffer (normal-mapped sprites with their normal map) and lit by a
+/// `LightingPass`. `encode` records all of this, while `draw` only handles
+/// plain screen views.
+///
+/// Sprites with a material draw with a pipeline built from its shader,
+/// cached per material and rebuilt when the material changes. Each distinct
+/// set of parameter values gets its own uniform buffer, so sprites sharing a
+/// material and values still batch together. Shader errors are logged and
+/// kept for `take_material_errors`; until fixed, those sprites draw with the
+/// built-in shader.
+///
+/// Shapes are drawn in z-order with the sprites, from a vertex buffer of
+/// their own with an untextured pipeline.
+pub struct SpritePass {
+    pipeline: wgpu::RenderPipeline,
+    premultiplied_pipeline: wgpu::RenderPipeline,
+    normal_pipeline: wgpu::RenderPipeline,
+    shape_pipeline: wgpu::RenderPipeline,
+    normal_shape_pipeline: wgpu::RenderPipeline,
+    target_format: wgpu::TextureFormat,
+    camera_bind_group_layout: wgpu::BindGroupLayout,
+    view_cameras: Vec<ViewCamera>,
+    views: Vec<PreparedView>,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    atlas_sampler: wgpu::Sampler,
+    atlas_pages: Vec<AtlasPage>,
+    atlas_regions: HashMap<AssetId, (usize, AtlasRegion)>,
+    standalone: TextureCache,
+    /// Camera targets, in the target format
+    render_textures: TextureCache,
+    post: PostProcessPass,
+    lighting: LightingPass,
+    debug: DebugPass,
+    /// Texture versions (see `TextureLookup::texture_version`) as uploaded
+    versions: HashMap<AssetId, u64>,
+    fallback: Option<GpuTexture>,
+    materials: HashMap<AssetId, GpuMaterial>,
+    material_textures: TextureCache,
+    /// White texture bound in place of material textures that aren't loaded
+    blank: Option<GpuTexture>,
+    material_errors: Vec<(AssetId, MaterialShaderError)>,
+    /// Material binding of each sprite in the view being prepared
+    sprite_materials: Vec<Option<MaterialBinding>>,
+    instance_buffer: InstanceBuffer,
+    instances: Vec<SpriteInstanceRaw>,
+    shape_buffer: ShapeBuffer,
+    shapes: Vec<ShapeVertex>,
+    batches: Vec<DrawBatch>,
+}
+
+impl SpritePass {
+    /// Create a sprite pass rendering into targets of the given format
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let camera_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+                label: Some("Camera Bind Group Layout"),
+            });
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+                label: Some("Texture Bind Group Layout"),
+            });
+
+        let pipeline = create_instanced_sprite_pipeline(
+            device,
+            target_format,
+            &camera_bind_group_layout,
+            &texture_bind_group_layout,
+        );
+
+        let premultiplied_pipeline = create_premultiplied_sprite_pipeline(
+            device,
+            target_format,
+            &camera_bind_group_layout,
+            &texture_bind_group_layout,
+        );
+
+        let normal_pipeline =
+            create_normal_sprite_pipeline(device, &camera_bind_group_layout, &texture_bind_group_layout);
+
+        let atlas_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Atlas Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let shape_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shape Shader"),
+            source: wgpu::ShaderSource::Wgsl(SHAPE_SHADER.into()),
+        });
+        let shape_pipeline = |format| {
+            create_shape_pipeline(device, &shape_shader, &camera_bind_group_layout, format, wgpu::PrimitiveTopology::TriangleList)
+        };
+        let (shape_pipeline, normal_shape_pipeline) = (shape_pipeline(target_format), shape_pipeline(NORMAL_BUFFER_FORMAT));
+
+        let debug = DebugPass::new(device, target_format, &camera_bind_group_layout);
+
+        Self {
+            pipeline,
+            premultiplied_pipeline,
+            normal_pipeline,
+            shape_pipeline,
+            normal_shape_pipeline,
+            target_format,
+            camera_bind_group_layout,
+            view_cameras: Vec::new(),
+            views: Vec::new(),
+            texture_bind_group_layout,
+            atlas_sampler,
+            atlas_pages: Vec::new(),
+            atlas_regions: HashMap::new(),
+            standalone: TextureCache::new(),
+            render_textures: TextureCache::new(),
+            post: PostProcessPass::new(device, target_format),
+            lighting: LightingPass::new(device, target_format),
+            debug,
+            versions: HashMap::new(),
+            fallback: None,
+            materials: HashMap::new(),
+            material_textures: TextureCache::new(),
+            blank: None,
+            material_errors: Vec::new(),
+            sprite_materials: Vec::new(),
+            instance_buffer: InstanceBuffer::new(device, INITIAL_INSTANCE_CAPACITY),
+            instances: Vec::new(),
+            shape_buffer: ShapeBuffer::new("Shape Vertex Buffer"),
+            shapes: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    /// Set the texture drawn for sprites whose texture isn't available
+    ///
+    /// Without a fallback those sprites are skipped.
+    pub fn set_fallback_texture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_data: &TextureData,
+    ) {
+        self.fallback = Some(GpuTexture::from_texture_data(
+            device,
+            queue,
+            &self.texture_bind_group_layout,
+            texture_data,
+            &TextureImportSettings::default(),
+            Some("Fallback Texture"),
+        ));
+    }
+
+    /// Take the shader errors of materials compiled since the last call
+    ///
+    /// A material that failed to compile is only tried again once it
+    /// changes (e.g. after `AssetManager::reload_material`).
+    pub fn take_material_errors(&mut self) -> Vec<(AssetId, MaterialShaderError)> {
+        std::mem::take(&mut self.material_errors)
+    }
+
+    /// Number of materials with a compiled pipeline
+    pub fn material_pipeline_count(&self) -> usize {
+        self.materials.values().filter(|gpu| gpu.compiled.is_some()).count()
+    }
+
+    /// Check if a texture has been uploaded
+    pub fn contains(&self, asset_id: AssetId) -> bool {
+        self.atlas_regions.contains_key(&asset_id) || self.standalone.contains(asset_id)
+    }
+
+    /// Get all uploaded texture IDs
+    pub fn texture_ids(&self) -> Vec<AssetId> {
+        let mut ids: Vec<AssetId> = self.atlas_regions.keys().copied().collect();
+        ids.extend(self.standalone.keys());
+        ids
+    }
+
+    /// Number of atlas pages allocated so far
+    pub fn atlas_page_count(&self) -> usize {
+        self.atlas_pages.len()
+    }
+
+    /// Capacity of the persistent instance buffer
+    pub fn instance_capacity(&self) -> usize {
+        self.instance_buffer.capacity()
+    }
+
+    /// Upload a texture, packing it into an atlas page if it is small enough
+    /// and its import settings allow sharing the atlas sampler
+    ///
+    /// # Returns
+    /// `true` if the texture was uploaded, `false` if it already was
+    pub fn upload_texture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        asset_id: AssetId,
+        texture_data: &TextureData,
+        settings: &TextureImportSettings,
+    ) -> bool {
+        if self.contains(asset_id) {
+            return false;
+        }
+
+        if settings.is_atlas_compatible() && fits_in_atlas(texture_data) {
+            if let Some((page, region)) = self.allocate_atlas_region(device, texture_data) {
+                self.write_atlas_region(queue, page, region, texture_data);
+                self.atlas_regions.insert(asset_id, (page, region));
+                log::debug!("Packed texture {:?} into atlas page {}", asset_id, page);
+                return true;
+            }
+        }
+
+        let label = format!("Texture {:?}", asset_id);
+        let gpu_texture = GpuTexture::from_texture_data(
+            device,
+            queue,
+            &self.texture_bind_group_layout,
+            texture_data,
+            settings,
+            Some(&label),
+        );
+        self.standalone.insert(asset_id, gpu_texture);
+        true
+    }
+
+    /// Drop an uploaded texture so the next `prepare` uploads it again,
+    /// e.g. after its import settings changed
+    ///
+    /// Space in atlas pages is not reclaimed.
+    ///
+    /// # Returns
+    /// `true` if the texture had been uploaded
+    pub fn invalidate_texture(&mut self, asset_id: AssetId) -> bool {
+        self.atlas_regions.remove(&asset_id).is_some() || self.standalone.remove(asset_id).is_some()
+    }
+
+    /// Build and upload this frame's instances for one camera
+    ///
+    /// `sprites` should already be sorted and are drawn to the whole target.
+    /// Textures missing from the GPU are uploaded from `textures` first.
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        sprites: &SpriteBatch,
+        textures: &dyn TextureLookup,
+        camera: &Camera,
+    ) -> RenderStats {
+        self.prepare_views(device, queue, &[(camera, sprites, None, None)], textures)
+    }
+
+    /// Build and upload this frame's instances for several views
+    ///
+    /// Views are drawn in the given order, each clipped to its viewport.
+    pub fn prepare_render_views(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        views: &[RenderView<'_>],
+        textures: &dyn TextureLookup,
+    ) -> RenderStats {
+        let views: Vec<_> = views
+            .iter()
+            .map(|view| (view.camera, view.sprites, Some(view.viewport), view.debug))
+            .collect();
+        self.prepare_views(device, queue, &views, textures)
+    }
+
+    fn prepare_views(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        views: &[(&Camera, &SpriteBatch, Option<ScreenRect>, Option<&DebugDraw>)],
+        textures: &dyn TextureLookup,
+    ) -> RenderStats {
+        let mut stats = RenderStats::default();
+        let mut instances = std::mem::take(&mut self.instances);
+        let mut shapes = std::mem::take(&mut self.shapes);
+        let mut batches = std::mem::take(&mut self.batches);
+        instances.clear();
+        shapes.clear();
+        batches.clear();
+        self.views.clear();
+        self.post.begin_frame();
+        self.lighting.begin_frame();
+        self.debug.begin_frame();
+        for compiled in self.materials.values_mut().filter_map(|gpu| gpu.compiled.as_mut()) {
+            compiled.params.clear();
+        }
+
+        // Create render textures before uploads so sprites can show any of them
+        for &(camera, _, _, _) in views {
+            if let Some(target) = camera.target {
+                self.ensure_render_texture(device, target, textures);
+            }
+        }
+
+        for &(camera, sprites, viewport, debug) in views {
+            let size = match camera.target {
+                Some(target) => match self.render_textures.get(target) {
+                    Some(texture) => (texture.width, texture.height),
+                    None => continue,
+                },
+                None => match viewport {
+                    Some(viewport) => (viewport.width, viewport.height),
+                    None => (camera.viewport_size.x as u32, camera.viewport_size.y as u32),
+                },
+            };
+            if size.0 == 0 || size.1 == 0 {
+                continue;
+            }
+            let viewport = if camera.target.is_some() { None } else { viewport };
+            let debug = debug.and_then(|debug| {
+                let area = viewport.unwrap_or(ScreenRect::full(size.0, size.1));
+                self.debug.prepare(debug, camera, area)
+            });
+            stats.visible_sprites += sprites.len() as u32;
+            stats.culled_sprites += sprites.culled() as u32;
+            // Nine-slice and tiled sprites draw as several instances
+            let expanded = sprites.expand_draw_modes(|id| {
+                let size = textures.texture(id).map(|t| (t.width, t.height)).or_else(|| {
+                    self.render_textures.get(id).map(|t| (t.width, t.height))
+                })?;
+                Some(Vec2::new(size.0 as f32, size.1 as f32))
+            });
+            let sprites = expanded.as_ref().unwrap_or(sprites);
+
+            let mut camera_uniform = CameraUniform::new();
+            camera_uniform.update(camera.view_projection());
+            // Views are drawn with the camera slot matching their index
+            let view_camera = self.view_camera(device, self.views.len());
+            queue.write_buffer(&view_camera.buffer, 0, bytemuck::cast_slice(&[camera_uniform]));
+            stats.buffer_uploads += 1;
+
+            let lit = sprites.lighting().is_some();
+            for sprite in sprites.iter().filter(|sprite| sprite.mesh.is_none()) {
+                self.sync_texture(device, queue, sprite.texture, textures, &mut stats);
+                if lit && sprite.normal_map.0 != 0 {
+                    self.sync_texture(device, queue, sprite.normal_map, textures, &mut stats);
+                }
+            }
+
+            let mut sprite_materials = std::mem::take(&mut self.sprite_materials);
+            sprite_materials.clear();
+            for sprite in sprites.iter() {
+                sprite_materials.push(self.bind_material(device, queue, sprite, textures, &mut stats));
+            }
+
+            let first_batch = batches.len();
+            stats.skipped_sprites += build_draw_batches(
+                sprites,
+                |id| self.resolve(id, camera.target),
+                |index| sprite_materials[index],
+                &mut instances,
+                &mut shapes,
+                &mut batches,
+            );
+            self.sprite_materials = sprite_materials;
+
+            let first_normal_batch = batches.len();
+            let lighting = sprites.lighting().map(|lighting| {
+                let normals: Vec<SpriteInstance> = sprites
+                    .iter()
+                    .map(|sprite| normal_instance(sprite, has_normal_map(sprite, |id| self.contains(id))))
+                    .collect();
+                append_draw_batches(
+                    normals.iter(),
+                    |id| self.resolve(id, camera.target),
+                    |_| None,
+                    &mut instances,
+                    &mut shapes,
+                    &mut batches,
+                );
+                self.lighting.prepare(device, queue, lighting, camera, size.0, size.1, &mut stats)
+            });
+
+            let post = if camera.post_process.is_empty() {
+                None
+            } else {
+                self.post.prepare(
+                    device,
+                    queue,
+                    &self.texture_bind_group_layout,
+                    &camera.post_process,
+                    size.0,
+                    size.1,
+                    textures,
+                    &mut stats,
+                )
+            };
+            self.views.push(PreparedView {
+                viewport,
+                target: camera.target,
+                post,
+                lighting,
+                batches: first_batch..first_normal_batch,
+                normal_batches: first_normal_batch..batches.len(),
+                debug,
+            });
+        }
+
+        self.instances = instances;
+        self.shapes = shapes;
+        self.batches = batches;
+        self.write_material_params(device, queue, &mut stats);
+
+        self.instance_buffer.write(device, queue, &self.instances, &mut stats);
+        self.shape_buffer.write(device, queue, &self.shapes, &mut stats);
+        self.debug.upload(device, queue, &mut stats);
+        stats.instances = self.instances.len() as u32;
+        stats.draw_calls = self.batches.len() as u32;
+        stats
+    }
+
+    /// Record the draw calls prepared by the last `prepare` for views
+    /// drawn straight to the screen
+    ///
+    /// Views with a render-texture target, effects or lights need their own
+    /// passes and are skipped; use `encode` to draw those too.
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        for (index, view) in self.views.iter().enumerate() {
+            if view.is_plain() {
+                self.draw_view(render_pass, index, view.viewport);
+                self.draw_debug(render_pass, index, view.viewport);
+            }
+        }
+    }
+
+    /// Record every prepared view: render textures first, then the views
+    /// drawn to `frame`, which is cleared to `clear` first
+    ///
+    /// Render textures are cleared to transparent. A view with effects or
+    /// lights is drawn into scratch textures cleared to `clear`, and the last
+    /// pass writes it over the view's area of `frame`.
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder, frame: &wgpu::TextureView, clear: wgpu::Color) {
+        for (index, view) in self.views.iter().enumerate() {
+            if let Some(texture) = view.target.and_then(|target| self.render_textures.get(target)) {
+                self.encode_view(encoder, index, &texture.view, wgpu::Color::TRANSPARENT);
+                self.encode_debug(encoder, index, &texture.view, None);
+            }
+        }
+
+        let mut load = wgpu::LoadOp::Clear(clear);
+        let mut index = 0;
+        while index < self.views.len() {
+            let view = &self.views[index];
+            if view.target.is_some() {
+                index += 1;
+            } else if !view.is_plain() {
+                if let wgpu::LoadOp::Clear(_) = load {
+                    begin_pass(encoder, frame, load);
+                    load = wgpu::LoadOp::Load;
+                }
+                self.encode_view(encoder, index, frame, clear);
+                self.encode_debug(encoder, index, frame, view.viewport);
+                index += 1;
+            } else {
+                // Consecutive plain views share one pass
+                let mut render_pass = begin_pass(encoder, frame, load);
+                load = wgpu::LoadOp::Load;
+                while let Some(view) = self.views.get(index).filter(|v| v.target.is_none() && v.is_plain()) {
+                    self.draw_view(&mut render_pass, index, view.viewport);
+                    self.draw_debug(&mut render_pass, index, view.viewport);
+                    index += 1;
+                }
+            }
+        }
+        if let wgpu::LoadOp::Clear(_) = load {
+            begin_pass(encoder, frame, load);
+        }
+    }
+
+    /// Record the view at `index` in passes of its own, replacing its area
+    /// of `output` (the viewport, or all of a render texture)
+    fn encode_view(&self, encoder: &mut wgpu::CommandEncoder, index: usize, output: &wgpu::TextureView, clear: wgpu::Color) {
+        let view = &self.views[index];
+        let destination = view.post.as_ref().map_or(output, |chain| self.post.input_view(chain));
+        let clear = wgpu::LoadOp::Clear(clear);
+        match &view.lighting {
+            Some(lit) => {
+                let mut render_pass = begin_pass(encoder, self.lighting.albedo_view(lit), clear);
+                self.draw_view(&mut render_pass, index, None);
+                drop(render_pass);
+                let mut render_pass = begin_pass(encoder, self.lighting.normal_view(lit), wgpu::LoadOp::Clear(FLAT_NORMAL));
+                self.draw_normals(&mut render_pass, index);
+                drop(render_pass);
+                let viewport = if view.post.is_some() { None } else { view.viewport };
+                self.lighting.encode(encoder, lit, destination, viewport);
+            }
+            None => {
+                let mut render_pass = begin_pass(encoder, destination, clear);
+                self.draw_view(&mut render_pass, index, None);
+            }
+        }
+        if let Some(chain) = &view.post {
+            self.post.encode(encoder, chain, output, view.viewport);
+        }
+    }
+
+    /// Scratch textures currently held for post-processing
+    pub fn post_process_scratch_count(&self) -> usize {
+        self.post.scratch_texture_count()
+    }
+
+    /// Record the draw calls of the view at `index`
+    fn draw_view<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, index: usize, viewport: Option<ScreenRect>) {
+        let view = &self.views[index];
+        if view.batches.is_empty() {
+            return;
+        }
+
+        let mut bound = BoundPipeline::Sprite;
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.buffer().slice(..));
+        set_viewport(render_pass, viewport);
+        render_pass.set_bind_group(0, &self.view_cameras[index].bind_group, &[]);
+
+        for batch in &self.batches[view.batches.clone()] {
+            if batch.slot == TextureSlot::Shape {
+                if let Some(buffer) = self.shape_buffer.buffer() {
+                    if bound != BoundPipeline::Shape {
+                        bound = BoundPipeline::Shape;
+                        render_pass.set_pipeline(&self.shape_pipeline);
+                        render_pass.set_vertex_buffer(0, buffer.slice(..));
+                    }
+                    render_pass.draw(batch.instances.clone(), 0..1);
+                }
+                continue;
+            }
+            let Some(bind_group) = self.bind_group(batch.slot) else {
+                continue;
+            };
+            let material = batch.material.and_then(|binding| self.material_bind_group(binding));
+            let (wanted, pipeline) = match (batch.material, material) {
+                (Some(binding), Some((pipeline, _))) => (BoundPipeline::Material(binding.material), pipeline),
+                _ if self.is_premultiplied(batch.slot) => (BoundPipeline::Premultiplied, &self.premultiplied_pipeline),
+                _ => (BoundPipeline::Sprite, &self.pipeline),
+            };
+            if wanted != bound {
+                if bound == BoundPipeline::Shape {
+                    render_pass.set_vertex_buffer(0, self.instance_buffer.buffer().slice(..));
+                }
+                bound = wanted;
+                render_pass.set_pipeline(pipeline);
+            }
+            render_pass.set_bind_group(1, bind_group, &[]);
+            if let Some((_, material_bind_group)) = material {
+                render_pass.set_bind_group(MATERIAL_BIND_GROUP, material_bind_group, &[]);
+            }
+            render_pass.draw(0..VERTICES_PER_INSTANCE, batch.instances.clone());
+        }
+    }
+
+    /// Record the debug shapes of the view at `index`
+    fn draw_debug<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, index: usize, viewport: Option<ScreenRect>) {
+        let Some(ranges) = &self.views[index].debug else {
+            return;
+        };
+        set_viewport(render_pass, viewport);
+        render_pass.set_bind_group(0, &self.view_cameras[index].bind_group, &[]);
+        self.debug.draw(render_pass, ranges);
+    }
+
+    /// Record the debug shapes of the view at `index` in a pass of their
+    /// own over `output`
+    fn encode_debug(&self, encoder: &mut wgpu::CommandEncoder, index: usize, output: &wgpu::TextureView, viewport: Option<ScreenRect>) {
+        if self.views[index].debug.is_some() {
+            let mut render_pass = begin_pass(encoder, output, wgpu::LoadOp::Load);
+            self.draw_debug(&mut render_pass, index, viewport);
+        }
+    }
+
+    /// Record the normal buffer draw calls of the lit view at `index`
+    fn draw_normals<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, index: usize) {
+        let view = &self.views[index];
+        if view.normal_batches.is_empty() {
+            return;
+        }
+
+        let mut bound = BoundPipeline::Sprite;
+        render_pass.set_pipeline(&self.normal_pipeline);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.buffer().slice(..));
+        render_pass.set_bind_group(0, &self.view_cameras[index].bind_group, &[]);
+        for batch in &self.batches[view.normal_batches.clone()] {
+            if batch.slot == TextureSlot::Shape {
+                if let Some(buffer) = self.shape_buffer.buffer() {
+                    if bound != BoundPipeline::Shape {
+                        bound = BoundPipeline::Shape;
+                        render_pass.set_pipeline(&self.normal_shape_pipeline);
+                        render_pass.set_vertex_buffer(0, buffer.slice(..));
+                    }
+                    render_pass.draw(batch.instances.clone(), 0..1);
+                }
+                continue;
+            }
+            if let Some(bind_group) = self.bind_group(batch.slot) {
+                if bound == BoundPipeline::Shape {
+                    bound = BoundPipeline::Sprite;
+                    render_pass.set_pipeline(&self.normal_pipeline);
+                    render_pass.set_vertex_buffer(0, self.instance_buffer.buffer().slice(..));
+                }
+                render_pass.set_bind_group(1, bind_group, &[]);
+                render_pass.draw(0..VERTICES_PER_INSTANCE, batch.instances.clone());
+            }
+        }
+    }
+
+    /// Upload a texture a sprite uses if it is missing or changed
+    fn sync_texture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        id: AssetId,
+        textures: &dyn TextureLookup,
+        stats: &mut RenderStats,
+    ) {
+        if self.render_textures.contains(id) {
+            return;
+        }
+        let version = textures.texture_version(id);
+        if self.contains(id) {
+            if self.versions.get(&id).copied().unwrap_or(0) == version {
+                return;
+            }
+            self.invalidate_texture(id);
+        }
+        match textures.texture(id) {
+            Some(texture_data) if texture_data.width > 0 && texture_data.height > 0 => {
+                let settings = textures.texture_settings(id);
+                if self.upload_texture(device, queue, id, texture_data, &settings) {
+                    self.versions.insert(id, version);
+                    stats.texture_uploads += 1;
+                }
+            }
+            _ => log::trace!("Texture not available for sprite: {:?}", id),
+        }
+    }
+
+    /// Create the GPU texture a camera renders into, or recreate it if the
+    /// asset's size changed
+    fn ensure_render_texture(&mut self, device: &wgpu::Device, id: AssetId, textures: &dyn TextureLookup) {
+        let Some(texture_data) = textures.texture(id) else {
+            log::warn!("Render texture not found for camera target: {:?}", id);
+            return;
+        };
+        let (width, height) = (texture_data.width, texture_data.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+        if self
+            .render_textures
+            .get(id)
+            .is_some_and(|texture| texture.width == width && texture.height == height)
+        {
+            return;
+        }
+
+        // Sprites may have shown the asset's blank pixels before it had a camera
+        self.invalidate_texture(id);
+        let label = format!("Render Texture {:?}", id);
+        let texture = GpuTexture::render_target(
+            device,
+            &self.texture_bind_group_layout,
+            width,
+            height,
+            self.target_format,
+            &textures.texture_settings(id),
+            Some(&label),
+        );
+        self.render_textures.insert(id, texture);
+    }
+
+    /// Camera uniform for the view at `index`, created on first use
+    fn view_camera(&mut self, device: &wgpu::Device, index: usize) -> &ViewCamera {
+        while self.view_cameras.len() <= index {
+            let label = format!("Camera {}", self.view_cameras.len());
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&label),
+                contents: bytemuck::cast_slice(&[CameraUniform::new()]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.camera_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+                label: Some(&label),
+            });
+            self.view_cameras.push(ViewCamera { buffer, bind_group });
+        }
+        &self.view_cameras[index]
+    }
+
+    /// Find where a texture is bound; `target` is the render texture being
+    /// drawn into, which can't be sampled at the same time
+    fn resolve(&self, asset_id: AssetId, target: Option<AssetId>) -> Option<(TextureSlot, [f32; 4])> {
+        if self.render_textures.contains(asset_id) {
+            (target != Some(asset_id)).then_some((TextureSlot::Render(asset_id), crate::FULL_UV_RECT))
+        } else if let Some((page, region)) = self.atlas_regions.get(&asset_id) {
+            Some((TextureSlot::Atlas(*page), region.uv_rect(ATLAS_PAGE_SIZE)))
+        } else if self.standalone.contains(asset_id) {
+            Some((TextureSlot::Standalone(asset_id), crate::FULL_UV_RECT))
+        } else {
+            self.fallback.as_ref().map(|_| (TextureSlot::Fallback, crate::FULL_UV_RECT))
+        }
+    }
+
+    fn bind_group(&self, slot: TextureSlot) -> Option<&wgpu::BindGroup> {
+        match slot {
+            TextureSlot::Atlas(page) => self.atlas_pages.get(page).map(|p| &p.bind_group),
+            TextureSlot::Standalone(id) => self.standalone.get(id).map(|t| &t.bind_group),
+            TextureSlot::Fallback => self.fallback.as_ref().map(|t| &t.bind_group),
+            TextureSlot::Render(id) => self.render_textures.get(id).map(|t| &t.bind_group),
+            TextureSlot::Shape => None,
+        }
+    }
+
+    fn material_bind_group(&self, binding: MaterialBinding) -> Option<(&wgpu::RenderPipeline, &wgpu::BindGroup)> {
+        let compiled = self.materials.get(&binding.material)?.compiled.as_ref()?;
+        let bind_group = compiled.bind_groups.get(binding.params)?;
+        Some((&compiled.pipeline, &bind_group.bind_group))
+    }
+
+    /// Find or add the parameter values a sprite's material draws it with
+    ///
+    /// Sprites without a material, or whose material isn't loaded or doesn't
+    /// compile, get `None` and draw with the built-in shader.
+    fn bind_material(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        sprite: &SpriteInstance,
+        textures: &dyn TextureLookup,
+        stats: &mut RenderStats,
+    ) -> Option<MaterialBinding> {
+        if sprite.material.0 == 0 {
+            return None;
+        }
+        let material = textures.material(sprite.material)?;
+        self.update_material(device, queue, sprite.material, material, textures, stats);
+
+        let GpuMaterial { material, compiled } = self.materials.get_mut(&sprite.material)?;
+        let compiled = compiled.as_mut()?;
+        let data = compiled.layout.pack(material, sprite.material_params.as_ref());
+        let params = match compiled.params.iter().position(|params| *params == data) {
+            Some(index) => index,
+            None => {
+                compiled.params.push(data);
+                compiled.params.len() - 1
+            }
+        };
+        Some(MaterialBinding {
+            material: sprite.material,
+            params,
+        })
+    }
+
+    /// Build the pipeline of a new or changed material and upload its textures
+    fn update_material(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        id: AssetId,
+        material: Arc<Material>,
+        textures: &dyn TextureLookup,
+        stats: &mut RenderStats,
+    ) {
+        match self.materials.get_mut(&id) {
+            Some(gpu) if Arc::ptr_eq(&gpu.material, &material) => {}
+            Some(gpu) if *gpu.material == *material => gpu.material = material,
+            _ => {
+                let compiled = match compile_material_shader(&material) {
+                    Ok(source) => Some(self.compile_material(device, id, &material, &source)),
+                    Err(error) => {
+                        log::error!("Material {:?} failed to compile: {}", id, error);
+                        self.material_errors.push((id, error));
+                        None
+                    }
+                };
+                self.materials.insert(id, GpuMaterial { material, compiled });
+            }
+        }
+
+        let Some(GpuMaterial {
+            material,
+            compiled: Some(compiled),
+        }) = self.materials.get_mut(&id)
+        else {
+            return;
+        };
+        if compiled.textures_bound {
+            return;
+        }
+        let mut all_uploaded = true;
+        for texture in &material.textures {
+            if self.material_textures.contains(texture.texture) {
+                continue;
+            }
+            match textures.texture(texture.texture) {
+                Some(texture_data) if texture_data.width > 0 && texture_data.height > 0 => {
+                    let label = format!("Material Texture {:?}", texture.texture);
+                    let gpu_texture = GpuTexture::from_texture_data(
+                        device,
+                        queue,
+                        &self.texture_bind_group_layout,
+                        texture_data,
+                        &textures.texture_settings(texture.texture),
+                        Some(&label),
+                    );
+                    self.material_textures.insert(texture.texture, gpu_texture);
+                    stats.texture_uploads += 1;
+                }
+                _ => all_uploaded = false,
+            }
+        }
+        if all_uploaded {
+            // Bind groups created so far have the blank texture in place of some
+            compiled.bind_groups.clear();
+            compiled.textures_bound = true;
+        }
+    }
+
+    fn compile_material(&self, device: &wgpu::Device, id: AssetId, material: &Material, source: &str) -> CompiledMaterial {
+        let bind_group_layout = create_material_bind_group_layout(device, material.textures.len());
+        let pipeline = create_material_pipeline(
+            device,
+            &format!("Material {:?}", id),
+            source,
+            self.target_format,
+            &self.camera_bind_group_layout,
+            &self.texture_bind_group_layout,
+            &bind_group_layout,
+        );
+        CompiledMaterial {
+            pipeline,
+            bind_group_layout,
+            layout: MaterialLayout::new(material),
+            params: Vec::new(),
+            bind_groups: Vec::new(),
+            textures_bound: false,
+        }
+    }
+
+    /// Upload this frame's material parameter values, creating bind groups as needed
+    fn write_material_params(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, stats: &mut RenderStats) {
+        if self.blank.is_none() {
+            let blank = TextureData {
+                width: 1,
+                height: 1,
+                pixels: vec![255; 4],
+            };
+            self.blank = Some(GpuTexture::from_texture_data(
+                device,
+                queue,
+                &self.texture_bind_group_layout,
+                &blank,
+                &TextureImportSettings::default(),
+                Some("Blank Material Texture"),
+            ));
+        }
+        let Some(blank) = &self.blank else {
+            return;
+        };
+
+        for (id, gpu) in &mut self.materials {
+            let Some(compiled) = &mut gpu.compiled else {
+                continue;
+            };
+            while compiled.bind_groups.len() < compiled.params.len() {
+                let label = format!("Material {:?} Params {}", id, compiled.bind_groups.len());
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some(&label),
+                    size: compiled.layout.size(),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                let mut entries = vec![wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }];
+                for (index, texture) in gpu.material.textures.iter().enumerate() {
+                    let texture = self.material_textures.get(texture.texture).unwrap_or(blank);
+                    entries.push(wgpu::BindGroupEntry {
+                        binding: 1 + 2 * index as u32,
+                        resource: wgpu::BindingResource::TextureView(&texture.view),
+                    });
+                    entries.push(wgpu::BindGroupEntry {
+                        binding: 2 + 2 * index as u32,
+                        resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                    });
+                }
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some(&label),
+                    layout: &compiled.bind_group_layout,
+                    entries: &entries,
+                });
+                compiled.bind_groups.push(MaterialBindGroup { buffer, bind_group });
+            }
+            for (data, bind_group) in compiled.params.iter().zip(&compiled.bind_groups) {
+                queue.write_buffer(&bind_group.buffer, 0, bytemuck::cast_slice(data));
+                stats.buffer_uploads += 1;
+            }
+        }
+    }
+
+    fn is_premultiplied(&self, slot: TextureSlot) -> bool {
+        match slot {
+            TextureSlot::Standalone(id) => self.standalone.get(id).is_some_and(|t| t.premultiplied),
+            TextureSlot::Render(_) => true,
+            TextureSlot::Atlas(_) | TextureSlot::Fallback | TextureSlot::Shape => false,
+        }
+    }
+
+    fn allocate_atlas_region(
+        &mut self,
+        device: &wgpu::Device,
+        texture_data: &TextureData,
+    ) -> Option<(usize, AtlasRegion)> {
+        for (index, page) in self.atlas_pages.iter_mut().enumerate() {
+            if let Some(region) = page.packer.allocate(texture_data.width, texture_data.height) {
+                return Some((index, region));
+            }
+        }
+
+        if self.atlas_pages.len() >= MAX_ATLAS_PAGES {
+            return None;
+        }
+        let mut page = self.create_atlas_page(device);
+        let region = page.packer.allocate(texture_data.width, texture_data.height)?;
+        self.atlas_pages.push(page);
+        Some((self.atlas_pages.len() - 1, region))
+    }
+
+    fn create_atlas_page(&self, device: &wgpu::Device) -> AtlasPage {
+        let label = format!("Sprite Atlas {}", self.atlas_pages.len());
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&label),
+            size: wgpu::Extent3d {
+                width: ATLAS_PAGE_SIZE,
+                height: ATLAS_PAGE_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&label),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.atlas_sampler),
+                },
+            ],
+        });
+
+        AtlasPage {
+            packer: AtlasPacker::new(ATLAS_PAGE_SIZE),
+            texture,
+            bind_group,
+        }
+    }
+
+    /// Copy a texture and its gutter into an atlas page
+    fn write_atlas_region(
+        &self,
+        queue: &wgpu::Queue,
+        page: usize,
+        region: AtlasRegion,
+        texture_data: &TextureData,
+    ) {
+        let padded = pad_with_gutter(texture_data);
+        let width = texture_data.width + 2;
+        let height = texture_data.height + 2;
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.atlas_pages[page].texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: region.x - 1,
+                    y: region.y - 1,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &padded,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+/// Restrict drawing to `viewport`, if given
+fn set_viewport(render_pass: &mut wgpu::RenderPass<'_>, viewport: Option<ScreenRect>) {
+    if let Some(viewport) = viewport {
+        render_pass.set_viewport(
+            viewport.x as f32,
+            viewport.y as f32,
+            viewport.width as f32,
+            viewport.height as f32,
+            0.0,
+            1.0,
+        );
+        render_pass.set_scissor_rect(viewport.x, viewport.y, viewport.width, viewport.height);
+    }
+}
+
+/// Begin a render pass into one color target
+fn begin_pass<'a>(
+    encoder: &'a mut wgpu::CommandEncoder,
+    view: &'a wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+) -> wgpu::RenderPass<'a> {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Sprite Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load,
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FULL_UV_RECT;
+    use glam::Vec2;
+
+    fn sprite(texture: u64, x: f32) -> SpriteInstance {
+        SpriteInstance::new(Vec2::new(x, 0.0), Vec2::splat(8.0), AssetId::new(texture))
+    }
+
+    #[test]
+    fn test_instance_layout_matches_shader() {
+        assert_eq!(std::mem::size_of::<SpriteInstanceRaw>(), 52);
+        let desc = SpriteInstanceRaw::desc();
+        assert_eq!(desc.step_mode, wgpu::VertexStepMode::Instance);
+        let offsets: Vec<u64> = desc.attributes.iter().map(|a| a.offset).collect();
+        assert_eq!(offsets, vec![0, 8, 16, 20, 36]);
+    }
+
+    #[test]
+    fn test_batches_merge_textures_sharing_a_slot() {
+        let mut sprites = SpriteBatch::new();
+        sprites.add(sprite(1, 0.0));
+        sprites.add(sprite(2, 1.0));
+        sprites.add(sprite(3, 2.0));
+        sprites.add(sprite(1, 3.0));
+
+        // Textures 1 and 2 share atlas page 0, texture 3 is standalone
+        let resolve = |id: AssetId| match id.0 {
+            1 => Some((TextureSlot::Atlas(0), [0.0, 0.0, 0.5, 0.5])),
+            2 => Some((TextureSlot::Atlas(0), [0.5, 0.0, 1.0, 0.5])),
+            3 => Some((TextureSlot::Standalone(id), FULL_UV_RECT)),
+            _ => None,
+        };
+
+        let mut instances = Vec::new();
+        let mut batches = Vec::new();
+        let skipped = build_draw_batches(&sprites, resolve, |_| None, &mut instances, &mut Vec::new(), &mut batches);
+
+        assert_eq!(skipped, 0);
+        assert_eq!(instances.len(), 4);
+        assert_eq!(
+            batches,
+            vec![
+                DrawBatch { slot: TextureSlot::Atlas(0), material: None, instances: 0..2 },
+                DrawBatch { slot: TextureSlot::Standalone(AssetId::new(3)), material: None, instances: 2..3 },
+                DrawBatch { slot: TextureSlot::Atlas(0), material: None, instances: 3..4 },
+            ]
+        );
+        assert_eq!(instances[1].uv_rect, [0.5, 0.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_batches_skip_unresolved_textures() {
+        let mut sprites = SpriteBatch::new();
+        sprites.add(sprite(1, 0.0));
+        sprites.add(sprite(9, 1.0));
+        sprites.add(sprite(1, 2.0));
+
+        let mut instances = Vec::new();
+        let mut batches = Vec::new();
+        let skipped = build_draw_batches(
+            &sprites,
+            |id| (id.0 == 1).then_some((TextureSlot::Standalone(id), FULL_UV_RECT)),
+            |_| None,
+            &mut instances,
+            &mut Vec::new(),
+            &mut batches,
+        );
+
+        assert_eq!(skipped, 1);
+        assert_eq!(instances.len(), 2);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].instances, 0..2);
+    }
+
+    #[test]
+    fn test_batches_split_on_material_bindings() {
+        let mut sprites = SpriteBatch::new();
+        for x in 0..4 {
+            sprites.add(sprite(1, x as f32));
+        }
+        // Sprites 1 and 2 share a material and its parameter values
+        let flash = |params| MaterialBinding {
+            material: AssetId::new(5),
+            params,
+        };
+        let bindings = [None, Some(flash(0)), Some(flash(0)), Some(flash(1))];
+
+        let mut instances = Vec::new();
+        let mut batches = Vec::new();
+        build_draw_batches(
+            &sprites,
+            |_| Some((TextureSlot::Atlas(0), FULL_UV_RECT)),
+            |index| bindings[index],
+            &mut instances,
+            &mut Vec::new(),
+            &mut batches,
+        );
+
+        let runs: Vec<_> = batches.iter().map(|batch| (batch.material, batch.instances.clone())).collect();
+        assert_eq!(runs, vec![(None, 0..1), (Some(flash(0)), 1..3), (Some(flash(1)), 3..4)]);
+    }
+
+    #[test]
+    fn test_consecutive_shapes_share_a_batch() {
+        let shape = |x: f32| {
+            let transform = longhorn_core::GlobalTransform { position: Vec2::new(x, 0.0), ..Default::default() };
+            crate::shape_instance(&longhorn_core::Shape::rectangle(Vec2::ONE), &transform).unwrap()
+        };
+        let mut sprites = SpriteBatch::new();
+        sprites.add(shape(0.0));
+        sprites.add(shape(1.0));
+        sprites.add(sprite(1, 2.0));
+        sprites.add(shape(3.0));
+
+        let mut instances = Vec::new();
+        let mut shapes = Vec::new();
+        let mut batches = Vec::new();
+        build_draw_batches(
+            &sprites,
+            |_| Some((TextureSlot::Atlas(0), FULL_UV_RECT)),
+            |_| None,
+            &mut instances,
+            &mut shapes,
+            &mut batches,
+        );
+
+        // A rectangle is fanned into four triangles
+        assert_eq!(instances.len(), 1);
+        assert_eq!(shapes.len(), 3 * 12);
+        let runs: Vec<_> = batches.iter().map(|batch| (batch.slot, batch.instances.clone())).collect();
+        assert_eq!(
+            runs,
+            vec![(TextureSlot::Shape, 0..24), (TextureSlot::Atlas(0), 0..1), (TextureSlot::Shape, 24..36)]
+        );
+    }
+}
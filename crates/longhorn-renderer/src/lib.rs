@@ -1,12 +1,37 @@
 mod color;
-mod camera;
 mod texture;
 mod sprite_batch;
+mod atlas;
+mod instancing;
+mod culling;
+mod scaling;
 pub mod pipeline;
 mod renderer;
+mod backend;
+mod software;
+mod text;
+mod tilemap;
+mod particles;
+mod post_process;
+mod lighting;
+mod debug_draw;
+mod shape;
 
 pub use color::*;
-pub use camera::{Camera, MainCamera};
+pub use longhorn_core::{Camera, MainCamera, ScreenRect, ViewportRect};
 pub use texture::*;
 pub use sprite_batch::*;
+pub use atlas::*;
+pub use instancing::*;
+pub use culling::*;
+pub use scaling::*;
 pub use renderer::*;
+pub use backend::*;
+pub use software::*;
+pub use text::*;
+pub use tilemap::*;
+pub use particles::*;
+pub use post_process::{PostChain, PostProcessPass};
+pub use debug_draw::*;
+pub use shape::*;
+pub use lighting::{normal_instance, Light, Lighting, LightingPass, LitView};
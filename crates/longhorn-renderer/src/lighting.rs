@@ -0,0 +1,688 @@
+use crate::{
+    pipeline::{
+        create_lighting_bind_group_layout, create_lighting_pipeline, LightUniform, LightingUniform,
+        LIGHTING_SHADER, LIGHT_BUFFER_FORMAT, MAX_LIGHTS, MAX_OCCLUDER_SEGMENTS, NORMAL_BUFFER_FORMAT, ShapeVertex,
+    },
+    sprite_batch::world_transform,
+    Camera, Color, RenderStats, ScreenRect, SpriteInstance,
+};
+use bytemuck::Zeroable;
+use glam::{Mat4, Vec2, Vec3};
+use longhorn_core::{
+    AssetId, GlobalLight2D, GlobalTransform, LightOccluder2D, PointLight2D, Rect, SpotLight2D, Transform, World,
+};
+
+/// Value of `Light::cos_outer` for lights without a cone
+const NO_CONE: f32 = -2.0;
+
+/// Normal buffer value where no sprite was drawn: flat, facing the viewer
+pub(crate) const FLAT_NORMAL: wgpu::Color = wgpu::Color {
+    r: 0.5,
+    g: 0.5,
+    b: 1.0,
+    a: 1.0,
+};
+
+/// A point or spot light as the lighting passes see it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub position: Vec2,
+    /// Color times intensity
+    pub color: Vec3,
+    pub radius: f32,
+    pub falloff: f32,
+    pub height: f32,
+    /// Unit direction of a spot light
+    pub direction: Vec2,
+    /// Cosine of half the cone angle; below -1 for point lights
+    pub cos_outer: f32,
+    /// Cosine of the half angle inside which the cone is at full strength
+    pub cos_inner: f32,
+    pub cast_shadows: bool,
+}
+
+impl Light {
+    pub fn point(light: &PointLight2D, transform: &GlobalTransform) -> Self {
+        Self {
+            position: transform.position,
+            color: Vec3::from(light.color) * light.intensity,
+            radius: light.radius,
+            falloff: light.falloff,
+            height: light.height,
+            direction: Vec2::X,
+            cos_outer: NO_CONE,
+            cos_inner: NO_CONE,
+            cast_shadows: light.cast_shadows,
+        }
+    }
+
+    pub fn spot(light: &SpotLight2D, transform: &GlobalTransform) -> Self {
+        let half = (light.angle.clamp(0.0, 360.0) / 2.0).to_radians();
+        Self {
+            position: transform.position,
+            color: Vec3::from(light.color) * light.intensity,
+            radius: light.radius,
+            falloff: light.falloff,
+            height: light.height,
+            direction: Vec2::from_angle(transform.rotation),
+            cos_outer: half.cos(),
+            cos_inner: (half * (1.0 - light.softness.clamp(0.0, 1.0))).cos(),
+            cast_shadows: light.cast_shadows,
+        }
+    }
+
+    /// World-space box around everything the light can reach
+    pub fn bounds(&self) -> Rect {
+        Rect::from_center_size(self.position, Vec2::splat(self.radius.max(0.0) * 2.0))
+    }
+
+    /// Light reaching a point with the given surface normal, before shadows
+    fn strength(&self, position: Vec2, normal: Vec3) -> f32 {
+        let d = self.position - position;
+        let distance = d.length();
+        if distance >= self.radius {
+            return 0.0;
+        }
+        let mut strength = (1.0 - distance / self.radius).powf(self.falloff);
+        if self.cos_outer >= -1.0 && distance > 0.0 {
+            strength *= smoothstep(self.cos_outer, self.cos_inner, self.direction.dot(-d / distance));
+        }
+        // Relative to a flat sprite, which is lit as if facing the light
+        let l = d.extend(self.height).normalize();
+        strength * (1.0 + normal.dot(l) - l.z).clamp(0.0, 1.0)
+    }
+}
+
+/// The lights of one view
+///
+/// Sprites drawn with lighting are multiplied by `light_at` their position:
+/// the ambient color plus every light reaching them. Light fades out
+/// towards its radius; on normal-mapped sprites it also depends on the
+/// angle between the surface and the light, which sits `height` above the
+/// sprites. Lights with `cast_shadows` don't reach points whose line to the
+/// light passes through an occluder.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lighting {
+    pub ambient: Vec3,
+    pub lights: Vec<Light>,
+    /// Occluder edges in world space
+    pub occluders: Vec<[Vec2; 2]>,
+}
+
+impl Lighting {
+    /// Gather the lights of a world
+    ///
+    /// Lights that can't reach `visible` are left out, as are occluder
+    /// edges no shadow-casting light reaches. At most `MAX_LIGHTS` lights
+    /// and `MAX_OCCLUDER_SEGMENTS` edges are kept.
+    ///
+    /// # Returns
+    /// `None` if the world has no light components, in which case sprites
+    /// are drawn unlit
+    pub fn collect(world: &World, visible: Option<Rect>) -> Option<Self> {
+        let mut lighting = Self::default();
+        let mut any = false;
+
+        for (_, light) in world.query::<&GlobalLight2D>().iter() {
+            any = true;
+            lighting.ambient += Vec3::from(light.color) * light.intensity;
+        }
+        for (_, (light, global, local)) in world
+            .query::<(&PointLight2D, Option<&GlobalTransform>, Option<&Transform>)>()
+            .iter()
+        {
+            any = true;
+            let transform = world_transform(global, local).unwrap_or_default();
+            lighting.add_light(Light::point(light, &transform), visible);
+        }
+        for (_, (light, global, local)) in world
+            .query::<(&SpotLight2D, Option<&GlobalTransform>, Option<&Transform>)>()
+            .iter()
+        {
+            any = true;
+            let transform = world_transform(global, local).unwrap_or_default();
+            lighting.add_light(Light::spot(light, &transform), visible);
+        }
+        if !any {
+            return None;
+        }
+
+        let shadow_bounds: Vec<Rect> = lighting
+            .lights
+            .iter()
+            .filter(|light| light.cast_shadows)
+            .map(Light::bounds)
+            .collect();
+        if !shadow_bounds.is_empty() {
+            for (_, (occluder, global, local)) in world
+                .query::<(&LightOccluder2D, Option<&GlobalTransform>, Option<&Transform>)>()
+                .iter()
+            {
+                let transform = world_transform(global, local).unwrap_or_default();
+                for segment in occluder.segments(&transform) {
+                    let bounds = Rect::new(segment[0].min(segment[1]), segment[0].max(segment[1]));
+                    if shadow_bounds.iter().any(|light| light.intersects(&bounds)) {
+                        lighting.occluders.push(segment);
+                    }
+                }
+            }
+            if lighting.occluders.len() > MAX_OCCLUDER_SEGMENTS {
+                log::debug!(
+                    "{} light occluder edges in view, only the first {} cast shadows",
+                    lighting.occluders.len(),
+                    MAX_OCCLUDER_SEGMENTS
+                );
+                lighting.occluders.truncate(MAX_OCCLUDER_SEGMENTS);
+            }
+        }
+        Some(lighting)
+    }
+
+    fn add_light(&mut self, light: Light, visible: Option<Rect>) {
+        if visible.is_some_and(|visible| !visible.intersects(&light.bounds())) {
+            return;
+        }
+        if self.lights.len() == MAX_LIGHTS {
+            log::debug!("More than {} lights in view, skipping the rest", MAX_LIGHTS);
+            return;
+        }
+        self.lights.push(light);
+    }
+
+    /// Light reaching a world position on a surface with the given normal
+    ///
+    /// `normal` is (0, 0, 1) for flat sprites. Multiply linear sprite color
+    /// by the result.
+    pub fn light_at(&self, position: Vec2, normal: Vec3) -> Vec3 {
+        let mut total = self.ambient;
+        for light in &self.lights {
+            let strength = light.strength(position, normal);
+            if strength <= 0.0 || (light.cast_shadows && self.shadowed(position, light.position)) {
+                continue;
+            }
+            total += light.color * strength;
+        }
+        total
+    }
+
+    /// Check if the line between two points passes through an occluder
+    ///
+    /// Crossing a single edge means one end is inside a shape, which
+    /// doesn't block: shapes stay lit and lights inside them still shine.
+    pub fn shadowed(&self, from: Vec2, to: Vec2) -> bool {
+        let r = to - from;
+        let mut crossings = 0;
+        for [a, b] in &self.occluders {
+            let s = *b - *a;
+            let denom = r.perp_dot(s);
+            if denom == 0.0 {
+                continue;
+            }
+            let offset = *a - from;
+            let t = offset.perp_dot(s) / denom;
+            let u = offset.perp_dot(r) / denom;
+            // Half-open along the edge so a line through a shared corner counts once
+            if t > 0.0 && t < 1.0 && (0.0..1.0).contains(&u) {
+                crossings += 1;
+                if crossings >= 2 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Pack for the light buffer pass of a view drawn with `view_projection`
+    pub fn to_uniform(&self, view_projection: Mat4) -> LightingUniform {
+        let mut uniform = LightingUniform::zeroed();
+        uniform.inv_view_proj = view_projection.inverse().to_cols_array_2d();
+        uniform.ambient = self.ambient.extend(1.0).to_array();
+        let lights = self.lights.len().min(MAX_LIGHTS);
+        let segments = self.occluders.len().min(MAX_OCCLUDER_SEGMENTS);
+        uniform.counts = [lights as u32, segments as u32, 0, 0];
+        for (packed, light) in uniform.lights.iter_mut().zip(&self.lights) {
+            *packed = LightUniform {
+                position: [light.position.x, light.position.y, light.radius, light.falloff],
+                color: light.color.extend(light.height).to_array(),
+                cone: [light.direction.x, light.direction.y, light.cos_outer, light.cos_inner],
+                shadow: [if light.cast_shadows { 1.0 } else { 0.0 }, 0.0, 0.0, 0.0],
+            };
+        }
+        for (packed, [a, b]) in uniform.segments.iter_mut().zip(&self.occluders) {
+            *packed = [a.x, a.y, b.x, b.y];
+        }
+        uniform
+    }
+}
+
+/// Build the instance drawing a sprite into a lit view's normal buffer
+///
+/// The texture is the sprite's normal map when `mapped`, otherwise its own
+/// texture, whose alpha then covers the buffer with flat normals. The tint
+/// encodes what `fs_normal` needs to turn the map's normals to world space:
+/// the sprite's rotation, and whether flipping or a negative scale mirrors
+/// the texture. Shape meshes are recolored to the encoded flat normal
+/// instead, keeping their alpha.
+pub fn normal_instance(sprite: &SpriteInstance, mapped: bool) -> SpriteInstance {
+    let [u0, v0, u1, v1] = sprite.uv_rect;
+    let mirror_x = (u1 < u0) != (sprite.size.x < 0.0);
+    let mirror_y = (v1 < v0) != (sprite.size.y < 0.0);
+    // Mirroring both axes is a half turn
+    let rotation = sprite.rotation + if mirror_y { std::f32::consts::PI } else { 0.0 };
+    let mode = match (mapped, mirror_x != mirror_y) {
+        (false, _) => 0.0,
+        (true, false) => 1.0,
+        (true, true) => -1.0,
+    };
+
+    let mut instance = sprite.clone();
+    instance.texture = if mapped { sprite.normal_map } else { sprite.texture };
+    instance.color = Color::new(rotation.cos(), rotation.sin(), mode, sprite.color.a);
+    if let Some(mesh) = &sprite.mesh {
+        let normal = flat_normal().truncate();
+        let recolor = |v: &ShapeVertex| ShapeVertex {
+            color: normal.extend(v.color[3]).to_array(),
+            ..*v
+        };
+        instance.mesh = Some(mesh.iter().map(recolor).collect());
+    }
+    instance
+}
+
+/// Decode a normal buffer value written by a `normal_instance`
+pub(crate) fn shade_normal(texel: glam::Vec4, tint: glam::Vec4) -> glam::Vec4 {
+    let mut normal = Vec3::Z;
+    if tint.z != 0.0 {
+        let local = texel.truncate() * 2.0 - 1.0;
+        let x = local.x * tint.z;
+        normal = Vec3::new(x * tint.x - local.y * tint.y, x * tint.y + local.y * tint.x, local.z);
+    }
+    (normal * 0.5 + 0.5).extend(texel.w * tint.w)
+}
+
+/// Encoded flat normal, as the normal buffer is cleared to
+pub(crate) fn flat_normal() -> glam::Vec4 {
+    glam::Vec4::new(FLAT_NORMAL.r as f32, FLAT_NORMAL.g as f32, FLAT_NORMAL.b as f32, FLAT_NORMAL.a as f32)
+}
+
+/// Whether a sprite draws its normal map into the normal buffer
+pub(crate) fn has_normal_map(sprite: &SpriteInstance, available: impl Fn(AssetId) -> bool) -> bool {
+    sprite.normal_map.0 != 0 && available(sprite.normal_map)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge0 == edge1 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Texture a lighting pass renders into, kept across frames
+struct ScratchTexture {
+    view: wgpu::TextureView,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    /// Handed out this frame
+    used: bool,
+}
+
+/// A lit view's buffers, prepared for drawing
+pub struct LitView {
+    albedo: usize,
+    normal: usize,
+    light: usize,
+    light_bind_group: wgpu::BindGroup,
+    composite_bind_group: wgpu::BindGroup,
+}
+
+/// Runs the lighting passes of lit views on the GPU
+///
+/// A lit view's sprites are drawn unlit into an albedo texture and, with
+/// `normal_instance`s, into a normal buffer. A full-screen pass then adds
+/// up the view's lights per pixel into a light buffer, and a second one
+/// writes albedo times light into the view's area of the real target.
+/// Scratch textures are reused across frames and dropped once a frame
+/// doesn't need them.
+pub struct LightingPass {
+    bind_group_layout: wgpu::BindGroupLayout,
+    light_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    target_format: wgpu::TextureFormat,
+    scratch: Vec<ScratchTexture>,
+    /// One uniform buffer per view prepared this frame
+    uniforms: Vec<wgpu::Buffer>,
+    next_uniform: usize,
+}
+
+impl LightingPass {
+    /// Create the lighting passes for targets of the given format
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = create_lighting_bind_group_layout(device);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Lighting Shader"),
+            source: wgpu::ShaderSource::Wgsl(LIGHTING_SHADER.into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Lighting Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let light_pipeline = create_lighting_pipeline(device, &shader, &layout, LIGHT_BUFFER_FORMAT, "fs_light");
+        let composite_pipeline = create_lighting_pipeline(device, &shader, &layout, target_format, "fs_composite");
+
+        Self {
+            bind_group_layout,
+            light_pipeline,
+            composite_pipeline,
+            target_format,
+            scratch: Vec::new(),
+            uniforms: Vec::new(),
+            next_uniform: 0,
+        }
+    }
+
+    /// Start a frame: free scratch textures the last frame didn't use
+    pub fn begin_frame(&mut self) {
+        self.scratch.retain(|scratch| scratch.used);
+        for scratch in &mut self.scratch {
+            scratch.used = false;
+        }
+        self.next_uniform = 0;
+    }
+
+    /// Number of scratch textures currently allocated
+    pub fn scratch_texture_count(&self) -> usize {
+        self.scratch.len()
+    }
+
+    /// Allocate a view's buffers and upload its lights
+    ///
+    /// Call `begin_frame` first each frame.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        lighting: &Lighting,
+        camera: &Camera,
+        width: u32,
+        height: u32,
+        stats: &mut RenderStats,
+    ) -> LitView {
+        let albedo = self.scratch(device, self.target_format, width, height);
+        let normal = self.scratch(device, NORMAL_BUFFER_FORMAT, width, height);
+        let light = self.scratch(device, LIGHT_BUFFER_FORMAT, width, height);
+
+        let buffer = self.uniform(device);
+        let uniform = lighting.to_uniform(camera.view_projection());
+        queue.write_buffer(&self.uniforms[buffer], 0, bytemuck::bytes_of(&uniform));
+        stats.buffer_uploads += 1;
+        stats.lights += lighting.lights.len() as u32;
+
+        let bind_group = |label, first: usize, second: usize| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(label),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&self.scratch[first].view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&self.scratch[second].view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.uniforms[buffer].as_entire_binding(),
+                    },
+                ],
+            })
+        };
+        // The light pass doesn't read its second texture; bind the normal buffer twice
+        let light_bind_group = bind_group("Light Buffer Bind Group", normal, normal);
+        let composite_bind_group = bind_group("Lighting Composite Bind Group", albedo, light);
+
+        LitView {
+            albedo,
+            normal,
+            light,
+            light_bind_group,
+            composite_bind_group,
+        }
+    }
+
+    /// Texture a lit view's sprites are drawn into, unlit
+    pub fn albedo_view(&self, view: &LitView) -> &wgpu::TextureView {
+        &self.scratch[view.albedo].view
+    }
+
+    /// Texture a lit view's `normal_instance`s are drawn into
+    pub fn normal_view(&self, view: &LitView) -> &wgpu::TextureView {
+        &self.scratch[view.normal].view
+    }
+
+    /// Record the light buffer pass and the composite, writing the lit view
+    /// to `target`
+    ///
+    /// With a `viewport`, the result only covers that area of the target.
+    pub fn encode(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &LitView,
+        target: &wgpu::TextureView,
+        viewport: Option<ScreenRect>,
+    ) {
+        let passes = [
+            (
+                &self.scratch[view.light].view,
+                wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                None,
+                &self.light_pipeline,
+                &view.light_bind_group,
+            ),
+            (target, wgpu::LoadOp::Load, viewport, &self.composite_pipeline, &view.composite_bind_group),
+        ];
+        for (output, load, viewport, pipeline, bind_group) in passes {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Lighting Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: output,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            if let Some(viewport) = viewport {
+                render_pass.set_viewport(
+                    viewport.x as f32,
+                    viewport.y as f32,
+                    viewport.width as f32,
+                    viewport.height as f32,
+                    0.0,
+                    1.0,
+                );
+                render_pass.set_scissor_rect(viewport.x, viewport.y, viewport.width, viewport.height);
+            }
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    /// Hand out an unused scratch texture of the given format and size,
+    /// creating one if needed
+    fn scratch(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat, width: u32, height: u32) -> usize {
+        if let Some(index) = self.scratch.iter().position(|scratch| {
+            !scratch.used && scratch.format == format && scratch.width == width && scratch.height == height
+        }) {
+            self.scratch[index].used = true;
+            return index;
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Lighting Scratch Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        self.scratch.push(ScratchTexture {
+            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            format,
+            width,
+            height,
+            used: true,
+        });
+        self.scratch.len() - 1
+    }
+
+    /// Index of a uniform buffer not yet used this frame
+    fn uniform(&mut self, device: &wgpu::Device) -> usize {
+        if self.next_uniform == self.uniforms.len() {
+            self.uniforms.push(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Lighting Uniform"),
+                size: std::mem::size_of::<LightingUniform>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        self.next_uniform += 1;
+        self.next_uniform - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::OccluderShape;
+
+    fn point(position: Vec2, radius: f32) -> Light {
+        let light = PointLight2D {
+            radius,
+            height: 0.0,
+            ..Default::default()
+        };
+        Light::point(&light, &GlobalTransform::from_transform(&Transform::from_position(position)))
+    }
+
+    #[test]
+    fn test_light_falloff_and_cone() {
+        let lighting = Lighting {
+            ambient: Vec3::splat(0.1),
+            lights: vec![point(Vec2::ZERO, 100.0)],
+            occluders: Vec::new(),
+        };
+        assert!((lighting.light_at(Vec2::new(50.0, 0.0), Vec3::Z).x - 0.6).abs() < 1e-5);
+        assert_eq!(lighting.light_at(Vec2::new(150.0, 0.0), Vec3::Z), Vec3::splat(0.1));
+
+        let spot = SpotLight2D {
+            angle: 90.0,
+            softness: 0.0,
+            height: 0.0,
+            ..Default::default()
+        };
+        let lighting = Lighting {
+            lights: vec![Light::spot(&spot, &GlobalTransform::new())],
+            ..Default::default()
+        };
+        // Pointing along +X: inside the cone at 30 degrees, outside at 60
+        assert!(lighting.light_at(Vec2::from_angle(30f32.to_radians()) * 10.0, Vec3::Z).x > 0.9);
+        assert_eq!(lighting.light_at(Vec2::from_angle(60f32.to_radians()) * 10.0, Vec3::Z), Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_normals_shade_relative_to_flat() {
+        let lighting = Lighting {
+            lights: vec![point(Vec2::ZERO, 100.0)],
+            ..Default::default()
+        };
+        let flat = lighting.light_at(Vec2::new(50.0, 0.0), Vec3::Z);
+        // Facing the light is as bright as flat; facing away is dark
+        assert_eq!(lighting.light_at(Vec2::new(50.0, 0.0), Vec3::NEG_X), flat);
+        assert_eq!(lighting.light_at(Vec2::new(50.0, 0.0), Vec3::X), Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_occluders_cast_shadows_behind_them() {
+        let mut light = point(Vec2::ZERO, 100.0);
+        light.cast_shadows = true;
+        let wall = LightOccluder2D::new(OccluderShape::Box {
+            size: Vec2::new(10.0, 40.0),
+        });
+        let lighting = Lighting {
+            lights: vec![light],
+            occluders: wall.segments(&GlobalTransform::from_transform(&Transform::from_position(Vec2::new(
+                50.0, 0.0,
+            )))),
+            ..Default::default()
+        };
+
+        assert_eq!(lighting.light_at(Vec2::new(80.0, 0.0), Vec3::Z), Vec3::ZERO);
+        // Next to the wall and inside it stays lit
+        assert!(lighting.light_at(Vec2::new(70.0, 40.0), Vec3::Z).x > 0.0);
+        assert!(lighting.light_at(Vec2::new(50.0, 0.0), Vec3::Z).x > 0.0);
+        // A light inside the shape isn't blocked by it
+        assert!(!lighting.shadowed(Vec2::new(80.0, 0.0), Vec2::new(50.0, 0.0)));
+    }
+
+    #[test]
+    fn test_collect_lights() {
+        let mut world = World::new();
+        assert_eq!(Lighting::collect(&world, None), None);
+
+        world.spawn().with(GlobalLight2D::default()).build();
+        world
+            .spawn()
+            .with(PointLight2D::default())
+            .with(Transform::from_position(Vec2::new(1000.0, 0.0)))
+            .build();
+        world
+            .spawn()
+            .with(LightOccluder2D::default())
+            .with(Transform::new())
+            .build();
+
+        let lighting = Lighting::collect(&world, None).unwrap();
+        assert_eq!(lighting.ambient, Vec3::splat(0.2));
+        assert_eq!(lighting.lights.len(), 1);
+        // No light casts shadows, so occluders are left out
+        assert!(lighting.occluders.is_empty());
+
+        // The light can't reach the visible area
+        let visible = Rect::from_center_size(Vec2::ZERO, Vec2::splat(100.0));
+        assert!(Lighting::collect(&world, Some(visible)).unwrap().lights.is_empty());
+    }
+
+    #[test]
+    fn test_normal_instance_encodes_mirroring() {
+        let mut sprite = SpriteInstance::new(Vec2::ZERO, Vec2::ONE, AssetId::new(1)).with_rotation(0.5);
+        sprite.normal_map = AssetId::new(2);
+
+        let flat = normal_instance(&sprite, false);
+        assert_eq!((flat.texture, flat.color.b), (AssetId::new(1), 0.0));
+
+        let mapped = normal_instance(&sprite, true);
+        assert_eq!((mapped.texture, mapped.color.b), (AssetId::new(2), 1.0));
+        assert!((mapped.color.r - 0.5f32.cos()).abs() < 1e-6);
+
+        // Flipped horizontally, the map's x axis points the other way
+        sprite.uv_rect = [1.0, 0.0, 0.0, 1.0];
+        let normal = shade_normal(glam::Vec4::new(1.0, 0.5, 0.5, 1.0), glam::Vec4::from(normal_instance(&sprite, true).color.to_array()));
+        assert!(normal.x < 0.5);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::sprite_batch::world_transform;
+use crate::{Color, SpriteBatch, SpriteInstance};
+use glam::Vec2;
+use longhorn_core::{
+    GlobalTransform, Particle, ParticleEmitter, ParticleState, Rect, SimulationSpace, Transform, World,
+};
+
+/// Build the render instance for one particle of an emitter
+///
+/// `transform` is the emitter's; it only applies to particles simulated in
+/// local space.
+pub fn particle_instance(particle: &Particle, emitter: &ParticleEmitter, transform: &GlobalTransform) -> SpriteInstance {
+    let effect = &emitter.effect;
+    let t = particle.progress();
+    let size = Vec2::splat(effect.size.sample(t).unwrap_or(1.0));
+    let [r, g, b, a] = effect.color.sample(t).unwrap_or([1.0; 4]);
+
+    let (position, size, rotation) = match effect.space {
+        SimulationSpace::World => (particle.position, size, particle.rotation),
+        SimulationSpace::Local => (
+            transform.transform_point(particle.position),
+            size * transform.scale,
+            transform.rotation + particle.rotation,
+        ),
+    };
+    SpriteInstance::new(position, size, emitter.texture)
+        .with_color(Color::new(r, g, b, a))
+        .with_z_index(emitter.z_index)
+        .with_rotation(rotation)
+}
+
+/// Add the live particles of every textured emitter to a batch
+///
+/// Particles are culled one by one against `visible`.
+///
+/// # Returns
+/// The number of particles culled
+pub(crate) fn collect_particles(world: &World, visible: Option<Rect>, batch: &mut SpriteBatch) -> usize {
+    let mut culled = 0;
+    for (_, (emitter, state, global, local)) in world
+        .query::<(&ParticleEmitter, &ParticleState, Option<&GlobalTransform>, Option<&Transform>)>()
+        .iter()
+    {
+        if emitter.texture.0 == 0 {
+            continue;
+        }
+        let transform = world_transform(global, local).unwrap_or_default();
+        for particle in state.particles() {
+            let instance = particle_instance(particle, emitter, &transform);
+            if visible.is_some_and(|visible| !visible.intersects(&instance.bounds())) {
+                culled += 1;
+                continue;
+            }
+            batch.add(instance);
+        }
+    }
+    culled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Camera;
+    use longhorn_core::{AssetId, Curve, ParticleEffect};
+
+    #[test]
+    fn test_particles_follow_curves_and_space() {
+        let mut world = World::new();
+        let mut effect = ParticleEffect::one_shot(1);
+        effect.speed = [0.0, 0.0];
+        effect.lifetime = [1.0, 1.0];
+        effect.size = Curve::linear(10.0, 20.0);
+        effect.space = SimulationSpace::Local;
+        let emitter = world
+            .spawn()
+            .with(ParticleEmitter::new(effect, AssetId::new(1)).with_z_index(-1))
+            .with(Transform::from_position(Vec2::new(30.0, 0.0)))
+            .build();
+        let untextured = world
+            .spawn()
+            .with(ParticleEmitter::new(ParticleEffect::one_shot(1), AssetId::new(0)))
+            .with(Transform::default())
+            .build();
+
+        longhorn_core::update_particles(&mut world, 0.1);
+        longhorn_core::update_particles(&mut world, 0.5);
+        assert_eq!(world.get::<ParticleState>(untextured).unwrap().len(), 1);
+
+        let batch = SpriteBatch::collect(&world);
+        assert_eq!(batch.len(), 1);
+        let particle = batch.iter().next().unwrap();
+        assert_eq!(particle.position, Vec2::new(30.0, 0.0));
+        assert_eq!(particle.size, Vec2::splat(15.0));
+        assert_eq!(particle.color.a, 0.5);
+        assert_eq!(particle.z_index, -1);
+
+        // Local particles move with the emitter
+        world.get_mut::<Transform>(emitter).unwrap().position = Vec2::new(500.0, 0.0);
+        let batch = SpriteBatch::collect(&world);
+        assert_eq!(batch.iter().next().unwrap().position, Vec2::new(500.0, 0.0));
+        let visible = SpriteBatch::collect_visible(&world, &Camera::new(200.0, 200.0));
+        assert_eq!((visible.len(), visible.culled()), (0, 1));
+    }
+}
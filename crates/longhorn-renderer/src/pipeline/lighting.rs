@@ -0,0 +1,116 @@
+use bytemuck::{Pod, Zeroable};
+
+/// WGSL source of the light buffer and composite passes
+pub const LIGHTING_SHADER: &str = include_str!("lighting.wgsl");
+
+/// Lights a view can have; must match `MAX_LIGHTS` in the shader
+pub const MAX_LIGHTS: usize = 64;
+
+/// Occluder edges a view can have; must match `MAX_OCCLUDER_SEGMENTS` in the shader
+pub const MAX_OCCLUDER_SEGMENTS: usize = 256;
+
+/// Format of the normal buffer
+pub const NORMAL_BUFFER_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Format of the light buffer; lights add up past 1
+pub const LIGHT_BUFFER_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// One light in the `LightingUniform`
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct LightUniform {
+    /// World position, radius and falloff exponent
+    pub position: [f32; 4],
+    /// Color times intensity, and height
+    pub color: [f32; 4],
+    /// Direction and cosines of the outer and inner cone half angles
+    pub cone: [f32; 4],
+    /// 1 in x if occluders block the light
+    pub shadow: [f32; 4],
+}
+
+/// Uniform data of the light buffer pass
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct LightingUniform {
+    pub inv_view_proj: [[f32; 4]; 4],
+    pub ambient: [f32; 4],
+    /// Number of lights and of occluder segments
+    pub counts: [u32; 4],
+    pub lights: [LightUniform; MAX_LIGHTS],
+    /// Segment start and end points
+    pub segments: [[f32; 4]; MAX_OCCLUDER_SEGMENTS],
+}
+
+/// Create the bind group layout of the lighting passes
+///
+/// Bindings 0 and 1 are textures read with `textureLoad` (the normal buffer
+/// for the light pass; albedo and light buffer for the composite) and 2 the
+/// `LightingUniform`.
+pub fn create_lighting_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    let texture = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+        },
+        count: None,
+    };
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Lighting Bind Group Layout"),
+        entries: &[
+            texture(0),
+            texture(1),
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    })
+}
+
+/// Create the pipeline of one lighting pass
+///
+/// `entry_point` names the fragment stage in `LIGHTING_SHADER`. Passes
+/// replace the target's pixels rather than blending.
+pub fn create_lighting_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+    target_format: wgpu::TextureFormat,
+    entry_point: &str,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(&format!("Lighting {} Pipeline", entry_point)),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format: target_format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,136 @@
+// 2D lighting passes
+//
+// `fs_light` turns the normal buffer into the light buffer: the ambient
+// color plus every light reaching the pixel. `fs_composite` multiplies the
+// unlit sprites (albedo) by the light buffer. Both draw one triangle
+// covering the target. The software renderer mirrors these in lighting.rs.
+
+const MAX_LIGHTS: u32 = 64u;
+const MAX_OCCLUDER_SEGMENTS: u32 = 256u;
+
+struct Light {
+    // World position (xy), radius (z) and falloff exponent (w)
+    position: vec4<f32>,
+    // Color times intensity (rgb) and height above the sprites (w)
+    color: vec4<f32>,
+    // Direction (xy) and cosines of the outer and inner cone half angles (zw);
+    // point lights have an outer cosine below -1
+    cone: vec4<f32>,
+    // Whether occluders block the light (x)
+    shadow: vec4<f32>,
+};
+
+struct LightingUniform {
+    inv_view_proj: mat4x4<f32>,
+    ambient: vec4<f32>,
+    // Number of lights (x) and occluder segments (y)
+    counts: vec4<u32>,
+    lights: array<Light, MAX_LIGHTS>,
+    // Segment start (xy) and end (zw) in world space
+    segments: array<vec4<f32>, MAX_OCCLUDER_SEGMENTS>,
+};
+
+@group(0) @binding(0)
+var t_first: texture_2d<f32>;
+
+@group(0) @binding(1)
+var t_second: texture_2d<f32>;
+
+@group(0) @binding(2)
+var<uniform> lighting: LightingUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // (0, 0) at the top-left of the target
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn texel_at(t: texture_2d<f32>, uv: vec2<f32>) -> vec4<f32> {
+    let size = vec2<f32>(textureDimensions(t));
+    let coords = clamp(vec2<i32>(uv * size), vec2<i32>(0), vec2<i32>(size) - 1);
+    return textureLoad(t, coords, 0);
+}
+
+fn cross2(a: vec2<f32>, b: vec2<f32>) -> f32 {
+    return a.x * b.y - a.y * b.x;
+}
+
+// A pixel is in shadow when the line to the light crosses two or more
+// occluder edges: it passes through a shape rather than starting or ending
+// inside one
+fn shadowed(start: vec2<f32>, end: vec2<f32>) -> bool {
+    let r = end - start;
+    var crossings = 0u;
+    for (var i = 0u; i < lighting.counts.y; i++) {
+        let segment = lighting.segments[i];
+        let s = segment.zw - segment.xy;
+        let denom = cross2(r, s);
+        if (denom == 0.0) {
+            continue;
+        }
+        let offset = segment.xy - start;
+        let t = cross2(offset, s) / denom;
+        let u = cross2(offset, r) / denom;
+        if (t > 0.0 && t < 1.0 && u >= 0.0 && u < 1.0) {
+            crossings += 1u;
+            if (crossings >= 2u) {
+                return true;
+            }
+        }
+    }
+    return false;
+}
+
+fn light_at(position: vec2<f32>, normal: vec3<f32>) -> vec3<f32> {
+    var total = lighting.ambient.rgb;
+    for (var i = 0u; i < lighting.counts.x; i++) {
+        let light = lighting.lights[i];
+        let d = light.position.xy - position;
+        let distance = length(d);
+        if (distance >= light.position.z) {
+            continue;
+        }
+        var strength = pow(1.0 - distance / light.position.z, light.position.w);
+        if (light.cone.z >= -1.0 && distance > 0.0) {
+            strength *= smoothstep(light.cone.z, light.cone.w, dot(light.cone.xy, -d / distance));
+        }
+        // Relative to a flat sprite, which is lit as if facing the light
+        let l = normalize(vec3<f32>(d, light.color.w));
+        strength *= clamp(1.0 + dot(normal, l) - l.z, 0.0, 1.0);
+        if (strength <= 0.0) {
+            continue;
+        }
+        if (light.shadow.x > 0.0 && shadowed(position, light.position.xy)) {
+            continue;
+        }
+        total += light.color.rgb * strength;
+    }
+    return total;
+}
+
+// t_first: normal buffer
+@fragment
+fn fs_light(in: VertexOutput) -> @location(0) vec4<f32> {
+    let ndc = vec2<f32>(in.uv.x * 2.0 - 1.0, 1.0 - in.uv.y * 2.0);
+    let world = lighting.inv_view_proj * vec4<f32>(ndc, 0.0, 1.0);
+    let encoded = texel_at(t_first, in.uv).xyz;
+    let normal = normalize(encoded * 2.0 - 1.0);
+    return vec4<f32>(light_at(world.xy / world.w, normal), 1.0);
+}
+
+// t_first: albedo, t_second: light buffer
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let albedo = texel_at(t_first, in.uv);
+    let light = texel_at(t_second, in.uv).rgb;
+    return vec4<f32>(albedo.rgb * light, albedo.a);
+}
@@ -0,0 +1,244 @@
+use super::{create_pipeline, SpriteBlend, SPRITE_VERTEX_SHADER};
+use crate::instancing::SpriteInstanceRaw;
+use longhorn_core::{Material, MaterialParams};
+use std::fmt::Write;
+use thiserror::Error;
+
+/// Bind group holding a material's parameters and textures
+pub const MATERIAL_BIND_GROUP: u32 = 2;
+
+/// Why a material's shader can't be used
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MaterialShaderError {
+    #[error("WGSL parse error: {0}")]
+    Parse(String),
+    #[error("WGSL validation error: {0}")]
+    Validation(String),
+    #[error("material shader must define `@fragment fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>`")]
+    MissingEntryPoint,
+}
+
+/// Offsets of a material's parameters in its uniform buffer
+///
+/// Follows the WGSL uniform layout of the generated `MaterialParams`
+/// struct: `f32` is 4-byte aligned, `vec2<f32>` 8-byte and `vec4<f32>`
+/// 16-byte, and the struct is padded to a multiple of 16 bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterialLayout {
+    /// Offset of each parameter, in floats
+    offsets: Vec<usize>,
+    /// Buffer size, in floats
+    len: usize,
+}
+
+impl MaterialLayout {
+    pub fn new(material: &Material) -> Self {
+        let mut offsets = Vec::with_capacity(material.params.len());
+        let mut len = 0usize;
+        for param in &material.params {
+            let components = param.ty.components();
+            // vec4 aligns to four floats, vec2 to two
+            len = len.next_multiple_of(components.next_power_of_two());
+            offsets.push(len);
+            len += components;
+        }
+        Self {
+            offsets,
+            len: len.max(1).next_multiple_of(4),
+        }
+    }
+
+    /// Size of the uniform buffer in bytes
+    pub fn size(&self) -> u64 {
+        (self.len * std::mem::size_of::<f32>()) as u64
+    }
+
+    /// Uniform data for an entity, from its parameter values or the
+    /// material's defaults
+    pub fn pack(&self, material: &Material, params: Option<&MaterialParams>) -> Vec<f32> {
+        let defaults = MaterialParams::default();
+        let params = params.unwrap_or(&defaults);
+        let mut data = vec![0.0; self.len];
+        for (param, &offset) in material.params.iter().zip(&self.offsets) {
+            let value = params.value(param);
+            data[offset..offset + value.len()].copy_from_slice(value);
+        }
+        data
+    }
+}
+
+/// Full WGSL source of a material
+///
+/// The sprite vertex stage, then the `material` uniform (group 2, binding
+/// 0) and each texture with its sampler (bindings 1 and 2, 3 and 4, ...),
+/// then the material's own source.
+pub fn material_shader_source(material: &Material) -> String {
+    let mut source = String::from(SPRITE_VERTEX_SHADER);
+    source.push_str("\n// Material parameters and textures\nstruct MaterialParams {\n");
+    if material.params.is_empty() {
+        // WGSL structs can't be empty
+        source.push_str("    _unused: f32,\n");
+    }
+    for param in &material.params {
+        let _ = writeln!(source, "    {}: {},", param.name, param.ty.wgsl_type());
+    }
+    let _ = writeln!(
+        source,
+        "}};\n\n@group({}) @binding(0)\nvar<uniform> material: MaterialParams;",
+        MATERIAL_BIND_GROUP
+    );
+    for (index, texture) in material.textures.iter().enumerate() {
+        let binding = 1 + 2 * index;
+        let _ = writeln!(
+            source,
+            "\n@group({group}) @binding({}) var {name}: texture_2d<f32>;\n@group({group}) @binding({}) var {name}_sampler: sampler;",
+            binding,
+            binding + 1,
+            group = MATERIAL_BIND_GROUP,
+            name = texture.name,
+        );
+    }
+    source.push('\n');
+    source.push_str(&material.source);
+    source
+}
+
+/// Build and validate a material's shader
+///
+/// # Returns
+/// The full WGSL source, ready for `create_material_pipeline`
+pub fn compile_material_shader(material: &Material) -> Result<String, MaterialShaderError> {
+    let source = material_shader_source(material);
+    let module = naga::front::wgsl::parse_str(&source)
+        .map_err(|e| MaterialShaderError::Parse(e.emit_to_string(&source)))?;
+    naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::empty())
+        .validate(&module)
+        .map_err(|e| MaterialShaderError::Validation(e.emit_to_string(&source)))?;
+
+    let has_fragment = module
+        .entry_points
+        .iter()
+        .any(|entry| entry.name == "fs_main" && entry.stage == naga::ShaderStage::Fragment);
+    if !has_fragment {
+        return Err(MaterialShaderError::MissingEntryPoint);
+    }
+    Ok(source)
+}
+
+/// Create the bind group layout for a material with `texture_count` textures
+pub fn create_material_bind_group_layout(device: &wgpu::Device, texture_count: usize) -> wgpu::BindGroupLayout {
+    let mut entries = vec![wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }];
+    for index in 0..texture_count as u32 {
+        entries.push(wgpu::BindGroupLayoutEntry {
+            binding: 1 + 2 * index,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            },
+            count: None,
+        });
+        entries.push(wgpu::BindGroupLayoutEntry {
+            binding: 2 + 2 * index,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        });
+    }
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Material Bind Group Layout"),
+        entries: &entries,
+    })
+}
+
+/// Create the instanced sprite pipeline for a material
+///
+/// `source` comes from `compile_material_shader`. Materials blend with
+/// straight alpha.
+pub fn create_material_pipeline(
+    device: &wgpu::Device,
+    label: &str,
+    source: &str,
+    surface_format: wgpu::TextureFormat,
+    camera_bind_group_layout: &wgpu::BindGroupLayout,
+    texture_bind_group_layout: &wgpu::BindGroupLayout,
+    material_bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    create_pipeline(
+        device,
+        label,
+        source,
+        SpriteInstanceRaw::desc(),
+        surface_format,
+        &[camera_bind_group_layout, texture_bind_group_layout, material_bind_group_layout],
+        SpriteBlend::Straight,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::{MaterialParamType, MaterialTexture, MaterialValue};
+
+    const FLASH: &str = "
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_diffuse, s_diffuse, in.tex_coords) * in.color;
+    let noise = textureSample(noise, noise_sampler, in.tex_coords).r;
+    let rgb = mix(color.rgb, material.tint.rgb, material.flash);
+    return vec4<f32>(rgb, color.a * step(material.offset.x, noise));
+}
+";
+
+    fn flash() -> Material {
+        let mut material = Material::new(FLASH)
+            .with_param("flash", MaterialParamType::Float, MaterialValue::Float(0.0))
+            .with_param("offset", MaterialParamType::Vec2, MaterialValue::Vec2([0.0, 0.0]))
+            .with_param("tint", MaterialParamType::Color, MaterialValue::Vec4([1.0; 4]));
+        material.textures.push(MaterialTexture {
+            name: "noise".to_string(),
+            image: "noise.png".to_string(),
+            texture: longhorn_core::AssetId::new(0),
+        });
+        material
+    }
+
+    #[test]
+    fn test_uniform_layout() {
+        let material = flash();
+        let layout = MaterialLayout::new(&material);
+        // flash at 0, offset aligned to 8 bytes, tint to 16
+        assert_eq!(layout.offsets, vec![0, 2, 4]);
+        assert_eq!(layout.size(), 32);
+
+        let params = MaterialParams::new().with("flash", MaterialValue::Float(0.75));
+        let data = layout.pack(&material, Some(&params));
+        assert_eq!(data, vec![0.75, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(MaterialLayout::new(&Material::new("")).size(), 16);
+    }
+
+    #[test]
+    fn test_material_shaders_compile() {
+        compile_material_shader(&flash()).expect("material should compile");
+
+        let mut undeclared = flash();
+        undeclared.params.pop();
+        assert!(matches!(
+            compile_material_shader(&undeclared),
+            Err(MaterialShaderError::Parse(message)) if message.contains("tint")
+        ));
+
+        let vertex_only = Material::new("fn helper() -> f32 { return 1.0; }");
+        assert_eq!(compile_material_shader(&vertex_only), Err(MaterialShaderError::MissingEntryPoint));
+    }
+}
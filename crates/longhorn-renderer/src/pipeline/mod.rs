@@ -1,6 +1,25 @@
 /// WGSL shader source for sprite rendering
 pub const SPRITE_SHADER: &str = include_str!("sprite.wgsl");
 
+/// WGSL source shared by instanced sprite shaders: camera, sprite texture,
+/// `VertexOutput` and the `vs_main` vertex stage
+pub const SPRITE_VERTEX_SHADER: &str = include_str!("sprite_vertex.wgsl");
+
+/// WGSL shader source for instanced sprite rendering
+pub const INSTANCED_SPRITE_SHADER: &str =
+    concat!(include_str!("sprite_vertex.wgsl"), "\n", include_str!("sprite_instanced.wgsl"));
+
+mod lighting;
+mod material;
+mod post_process;
+mod shape;
+
+pub use lighting::*;
+pub use material::*;
+pub use post_process::*;
+pub use shape::*;
+
+use crate::instancing::SpriteInstanceRaw;
 use crate::sprite_batch::SpriteVertex;
 use bytemuck::{Pod, Zeroable};
 use wgpu;
@@ -36,33 +55,139 @@ pub fn create_sprite_pipeline(
     surface_format: wgpu::TextureFormat,
     camera_bind_group_layout: &wgpu::BindGroupLayout,
     texture_bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    create_pipeline(
+        device,
+        "Sprite",
+        SPRITE_SHADER,
+        SpriteVertex::desc(),
+        surface_format,
+        &[camera_bind_group_layout, texture_bind_group_layout],
+        SpriteBlend::Straight,
+    )
+}
+
+/// Create the instanced sprite pipeline
+///
+/// Draws six vertices per instance; the quad is generated in the vertex
+/// shader from the `SpriteInstanceRaw` instance buffer.
+pub fn create_instanced_sprite_pipeline(
+    device: &wgpu::Device,
+    surface_format: wgpu::TextureFormat,
+    camera_bind_group_layout: &wgpu::BindGroupLayout,
+    texture_bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    create_pipeline(
+        device,
+        "Instanced Sprite",
+        INSTANCED_SPRITE_SHADER,
+        SpriteInstanceRaw::desc(),
+        surface_format,
+        &[camera_bind_group_layout, texture_bind_group_layout],
+        SpriteBlend::Straight,
+    )
+}
+
+/// Create the instanced sprite pipeline for premultiplied-alpha textures
+pub fn create_premultiplied_sprite_pipeline(
+    device: &wgpu::Device,
+    surface_format: wgpu::TextureFormat,
+    camera_bind_group_layout: &wgpu::BindGroupLayout,
+    texture_bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    create_pipeline(
+        device,
+        "Premultiplied Sprite",
+        INSTANCED_SPRITE_SHADER,
+        SpriteInstanceRaw::desc(),
+        surface_format,
+        &[camera_bind_group_layout, texture_bind_group_layout],
+        SpriteBlend::Premultiplied,
+    )
+}
+
+/// Create the instanced pipeline drawing sprites into a lit view's normal buffer
+///
+/// Expects instances built by `normal_instance`.
+pub fn create_normal_sprite_pipeline(
+    device: &wgpu::Device,
+    camera_bind_group_layout: &wgpu::BindGroupLayout,
+    texture_bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    create_pipeline(
+        device,
+        "Normal Sprite",
+        INSTANCED_SPRITE_SHADER,
+        SpriteInstanceRaw::desc(),
+        NORMAL_BUFFER_FORMAT,
+        &[camera_bind_group_layout, texture_bind_group_layout],
+        SpriteBlend::Normal,
+    )
+}
+
+/// How a pipeline blends texture color into the target
+#[derive(Clone, Copy)]
+enum SpriteBlend {
+    /// Straight (unassociated) alpha
+    Straight,
+    /// Color already multiplied by alpha
+    Premultiplied,
+    /// Encoded normals, blended with straight alpha
+    Normal,
+}
+
+impl SpriteBlend {
+    fn fragment_entry_point(self) -> &'static str {
+        match self {
+            SpriteBlend::Straight => "fs_main",
+            SpriteBlend::Premultiplied => "fs_premultiplied",
+            SpriteBlend::Normal => "fs_normal",
+        }
+    }
+
+    fn state(self) -> wgpu::BlendState {
+        match self {
+            SpriteBlend::Straight | SpriteBlend::Normal => wgpu::BlendState::ALPHA_BLENDING,
+            SpriteBlend::Premultiplied => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        }
+    }
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    label: &str,
+    shader_source: &str,
+    buffer: wgpu::VertexBufferLayout<'static>,
+    surface_format: wgpu::TextureFormat,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    blend: SpriteBlend,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Sprite Shader"),
-        source: wgpu::ShaderSource::Wgsl(SPRITE_SHADER.into()),
+        label: Some(&format!("{} Shader", label)),
+        source: wgpu::ShaderSource::Wgsl(shader_source.into()),
     });
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: Some("Sprite Pipeline Layout"),
-        bind_group_layouts: &[camera_bind_group_layout, texture_bind_group_layout],
+        label: Some(&format!("{} Pipeline Layout", label)),
+        bind_group_layouts,
         push_constant_ranges: &[],
     });
 
     device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: Some("Sprite Pipeline"),
+        label: Some(&format!("{} Pipeline", label)),
         layout: Some(&pipeline_layout),
         vertex: wgpu::VertexState {
             module: &shader,
             entry_point: "vs_main",
-            buffers: &[SpriteVertex::desc()],
+            buffers: &[buffer],
             compilation_options: Default::default(),
         },
         fragment: Some(wgpu::FragmentState {
             module: &shader,
-            entry_point: "fs_main",
+            entry_point: blend.fragment_entry_point(),
             targets: &[Some(wgpu::ColorTargetState {
                 format: surface_format,
-                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                blend: Some(blend.state()),
                 write_mask: wgpu::ColorWrites::ALL,
             })],
             compilation_options: Default::default(),
@@ -86,3 +211,24 @@ pub fn create_sprite_pipeline(
         cache: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(source: &str) {
+        let module = naga::front::wgsl::parse_str(source).expect("shader should parse");
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::empty())
+            .validate(&module)
+            .expect("shader should validate");
+    }
+
+    #[test]
+    fn test_shaders_validate() {
+        validate(SPRITE_SHADER);
+        validate(INSTANCED_SPRITE_SHADER);
+        validate(POST_PROCESS_SHADER);
+        validate(LIGHTING_SHADER);
+        validate(SHAPE_SHADER);
+    }
+}
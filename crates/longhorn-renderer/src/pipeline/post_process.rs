@@ -0,0 +1,103 @@
+use bytemuck::{Pod, Zeroable};
+
+/// WGSL source of the post-processing passes
+pub const POST_PROCESS_SHADER: &str = include_str!("post_process.wgsl");
+
+/// Uniform data of one post-processing pass
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct PostUniform {
+    /// Source size in pixels and one over it
+    pub size: [f32; 4],
+    pub params: [f32; 4],
+    pub extra: [f32; 4],
+}
+
+impl PostUniform {
+    pub fn new(width: u32, height: u32, params: [f32; 4], extra: [f32; 4]) -> Self {
+        let (width, height) = (width as f32, height as f32);
+        Self {
+            size: [width, height, 1.0 / width, 1.0 / height],
+            params,
+            extra,
+        }
+    }
+}
+
+/// Create the bind group layout shared by all post-processing passes
+///
+/// Binding 0 is the source texture, 1 a linear clamping sampler, 2 the
+/// `PostUniform` and 3 the auxiliary texture.
+pub fn create_post_process_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    let texture = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+        },
+        count: None,
+    };
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Post Process Bind Group Layout"),
+        entries: &[
+            texture(0),
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            texture(3),
+        ],
+    })
+}
+
+/// Create the pipeline of one post-processing pass
+///
+/// `entry_point` names the fragment stage in `POST_PROCESS_SHADER`. Passes
+/// replace the target's pixels rather than blending.
+pub fn create_post_process_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    layout: &wgpu::PipelineLayout,
+    target_format: wgpu::TextureFormat,
+    entry_point: &str,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(&format!("Post Process {} Pipeline", entry_point)),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format: target_format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,135 @@
+// Full-screen post-processing passes
+//
+// Every pass draws one triangle covering the target and reads `t_source`;
+// color grading and the bloom combine also read `t_aux`. The software
+// renderer mirrors these in post_process.rs.
+
+struct PostUniform {
+    // Source size in pixels (xy) and one over it (zw)
+    size: vec4<f32>,
+    params: vec4<f32>,
+    extra: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+
+@group(0) @binding(1)
+var s_linear: sampler;
+
+@group(0) @binding(2)
+var<uniform> post: PostUniform;
+
+@group(0) @binding(3)
+var t_aux: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // (0, 0) at the top-left of the target
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn source(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(t_source, s_linear, uv, 0.0);
+}
+
+fn encode_srgb(c: vec3<f32>) -> vec3<f32> {
+    let low = c * 12.92;
+    let high = 1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, c <= vec3<f32>(0.0031308));
+}
+
+@fragment
+fn fs_copy(in: VertexOutput) -> @location(0) vec4<f32> {
+    return source(in.uv);
+}
+
+// params: intensity, radius, softness; extra: color
+@fragment
+fn fs_vignette(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = source(in.uv);
+    let distance = length(in.uv - 0.5) * sqrt(2.0);
+    let amount = smoothstep(post.params.y, post.params.y + post.params.z, distance) * post.params.x;
+    return vec4<f32>(mix(color.rgb, post.extra.rgb, amount), color.a);
+}
+
+// params: block size in pixels
+@fragment
+fn fs_pixelate(in: VertexOutput) -> @location(0) vec4<f32> {
+    let block = post.params.x;
+    let pixel = floor(in.uv * post.size.xy);
+    let center = floor(pixel / block) * block + floor(block / 2.0);
+    let max_pixel = vec2<i32>(post.size.xy) - 1;
+    return textureLoad(t_source, clamp(vec2<i32>(center), vec2<i32>(0), max_pixel), 0);
+}
+
+// params: curvature, scanline strength
+@fragment
+fn fs_crt(in: VertexOutput) -> @location(0) vec4<f32> {
+    var c = in.uv * 2.0 - 1.0;
+    c = c * (1.0 + post.params.x * c.yx * c.yx);
+    let uv = c * 0.5 + 0.5;
+    if any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+    let color = source(uv);
+    let row = floor(uv.y * post.size.y);
+    let scanline = 1.0 - post.params.y * (row % 2.0);
+    return vec4<f32>(color.rgb * scanline, color.a);
+}
+
+// params: intensity, LUT size; t_aux: LUT strip
+@fragment
+fn fs_color_grading(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = source(in.uv);
+    let n = post.params.y;
+    let c = encode_srgb(clamp(color.rgb, vec3<f32>(0.0), vec3<f32>(1.0)));
+    let blue = c.b * (n - 1.0);
+    let slice0 = floor(blue);
+    let slice1 = min(slice0 + 1.0, n - 1.0);
+    let texel = c.rg * (n - 1.0) + 0.5;
+    let lut_size = vec2<f32>(n * n, n);
+    let a = textureSampleLevel(t_aux, s_linear, vec2<f32>(slice0 * n + texel.x, texel.y) / lut_size, 0.0);
+    let b = textureSampleLevel(t_aux, s_linear, vec2<f32>(slice1 * n + texel.x, texel.y) / lut_size, 0.0);
+    let graded = mix(a.rgb, b.rgb, blue - slice0);
+    return vec4<f32>(mix(color.rgb, graded, post.params.x), color.a);
+}
+
+// params: threshold
+@fragment
+fn fs_bloom_extract(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = source(in.uv);
+    let brightest = max(color.r, max(color.g, color.b));
+    let amount = max(brightest - post.params.x, 0.0) / max(brightest, 0.0001);
+    return vec4<f32>(color.rgb * amount, 1.0);
+}
+
+// params: tap step in pixels (xy)
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    var weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+    let step = post.params.xy * post.size.zw;
+    var sum = source(in.uv) * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = step * f32(i);
+        sum += (source(in.uv + offset) + source(in.uv - offset)) * weights[i];
+    }
+    return sum;
+}
+
+// params: intensity; t_aux: blurred highlights
+@fragment
+fn fs_bloom_combine(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = source(in.uv);
+    let glow = textureSampleLevel(t_aux, s_linear, in.uv, 0.0);
+    return vec4<f32>(color.rgb + glow.rgb * post.params.x, color.a);
+}
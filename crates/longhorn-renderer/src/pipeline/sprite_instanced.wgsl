@@ -0,0 +1,29 @@
+// Fragment stages of the instanced sprite shader, appended to sprite_vertex.wgsl
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    return tex_color * in.color;
+}
+
+// Textures imported with premultiplied alpha; the tint is premultiplied to match
+@fragment
+fn fs_premultiplied(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    return tex_color * vec4<f32>(in.color.rgb * in.color.a, in.color.a);
+}
+
+// Normal buffer pass of lit views. The tint carries the sprite's rotation as
+// (cos, sin), whether the texture is a normal map (z = 0 for flat sprites,
+// -1 when mirrored on one axis) and the alpha
+@fragment
+fn fs_normal(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    var normal = vec3<f32>(0.0, 0.0, 1.0);
+    if (in.color.z != 0.0) {
+        let local = tex_color.xyz * 2.0 - 1.0;
+        let x = local.x * in.color.z;
+        normal = vec3<f32>(x * in.color.x - local.y * in.color.y, x * in.color.y + local.y * in.color.x, local.z);
+    }
+    return vec4<f32>(normal * 0.5 + 0.5, tex_color.a * in.color.w);
+}
@@ -0,0 +1,67 @@
+// Camera uniform, sprite texture and the instanced vertex stage shared by
+// the built-in sprite shader and materials
+
+// Camera uniform
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+// Texture and sampler
+@group(1) @binding(0)
+var t_diffuse: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s_diffuse: sampler;
+
+// Per-sprite instance input
+struct InstanceInput {
+    @location(0) position: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) rotation: f32,
+    @location(3) uv_rect: vec4<f32>,
+    @location(4) color: vec4<f32>,
+};
+
+// Vertex output / Fragment input
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+// Quad corners in the same order as SpriteBatch::generate_vertices
+// Triangle 1: top-left, bottom-left, bottom-right
+// Triangle 2: top-left, bottom-right, top-right
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-0.5, 0.5),
+        vec2<f32>(-0.5, -0.5),
+        vec2<f32>(0.5, -0.5),
+        vec2<f32>(-0.5, 0.5),
+        vec2<f32>(0.5, -0.5),
+        vec2<f32>(0.5, 0.5),
+    );
+    var uvs = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+    );
+
+    let corner = corners[vertex_index] * instance.size;
+    let s = sin(instance.rotation);
+    let c = cos(instance.rotation);
+    let world = instance.position + vec2<f32>(corner.x * c - corner.y * s, corner.x * s + corner.y * c);
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world, 0.0, 1.0);
+    out.tex_coords = mix(instance.uv_rect.xy, instance.uv_rect.zw, uvs[vertex_index]);
+    out.color = instance.color;
+    return out;
+}
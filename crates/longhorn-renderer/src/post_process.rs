@@ -0,0 +1,864 @@
+use crate::{
+    backend::TextureLookup,
+    pipeline::{create_post_process_bind_group_layout, create_post_process_pipeline, PostUniform, POST_PROCESS_SHADER},
+    software::{linear_to_srgb, sample},
+    texture::{self, GpuTexture, TextureCache},
+    RenderStats, ScreenRect,
+};
+use glam::{Vec2, Vec3, Vec4};
+use longhorn_assets::{TextureData, TextureFilter, TextureImportSettings, TextureWrap};
+use longhorn_core::{AssetId, PostEffect};
+use std::collections::HashMap;
+
+/// Weights of the 9-tap blur, from the center tap outwards
+const BLUR_WEIGHTS: [f32; 5] = [0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216];
+
+/// Fragment stage of a post-processing pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PassKind {
+    Copy,
+    Vignette,
+    Pixelate,
+    Crt,
+    ColorGrading,
+    BloomExtract,
+    Blur,
+    BloomCombine,
+}
+
+impl PassKind {
+    const ALL: [PassKind; 8] = [
+        PassKind::Copy,
+        PassKind::Vignette,
+        PassKind::Pixelate,
+        PassKind::Crt,
+        PassKind::ColorGrading,
+        PassKind::BloomExtract,
+        PassKind::Blur,
+        PassKind::BloomCombine,
+    ];
+
+    fn entry_point(self) -> &'static str {
+        match self {
+            PassKind::Copy => "fs_copy",
+            PassKind::Vignette => "fs_vignette",
+            PassKind::Pixelate => "fs_pixelate",
+            PassKind::Crt => "fs_crt",
+            PassKind::ColorGrading => "fs_color_grading",
+            PassKind::BloomExtract => "fs_bloom_extract",
+            PassKind::Blur => "fs_blur",
+            PassKind::BloomCombine => "fs_bloom_combine",
+        }
+    }
+}
+
+/// An image within the passes of one effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    /// What the effect is applied to
+    Input,
+    /// Intermediate image
+    Temp(usize),
+    /// The effect's result
+    Output,
+}
+
+/// Second texture a pass reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aux {
+    None,
+    Slot(Slot),
+    Lut(AssetId),
+}
+
+/// One full-screen pass of an effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PassPlan {
+    kind: PassKind,
+    params: [f32; 4],
+    extra: [f32; 4],
+    source: Slot,
+    aux: Aux,
+    output: Slot,
+}
+
+impl PassPlan {
+    fn new(kind: PassKind, params: [f32; 4], source: Slot, output: Slot) -> Self {
+        Self {
+            kind,
+            params,
+            extra: [0.0; 4],
+            source,
+            aux: Aux::None,
+            output,
+        }
+    }
+}
+
+/// The passes running an effect, shared by the GPU and CPU implementations
+///
+/// # Returns
+/// `None` if the effect can't run, e.g. its LUT isn't loaded or isn't a LUT strip
+fn effect_passes(effect: &PostEffect, textures: &dyn TextureLookup) -> Option<Vec<PassPlan>> {
+    use Slot::{Input, Output, Temp};
+    let passes = match effect {
+        PostEffect::Vignette(vignette) => {
+            let [r, g, b] = vignette.color;
+            vec![PassPlan {
+                extra: [r, g, b, 0.0],
+                ..PassPlan::new(
+                    PassKind::Vignette,
+                    [vignette.intensity, vignette.radius, vignette.softness.max(1e-4), 0.0],
+                    Input,
+                    Output,
+                )
+            }]
+        }
+        PostEffect::Pixelate(pixelate) => vec![PassPlan::new(
+            PassKind::Pixelate,
+            [pixelate.pixel_size.max(1) as f32, 0.0, 0.0, 0.0],
+            Input,
+            Output,
+        )],
+        PostEffect::Crt(crt) => vec![PassPlan::new(
+            PassKind::Crt,
+            [crt.curvature, crt.scanlines, 0.0, 0.0],
+            Input,
+            Output,
+        )],
+        PostEffect::ColorGrading(grading) => {
+            let size = lut_size(textures.texture(grading.lut)?)?;
+            vec![PassPlan {
+                aux: Aux::Lut(grading.lut),
+                ..PassPlan::new(PassKind::ColorGrading, [grading.intensity, size as f32, 0.0, 0.0], Input, Output)
+            }]
+        }
+        PostEffect::Bloom(bloom) => vec![
+            PassPlan::new(PassKind::BloomExtract, [bloom.threshold, 0.0, 0.0, 0.0], Input, Temp(0)),
+            PassPlan::new(PassKind::Blur, [bloom.radius, 0.0, 0.0, 0.0], Temp(0), Temp(1)),
+            PassPlan::new(PassKind::Blur, [0.0, bloom.radius, 0.0, 0.0], Temp(1), Temp(0)),
+            PassPlan {
+                aux: Aux::Slot(Temp(0)),
+                ..PassPlan::new(PassKind::BloomCombine, [bloom.intensity, 0.0, 0.0, 0.0], Input, Output)
+            },
+        ],
+    };
+    Some(passes)
+}
+
+/// Slice size of a LUT strip (`size * size` by `size` texels)
+fn lut_size(lut: &TextureData) -> Option<u32> {
+    (lut.height >= 2 && lut.width == lut.height * lut.height).then_some(lut.height)
+}
+
+/// Number of intermediate images an effect's passes use
+fn temp_count(passes: &[PassPlan]) -> usize {
+    passes
+        .iter()
+        .filter_map(|pass| match pass.output {
+            Slot::Temp(index) => Some(index + 1),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Linear-space RGBA image, rows top to bottom
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct LinearImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Vec4>,
+}
+
+impl LinearImage {
+    pub fn new(width: u32, height: u32, fill: Vec4) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; (width * height) as usize],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Vec4 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn pixel_mut(&mut self, x: u32, y: u32) -> &mut Vec4 {
+        &mut self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Get a pixel, clamping coordinates to the edges
+    fn load(&self, x: i64, y: i64) -> Vec4 {
+        let x = x.clamp(0, self.width as i64 - 1);
+        let y = y.clamp(0, self.height as i64 - 1);
+        self.pixels[(y * self.width as i64 + x) as usize]
+    }
+
+    /// Sample with bilinear filtering and clamped edges, as the GPU passes do
+    fn sample(&self, uv: Vec2) -> Vec4 {
+        let p = uv * Vec2::new(self.width as f32, self.height as f32) - 0.5;
+        let base = p.floor();
+        let t = p - base;
+        let (x, y) = (base.x as i64, base.y as i64);
+        let top = self.load(x, y).lerp(self.load(x + 1, y), t.x);
+        let bottom = self.load(x, y + 1).lerp(self.load(x + 1, y + 1), t.x);
+        top.lerp(bottom, t.y)
+    }
+
+    /// Copy an image in with its top-left corner at `(x, y)`
+    pub fn blit(&mut self, x: u32, y: u32, image: &LinearImage) {
+        for row in 0..image.height {
+            let start = ((y + row) * self.width + x) as usize;
+            let source = (row * image.width) as usize;
+            self.pixels[start..start + image.width as usize]
+                .copy_from_slice(&image.pixels[source..source + image.width as usize]);
+        }
+    }
+
+    /// Encode as sRGB into RGBA8 pixels
+    pub fn write_rgba8(&self, pixels: &mut [u8]) {
+        for (pixel, color) in pixels.chunks_exact_mut(4).zip(&self.pixels) {
+            pixel[0] = linear_to_srgb(color.x);
+            pixel[1] = linear_to_srgb(color.y);
+            pixel[2] = linear_to_srgb(color.z);
+            pixel[3] = (color.w.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+
+    /// Encode as sRGB texture data
+    pub fn to_texture_data(&self) -> TextureData {
+        let mut pixels = vec![0; self.pixels.len() * 4];
+        self.write_rgba8(&mut pixels);
+        TextureData {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+}
+
+/// Second image a CPU pass reads
+enum CpuAux<'a> {
+    None,
+    Image(&'a LinearImage),
+    Lut(&'a TextureData, TextureImportSettings),
+}
+
+/// Apply effects to an image on the CPU, matching `PostProcessPass`
+///
+/// Effects that can't run (see `PostEffect`) are skipped.
+pub(crate) fn apply_effects(mut image: LinearImage, effects: &[PostEffect], textures: &dyn TextureLookup) -> LinearImage {
+    if image.pixels.is_empty() {
+        return image;
+    }
+    for effect in effects {
+        let Some(passes) = effect_passes(effect, textures) else {
+            log::trace!("Skipping post effect that can't run: {:?}", effect);
+            continue;
+        };
+        let mut temps = vec![LinearImage::default(); temp_count(&passes)];
+        let mut output = None;
+        for pass in &passes {
+            let slot_image = |slot: Slot| match slot {
+                Slot::Temp(index) => &temps[index],
+                Slot::Input | Slot::Output => &image,
+            };
+            let aux = match pass.aux {
+                Aux::None => CpuAux::None,
+                Aux::Slot(slot) => CpuAux::Image(slot_image(slot)),
+                Aux::Lut(id) => match textures.texture(id) {
+                    Some(lut) => CpuAux::Lut(
+                        lut,
+                        TextureImportSettings {
+                            filter: TextureFilter::Linear,
+                            wrap: TextureWrap::Clamp,
+                            ..textures.texture_settings(id)
+                        },
+                    ),
+                    None => CpuAux::None,
+                },
+            };
+            let result = run_pass(pass, slot_image(pass.source), aux);
+            match pass.output {
+                Slot::Temp(index) => temps[index] = result,
+                Slot::Input | Slot::Output => output = Some(result),
+            }
+        }
+        if let Some(output) = output {
+            image = output;
+        }
+    }
+    image
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Run one pass over every pixel, as its fragment stage would
+fn run_pass(pass: &PassPlan, source: &LinearImage, aux: CpuAux<'_>) -> LinearImage {
+    let size = Vec2::new(source.width as f32, source.height as f32);
+    let [p0, p1, p2, _] = pass.params;
+    let mut output = LinearImage::new(source.width, source.height, Vec4::ZERO);
+
+    for y in 0..source.height {
+        for x in 0..source.width {
+            let uv = (Vec2::new(x as f32, y as f32) + 0.5) / size;
+            let color = match pass.kind {
+                PassKind::Copy => source.sample(uv),
+                PassKind::Vignette => {
+                    let color = source.sample(uv);
+                    let distance = (uv - 0.5).length() * std::f32::consts::SQRT_2;
+                    let amount = smoothstep(p1, p1 + p2, distance) * p0;
+                    let tint = Vec3::new(pass.extra[0], pass.extra[1], pass.extra[2]);
+                    color.truncate().lerp(tint, amount).extend(color.w)
+                }
+                PassKind::Pixelate => {
+                    let pixel = (uv * size).floor();
+                    let center = (pixel / p0).floor() * p0 + (p0 / 2.0).floor();
+                    source.load(center.x as i64, center.y as i64)
+                }
+                PassKind::Crt => {
+                    let mut c = uv * 2.0 - 1.0;
+                    c *= 1.0 + p0 * Vec2::new(c.y * c.y, c.x * c.x);
+                    let uv = c * 0.5 + 0.5;
+                    if uv.cmplt(Vec2::ZERO).any() || uv.cmpgt(Vec2::ONE).any() {
+                        Vec4::new(0.0, 0.0, 0.0, 1.0)
+                    } else {
+                        let color = source.sample(uv);
+                        let row = (uv.y * size.y).floor();
+                        let scanline = 1.0 - p1 * (row % 2.0);
+                        (color.truncate() * scanline).extend(color.w)
+                    }
+                }
+                PassKind::ColorGrading => {
+                    let color = source.sample(uv);
+                    let CpuAux::Lut(lut, settings) = &aux else {
+                        output.pixels[(y * source.width + x) as usize] = color;
+                        continue;
+                    };
+                    let n = p1;
+                    let c = color.truncate().clamp(Vec3::ZERO, Vec3::ONE);
+                    let c = Vec3::new(
+                        texture::linear_to_srgb(c.x),
+                        texture::linear_to_srgb(c.y),
+                        texture::linear_to_srgb(c.z),
+                    );
+                    let blue = c.z * (n - 1.0);
+                    let slice0 = blue.floor();
+                    let slice1 = (slice0 + 1.0).min(n - 1.0);
+                    let texel = Vec2::new(c.x, c.y) * (n - 1.0) + 0.5;
+                    let lut_size = Vec2::new(n * n, n);
+                    let a = sample(lut, settings, Vec2::new(slice0 * n + texel.x, texel.y) / lut_size);
+                    let b = sample(lut, settings, Vec2::new(slice1 * n + texel.x, texel.y) / lut_size);
+                    let graded = a.truncate().lerp(b.truncate(), blue - slice0);
+                    color.truncate().lerp(graded, p0).extend(color.w)
+                }
+                PassKind::BloomExtract => {
+                    let color = source.sample(uv);
+                    let brightest = color.x.max(color.y).max(color.z);
+                    let amount = (brightest - p0).max(0.0) / brightest.max(0.0001);
+                    (color.truncate() * amount).extend(1.0)
+                }
+                PassKind::Blur => {
+                    let step = Vec2::new(p0, p1) / size;
+                    let mut sum = source.sample(uv) * BLUR_WEIGHTS[0];
+                    for (i, weight) in BLUR_WEIGHTS.iter().enumerate().skip(1) {
+                        let offset = step * i as f32;
+                        sum += (source.sample(uv + offset) + source.sample(uv - offset)) * *weight;
+                    }
+                    sum
+                }
+                PassKind::BloomCombine => {
+                    let color = source.sample(uv);
+                    let glow = match &aux {
+                        CpuAux::Image(glow) => glow.sample(uv),
+                        _ => Vec4::ZERO,
+                    };
+                    (color.truncate() + glow.truncate() * p0).extend(color.w)
+                }
+            };
+            output.pixels[(y * source.width + x) as usize] = color;
+        }
+    }
+    output
+}
+
+/// Texture a pass renders into, kept across frames
+struct ScratchTexture {
+    view: wgpu::TextureView,
+    width: u32,
+    height: u32,
+    /// Handed out this frame
+    used: bool,
+}
+
+/// One prepared pass of a `PostChain`
+struct PreparedPass {
+    kind: PassKind,
+    bind_group: wgpu::BindGroup,
+    /// Scratch texture written, or `None` for the chain's destination
+    output: Option<usize>,
+}
+
+/// A view's effects, prepared for drawing
+pub struct PostChain {
+    /// Scratch texture the view is drawn into before its effects
+    input: usize,
+    passes: Vec<PreparedPass>,
+}
+
+/// Runs camera post-processing effects on the GPU
+///
+/// A view with effects is drawn into a scratch texture the size of its
+/// viewport; each effect then runs one or more full-screen passes, the last
+/// writing into the view's area of the real target. Scratch textures are
+/// reused across frames and dropped once a frame doesn't need them.
+pub struct PostProcessPass {
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipelines: HashMap<PassKind, wgpu::RenderPipeline>,
+    sampler: wgpu::Sampler,
+    target_format: wgpu::TextureFormat,
+    scratch: Vec<ScratchTexture>,
+    /// One uniform buffer per pass prepared this frame
+    uniforms: Vec<wgpu::Buffer>,
+    next_uniform: usize,
+    luts: TextureCache,
+    /// Texture versions (see `TextureLookup::texture_version`) as uploaded
+    lut_versions: HashMap<AssetId, u64>,
+    /// Bound as the auxiliary texture by passes that don't read one
+    blank: Option<GpuTexture>,
+}
+
+impl PostProcessPass {
+    /// Create the post-processing passes for targets of the given format
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = create_post_process_bind_group_layout(device);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Post Process Shader"),
+            source: wgpu::ShaderSource::Wgsl(POST_PROCESS_SHADER.into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post Process Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipelines = PassKind::ALL
+            .iter()
+            .map(|&kind| {
+                let pipeline = create_post_process_pipeline(device, &shader, &layout, target_format, kind.entry_point());
+                (kind, pipeline)
+            })
+            .collect();
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Process Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            bind_group_layout,
+            pipelines,
+            sampler,
+            target_format,
+            scratch: Vec::new(),
+            uniforms: Vec::new(),
+            next_uniform: 0,
+            luts: TextureCache::new(),
+            lut_versions: HashMap::new(),
+            blank: None,
+        }
+    }
+
+    /// Start a frame: free scratch textures the last frame didn't use
+    pub fn begin_frame(&mut self) {
+        self.scratch.retain(|scratch| scratch.used);
+        for scratch in &mut self.scratch {
+            scratch.used = false;
+        }
+        self.next_uniform = 0;
+    }
+
+    /// Number of scratch textures currently allocated
+    pub fn scratch_texture_count(&self) -> usize {
+        self.scratch.len()
+    }
+
+    /// Plan a view's effects, uploading their uniforms and LUTs
+    ///
+    /// `texture_layout` is the sprite texture bind group layout LUTs are
+    /// uploaded with. Call `begin_frame` first each frame.
+    ///
+    /// # Returns
+    /// `None` if none of the effects can run
+    #[allow(clippy::too_many_arguments)]
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_layout: &wgpu::BindGroupLayout,
+        effects: &[PostEffect],
+        width: u32,
+        height: u32,
+        textures: &dyn TextureLookup,
+        stats: &mut RenderStats,
+    ) -> Option<PostChain> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let effects: Vec<Vec<PassPlan>> = effects
+            .iter()
+            .filter_map(|effect| {
+                let passes = effect_passes(effect, textures);
+                if passes.is_none() {
+                    log::trace!("Skipping post effect that can't run: {:?}", effect);
+                }
+                passes
+            })
+            .collect();
+        if effects.is_empty() {
+            return None;
+        }
+
+        for pass in effects.iter().flatten() {
+            if let Aux::Lut(id) = pass.aux {
+                self.upload_lut(device, queue, texture_layout, id, textures, stats);
+            }
+        }
+        if self.blank.is_none() {
+            let blank = TextureData {
+                width: 1,
+                height: 1,
+                pixels: vec![0; 4],
+            };
+            self.blank = Some(GpuTexture::from_texture_data(
+                device,
+                queue,
+                texture_layout,
+                &blank,
+                &TextureImportSettings::default(),
+                Some("Blank Post Process Texture"),
+            ));
+        }
+
+        let input = self.scratch(device, width, height);
+        let mut current = input;
+        let mut passes = Vec::new();
+        for (index, effect) in effects.iter().enumerate() {
+            let temps: Vec<usize> = (0..temp_count(effect)).map(|_| self.scratch(device, width, height)).collect();
+            let output = (index + 1 < effects.len()).then(|| self.scratch(device, width, height));
+            let image = |slot: Slot| match slot {
+                Slot::Input => Some(current),
+                Slot::Temp(index) => Some(temps[index]),
+                Slot::Output => output,
+            };
+
+            for pass in effect {
+                let uniform = PostUniform::new(width, height, pass.params, pass.extra);
+                let buffer = self.uniform(device);
+                queue.write_buffer(&self.uniforms[buffer], 0, bytemuck::bytes_of(&uniform));
+                stats.buffer_uploads += 1;
+
+                let blank = self.blank.as_ref().map(|blank| &blank.view)?;
+                let source = image(pass.source).map_or(blank, |index| &self.scratch[index].view);
+                let aux = match pass.aux {
+                    Aux::None => blank,
+                    Aux::Slot(slot) => image(slot).map_or(blank, |index| &self.scratch[index].view),
+                    Aux::Lut(id) => self.luts.get(id).map_or(blank, |lut| &lut.view),
+                };
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Post Process Bind Group"),
+                    layout: &self.bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(source),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&self.sampler),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: self.uniforms[buffer].as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: wgpu::BindingResource::TextureView(aux),
+                        },
+                    ],
+                });
+                passes.push(PreparedPass {
+                    kind: pass.kind,
+                    bind_group,
+                    output: image(pass.output),
+                });
+            }
+            if let Some(output) = output {
+                current = output;
+            }
+        }
+
+        stats.post_process_passes += passes.len() as u32;
+        Some(PostChain { input, passes })
+    }
+
+    /// Texture a chain's view should be drawn into
+    pub fn input_view(&self, chain: &PostChain) -> &wgpu::TextureView {
+        &self.scratch[chain.input].view
+    }
+
+    /// Record a chain's passes, writing the result to `target`
+    ///
+    /// With a `viewport`, the result only covers that area of the target.
+    pub fn encode(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        chain: &PostChain,
+        target: &wgpu::TextureView,
+        viewport: Option<ScreenRect>,
+    ) {
+        for pass in &chain.passes {
+            let (view, load) = match pass.output {
+                Some(index) => (&self.scratch[index].view, wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)),
+                None => (target, wgpu::LoadOp::Load),
+            };
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post Process Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            if let (None, Some(viewport)) = (pass.output, viewport) {
+                render_pass.set_viewport(
+                    viewport.x as f32,
+                    viewport.y as f32,
+                    viewport.width as f32,
+                    viewport.height as f32,
+                    0.0,
+                    1.0,
+                );
+                render_pass.set_scissor_rect(viewport.x, viewport.y, viewport.width, viewport.height);
+            }
+            render_pass.set_pipeline(&self.pipelines[&pass.kind]);
+            render_pass.set_bind_group(0, &pass.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    /// Hand out an unused scratch texture of the given size, creating one if needed
+    fn scratch(&mut self, device: &wgpu::Device, width: u32, height: u32) -> usize {
+        if let Some(index) = self
+            .scratch
+            .iter()
+            .position(|scratch| !scratch.used && scratch.width == width && scratch.height == height)
+        {
+            self.scratch[index].used = true;
+            return index;
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Post Process Scratch Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.target_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        self.scratch.push(ScratchTexture {
+            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            width,
+            height,
+            used: true,
+        });
+        self.scratch.len() - 1
+    }
+
+    /// Index of a uniform buffer not yet used this frame
+    fn uniform(&mut self, device: &wgpu::Device) -> usize {
+        if self.next_uniform == self.uniforms.len() {
+            self.uniforms.push(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Post Process Uniform"),
+                size: std::mem::size_of::<PostUniform>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        self.next_uniform += 1;
+        self.next_uniform - 1
+    }
+
+    /// Upload a LUT, or upload it again if its pixels changed
+    fn upload_lut(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture_layout: &wgpu::BindGroupLayout,
+        id: AssetId,
+        textures: &dyn TextureLookup,
+        stats: &mut RenderStats,
+    ) {
+        let version = textures.texture_version(id);
+        if self.luts.contains(id) && self.lut_versions.get(&id) == Some(&version) {
+            return;
+        }
+        let Some(lut) = textures.texture(id) else {
+            return;
+        };
+        let label = format!("LUT {:?}", id);
+        let texture = GpuTexture::from_texture_data(
+            device,
+            queue,
+            texture_layout,
+            lut,
+            &textures.texture_settings(id),
+            Some(&label),
+        );
+        self.luts.insert(id, texture);
+        self.lut_versions.insert(id, version);
+        stats.texture_uploads += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::{Bloom, ColorGrading, Crt, Pixelate, Vignette};
+
+    fn gradient(width: u32, height: u32) -> LinearImage {
+        let mut image = LinearImage::new(width, height, Vec4::ONE);
+        for y in 0..height {
+            for x in 0..width {
+                *image.pixel_mut(x, y) = Vec4::new(x as f32 / width as f32, y as f32 / height as f32, 0.5, 1.0);
+            }
+        }
+        image
+    }
+
+    /// Identity LUT strip with `size` slices
+    fn identity_lut(size: u32) -> TextureData {
+        let mut pixels = Vec::new();
+        for y in 0..size {
+            for x in 0..size * size {
+                let scale = |v: u32| (v as f32 / (size - 1) as f32 * 255.0).round() as u8;
+                pixels.extend_from_slice(&[scale(x % size), scale(y), scale(x / size), 255]);
+            }
+        }
+        TextureData {
+            width: size * size,
+            height: size,
+            pixels,
+        }
+    }
+
+    #[test]
+    fn test_pixelate_repeats_block_centers() {
+        let image = gradient(8, 4);
+        let effects = [PostEffect::Pixelate(Pixelate { pixel_size: 4 })];
+        let result = apply_effects(image.clone(), &effects, &HashMap::<AssetId, TextureData>::new());
+
+        for y in 0..4 {
+            for x in 0..8 {
+                let center = image.load(x / 4 * 4 + 2, 2);
+                assert_eq!(result.load(x, y), center);
+            }
+        }
+    }
+
+    #[test]
+    fn test_vignette_darkens_corners_only() {
+        let image = LinearImage::new(16, 16, Vec4::ONE);
+        let effects = [PostEffect::Vignette(Vignette {
+            intensity: 1.0,
+            radius: 0.5,
+            softness: 0.25,
+            color: [0.0; 3],
+        })];
+        let result = apply_effects(image, &effects, &HashMap::<AssetId, TextureData>::new());
+
+        assert_eq!(result.load(8, 8), Vec4::ONE);
+        let corner = result.load(0, 0);
+        assert!(corner.x < 0.05 && corner.w == 1.0);
+    }
+
+    #[test]
+    fn test_crt_blackens_outside_and_darkens_odd_rows() {
+        let image = LinearImage::new(16, 16, Vec4::ONE);
+        let flat = [PostEffect::Crt(Crt {
+            curvature: 0.0,
+            scanlines: 0.5,
+        })];
+        let result = apply_effects(image.clone(), &flat, &HashMap::<AssetId, TextureData>::new());
+        assert_eq!(result.load(4, 4).x, 1.0);
+        assert_eq!(result.load(4, 5).x, 0.5);
+
+        let curved = [PostEffect::Crt(Crt {
+            curvature: 0.5,
+            scanlines: 0.0,
+        })];
+        let result = apply_effects(image, &curved, &HashMap::<AssetId, TextureData>::new());
+        assert_eq!(result.load(0, 0), Vec4::new(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(result.load(8, 8), Vec4::ONE);
+    }
+
+    #[test]
+    fn test_color_grading_with_identity_lut_keeps_colors() {
+        let mut textures = HashMap::new();
+        textures.insert(AssetId::new(5), identity_lut(16));
+        let image = gradient(8, 8);
+        let grading = |lut| {
+            [PostEffect::ColorGrading(ColorGrading {
+                lut: AssetId::new(lut),
+                intensity: 1.0,
+            })]
+        };
+
+        let result = apply_effects(image.clone(), &grading(5), &textures);
+        for (a, b) in result.pixels.iter().zip(&image.pixels) {
+            assert!((*a - *b).abs().max_element() < 0.02, "{:?} vs {:?}", a, b);
+        }
+
+        // A missing LUT skips the effect
+        assert_eq!(apply_effects(image.clone(), &grading(6), &textures), image);
+    }
+
+    #[test]
+    fn test_bloom_spreads_bright_pixels() {
+        let mut image = LinearImage::new(16, 1, Vec4::new(0.0, 0.0, 0.0, 1.0));
+        *image.pixel_mut(8, 0) = Vec4::ONE;
+        let effects = [PostEffect::Bloom(Bloom {
+            threshold: 0.5,
+            intensity: 1.0,
+            radius: 1.0,
+        })];
+        let result = apply_effects(image, &effects, &HashMap::<AssetId, TextureData>::new());
+
+        // Neighbours glow, fading with distance; far pixels stay dark
+        assert!(result.load(9, 0).x > result.load(11, 0).x);
+        assert!(result.load(11, 0).x > 0.0);
+        assert_eq!(result.load(0, 0).x, 0.0);
+        assert_eq!(result.load(9, 0).w, 1.0);
+    }
+}
@@ -1,13 +1,10 @@
 use crate::{
-    camera::Camera,
-    pipeline::{create_sprite_pipeline, CameraUniform},
-    sprite_batch::{SpriteBatch, SpriteVertex},
-    texture::{GpuTexture, TextureCache},
-    Color,
+    backend::{RenderBackend, RenderView, TextureLookup},
+    instancing::{RenderStats, SpritePass},
+    Camera, Color,
 };
-use longhorn_assets::{AssetHandle, AssetManager, AssetSource, TextureData};
-use longhorn_core::{AssetId, Sprite, Transform, World};
-use wgpu::{self, util::DeviceExt};
+use longhorn_assets::{AssetManager, AssetSource, TextureData, TextureImportSettings};
+use longhorn_core::{AssetId, World};
 
 /// Main renderer for 2D sprites
 pub struct Renderer {
@@ -15,15 +12,8 @@ pub struct Renderer {
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
-    pipeline: wgpu::RenderPipeline,
-    camera_buffer: wgpu::Buffer,
-    camera_bind_group: wgpu::BindGroup,
-    camera_uniform: CameraUniform,
-    vertex_buffer: wgpu::Buffer,
-    texture_bind_group_layout: wgpu::BindGroupLayout,
-    texture_cache: TextureCache,
+    sprite_pass: SpritePass,
     clear_color: Color,
-    max_vertices: usize,
 }
 
 impl Renderer {
@@ -86,95 +76,15 @@ impl Renderer {
         };
         surface.configure(&device, &config);
 
-        // Create camera uniform buffer
-        let camera_uniform = CameraUniform::new();
-        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Camera Buffer"),
-            contents: bytemuck::cast_slice(&[camera_uniform]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
-
-        // Create camera bind group layout
-        let camera_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-                label: Some("Camera Bind Group Layout"),
-            });
-
-        // Create camera bind group
-        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
-            label: Some("Camera Bind Group"),
-        });
-
-        // Create texture bind group layout
-        let texture_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Texture {
-                            multisampled: false,
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                        count: None,
-                    },
-                ],
-                label: Some("Texture Bind Group Layout"),
-            });
-
-        // Create pipeline
-        let pipeline = create_sprite_pipeline(
-            &device,
-            surface_format,
-            &camera_bind_group_layout,
-            &texture_bind_group_layout,
-        );
-
-        // Create vertex buffer (large enough for many sprites)
-        let max_vertices = 10000 * 6; // 10000 sprites * 6 vertices each
-        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Vertex Buffer"),
-            size: (max_vertices * std::mem::size_of::<SpriteVertex>()) as u64,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let sprite_pass = SpritePass::new(&device, surface_format);
 
         Ok(Self {
             surface,
             device,
             queue,
             config,
-            pipeline,
-            camera_buffer,
-            camera_bind_group,
-            camera_uniform,
-            vertex_buffer,
-            texture_bind_group_layout,
-            texture_cache: TextureCache::new(),
+            sprite_pass,
             clear_color: Color::BLACK,
-            max_vertices,
         })
     }
 
@@ -194,12 +104,12 @@ impl Renderer {
 
     /// Check if a texture is loaded
     pub fn has_texture(&self, asset_id: AssetId) -> bool {
-        self.texture_cache.contains(asset_id)
+        self.sprite_pass.contains(asset_id)
     }
 
     /// Get all loaded texture IDs
     pub fn loaded_texture_ids(&self) -> Vec<AssetId> {
-        self.texture_cache.keys()
+        self.sprite_pass.texture_ids()
     }
 
     /// Upload a texture to the GPU
@@ -207,65 +117,46 @@ impl Renderer {
         &mut self,
         asset_id: AssetId,
         texture_data: &TextureData,
+        settings: &TextureImportSettings,
     ) -> Result<(), RendererError> {
-        if !self.texture_cache.contains(asset_id) {
-            let label = format!("Texture {:?}", asset_id);
-            let gpu_texture = GpuTexture::from_texture_data(
-                &self.device,
-                &self.queue,
-                &self.texture_bind_group_layout,
-                texture_data,
-                Some(&label),
-            );
-            self.texture_cache.insert(asset_id, gpu_texture);
-        }
+        self.sprite_pass
+            .upload_texture(&self.device, &self.queue, asset_id, texture_data, settings);
         Ok(())
     }
 
+    /// Drop an uploaded texture so it is uploaded again on next use
+    ///
+    /// Call after a texture's import settings change.
+    pub fn invalidate_texture(&mut self, asset_id: AssetId) {
+        self.sprite_pass.invalidate_texture(asset_id);
+    }
+
     /// Render the world
+    ///
+    /// # Returns
+    /// Draw call, instance and upload counts for the frame
     pub fn render<S: AssetSource>(
         &mut self,
         world: &World,
         asset_manager: &AssetManager<S>,
         camera: &Camera,
-    ) -> Result<(), RendererError> {
-        // Update camera uniform
-        self.camera_uniform.update(camera.view_projection());
-        self.queue.write_buffer(
-            &self.camera_buffer,
-            0,
-            bytemuck::cast_slice(&[self.camera_uniform]),
-        );
-
-        // Collect sprites from the world
-        let mut batch = SpriteBatch::new();
-        for (entity_id, (sprite, transform)) in world.query::<(&Sprite, &Transform)>().iter() {
-            // Upload texture if not already cached
-            if !self.texture_cache.contains(sprite.texture) {
-                let handle = AssetHandle::<TextureData>::new(sprite.texture);
-                if let Some(texture_data) = asset_manager.get_texture(handle) {
-                    self.upload_texture(sprite.texture, texture_data)?;
-                } else {
-                    log::warn!(
-                        "Texture not found for entity {:?}: {:?}",
-                        entity_id,
-                        sprite.texture
-                    );
-                    continue;
-                }
-            }
-
-            batch.add(crate::sprite_batch::SpriteInstance {
-                position: transform.position,
-                size: sprite.size,
-                color: Color::new(sprite.color[0], sprite.color[1], sprite.color[2], sprite.color[3]),
-                texture: sprite.texture,
-                z_index: 0, // No z_index in Sprite component, default to 0
-            });
-        }
+    ) -> Result<RenderStats, RendererError> {
+        self.render_world(world, asset_manager, camera)
+    }
 
-        // Sort sprites by z-index
-        batch.sort();
+    /// Render sorted sprite views using textures from any lookup
+    fn draw_views(
+        &mut self,
+        views: &[RenderView<'_>],
+        textures: &dyn TextureLookup,
+    ) -> Result<RenderStats, RendererError> {
+        // Build and upload this frame's instances
+        let stats = self
+            .sprite_pass
+            .prepare_render_views(&self.device, &self.queue, views, textures);
+        if stats.skipped_sprites > 0 {
+            log::warn!("Skipped {} sprites with missing textures", stats.skipped_sprites);
+        }
 
         // Get current surface texture
         let output = self.surface.get_current_texture()?;
@@ -280,86 +171,41 @@ impl Renderer {
                 label: Some("Render Encoder"),
             });
 
-        // Begin render pass
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(self.clear_color.to_wgpu()),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
-
-            render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
-
-            // Render sprites grouped by texture
-            let mut current_texture: Option<AssetId> = None;
-            let mut vertices = Vec::new();
-
-            for sprite in batch.iter() {
-                // If texture changed, flush previous batch
-                if current_texture.is_some() && current_texture != Some(sprite.texture) {
-                    self.render_vertices(&mut render_pass, &vertices, current_texture.unwrap())?;
-                    vertices.clear();
-                }
-
-                current_texture = Some(sprite.texture);
-                let sprite_vertices = SpriteBatch::generate_vertices(sprite);
-                vertices.extend_from_slice(&sprite_vertices);
-            }
-
-            // Render remaining vertices
-            if !vertices.is_empty() && current_texture.is_some() {
-                self.render_vertices(&mut render_pass, &vertices, current_texture.unwrap())?;
-            }
-        }
+        // Render textures, then the screen views
+        self.sprite_pass
+            .encode(&mut encoder, &view, self.clear_color.to_wgpu());
 
         // Submit command buffer
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
 
-        Ok(())
+        Ok(stats)
     }
+}
 
-    /// Render a batch of vertices
-    fn render_vertices<'a>(
-        &'a self,
-        render_pass: &mut wgpu::RenderPass<'a>,
-        vertices: &[SpriteVertex],
-        asset_id: AssetId,
-    ) -> Result<(), RendererError> {
-        if vertices.is_empty() {
-            return Ok(());
-        }
-
-        if vertices.len() > self.max_vertices {
-            return Err(RendererError::TooManyVertices);
-        }
+impl RenderBackend for Renderer {
+    fn resize(&mut self, width: u32, height: u32) {
+        Renderer::resize(self, width, height);
+    }
 
-        // Upload vertices to GPU
-        self.queue
-            .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices));
+    fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
 
-        // Get texture bind group
-        let texture = self
-            .texture_cache
-            .get(asset_id)
-            .ok_or_else(|| RendererError::TextureNotFound(asset_id))?;
+    fn set_clear_color(&mut self, color: Color) {
+        Renderer::set_clear_color(self, color);
+    }
 
-        // Set texture and draw
-        render_pass.set_bind_group(1, &texture.bind_group, &[]);
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.draw(0..vertices.len() as u32, 0..1);
+    fn loaded_texture_ids(&self) -> Vec<AssetId> {
+        Renderer::loaded_texture_ids(self)
+    }
 
-        Ok(())
+    fn render_views(
+        &mut self,
+        views: &[RenderView<'_>],
+        textures: &dyn TextureLookup,
+    ) -> Result<RenderStats, RendererError> {
+        self.draw_views(views, textures)
     }
 }
 
@@ -383,4 +229,7 @@ pub enum RendererError {
 
     #[error("Too many vertices")]
     TooManyVertices,
+
+    #[error("Image error: {0}")]
+    Image(String),
 }
@@ -0,0 +1,177 @@
+use crate::ScreenRect;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// How the design resolution is mapped onto screens of other sizes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleMode {
+    /// Show exactly the design area, as large as fits; bars fill the rest
+    /// (letterbox or pillarbox)
+    #[default]
+    Fit,
+    /// Cover the whole screen; the design area is cropped on one axis
+    Fill,
+    /// Cover the whole screen, showing more than the design area on one axis
+    Expand,
+    /// Like `Fit`, but only scale by whole numbers so pixels stay square
+    ///
+    /// Screens smaller than the design resolution fall back to `Fit`.
+    PixelPerfect,
+}
+
+/// The mapping between screen pixels and game space for one screen size
+///
+/// Game space is the design resolution in pixels, origin at the top-left.
+/// With `Fill` part of it is off screen; with `Expand` screen areas beyond it
+/// map to coordinates outside `0..design_size`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportScaling {
+    /// Screen pixels covered by the game
+    pub viewport: ScreenRect,
+    /// Game-space size visible in `viewport`
+    pub visible_size: Vec2,
+    /// Screen pixels per game unit
+    pub scale: f32,
+    /// Game-space position of the viewport's top-left corner
+    pub origin: Vec2,
+}
+
+impl ViewportScaling {
+    /// Compute the scaling of a design resolution onto a screen
+    pub fn new(mode: ScaleMode, design_size: Vec2, screen_width: u32, screen_height: u32) -> Self {
+        let screen = Vec2::new(screen_width as f32, screen_height as f32);
+        if design_size.x <= 0.0 || design_size.y <= 0.0 || screen.x <= 0.0 || screen.y <= 0.0 {
+            return Self::identity(screen_width, screen_height);
+        }
+
+        let ratio = screen / design_size;
+        match mode {
+            ScaleMode::Fit => Self::boxed(design_size, screen, ratio.min_element()),
+            ScaleMode::PixelPerfect => {
+                let whole = ratio.min_element().floor();
+                let scale = if whole >= 1.0 { whole } else { ratio.min_element() };
+                Self::boxed(design_size, screen, scale)
+            }
+            ScaleMode::Fill => Self::covering(design_size, screen, ratio.max_element()),
+            ScaleMode::Expand => Self::covering(design_size, screen, ratio.min_element()),
+        }
+    }
+
+    /// One game unit per pixel over the whole screen
+    pub fn identity(screen_width: u32, screen_height: u32) -> Self {
+        Self {
+            viewport: ScreenRect::full(screen_width, screen_height),
+            visible_size: Vec2::new(screen_width as f32, screen_height as f32),
+            scale: 1.0,
+            origin: Vec2::ZERO,
+        }
+    }
+
+    /// The whole design area, centered and scaled by `scale`
+    fn boxed(design_size: Vec2, screen: Vec2, scale: f32) -> Self {
+        let size = (design_size * scale).round().min(screen);
+        let offset = ((screen - size) / 2.0).floor();
+        Self {
+            viewport: ScreenRect {
+                x: offset.x as u32,
+                y: offset.y as u32,
+                width: size.x as u32,
+                height: size.y as u32,
+            },
+            visible_size: design_size,
+            scale,
+            origin: Vec2::ZERO,
+        }
+    }
+
+    /// The whole screen, showing the part of game space centered on the design area
+    fn covering(design_size: Vec2, screen: Vec2, scale: f32) -> Self {
+        let visible_size = screen / scale;
+        Self {
+            viewport: ScreenRect::full(screen.x as u32, screen.y as u32),
+            visible_size,
+            scale,
+            origin: (design_size - visible_size) / 2.0,
+        }
+    }
+
+    /// Convert a screen pixel position to game space
+    pub fn screen_to_game(&self, position: Vec2) -> Vec2 {
+        let viewport_origin = Vec2::new(self.viewport.x as f32, self.viewport.y as f32);
+        (position - viewport_origin) / self.scale + self.origin
+    }
+
+    /// Convert a game-space position to screen pixels
+    pub fn game_to_screen(&self, position: Vec2) -> Vec2 {
+        let viewport_origin = Vec2::new(self.viewport.x as f32, self.viewport.y as f32);
+        (position - self.origin) * self.scale + viewport_origin
+    }
+
+    /// Check if a screen position is inside the game viewport (not on a bar)
+    pub fn contains_screen(&self, position: Vec2) -> bool {
+        let min = Vec2::new(self.viewport.x as f32, self.viewport.y as f32);
+        let max = min + Vec2::new(self.viewport.width as f32, self.viewport.height as f32);
+        position.cmpge(min).all() && position.cmplt(max).all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESIGN: Vec2 = Vec2::new(800.0, 600.0);
+
+    #[test]
+    fn test_fit_letterboxes() {
+        // Wider screen: pillarbox
+        let scaling = ViewportScaling::new(ScaleMode::Fit, DESIGN, 1920, 1080);
+        assert_eq!(scaling.scale, 1.8);
+        assert_eq!(scaling.viewport, ScreenRect { x: 240, y: 0, width: 1440, height: 1080 });
+        assert_eq!(scaling.visible_size, DESIGN);
+        assert_eq!(scaling.screen_to_game(Vec2::new(960.0, 540.0)), Vec2::new(400.0, 300.0));
+        assert!(!scaling.contains_screen(Vec2::new(100.0, 540.0)));
+
+        // Taller screen: letterbox
+        let scaling = ViewportScaling::new(ScaleMode::Fit, DESIGN, 400, 600);
+        assert_eq!(scaling.viewport, ScreenRect { x: 0, y: 150, width: 400, height: 300 });
+    }
+
+    #[test]
+    fn test_fill_crops_and_expand_shows_more() {
+        let fill = ViewportScaling::new(ScaleMode::Fill, DESIGN, 1920, 1080);
+        assert_eq!(fill.viewport, ScreenRect::full(1920, 1080));
+        assert_eq!(fill.scale, 2.4);
+        assert!(fill.visible_size.abs_diff_eq(Vec2::new(800.0, 450.0), 0.01));
+        assert!(fill.screen_to_game(Vec2::ZERO).abs_diff_eq(Vec2::new(0.0, 75.0), 0.01));
+
+        let expand = ViewportScaling::new(ScaleMode::Expand, DESIGN, 1920, 1080);
+        assert_eq!(expand.scale, 1.8);
+        assert!((expand.visible_size.x - 1066.667).abs() < 0.01);
+        assert_eq!(expand.visible_size.y, 600.0);
+        // The design center stays at the screen center
+        assert!(expand.screen_to_game(Vec2::new(960.0, 540.0)).abs_diff_eq(Vec2::new(400.0, 300.0), 0.01));
+    }
+
+    #[test]
+    fn test_pixel_perfect_uses_whole_scales() {
+        let scaling = ViewportScaling::new(ScaleMode::PixelPerfect, Vec2::new(320.0, 180.0), 1000, 700);
+        assert_eq!(scaling.scale, 3.0);
+        assert_eq!(scaling.viewport, ScreenRect { x: 20, y: 80, width: 960, height: 540 });
+
+        // Screens smaller than the design fall back to fractional scaling
+        let small = ViewportScaling::new(ScaleMode::PixelPerfect, Vec2::new(320.0, 180.0), 160, 160);
+        assert_eq!(small.scale, 0.5);
+        assert_eq!(small.viewport, ScreenRect { x: 0, y: 35, width: 160, height: 90 });
+    }
+
+    #[test]
+    fn test_screen_game_roundtrip() {
+        for mode in [ScaleMode::Fit, ScaleMode::Fill, ScaleMode::Expand, ScaleMode::PixelPerfect] {
+            let scaling = ViewportScaling::new(mode, DESIGN, 1170, 2532);
+            let screen = Vec2::new(123.0, 456.0);
+            let back = scaling.game_to_screen(scaling.screen_to_game(screen));
+            assert!((back - screen).length() < 0.01, "{mode:?}");
+        }
+    }
+}
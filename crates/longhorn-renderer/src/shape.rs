@@ -0,0 +1,314 @@
+use crate::pipeline::ShapeVertex;
+use crate::sprite_batch::world_transform;
+use crate::{RenderStats, SpriteBatch, SpriteInstance};
+use glam::Vec2;
+use longhorn_core::{AssetId, GlobalTransform, Rect, Shape, ShapeGeometry, Transform, World};
+
+/// Longest a stroke's miter join may get, in half stroke widths; sharper
+/// corners are cut to this length
+pub const SHAPE_MITER_LIMIT: f32 = 4.0;
+
+/// Points closer than this are merged before tessellating
+const POINT_EPSILON: f32 = 1e-5;
+
+/// Tessellate a shape into a triangle list in its local space: the fill,
+/// then the stroke over it
+///
+/// Rectangles, rounded rectangles and circles are fanned from their center;
+/// polygons are ear-clipped, so concave ones fill correctly. Strokes are
+/// centered on the outline with mitered corners and square-cut ends.
+pub fn tessellate_shape(shape: &Shape) -> Vec<ShapeVertex> {
+    let mut vertices = Vec::new();
+    let Some(bounds) = shape.geometry.bounds() else {
+        return vertices;
+    };
+    let closed = shape.geometry.is_closed();
+    let outline = dedup_points(shape.geometry.outline(), closed);
+
+    if let Some(fill) = shape.fill.as_ref().filter(|_| closed && outline.len() >= 3) {
+        let vertex = |position: Vec2| ShapeVertex {
+            position: position.to_array(),
+            color: fill.color_at(position, bounds),
+        };
+        match shape.geometry {
+            ShapeGeometry::Polygon { .. } => {
+                for triangle in triangulate(&outline) {
+                    vertices.extend(triangle.map(|i| vertex(outline[i])));
+                }
+            }
+            _ => {
+                let center = bounds.center();
+                for (i, &point) in outline.iter().enumerate() {
+                    let next = outline[(i + 1) % outline.len()];
+                    vertices.extend([vertex(center), vertex(point), vertex(next)]);
+                }
+            }
+        }
+    }
+
+    if let Some(stroke) = shape.stroke.filter(|stroke| stroke.width > 0.0) {
+        stroke_triangles(&outline, closed, stroke.width / 2.0, stroke.color, &mut vertices);
+    }
+    vertices
+}
+
+/// Build the render instance for a shape entity, or `None` if the shape
+/// draws nothing
+///
+/// The mesh is moved to world space; the instance's position and size are
+/// its bounding box, so it is culled like a sprite.
+pub fn shape_instance(shape: &Shape, transform: &GlobalTransform) -> Option<SpriteInstance> {
+    let mut mesh = tessellate_shape(shape);
+    let first = transform.transform_point(Vec2::from(mesh.first()?.position));
+    let (mut min, mut max) = (first, first);
+    for vertex in &mut mesh {
+        let position = transform.transform_point(Vec2::from(vertex.position));
+        vertex.position = position.to_array();
+        min = min.min(position);
+        max = max.max(position);
+    }
+    let mut instance = SpriteInstance::new((min + max) / 2.0, max - min, AssetId::new(0)).with_z_index(shape.z_index);
+    instance.mesh = Some(mesh.into());
+    Some(instance)
+}
+
+/// Add every shape in the world to a batch, culling against `visible`
+///
+/// # Returns
+/// The number of shapes culled
+pub(crate) fn collect_shapes(world: &World, visible: Option<Rect>, batch: &mut SpriteBatch) -> usize {
+    let mut culled = 0;
+    for (_, (shape, global, local)) in world
+        .query::<(&Shape, Option<&GlobalTransform>, Option<&Transform>)>()
+        .iter()
+    {
+        let Some(transform) = world_transform(global, local) else {
+            continue;
+        };
+        let Some(instance) = shape_instance(shape, &transform) else {
+            continue;
+        };
+        if visible.is_some_and(|visible| !visible.intersects(&instance.bounds())) {
+            culled += 1;
+            continue;
+        }
+        batch.add(instance);
+    }
+    culled
+}
+
+/// Drop consecutive duplicate points, and the closing point of a closed
+/// outline that repeats its first
+fn dedup_points(mut points: Vec<Vec2>, closed: bool) -> Vec<Vec2> {
+    points.dedup_by(|b, a| a.distance_squared(*b) <= POINT_EPSILON * POINT_EPSILON);
+    if closed && points.len() > 1 && points[0].distance_squared(points[points.len() - 1]) <= POINT_EPSILON * POINT_EPSILON {
+        points.pop();
+    }
+    points
+}
+
+/// Split a simple polygon into triangles by ear clipping
+///
+/// Returns counter-clockwise index triples. Outlines whose edges cross have
+/// no ears left at some point; their remaining vertices are clipped in order
+/// so something is still drawn.
+fn triangulate(points: &[Vec2]) -> Vec<[usize; 3]> {
+    let mut indices: Vec<usize> = (0..points.len()).collect();
+    if signed_area(points) < 0.0 {
+        indices.reverse();
+    }
+    let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+    while indices.len() > 3 {
+        let count = indices.len();
+        let corner = |i: usize| [indices[(i + count - 1) % count], indices[i], indices[(i + 1) % count]];
+        let ear = (0..count).find(|&i| {
+            let [a, b, c] = corner(i).map(|index| points[index]);
+            cross(b - a, c - b) >= 0.0
+                && !indices.iter().any(|&j| {
+                    let p = points[j];
+                    p != a && p != b && p != c && in_triangle(p, a, b, c)
+                })
+        });
+        let ear = ear.unwrap_or(0);
+        triangles.push(corner(ear));
+        indices.remove(ear);
+    }
+    if let [a, b, c] = indices[..] {
+        triangles.push([a, b, c]);
+    }
+    triangles
+}
+
+/// Append the triangles of a line of half width `half` along `points`
+fn stroke_triangles(points: &[Vec2], closed: bool, half: f32, color: [f32; 4], vertices: &mut Vec<ShapeVertex>) {
+    let count = points.len();
+    if count < 2 {
+        return;
+    }
+    let closed = closed && count > 2;
+    let direction = |from: usize, to: usize| (points[to] - points[from]).normalize_or_zero();
+    let offsets: Vec<Vec2> = (0..count)
+        .map(|i| {
+            let previous = if i > 0 { Some(i - 1) } else { closed.then_some(count - 1) };
+            let next = if i + 1 < count { Some(i + 1) } else { closed.then_some(0) };
+            let incoming = previous.map(|p| direction(p, i).perp());
+            let outgoing = next.map(|n| direction(i, n).perp());
+            match (incoming, outgoing) {
+                (Some(a), Some(b)) => {
+                    let miter = (a + b).normalize_or(a);
+                    let length = half / miter.dot(b).max(1.0 / SHAPE_MITER_LIMIT);
+                    miter * length
+                }
+                (Some(normal), None) | (None, Some(normal)) => normal * half,
+                (None, None) => Vec2::ZERO,
+            }
+        })
+        .collect();
+
+    let segments = if closed { count } else { count - 1 };
+    for i in 0..segments {
+        let j = (i + 1) % count;
+        let vertex = |position: Vec2| ShapeVertex { position: position.to_array(), color };
+        let (left_i, right_i) = (points[i] + offsets[i], points[i] - offsets[i]);
+        let (left_j, right_j) = (points[j] + offsets[j], points[j] - offsets[j]);
+        vertices.extend([left_i, right_i, right_j, left_i, right_j, left_j].map(vertex));
+    }
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for (i, &a) in points.iter().enumerate() {
+        area += cross(a, points[(i + 1) % points.len()]);
+    }
+    area / 2.0
+}
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+/// Whether `p` lies inside or on the counter-clockwise triangle `a b c`
+fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    cross(b - a, p - a) >= 0.0 && cross(c - b, p - b) >= 0.0 && cross(a - c, p - c) >= 0.0
+}
+
+/// Persistent GPU buffer of `ShapeVertex` lists
+///
+/// Created on first use and reallocated (to the next power of two) when a
+/// frame has more vertices than it can hold.
+pub(crate) struct ShapeBuffer {
+    label: &'static str,
+    buffer: Option<wgpu::Buffer>,
+    capacity: usize,
+}
+
+impl ShapeBuffer {
+    pub fn new(label: &'static str) -> Self {
+        Self { label, buffer: None, capacity: 0 }
+    }
+
+    /// The underlying GPU buffer, once something was written
+    pub fn buffer(&self) -> Option<&wgpu::Buffer> {
+        self.buffer.as_ref()
+    }
+
+    /// Upload this frame's vertices, growing the buffer if needed
+    pub fn write(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, vertices: &[ShapeVertex], stats: &mut RenderStats) {
+        if vertices.is_empty() {
+            return;
+        }
+        if self.buffer.is_none() || vertices.len() > self.capacity {
+            self.capacity = vertices.len().next_power_of_two();
+            self.buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(self.label),
+                size: (self.capacity * std::mem::size_of::<ShapeVertex>()) as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+            stats.buffer_allocations += 1;
+        }
+        if let Some(buffer) = &self.buffer {
+            queue.write_buffer(buffer, 0, bytemuck::cast_slice(vertices));
+            stats.buffer_uploads += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::{ShapeFill, SHAPE_CIRCLE_SEGMENTS};
+
+    fn area(vertices: &[ShapeVertex]) -> f32 {
+        vertices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|v| Vec2::from(v.position));
+                cross(b - a, c - a).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_fills_cover_the_outline() {
+        let rectangle = tessellate_shape(&Shape::rectangle(Vec2::new(4.0, 2.0)));
+        assert_eq!(rectangle.len(), 4 * 3);
+        assert!((area(&rectangle) - 8.0).abs() < 1e-4);
+
+        let circle = tessellate_shape(&Shape::circle(1.0));
+        assert_eq!(circle.len(), SHAPE_CIRCLE_SEGMENTS * 3);
+
+        // An L shape: its concave corner must not be filled over
+        let l_shape = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+        let points: Vec<Vec2> = l_shape.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+        let mut clockwise = points.clone();
+        clockwise.reverse();
+        for points in [points, clockwise] {
+            let polygon = tessellate_shape(&Shape::polygon(points));
+            assert_eq!(polygon.len(), 4 * 3);
+            assert!((area(&polygon) - 3.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_strokes_miter_and_cap() {
+        // A right angle: two quads with a mitered corner, butt ends
+        let line = Shape::polyline(vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0), Vec2::new(10.0, 10.0)])
+            .with_stroke([1.0; 4], 2.0);
+        let vertices = tessellate_shape(&line);
+        assert_eq!(vertices.len(), 2 * 6);
+        assert_eq!(vertices[0].position, [0.0, 1.0]);
+        assert_eq!(vertices[1].position, [0.0, -1.0]);
+        let corner = Vec2::from(vertices[2].position);
+        assert!((corner - Vec2::new(11.0, -1.0)).length() < 1e-4);
+        assert!((area(&vertices) - 2.0 * 20.0).abs() < 1e-3);
+
+        // A hairpin turn is cut at the miter limit
+        let hairpin = Shape::polyline(vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(0.0, 0.1)]).with_stroke([1.0; 4], 2.0);
+        let vertices = tessellate_shape(&hairpin);
+        let tip = vertices.iter().map(|v| Vec2::from(v.position).x).fold(f32::MIN, f32::max);
+        assert!(tip <= 10.0 + SHAPE_MITER_LIMIT + 1e-3);
+    }
+
+    #[test]
+    fn test_shape_instances_are_placed_in_world_space() {
+        let shape = Shape::rectangle(Vec2::new(4.0, 2.0))
+            .with_fill(ShapeFill::LinearGradient { start: [0.0; 4], end: [1.0; 4], angle: 0.0 })
+            .with_z_index(3);
+        let transform = GlobalTransform { position: Vec2::new(10.0, 0.0), rotation: 0.0, scale: Vec2::splat(2.0) };
+        let instance = shape_instance(&shape, &transform).unwrap();
+        assert_eq!((instance.position, instance.size, instance.z_index), (Vec2::new(10.0, 0.0), Vec2::new(8.0, 4.0), 3));
+        let mesh = instance.mesh.unwrap();
+        let left = mesh.iter().find(|v| v.position == [6.0, -2.0]).unwrap();
+        assert_eq!(left.color, [0.0; 4]);
+
+        assert!(shape_instance(&Shape::polygon(Vec::new()), &transform).is_none());
+        assert!(shape_instance(&Shape::rectangle(Vec2::ONE).without_fill(), &transform).is_none());
+
+        let mut world = World::new();
+        world.spawn().with(shape).with(Transform::from_position(Vec2::new(500.0, 0.0))).build();
+        let visible = SpriteBatch::collect_visible(&world, &crate::Camera::new(200.0, 200.0));
+        assert_eq!((visible.len(), visible.culled()), (0, 1));
+        assert_eq!(SpriteBatch::collect(&world).len(), 1);
+    }
+}
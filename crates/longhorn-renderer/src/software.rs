@@ -0,0 +1,1050 @@
+use crate::{
+    backend::{RenderBackend, RenderView, TextureLookup},
+    lighting::{flat_normal, has_normal_map, normal_instance, shade_normal},
+    pipeline::ShapeVertex,
+    post_process::{apply_effects, LinearImage},
+    sprite_batch::{SpriteBatch, SpriteVertex},
+    debug_pixel_size, Camera, Color, DebugDraw, Lighting, RenderStats, RendererError, ScreenRect,
+};
+use glam::{Vec2, Vec4};
+use longhorn_assets::{TextureData, TextureFilter, TextureImportSettings, TextureWrap};
+use longhorn_core::AssetId;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// RGBA8 image in sRGB, rows top to bottom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameBuffer {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Result of comparing two frame buffers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageDiff {
+    /// Largest per-channel difference
+    pub max_difference: u8,
+    /// Number of pixels with any channel differing by more than the tolerance
+    pub differing_pixels: usize,
+}
+
+impl FrameBuffer {
+    /// Create a frame buffer filled with transparent black
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    /// Get the RGBA value of a pixel
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * self.width + x) * 4) as usize;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    /// Load a PNG file
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, RendererError> {
+        let image = image::open(path.as_ref())
+            .map_err(|e| RendererError::Image(e.to_string()))?
+            .to_rgba8();
+        Ok(Self {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
+        })
+    }
+
+    /// Save as a PNG file
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), RendererError> {
+        image::save_buffer(
+            path.as_ref(),
+            &self.pixels,
+            self.width,
+            self.height,
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(|e| RendererError::Image(e.to_string()))
+    }
+
+    /// Compare against another image, counting pixels that differ by more
+    /// than `tolerance` in any channel
+    ///
+    /// # Returns
+    /// `None` if the images have different sizes
+    pub fn diff(&self, other: &FrameBuffer, tolerance: u8) -> Option<ImageDiff> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+
+        let mut result = ImageDiff { max_difference: 0, differing_pixels: 0 };
+        for (a, b) in self.pixels.chunks_exact(4).zip(other.pixels.chunks_exact(4)) {
+            let difference = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0);
+            result.max_difference = result.max_difference.max(difference);
+            if difference > tolerance {
+                result.differing_pixels += 1;
+            }
+        }
+        Some(result)
+    }
+
+    /// Check if another image matches within `tolerance` per channel
+    pub fn matches(&self, other: &FrameBuffer, tolerance: u8) -> bool {
+        self.diff(other, tolerance).is_some_and(|d| d.differing_pixels == 0)
+    }
+}
+
+/// CPU rasterizer implementing the sprite pipeline without a GPU
+///
+/// Mirrors the wgpu pipeline: the same vertices from
+/// `SpriteBatch::generate_vertices`, sampling per the texture's import
+/// settings (filter, wrap, sRGB or linear data), tint multiplication,
+/// straight or premultiplied alpha blending in linear space and an
+/// sRGB-encoded output. Mipmaps are not emulated; the base level is always
+/// sampled. Triangles use the top-left fill rule so the shared quad diagonal
+/// is only covered once. Lit views draw a normal buffer alongside and are
+/// multiplied by their lights per pixel, and post-processing effects run
+/// per pixel, both with the same math as the GPU passes.
+pub struct SoftwareRenderer {
+    width: u32,
+    height: u32,
+    clear_color: Color,
+    /// Linear-space color target
+    target: LinearImage,
+    frame: FrameBuffer,
+    used_textures: BTreeSet<u64>,
+    /// What cameras with a render-texture target last rendered
+    render_textures: HashMap<AssetId, TextureData>,
+}
+
+impl SoftwareRenderer {
+    /// Create a software renderer with the given target size
+    pub fn new(width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        Self {
+            width,
+            height,
+            clear_color: Color::BLACK,
+            target: LinearImage::new(width, height, Vec4::ZERO),
+            frame: FrameBuffer::new(width, height),
+            used_textures: BTreeSet::new(),
+            render_textures: HashMap::new(),
+        }
+    }
+
+    /// Get what a camera last rendered into a render texture
+    pub fn render_texture(&self, id: AssetId) -> Option<&TextureData> {
+        self.render_textures.get(&id)
+    }
+
+    /// Rasterize one triangle into the viewport area of a linear target
+    #[allow(clippy::too_many_arguments)]
+    fn draw_triangle(
+        target: &mut LinearImage,
+        vertices: [&SpriteVertex; 3],
+        camera: &glam::Mat4,
+        viewport: ScreenRect,
+        texture: &TextureData,
+        settings: &TextureImportSettings,
+        shade: Shade,
+    ) {
+        let origin = Vec2::new(viewport.x as f32, viewport.y as f32);
+        let size = Vec2::new(viewport.width as f32, viewport.height as f32);
+        let mut points = vertices.map(|v| {
+            let clip = *camera * Vec4::new(v.position[0], v.position[1], 0.0, 1.0);
+            origin + Vec2::new((clip.x + 1.0) * 0.5 * size.x, (1.0 - clip.y) * 0.5 * size.y)
+        });
+        let mut uvs = vertices.map(|v| Vec2::from(v.tex_coords));
+        let mut tints = vertices.map(|v| Vec4::from(v.color));
+
+        let mut area = edge(points[0], points[1], points[2]);
+        if area == 0.0 {
+            return;
+        }
+        if area < 0.0 {
+            points.swap(1, 2);
+            uvs.swap(1, 2);
+            tints.swap(1, 2);
+            area = -area;
+        }
+
+        let min = points[0].min(points[1]).min(points[2]).max(origin);
+        let max = points[0].max(points[1]).max(points[2]).min(origin + size);
+        let edges = [(1, 2), (2, 0), (0, 1)];
+
+        for y in min.y.floor() as u32..max.y.ceil() as u32 {
+            for x in min.x.floor() as u32..max.x.ceil() as u32 {
+                let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+                let mut weights = [0.0; 3];
+                let mut inside = true;
+                for (i, &(a, b)) in edges.iter().enumerate() {
+                    let w = edge(points[a], points[b], p);
+                    if w < 0.0 || (w == 0.0 && !is_top_left(points[a], points[b])) {
+                        inside = false;
+                        break;
+                    }
+                    weights[i] = w / area;
+                }
+                if !inside {
+                    continue;
+                }
+
+                let uv = uvs[0] * weights[0] + uvs[1] * weights[1] + uvs[2] * weights[2];
+                let tint = tints[0] * weights[0] + tints[1] * weights[1] + tints[2] * weights[2];
+                let texel = sample(texture, settings, uv);
+                let dst = target.pixel_mut(x, y);
+                if shade == Shade::Normal {
+                    let src = shade_normal(texel, tint);
+                    *dst = (src.truncate() * src.w + dst.truncate() * (1.0 - src.w)).extend(dst.w);
+                } else if settings.premultiply_alpha {
+                    let src = texel * (tint.truncate() * tint.w).extend(tint.w);
+                    *dst = src + *dst * (1.0 - src.w);
+                } else {
+                    let src = texel * tint;
+                    let rgb = src.truncate() * src.w + dst.truncate() * (1.0 - src.w);
+                    let alpha = src.w + dst.w * (1.0 - src.w);
+                    *dst = rgb.extend(alpha);
+                }
+            }
+        }
+    }
+
+    /// Draw one view's sprites into the viewport area of a target
+    fn draw_view(
+        &mut self,
+        target: &mut LinearImage,
+        sprites: &SpriteBatch,
+        view_projection: &glam::Mat4,
+        viewport: ScreenRect,
+        textures: &dyn TextureLookup,
+        stats: &mut RenderStats,
+    ) {
+        stats.visible_sprites += sprites.len() as u32;
+        stats.culled_sprites += sprites.culled() as u32;
+
+        // Count draw calls as the GPU backend would without an atlas: one per texture run
+        let mut current_texture = None;
+        for sprite in sprites.iter() {
+            if let Some(mesh) = &sprite.mesh {
+                // Consecutive shapes share a draw call
+                if current_texture != Some(None) {
+                    current_texture = Some(None);
+                    stats.draw_calls += 1;
+                }
+                Self::draw_mesh(target, mesh, view_projection, viewport);
+                continue;
+            }
+            let Some(texture) = textures.texture(sprite.texture) else {
+                log::warn!("Texture not found for sprite: {:?}", sprite.texture);
+                stats.skipped_sprites += 1;
+                continue;
+            };
+            if texture.width == 0 || texture.height == 0 {
+                stats.skipped_sprites += 1;
+                continue;
+            }
+            self.used_textures.insert(sprite.texture.0);
+            if current_texture != Some(Some(sprite.texture)) {
+                current_texture = Some(Some(sprite.texture));
+                stats.draw_calls += 1;
+            }
+
+            let settings = textures.texture_settings(sprite.texture);
+            let vertices = SpriteBatch::generate_vertices(sprite, texture_size(texture));
+            stats.instances += (vertices.len() / 6) as u32;
+            for v in vertices.chunks_exact(6) {
+                Self::draw_triangle(target, [&v[0], &v[1], &v[2]], view_projection, viewport, texture, &settings, Shade::Color);
+                Self::draw_triangle(target, [&v[3], &v[4], &v[5]], view_projection, viewport, texture, &settings, Shade::Color);
+            }
+        }
+
+        if let Some(lighting) = sprites.lighting() {
+            Self::light_view(target, sprites, lighting, view_projection, viewport, textures);
+        }
+    }
+
+    /// Multiply the viewport area of a target by the lights reaching each
+    /// pixel, shading normal-mapped sprites with their normal map
+    fn light_view(
+        target: &mut LinearImage,
+        sprites: &SpriteBatch,
+        lighting: &Lighting,
+        view_projection: &glam::Mat4,
+        viewport: ScreenRect,
+        textures: &dyn TextureLookup,
+    ) {
+        let mut normals = LinearImage::new(target.width, target.height, flat_normal());
+        for sprite in sprites.iter() {
+            if sprite.mesh.is_some() {
+                // Already encoded, so drawn as plain color
+                if let Some(mesh) = &normal_instance(sprite, false).mesh {
+                    Self::draw_mesh(&mut normals, mesh, view_projection, viewport);
+                }
+                continue;
+            }
+            let Some(albedo) = textures.texture(sprite.texture).filter(|t| t.width > 0 && t.height > 0) else {
+                continue;
+            };
+            let mapped = has_normal_map(sprite, |id| textures.texture(id).is_some_and(|t| t.width > 0 && t.height > 0));
+            let instance = normal_instance(sprite, mapped);
+            let Some(texture) = textures.texture(instance.texture) else {
+                continue;
+            };
+            let settings = textures.texture_settings(instance.texture);
+            // Slice by the sprite's own texture, so the normal map lines up with it
+            let vertices = SpriteBatch::generate_vertices(&instance, texture_size(albedo));
+            for v in vertices.chunks_exact(6) {
+                for triangle in [[&v[0], &v[1], &v[2]], [&v[3], &v[4], &v[5]]] {
+                    Self::draw_triangle(&mut normals, triangle, view_projection, viewport, texture, &settings, Shade::Normal);
+                }
+            }
+        }
+
+        let inverse = view_projection.inverse();
+        for y in viewport.y..viewport.y + viewport.height {
+            for x in viewport.x..viewport.x + viewport.width {
+                let ndc = Vec2::new(
+                    (x - viewport.x) as f32 + 0.5,
+                    (y - viewport.y) as f32 + 0.5,
+                ) / Vec2::new(viewport.width as f32, viewport.height as f32);
+                let world = inverse * Vec4::new(ndc.x * 2.0 - 1.0, 1.0 - ndc.y * 2.0, 0.0, 1.0);
+                let normal = (normals.pixel(x, y).truncate() * 2.0 - 1.0).normalize();
+                let light = lighting.light_at(world.truncate().truncate() / world.w, normal);
+                let pixel = target.pixel_mut(x, y);
+                *pixel = (pixel.truncate() * light).extend(pixel.w);
+            }
+        }
+    }
+
+    /// Draw an untextured triangle list, as shapes and debug geometry are
+    fn draw_mesh(target: &mut LinearImage, mesh: &[ShapeVertex], view_projection: &glam::Mat4, viewport: ScreenRect) {
+        let white = TextureData { width: 1, height: 1, pixels: vec![255; 4] };
+        let settings = TextureImportSettings::default();
+        let vertices: Vec<SpriteVertex> = mesh
+            .iter()
+            .map(|v| SpriteVertex { position: v.position, tex_coords: [0.5, 0.5], color: v.color })
+            .collect();
+        for v in vertices.chunks_exact(3) {
+            Self::draw_triangle(target, [&v[0], &v[1], &v[2]], view_projection, viewport, &white, &settings, Shade::Color);
+        }
+    }
+
+    /// Draw a view's debug shapes over the viewport area of a target
+    fn draw_debug(target: &mut LinearImage, debug: &DebugDraw, camera: &Camera, viewport: ScreenRect) {
+        let geometry = debug.geometry(debug_pixel_size(camera, viewport));
+        if geometry.is_empty() {
+            return;
+        }
+        let view_projection = camera.view_projection();
+        Self::draw_mesh(target, &geometry.triangles, &view_projection, viewport);
+
+        let origin = Vec2::new(viewport.x as f32, viewport.y as f32);
+        let size = Vec2::new(viewport.width as f32, viewport.height as f32);
+        let to_screen = |position: [f32; 2]| {
+            let clip = view_projection * Vec4::new(position[0], position[1], 0.0, 1.0);
+            origin + Vec2::new((clip.x + 1.0) * 0.5 * size.x, (1.0 - clip.y) * 0.5 * size.y)
+        };
+        for line in geometry.lines.chunks_exact(2) {
+            let (start, end) = (to_screen(line[0].position), to_screen(line[1].position));
+            let color = Vec4::from(line[0].color);
+            // One pixel per step along the longer axis, sampled at pixel centers
+            let steps = (end - start).abs().max_element().ceil().max(1.0) as u32;
+            let mut last = None;
+            for step in 0..=steps {
+                let point = start.lerp(end, step as f32 / steps as f32);
+                if point.cmplt(origin).any() || point.cmpge(origin + size).any() {
+                    continue;
+                }
+                let pixel = (point.x as u32, point.y as u32);
+                if last == Some(pixel) {
+                    continue;
+                }
+                last = Some(pixel);
+                let dst = target.pixel_mut(pixel.0, pixel.1);
+                let rgb = color.truncate() * color.w + dst.truncate() * (1.0 - color.w);
+                *dst = rgb.extend(color.w + dst.w * (1.0 - color.w));
+            }
+        }
+    }
+
+    /// Encode the linear target into the sRGB frame buffer
+    fn resolve(&mut self) {
+        self.target.write_rgba8(&mut self.frame.pixels);
+    }
+
+    /// Render a camera with a render-texture target, keeping the result
+    fn render_target_view(&mut self, view: &RenderView<'_>, target: AssetId, textures: &dyn TextureLookup, stats: &mut RenderStats) {
+        let Some((width, height)) = textures.texture(target).map(|t| (t.width, t.height)) else {
+            log::warn!("Render texture not found for camera target: {:?}", target);
+            return;
+        };
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let mut image = LinearImage::new(width, height, Vec4::ZERO);
+        let viewport = clip_viewport(view.viewport, width, height);
+        let mut rendered = std::mem::take(&mut self.render_textures);
+        if !viewport.is_empty() {
+            let lookup = RenderedTextures {
+                rendered: &rendered,
+                textures,
+                exclude: Some(target),
+            };
+            self.draw_view(&mut image, view.sprites, &view.camera.view_projection(), viewport, &lookup, stats);
+        }
+        let mut image = apply_effects(image, &view.camera.post_process, textures);
+        if let Some(debug) = view.debug.filter(|_| !viewport.is_empty()) {
+            Self::draw_debug(&mut image, debug, view.camera, viewport);
+        }
+        rendered.insert(target, image.to_texture_data());
+        self.render_textures = rendered;
+    }
+}
+
+/// What `draw_triangle` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shade {
+    /// Texture color times the tint
+    Color,
+    /// The normal a `normal_instance` encodes, into a normal buffer
+    Normal,
+}
+
+/// Texture lookup that also serves render textures drawn this frame
+struct RenderedTextures<'a> {
+    rendered: &'a HashMap<AssetId, TextureData>,
+    textures: &'a dyn TextureLookup,
+    /// Render texture being drawn, which can't be read at the same time
+    exclude: Option<AssetId>,
+}
+
+impl TextureLookup for RenderedTextures<'_> {
+    fn texture(&self, id: AssetId) -> Option<&TextureData> {
+        if self.exclude == Some(id) {
+            return None;
+        }
+        self.rendered.get(&id).or_else(|| self.textures.texture(id))
+    }
+
+    fn texture_settings(&self, id: AssetId) -> TextureImportSettings {
+        let settings = self.textures.texture_settings(id);
+        if self.rendered.contains_key(&id) {
+            // Drawn over transparency with sRGB output, which leaves color premultiplied
+            TextureImportSettings {
+                premultiply_alpha: true,
+                srgb: true,
+                ..settings
+            }
+        } else {
+            settings
+        }
+    }
+}
+
+/// Clip a viewport to a target's size
+fn clip_viewport(viewport: ScreenRect, width: u32, height: u32) -> ScreenRect {
+    let x = viewport.x.min(width);
+    let y = viewport.y.min(height);
+    ScreenRect {
+        x,
+        y,
+        width: viewport.width.min(width - x),
+        height: viewport.height.min(height - y),
+    }
+}
+
+impl RenderBackend for SoftwareRenderer {
+    fn resize(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            *self = Self {
+                clear_color: self.clear_color,
+                used_textures: std::mem::take(&mut self.used_textures),
+                render_textures: std::mem::take(&mut self.render_textures),
+                ..Self::new(width, height)
+            };
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn set_clear_color(&mut self, color: Color) {
+        self.clear_color = color;
+    }
+
+    fn loaded_texture_ids(&self) -> Vec<AssetId> {
+        self.used_textures.iter().map(|&id| AssetId::new(id)).collect()
+    }
+
+    fn render_views(
+        &mut self,
+        views: &[RenderView<'_>],
+        textures: &dyn TextureLookup,
+    ) -> Result<RenderStats, RendererError> {
+        let clear = Vec4::from(self.clear_color.to_array());
+        let mut stats = RenderStats::default();
+
+        // Render textures first so screen views can display them
+        for view in views {
+            if let Some(target) = view.camera.target {
+                self.render_target_view(view, target, textures, &mut stats);
+            }
+        }
+
+        let mut target = std::mem::take(&mut self.target);
+        target.pixels.fill(clear);
+        let rendered = std::mem::take(&mut self.render_textures);
+        let lookup = RenderedTextures {
+            rendered: &rendered,
+            textures,
+            exclude: None,
+        };
+        for view in views.iter().filter(|view| view.camera.target.is_none()) {
+            let viewport = clip_viewport(view.viewport, self.width, self.height);
+            if viewport.is_empty() {
+                continue;
+            }
+            let view_projection = view.camera.view_projection();
+            if view.camera.post_process.is_empty() && view.sprites.lighting().is_none() {
+                self.draw_view(&mut target, view.sprites, &view_projection, viewport, &lookup, &mut stats);
+            } else {
+                // Drawn on its own over the clear color, then lit and processed into place
+                let mut image = LinearImage::new(viewport.width, viewport.height, clear);
+                let local = ScreenRect::full(viewport.width, viewport.height);
+                self.draw_view(&mut image, view.sprites, &view_projection, local, &lookup, &mut stats);
+                let image = apply_effects(image, &view.camera.post_process, textures);
+                target.blit(viewport.x, viewport.y, &image);
+            }
+            if let Some(debug) = view.debug {
+                Self::draw_debug(&mut target, debug, view.camera, viewport);
+            }
+        }
+        self.render_textures = rendered;
+        self.target = target;
+
+        self.resolve();
+        Ok(stats)
+    }
+
+    fn frame(&self) -> Option<&FrameBuffer> {
+        Some(&self.frame)
+    }
+}
+
+/// Size of a texture in pixels, as `SpriteInstance::pieces` expects it
+fn texture_size(texture: &TextureData) -> Vec2 {
+    Vec2::new(texture.width as f32, texture.height as f32)
+}
+
+/// Signed area (times two) of the triangle `a, b, p` in y-down screen space
+fn edge(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Top-left fill rule for an edge of a positive-area triangle
+fn is_top_left(a: Vec2, b: Vec2) -> bool {
+    let d = b - a;
+    (d.y == 0.0 && d.x > 0.0) || d.y < 0.0
+}
+
+/// Sample a texture as the GPU sampler configured by `settings` would,
+/// returning linear RGBA
+pub(crate) fn sample(texture: &TextureData, settings: &TextureImportSettings, uv: Vec2) -> Vec4 {
+    let position = uv * Vec2::new(texture.width as f32, texture.height as f32);
+    match settings.filter {
+        TextureFilter::Nearest => {
+            let p = position.floor();
+            texel(texture, settings, p.x as i64, p.y as i64)
+        }
+        TextureFilter::Linear => {
+            let p = position - 0.5;
+            let base = p.floor();
+            let t = p - base;
+            let (x, y) = (base.x as i64, base.y as i64);
+            let top = texel(texture, settings, x, y).lerp(texel(texture, settings, x + 1, y), t.x);
+            let bottom = texel(texture, settings, x, y + 1).lerp(texel(texture, settings, x + 1, y + 1), t.x);
+            top.lerp(bottom, t.y)
+        }
+    }
+}
+
+/// Fetch one texel, applying the wrap mode, as linear RGBA
+fn texel(texture: &TextureData, settings: &TextureImportSettings, x: i64, y: i64) -> Vec4 {
+    let x = wrap_coordinate(x, texture.width, settings.wrap);
+    let y = wrap_coordinate(y, texture.height, settings.wrap);
+    let i = ((y * texture.width + x) * 4) as usize;
+    let Some(texel) = texture.pixels.get(i..i + 4) else {
+        return Vec4::ZERO;
+    };
+
+    let alpha = texel[3] as f32 / 255.0;
+    if settings.srgb {
+        let lut = srgb_to_linear_table();
+        Vec4::new(lut[texel[0] as usize], lut[texel[1] as usize], lut[texel[2] as usize], alpha)
+    } else {
+        Vec4::new(texel[0] as f32 / 255.0, texel[1] as f32 / 255.0, texel[2] as f32 / 255.0, alpha)
+    }
+}
+
+fn wrap_coordinate(i: i64, size: u32, wrap: TextureWrap) -> u32 {
+    let size = size as i64;
+    let wrapped = match wrap {
+        TextureWrap::Clamp => i.clamp(0, size - 1),
+        TextureWrap::Repeat => i.rem_euclid(size),
+        TextureWrap::MirrorRepeat => {
+            let i = i.rem_euclid(size * 2);
+            if i < size {
+                i
+            } else {
+                size * 2 - 1 - i
+            }
+        }
+    };
+    wrapped as u32
+}
+
+/// sRGB byte to linear lookup table
+fn srgb_to_linear_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0.0; 256];
+        for (i, value) in table.iter_mut().enumerate() {
+            let c = i as f32 / 255.0;
+            *value = if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            };
+        }
+        table
+    })
+}
+
+/// Encode a linear channel as an sRGB byte
+pub(crate) fn linear_to_srgb(value: f32) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let encoded = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Camera;
+    use longhorn_core::{
+        Crt, GlobalLight2D, LightOccluder2D, OccluderShape, PointLight2D, PostEffect, Shape, ShapeFill, SliceBorder, Sprite,
+        SpriteDrawMode, Transform, ViewportRect, World,
+    };
+    use std::collections::HashMap;
+
+    fn white_texture() -> HashMap<AssetId, TextureData> {
+        let mut textures = HashMap::new();
+        textures.insert(
+            AssetId::new(1),
+            TextureData { width: 1, height: 1, pixels: vec![255, 255, 255, 255] },
+        );
+        textures
+    }
+
+    #[test]
+    fn test_clear_color() {
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.set_clear_color(Color::RED);
+        renderer.render_world(&World::new(), &white_texture(), &Camera::new(4.0, 4.0)).unwrap();
+
+        assert_eq!(renderer.frame().unwrap().pixel(2, 2), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_quad_covers_exact_pixels() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(4.0, 4.0)))
+            .with(Transform::new())
+            .build();
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let stats = renderer.render_world(&world, &white_texture(), &Camera::new(8.0, 8.0)).unwrap();
+        assert_eq!((stats.draw_calls, stats.instances), (1, 1));
+        let frame = renderer.frame().unwrap();
+
+        let covered = frame.pixels.chunks_exact(4).filter(|p| p[0] == 255).count();
+        assert_eq!(covered, 16);
+        assert_eq!(frame.pixel(2, 2), [255, 255, 255, 255]);
+        assert_eq!(frame.pixel(1, 1), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_shapes_draw_gradients_in_z_order_with_sprites() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(4.0, 4.0)))
+            .with(Transform::new())
+            .build();
+        let gradient = ShapeFill::LinearGradient { start: [1.0, 0.0, 0.0, 1.0], end: [0.0, 0.0, 1.0, 1.0], angle: 0.0 };
+        world
+            .spawn()
+            .with(Shape::rectangle(Vec2::new(8.0, 8.0)).with_fill(gradient).with_z_index(-1))
+            .with(Transform::new())
+            .build();
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let stats = renderer.render_world(&world, &white_texture(), &Camera::new(8.0, 8.0)).unwrap();
+        assert_eq!(stats.draw_calls, 2);
+        let frame = renderer.frame().unwrap();
+
+        // Red on the left fading to blue on the right, under the sprite
+        let (left, right) = (frame.pixel(0, 1), frame.pixel(7, 1));
+        assert!(left[0] > right[0] && left[2] < right[2]);
+        assert_eq!(frame.pixel(4, 1), [linear_to_srgb(7.0 / 16.0), 0, linear_to_srgb(9.0 / 16.0), 255]);
+        assert_eq!(frame.pixel(3, 3), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_alpha_blending_covers_diagonal_once() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::with_color(AssetId::new(1), Vec2::new(8.0, 8.0), [1.0, 1.0, 1.0, 0.5]))
+            .with(Transform::new())
+            .build();
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        renderer.render_world(&world, &white_texture(), &Camera::new(8.0, 8.0)).unwrap();
+        let frame = renderer.frame().unwrap();
+
+        // Half white over black in linear space, every pixel identical
+        let expected = frame.pixel(0, 0);
+        assert_eq!(expected[0], linear_to_srgb(0.5));
+        assert!(frame.pixels.chunks_exact(4).all(|p| p == expected));
+    }
+
+    #[test]
+    fn test_sampling_follows_import_settings() {
+        // Black and white texels side by side, stored as linear data
+        let texture = TextureData { width: 2, height: 1, pixels: vec![0, 0, 0, 255, 255, 255, 255, 255] };
+        let mut settings = TextureImportSettings { srgb: false, ..Default::default() };
+
+        assert_eq!(sample(&texture, &settings, Vec2::new(0.75, 0.5)).x, 1.0);
+        // Clamped coordinates stick to the edge texel
+        assert_eq!(sample(&texture, &settings, Vec2::new(1.25, 0.5)).x, 1.0);
+
+        settings.wrap = TextureWrap::Repeat;
+        assert_eq!(sample(&texture, &settings, Vec2::new(1.25, 0.5)).x, 0.0);
+        settings.wrap = TextureWrap::MirrorRepeat;
+        assert_eq!(sample(&texture, &settings, Vec2::new(1.25, 0.5)).x, 1.0);
+        assert_eq!(sample(&texture, &settings, Vec2::new(1.75, 0.5)).x, 0.0);
+
+        // Halfway between the texel centers blends both
+        settings.wrap = TextureWrap::Clamp;
+        settings.filter = TextureFilter::Linear;
+        assert_eq!(sample(&texture, &settings, Vec2::new(0.5, 0.5)).x, 0.5);
+
+        // sRGB data is decoded before filtering
+        let gray = TextureData { width: 1, height: 1, pixels: vec![188, 188, 188, 255] };
+        let srgb = sample(&gray, &TextureImportSettings::default(), Vec2::splat(0.5)).x;
+        assert!((srgb - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_premultiplied_textures_blend_as_premultiplied() {
+        struct Premultiplied(HashMap<AssetId, TextureData>);
+        impl TextureLookup for Premultiplied {
+            fn texture(&self, id: AssetId) -> Option<&TextureData> {
+                self.0.get(&id)
+            }
+            fn texture_settings(&self, _id: AssetId) -> TextureImportSettings {
+                TextureImportSettings { premultiply_alpha: true, srgb: false, ..Default::default() }
+            }
+        }
+
+        // Half-transparent white, premultiplied
+        let mut textures = HashMap::new();
+        textures.insert(AssetId::new(1), TextureData { width: 1, height: 1, pixels: vec![128, 128, 128, 128] });
+        let textures = Premultiplied(textures);
+
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(4.0, 4.0)))
+            .with(Transform::new())
+            .build();
+
+        let mut renderer = SoftwareRenderer::new(4, 4);
+        renderer.render_world(&world, &textures, &Camera::new(4.0, 4.0)).unwrap();
+
+        // Same result as straight alpha: half white over black
+        assert_eq!(renderer.frame().unwrap().pixel(1, 1)[0], linear_to_srgb(128.0 / 255.0));
+    }
+
+    #[test]
+    fn test_views_render_to_their_viewports() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(4.0, 4.0)))
+            .with(Transform::new())
+            .build();
+
+        // Left camera looks at the sprite, right camera looks away
+        let left = Camera::new(4.0, 8.0);
+        let mut right = Camera::new(4.0, 8.0);
+        right.position = Vec2::new(100.0, 0.0);
+        let left_sprites = SpriteBatch::collect_visible(&world, &left);
+        let right_sprites = SpriteBatch::collect_visible(&world, &right);
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let views = [
+            RenderView {
+                camera: &left,
+                sprites: &left_sprites,
+                viewport: ViewportRect::new(0.0, 0.0, 0.5, 1.0).to_pixels(8, 8),
+                debug: None,
+            },
+            RenderView {
+                camera: &right,
+                sprites: &right_sprites,
+                viewport: ViewportRect::new(0.5, 0.0, 0.5, 1.0).to_pixels(8, 8),
+                debug: None,
+            },
+        ];
+        let stats = renderer.render_views(&views, &white_texture()).unwrap();
+        assert_eq!((stats.visible_sprites, stats.culled_sprites), (1, 1));
+
+        // The sprite fills the left view only
+        let frame = renderer.frame().unwrap();
+        let covered: Vec<_> = (0..8).filter(|&x| frame.pixel(x, 4)[0] == 255).collect();
+        assert_eq!(covered, vec![0, 1, 2, 3]);
+        assert_eq!(frame.pixel(1, 1), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-draw")]
+    fn test_debug_shapes_draw_over_screen_views() {
+        let camera = Camera::new(8.0, 8.0);
+        let sprites = SpriteBatch::new();
+        let mut debug = DebugDraw::new();
+        debug.line(Vec2::new(-3.5, 0.5), Vec2::new(3.5, 0.5), Color::RED);
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let views = [RenderView { camera: &camera, sprites: &sprites, viewport: ScreenRect::full(8, 8), debug: Some(&debug) }];
+        renderer.render_views(&views, &white_texture()).unwrap();
+
+        let frame = renderer.frame().unwrap();
+        assert!((0..8).all(|x| frame.pixel(x, 3) == [255, 0, 0, 255]));
+        assert_eq!(frame.pixel(0, 4), [0, 0, 0, 255]);
+
+        // Disabled, nothing is queued or drawn
+        debug.set_enabled(false);
+        debug.line(Vec2::new(-3.5, 0.5), Vec2::new(3.5, 0.5), Color::RED);
+        let views = [RenderView { camera: &camera, sprites: &sprites, viewport: ScreenRect::full(8, 8), debug: Some(&debug) }];
+        renderer.render_views(&views, &white_texture()).unwrap();
+        assert_eq!(renderer.frame().unwrap().pixel(0, 3), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_camera_target_renders_into_texture_shown_by_sprites() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(2.0, 2.0)))
+            .with(Transform::new())
+            .build();
+        // Shows the render texture, away from what the target camera sees
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(2), Vec2::new(8.0, 8.0)))
+            .with(Transform::from_position(Vec2::new(100.0, 0.0)))
+            .build();
+
+        let mut textures = white_texture();
+        textures.insert(AssetId::new(2), TextureData { width: 4, height: 4, pixels: vec![0; 64] });
+
+        let target = Camera::new(4.0, 4.0).with_target(AssetId::new(2));
+        let mut screen = Camera::new(8.0, 8.0);
+        screen.position = Vec2::new(100.0, 0.0);
+        let target_sprites = SpriteBatch::collect_visible(&world, &target);
+        let screen_sprites = SpriteBatch::collect_visible(&world, &screen);
+
+        // Listed after the screen view, but still rendered first
+        let views = [
+            RenderView { camera: &screen, sprites: &screen_sprites, viewport: ScreenRect::full(8, 8), debug: None },
+            RenderView { camera: &target, sprites: &target_sprites, viewport: ScreenRect::full(4, 4), debug: None },
+        ];
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        renderer.render_views(&views, &textures).unwrap();
+
+        let rendered = renderer.render_texture(AssetId::new(2)).unwrap();
+        assert_eq!(&rendered.pixels[(1 + 4) * 4..(1 + 4) * 4 + 4], &[255, 255, 255, 255]);
+        assert_eq!(&rendered.pixels[0..4], &[0, 0, 0, 0]);
+
+        // The 2x2 white square in the middle of the texture, scaled up
+        let frame = renderer.frame().unwrap();
+        assert_eq!(frame.pixel(3, 3), [255, 255, 255, 255]);
+        assert_eq!(frame.pixel(0, 0), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_view_effects_apply_within_viewport() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(8.0, 8.0)))
+            .with(Transform::new())
+            .build();
+
+        let camera = Camera::new(8.0, 8.0).with_effect(PostEffect::Crt(Crt { curvature: 0.0, scanlines: 1.0 }));
+        let sprites = SpriteBatch::collect_visible(&world, &camera);
+        let mut renderer = SoftwareRenderer::new(16, 8);
+        renderer.set_clear_color(Color::RED);
+        let views = [RenderView {
+            camera: &camera,
+            sprites: &sprites,
+            viewport: ScreenRect { x: 8, y: 0, width: 8, height: 8 },
+            debug: None,
+        }];
+        renderer.render_views(&views, &white_texture()).unwrap();
+
+        // Odd rows of the view are blacked out; the rest of the frame is untouched
+        let frame = renderer.frame().unwrap();
+        assert_eq!(frame.pixel(12, 2), [255, 255, 255, 255]);
+        assert_eq!(frame.pixel(12, 3), [0, 0, 0, 255]);
+        assert_eq!(frame.pixel(2, 3), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_lights_shade_and_shadow_sprites() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(16.0, 16.0)))
+            .with(Transform::new())
+            .build();
+        world
+            .spawn()
+            .with(GlobalLight2D { color: [1.0; 3], intensity: 0.25 })
+            .build();
+        world
+            .spawn()
+            .with(PointLight2D { radius: 16.0, height: 0.0, cast_shadows: true, ..Default::default() })
+            .with(Transform::from_position(Vec2::new(-8.0, 0.0)))
+            .build();
+        world
+            .spawn()
+            .with(LightOccluder2D::new(OccluderShape::Box { size: Vec2::new(2.0, 16.0) }))
+            .with(Transform::new())
+            .build();
+
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.render_world(&world, &white_texture(), &Camera::new(16.0, 16.0)).unwrap();
+        let frame = renderer.frame().unwrap();
+
+        // Near the light: ambient plus most of the light
+        assert!(frame.pixel(1, 8)[0] > linear_to_srgb(1.0 - 0.15));
+        // Behind the wall only the ambient light reaches
+        assert_eq!(frame.pixel(12, 8)[0], linear_to_srgb(0.25));
+        // The wall itself stays lit
+        assert!(frame.pixel(8, 8)[0] > linear_to_srgb(0.25));
+    }
+
+    #[test]
+    fn test_nine_slice_keeps_corners_and_stretches_center() {
+        // Red corners, green edges and a blue center
+        let mut pixels = Vec::new();
+        for y in 0..3 {
+            for x in 0..3 {
+                pixels.extend_from_slice(match (x == 1, y == 1) {
+                    (false, false) => &[255, 0, 0, 255],
+                    (true, true) => &[0, 0, 255, 255],
+                    _ => &[0, 255, 0, 255],
+                });
+            }
+        }
+        let mut textures = HashMap::new();
+        textures.insert(AssetId::new(1), TextureData { width: 3, height: 3, pixels });
+
+        let mut world = World::new();
+        let border = SliceBorder::uniform(1.0);
+        world
+            .spawn()
+            .with(Sprite::new(AssetId::new(1), Vec2::new(8.0, 4.0)).with_draw_mode(SpriteDrawMode::NineSlice { border }))
+            .with(Transform::new())
+            .build();
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        let stats = renderer.render_world(&world, &textures, &Camera::new(8.0, 8.0)).unwrap();
+        assert_eq!(stats.instances, 9);
+        let frame = renderer.frame().unwrap();
+
+        // The sprite covers rows 2..6; corners stay one pixel
+        assert_eq!(frame.pixel(0, 2), [255, 0, 0, 255]);
+        assert_eq!(frame.pixel(7, 5), [255, 0, 0, 255]);
+        assert_eq!(frame.pixel(3, 2), [0, 255, 0, 255]);
+        assert_eq!(frame.pixel(0, 4), [0, 255, 0, 255]);
+        assert_eq!(frame.pixel(1, 3), [0, 0, 255, 255]);
+        assert_eq!(frame.pixel(6, 4), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_normal_maps_follow_sprite_flip() {
+        struct NormalMap(HashMap<AssetId, TextureData>);
+        impl TextureLookup for NormalMap {
+            fn texture(&self, id: AssetId) -> Option<&TextureData> {
+                self.0.get(&id)
+            }
+            fn texture_settings(&self, id: AssetId) -> TextureImportSettings {
+                TextureImportSettings { srgb: id != AssetId::new(2), ..Default::default() }
+            }
+        }
+
+        // Every normal of the map faces -X
+        let mut textures = white_texture();
+        textures.insert(AssetId::new(2), TextureData { width: 1, height: 1, pixels: vec![0, 128, 128, 255] });
+        let textures = NormalMap(textures);
+
+        let render = |flip_x: bool| {
+            let mut sprite = Sprite::new(AssetId::new(1), Vec2::new(4.0, 4.0)).with_normal_map(AssetId::new(2));
+            sprite.flip_x = flip_x;
+            let mut world = World::new();
+            world.spawn().with(sprite).with(Transform::new()).build();
+            world
+                .spawn()
+                .with(PointLight2D { radius: 16.0, height: 0.0, ..Default::default() })
+                .with(Transform::from_position(Vec2::new(8.0, 0.0)))
+                .build();
+
+            let mut renderer = SoftwareRenderer::new(4, 4);
+            renderer.render_world(&world, &textures, &Camera::new(4.0, 4.0)).unwrap();
+            renderer.frame().unwrap().pixel(2, 2)[0]
+        };
+
+        // Facing away from the light on the right, until flipped to face it
+        assert!(render(false) < 8);
+        assert!(render(true) > 128);
+    }
+
+    #[test]
+    fn test_image_diff() {
+        let a = FrameBuffer::new(2, 2);
+        let mut b = a.clone();
+        b.pixels[0] = 3;
+
+        assert!(a.matches(&b, 3));
+        assert!(!a.matches(&b, 2));
+        assert_eq!(a.diff(&b, 0).unwrap().differing_pixels, 1);
+        assert!(a.diff(&FrameBuffer::new(1, 1), 0).is_none());
+    }
+}
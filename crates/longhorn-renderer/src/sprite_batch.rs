@@ -1,7 +1,8 @@
-use crate::Color;
+use crate::{pipeline::ShapeVertex, Camera, Color, Lighting};
 use bytemuck::{Pod, Zeroable};
 use glam::Vec2;
-use longhorn_core::AssetId;
+use longhorn_core::{AssetId, GlobalTransform, MaterialParams, Rect, Sprite, SpriteDrawMode, Transform, World};
+use std::sync::Arc;
 
 /// Vertex data for sprite rendering
 #[repr(C)]
@@ -42,14 +43,39 @@ impl SpriteVertex {
     }
 }
 
+/// Full texture UV rect (u0, v0, u1, v1)
+pub const FULL_UV_RECT: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+/// Most tiles a tiled sprite repeats along one axis; past this the tiles
+/// stretch to cover the sprite
+pub const MAX_TILES_PER_AXIS: usize = 128;
+
 /// Instance data for a single sprite
 #[derive(Debug, Clone)]
 pub struct SpriteInstance {
     pub position: Vec2,
     pub size: Vec2,
+    /// Rotation around the sprite center (radians, counter-clockwise)
+    pub rotation: f32,
+    /// Texture region as (u0, v0, u1, v1); v = 0 is the top of the image
+    pub uv_rect: [f32; 4],
     pub color: Color,
     pub texture: AssetId,
     pub z_index: i32,
+    /// Material drawing the instance; 0 uses the built-in sprite shader
+    pub material: AssetId,
+    /// The entity's material parameter values
+    pub material_params: Option<MaterialParams>,
+    /// Normal map shading the instance in lit views; 0 for a flat sprite
+    pub normal_map: AssetId,
+    /// How the texture fills `size`; see `pieces`
+    pub draw_mode: SpriteDrawMode,
+    /// World size of one texture pixel in the parts of the sprite that
+    /// aren't stretched
+    pub texel_size: Vec2,
+    /// World-space triangles drawn untextured instead of the quad, for
+    /// shapes; `position` and `size` are then their bounding box
+    pub mesh: Option<Arc<[ShapeVertex]>>,
 }
 
 impl SpriteInstance {
@@ -57,10 +83,116 @@ impl SpriteInstance {
         Self {
             position,
             size,
+            rotation: 0.0,
+            uv_rect: FULL_UV_RECT,
             color: Color::WHITE,
             texture,
             z_index: 0,
+            material: AssetId::new(0),
+            material_params: None,
+            normal_map: AssetId::new(0),
+            draw_mode: SpriteDrawMode::Simple,
+            texel_size: Vec2::ONE,
+            mesh: None,
+        }
+    }
+
+    /// Build an instance from a sprite component and its world transform
+    ///
+    /// Scale multiplies the sprite size and `flip_x`/`flip_y` mirror the UVs.
+    /// Every render backend goes through this so they agree on placement.
+    pub fn from_sprite(sprite: &Sprite, transform: &GlobalTransform) -> Self {
+        let [mut u0, mut v0, mut u1, mut v1] = FULL_UV_RECT;
+        if sprite.flip_x {
+            std::mem::swap(&mut u0, &mut u1);
+        }
+        if sprite.flip_y {
+            std::mem::swap(&mut v0, &mut v1);
+        }
+
+        Self {
+            position: transform.position,
+            size: sprite.size * transform.scale,
+            rotation: transform.rotation,
+            uv_rect: [u0, v0, u1, v1],
+            color: Color::new(sprite.color[0], sprite.color[1], sprite.color[2], sprite.color[3]),
+            texture: sprite.texture,
+            z_index: 0,
+            material: sprite.material,
+            material_params: None,
+            normal_map: sprite.normal_map,
+            draw_mode: sprite.draw_mode,
+            texel_size: transform.scale.abs(),
+            mesh: None,
+        }
+    }
+
+    /// Split the instance into the simple quads its draw mode is made of,
+    /// given the size of its texture in pixels
+    ///
+    /// A `Simple` instance is its own only piece. Nine-slice corners keep
+    /// their size in texels and shrink together when the sprite is smaller
+    /// than its borders. Tiles start at the top-left corner of the area they
+    /// fill and the last one on each axis is cropped.
+    pub fn pieces(&self, texture_size: Vec2) -> Vec<SpriteInstance> {
+        let (border, tiled) = match self.draw_mode {
+            SpriteDrawMode::Simple => return vec![self.clone()],
+            SpriteDrawMode::NineSlice { border } => (border, false),
+            SpriteDrawMode::Tiled => (Default::default(), true),
+            SpriteDrawMode::TiledNineSlice { border } => (border, true),
+        };
+
+        let [u0, v0, u1, v1] = self.uv_rect;
+        // A flipped rect starts on the texture's right (or bottom) edge
+        let (start_x, end_x) = if u0 <= u1 { (border.left, border.right) } else { (border.right, border.left) };
+        let (start_y, end_y) = if v0 <= v1 { (border.top, border.bottom) } else { (border.bottom, border.top) };
+        let columns = slice_axis(
+            self.size.x.abs(),
+            [start_x, end_x],
+            self.texel_size.x,
+            texture_size.x * (u1 - u0).abs(),
+            [u0, u1],
+            tiled,
+        );
+        let rows = slice_axis(
+            self.size.y.abs(),
+            [start_y, end_y],
+            self.texel_size.y,
+            texture_size.y * (v1 - v0).abs(),
+            [v0, v1],
+            tiled,
+        );
+
+        // Slices run from the quad's top-left corner, which negative sizes mirror
+        let sign = Vec2::new(self.size.x.signum(), self.size.y.signum());
+        let (sin, cos) = self.rotation.sin_cos();
+        let mut pieces = Vec::with_capacity(columns.len() * rows.len());
+        for row in &rows {
+            for column in &columns {
+                let center = Vec2::new(
+                    column.start + column.length / 2.0 - self.size.x.abs() / 2.0,
+                    self.size.y.abs() / 2.0 - row.start - row.length / 2.0,
+                ) * sign;
+                let mut piece = self.clone();
+                piece.position = self.position + Vec2::new(center.x * cos - center.y * sin, center.x * sin + center.y * cos);
+                piece.size = Vec2::new(column.length, row.length) * sign;
+                piece.uv_rect = [column.uv[0], row.uv[0], column.uv[1], row.uv[1]];
+                piece.draw_mode = SpriteDrawMode::Simple;
+                pieces.push(piece);
+            }
         }
+        pieces
+    }
+
+    /// World-space bounding box of the (possibly rotated) quad
+    pub fn bounds(&self) -> Rect {
+        let half = self.size.abs() / 2.0;
+        let (sin, cos) = self.rotation.sin_cos();
+        let extent = Vec2::new(
+            half.x * cos.abs() + half.y * sin.abs(),
+            half.x * sin.abs() + half.y * cos.abs(),
+        );
+        Rect::new(self.position - extent, self.position + extent)
     }
 
     pub fn with_color(mut self, color: Color) -> Self {
@@ -72,11 +204,26 @@ impl SpriteInstance {
         self.z_index = z_index;
         self
     }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Use an entity's material parameter values, if it has any
+    pub fn with_material_params(mut self, params: Option<&MaterialParams>) -> Self {
+        self.material_params = params.cloned();
+        self
+    }
 }
 
 /// Batch of sprites for rendering
 pub struct SpriteBatch {
     sprites: Vec<SpriteInstance>,
+    /// Sprites left out by culling when the batch was collected
+    culled: usize,
+    /// Lights of the world the batch was collected from, if it has any
+    lighting: Option<Lighting>,
 }
 
 impl SpriteBatch {
@@ -84,6 +231,8 @@ impl SpriteBatch {
     pub fn new() -> Self {
         Self {
             sprites: Vec::new(),
+            culled: 0,
+            lighting: None,
         }
     }
 
@@ -95,6 +244,28 @@ impl SpriteBatch {
     /// Clear all sprites from the batch
     pub fn clear(&mut self) {
         self.sprites.clear();
+        self.culled = 0;
+        self.lighting = None;
+    }
+
+    /// Number of sprites culled while collecting this batch
+    pub fn culled(&self) -> usize {
+        self.culled
+    }
+
+    /// Record sprites culled while collecting this batch
+    pub fn set_culled(&mut self, culled: usize) {
+        self.culled = culled;
+    }
+
+    /// Lights the batch is drawn with; `None` draws it unlit
+    pub fn lighting(&self) -> Option<&Lighting> {
+        self.lighting.as_ref()
+    }
+
+    /// Set the lights the batch is drawn with
+    pub fn set_lighting(&mut self, lighting: Option<Lighting>) {
+        self.lighting = lighting;
     }
 
     /// Get the number of sprites in the batch
@@ -107,11 +278,54 @@ impl SpriteBatch {
         self.sprites.is_empty()
     }
 
-    /// Sort sprites by texture (for batching) then z-index (for layering)
+    /// Collect every sprite in the world, plus the glyphs of laid-out texts,
+    /// the tiles of tilemaps, live particles and shapes, and the world's lights
+    ///
+    /// Uses `GlobalTransform` when present and falls back to the local
+    /// `Transform` for entities that haven't been propagated yet. Sprites
+    /// without either are skipped.
+    pub fn collect(world: &World) -> Self {
+        Self::collect_in(world, None)
+    }
+
+    /// Collect the sprites whose bounds overlap the camera's visible area
+    ///
+    /// Tests every sprite; for large worlds use a `SpriteIndex` instead.
+    pub fn collect_visible(world: &World, camera: &Camera) -> Self {
+        Self::collect_in(world, Some(camera.visible_rect()))
+    }
+
+    fn collect_in(world: &World, visible: Option<Rect>) -> Self {
+        let mut batch = Self::new();
+        for (_, (sprite, global, local, params)) in world
+            .query::<(&Sprite, Option<&GlobalTransform>, Option<&Transform>, Option<&MaterialParams>)>()
+            .iter()
+        {
+            let Some(transform) = world_transform(global, local) else {
+                continue;
+            };
+            let instance = SpriteInstance::from_sprite(sprite, &transform).with_material_params(params);
+            if visible.is_some_and(|visible| !visible.intersects(&instance.bounds())) {
+                batch.culled += 1;
+                continue;
+            }
+            batch.add(instance);
+        }
+        batch.culled += crate::text::collect_text(world, visible, &mut batch);
+        batch.culled += crate::tilemap::collect_tilemaps(world, visible, &mut batch);
+        batch.culled += crate::particles::collect_particles(world, visible, &mut batch);
+        batch.culled += crate::shape::collect_shapes(world, visible, &mut batch);
+        batch.lighting = Lighting::collect(world, visible);
+        batch
+    }
+
+    /// Sort sprites by z-index (for layering) then material and texture (for batching)
     pub fn sort(&mut self) {
-        // Sort by texture first to group sprites for efficient batching,
-        // then by z_index for proper layering within each texture group
-        self.sprites.sort_by_key(|s| (s.texture.0, s.z_index));
+        // Layering must win over batching, otherwise sprites with different
+        // textures draw in texture order; within a layer, group by material
+        // and texture. The sort is stable, so equal keys keep their
+        // collection order.
+        self.sprites.sort_by_key(|s| (s.z_index, s.material.0, s.texture.0));
     }
 
     /// Get an iterator over the sprites
@@ -119,16 +333,50 @@ impl SpriteBatch {
         self.sprites.iter()
     }
 
-    /// Generate vertices for a sprite (2 triangles = 6 vertices)
-    pub fn generate_vertices(sprite: &SpriteInstance) -> [SpriteVertex; 6] {
-        let half_width = sprite.size.x / 2.0;
-        let half_height = sprite.size.y / 2.0;
+    /// Generate vertices for a sprite, 2 triangles (6 vertices) for each of
+    /// its pieces (see `SpriteInstance::pieces`)
+    pub fn generate_vertices(sprite: &SpriteInstance, texture_size: Vec2) -> Vec<SpriteVertex> {
+        sprite.pieces(texture_size).iter().flat_map(Self::quad_vertices).collect()
+    }
+
+    /// Copy of the batch with every sprite split into its pieces, or `None`
+    /// when all sprites are drawn as simple quads
+    ///
+    /// `texture_size` gives a texture's size in pixels; sprites whose
+    /// texture has none are kept whole.
+    pub fn expand_draw_modes(&self, texture_size: impl Fn(AssetId) -> Option<Vec2>) -> Option<SpriteBatch> {
+        if self.sprites.iter().all(|sprite| sprite.draw_mode.is_simple()) {
+            return None;
+        }
+        let mut expanded = Self::new();
+        for sprite in &self.sprites {
+            match texture_size(sprite.texture) {
+                Some(size) if !sprite.draw_mode.is_simple() => expanded.sprites.extend(sprite.pieces(size)),
+                _ => expanded.add(sprite.clone()),
+            }
+        }
+        expanded.culled = self.culled;
+        expanded.lighting = self.lighting.clone();
+        Some(expanded)
+    }
+
+    /// Generate the vertices of one quad (2 triangles = 6 vertices)
+    fn quad_vertices(sprite: &SpriteInstance) -> [SpriteVertex; 6] {
+        let half = sprite.size / 2.0;
+        let (sin, cos) = sprite.rotation.sin_cos();
+        let corner = |x: f32, y: f32| -> [f32; 2] {
+            [
+                sprite.position.x + x * cos - y * sin,
+                sprite.position.y + x * sin + y * cos,
+            ]
+        };
 
-        let x0 = sprite.position.x - half_width;
-        let y0 = sprite.position.y - half_height;
-        let x1 = sprite.position.x + half_width;
-        let y1 = sprite.position.y + half_height;
+        let top_left = corner(-half.x, half.y);
+        let bottom_left = corner(-half.x, -half.y);
+        let bottom_right = corner(half.x, -half.y);
+        let top_right = corner(half.x, half.y);
 
+        let [u0, v0, u1, v1] = sprite.uv_rect;
         let color = sprite.color.to_array();
 
         // Two triangles forming a quad
@@ -137,42 +385,249 @@ impl SpriteBatch {
         [
             // Triangle 1
             SpriteVertex {
-                position: [x0, y1], // top-left
-                tex_coords: [0.0, 0.0],
+                position: top_left,
+                tex_coords: [u0, v0],
                 color,
             },
             SpriteVertex {
-                position: [x0, y0], // bottom-left
-                tex_coords: [0.0, 1.0],
+                position: bottom_left,
+                tex_coords: [u0, v1],
                 color,
             },
             SpriteVertex {
-                position: [x1, y0], // bottom-right
-                tex_coords: [1.0, 1.0],
+                position: bottom_right,
+                tex_coords: [u1, v1],
                 color,
             },
             // Triangle 2
             SpriteVertex {
-                position: [x0, y1], // top-left
-                tex_coords: [0.0, 0.0],
+                position: top_left,
+                tex_coords: [u0, v0],
                 color,
             },
             SpriteVertex {
-                position: [x1, y0], // bottom-right
-                tex_coords: [1.0, 1.0],
+                position: bottom_right,
+                tex_coords: [u1, v1],
                 color,
             },
             SpriteVertex {
-                position: [x1, y1], // top-right
-                tex_coords: [1.0, 0.0],
+                position: top_right,
+                tex_coords: [u1, v0],
                 color,
             },
         ]
     }
 }
 
+/// A run of a sprite along one axis, from the start of its UV rect
+struct Slice {
+    start: f32,
+    length: f32,
+    uv: [f32; 2],
+}
+
+/// Slice one axis of a sprite `length` world units long into its start
+/// border, middle and end border, repeating the middle when `tiled`
+///
+/// Borders are in texels, from the `uv` start and end sides; `texels` is the
+/// size of the UV rect in texels.
+fn slice_axis(length: f32, border: [f32; 2], texel: f32, texels: f32, uv: [f32; 2], tiled: bool) -> Vec<Slice> {
+    let mut slices = Vec::new();
+    if length <= 0.0 || texels <= 0.0 {
+        return slices;
+    }
+    let start_texels = border[0].clamp(0.0, texels);
+    let border = [start_texels, border[1].clamp(0.0, texels - start_texels)];
+    let uv_at = |texel_offset: f32| uv[0] + (uv[1] - uv[0]) * texel_offset / texels;
+
+    let mut start = border[0] * texel;
+    let mut end = border[1] * texel;
+    // Corners shrink together when they don't fit
+    if start + end > length {
+        let fit = length / (start + end);
+        start *= fit;
+        end *= fit;
+    }
+    let middle = length - start - end;
+    let middle_uv = [uv_at(border[0]), uv_at(texels - border[1])];
+
+    let mut push = |slice: Slice| {
+        if slice.length > 0.0 {
+            slices.push(slice);
+        }
+    };
+    push(Slice {
+        start: 0.0,
+        length: start,
+        uv: [uv[0], middle_uv[0]],
+    });
+    let tile = (texels - border[0] - border[1]) * texel;
+    if tiled && tile > 0.0 {
+        let tile = tile.max(middle / MAX_TILES_PER_AXIS as f32);
+        let mut offset = 0.0;
+        while middle - offset > 1e-4 {
+            let length = tile.min(middle - offset);
+            let fraction = length / tile;
+            push(Slice {
+                start: start + offset,
+                length,
+                uv: [middle_uv[0], middle_uv[0] + (middle_uv[1] - middle_uv[0]) * fraction],
+            });
+            offset += tile;
+        }
+    } else {
+        push(Slice {
+            start,
+            length: middle,
+            uv: middle_uv,
+        });
+    }
+    push(Slice {
+        start: start + middle,
+        length: end,
+        uv: [middle_uv[1], uv[1]],
+    });
+    slices
+}
+
+/// The transform sprites are drawn with: `GlobalTransform`, else the local `Transform`
+pub(crate) fn world_transform(
+    global: Option<&GlobalTransform>,
+    local: Option<&Transform>,
+) -> Option<GlobalTransform> {
+    match (global, local) {
+        (Some(global), _) => Some(*global),
+        (None, Some(local)) => Some(GlobalTransform::from_transform(local)),
+        (None, None) => None,
+    }
+}
+
 impl Default for SpriteBatch {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_layers_before_textures() {
+        let mut batch = SpriteBatch::new();
+        batch.add(SpriteInstance::new(Vec2::ZERO, Vec2::ONE, AssetId::new(1)).with_z_index(1));
+        batch.add(SpriteInstance::new(Vec2::ZERO, Vec2::ONE, AssetId::new(2)).with_z_index(0));
+        batch.add(SpriteInstance::new(Vec2::ZERO, Vec2::ONE, AssetId::new(1)).with_z_index(0));
+        batch.sort();
+
+        let order: Vec<_> = batch.iter().map(|s| (s.z_index, s.texture.0)).collect();
+        assert_eq!(order, vec![(0, 1), (0, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn test_generate_vertices_rotation_and_flip() {
+        let mut sprite = Sprite::new(AssetId::new(1), Vec2::new(2.0, 2.0));
+        sprite.flip_x = true;
+        let transform = GlobalTransform {
+            position: Vec2::new(10.0, 0.0),
+            rotation: std::f32::consts::FRAC_PI_2,
+            scale: Vec2::ONE,
+        };
+
+        let vertices = SpriteBatch::generate_vertices(&SpriteInstance::from_sprite(&sprite, &transform), Vec2::ONE);
+
+        // Top-left corner (-1, 1) rotated 90 degrees lands at (-1, -1) from the center
+        assert!((vertices[0].position[0] - 9.0).abs() < 1e-5);
+        assert!((vertices[0].position[1] + 1.0).abs() < 1e-5);
+        // Flipped horizontally, the left edge samples u = 1
+        assert_eq!(vertices[0].tex_coords, [1.0, 0.0]);
+    }
+
+    fn nine_slice(size: Vec2, inset: f32) -> SpriteInstance {
+        let sprite = Sprite::new(AssetId::new(1), size).with_draw_mode(SpriteDrawMode::NineSlice {
+            border: longhorn_core::SliceBorder::uniform(inset),
+        });
+        SpriteInstance::from_sprite(&sprite, &GlobalTransform::default())
+    }
+
+    #[test]
+    fn test_nine_slice_pieces() {
+        let pieces = nine_slice(Vec2::new(64.0, 32.0), 4.0).pieces(Vec2::splat(16.0));
+        assert_eq!(pieces.len(), 9);
+
+        // Top-left corner keeps its 4x4 texels
+        let corner = &pieces[0];
+        assert_eq!(corner.size, Vec2::splat(4.0));
+        assert_eq!(corner.position, Vec2::new(-30.0, 14.0));
+        assert_eq!(corner.uv_rect, [0.0, 0.0, 0.25, 0.25]);
+
+        // The center stretches over the rest
+        let center = &pieces[4];
+        assert_eq!(center.size, Vec2::new(56.0, 24.0));
+        assert_eq!(center.position, Vec2::ZERO);
+        assert_eq!(center.uv_rect, [0.25, 0.25, 0.75, 0.75]);
+        assert!(pieces.iter().all(|piece| piece.draw_mode.is_simple()));
+    }
+
+    #[test]
+    fn test_nine_slice_corners_shrink_to_fit() {
+        let pieces = nine_slice(Vec2::splat(4.0), 4.0).pieces(Vec2::splat(16.0));
+        assert_eq!(pieces.len(), 4);
+        assert!(pieces.iter().all(|piece| piece.size == Vec2::splat(2.0)));
+        assert_eq!(pieces[3].uv_rect, [0.75, 0.75, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_tiled_pieces_crop_last_tile_and_follow_flip() {
+        let mut sprite = Sprite::new(AssetId::new(1), Vec2::new(40.0, 16.0)).with_draw_mode(SpriteDrawMode::Tiled);
+        let instance = SpriteInstance::from_sprite(&sprite, &GlobalTransform::default());
+        let pieces = instance.pieces(Vec2::splat(16.0));
+        let columns: Vec<_> = pieces.iter().map(|p| (p.position.x, p.size.x, p.uv_rect[0], p.uv_rect[2])).collect();
+        assert_eq!(columns, vec![(-12.0, 16.0, 0.0, 1.0), (4.0, 16.0, 0.0, 1.0), (16.0, 8.0, 0.0, 0.5)]);
+
+        // Flipped, tiles still start on the left but sample the texture mirrored
+        sprite.flip_x = true;
+        let pieces = SpriteInstance::from_sprite(&sprite, &GlobalTransform::default()).pieces(Vec2::splat(16.0));
+        assert_eq!(pieces[2].uv_rect[0], 1.0);
+        assert_eq!(pieces[2].uv_rect[2], 0.5);
+        assert_eq!(SpriteBatch::generate_vertices(&instance, Vec2::splat(16.0)).len(), 18);
+
+        // Tiles never exceed the per-axis limit
+        sprite.size = Vec2::new(16.0 * 1000.0, 16.0);
+        let pieces = SpriteInstance::from_sprite(&sprite, &GlobalTransform::default()).pieces(Vec2::splat(16.0));
+        assert!(pieces.len() <= MAX_TILES_PER_AXIS + 1);
+    }
+
+    #[test]
+    fn test_bounds_respect_rotation_and_scale() {
+        let sprite = Sprite::new(AssetId::new(1), Vec2::new(4.0, 2.0));
+        let transform = GlobalTransform {
+            position: Vec2::new(10.0, 5.0),
+            rotation: std::f32::consts::FRAC_PI_2,
+            scale: Vec2::new(2.0, -1.0),
+        };
+
+        // 8x2 after scaling (negative scale mirrors, it doesn't shrink), 2x8 after rotating
+        let bounds = SpriteInstance::from_sprite(&sprite, &transform).bounds();
+        assert!((bounds.min - Vec2::new(9.0, 1.0)).length() < 1e-5);
+        assert!((bounds.max - Vec2::new(11.0, 9.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_collect_visible_culls_offscreen_sprites() {
+        let mut world = World::new();
+        for x in [0.0, 52.0, 500.0] {
+            world
+                .spawn()
+                .with(Sprite::new(AssetId::new(1), Vec2::new(10.0, 10.0)))
+                .with(Transform::from_position(Vec2::new(x, 0.0)))
+                .build();
+        }
+
+        // Visible area is -50..50; the sprite at 52 pokes in by 3 units
+        let batch = SpriteBatch::collect_visible(&world, &Camera::new(100.0, 100.0));
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.culled(), 1);
+        assert_eq!(SpriteBatch::collect(&world).len(), 3);
+    }
+}
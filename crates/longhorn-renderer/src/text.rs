@@ -0,0 +1,594 @@
+use crate::sprite_batch::world_transform;
+use crate::{AtlasPacker, AtlasRegion, Color, SpriteBatch, SpriteInstance};
+use glam::Vec2;
+use longhorn_assets::{
+    AssetHandle, AssetManager, AssetSource, FontData, TextureData, TextureFilter, TextureImportSettings,
+};
+use longhorn_core::{AssetId, EntityHandle, GlobalTransform, Rect, SpriteDrawMode, Text, TextAlign, Transform, World};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Size of each glyph atlas page in pixels
+pub const GLYPH_PAGE_SIZE: u32 = 512;
+
+/// A character placed by `layout_text`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedChar {
+    pub c: char,
+    /// Pen position on the baseline, relative to the text anchor with y
+    /// growing downwards from the top of the first line
+    pub pen: Vec2,
+}
+
+/// Result of laying out a text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextLayout {
+    /// Visible characters (whitespace is left out)
+    pub chars: Vec<PlacedChar>,
+    pub line_count: usize,
+    /// Width of the widest line and height of all lines
+    pub size: Vec2,
+}
+
+/// Break a text into lines and place its characters
+///
+/// Lines break at `\n` and, with a wrap width, at the last space that keeps
+/// the line within it; words wider than the wrap width break between
+/// characters. Kerning is applied between consecutive characters.
+pub fn layout_text(text: &Text, font: &FontData) -> TextLayout {
+    let size = text.size;
+    let metrics = font.metrics(size);
+    let mut layout = TextLayout::default();
+
+    for paragraph in text.content.split('\n') {
+        let paragraph: Vec<char> = paragraph.trim_end_matches('\r').chars().collect();
+        for range in break_lines(&paragraph, font, size, text.wrap_width) {
+            let line = &paragraph[range];
+            let visible = line.len() - line.iter().rev().take_while(|c| c.is_whitespace()).count();
+            let width = measure(&line[..visible], font, size);
+            let mut x = match text.align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (-width / 2.0).round(),
+                TextAlign::Right => -width,
+            };
+            let baseline = (metrics.ascent + layout.line_count as f32 * metrics.line_height).round();
+
+            for (i, &c) in line.iter().enumerate() {
+                if i > 0 {
+                    x += font.kerning(line[i - 1], c, size);
+                }
+                if !c.is_whitespace() {
+                    layout.chars.push(PlacedChar {
+                        c,
+                        pen: Vec2::new(x, baseline),
+                    });
+                }
+                x += font.advance(c, size);
+            }
+
+            layout.size.x = layout.size.x.max(width);
+            layout.line_count += 1;
+        }
+    }
+    layout.size.y = layout.line_count as f32 * metrics.line_height;
+    layout
+}
+
+/// Width of a run of characters, including kerning
+fn measure(chars: &[char], font: &FontData, size: f32) -> f32 {
+    let kerning: f32 = chars.windows(2).map(|pair| font.kerning(pair[0], pair[1], size)).sum();
+    chars.iter().map(|&c| font.advance(c, size)).sum::<f32>() + kerning
+}
+
+/// Split a paragraph into line ranges no wider than `wrap_width`
+///
+/// The space a line breaks at is dropped.
+fn break_lines(chars: &[char], font: &FontData, size: f32, wrap_width: Option<f32>) -> Vec<Range<usize>> {
+    let Some(wrap_width) = wrap_width.filter(|width| *width > 0.0) else {
+        return std::iter::once(0..chars.len()).collect();
+    };
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut x = 0.0;
+    let mut last_space = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let kerning = if i > start { font.kerning(chars[i - 1], c, size) } else { 0.0 };
+        let advance = kerning + font.advance(c, size);
+
+        if c.is_whitespace() {
+            last_space = Some(i);
+        } else if i > start && x + advance > wrap_width {
+            match last_space {
+                Some(space) if space > start => {
+                    lines.push(start..space);
+                    start = space + 1;
+                }
+                _ => {
+                    lines.push(start..i);
+                    start = i;
+                }
+            }
+            last_space = None;
+            x = measure(&chars[start..i], font, size);
+            // Place this character again at the start of the new line
+            continue;
+        }
+        x += advance;
+        i += 1;
+    }
+    lines.push(start..chars.len());
+    lines
+}
+
+/// One glyph quad of a laid-out text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphQuad {
+    /// Quad center relative to the entity, before its transform (y-up)
+    pub center: Vec2,
+    pub size: Vec2,
+    /// Texture region as (u0, v0, u1, v1)
+    pub uv_rect: [f32; 4],
+    /// Glyph atlas page or bitmap font page
+    pub texture: AssetId,
+}
+
+/// Glyph quads the `TextSystem` laid out for an entity's `Text`
+///
+/// Added and kept up to date by `TextSystem::update`; not serialized.
+#[derive(Debug, Clone)]
+pub struct TextGlyphs {
+    /// The text the glyphs were laid out for
+    source: Text,
+    pub glyphs: Vec<GlyphQuad>,
+    pub color: Color,
+    /// Width of the widest line and height of all lines
+    pub size: Vec2,
+}
+
+impl TextGlyphs {
+    /// The text the glyphs were laid out for
+    pub fn source(&self) -> &Text {
+        &self.source
+    }
+}
+
+/// A glyph rasterized into an atlas page
+#[derive(Debug, Clone, Copy)]
+struct AtlasGlyph {
+    page: usize,
+    region: AtlasRegion,
+    /// Top-left corner relative to the pen on the baseline (y down)
+    offset: Vec2,
+}
+
+/// A glyph atlas texture generated at runtime
+struct GlyphPage {
+    id: AssetId,
+    packer: AtlasPacker,
+    texture: TextureData,
+    dirty: bool,
+}
+
+impl GlyphPage {
+    fn new(id: AssetId, size: u32) -> Self {
+        // White everywhere so filtering at glyph edges doesn't darken them
+        let pixels = [255, 255, 255, 0].repeat((size * size) as usize);
+        Self {
+            id,
+            packer: AtlasPacker::new(size),
+            texture: TextureData {
+                width: size,
+                height: size,
+                pixels,
+            },
+            dirty: true,
+        }
+    }
+
+    fn write(&mut self, region: AtlasRegion, coverage: &[u8]) {
+        let width = self.texture.width as usize;
+        for (row, line) in coverage.chunks_exact(region.width as usize).enumerate() {
+            let y = region.y as usize + row;
+            for (column, &alpha) in line.iter().enumerate() {
+                let x = region.x as usize + column;
+                self.texture.pixels[(y * width + x) * 4 + 3] = alpha;
+            }
+        }
+        self.dirty = true;
+    }
+}
+
+/// Glyphs of one TrueType font rasterized at one size
+#[derive(Default)]
+struct GlyphAtlas {
+    pages: Vec<GlyphPage>,
+    /// `None` for characters without an outline
+    glyphs: HashMap<char, Option<AtlasGlyph>>,
+}
+
+impl GlyphAtlas {
+    /// Get a glyph, rasterizing it into a page first if needed
+    fn glyph<S: AssetSource>(
+        &mut self,
+        c: char,
+        font: &FontData,
+        size: f32,
+        assets: &AssetManager<S>,
+    ) -> Option<AtlasGlyph> {
+        if let Some(glyph) = self.glyphs.get(&c) {
+            return *glyph;
+        }
+
+        let glyph = font.rasterize(c, size).map(|raster| {
+            let (page, region) = self.allocate(raster.width, raster.height, assets);
+            self.pages[page].write(region, &raster.coverage);
+            AtlasGlyph {
+                page,
+                region,
+                offset: Vec2::from(raster.offset),
+            }
+        });
+        self.glyphs.insert(c, glyph);
+        glyph
+    }
+
+    fn allocate<S: AssetSource>(&mut self, width: u32, height: u32, assets: &AssetManager<S>) -> (usize, AtlasRegion) {
+        if let Some(page) = self.pages.last_mut() {
+            if let Some(region) = page.packer.allocate(width, height) {
+                return (self.pages.len() - 1, region);
+            }
+        }
+
+        // Glyphs too big for a regular page get a page of their own size
+        let size = GLYPH_PAGE_SIZE.max((width.max(height) + 2).next_power_of_two());
+        let mut page = GlyphPage::new(assets.reserve_asset_id(), size);
+        let region = page
+            .packer
+            .allocate(width, height)
+            .expect("glyph fits an empty page sized for it");
+        self.pages.push(page);
+        (self.pages.len() - 1, region)
+    }
+}
+
+/// Lays out `Text` components into glyph quads drawn by the sprite pipeline
+///
+/// TrueType glyphs are rasterized on demand into atlas pages per font and
+/// size; the pages are added to the asset manager as textures, so every
+/// render backend draws them like any other sprite texture. Bitmap font
+/// glyphs are drawn straight from the font's page textures.
+#[derive(Default)]
+pub struct TextSystem {
+    atlases: HashMap<(AssetId, u32), GlyphAtlas>,
+}
+
+impl TextSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lay out texts that changed since the last update
+    ///
+    /// Adds or refreshes a `TextGlyphs` component on every entity with a
+    /// `Text`, loading fonts that aren't loaded yet, and uploads new glyphs
+    /// to the asset manager.
+    pub fn update<S: AssetSource>(&mut self, world: &mut World, assets: &mut AssetManager<S>) {
+        let stale: Vec<_> = world
+            .inner()
+            .query::<(&Text, Option<&TextGlyphs>)>()
+            .iter()
+            .filter(|(_, (text, glyphs))| glyphs.is_none_or(|glyphs| glyphs.source != **text))
+            .map(|(entity, (text, _))| (entity, text.clone()))
+            .collect();
+        let orphaned: Vec<_> = world
+            .inner()
+            .query::<&TextGlyphs>()
+            .without::<&Text>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in orphaned {
+            let _ = world.remove_untracked::<TextGlyphs>(EntityHandle::new(entity));
+        }
+        for (entity, text) in stale {
+            let glyphs = self.layout(text, assets);
+            let _ = world.insert_untracked(EntityHandle::new(entity), glyphs);
+        }
+
+        self.flush(assets);
+    }
+
+    /// Lay out a text that isn't a component, such as a UI label
+    ///
+    /// Glyphs share the atlases of `update`; new ones are uploaded to the
+    /// asset manager right away.
+    pub fn layout_glyphs<S: AssetSource>(&mut self, text: &Text, assets: &mut AssetManager<S>) -> TextGlyphs {
+        let glyphs = self.layout(text.clone(), assets);
+        self.flush(assets);
+        glyphs
+    }
+
+    /// Number of glyph atlas pages generated so far
+    pub fn page_count(&self) -> usize {
+        self.atlases.values().map(|atlas| atlas.pages.len()).sum()
+    }
+
+    fn layout<S: AssetSource>(&mut self, text: Text, assets: &mut AssetManager<S>) -> TextGlyphs {
+        let color = Color::new(text.color[0], text.color[1], text.color[2], text.color[3]);
+        let mut glyphs = Vec::new();
+
+        let font = match assets.get_font(AssetHandle::new(text.font)) {
+            Some(font) => Some(font),
+            None => match assets.load_font_by_id(text.font) {
+                Ok(handle) => assets.get_font(handle),
+                Err(e) => {
+                    log::warn!("Failed to load font {:?}: {}", text.font, e);
+                    None
+                }
+            },
+        };
+        let Some(font) = font.filter(|_| text.size > 0.0) else {
+            return TextGlyphs {
+                source: text,
+                glyphs,
+                color,
+                size: Vec2::ZERO,
+            };
+        };
+
+        let layout = layout_text(&text, &font);
+        match &*font {
+            FontData::TrueType(_) => {
+                let atlas = self.atlases.entry((text.font, text.size.to_bits())).or_default();
+                for placed in &layout.chars {
+                    let Some(glyph) = atlas.glyph(placed.c, &font, text.size, assets) else {
+                        continue;
+                    };
+                    let page = &atlas.pages[glyph.page];
+                    let top_left = placed.pen.round() + glyph.offset;
+                    let size = Vec2::new(glyph.region.width as f32, glyph.region.height as f32);
+                    glyphs.push(GlyphQuad {
+                        center: Vec2::new(top_left.x + size.x / 2.0, -(top_left.y + size.y / 2.0)),
+                        size,
+                        uv_rect: glyph.region.uv_rect(page.packer.size()),
+                        texture: page.id,
+                    });
+                }
+            }
+            FontData::Bitmap(bitmap) => {
+                let scale = bitmap.scale(text.size);
+                let ascent = font.metrics(text.size).ascent;
+                let page_size = Vec2::new(bitmap.page_width.max(1) as f32, bitmap.page_height.max(1) as f32);
+                for placed in &layout.chars {
+                    let Some(glyph) = bitmap.glyphs.get(&placed.c) else {
+                        continue;
+                    };
+                    let Some(&texture) = bitmap.page_textures.get(glyph.page) else {
+                        continue;
+                    };
+                    let region = Vec2::new(glyph.width as f32, glyph.height as f32);
+                    let top_left = Vec2::new(
+                        placed.pen.x + glyph.x_offset * scale,
+                        placed.pen.y - ascent + glyph.y_offset * scale,
+                    );
+                    let size = region * scale;
+                    let uv_min = Vec2::new(glyph.x as f32, glyph.y as f32) / page_size;
+                    let uv_max = uv_min + region / page_size;
+                    glyphs.push(GlyphQuad {
+                        center: Vec2::new(top_left.x + size.x / 2.0, -(top_left.y + size.y / 2.0)),
+                        size,
+                        uv_rect: [uv_min.x, uv_min.y, uv_max.x, uv_max.y],
+                        texture,
+                    });
+                }
+            }
+        }
+
+        TextGlyphs {
+            source: text,
+            glyphs,
+            color,
+            size: layout.size,
+        }
+    }
+
+    /// Hand glyph pages with new glyphs to the asset manager
+    fn flush<S: AssetSource>(&mut self, assets: &mut AssetManager<S>) {
+        let settings = TextureImportSettings {
+            filter: TextureFilter::Linear,
+            ..Default::default()
+        };
+        for (&(font, size), atlas) in &mut self.atlases {
+            for (index, page) in atlas.pages.iter_mut().enumerate().filter(|(_, page)| page.dirty) {
+                let key = format!("<glyphs {} {}px {}>", font.0, f32::from_bits(size), index);
+                assets.insert_texture(&key, page.id, page.texture.clone(), settings);
+                page.dirty = false;
+            }
+        }
+    }
+}
+
+/// Build the render instance for one glyph of a text entity
+pub fn glyph_instance(glyph: &GlyphQuad, color: Color, transform: &GlobalTransform) -> SpriteInstance {
+    let (sin, cos) = transform.rotation.sin_cos();
+    let offset = glyph.center * transform.scale;
+    let offset = Vec2::new(offset.x * cos - offset.y * sin, offset.x * sin + offset.y * cos);
+    SpriteInstance {
+        position: transform.position + offset,
+        size: glyph.size * transform.scale,
+        rotation: transform.rotation,
+        uv_rect: glyph.uv_rect,
+        color,
+        texture: glyph.texture,
+        z_index: 0,
+        material: AssetId::new(0),
+        material_params: None,
+        normal_map: AssetId::new(0),
+        draw_mode: SpriteDrawMode::Simple,
+        texel_size: Vec2::ONE,
+        mesh: None,
+    }
+}
+
+/// Add the glyphs of every laid-out text to a batch, culling against `visible`
+///
+/// # Returns
+/// The number of glyphs culled
+pub(crate) fn collect_text(world: &World, visible: Option<Rect>, batch: &mut SpriteBatch) -> usize {
+    let mut culled = 0;
+    for (_, (text, global, local)) in world
+        .query::<(&TextGlyphs, Option<&GlobalTransform>, Option<&Transform>)>()
+        .iter()
+    {
+        let Some(transform) = world_transform(global, local) else {
+            continue;
+        };
+        for glyph in &text.glyphs {
+            let instance = glyph_instance(glyph, text.color, &transform);
+            if visible.is_some_and(|visible| !visible.intersects(&instance.bounds())) {
+                culled += 1;
+                continue;
+            }
+            batch.add(instance);
+        }
+    }
+    culled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_assets::{BitmapFont, BitmapGlyph, FilesystemSource};
+
+    /// Monospace bitmap font: 10 units per character at size 10, 'A' 'V' kerned by -2
+    fn bitmap_font() -> FontData {
+        let mut font = BitmapFont {
+            size: 10.0,
+            line_height: 12.0,
+            base: 8.0,
+            page_width: 100,
+            page_height: 100,
+            page_textures: vec![AssetId::new(1)],
+            ..Default::default()
+        };
+        for c in "ABCDEFGHIJKLMNOPQRSTUVWXYZ ".chars() {
+            let glyph = BitmapGlyph {
+                x: 0,
+                y: 0,
+                width: 8,
+                height: 8,
+                x_offset: 1.0,
+                y_offset: 0.0,
+                x_advance: 10.0,
+                page: 0,
+            };
+            font.glyphs.insert(c, glyph);
+        }
+        font.kerning.insert(('A', 'V'), -2.0);
+        FontData::Bitmap(font)
+    }
+
+    fn lines(layout: &TextLayout) -> Vec<(f32, String)> {
+        let mut lines: Vec<(f32, String)> = Vec::new();
+        for placed in &layout.chars {
+            match lines.last_mut() {
+                Some((y, line)) if *y == placed.pen.y => line.push(placed.c),
+                _ => lines.push((placed.pen.y, placed.c.to_string())),
+            }
+        }
+        lines
+    }
+
+    #[test]
+    fn test_layout_wraps_words_and_breaks_long_words() {
+        let font = bitmap_font();
+        let text = Text::new("THE QUICK FOX\nABCDEFGHIJ", AssetId::new(1), 10.0).with_wrap_width(85.0);
+        let layout = layout_text(&text, &font);
+
+        let (baselines, lines): (Vec<f32>, Vec<String>) = lines(&layout).into_iter().unzip();
+        assert_eq!(lines, vec!["THE", "QUICK", "FOX", "ABCDEFGH", "IJ"]);
+        assert_eq!(layout.line_count, 5);
+        assert_eq!(layout.size, Vec2::new(80.0, 60.0));
+
+        // Baselines are a line height apart, starting at the ascent
+        assert_eq!(baselines, vec![8.0, 20.0, 32.0, 44.0, 56.0]);
+    }
+
+    #[test]
+    fn test_layout_alignment_and_kerning() {
+        let font = bitmap_font();
+        let mut text = Text::new("AV A", AssetId::new(1), 20.0);
+
+        // Kerning pulls V towards A (scaled with the size)
+        let layout = layout_text(&text, &font);
+        let x: Vec<f32> = layout.chars.iter().map(|placed| placed.pen.x).collect();
+        assert_eq!(x, vec![0.0, 16.0, 56.0]);
+        assert_eq!(layout.size.x, 76.0);
+
+        text.align = TextAlign::Center;
+        assert_eq!(layout_text(&text, &font).chars[0].pen.x, -38.0);
+        // Trailing spaces don't count towards the aligned width
+        text.content = "AV A  ".to_string();
+        text.align = TextAlign::Right;
+        assert_eq!(layout_text(&text, &font).chars[2].pen.x, -20.0);
+    }
+
+    #[test]
+    fn test_text_system_rasterizes_and_tracks_changes() {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "longhorn_text_test_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        std::fs::write(temp_dir.join("ui.ttf"), epaint_default_fonts::UBUNTU_LIGHT).unwrap();
+        let mut assets = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        let font = assets.load_font("ui.ttf").unwrap().id();
+
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(Text::new("Hi there", font, 24.0))
+            .with(Transform::from_position(Vec2::new(100.0, 50.0)))
+            .build();
+
+        let mut system = TextSystem::new();
+        system.update(&mut world, &mut assets);
+        assert_eq!(system.page_count(), 1);
+
+        let glyphs = world.get::<TextGlyphs>(entity).unwrap();
+        assert_eq!(glyphs.glyphs.len(), 7);
+        let page = glyphs.glyphs[0].texture;
+        assert!(assets.is_texture_loaded(page));
+        assert_eq!(assets.texture_settings(page).filter, TextureFilter::Linear);
+        // Glyphs hang below the anchor, left-aligned to it
+        assert!(glyphs.glyphs.iter().all(|g| g.center.y < 0.0 && g.center.x > 0.0));
+        drop(glyphs);
+
+        let batch = SpriteBatch::collect(&world);
+        assert_eq!(batch.len(), 7);
+        assert!(batch.iter().all(|s| s.position.y < 50.0 && s.position.x > 100.0));
+
+        // Unchanged text is not laid out again; new glyphs update the page
+        let version = assets.texture_version(page);
+        system.update(&mut world, &mut assets);
+        assert_eq!(assets.texture_version(page), version);
+        world.get_mut::<Text>(entity).unwrap().set_content("Score: 42");
+        system.update(&mut world, &mut assets);
+        assert_eq!(world.get::<TextGlyphs>(entity).unwrap().glyphs.len(), 8);
+        assert_eq!(assets.texture_version(page), version + 1);
+
+        // Removing the text removes its glyphs
+        world.inner_mut().remove_one::<Text>(entity.id()).unwrap();
+        system.update(&mut world, &mut assets);
+        assert!(!world.has::<TextGlyphs>(entity));
+
+        std::fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use longhorn_assets::TextureData;
+use longhorn_assets::{TextureData, TextureFilter, TextureImportSettings, TextureWrap};
 use longhorn_core::AssetId;
 use std::collections::HashMap;
 use wgpu;
@@ -11,17 +11,30 @@ pub struct GpuTexture {
     pub bind_group: wgpu::BindGroup,
     pub width: u32,
     pub height: u32,
+    /// Color is premultiplied by alpha and must be blended as such
+    pub premultiplied: bool,
 }
 
 impl GpuTexture {
     /// Create a GPU texture from texture data
+    ///
+    /// `settings` choose the sampler, the texture format (sRGB or linear) and
+    /// whether a mipmap chain is generated. Pixel changes such as max size
+    /// and premultiplication are expected to already be applied.
     pub fn from_texture_data(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         bind_group_layout: &wgpu::BindGroupLayout,
         texture_data: &TextureData,
+        settings: &TextureImportSettings,
         label: Option<&str>,
     ) -> Self {
+        let levels = if settings.mipmaps {
+            generate_mip_chain(texture_data, settings.srgb)
+        } else {
+            Vec::new()
+        };
+
         let size = wgpu::Extent3d {
             width: texture_data.width,
             height: texture_data.height,
@@ -31,68 +44,203 @@ impl GpuTexture {
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label,
             size,
-            mip_level_count: 1,
+            mip_level_count: 1 + levels.len() as u32,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            format: if settings.srgb {
+                wgpu::TextureFormat::Rgba8UnormSrgb
+            } else {
+                wgpu::TextureFormat::Rgba8Unorm
+            },
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
 
-        queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &texture_data.pixels,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * texture_data.width),
-                rows_per_image: Some(texture_data.height),
-            },
-            size,
-        );
+        for (mip_level, level) in std::iter::once(texture_data).chain(&levels).enumerate() {
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: mip_level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &level.pixels,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(level.bytes_per_row()),
+                    rows_per_image: Some(level.height),
+                },
+                wgpu::Extent3d {
+                    width: level.width,
+                    height: level.height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let (sampler, bind_group) = create_sampler_bind_group(device, bind_group_layout, &view, settings, label);
 
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Nearest,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
+        Self {
+            texture,
+            view,
+            sampler,
+            bind_group,
+            width: texture_data.width,
+            height: texture_data.height,
+            premultiplied: settings.premultiply_alpha,
+        }
+    }
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+    /// Create a texture that can be rendered into and then sampled, e.g. a
+    /// camera's render-texture target
+    ///
+    /// Only the sampler part of `settings` applies. Rendered sprites are
+    /// blended over transparency, so color ends up premultiplied.
+    pub fn render_target(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        settings: &TextureImportSettings,
+        label: Option<&str>,
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
             label,
-            layout: bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-            ],
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
         });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let (sampler, bind_group) = create_sampler_bind_group(device, bind_group_layout, &view, settings, label);
 
         Self {
             texture,
             view,
             sampler,
             bind_group,
-            width: texture_data.width,
-            height: texture_data.height,
+            width,
+            height,
+            premultiplied: true,
         }
     }
 }
 
+/// Create the sampler `settings` ask for and a bind group of it and a view
+fn create_sampler_bind_group(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+    settings: &TextureImportSettings,
+    label: Option<&str>,
+) -> (wgpu::Sampler, wgpu::BindGroup) {
+    let address_mode = address_mode(settings.wrap);
+    let filter = filter_mode(settings.filter);
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        address_mode_u: address_mode,
+        address_mode_v: address_mode,
+        address_mode_w: address_mode,
+        mag_filter: filter,
+        min_filter: filter,
+        mipmap_filter: filter,
+        ..Default::default()
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label,
+        layout: bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&sampler),
+            },
+        ],
+    });
+    (sampler, bind_group)
+}
+
+fn address_mode(wrap: TextureWrap) -> wgpu::AddressMode {
+    match wrap {
+        TextureWrap::Clamp => wgpu::AddressMode::ClampToEdge,
+        TextureWrap::Repeat => wgpu::AddressMode::Repeat,
+        TextureWrap::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+    }
+}
+
+fn filter_mode(filter: TextureFilter) -> wgpu::FilterMode {
+    match filter {
+        TextureFilter::Nearest => wgpu::FilterMode::Nearest,
+        TextureFilter::Linear => wgpu::FilterMode::Linear,
+    }
+}
+
+/// Build the mip levels below a texture, halving down to 1x1
+///
+/// Each level averages 2x2 blocks of the previous one; sRGB color is
+/// averaged in linear space so mips don't darken.
+pub fn generate_mip_chain(texture_data: &TextureData, srgb: bool) -> Vec<TextureData> {
+    let mut levels: Vec<TextureData> = Vec::new();
+    loop {
+        let previous = levels.last().unwrap_or(texture_data);
+        if previous.width <= 1 && previous.height <= 1 {
+            break;
+        }
+        let width = (previous.width / 2).max(1);
+        let height = (previous.height / 2).max(1);
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0.0f32; 4];
+                for (sx, sy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let px = (x * 2 + sx).min(previous.width - 1);
+                    let py = (y * 2 + sy).min(previous.height - 1);
+                    let i = ((py * previous.width + px) * 4) as usize;
+                    for (c, total) in sum.iter_mut().enumerate() {
+                        let value = previous.pixels[i + c] as f32 / 255.0;
+                        *total += if srgb && c < 3 { srgb_to_linear(value) } else { value };
+                    }
+                }
+                for (c, total) in sum.iter().enumerate() {
+                    let value = total / 4.0;
+                    let value = if srgb && c < 3 { linear_to_srgb(value) } else { value };
+                    pixels.push((value * 255.0).round() as u8);
+                }
+            }
+        }
+        levels.push(TextureData { width, height, pixels });
+    }
+    levels
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub(crate) fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 /// Cache for GPU textures
 pub struct TextureCache {
     textures: HashMap<AssetId, GpuTexture>,
@@ -116,6 +264,11 @@ impl TextureCache {
         self.textures.get(&asset_id)
     }
 
+    /// Remove a texture from the cache
+    pub fn remove(&mut self, asset_id: AssetId) -> Option<GpuTexture> {
+        self.textures.remove(&asset_id)
+    }
+
     /// Check if a texture is cached
     pub fn contains(&self, asset_id: AssetId) -> bool {
         self.textures.contains_key(&asset_id)
@@ -137,3 +290,25 @@ impl Default for TextureCache {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mip_chain_halves_to_one_pixel() {
+        let texture = TextureData {
+            width: 4,
+            height: 2,
+            pixels: [255, 255, 255, 255, 0, 0, 0, 255].repeat(4),
+        };
+        let levels = generate_mip_chain(&texture, false);
+        let sizes: Vec<_> = levels.iter().map(|l| (l.width, l.height)).collect();
+        assert_eq!(sizes, vec![(2, 1), (1, 1)]);
+        assert_eq!(levels[0].pixels[..4], [128, 128, 128, 255]);
+
+        // sRGB averages in linear space, so 50% gray is brighter than 128
+        let levels = generate_mip_chain(&texture, true);
+        assert_eq!(levels[0].pixels[..4], [188, 188, 188, 255]);
+    }
+}
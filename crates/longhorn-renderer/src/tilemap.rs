@@ -0,0 +1,354 @@
+use crate::sprite_batch::world_transform;
+use crate::{Color, SpriteBatch, SpriteInstance};
+use glam::Vec2;
+use longhorn_assets::{AssetHandle, AssetManager, AssetSource};
+use longhorn_core::{AssetId, EntityHandle, GlobalTransform, Rect, SpriteDrawMode, TileChunk, TileSet, Tilemap, Transform, World};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// One tile quad of a tilemap chunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileQuad {
+    /// Quad center relative to the entity, before its transform (y-up)
+    pub center: Vec2,
+    /// Texture region as (u0, v0, u1, v1)
+    pub uv_rect: [f32; 4],
+}
+
+/// Cached quads of one chunk
+#[derive(Debug, Clone)]
+struct ChunkGeometry {
+    /// Revision of the chunk the quads were built from
+    revision: u64,
+    /// Area of the chunk relative to the entity
+    bounds: Rect,
+    quads: Vec<TileQuad>,
+}
+
+/// Tile quads the `TilemapSystem` built for an entity's `Tilemap`
+///
+/// Quads are cached per chunk and rebuilt only when the chunk's revision
+/// changes. Added and kept up to date by `TilemapSystem::update`; not
+/// serialized.
+#[derive(Debug, Clone, Default)]
+pub struct TilemapGeometry {
+    /// Tile set the quads were built from; `None` until it's loaded
+    tileset: Option<(AssetId, Arc<TileSet>)>,
+    cell_size: Vec2,
+    /// Chunk geometry by layer index and chunk coordinate
+    chunks: HashMap<(usize, (i32, i32)), ChunkGeometry>,
+}
+
+impl TilemapGeometry {
+    /// Texture the tiles are drawn with, once the tile set is loaded
+    pub fn texture(&self) -> Option<AssetId> {
+        self.tileset.as_ref().map(|(_, tileset)| tileset.texture)
+    }
+
+    /// Number of chunks with cached geometry
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Bring the cached chunks in line with the tilemap
+    ///
+    /// # Returns
+    /// The number of chunks rebuilt
+    fn update(&mut self, tilemap: &Tilemap, tileset: Option<&Arc<TileSet>>) -> usize {
+        let same_tileset = match (&self.tileset, tileset) {
+            (Some((id, cached)), Some(tileset)) => *id == tilemap.tileset && Arc::ptr_eq(cached, tileset),
+            _ => false,
+        };
+        if !same_tileset || self.cell_size != tilemap.cell_size {
+            self.chunks.clear();
+            self.tileset = tileset.map(|tileset| (tilemap.tileset, Arc::clone(tileset)));
+            self.cell_size = tilemap.cell_size;
+        }
+        let Some((_, tileset)) = &self.tileset else {
+            return 0;
+        };
+
+        let mut rebuilt = 0;
+        let mut live = HashSet::new();
+        for (layer_index, layer) in tilemap.layers.iter().enumerate() {
+            for (coord, chunk) in layer.chunks() {
+                let key = (layer_index, coord);
+                live.insert(key);
+                if self.chunks.get(&key).is_some_and(|cached| cached.revision == chunk.revision()) {
+                    continue;
+                }
+                self.chunks.insert(key, build_chunk(tilemap, tileset, coord, chunk));
+                rebuilt += 1;
+            }
+        }
+        self.chunks.retain(|key, _| live.contains(key));
+        rebuilt
+    }
+}
+
+/// Build the quads of one chunk
+fn build_chunk(tilemap: &Tilemap, tileset: &TileSet, (cx, cy): (i32, i32), chunk: &TileChunk) -> ChunkGeometry {
+    let origin = (cx * longhorn_core::TILE_CHUNK_SIZE, cy * longhorn_core::TILE_CHUNK_SIZE);
+    let quads = chunk
+        .tiles()
+        .filter_map(|(x, y, tile)| {
+            let uv_rect = tileset.uv_rect(tile)?;
+            let center = tilemap.cell_rect(origin.0 + x, origin.1 + y).center();
+            Some(TileQuad { center, uv_rect })
+        })
+        .collect();
+    ChunkGeometry {
+        revision: chunk.revision(),
+        bounds: tilemap.chunk_rect(cx, cy),
+        quads,
+    }
+}
+
+/// Builds and caches the geometry of `Tilemap` components
+///
+/// Tile sets are loaded on demand; tilemaps whose tile set can't be loaded
+/// don't draw.
+#[derive(Default)]
+pub struct TilemapSystem {
+    /// Tile sets that failed to load, so they aren't retried every frame
+    missing: HashSet<AssetId>,
+    /// Chunks rebuilt by the last update
+    rebuilt: usize,
+}
+
+impl TilemapSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuild the geometry of chunks that changed since the last update
+    ///
+    /// Adds a `TilemapGeometry` component to every entity with a `Tilemap`
+    /// and removes it from entities that lost theirs.
+    pub fn update<S: AssetSource>(&mut self, world: &mut World, assets: &mut AssetManager<S>) {
+        let added: Vec<_> = world
+            .inner()
+            .query::<&Tilemap>()
+            .without::<&TilemapGeometry>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect();
+        let orphaned: Vec<_> = world
+            .inner()
+            .query::<&TilemapGeometry>()
+            .without::<&Tilemap>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in added {
+            let _ = world.insert_untracked(EntityHandle::new(entity), TilemapGeometry::default());
+        }
+        for entity in orphaned {
+            let _ = world.remove_untracked::<TilemapGeometry>(EntityHandle::new(entity));
+        }
+
+        let ids: HashSet<AssetId> = world
+            .inner()
+            .query::<&Tilemap>()
+            .iter()
+            .map(|(_, tilemap)| tilemap.tileset)
+            .collect();
+        let tilesets: HashMap<AssetId, Arc<TileSet>> = ids
+            .into_iter()
+            .filter_map(|id| Some((id, self.tileset(id, assets)?)))
+            .collect();
+
+        self.rebuilt = 0;
+        for (_, (tilemap, geometry)) in world.query_mut::<(&Tilemap, &mut TilemapGeometry)>().iter() {
+            self.rebuilt += geometry.update(tilemap, tilesets.get(&tilemap.tileset));
+        }
+    }
+
+    /// Number of chunks rebuilt by the last update
+    pub fn rebuilt(&self) -> usize {
+        self.rebuilt
+    }
+
+    fn tileset<S: AssetSource>(&mut self, id: AssetId, assets: &mut AssetManager<S>) -> Option<Arc<TileSet>> {
+        if let Some(tileset) = assets.get_tileset(AssetHandle::new(id)) {
+            return Some(tileset);
+        }
+        if self.missing.contains(&id) {
+            return None;
+        }
+        match assets.load_tileset_by_id(id) {
+            Ok(handle) => assets.get_tileset(handle),
+            Err(e) => {
+                log::warn!("Failed to load tile set {:?}: {}", id, e);
+                self.missing.insert(id);
+                None
+            }
+        }
+    }
+}
+
+/// Build the render instance for one tile of a tilemap entity
+pub fn tile_instance(
+    quad: &TileQuad,
+    cell_size: Vec2,
+    texture: AssetId,
+    z_index: i32,
+    transform: &GlobalTransform,
+) -> SpriteInstance {
+    SpriteInstance {
+        position: transform.transform_point(quad.center),
+        size: cell_size * transform.scale,
+        rotation: transform.rotation,
+        uv_rect: quad.uv_rect,
+        color: Color::WHITE,
+        texture,
+        z_index,
+        material: AssetId::new(0),
+        material_params: None,
+        normal_map: AssetId::new(0),
+        draw_mode: SpriteDrawMode::Simple,
+        texel_size: Vec2::ONE,
+        mesh: None,
+    }
+}
+
+/// World-space bounding box of an area relative to an entity
+fn world_bounds(rect: &Rect, transform: &GlobalTransform) -> Rect {
+    let corners = [
+        rect.min,
+        Vec2::new(rect.max.x, rect.min.y),
+        rect.max,
+        Vec2::new(rect.min.x, rect.max.y),
+    ]
+    .map(|corner| transform.transform_point(corner));
+    let min = corners.iter().fold(Vec2::MAX, |min, corner| min.min(*corner));
+    let max = corners.iter().fold(Vec2::MIN, |max, corner| max.max(*corner));
+    Rect::new(min, max)
+}
+
+/// Add the tiles of every visible tilemap layer to a batch
+///
+/// Whole chunks are culled against `visible`.
+///
+/// # Returns
+/// The number of tiles culled
+pub(crate) fn collect_tilemaps(world: &World, visible: Option<Rect>, batch: &mut SpriteBatch) -> usize {
+    let mut culled = 0;
+    for (_, (tilemap, geometry, global, local)) in world
+        .query::<(&Tilemap, &TilemapGeometry, Option<&GlobalTransform>, Option<&Transform>)>()
+        .iter()
+    {
+        let (Some(transform), Some(texture)) = (world_transform(global, local), geometry.texture()) else {
+            continue;
+        };
+        for (layer_index, layer) in tilemap.layers.iter().enumerate() {
+            if !layer.visible {
+                continue;
+            }
+            for (coord, _) in layer.chunks() {
+                let Some(chunk) = geometry.chunks.get(&(layer_index, coord)) else {
+                    continue;
+                };
+                if visible.is_some_and(|visible| !visible.intersects(&world_bounds(&chunk.bounds, &transform))) {
+                    culled += chunk.quads.len();
+                    continue;
+                }
+                for quad in &chunk.quads {
+                    batch.add(tile_instance(quad, geometry.cell_size, texture, layer.z_index, &transform));
+                }
+            }
+        }
+    }
+    culled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Camera;
+    use longhorn_assets::FilesystemSource;
+
+    fn setup() -> (std::path::PathBuf, AssetManager<FilesystemSource>, AssetId) {
+        let temp_dir = std::env::temp_dir().join(format!(
+            "longhorn_tilemap_test_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        image::RgbaImage::from_pixel(4, 2, image::Rgba([255, 255, 255, 255]))
+            .save(temp_dir.join("tiles.png"))
+            .unwrap();
+        std::fs::write(
+            temp_dir.join("tiles.tileset"),
+            r#"{"image": "tiles.png", "tile_width": 2, "tile_height": 2}"#,
+        )
+        .unwrap();
+        let mut assets = AssetManager::new(FilesystemSource::new(&temp_dir), &temp_dir);
+        let tileset = assets.load_tileset("tiles.tileset").unwrap().id();
+        (temp_dir, assets, tileset)
+    }
+
+    #[test]
+    fn test_chunks_are_cached_until_edited() {
+        let (temp_dir, mut assets, tileset) = setup();
+        let mut world = World::new();
+        let mut tilemap = Tilemap::new(tileset, Vec2::splat(10.0)).with_layer("Ground");
+        for x in 0..20 {
+            tilemap.set_tile(0, x, 0, Some(1));
+        }
+        let entity = world
+            .spawn()
+            .with(tilemap)
+            .with(Transform::from_position(Vec2::new(0.0, 50.0)))
+            .build();
+
+        let mut system = TilemapSystem::new();
+        system.update(&mut world, &mut assets);
+        assert_eq!(system.rebuilt(), 2);
+        system.update(&mut world, &mut assets);
+        assert_eq!(system.rebuilt(), 0);
+
+        // Editing a tile rebuilds only its chunk
+        world.get_mut::<Tilemap>(entity).unwrap().set_tile(0, 18, 0, Some(0));
+        system.update(&mut world, &mut assets);
+        assert_eq!(system.rebuilt(), 1);
+
+        let batch = SpriteBatch::collect(&world);
+        assert_eq!(batch.len(), 20);
+        let first = batch.iter().next().unwrap();
+        assert_eq!(first.position, Vec2::new(5.0, 45.0));
+        assert_eq!(first.uv_rect, [0.5, 0.0, 1.0, 1.0]);
+
+        // The second chunk starts 160 units right of the map, outside a view of -100..100
+        let camera = Camera::new(200.0, 200.0);
+        let visible = SpriteBatch::collect_visible(&world, &camera);
+        assert_eq!((visible.len(), visible.culled()), (16, 4));
+
+        // Hidden layers don't draw; removing the component drops its geometry
+        world.get_mut::<Tilemap>(entity).unwrap().layers[0].visible = false;
+        assert!(SpriteBatch::collect(&world).is_empty());
+        world.remove::<Tilemap>(entity).unwrap();
+        system.update(&mut world, &mut assets);
+        assert!(world.get::<TilemapGeometry>(entity).is_err());
+
+        std::fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_tileset_draws_nothing() {
+        let (temp_dir, mut assets, _) = setup();
+        let mut world = World::new();
+        let mut tilemap = Tilemap::new(AssetId::new(999), Vec2::splat(10.0)).with_layer("Ground");
+        tilemap.set_tile(0, 0, 0, Some(0));
+        let entity = world.spawn().with(tilemap).with(Transform::default()).build();
+
+        let mut system = TilemapSystem::new();
+        system.update(&mut world, &mut assets);
+        assert_eq!(world.get::<TilemapGeometry>(entity).unwrap().chunk_count(), 0);
+        assert!(SpriteBatch::collect(&world).is_empty());
+
+        std::fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+//! Golden-image tests for the software renderer
+//!
+//! Each test renders a small scene and compares it against a reference PNG
+//! in `tests/golden/`. Set `LONGHORN_UPDATE_GOLDEN=1` to (re)write the
+//! references after an intended rendering change.
+
+use glam::Vec2;
+use longhorn_assets::TextureData;
+use longhorn_core::{AssetId, Sprite, Transform, World};
+use longhorn_renderer::{Camera, Color, FrameBuffer, RenderBackend, SoftwareRenderer};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Per-channel tolerance, to absorb float rounding differences between platforms
+const TOLERANCE: u8 = 2;
+
+const WHITE: AssetId = AssetId(1);
+const CHECKER: AssetId = AssetId(2);
+
+fn textures() -> HashMap<AssetId, TextureData> {
+    let mut textures = HashMap::new();
+    textures.insert(
+        WHITE,
+        TextureData { width: 1, height: 1, pixels: vec![255, 255, 255, 255] },
+    );
+
+    // 2x2: red, green / blue, transparent
+    textures.insert(
+        CHECKER,
+        TextureData {
+            width: 2,
+            height: 2,
+            pixels: vec![
+                255, 0, 0, 255, 0, 255, 0, 255, //
+                0, 0, 255, 255, 0, 0, 0, 0,
+            ],
+        },
+    );
+    textures
+}
+
+fn render(world: &World, camera: &Camera) -> FrameBuffer {
+    let mut renderer = SoftwareRenderer::new(camera.viewport_size.x as u32, camera.viewport_size.y as u32);
+    renderer.set_clear_color(Color::new(0.1, 0.1, 0.2, 1.0));
+    renderer.render_world(world, &textures(), camera).unwrap();
+    renderer.frame().unwrap().clone()
+}
+
+fn assert_golden(name: &str, frame: &FrameBuffer) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.png", name));
+
+    if std::env::var_os("LONGHORN_UPDATE_GOLDEN").is_some() {
+        frame.save_png(&path).unwrap();
+        return;
+    }
+
+    let reference = FrameBuffer::load_png(&path)
+        .unwrap_or_else(|e| panic!("Missing golden image {}: {}", path.display(), e));
+    let diff = frame
+        .diff(&reference, TOLERANCE)
+        .unwrap_or_else(|| panic!("Golden image {} has a different size", name));
+
+    if diff.differing_pixels > 0 {
+        let actual = path.with_extension("actual.png");
+        frame.save_png(&actual).ok();
+        panic!(
+            "{} differs from golden image: {} pixels, max difference {} (actual written to {})",
+            name,
+            diff.differing_pixels,
+            diff.max_difference,
+            actual.display()
+        );
+    }
+}
+
+#[test]
+fn golden_tint_and_alpha_blending() {
+    let mut world = World::new();
+    world
+        .spawn()
+        .with(Sprite::with_color(WHITE, Vec2::new(24.0, 24.0), [1.0, 0.2, 0.2, 1.0]))
+        .with(Transform::from_position(Vec2::new(-6.0, 6.0)))
+        .build();
+    world
+        .spawn()
+        .with(Sprite::with_color(WHITE, Vec2::new(24.0, 24.0), [0.2, 0.4, 1.0, 0.5]))
+        .with(Transform::from_position(Vec2::new(6.0, -6.0)))
+        .build();
+
+    assert_golden("tint_and_alpha_blending", &render(&world, &Camera::new(48.0, 48.0)));
+}
+
+#[test]
+fn golden_uvs_rotation_and_flip() {
+    let mut world = World::new();
+    world
+        .spawn()
+        .with(Sprite::new(CHECKER, Vec2::new(16.0, 16.0)))
+        .with(Transform::from_position(Vec2::new(-12.0, 0.0)))
+        .build();
+
+    let mut flipped = Sprite::new(CHECKER, Vec2::new(8.0, 8.0));
+    flipped.flip_x = true;
+    world
+        .spawn()
+        .with(flipped)
+        .with(Transform::from_components(
+            Vec2::new(12.0, 0.0),
+            std::f32::consts::FRAC_PI_4,
+            Vec2::new(2.0, 2.0),
+        ))
+        .build();
+
+    assert_golden("uvs_rotation_and_flip", &render(&world, &Camera::new(48.0, 32.0)));
+}
+
+#[test]
+fn golden_camera_zoom_and_offset() {
+    let mut world = World::new();
+    for (i, color) in [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]].iter().enumerate() {
+        world
+            .spawn()
+            .with(Sprite::with_color(WHITE, Vec2::new(8.0, 8.0), *color))
+            .with(Transform::from_position(Vec2::new(i as f32 * 10.0, 0.0)))
+            .build();
+    }
+
+    let mut camera = Camera::new(40.0, 24.0);
+    camera.position = Vec2::new(10.0, 2.0);
+    camera.zoom = 1.5;
+
+    assert_golden("camera_zoom_and_offset", &render(&world, &camera));
+}
+
+#[test]
+fn missing_texture_is_skipped() {
+    let mut world = World::new();
+    world
+        .spawn()
+        .with(Sprite::new(AssetId(99), Vec2::new(48.0, 48.0)))
+        .with(Transform::new())
+        .build();
+
+    let empty = render(&World::new(), &Camera::new(8.0, 8.0));
+    assert!(render(&world, &Camera::new(8.0, 8.0)).matches(&empty, 0));
+}
@@ -18,6 +18,110 @@ declare module "longhorn" {
         flipX: boolean;
         flipY: boolean;
         zIndex: number;
+        /** Material asset ID; 0 draws with the built-in sprite shader */
+        material: number;
+        /** Normal map asset ID shading the sprite in lit scenes; 0 for a flat sprite */
+        normalMap: number;
+        /** How the texture fills the sprite's size; borders are in texture pixels */
+        drawMode: SpriteDrawMode;
+    }
+
+    export interface SliceBorder {
+        left: number;
+        right: number;
+        top: number;
+        bottom: number;
+    }
+
+    export type SpriteDrawMode =
+        | { mode: "simple" }
+        | { mode: "nine_slice"; border: SliceBorder }
+        | { mode: "tiled" }
+        | { mode: "tiled_nine_slice"; border: SliceBorder };
+
+    /**
+     * Values of the entity's material parameters, available as `self.material`
+     * when its sprite has a material. Assign numbers, [x, y] or [r, g, b, a];
+     * parameters without a value use the material's defaults.
+     */
+    export type MaterialParams = Record<string, number | [number, number] | [number, number, number, number]>;
+
+    export interface Text {
+        /** Numbers are converted to strings, so scores can be assigned directly */
+        content: string | number;
+        font: number;
+        size: number;
+        color: [number, number, number, number];
+        align: "left" | "center" | "right";
+        wrapWidth: number | null;
+    }
+
+    /** Tiles of the entity's Tilemap, available as `self.tilemap` */
+    export interface Tilemap {
+        readonly cellSize: Vec2;
+        /** Layer names in draw order; layers can be passed by index or name */
+        readonly layers: string[];
+        /** Tile set index at a cell of a layer (the first by default), or null if empty */
+        getTile(x: number, y: number, layer?: number | string): number | null;
+        /** Set a cell to a tile set index or clear it with null; false if the layer doesn't exist */
+        setTile(x: number, y: number, tile: number | null, layer?: number | string): boolean;
+        /** Cell containing a point relative to the entity; cell rows grow downwards */
+        cellAt(x: number, y: number): Vec2;
+    }
+
+    /** Controls of the entity's ParticleEmitter, available as `self.emitter` */
+    export interface ParticleEmitter {
+        /** Whether the emission cycle runs; set through play() and stop() */
+        readonly playing: boolean;
+        /** Live particles when the script was called */
+        readonly particleCount: number;
+        /** Emit particles on the next particle update, playing or not */
+        burst(count: number): void;
+        /** Start the emission cycle, from the beginning if it wasn't running */
+        play(): void;
+        /** Stop emitting; live particles finish their lifetime */
+        stop(): void;
+    }
+
+    /**
+     * Parameters and active states of the entity's StateMachine, available as
+     * `self.stateMachine`. Changes apply when the script call returns. The
+     * machine calls `onStateEnter(self, state)` and `onStateExit(self, state)`
+     * on the entity's scripts as it enters and leaves states.
+     */
+    export interface StateMachine {
+        /** Innermost active state, or null before the machine's first update */
+        readonly state: string | null;
+        /** Active states from the top level down to the innermost */
+        readonly active: string[];
+        /** Whether a state is active, directly or through an active child */
+        is(state: string): boolean;
+        get(parameter: string): number | boolean | undefined;
+        set(parameter: string, value: number | boolean): void;
+        /** Fire transitions waiting for this event on the machine's next update */
+        send(event: string): void;
+    }
+
+    /**
+     * Controls of the entity's NavAgent, available as `self.navAgent`. The
+     * agent starts walking after the script call returns, and sends a
+     * `DestinationReached` or `PathNotFound` event to its entity.
+     */
+    export interface NavAgent {
+        /** World units per second */
+        speed: number;
+        /** How close to the destination the agent stops */
+        stoppingDistance: number;
+        readonly destination: Vec2 | null;
+        readonly status: "idle" | "pending" | "moving" | "arrived" | "unreachable";
+        /** Waypoints still ahead, ending at the destination */
+        readonly path: Vec2[];
+        readonly arrived: boolean;
+        /** Walk to a point around walls and obstacles */
+        moveTo(x: number, y: number): void;
+        moveTo(point: Vec2): void;
+        /** Stop where the agent is */
+        stop(): void;
     }
 
     export interface Entity {
@@ -33,6 +137,7 @@ declare module "longhorn" {
 
     export const Transform: ComponentType<Transform>;
     export const Sprite: ComponentType<Sprite>;
+    export const Text: ComponentType<Text>;
 
     export interface EntityBuilder {
         with<T>(component: ComponentType<T>, value: Partial<T>): EntityBuilder;
@@ -53,4 +158,94 @@ declare module "longhorn" {
     }
 
     export const input: Input;
+
+    /**
+     * Fields a tween animates: `position` and `scale` take `{ x?, y? }`
+     * (or one number for a uniform scale), `color` takes `[r, g, b, a?]`.
+     * Sprite fields need a Sprite, `zoom` a Camera.
+     */
+    export interface TweenValues {
+        position?: Partial<Vec2>;
+        rotation?: number;
+        scale?: Partial<Vec2> | number;
+        color?: [number, number, number, number?];
+        alpha?: number;
+        zoom?: number;
+    }
+
+    export type Ease =
+        | "linear"
+        | `ease${"In" | "Out" | "InOut"}${"Quad" | "Cubic" | "Sine" | "Expo" | "Back" | "Elastic" | "Bounce"}`;
+
+    /**
+     * Steps of a tween, run one after the other. The tween starts from the
+     * entity's current values when the script call returns and sends a
+     * `TweenCompleted` event once done.
+     */
+    export interface TweenBuilder {
+        to(values: TweenValues, duration: number, ease?: Ease): TweenBuilder;
+        wait(duration: number): TweenBuilder;
+        /** Run the groups built by each callback at the same time */
+        parallel(...groups: ((group: TweenBuilder) => void)[]): TweenBuilder;
+        delay(seconds: number): TweenBuilder;
+        /** Play `count` times, or forever without a count */
+        loop(count?: number): TweenBuilder;
+        /** Run every other play backwards */
+        yoyo(): TweenBuilder;
+        /** Name reported in the completion event */
+        named(name: string): TweenBuilder;
+    }
+
+    export interface Engine {
+        emit(eventName: string, data?: unknown): void;
+        sendTo(entity: number, eventName: string, data?: unknown): void;
+        /**
+         * Add trauma (0..1) to a camera, or the main camera when none is given.
+         * The camera shakes by the square of its trauma, which decays over time.
+         */
+        shakeCamera(trauma: number, camera?: Entity | number): void;
+    }
+
+    export interface Engine {
+        tween(entity: Entity | number): TweenBuilder;
+        /** Stop every tween on the entity, leaving its fields where they are */
+        cancelTweens(entity: Entity | number): void;
+    }
+
+    export const engine: Engine;
+
+    /**
+     * Event from a UI element, passed to `onClick(self, event)` and
+     * `onValueChanged(self, event)` on the scripts of the target and then of
+     * each ancestor up to its canvas.
+     */
+    export interface UiEvent {
+        readonly type: "click" | "valueChanged";
+        /** Element the event happened on */
+        readonly target: Entity;
+        /** Element whose scripts are handling the event */
+        readonly currentTarget: Entity;
+        /** Pointer position in canvas space */
+        readonly position: Vec2;
+        /** New slider value, for valueChanged */
+        readonly value?: number;
+        /** Keep the event from reaching the scripts of further ancestors */
+        stopPropagation(): void;
+    }
+
+    /**
+     * Lines, outlines and labels drawn over the game and the editor's scene
+     * view, in world space. Shapes last one frame unless given a duration in
+     * seconds; colors default to white. Nothing is drawn in shipping builds.
+     */
+    export interface DebugDraw {
+        line(start: Vec2, end: Vec2, color?: [number, number, number, number], duration?: number): void;
+        rect(center: Vec2, size: Vec2, color?: [number, number, number, number], duration?: number): void;
+        circle(center: Vec2, radius: number, color?: [number, number, number, number], duration?: number): void;
+        arrow(start: Vec2, end: Vec2, color?: [number, number, number, number], duration?: number): void;
+        /** Label with its top-left corner at `position`, the same size at any zoom */
+        text(position: Vec2, text: string | number, color?: [number, number, number, number], duration?: number): void;
+    }
+
+    export const debug: DebugDraw;
 }
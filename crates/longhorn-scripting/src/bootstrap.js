@@ -5,6 +5,7 @@
 // Component type markers (used for self.get(Transform))
 const Transform = { name: "Transform" };
 const Sprite = { name: "Sprite" };
+const Text = { name: "Text" };
 
 // Entity class - passed as 'self' to lifecycle methods
 class Entity {
@@ -27,6 +28,141 @@ class Entity {
   }
 }
 
+// Tile access for self.tilemap, wired to the entity's Tilemap while its script runs
+class TilemapAccess {
+  constructor(info) {
+    this.cellSize = info.cellSize;
+    this.layers = info.layers;
+  }
+
+  // Layers can be given by index or name; the first layer is the default
+  layerIndex(layer) {
+    if (layer == null) return 0;
+    if (typeof layer === "number") return layer;
+    const index = this.layers.indexOf(layer);
+    if (index < 0) throw new Error(`Unknown tilemap layer '${layer}'`);
+    return index;
+  }
+
+  // Tile set index at a cell, or null if the cell is empty
+  getTile(x, y, layer) {
+    const tile = __longhorn_get_tile(this.layerIndex(layer), x, y);
+    return tile < 0 ? null : tile;
+  }
+
+  // Set a cell to a tile set index, or clear it with null
+  setTile(x, y, tile, layer) {
+    return __longhorn_set_tile(this.layerIndex(layer), x, y, tile == null ? -1 : tile);
+  }
+
+  // Cell containing a point relative to the tilemap entity
+  cellAt(x, y) {
+    return { x: Math.floor(x / this.cellSize.x), y: Math.floor(-y / this.cellSize.y) };
+  }
+}
+
+// Emitter controls for self.emitter; applied when the script returns
+class ParticleEmitterAccess {
+  constructor(info) {
+    this.playing = info.playing;
+    this.particleCount = info.particleCount;
+    this.pending = 0;
+  }
+
+  // Emit particles on the next particle update, playing or not
+  burst(count) {
+    this.pending += Math.max(0, Math.floor(count));
+  }
+
+  play() {
+    this.playing = true;
+  }
+
+  stop() {
+    this.playing = false;
+  }
+}
+
+// Parameters and active states of the entity's StateMachine; parameter
+// changes and sent events apply when the script call returns
+class StateMachineAccess {
+  constructor(info) {
+    this.active = info.active;
+    this.parameters = info.parameters;
+    this.events = [];
+  }
+
+  // Innermost active state, or null before the machine's first update
+  get state() {
+    return this.active.length > 0 ? this.active[this.active.length - 1] : null;
+  }
+
+  // Whether a state is active, directly or through an active child
+  is(state) {
+    return this.active.includes(state);
+  }
+
+  get(name) {
+    return this.parameters[name];
+  }
+
+  set(name, value) {
+    this.parameters[name] = typeof value === "boolean" ? value : Number(value);
+  }
+
+  // Fire transitions waiting for this event on the machine's next update
+  send(event) {
+    this.events.push(String(event));
+  }
+}
+
+// NavAgent controls; the agent starts walking after the script call returns
+class NavAgentAccess {
+  constructor(info) {
+    this.speed = info.speed;
+    this.stoppingDistance = info.stoppingDistance;
+    this.destination = info.destination;
+    this.status = info.status;
+    this.path = info.path;
+    this.repath = false;
+  }
+
+  // Whether the agent got to its destination
+  get arrived() {
+    return this.status === "arrived";
+  }
+
+  // Walk to a point (x, y or a vector), finding a new path
+  moveTo(x, y) {
+    const point = typeof x === "object" ? x : { x, y };
+    this.destination = { x: Number(point.x), y: Number(point.y) };
+    this.repath = true;
+  }
+
+  // Stop where the agent is
+  stop() {
+    this.destination = null;
+    this.repath = false;
+  }
+}
+
+// Event from a UI element, passed to onClick and onValueChanged; bubbles
+// from the target up to its canvas until stopPropagation() is called
+class UiEvent {
+  constructor(data) {
+    this.type = data.type;
+    this.target = new Entity(data.target);
+    this.currentTarget = this.target;
+    this.position = data.position;
+    if (data.value !== undefined) this.value = data.value;
+    this.propagationStopped = false;
+  }
+
+  stopPropagation() {
+    this.propagationStopped = true;
+  }
+}
+
 // Script class registry (populated when scripts are loaded)
 const __scripts = {};
 
@@ -74,6 +210,12 @@ globalThis.console = {
 globalThis.Entity = Entity;
 globalThis.Transform = Transform;
 globalThis.Sprite = Sprite;
+globalThis.Text = Text;
+globalThis.TilemapAccess = TilemapAccess;
+globalThis.ParticleEmitterAccess = ParticleEmitterAccess;
+globalThis.StateMachineAccess = StateMachineAccess;
+globalThis.NavAgentAccess = NavAgentAccess;
+globalThis.UiEvent = UiEvent;
 globalThis.__scripts = __scripts;
 globalThis.__instances = __instances;
 
@@ -92,4 +234,136 @@ globalThis.engine.sendTo = function(entityId, eventName, data) {
   __longhorn_emit_to_entity(entityId, eventName, dataJson);
 };
 
+// Add screen shake trauma (0..1) to a camera entity, or the main camera
+globalThis.engine.shakeCamera = function(trauma, camera) {
+  const cameraId = camera == null ? 0 : (typeof camera === "object" ? camera.id : camera);
+  __longhorn_camera_shake(trauma, cameraId);
+};
+
+// Immediate-mode debug drawing over the game; shapes last one frame unless
+// given a duration in seconds. Colors are [r, g, b, a] (white by default).
+const __debug_draw = (draw, color, duration) => {
+  draw.color = color || [1, 1, 1, 1];
+  draw.duration = duration || 0;
+  __longhorn_debug_draw(JSON.stringify(draw));
+};
+
+globalThis.debug = {
+  line(start, end, color, duration) {
+    __debug_draw({ shape: "line", start, end }, color, duration);
+  },
+  rect(center, size, color, duration) {
+    __debug_draw({ shape: "rect", center, size }, color, duration);
+  },
+  circle(center, radius, color, duration) {
+    __debug_draw({ shape: "circle", center, radius }, color, duration);
+  },
+  arrow(start, end, color, duration) {
+    __debug_draw({ shape: "arrow", start, end }, color, duration);
+  },
+  text(position, text, color, duration) {
+    __debug_draw({ shape: "text", position, text: String(text) }, color, duration);
+  },
+};
+
+// Tweens, built fluently and started when the script call returns:
+//   engine.tween(self).to({ position: { x: 100 } }, 0.5, "easeOutBack").wait(0.2).to({ alpha: 0 }, 0.3);
+// Steps run in sequence; parallel() runs groups built by callbacks together.
+const __tween_values = (values) => {
+  const fields = [];
+  for (const key of Object.keys(values)) {
+    const value = values[key];
+    if (key === "color" && Array.isArray(value)) {
+      ["color.r", "color.g", "color.b", "alpha"].forEach((field, i) => {
+        if (value[i] !== undefined) fields.push([field, value[i]]);
+      });
+    } else if (key === "scale" && typeof value === "number") {
+      fields.push(["scale.x", value], ["scale.y", value]);
+    } else if (typeof value === "object") {
+      for (const axis of Object.keys(value)) {
+        fields.push([axis === "a" ? "alpha" : key + "." + axis, value[axis]]);
+      }
+    } else {
+      fields.push([key, value]);
+    }
+  }
+  return fields;
+};
+
+class TweenBuilder {
+  constructor() {
+    this._steps = [];
+    this._delay = 0;
+    this._loops = 1;
+    this._yoyo = false;
+    this._name = null;
+  }
+
+  to(values, duration, ease) {
+    this._steps.push({ kind: "to", values: __tween_values(values), duration, ease: ease || "linear" });
+    return this;
+  }
+
+  wait(duration) {
+    this._steps.push({ kind: "to", values: [], duration });
+    return this;
+  }
+
+  parallel(...groups) {
+    const steps = groups.map((build) => {
+      const group = new TweenBuilder();
+      build(group);
+      return group.toJSON();
+    });
+    this._steps.push({ kind: "parallel", steps });
+    return this;
+  }
+
+  delay(seconds) {
+    this._delay = seconds;
+    return this;
+  }
+
+  // Play `count` times, or forever without a count
+  loop(count) {
+    this._loops = count === undefined ? 0 : Math.max(1, count);
+    return this;
+  }
+
+  yoyo() {
+    this._yoyo = true;
+    return this;
+  }
+
+  named(name) {
+    this._name = String(name);
+    return this;
+  }
+
+  toJSON() {
+    return { kind: "sequence", steps: this._steps, delay: this._delay, loops: this._loops, yoyo: this._yoyo, name: this._name };
+  }
+}
+
+const __tween_commands = [];
+const __entity_id = (entity) => (typeof entity === "object" ? entity.id : entity);
+
+globalThis.engine.tween = function(entity) {
+  const tween = new TweenBuilder();
+  __tween_commands.push({ op: "play", entity: __entity_id(entity), tween });
+  return tween;
+};
+
+// Stop every tween on an entity, leaving its fields where they are
+globalThis.engine.cancelTweens = function(entity) {
+  __tween_commands.push({ op: "cancel", entity: __entity_id(entity) });
+};
+
+globalThis.__flush_tweens = function() {
+  for (const command of __tween_commands) {
+    __longhorn_tween(JSON.stringify(command));
+  }
+  __tween_commands.length = 0;
+};
+
 "bootstrap loaded";
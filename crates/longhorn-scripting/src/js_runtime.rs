@@ -3,7 +3,10 @@
 
 use rquickjs::{Context, Function, Runtime, Value};
 
-use crate::ops::{get_console_callback, push_pending_event, push_pending_targeted_event};
+use crate::ops::{
+    get_console_callback, get_current_tile, push_pending_camera_shake, push_pending_debug_draw,
+    push_pending_event, push_pending_targeted_event, push_pending_tween, set_current_tile,
+};
 
 /// Wrapper around rquickjs Runtime and Context
 pub struct LonghornJsRuntime {
@@ -70,6 +73,57 @@ impl LonghornJsRuntime {
             globals
                 .set("__longhorn_emit_to_entity", emit_to_fn)
                 .expect("Failed to register __longhorn_emit_to_entity");
+
+            // Register __longhorn_camera_shake(trauma, camera_id), camera_id 0 = main camera
+            let shake_fn = Function::new(ctx.clone(), |trauma: f64, camera_id: u64| {
+                push_pending_camera_shake((camera_id != 0).then_some(camera_id), trauma as f32);
+            })
+            .expect("Failed to create camera_shake function");
+            globals
+                .set("__longhorn_camera_shake", shake_fn)
+                .expect("Failed to register __longhorn_camera_shake");
+
+            // Register __longhorn_debug_draw(draw_json), see JsDebugDraw
+            let debug_draw_fn = Function::new(ctx.clone(), |draw_json: String| {
+                match serde_json::from_str(&draw_json) {
+                    Ok(draw) => push_pending_debug_draw(draw),
+                    Err(e) => log::warn!("Invalid debug shape {}: {}", draw_json, e),
+                }
+            })
+            .expect("Failed to create debug_draw function");
+            globals
+                .set("__longhorn_debug_draw", debug_draw_fn)
+                .expect("Failed to register __longhorn_debug_draw");
+
+            // Register __longhorn_tween(command_json), see JsTweenCommand
+            let tween_fn = Function::new(ctx.clone(), |command_json: String| {
+                match serde_json::from_str(&command_json) {
+                    Ok(command) => push_pending_tween(command),
+                    Err(e) => log::warn!("Invalid tween {}: {}", command_json, e),
+                }
+            })
+            .expect("Failed to create tween function");
+            globals
+                .set("__longhorn_tween", tween_fn)
+                .expect("Failed to register __longhorn_tween");
+
+            // Register __longhorn_get_tile(layer, x, y) on the running entity's tilemap, -1 = empty
+            let get_tile_fn = Function::new(ctx.clone(), |layer: u32, x: i32, y: i32| -> i64 {
+                get_current_tile(layer as usize, x, y).map_or(-1, i64::from)
+            })
+            .expect("Failed to create get_tile function");
+            globals
+                .set("__longhorn_get_tile", get_tile_fn)
+                .expect("Failed to register __longhorn_get_tile");
+
+            // Register __longhorn_set_tile(layer, x, y, tile), tile < 0 clears the cell
+            let set_tile_fn = Function::new(ctx.clone(), |layer: u32, x: i32, y: i32, tile: i64| -> bool {
+                set_current_tile(layer as usize, x, y, u32::try_from(tile).ok())
+            })
+            .expect("Failed to create set_tile function");
+            globals
+                .set("__longhorn_set_tile", set_tile_fn)
+                .expect("Failed to register __longhorn_set_tile");
         });
     }
 
@@ -198,4 +252,95 @@ mod tests {
         assert!(called.load(Ordering::SeqCst));
         crate::ops::set_console_callback(None);
     }
+
+    #[test]
+    fn test_camera_shake_op() {
+        crate::ops::take_pending_camera_shakes();
+
+        let mut runtime = LonghornJsRuntime::new();
+        runtime
+            .execute_script("test", "__longhorn_camera_shake(0.5, 0); __longhorn_camera_shake(1, 42)")
+            .unwrap();
+
+        let shakes = crate::ops::take_pending_camera_shakes();
+        assert_eq!(shakes, vec![(None, 0.5), (Some(42), 1.0)]);
+    }
+
+    #[test]
+    fn test_debug_draw_op() {
+        use crate::ops::{JsDebugDraw, JsDebugShape, JsVec2};
+        crate::ops::take_pending_debug_draws();
+
+        let mut runtime = LonghornJsRuntime::new();
+        runtime
+            .execute_script(
+                "test",
+                r#"__longhorn_debug_draw(JSON.stringify({ shape: "circle", center: { x: 1, y: 2 }, radius: 3, duration: 0.5 }));
+                __longhorn_debug_draw("not json")"#,
+            )
+            .unwrap();
+
+        let draws = crate::ops::take_pending_debug_draws();
+        assert_eq!(
+            draws,
+            vec![JsDebugDraw {
+                shape: JsDebugShape::Circle { center: JsVec2 { x: 1.0, y: 2.0 }, radius: 3.0 },
+                color: [1.0, 1.0, 1.0, 1.0],
+                duration: 0.5,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_tween_builder() {
+        use crate::ops::{JsTween, JsTweenCommand, JsTweenKind};
+        crate::ops::take_pending_tweens();
+
+        let mut runtime = LonghornJsRuntime::new();
+        runtime.execute_script("bootstrap", crate::BOOTSTRAP_JS).unwrap();
+        runtime
+            .execute_script(
+                "test",
+                r#"engine.tween({ id: 7 })
+                    .to({ position: { x: 10 }, color: [1, 0, 0] }, 0.5, "easeOutBack")
+                    .parallel(g => g.wait(0.25), g => g.to({ scale: 2 }, 1).loop().yoyo())
+                    .named("pulse");
+                engine.cancelTweens(8);
+                __flush_tweens();
+                __flush_tweens();"#,
+            )
+            .unwrap();
+
+        let tween = |kind, loops, yoyo, name: Option<&str>| JsTween {
+            kind,
+            delay: 0.0,
+            loops,
+            yoyo,
+            name: name.map(String::from),
+        };
+        let to = |values: &[(&str, f64)], duration, ease: &str| JsTweenKind::To {
+            values: values.iter().map(|(name, value)| (name.to_string(), *value)).collect(),
+            duration,
+            ease: ease.to_string(),
+        };
+        let group = |step, loops, yoyo| tween(JsTweenKind::Sequence { steps: vec![tween(step, 1, false, None)] }, loops, yoyo, None);
+        let color = [("position.x", 10.0), ("color.r", 1.0), ("color.g", 0.0), ("color.b", 0.0)];
+        let parallel = JsTweenKind::Parallel {
+            steps: vec![
+                group(to(&[], 0.25, "linear"), 1, false),
+                group(to(&[("scale.x", 2.0), ("scale.y", 2.0)], 1.0, "linear"), 0, true),
+            ],
+        };
+        let steps = vec![tween(to(&color, 0.5, "easeOutBack"), 1, false, None), tween(parallel, 1, false, None)];
+        assert_eq!(
+            crate::ops::take_pending_tweens(),
+            vec![
+                JsTweenCommand::Play {
+                    entity: 7,
+                    tween: tween(JsTweenKind::Sequence { steps }, 1, false, Some("pulse")),
+                },
+                JsTweenCommand::Cancel { entity: 8 },
+            ]
+        );
+    }
 }
@@ -6,8 +6,9 @@ mod runtime;
 pub use compiler::*;
 pub use js_runtime::*;
 pub use ops::{
-    set_console_callback, take_pending_events, take_pending_targeted_events, ConsoleCallback,
-    JsSelf, JsSprite, JsTransform, JsVec2, OpsState,
+    set_console_callback, take_pending_camera_shakes, take_pending_debug_draws, take_pending_events,
+    take_pending_targeted_events, take_pending_tweens, ConsoleCallback,
+    JsDebugDraw, JsDebugShape, JsSelf, JsSprite, JsTransform, JsTween, JsTweenCommand, JsTweenKind, JsVec2, OpsState,
 };
 pub use runtime::*;
 
@@ -4,8 +4,9 @@
 //! These ops are registered as global functions in the QuickJS runtime
 //! and called from JavaScript via the bootstrap.js wrappers.
 
-use longhorn_core::Vec2;
+use longhorn_core::{NavAgent, ParameterValue, ParticleEmitter, ParticleState, StateMachine, Tilemap, Vec2};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Callback for console output (type-erased to avoid editor dependency)
@@ -28,6 +29,30 @@ thread_local! {
         std::cell::RefCell::new(Vec::new());
 }
 
+thread_local! {
+    /// Pending camera shakes requested by scripts (camera GUID or main camera, trauma)
+    static PENDING_CAMERA_SHAKES: std::cell::RefCell<Vec<(Option<u64>, f32)>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+thread_local! {
+    /// Pending debug shapes drawn by scripts
+    static PENDING_DEBUG_DRAWS: std::cell::RefCell<Vec<JsDebugDraw>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+thread_local! {
+    /// Pending tweens started and cancelled by scripts, in call order
+    static PENDING_TWEENS: std::cell::RefCell<Vec<JsTweenCommand>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+thread_local! {
+    /// Tilemap of the entity whose script is running, and whether the script changed it
+    static CURRENT_TILEMAP: std::cell::RefCell<Option<(Tilemap, bool)>> =
+        const { std::cell::RefCell::new(None) };
+}
+
 /// Set the console callback for the current thread
 pub fn set_console_callback(callback: Option<ConsoleCallback>) {
     CONSOLE_CALLBACK.with(|cb| {
@@ -54,6 +79,29 @@ pub fn push_pending_targeted_event(entity_id: u64, event_name: String, data: ser
     });
 }
 
+/// Push a pending camera shake (called from js_runtime ops)
+///
+/// `camera_id` is the camera entity's GUID, or `None` for the main camera.
+pub fn push_pending_camera_shake(camera_id: Option<u64>, trauma: f32) {
+    PENDING_CAMERA_SHAKES.with(|shakes| {
+        shakes.borrow_mut().push((camera_id, trauma));
+    });
+}
+
+/// Push a pending debug shape (called from js_runtime ops)
+pub fn push_pending_debug_draw(draw: JsDebugDraw) {
+    PENDING_DEBUG_DRAWS.with(|draws| {
+        draws.borrow_mut().push(draw);
+    });
+}
+
+/// Push a pending tween command (called from js_runtime ops)
+pub fn push_pending_tween(command: JsTweenCommand) {
+    PENDING_TWEENS.with(|tweens| {
+        tweens.borrow_mut().push(command);
+    });
+}
+
 /// Collect all pending events emitted by scripts and clear the queue
 pub fn take_pending_events() -> Vec<(String, serde_json::Value)> {
     PENDING_EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()))
@@ -64,6 +112,61 @@ pub fn take_pending_targeted_events() -> Vec<(u64, String, serde_json::Value)> {
     PENDING_TARGETED_EVENTS.with(|events| std::mem::take(&mut *events.borrow_mut()))
 }
 
+/// Collect all pending camera shakes requested by scripts and clear the queue
+pub fn take_pending_camera_shakes() -> Vec<(Option<u64>, f32)> {
+    PENDING_CAMERA_SHAKES.with(|shakes| std::mem::take(&mut *shakes.borrow_mut()))
+}
+
+/// Collect all pending debug shapes drawn by scripts and clear the queue
+pub fn take_pending_debug_draws() -> Vec<JsDebugDraw> {
+    PENDING_DEBUG_DRAWS.with(|draws| std::mem::take(&mut *draws.borrow_mut()))
+}
+
+/// Collect all pending tween commands from scripts and clear the queue
+pub fn take_pending_tweens() -> Vec<JsTweenCommand> {
+    PENDING_TWEENS.with(|tweens| std::mem::take(&mut *tweens.borrow_mut()))
+}
+
+/// Make a tilemap readable and writable by tile ops until `return_tilemap`
+pub(crate) fn lend_tilemap(tilemap: Tilemap) {
+    CURRENT_TILEMAP.with(|current| {
+        *current.borrow_mut() = Some((tilemap, false));
+    });
+}
+
+/// Take back the lent tilemap and whether a script changed it
+pub(crate) fn return_tilemap() -> Option<(Tilemap, bool)> {
+    CURRENT_TILEMAP.with(|current| current.borrow_mut().take())
+}
+
+/// Read a tile of the lent tilemap (called from js_runtime ops)
+pub fn get_current_tile(layer: usize, x: i32, y: i32) -> Option<u32> {
+    CURRENT_TILEMAP.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .and_then(|(tilemap, _)| tilemap.get_tile(layer, x, y))
+    })
+}
+
+/// Set or clear a tile of the lent tilemap (called from js_runtime ops)
+///
+/// Returns false if no tilemap is lent or the layer doesn't exist.
+pub fn set_current_tile(layer: usize, x: i32, y: i32, tile: Option<u32>) -> bool {
+    CURRENT_TILEMAP.with(|current| {
+        let mut current = current.borrow_mut();
+        let Some((tilemap, changed)) = current.as_mut().filter(|(tilemap, _)| layer < tilemap.layers.len())
+        else {
+            return false;
+        };
+        if tilemap.get_tile(layer, x, y) != tile {
+            tilemap.set_tile(layer, x, y, tile);
+            *changed = true;
+        }
+        true
+    })
+}
+
 /// Shared state accessible from ops
 pub struct OpsState {
     /// Current entity ID being processed
@@ -93,12 +196,88 @@ pub struct JsTransform {
     pub scale: JsVec2,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JsVec2 {
     pub x: f64,
     pub y: f64,
 }
 
+/// Debug shape drawn by a script through the `debug` global
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum JsDebugShape {
+    Line { start: JsVec2, end: JsVec2 },
+    Rect { center: JsVec2, size: JsVec2 },
+    Circle { center: JsVec2, radius: f64 },
+    Arrow { start: JsVec2, end: JsVec2 },
+    Text { position: JsVec2, text: String },
+}
+
+/// A debug shape with its color and how many seconds it stays on screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsDebugDraw {
+    #[serde(flatten)]
+    pub shape: JsDebugShape,
+    #[serde(default = "default_debug_color")]
+    pub color: [f64; 4],
+    /// Seconds to keep drawing the shape; 0 draws it for one frame
+    #[serde(default)]
+    pub duration: f64,
+}
+
+fn default_debug_color() -> [f64; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
+
+/// What a scripted tween does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JsTweenKind {
+    /// Animate fields, named like `"position.x"` or `"alpha"`, to values;
+    /// without values, just waits
+    To {
+        values: Vec<(String, f64)>,
+        duration: f64,
+        #[serde(default = "default_ease")]
+        ease: String,
+    },
+    Sequence { steps: Vec<JsTween> },
+    Parallel { steps: Vec<JsTween> },
+}
+
+/// Tween built by a script through `engine.tween`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsTween {
+    #[serde(flatten)]
+    pub kind: JsTweenKind,
+    #[serde(default)]
+    pub delay: f64,
+    /// Number of plays; 0 loops forever
+    #[serde(default = "default_loops")]
+    pub loops: u32,
+    #[serde(default)]
+    pub yoyo: bool,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+fn default_ease() -> String {
+    "linear".to_string()
+}
+
+fn default_loops() -> u32 {
+    1
+}
+
+/// Tween started or cancelled on an entity, by GUID
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JsTweenCommand {
+    Play { entity: u64, tween: JsTween },
+    /// Stop every tween playing on the entity
+    Cancel { entity: u64 },
+}
+
 /// Sprite data for JS interop
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -108,6 +287,15 @@ pub struct JsSprite {
     pub color: [f64; 4],
     pub flip_x: bool,
     pub flip_y: bool,
+    /// Material asset ID; 0 uses the built-in sprite shader
+    #[serde(default)]
+    pub material: u64,
+    /// Normal map asset ID for lit scenes; 0 for a flat sprite
+    #[serde(default)]
+    pub normal_map: u64,
+    /// How the texture fills `size`, e.g. `{ mode: "nine_slice", border: { ... } }`
+    #[serde(default)]
+    pub draw_mode: longhorn_core::SpriteDrawMode,
 }
 
 impl From<&longhorn_core::Transform> for JsTransform {
@@ -152,6 +340,9 @@ impl From<&longhorn_core::Sprite> for JsSprite {
             ],
             flip_x: s.flip_x,
             flip_y: s.flip_y,
+            material: s.material.0,
+            normal_map: s.normal_map.0,
+            draw_mode: s.draw_mode,
         }
     }
 }
@@ -169,6 +360,169 @@ impl From<JsSprite> for longhorn_core::Sprite {
             ],
             flip_x: s.flip_x,
             flip_y: s.flip_y,
+            material: longhorn_core::AssetId::new(s.material),
+            normal_map: longhorn_core::AssetId::new(s.normal_map),
+            draw_mode: s.draw_mode,
+        }
+    }
+}
+
+/// Text data for JS interop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsText {
+    /// Numbers and booleans assigned by scripts (e.g. a score) are converted to strings
+    #[serde(deserialize_with = "content_from_js")]
+    pub content: String,
+    pub font: u64,
+    pub size: f64,
+    pub color: [f64; 4],
+    pub align: longhorn_core::TextAlign,
+    pub wrap_width: Option<f64>,
+}
+
+/// Accept any JSON scalar as text content
+fn content_from_js<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(s) => s,
+        serde_json::Value::Null => String::new(),
+        value => value.to_string(),
+    })
+}
+
+impl From<&longhorn_core::Text> for JsText {
+    fn from(t: &longhorn_core::Text) -> Self {
+        Self {
+            content: t.content.clone(),
+            font: t.font.0,
+            size: t.size as f64,
+            color: [
+                t.color[0] as f64,
+                t.color[1] as f64,
+                t.color[2] as f64,
+                t.color[3] as f64,
+            ],
+            align: t.align,
+            wrap_width: t.wrap_width.map(|w| w as f64),
+        }
+    }
+}
+
+impl From<JsText> for longhorn_core::Text {
+    fn from(t: JsText) -> Self {
+        Self {
+            content: t.content,
+            font: longhorn_core::AssetId::new(t.font),
+            size: t.size as f32,
+            color: [
+                t.color[0] as f32,
+                t.color[1] as f32,
+                t.color[2] as f32,
+                t.color[3] as f32,
+            ],
+            align: t.align,
+            wrap_width: t.wrap_width.map(|w| w as f32),
+        }
+    }
+}
+
+/// Tilemap shape for JS interop; tiles are read and written through ops
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsTilemap {
+    pub cell_size: JsVec2,
+    /// Layer names in draw order
+    pub layers: Vec<String>,
+}
+
+impl From<&Tilemap> for JsTilemap {
+    fn from(t: &Tilemap) -> Self {
+        Self {
+            cell_size: JsVec2 {
+                x: t.cell_size.x as f64,
+                y: t.cell_size.y as f64,
+            },
+            layers: t.layers.iter().map(|layer| layer.name.clone()).collect(),
+        }
+    }
+}
+
+/// Particle emitter controls for JS interop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsParticleEmitter {
+    pub playing: bool,
+    /// Particles requested with `burst` during the call
+    #[serde(default)]
+    pub pending: u32,
+    /// Live particles when the call started
+    #[serde(default, skip_deserializing)]
+    pub particle_count: usize,
+}
+
+impl JsParticleEmitter {
+    pub fn new(emitter: &ParticleEmitter, state: Option<&ParticleState>) -> Self {
+        Self {
+            playing: emitter.playing,
+            pending: 0,
+            particle_count: state.map_or(0, ParticleState::len),
+        }
+    }
+}
+
+/// State machine parameters and active states for JS interop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsStateMachine {
+    /// Active states from the top level down when the call started
+    #[serde(default, skip_deserializing)]
+    pub active: Vec<String>,
+    pub parameters: HashMap<String, ParameterValue>,
+    /// Events sent with `send` during the call
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl JsStateMachine {
+    pub fn new(machine: &StateMachine) -> Self {
+        Self {
+            active: machine.active_states().to_vec(),
+            parameters: machine.parameters.clone(),
+            events: Vec::new(),
+        }
+    }
+}
+
+/// NavAgent destination and progress for JS interop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsNavAgent {
+    pub speed: f64,
+    pub stopping_distance: f64,
+    pub destination: Option<JsVec2>,
+    /// Set by `moveTo`, so walking to the current destination finds a new path
+    #[serde(default)]
+    pub repath: bool,
+    /// Status name when the call started
+    #[serde(default, skip_deserializing)]
+    pub status: String,
+    /// Waypoints still ahead when the call started
+    #[serde(default, skip_deserializing)]
+    pub path: Vec<JsVec2>,
+}
+
+impl JsNavAgent {
+    pub fn new(agent: &NavAgent) -> Self {
+        let vec2 = |v: Vec2| JsVec2 {
+            x: v.x as f64,
+            y: v.y as f64,
+        };
+        Self {
+            speed: agent.speed as f64,
+            stopping_distance: agent.stopping_distance as f64,
+            destination: agent.destination.map(vec2),
+            repath: false,
+            status: agent.status.name().to_string(),
+            path: agent.path.iter().copied().map(vec2).collect(),
         }
     }
 }
@@ -179,6 +533,21 @@ pub struct JsSelf {
     pub id: u64,
     pub transform: Option<JsTransform>,
     pub sprite: Option<JsSprite>,
+    #[serde(default)]
+    pub text: Option<JsText>,
+    /// Read-only here: scripts change tiles through `self.tilemap.setTile`
+    #[serde(default, skip_deserializing)]
+    pub tilemap: Option<JsTilemap>,
+    #[serde(default)]
+    pub emitter: Option<JsParticleEmitter>,
+    #[serde(default, rename = "stateMachine")]
+    pub state_machine: Option<JsStateMachine>,
+    #[serde(default, rename = "navAgent")]
+    pub nav_agent: Option<JsNavAgent>,
+    /// Material parameter values by name, present when the entity has them
+    /// or its sprite has a material
+    #[serde(default)]
+    pub material: Option<longhorn_core::MaterialParams>,
 }
 
 #[cfg(test)]
@@ -238,4 +607,25 @@ mod tests {
         assert_eq!(events[0].0, 123); // entity_id
         assert_eq!(events[0].1, "hit"); // event_name
     }
+
+    #[test]
+    fn test_lent_tilemap() {
+        assert!(!set_current_tile(0, 0, 0, Some(1)));
+
+        let mut tilemap = Tilemap::default().with_layer("Ground");
+        tilemap.set_tile(0, 2, 3, Some(4));
+        lend_tilemap(tilemap);
+        assert_eq!(get_current_tile(0, 2, 3), Some(4));
+        assert!(set_current_tile(0, 2, 3, Some(4)));
+        assert!(!set_current_tile(1, 0, 0, Some(1)));
+        let (tilemap, changed) = return_tilemap().unwrap();
+        assert!(!changed);
+
+        lend_tilemap(tilemap);
+        assert!(set_current_tile(0, 2, 3, None));
+        let (tilemap, changed) = return_tilemap().unwrap();
+        assert!(changed);
+        assert_eq!(tilemap.get_tile(0, 2, 3), None);
+        assert!(return_tilemap().is_none());
+    }
 }
@@ -1,21 +1,26 @@
 // crates/longhorn-scripting/src/runtime.rs
 use crate::compiler::{CompiledScript, TypeScriptCompiler};
 use crate::js_runtime::LonghornJsRuntime;
-use crate::ops::{JsSelf, JsSprite, JsTransform};
+use crate::ops::{
+    lend_tilemap, return_tilemap, JsNavAgent, JsParticleEmitter, JsSelf, JsSprite, JsStateMachine, JsText, JsTilemap,
+    JsTransform,
+};
 use crate::BOOTSTRAP_JS;
-use longhorn_core::{EntityId, Script, Sprite, Transform, World, LonghornError, Result};
+use longhorn_core::{
+    EntityGuid, MaterialParams, NavAgent, ParticleEmitter, ParticleState, Script, Sprite, StateMachine, Text, Tilemap, Transform, Vec2, World,
+    LonghornError, Result,
+};
 use std::collections::HashMap;
 use std::path::Path;
 
-/// Unique identifier for a script instance (entity_id, script_path)
+/// Unique identifier for a script instance (entity GUID, script_path)
 type ScriptInstanceId = (u64, String);
 
 /// Runtime state for a single script instance
 struct ScriptInstance {
     /// Compiled script reference
     script_path: String,
-    /// Instance properties (copied from Script component)
-    #[allow(dead_code)] // TODO: Will be used when calling JS methods
+    /// Instance properties as last applied from the Script component (JSON)
     properties: HashMap<String, String>,
     /// Whether onStart has been called
     started: bool,
@@ -23,6 +28,25 @@ struct ScriptInstance {
     enabled: bool,
 }
 
+/// JavaScript expression for an inspector property value
+fn property_js(value: &longhorn_core::ScriptValue, world: &World) -> String {
+    match value {
+        longhorn_core::ScriptValue::Number(n) => n.to_string(),
+        longhorn_core::ScriptValue::String(s) => format!("\"{}\"", s),
+        longhorn_core::ScriptValue::Boolean(b) => b.to_string(),
+        longhorn_core::ScriptValue::Vec2 { x, y } => {
+            format!("{{x: {}, y: {}}}", x, y)
+        }
+        longhorn_core::ScriptValue::Entity { entity } => {
+            // Resolve to a live Entity, or null if the target is gone
+            match entity.guid().filter(|_| entity.resolve(world).is_some()) {
+                Some(guid) => format!("new Entity({})", guid.get()),
+                None => "null".to_string(),
+            }
+        }
+    }
+}
+
 /// Script runtime - manages TypeScript execution via rquickjs (QuickJS)
 pub struct ScriptRuntime {
     /// Compiled scripts cache (path -> compiled)
@@ -174,13 +198,29 @@ impl ScriptRuntime {
             None => return,
         };
 
-        // Query all entities with Script components
-        for (entity_id, script) in world.query::<&Script>().iter() {
-            let entity_bits = entity_id.to_bits().get();
+        // Query all entities with Script components (keyed by GUID so instances survive respawns)
+        for (_entity_id, (script, guid)) in world.query::<(&Script, &EntityGuid)>().iter() {
+            let entity_bits = guid.get();
             let instance_key = format!("{}_{}", entity_bits, script.path);
 
-            if self.instances.contains_key(&(entity_bits, script.path.clone())) {
-                continue; // Already exists
+            if let Some(instance) = self.instances.get_mut(&(entity_bits, script.path.clone())) {
+                // Push properties changed on the component since the last sync (e.g. by animation clips)
+                for (prop_name, prop_value) in &script.properties {
+                    let json = serde_json::to_string(prop_value).unwrap_or_default();
+                    if instance.properties.get(prop_name) == Some(&json) {
+                        continue;
+                    }
+                    let set_prop_code = format!(
+                        r#"if (__instances["{}"]) __instances["{}"].{} = {};"#,
+                        instance_key,
+                        instance_key,
+                        prop_name,
+                        property_js(prop_value, world)
+                    );
+                    let _ = js_runtime.execute_script("longhorn:set_prop", &set_prop_code);
+                    instance.properties.insert(prop_name.clone(), json);
+                }
+                continue;
             }
 
             // Create instance in JS
@@ -189,9 +229,9 @@ impl ScriptRuntime {
                 const ScriptClass = __scripts["{}"];
                 if (ScriptClass) {{
                     __instances["{}"] = new ScriptClass();
-                    "created"
+                    return "created";
                 }} else {{
-                    "script not found"
+                    return "script not found";
                 }}
             }})()"#,
                 script.path, instance_key
@@ -202,17 +242,11 @@ impl ScriptRuntime {
                     if result == "created" {
                         // Apply inspector properties
                         for (prop_name, prop_value) in &script.properties {
-                            let value_js = match prop_value {
-                                longhorn_core::ScriptValue::Number(n) => n.to_string(),
-                                longhorn_core::ScriptValue::String(s) => format!("\"{}\"", s),
-                                longhorn_core::ScriptValue::Boolean(b) => b.to_string(),
-                                longhorn_core::ScriptValue::Vec2 { x, y } => {
-                                    format!("{{x: {}, y: {}}}", x, y)
-                                }
-                            };
                             let set_prop_code = format!(
                                 r#"__instances["{}"].{} = {};"#,
-                                instance_key, prop_name, value_js
+                                instance_key,
+                                prop_name,
+                                property_js(prop_value, world)
                             );
                             let _ = js_runtime.execute_script("longhorn:set_prop", &set_prop_code);
                         }
@@ -246,19 +280,8 @@ impl ScriptRuntime {
         }
     }
 
-    /// Run a lifecycle method on all script instances
-    fn run_lifecycle(&mut self, method: &str, world: &mut World, dt: f32) -> Result<()> {
-        // Check if we have an error (game should be paused)
-        if self.error.is_some() {
-            return Err(LonghornError::Scripting(self.error.clone().unwrap()));
-        }
-
-        let js_runtime = match &mut self.js_runtime {
-            Some(rt) => rt,
-            None => return Ok(()),
-        };
-
-        // Get sorted instance IDs by execution order
+    /// Script instances in execution order, ties broken by entity
+    fn sorted_instances(&self) -> Vec<ScriptInstanceId> {
         let mut sorted_instances: Vec<_> = self.instances.keys().cloned().collect();
         sorted_instances.sort_by(|a, b| {
             let order_a = self
@@ -273,10 +296,23 @@ impl ScriptRuntime {
                 .unwrap_or(0);
             order_a.cmp(&order_b).then_with(|| a.0.cmp(&b.0))
         });
+        sorted_instances
+    }
+
+    /// Run a lifecycle method on all script instances
+    fn run_lifecycle(&mut self, method: &str, world: &mut World, dt: f32) -> Result<()> {
+        // Check if we have an error (game should be paused)
+        if self.error.is_some() {
+            return Err(LonghornError::Scripting(self.error.clone().unwrap()));
+        }
+
+        if self.js_runtime.is_none() {
+            return Ok(());
+        }
 
         // Call method on each instance
-        for (entity_id, script_path) in sorted_instances {
-            let instance = match self.instances.get_mut(&(entity_id, script_path.clone())) {
+        for (entity_id, script_path) in self.sorted_instances() {
+            let instance = match self.instances.get(&(entity_id, script_path.clone())) {
                 Some(inst) => inst,
                 None => continue,
             };
@@ -290,92 +326,243 @@ impl ScriptRuntime {
                 continue;
             }
 
-            let instance_key = format!("{}_{}", entity_id, script_path);
-
-            // Convert entity_id back to EntityHandle
-            let entity_handle = match EntityId::from_bits(entity_id) {
-                Some(id) => longhorn_core::EntityHandle::new(id),
-                None => {
-                    log::error!("Invalid entity ID: {}", entity_id);
-                    continue;
+            let called = self.call_instance(method, world, entity_id, &script_path, &dt.to_string())?;
+            if called && method == "onStart" {
+                if let Some(instance) = self.instances.get_mut(&(entity_id, script_path)) {
+                    instance.started = true;
                 }
-            };
+            }
+        }
 
-            // Build self object with component data
-            let transform: Option<JsTransform> = world
-                .get::<Transform>(entity_handle)
-                .ok()
-                .map(|t| JsTransform::from(&*t));
-
-            let sprite: Option<JsSprite> = world
-                .get::<Sprite>(entity_handle)
-                .ok()
-                .map(|s| JsSprite::from(&*s));
-
-            let self_data = JsSelf {
-                id: entity_id,
-                transform,
-                sprite,
-            };
+        Ok(())
+    }
 
-            let self_json = serde_json::to_string(&self_data)
-                .map_err(|e| LonghornError::Scripting(format!("Failed to serialize self: {}", e)))?;
+    /// Call a method on one script instance with `self` built from its
+    /// entity and `arg` (a JS expression) as second argument, then write
+    /// component changes back
+    ///
+    /// # Returns
+    /// Whether the instance's entity exists
+    fn call_instance(
+        &mut self,
+        method: &str,
+        world: &mut World,
+        entity_id: u64,
+        script_path: &str,
+        arg: &str,
+    ) -> Result<bool> {
+        let js_runtime = match &mut self.js_runtime {
+            Some(rt) => rt,
+            None => return Ok(false),
+        };
 
-            // Build the call code
-            let call_code = format!(
-                r#"(() => {{
-                const inst = __instances["{}"];
-                if (inst && typeof inst.{} === "function") {{
-                    const self = {};
-                    inst.{}(self, {});
-                    return JSON.stringify({{ id: self.id, transform: self.transform, sprite: self.sprite }});
-                }} else {{
-                    return "no method";
-                }}
-            }})()"#,
-                instance_key, method, self_json, method, dt
-            );
+        let instance_key = format!("{}_{}", entity_id, script_path);
 
-            match js_runtime.execute_script("longhorn:call_lifecycle", &call_code) {
-                Ok(result) => {
-                    if result != "no method" {
-                        // Write back component changes
-                        match serde_json::from_str::<JsSelf>(&result) {
-                            Ok(changes) => {
-                                if let Some(t) = changes.transform {
-                                    if let Err(e) = world.set(entity_handle, Transform::from(t)) {
-                                        log::warn!("Failed to write back Transform for entity {}: {}", entity_id, e);
+        // Resolve the GUID to a live entity
+        let entity_handle = match world.entity_by_guid(EntityGuid(entity_id)) {
+            Some(handle) => handle,
+            None => {
+                log::debug!("Skipping script instance for missing entity {}", entity_id);
+                return Ok(false);
+            }
+        };
+
+        // Build self object with component data
+        let transform: Option<JsTransform> = world
+            .get::<Transform>(entity_handle)
+            .ok()
+            .map(|t| JsTransform::from(&*t));
+
+        let sprite: Option<JsSprite> = world
+            .get::<Sprite>(entity_handle)
+            .ok()
+            .map(|s| JsSprite::from(&*s));
+
+        let text: Option<JsText> = world
+            .get::<Text>(entity_handle)
+            .ok()
+            .map(|t| JsText::from(&*t));
+
+        let tilemap: Option<JsTilemap> = world
+            .get::<Tilemap>(entity_handle)
+            .ok()
+            .map(|t| JsTilemap::from(&*t));
+
+        let emitter: Option<JsParticleEmitter> = world
+            .get::<ParticleEmitter>(entity_handle)
+            .ok()
+            .map(|e| JsParticleEmitter::new(&e, world.get::<ParticleState>(entity_handle).ok().as_deref()));
+
+        let state_machine: Option<JsStateMachine> = world
+            .get::<StateMachine>(entity_handle)
+            .ok()
+            .map(|m| JsStateMachine::new(&m));
+
+        let nav_agent: Option<JsNavAgent> = world
+            .get::<NavAgent>(entity_handle)
+            .ok()
+            .map(|a| JsNavAgent::new(&a));
+
+        let material: Option<MaterialParams> = match world.get::<MaterialParams>(entity_handle) {
+            Ok(params) => Some((*params).clone()),
+            Err(_) => sprite
+                .as_ref()
+                .filter(|sprite| sprite.material != 0)
+                .map(|_| MaterialParams::new()),
+        };
+
+        let self_data = JsSelf {
+            id: entity_id,
+            transform,
+            sprite,
+            text,
+            tilemap,
+            emitter,
+            state_machine,
+            nav_agent,
+            material,
+        };
+
+        let self_json = serde_json::to_string(&self_data)
+            .map_err(|e| LonghornError::Scripting(format!("Failed to serialize self: {}", e)))?;
+
+        // Build the call code
+        let call_code = format!(
+            r#"(() => {{
+            const inst = __instances["{}"];
+            if (inst && typeof inst.{} === "function") {{
+                const self = {};
+                if (self.tilemap) self.tilemap = new TilemapAccess(self.tilemap);
+                if (self.emitter) self.emitter = new ParticleEmitterAccess(self.emitter);
+                if (self.stateMachine) self.stateMachine = new StateMachineAccess(self.stateMachine);
+                if (self.navAgent) self.navAgent = new NavAgentAccess(self.navAgent);
+                inst.{}(self, {});
+                __flush_tweens();
+                return JSON.stringify({{ id: self.id, transform: self.transform, sprite: self.sprite, text: self.text, emitter: self.emitter, stateMachine: self.stateMachine, navAgent: self.navAgent, material: self.material }});
+            }} else {{
+                return "no method";
+            }}
+        }})()"#,
+            instance_key, method, self_json, method, arg
+        );
+
+        // Lend the tilemap to the tile ops for the duration of the call
+        // instead of copying its tiles into `self`
+        if self_data.tilemap.is_some() {
+            if let Ok(current) = world.query_one_mut::<&mut Tilemap>(entity_handle) {
+                lend_tilemap(std::mem::take(current));
+            }
+        }
+        let result = js_runtime.execute_script("longhorn:call_lifecycle", &call_code);
+        if let Some((tilemap, changed)) = return_tilemap() {
+            if changed {
+                if let Ok(mut current) = world.get_mut::<Tilemap>(entity_handle) {
+                    *current = tilemap;
+                }
+            } else if let Ok(current) = world.query_one_mut::<&mut Tilemap>(entity_handle) {
+                *current = tilemap;
+            }
+        }
+
+        match result {
+            Ok(result) => {
+                if result != "no method" {
+                    // Write back component changes
+                    match serde_json::from_str::<JsSelf>(&result) {
+                        Ok(changes) => {
+                            if let Some(t) = changes.transform {
+                                if let Err(e) = world.set(entity_handle, Transform::from(t)) {
+                                    log::warn!("Failed to write back Transform for entity {}: {}", entity_id, e);
+                                }
+                            }
+                            if let Some(s) = changes.sprite {
+                                if let Err(e) = world.set(entity_handle, Sprite::from(s)) {
+                                    log::warn!("Failed to write back Sprite for entity {}: {}", entity_id, e);
+                                }
+                            }
+                            if let Some(t) = changes.text {
+                                if let Err(e) = world.set(entity_handle, Text::from(t)) {
+                                    log::warn!("Failed to write back Text for entity {}: {}", entity_id, e);
+                                }
+                            }
+                            // Only record material changes when a value was assigned
+                            if let Some(params) = changes.material {
+                                let changed = match world.get::<MaterialParams>(entity_handle) {
+                                    Ok(current) => *current != params,
+                                    Err(_) => !params.0.is_empty(),
+                                };
+                                if changed {
+                                    if let Err(e) = world.set(entity_handle, params) {
+                                        log::warn!("Failed to write back MaterialParams for entity {}: {}", entity_id, e);
                                     }
                                 }
-                                if let Some(s) = changes.sprite {
-                                    if let Err(e) = world.set(entity_handle, Sprite::from(s)) {
-                                        log::warn!("Failed to write back Sprite for entity {}: {}", entity_id, e);
+                            }
+                            // Only touch the emitter when the script played, stopped or burst it
+                            if let Some(e) = changes.emitter {
+                                let changed = world
+                                    .get::<ParticleEmitter>(entity_handle)
+                                    .is_ok_and(|current| current.playing != e.playing || e.pending > 0);
+                                if changed {
+                                    if let Ok(mut current) = world.get_mut::<ParticleEmitter>(entity_handle) {
+                                        current.playing = e.playing;
+                                        current.burst(e.pending);
                                     }
                                 }
                             }
-                            Err(e) => {
-                                log::warn!("Failed to parse component changes from {}.{}(): {}", script_path, method, e);
+                            // Only touch the machine when the script set parameters or sent events
+                            if let Some(m) = changes.state_machine {
+                                let changed = world
+                                    .get::<StateMachine>(entity_handle)
+                                    .is_ok_and(|current| current.parameters != m.parameters || !m.events.is_empty());
+                                if changed {
+                                    if let Ok(mut current) = world.get_mut::<StateMachine>(entity_handle) {
+                                        current.parameters = m.parameters;
+                                        m.events.into_iter().for_each(|event| current.send(event));
+                                    }
+                                }
+                            }
+                            // Only touch the agent when the script moved, stopped or retuned it
+                            if let Some(a) = changes.nav_agent {
+                                let destination = a.destination.map(|d| Vec2::new(d.x as f32, d.y as f32));
+                                let changed = world.get::<NavAgent>(entity_handle).is_ok_and(|current| {
+                                    a.repath
+                                        || current.destination != destination
+                                        || current.speed != a.speed as f32
+                                        || current.stopping_distance != a.stopping_distance as f32
+                                });
+                                if changed {
+                                    if let Ok(mut current) = world.get_mut::<NavAgent>(entity_handle) {
+                                        current.speed = a.speed as f32;
+                                        current.stopping_distance = a.stopping_distance as f32;
+                                        match destination {
+                                            Some(destination) if a.repath || current.destination != Some(destination) => {
+                                                current.set_destination(destination)
+                                            }
+                                            Some(_) => {}
+                                            None => current.stop(),
+                                        }
+                                    }
+                                }
                             }
                         }
-                    }
-
-                    if method == "onStart" {
-                        instance.started = true;
+                        Err(e) => {
+                            log::warn!("Failed to parse component changes from {}.{}(): {}", script_path, method, e);
+                        }
                     }
                 }
-                Err(e) => {
-                    let error_msg = format!(
-                        "Script error in {}.{}(): {}",
-                        script_path, method, e
-                    );
-                    log::error!("{}", error_msg);
-                    self.error = Some(error_msg.clone());
-                    return Err(LonghornError::Scripting(error_msg));
-                }
+            }
+            Err(e) => {
+                let error_msg = format!(
+                    "Script error in {}.{}(): {}",
+                    script_path, method, e
+                );
+                log::error!("{}", error_msg);
+                self.error = Some(error_msg.clone());
+                return Err(LonghornError::Scripting(error_msg));
             }
         }
 
-        Ok(())
+        Ok(true)
     }
 
     /// Update the game (call onUpdate)
@@ -391,6 +578,87 @@ impl ScriptRuntime {
         self.run_lifecycle("onUpdate", world, delta)
     }
 
+    /// Send a UI event to the scripts of the entities along its path
+    ///
+    /// `path` lists entity GUIDs from the event target up to its canvas and
+    /// `event` is the event data as JSON (`type`, `target`, `position` and,
+    /// for value changes, `value`). Each entity's scripts get `method` called
+    /// with `self` and a `UiEvent` whose `currentTarget` is that entity, until
+    /// one calls `stopPropagation()`.
+    pub fn dispatch_event(&mut self, world: &mut World, path: &[u64], method: &str, event: &str) -> Result<()> {
+        if !self.initialized {
+            return Ok(());
+        }
+        if let Some(error) = &self.error {
+            return Err(LonghornError::Scripting(error.clone()));
+        }
+
+        // New entities can be on the path before their first onUpdate
+        self.sync_instances(world);
+        let Some(js_runtime) = &mut self.js_runtime else {
+            return Ok(());
+        };
+        js_runtime
+            .execute_script("longhorn:create_event", &format!("globalThis.__event = new UiEvent({});", event))
+            .map_err(|e| LonghornError::Scripting(format!("Failed to create {} event: {}", method, e)))?;
+
+        let sorted_instances = self.sorted_instances();
+        for &entity_id in path {
+            let instances: Vec<_> = sorted_instances
+                .iter()
+                .filter(|(id, _)| *id == entity_id)
+                .filter(|key| self.instances.get(*key).is_some_and(|instance| instance.enabled))
+                .cloned()
+                .collect();
+            if instances.is_empty() {
+                continue;
+            }
+
+            if let Some(js_runtime) = &mut self.js_runtime {
+                let _ = js_runtime.execute_script(
+                    "longhorn:set_event_target",
+                    &format!("__event.currentTarget = new Entity({});", entity_id),
+                );
+            }
+            for (_, script_path) in instances {
+                self.call_instance(method, world, entity_id, &script_path, "__event")?;
+            }
+
+            let stopped = self
+                .js_runtime
+                .as_mut()
+                .and_then(|rt| rt.execute_script("longhorn:event_stopped", "String(__event.propagationStopped)").ok());
+            if stopped.as_deref() == Some("true") {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Call `method` with `self` and `arg` (a JS expression) on the enabled
+    /// scripts of one entity, e.g. a state machine's `onStateEnter`
+    pub fn call_entity(&mut self, world: &mut World, entity_id: u64, method: &str, arg: &str) -> Result<()> {
+        if !self.initialized {
+            return Ok(());
+        }
+        if let Some(error) = &self.error {
+            return Err(LonghornError::Scripting(error.clone()));
+        }
+
+        self.sync_instances(world);
+        let instances: Vec<_> = self
+            .sorted_instances()
+            .into_iter()
+            .filter(|(id, _)| *id == entity_id)
+            .filter(|key| self.instances.get(key).is_some_and(|instance| instance.enabled))
+            .collect();
+        for (_, script_path) in instances {
+            self.call_instance(method, world, entity_id, &script_path, arg)?;
+        }
+        Ok(())
+    }
+
     /// Handle touch start event
     pub fn on_touch_start(&mut self, _world: &mut World, _x: f32, _y: f32) -> Result<()> {
         if !self.initialized {
@@ -547,9 +815,20 @@ export default class TestScript {
 
         // Verify instance was created
         assert_eq!(runtime.instances.len(), 1);
-        let instance_id = (entity.id().to_bits().get(), "TestScript.ts".to_string());
+        let instance_id = (world.guid(entity).unwrap().get(), "TestScript.ts".to_string());
         assert!(runtime.instances.contains_key(&instance_id));
 
+        // Properties changed on the component reach the existing instance
+        world
+            .get_mut::<Script>(entity)
+            .unwrap()
+            .properties
+            .insert("speed".to_string(), longhorn_core::ScriptValue::Number(7.5));
+        runtime.update(&mut world, 0.0).unwrap();
+        let js = runtime.js_runtime.as_mut().unwrap();
+        let key = format!("{}_TestScript.ts", instance_id.0);
+        assert_eq!(js.execute_script("test", &format!(r#"__instances["{}"].speed"#, key)).unwrap(), "7.5");
+
         // Cleanup
         std::fs::remove_dir_all(&test_dir).ok();
     }
@@ -614,4 +893,157 @@ export default class TestScript {
         // Cleanup
         std::fs::remove_dir_all(&test_dir).ok();
     }
+
+    #[test]
+    fn test_dispatch_event_bubbles_until_stopped() {
+        let test_dir = std::env::temp_dir().join("test_game_dispatch_event");
+        let scripts_dir = test_dir.join("scripts");
+        std::fs::create_dir_all(&scripts_dir).unwrap();
+
+        std::fs::write(
+            scripts_dir.join("Button.ts"),
+            r#"export default class Button {
+    onClick(self, event) {
+        engine.emit("button", [event.target.id, event.currentTarget.id, event.type]);
+    }
+}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            scripts_dir.join("Panel.ts"),
+            r#"export default class Panel {
+    onClick(self, event) {
+        engine.emit("panel", [event.currentTarget.id]);
+        event.stopPropagation();
+    }
+}"#,
+        )
+        .unwrap();
+
+        let mut runtime = ScriptRuntime::new();
+        runtime.load_game(&test_dir).unwrap();
+
+        let mut world = World::new();
+        let button = world.spawn().with(Script::new("Button.ts")).build();
+        let panel = world.spawn().with(Script::new("Panel.ts")).build();
+        // Never reached: the panel stops the event
+        let canvas = world.spawn().with(Script::new("Button.ts")).build();
+        runtime.initialize(&mut world).unwrap();
+        crate::take_pending_events();
+
+        let path: Vec<u64> = [button, panel, canvas]
+            .iter()
+            .map(|&entity| world.guid(entity).unwrap().get())
+            .collect();
+        let event = format!(r#"{{"type":"click","target":{},"position":{{"x":1,"y":2}}}}"#, path[0]);
+        runtime.dispatch_event(&mut world, &path, "onClick", &event).unwrap();
+
+        let events = crate::take_pending_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].0, "button");
+        assert_eq!(events[0].1, serde_json::json!([path[0], path[0], "click"]));
+        assert_eq!(events[1].0, "panel");
+        assert_eq!(events[1].1, serde_json::json!([path[1]]));
+
+        std::fs::remove_dir_all(&test_dir).ok();
+    }
+
+    #[test]
+    fn test_call_entity_with_state_machine() {
+        use longhorn_core::{AssetId, ParameterValue, State, StateChart};
+
+        let test_dir = std::env::temp_dir().join("test_game_call_entity");
+        let scripts_dir = test_dir.join("scripts");
+        std::fs::create_dir_all(&scripts_dir).unwrap();
+
+        std::fs::write(
+            scripts_dir.join("Guard.ts"),
+            r#"export default class Guard {
+    onStateEnter(self, state) {
+        engine.emit("entered", [state, self.stateMachine.state, self.stateMachine.is("Patrol"), self.stateMachine.get("alert")]);
+        self.stateMachine.set("alert", true);
+        self.stateMachine.send("spotted");
+    }
+}"#,
+        )
+        .unwrap();
+
+        let mut runtime = ScriptRuntime::new();
+        runtime.load_game(&test_dir).unwrap();
+
+        let chart = std::sync::Arc::new(
+            StateChart::new(vec![State::new("Patrol").with_state(State::new("Walk"))])
+                .with_parameter("alert", ParameterValue::Bool(false)),
+        );
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(Script::new("Guard.ts"))
+            .with(StateMachine::new(AssetId::new(1)))
+            .build();
+        longhorn_core::update_state_machines(&mut world, 0.0, |_| Some(chart.clone()));
+        runtime.initialize(&mut world).unwrap();
+        crate::take_pending_events();
+
+        let guid = world.guid(entity).unwrap().get();
+        runtime.call_entity(&mut world, guid, "onStateEnter", r#""Walk""#).unwrap();
+
+        let events = crate::take_pending_events();
+        assert_eq!(events[0].1, serde_json::json!(["Walk", "Walk", true, false]));
+        let machine = world.get::<StateMachine>(entity).unwrap();
+        assert_eq!(machine.parameter("alert"), Some(ParameterValue::Bool(true)));
+        assert_eq!(machine.pending_events(), ["spotted"]);
+
+        std::fs::remove_dir_all(&test_dir).ok();
+    }
+
+    #[test]
+    fn test_call_entity_with_nav_agent() {
+        let test_dir = std::env::temp_dir().join("test_game_nav_agent");
+        let scripts_dir = test_dir.join("scripts");
+        std::fs::create_dir_all(&scripts_dir).unwrap();
+
+        std::fs::write(
+            scripts_dir.join("Hunter.ts"),
+            r#"export default class Hunter {
+    chase(self, target) {
+        engine.emit("agent", [self.navAgent.status, self.navAgent.arrived, self.navAgent.destination]);
+        self.navAgent.speed = 50;
+        self.navAgent.moveTo(target[0], target[1]);
+    }
+
+    halt(self) {
+        self.navAgent.stop();
+    }
+}"#,
+        )
+        .unwrap();
+
+        let mut runtime = ScriptRuntime::new();
+        runtime.load_game(&test_dir).unwrap();
+
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .with(Script::new("Hunter.ts"))
+            .with(NavAgent::new(100.0))
+            .build();
+        runtime.initialize(&mut world).unwrap();
+        crate::take_pending_events();
+
+        let guid = world.guid(entity).unwrap().get();
+        runtime.call_entity(&mut world, guid, "chase", "[40, 20]").unwrap();
+        let events = crate::take_pending_events();
+        assert_eq!(events[0].1, serde_json::json!(["idle", false, null]));
+        {
+            let agent = world.get::<NavAgent>(entity).unwrap();
+            assert_eq!(agent.speed, 50.0);
+            assert_eq!(agent.destination, Some(Vec2::new(40.0, 20.0)));
+        }
+
+        runtime.call_entity(&mut world, guid, "halt", "null").unwrap();
+        assert_eq!(world.get::<NavAgent>(entity).unwrap().destination, None);
+
+        std::fs::remove_dir_all(&test_dir).ok();
+    }
 }
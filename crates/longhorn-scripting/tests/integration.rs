@@ -1,4 +1,7 @@
-use longhorn_core::{Script, Transform, Vec2, World};
+use longhorn_core::{
+    AssetId, EntityGuid, EntityRef, MaterialParams, MaterialValue, ParticleEffect, ParticleEmitter, Script, ScriptValue,
+    Sprite, Text, TextAlign, Tilemap, Transform, Vec2, World,
+};
 use longhorn_scripting::ScriptRuntime;
 use std::path::PathBuf;
 
@@ -223,3 +226,245 @@ export default class CheckSprite {
 
     std::fs::remove_dir_all(&test_dir).ok();
 }
+
+#[test]
+fn test_script_sets_text() {
+    let test_dir = std::env::temp_dir().join("test_script_sets_text");
+    let scripts_dir = test_dir.join("scripts");
+    std::fs::create_dir_all(&scripts_dir).unwrap();
+
+    // Scores can be assigned as numbers
+    let script = r#"
+export default class Score {
+    score = 0;
+    onUpdate(self, dt) {
+        this.score += 10;
+        self.text.content = this.score;
+        if (this.score > 10) {
+            self.text.content = "Score: " + this.score;
+            self.text.align = "center";
+        }
+    }
+}
+"#;
+    std::fs::write(scripts_dir.join("Score.ts"), script).unwrap();
+
+    let mut runtime = ScriptRuntime::new();
+    runtime.load_game(&test_dir).unwrap();
+
+    let mut world = World::new();
+    let entity = world
+        .spawn()
+        .with(Script::new("Score.ts"))
+        .with(Text::new("", longhorn_core::AssetId::new(3), 24.0))
+        .build();
+
+    runtime.initialize(&mut world).unwrap();
+
+    runtime.update(&mut world, 0.016).unwrap();
+    assert_eq!(world.get::<Text>(entity).unwrap().content, "10");
+
+    runtime.update(&mut world, 0.016).unwrap();
+    {
+        let text = world.get::<Text>(entity).unwrap();
+        assert_eq!(text.content, "Score: 20");
+        assert_eq!(text.align, TextAlign::Center);
+        assert_eq!(text.font, longhorn_core::AssetId::new(3));
+        assert_eq!(text.size, 24.0);
+    }
+
+    std::fs::remove_dir_all(&test_dir).ok();
+}
+
+#[test]
+fn test_script_edits_tilemap() {
+    let test_dir = std::env::temp_dir().join("test_script_edits_tilemap");
+    let scripts_dir = test_dir.join("scripts");
+    std::fs::create_dir_all(&scripts_dir).unwrap();
+
+    // Moves the ground tile under the cursor cell to the decor layer, one step per update
+    let script = r#"
+export default class Digger {
+    onUpdate(self, dt) {
+        const cell = self.tilemap.cellAt(self.tilemap.cellSize.x * 2.5, -1);
+        const tile = self.tilemap.getTile(cell.x, cell.y);
+        if (tile !== null) {
+            self.tilemap.setTile(cell.x, cell.y, null);
+            self.tilemap.setTile(cell.x, cell.y, tile + 1, "Decor");
+        }
+    }
+}
+"#;
+    std::fs::write(scripts_dir.join("Digger.ts"), script).unwrap();
+
+    let mut runtime = ScriptRuntime::new();
+    runtime.load_game(&test_dir).unwrap();
+
+    let mut tilemap = Tilemap::new(longhorn_core::AssetId::new(1), Vec2::splat(16.0))
+        .with_layer("Ground")
+        .with_layer("Decor");
+    tilemap.set_tile(0, 2, 0, Some(4));
+    tilemap.set_tile(0, 3, 0, Some(7));
+
+    let mut world = World::new();
+    let entity = world.spawn().with(Script::new("Digger.ts")).with(tilemap).build();
+
+    runtime.initialize(&mut world).unwrap();
+    runtime.update(&mut world, 0.016).unwrap();
+    {
+        let tilemap = world.get::<Tilemap>(entity).unwrap();
+        assert_eq!(tilemap.get_tile(0, 2, 0), None);
+        assert_eq!(tilemap.get_tile(1, 2, 0), Some(5));
+        assert_eq!(tilemap.get_tile(0, 3, 0), Some(7));
+    }
+
+    // Nothing left to dig: the tilemap comes back untouched
+    runtime.update(&mut world, 0.016).unwrap();
+    let tilemap = world.get::<Tilemap>(entity).unwrap();
+    assert_eq!(tilemap.layers.len(), 2);
+    assert_eq!(tilemap.get_tile(1, 2, 0), Some(5));
+    assert_eq!(tilemap.get_tile(0, 3, 0), Some(7));
+
+    std::fs::remove_dir_all(&test_dir).ok();
+}
+
+#[test]
+fn test_script_entity_ref_property() {
+    let test_dir = std::env::temp_dir().join("test_script_entity_ref");
+    let scripts_dir = test_dir.join("scripts");
+    std::fs::create_dir_all(&scripts_dir).unwrap();
+
+    // Script that reads the id of its target entity (or -1 when unresolved)
+    let script = r#"
+export default class Follow {
+    target = null;
+    onStart(self) {
+        if (this.target instanceof Entity) {
+            self.transform.position.x = this.target.id;
+        } else {
+            self.transform.position.x = -1;
+        }
+    }
+}
+"#;
+    std::fs::write(scripts_dir.join("Follow.ts"), script).unwrap();
+
+    let mut runtime = ScriptRuntime::new();
+    runtime.load_game(&test_dir).unwrap();
+
+    let mut world = World::new();
+    world.spawn().with(EntityGuid(42)).build();
+
+    let mut live = Script::new("Follow.ts");
+    live.set_property("target", ScriptValue::Entity { entity: EntityRef::new(EntityGuid(42)) });
+    let follower = world.spawn().with(live).with(Transform::new()).build();
+
+    let mut dangling = Script::new("Follow.ts");
+    dangling.set_property("target", ScriptValue::Entity { entity: EntityRef::new(EntityGuid(7)) });
+    let orphan = world.spawn().with(dangling).with(Transform::new()).build();
+
+    runtime.initialize(&mut world).unwrap();
+
+    assert_eq!(world.get::<Transform>(follower).unwrap().position.x, 42.0);
+    assert_eq!(world.get::<Transform>(orphan).unwrap().position.x, -1.0);
+
+    std::fs::remove_dir_all(&test_dir).ok();
+}
+
+#[test]
+fn test_script_bursts_particle_emitter() {
+    let test_dir = std::env::temp_dir().join("test_script_bursts_particle_emitter");
+    let scripts_dir = test_dir.join("scripts");
+    std::fs::create_dir_all(&scripts_dir).unwrap();
+
+    // Stops the emitter and bursts until particles are alive
+    let script = r#"
+export default class Sparks {
+    onUpdate(self, dt) {
+        if (self.emitter.particleCount === 0) {
+            self.emitter.stop();
+            self.emitter.burst(3);
+            self.emitter.burst(2.5);
+        }
+    }
+}
+"#;
+    std::fs::write(scripts_dir.join("Sparks.ts"), script).unwrap();
+
+    let mut runtime = ScriptRuntime::new();
+    runtime.load_game(&test_dir).unwrap();
+
+    let mut world = World::new();
+    let emitter = ParticleEmitter::new(ParticleEffect::continuous(0.0), longhorn_core::AssetId::new(1));
+    let entity = world
+        .spawn()
+        .with(Script::new("Sparks.ts"))
+        .with(emitter)
+        .with(Transform::default())
+        .build();
+
+    runtime.initialize(&mut world).unwrap();
+    runtime.update(&mut world, 0.016).unwrap();
+    {
+        let emitter = world.get::<ParticleEmitter>(entity).unwrap();
+        assert!(!emitter.playing);
+        assert_eq!(emitter.pending, 5);
+    }
+
+    longhorn_core::update_particles(&mut world, 0.016);
+    assert_eq!(world.get::<longhorn_core::ParticleState>(entity).unwrap().len(), 5);
+
+    // With particles alive the script leaves the emitter alone
+    runtime.update(&mut world, 0.016).unwrap();
+    assert_eq!(world.get::<ParticleEmitter>(entity).unwrap().pending, 0);
+
+    std::fs::remove_dir_all(&test_dir).ok();
+}
+
+#[test]
+fn test_script_animates_material_params() {
+    let test_dir = std::env::temp_dir().join("test_script_animates_material_params");
+    let scripts_dir = test_dir.join("scripts");
+    std::fs::create_dir_all(&scripts_dir).unwrap();
+
+    // Fades a hit flash out and tints the sprite once
+    let script = r#"
+export default class HitFlash {
+    onStart(self) {
+        if (!self.material) return;
+        self.material.flash = 1;
+        self.material.tint = [1, 0, 0, 1];
+    }
+    onUpdate(self, dt) {
+        if (!self.material) return;
+        self.material.flash = Math.max(0, self.material.flash - 0.25);
+    }
+}
+"#;
+    std::fs::write(scripts_dir.join("HitFlash.ts"), script).unwrap();
+
+    let mut runtime = ScriptRuntime::new();
+    runtime.load_game(&test_dir).unwrap();
+
+    let mut world = World::new();
+    let sprite = Sprite::new(AssetId::new(1), Vec2::splat(16.0)).with_material(AssetId::new(2));
+    let entity = world.spawn().with(Script::new("HitFlash.ts")).with(sprite).build();
+    let plain = world
+        .spawn()
+        .with(Script::new("HitFlash.ts"))
+        .with(Sprite::new(AssetId::new(1), Vec2::splat(16.0)))
+        .build();
+
+    runtime.initialize(&mut world).unwrap();
+    runtime.update(&mut world, 0.016).unwrap();
+    runtime.update(&mut world, 0.016).unwrap();
+
+    let params = world.get::<MaterialParams>(entity).unwrap();
+    assert_eq!(params.get("flash"), Some(MaterialValue::Float(0.5)));
+    assert_eq!(params.get("tint"), Some(MaterialValue::Vec4([1.0, 0.0, 0.0, 1.0])));
+    assert_eq!(world.get::<Sprite>(entity).unwrap().material, AssetId::new(2));
+    // Sprites without a material have no `self.material`
+    assert!(world.get::<MaterialParams>(plain).is_err());
+
+    std::fs::remove_dir_all(&test_dir).ok();
+}
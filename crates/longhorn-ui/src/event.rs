@@ -0,0 +1,42 @@
+use glam::Vec2;
+use longhorn_core::EntityHandle;
+
+/// What happened to a UI element
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UiEventKind {
+    /// A press was released over the element it started on
+    Click,
+    /// A slider was dragged to a new value
+    ValueChanged(f32),
+}
+
+impl UiEventKind {
+    /// Event type name scripts see
+    pub fn name(&self) -> &'static str {
+        match self {
+            UiEventKind::Click => "click",
+            UiEventKind::ValueChanged(_) => "valueChanged",
+        }
+    }
+
+    /// Script method handling the event
+    pub fn handler(&self) -> &'static str {
+        match self {
+            UiEventKind::Click => "onClick",
+            UiEventKind::ValueChanged(_) => "onValueChanged",
+        }
+    }
+}
+
+/// An interaction with a UI element, bubbling up to its canvas
+#[derive(Debug, Clone, PartialEq)]
+pub struct UiEvent {
+    pub kind: UiEventKind,
+    /// Element the event happened on
+    pub target: EntityHandle,
+    /// The target followed by its ancestors up to the canvas, in the order
+    /// handlers see the event
+    pub path: Vec<EntityHandle>,
+    /// Pointer position in the canvas space of the target
+    pub position: Vec2,
+}
@@ -0,0 +1,317 @@
+use glam::Vec2;
+use longhorn_core::{
+    Canvas, CanvasScale, Children, Enabled, EntityHandle, Rect, RectTransform, UiAlign, UiButton, UiDirection,
+    UiImage, UiJustify, UiLayout, UiLayoutItem, UiPanel, UiScrollView, UiSlider, World,
+};
+use longhorn_renderer::ViewportScaling;
+
+/// A UI element placed by `UiTree::build`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UiNode {
+    pub entity: EntityHandle,
+    /// Rect in canvas space (origin top-left, y down), after scrolling
+    pub rect: Rect,
+    /// Area the element is visible in: its canvas, narrowed by the scroll
+    /// views around it
+    pub clip: Rect,
+    /// Index of the parent node; `None` for canvases
+    pub parent: Option<usize>,
+    /// Canvas units per game unit
+    pub scale: f32,
+    /// Scroll offset the children were laid out with
+    pub scroll: Vec2,
+}
+
+/// Every UI element of a world, laid out for one screen
+///
+/// Nodes are in draw order: canvases by ascending `sort_order`, each
+/// followed by its elements depth first, children in `Children` order.
+/// Entities disabled with `Enabled(false)` are left out with their
+/// descendants.
+#[derive(Debug, Clone, Default)]
+pub struct UiTree {
+    nodes: Vec<UiNode>,
+    /// Game-space position of the top-left corner of the canvases
+    origin: Vec2,
+}
+
+impl UiTree {
+    /// Lay out every canvas over the game area described by `scaling`
+    pub fn build(world: &World, scaling: &ViewportScaling) -> Self {
+        let mut tree = Self {
+            nodes: Vec::new(),
+            origin: scaling.origin,
+        };
+
+        let mut canvases: Vec<_> = world
+            .query::<&Canvas>()
+            .iter()
+            .map(|(entity, canvas)| (EntityHandle::new(entity), *canvas))
+            .filter(|(entity, _)| is_enabled(world, *entity))
+            .collect();
+        canvases.sort_by_key(|(entity, canvas)| (canvas.sort_order, entity.id.to_bits()));
+
+        for (entity, canvas) in canvases {
+            let scale = match canvas.scale {
+                CanvasScale::Game => 1.0,
+                CanvasScale::Pixel => scaling.scale,
+            };
+            let rect = Rect::from_pos_size(Vec2::ZERO, scaling.visible_size * scale);
+            tree.nodes.push(UiNode {
+                entity,
+                rect,
+                clip: rect,
+                parent: None,
+                scale,
+                scroll: Vec2::ZERO,
+            });
+            tree.place_children(world, tree.nodes.len() - 1);
+        }
+        tree
+    }
+
+    /// Lay out the children of a node and, recursively, their descendants
+    fn place_children(&mut self, world: &World, index: usize) {
+        let node = self.nodes[index].clone();
+        let Ok(children) = world.get::<Children>(node.entity) else {
+            return;
+        };
+        // Nested canvases are laid out as roots of their own
+        let children: Vec<(EntityHandle, RectTransform)> = children
+            .iter()
+            .map(|&child| EntityHandle::new(child))
+            .filter(|&child| !world.has::<Canvas>(child) && is_enabled(world, child))
+            .filter_map(|child| world.get::<RectTransform>(child).ok().map(|rect| (child, *rect)))
+            .collect();
+        if children.is_empty() {
+            return;
+        }
+
+        let rects = match world.get::<UiLayout>(node.entity) {
+            Ok(layout) => {
+                let items: Vec<_> = children
+                    .iter()
+                    .map(|(child, rect)| {
+                        let grow = world.get::<UiLayoutItem>(*child).map(|item| item.grow).unwrap_or(0.0);
+                        (rect.size, grow)
+                    })
+                    .collect();
+                flex(&layout, node.rect, &items)
+            }
+            Err(_) => children.iter().map(|(_, rect)| rect.resolve(node.rect)).collect(),
+        };
+
+        let (offset, clip) = match world.get::<UiScrollView>(node.entity) {
+            Ok(scroll) => (scroll.offset, clip_rect(node.clip, node.rect)),
+            Err(_) => (Vec2::ZERO, node.clip),
+        };
+        self.nodes[index].scroll = offset;
+
+        for ((child, _), rect) in children.into_iter().zip(rects) {
+            self.nodes.push(UiNode {
+                entity: child,
+                rect: rect.translate(-offset),
+                clip,
+                parent: Some(index),
+                scale: node.scale,
+                scroll: Vec2::ZERO,
+            });
+            self.place_children(world, self.nodes.len() - 1);
+        }
+    }
+
+    /// Nodes in draw order
+    pub fn nodes(&self) -> &[UiNode] {
+        &self.nodes
+    }
+
+    /// Index of an entity's node
+    pub fn index_of(&self, entity: EntityHandle) -> Option<usize> {
+        self.nodes.iter().position(|node| node.entity == entity)
+    }
+
+    /// Convert a game-space position into the canvas space of a node
+    pub fn to_canvas(&self, index: usize, position: Vec2) -> Vec2 {
+        (position - self.origin) * self.nodes[index].scale
+    }
+
+    /// Index of the topmost element under a game-space position that
+    /// receives touches
+    ///
+    /// Elements with a panel, image, button, slider or scroll view receive
+    /// touches; labels and bare canvases let them through.
+    pub fn hit_test(&self, world: &World, position: Vec2) -> Option<usize> {
+        (0..self.nodes.len()).rev().find(|&index| {
+            let node = &self.nodes[index];
+            let point = self.to_canvas(index, position);
+            node.parent.is_some()
+                && node.rect.contains(point)
+                && node.clip.contains(point)
+                && receives_touches(world, node.entity)
+        })
+    }
+
+    /// Entities from a node up to its canvas
+    pub fn path(&self, index: usize) -> Vec<EntityHandle> {
+        let mut path = Vec::new();
+        let mut current = Some(index);
+        while let Some(index) = current {
+            path.push(self.nodes[index].entity);
+            current = self.nodes[index].parent;
+        }
+        path
+    }
+
+    /// Furthest a scroll view can scroll: how far its children reach past
+    /// its bottom-right corner
+    pub fn scroll_range(&self, index: usize) -> Vec2 {
+        let node = &self.nodes[index];
+        self.nodes
+            .iter()
+            .filter(|child| child.parent == Some(index))
+            .map(|child| child.rect.max + node.scroll - node.rect.max)
+            .fold(Vec2::ZERO, Vec2::max)
+    }
+}
+
+/// Whether an entity is not disabled with `Enabled(false)`
+fn is_enabled(world: &World, entity: EntityHandle) -> bool {
+    world.get::<Enabled>(entity).map(|enabled| enabled.is_enabled()).unwrap_or(true)
+}
+
+/// Whether touches on an element stop at it
+fn receives_touches(world: &World, entity: EntityHandle) -> bool {
+    world.has::<UiPanel>(entity)
+        || world.has::<UiImage>(entity)
+        || world.has::<UiButton>(entity)
+        || world.has::<UiSlider>(entity)
+        || world.has::<UiScrollView>(entity)
+}
+
+/// The part of `rect` inside `clip`, empty if they don't overlap
+fn clip_rect(clip: Rect, rect: Rect) -> Rect {
+    clip.intersection(&rect).unwrap_or(Rect::from_pos_size(rect.min, Vec2::ZERO))
+}
+
+/// Place children of the given sizes and grow factors inside `area`
+pub fn flex(layout: &UiLayout, area: Rect, items: &[(Vec2, f32)]) -> Vec<Rect> {
+    let (main, cross) = match layout.direction {
+        UiDirection::Row => (0, 1),
+        UiDirection::Column => (1, 0),
+    };
+    let min = area.min + Vec2::splat(layout.padding);
+    let inner = (area.max - Vec2::splat(layout.padding) - min).max(Vec2::ZERO);
+
+    let gaps = layout.gap * items.len().saturating_sub(1) as f32;
+    let used: f32 = items.iter().map(|(size, _)| size[main]).sum::<f32>() + gaps;
+    let free = (inner[main] - used).max(0.0);
+    let total_grow: f32 = items.iter().map(|(_, grow)| grow.max(0.0)).sum();
+
+    // Growing children take the free space; otherwise it goes around them
+    let (mut cursor, spacing) = if total_grow > 0.0 {
+        (0.0, layout.gap)
+    } else {
+        match layout.justify {
+            UiJustify::Start => (0.0, layout.gap),
+            UiJustify::Center => (free / 2.0, layout.gap),
+            UiJustify::End => (free, layout.gap),
+            UiJustify::SpaceBetween if items.len() > 1 => (0.0, layout.gap + free / (items.len() - 1) as f32),
+            UiJustify::SpaceBetween => (0.0, layout.gap),
+        }
+    };
+
+    items
+        .iter()
+        .map(|&(size, grow)| {
+            let mut placed = size.max(Vec2::ZERO);
+            if total_grow > 0.0 {
+                placed[main] += free * grow.max(0.0) / total_grow;
+            }
+            if layout.align == UiAlign::Stretch {
+                placed[cross] = inner[cross];
+            }
+
+            let mut position = Vec2::ZERO;
+            position[main] = cursor;
+            position[cross] = match layout.align {
+                UiAlign::Start | UiAlign::Stretch => 0.0,
+                UiAlign::Center => (inner[cross] - placed[cross]) / 2.0,
+                UiAlign::End => inner[cross] - placed[cross],
+            };
+            cursor += placed[main] + spacing;
+            Rect::from_pos_size(min + position, placed)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::add_child;
+    use longhorn_renderer::ScaleMode;
+
+    #[test]
+    fn test_flex_grows_and_justifies() {
+        let area = Rect::from_pos_size(Vec2::ZERO, Vec2::new(100.0, 40.0));
+        let items = [(Vec2::new(20.0, 10.0), 0.0), (Vec2::new(20.0, 10.0), 1.0)];
+
+        // The growing child takes the space left after padding and gaps
+        let layout = UiLayout::row().with_gap(10.0).with_padding(5.0).with_align(UiAlign::Stretch);
+        let rects = flex(&layout, area, &items);
+        assert_eq!(rects[0], Rect::from_pos_size(Vec2::new(5.0, 5.0), Vec2::new(20.0, 30.0)));
+        assert_eq!(rects[1], Rect::from_pos_size(Vec2::new(35.0, 5.0), Vec2::new(60.0, 30.0)));
+
+        let fixed = [(Vec2::new(20.0, 10.0), 0.0); 3];
+        let between = UiLayout::row().with_justify(UiJustify::SpaceBetween).with_align(UiAlign::Center);
+        let rects = flex(&between, area, &fixed);
+        assert_eq!(rects.iter().map(|r| r.min.x).collect::<Vec<_>>(), vec![0.0, 40.0, 80.0]);
+        assert!(rects.iter().all(|r| r.min.y == 15.0));
+
+        let column = UiLayout::column().with_justify(UiJustify::End).with_align(UiAlign::End);
+        let rects = flex(&column, area, &fixed[..2]);
+        assert_eq!(rects[1], Rect::from_pos_size(Vec2::new(80.0, 30.0), Vec2::new(20.0, 10.0)));
+    }
+
+    #[test]
+    fn test_tree_scrolls_clips_and_hit_tests() {
+        let mut world = World::new();
+        let canvas = world.spawn().with(Canvas::new()).build();
+        let list = world
+            .spawn()
+            .with(RectTransform::new(Vec2::new(10.0, 10.0), Vec2::new(100.0, 100.0)))
+            .with(UiScrollView { offset: Vec2::new(0.0, 30.0), ..UiScrollView::vertical() })
+            .with(UiLayout::column())
+            .build();
+        add_child(&mut world, canvas, list).unwrap();
+        let items: Vec<_> = (0..4)
+            .map(|_| {
+                let item = world
+                    .spawn()
+                    .with(RectTransform::new(Vec2::ZERO, Vec2::new(100.0, 50.0)))
+                    .with(UiButton::new())
+                    .build();
+                add_child(&mut world, list, item).unwrap();
+                item
+            })
+            .collect();
+        let hidden = world.spawn().with(RectTransform::default()).with(Enabled::new(false)).build();
+        add_child(&mut world, list, hidden).unwrap();
+
+        // Letterboxed 2:1 screen: the game area starts 400 pixels in
+        let scaling = ViewportScaling::new(ScaleMode::Fit, Vec2::new(800.0, 600.0), 1600, 600);
+        let tree = UiTree::build(&world, &scaling);
+        assert_eq!(tree.nodes().len(), 6);
+        let second = tree.index_of(items[1]).unwrap();
+        assert_eq!(tree.nodes()[second].rect, Rect::from_pos_size(Vec2::new(10.0, 30.0), Vec2::new(100.0, 50.0)));
+        assert_eq!(tree.nodes()[second].clip, Rect::from_pos_size(Vec2::new(10.0, 10.0), Vec2::new(100.0, 100.0)));
+        assert_eq!(tree.path(second), vec![items[1], list, canvas]);
+        assert_eq!(tree.scroll_range(tree.index_of(list).unwrap()), Vec2::new(0.0, 100.0));
+
+        // Game space matches canvas space with `CanvasScale::Game`
+        assert_eq!(tree.hit_test(&world, Vec2::new(50.0, 60.0)), Some(second));
+        // The first item is scrolled out of view above the list
+        assert_eq!(tree.hit_test(&world, Vec2::new(50.0, 5.0)), None);
+        // Empty canvas lets touches through to the game
+        assert_eq!(tree.hit_test(&world, Vec2::new(500.0, 500.0)), None);
+    }
+}
@@ -0,0 +1,14 @@
+//! In-game UI: canvases of anchored rects with panels, images, labels,
+//! buttons, sliders and scroll views, laid out in screen space over the game
+//!
+//! The components live in `longhorn-core` so scenes can save them; this
+//! crate lays them out, turns touches into bubbling events and builds the
+//! sprites that draw them.
+
+mod event;
+mod layout;
+mod system;
+
+pub use event::*;
+pub use layout::*;
+pub use system::*;
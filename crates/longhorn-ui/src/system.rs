@@ -0,0 +1,566 @@
+use crate::{UiEvent, UiEventKind, UiTree};
+use glam::Vec2;
+use longhorn_assets::{AssetManager, AssetSource};
+use longhorn_core::{
+    EntityHandle, GlobalTransform, Rect, Shape, Text, TextAlign, UiButton, UiImage, UiLabel, UiPanel, UiScrollView,
+    UiSlider, VerticalAlign, World,
+};
+use longhorn_input::{InputState, TouchEvent};
+use longhorn_renderer::{
+    glyph_instance, shape_instance, Camera, Color, ScreenRect, SpriteBatch, SpriteInstance, TextGlyphs, TextSystem,
+    ViewportScaling,
+};
+use std::collections::HashMap;
+
+/// Distance in canvas units a press inside a scroll view moves before it
+/// scrolls instead of clicking
+pub const UI_DRAG_THRESHOLD: f32 = 10.0;
+
+/// Height of a slider's track as a fraction of the slider's height
+const SLIDER_TRACK_HEIGHT: f32 = 0.4;
+
+/// UI drawn with one camera, clipped to one screen area
+pub struct UiView {
+    /// Camera showing the view's canvas area, with canvas y flipped upwards
+    pub camera: Camera,
+    pub viewport: ScreenRect,
+    /// Elements in draw order; don't sort them
+    pub sprites: SpriteBatch,
+}
+
+/// A touch that started on a UI element
+#[derive(Debug, Clone)]
+struct Press {
+    /// Topmost element under the touch when it started
+    target: EntityHandle,
+    /// First button or slider from the target up, which handles the press
+    control: Option<EntityHandle>,
+    /// Whether the control can be used
+    interactable: bool,
+    /// First scroll view from the target up
+    scroll_view: Option<EntityHandle>,
+    /// Game-space positions where the touch started and last moved to
+    start: Vec2,
+    last: Vec2,
+    /// Whether the touch is dragging the scroll view, cancelling the click
+    scrolling: bool,
+}
+
+/// Lays out, hit-tests and draws UI elements
+///
+/// `update` turns this frame's touches into clicks, slider changes and
+/// scrolling; `collect` turns the elements into sprites drawn over the game.
+/// Both lay the UI out again, so changes made in between show up right away.
+#[derive(Default)]
+pub struct UiSystem {
+    tree: UiTree,
+    press: Option<Press>,
+    events: Vec<UiEvent>,
+    /// Laid-out label text per entity
+    labels: HashMap<EntityHandle, TextGlyphs>,
+}
+
+impl UiSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle this frame's touches
+    ///
+    /// Touch positions are in game space, as `InputState` holds them. Events
+    /// from earlier frames are dropped.
+    pub fn update(&mut self, world: &mut World, input: &InputState, scaling: &ViewportScaling) {
+        self.events.clear();
+        self.tree = UiTree::build(world, scaling);
+
+        for event in input.events() {
+            let position = event.position();
+            match event {
+                TouchEvent::Start { .. } => self.press_start(world, position),
+                TouchEvent::Move { .. } => self.press_move(world, position),
+                TouchEvent::End { .. } => self.press_end(world, position),
+            }
+        }
+    }
+
+    /// Events from the last `update`
+    pub fn events(&self) -> &[UiEvent] {
+        &self.events
+    }
+
+    /// The layout from the last `update` or `collect`
+    pub fn tree(&self) -> &UiTree {
+        &self.tree
+    }
+
+    /// Whether the current touch started on a UI element, so the game can
+    /// ignore it
+    pub fn captures_touch(&self) -> bool {
+        self.press.is_some()
+    }
+
+    /// Button drawn pressed, if any
+    pub fn pressed_button(&self) -> Option<EntityHandle> {
+        self.press
+            .as_ref()
+            .filter(|press| press.interactable && !press.scrolling)
+            .and_then(|press| press.control)
+    }
+
+    fn press_start(&mut self, world: &mut World, position: Vec2) {
+        self.press = None;
+        let Some(index) = self.tree.hit_test(world, position) else {
+            return;
+        };
+        let path = self.tree.path(index);
+        let control = path
+            .iter()
+            .copied()
+            .find(|&entity| world.has::<UiButton>(entity) || world.has::<UiSlider>(entity));
+        let interactable = control.is_none_or(|control| {
+            let button = world.get::<UiButton>(control).map(|button| button.interactable);
+            let slider = world.get::<UiSlider>(control).map(|slider| slider.interactable);
+            button.or(slider).unwrap_or(true)
+        });
+        self.press = Some(Press {
+            target: path[0],
+            control,
+            interactable,
+            scroll_view: path.iter().copied().find(|&entity| world.has::<UiScrollView>(entity)),
+            start: position,
+            last: position,
+            scrolling: false,
+        });
+
+        if let Some(slider) = control.filter(|&control| interactable && world.has::<UiSlider>(control)) {
+            self.drag_slider(world, slider, position);
+        }
+    }
+
+    fn press_move(&mut self, world: &mut World, position: Vec2) {
+        let Some(mut press) = self.press.take() else {
+            return;
+        };
+        let slider = press.control.filter(|&control| world.has::<UiSlider>(control));
+        match (slider, press.scroll_view) {
+            (Some(slider), _) => {
+                if press.interactable {
+                    self.drag_slider(world, slider, position);
+                }
+            }
+            (None, Some(scroll_view)) => {
+                if let Some(index) = self.tree.index_of(scroll_view) {
+                    let scale = self.tree.nodes()[index].scale;
+                    if !press.scrolling && (position - press.start).length() * scale > UI_DRAG_THRESHOLD {
+                        press.scrolling = true;
+                    }
+                    if press.scrolling {
+                        self.drag_scroll_view(world, index, (position - press.last) * scale);
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+        press.last = position;
+        self.press = Some(press);
+    }
+
+    fn press_end(&mut self, world: &mut World, position: Vec2) {
+        let Some(press) = self.press.take() else {
+            return;
+        };
+        let is_slider = press.control.is_some_and(|control| world.has::<UiSlider>(control));
+        if press.scrolling || is_slider || !press.interactable {
+            return;
+        }
+
+        // Only a release over the pressed element (or inside it) clicks it
+        let released_on_target = self
+            .tree
+            .hit_test(world, position)
+            .is_some_and(|index| self.tree.path(index).contains(&press.target));
+        if let Some(index) = self.tree.index_of(press.target).filter(|_| released_on_target) {
+            self.events.push(UiEvent {
+                kind: UiEventKind::Click,
+                target: press.target,
+                path: self.tree.path(index),
+                position: self.tree.to_canvas(index, position),
+            });
+        }
+    }
+
+    /// Move a slider's value to the pointer, emitting a change event
+    fn drag_slider(&mut self, world: &mut World, slider: EntityHandle, position: Vec2) {
+        let Some(index) = self.tree.index_of(slider) else {
+            return;
+        };
+        let rect = self.tree.nodes()[index].rect;
+        let point = self.tree.to_canvas(index, position);
+        let Ok(mut state) = world.get_mut::<UiSlider>(slider) else {
+            return;
+        };
+
+        let (start, length) = slider_track(rect);
+        let fraction = if length > 0.0 { ((point.x - start) / length).clamp(0.0, 1.0) } else { 0.0 };
+        let value = state.snap(state.min + fraction * (state.max - state.min));
+        if value == state.value {
+            return;
+        }
+        state.value = value;
+        drop(state);
+
+        self.events.push(UiEvent {
+            kind: UiEventKind::ValueChanged(value),
+            target: slider,
+            path: self.tree.path(index),
+            position: point,
+        });
+    }
+
+    /// Scroll by a pointer movement in canvas units, within the content
+    fn drag_scroll_view(&mut self, world: &mut World, index: usize, delta: Vec2) {
+        let range = self.tree.scroll_range(index);
+        let Ok(mut scroll) = world.get_mut::<UiScrollView>(self.tree.nodes()[index].entity) else {
+            return;
+        };
+        let mut offset = scroll.offset;
+        if scroll.horizontal {
+            offset.x -= delta.x;
+        }
+        if scroll.vertical {
+            offset.y -= delta.y;
+        }
+        scroll.offset = offset.clamp(Vec2::ZERO, range);
+    }
+
+    /// Lay out the UI and build the views drawing it over the game
+    ///
+    /// Views come in draw order; each scroll view starts a new one clipped
+    /// to its rect. Label text is laid out through `text`, which uploads new
+    /// glyphs to `assets`.
+    pub fn collect<S: AssetSource>(
+        &mut self,
+        world: &World,
+        text: &mut TextSystem,
+        assets: &mut AssetManager<S>,
+        scaling: &ViewportScaling,
+    ) -> Vec<UiView> {
+        self.tree = UiTree::build(world, scaling);
+        self.labels.retain(|&entity, _| world.has::<UiLabel>(entity));
+        let pressed = self.pressed_button();
+
+        let mut views = Vec::new();
+        let mut current: Option<(Rect, f32)> = None;
+        let mut sprites = SpriteBatch::new();
+        for node in self.tree.nodes() {
+            if current != Some((node.clip, node.scale)) {
+                if let Some((clip, scale)) = current.filter(|_| !sprites.is_empty()) {
+                    views.extend(ui_view(clip, scale, scaling, std::mem::take(&mut sprites)));
+                }
+                sprites = SpriteBatch::new();
+                current = Some((node.clip, node.scale));
+            }
+
+            let entity = node.entity;
+            let rect = node.rect;
+            let tint = match world.get::<UiButton>(entity) {
+                Ok(button) if !button.interactable => button.disabled_color,
+                Ok(button) if pressed == Some(entity) => button.pressed_color,
+                Ok(button) => button.normal_color,
+                Err(_) => [1.0; 4],
+            };
+
+            if let Ok(panel) = world.get::<UiPanel>(entity) {
+                let shape = if panel.corner_radius > 0.0 {
+                    Shape::rounded_rectangle(rect.size(), panel.corner_radius)
+                } else {
+                    Shape::rectangle(rect.size())
+                };
+                let mut shape = shape.with_fill_color(multiply(panel.color, tint));
+                shape.stroke = panel.border;
+                add_shape(&mut sprites, &shape, rect.center());
+            }
+            if let Ok(image) = world.get::<UiImage>(entity) {
+                let mut instance = SpriteInstance::new(to_world(rect.center()), rect.size(), image.texture)
+                    .with_color(color(multiply(image.color, tint)));
+                instance.draw_mode = image.draw_mode;
+                sprites.add(instance);
+            }
+            if let Ok(slider) = world.get::<UiSlider>(entity) {
+                add_slider(&mut sprites, &slider, rect);
+            }
+            if let Ok(label) = world.get::<UiLabel>(entity) {
+                let source = Text {
+                    content: label.text.clone(),
+                    font: label.font,
+                    size: label.size,
+                    color: label.color,
+                    align: label.align,
+                    wrap_width: label.wrap.then(|| rect.width()),
+                };
+                let glyphs = self.labels.entry(entity).or_insert_with(|| text.layout_glyphs(&source, assets));
+                if *glyphs.source() != source {
+                    *glyphs = text.layout_glyphs(&source, assets);
+                }
+                add_label(&mut sprites, glyphs, &label, rect);
+            }
+        }
+        if let Some((clip, scale)) = current.filter(|_| !sprites.is_empty()) {
+            views.extend(ui_view(clip, scale, scaling, sprites));
+        }
+        views
+    }
+}
+
+/// Canvas space is y-down; cameras look at a y-up world
+fn to_world(point: Vec2) -> Vec2 {
+    Vec2::new(point.x, -point.y)
+}
+
+fn color(c: [f32; 4]) -> Color {
+    Color::new(c[0], c[1], c[2], c[3])
+}
+
+fn multiply(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] * b[i])
+}
+
+/// Start and length of the part of a slider the handle center moves along
+fn slider_track(rect: Rect) -> (f32, f32) {
+    let radius = rect.height() / 2.0;
+    (rect.min.x + radius, (rect.width() - 2.0 * radius).max(0.0))
+}
+
+/// Add a shape centered on a canvas-space point
+fn add_shape(sprites: &mut SpriteBatch, shape: &Shape, center: Vec2) {
+    let transform = GlobalTransform {
+        position: to_world(center),
+        ..GlobalTransform::new()
+    };
+    if let Some(instance) = shape_instance(shape, &transform) {
+        sprites.add(instance);
+    }
+}
+
+/// Add a slider's track, fill and handle
+fn add_slider(sprites: &mut SpriteBatch, slider: &UiSlider, rect: Rect) {
+    let (start, length) = slider_track(rect);
+    let handle = Vec2::new(start + length * slider.fraction(), rect.center().y);
+    let height = rect.height() * SLIDER_TRACK_HEIGHT;
+
+    let track = Shape::rounded_rectangle(Vec2::new(rect.width(), height), height / 2.0).with_fill_color(slider.track_color);
+    add_shape(sprites, &track, rect.center());
+    let fill_width = handle.x - rect.min.x;
+    if fill_width > 0.0 {
+        let fill = Shape::rounded_rectangle(Vec2::new(fill_width, height), height / 2.0).with_fill_color(slider.fill_color);
+        add_shape(sprites, &fill, Vec2::new(rect.min.x + fill_width / 2.0, handle.y));
+    }
+    let knob = Shape::circle(rect.height() / 2.0).with_fill_color(slider.handle_color);
+    add_shape(sprites, &knob, handle);
+}
+
+/// Add a label's glyphs, aligned inside its rect
+fn add_label(sprites: &mut SpriteBatch, glyphs: &TextGlyphs, label: &UiLabel, rect: Rect) {
+    let x = match label.align {
+        TextAlign::Left => rect.min.x,
+        TextAlign::Center => rect.center().x,
+        TextAlign::Right => rect.max.x,
+    };
+    let top = match label.vertical_align {
+        VerticalAlign::Top => rect.min.y,
+        VerticalAlign::Middle => rect.center().y - glyphs.size.y / 2.0,
+        VerticalAlign::Bottom => rect.max.y - glyphs.size.y,
+    };
+    let transform = GlobalTransform {
+        position: to_world(Vec2::new(x, top.round())),
+        ..GlobalTransform::new()
+    };
+    for glyph in &glyphs.glyphs {
+        sprites.add(glyph_instance(glyph, glyphs.color, &transform));
+    }
+}
+
+/// Build the view showing a clip area of a canvas, or `None` if it covers
+/// no pixels
+fn ui_view(clip: Rect, scale: f32, scaling: &ViewportScaling, sprites: SpriteBatch) -> Option<UiView> {
+    let pixels_per_unit = scaling.scale / scale;
+    let area = scaling.viewport;
+    let origin = Vec2::new(area.x as f32, area.y as f32);
+    let end = origin + Vec2::new(area.width as f32, area.height as f32);
+
+    // Snap to whole pixels and show exactly the canvas area they cover
+    let min = (origin + clip.min * pixels_per_unit).round().max(origin);
+    let max = (origin + clip.max * pixels_per_unit).round().min(end);
+    if max.x <= min.x || max.y <= min.y {
+        return None;
+    }
+    let shown = Rect::new((min - origin) / pixels_per_unit, (max - origin) / pixels_per_unit);
+
+    let mut camera = Camera::new(shown.width(), shown.height());
+    camera.position = to_world(shown.center());
+    Some(UiView {
+        camera,
+        viewport: ScreenRect {
+            x: min.x as u32,
+            y: min.y as u32,
+            width: (max.x - min.x) as u32,
+            height: (max.y - min.y) as u32,
+        },
+        sprites,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use longhorn_core::{add_child, Canvas, RectTransform, UiLayout};
+    use longhorn_renderer::ScaleMode;
+
+    fn touch(input: &mut InputState, events: &[TouchEvent]) {
+        input.begin_frame();
+        for &event in events {
+            input.handle_event(event);
+        }
+    }
+
+    /// Canvas with a vertical list of buttons inside a scroll view
+    fn menu(world: &mut World) -> (EntityHandle, EntityHandle, Vec<EntityHandle>) {
+        let canvas = world.spawn().with(Canvas::new()).build();
+        let list = world
+            .spawn()
+            .with(RectTransform::new(Vec2::ZERO, Vec2::new(200.0, 100.0)))
+            .with(UiScrollView::vertical())
+            .with(UiLayout::column())
+            .build();
+        add_child(world, canvas, list).unwrap();
+        let buttons = (0..4)
+            .map(|_| {
+                let button = world
+                    .spawn()
+                    .with(RectTransform::new(Vec2::ZERO, Vec2::new(200.0, 50.0)))
+                    .with(UiPanel::default())
+                    .with(UiButton::new())
+                    .build();
+                add_child(world, list, button).unwrap();
+                button
+            })
+            .collect();
+        (canvas, list, buttons)
+    }
+
+    #[test]
+    fn test_click_bubbles_from_target_to_canvas() {
+        let mut world = World::new();
+        let (canvas, list, buttons) = menu(&mut world);
+        let scaling = ViewportScaling::identity(400, 300);
+        let mut ui = UiSystem::new();
+        let mut input = InputState::new();
+
+        touch(&mut input, &[TouchEvent::Start { x: 20.0, y: 60.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert!(ui.events().is_empty());
+        assert!(ui.captures_touch());
+        assert_eq!(ui.pressed_button(), Some(buttons[1]));
+
+        touch(&mut input, &[TouchEvent::End { x: 22.0, y: 62.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        let click = &ui.events()[0];
+        assert_eq!(click.kind, UiEventKind::Click);
+        assert_eq!(click.path, vec![buttons[1], list, canvas]);
+        assert_eq!(click.position, Vec2::new(22.0, 62.0));
+
+        // Releasing outside the pressed button doesn't click
+        touch(&mut input, &[TouchEvent::Start { x: 20.0, y: 60.0 }, TouchEvent::End { x: 20.0, y: 10.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert!(ui.events().is_empty());
+
+        // Disabled buttons don't click either
+        world.get_mut::<UiButton>(buttons[1]).unwrap().interactable = false;
+        touch(&mut input, &[TouchEvent::Start { x: 20.0, y: 60.0 }, TouchEvent::End { x: 20.0, y: 60.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert!(ui.events().is_empty());
+
+        // Touches outside any element reach the game
+        touch(&mut input, &[TouchEvent::Start { x: 300.0, y: 250.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert!(!ui.captures_touch());
+    }
+
+    #[test]
+    fn test_dragging_a_scroll_view_cancels_the_click() {
+        let mut world = World::new();
+        let (_, list, buttons) = menu(&mut world);
+        let scaling = ViewportScaling::identity(400, 300);
+        let mut ui = UiSystem::new();
+        let mut input = InputState::new();
+
+        touch(&mut input, &[TouchEvent::Start { x: 20.0, y: 90.0 }, TouchEvent::Move { x: 20.0, y: 85.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert_eq!(world.get::<UiScrollView>(list).unwrap().offset, Vec2::ZERO);
+
+        // Past the threshold the whole movement scrolls, clamped to the content
+        touch(&mut input, &[TouchEvent::Move { x: 20.0, y: 40.0 }, TouchEvent::Move { x: 20.0, y: -200.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert_eq!(world.get::<UiScrollView>(list).unwrap().offset, Vec2::new(0.0, 100.0));
+        assert_eq!(ui.pressed_button(), None);
+
+        touch(&mut input, &[TouchEvent::End { x: 20.0, y: -200.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert!(ui.events().is_empty());
+        assert_eq!(ui.tree().hit_test(&world, Vec2::new(20.0, 10.0)), ui.tree().index_of(buttons[2]));
+    }
+
+    #[test]
+    fn test_slider_follows_the_touch_in_steps() {
+        let mut world = World::new();
+        let canvas = world.spawn().with(Canvas::new()).build();
+        let slider = world
+            .spawn()
+            .with(RectTransform::new(Vec2::new(100.0, 100.0), Vec2::new(120.0, 20.0)))
+            .with(UiSlider::new(0.0, 10.0).with_step(1.0))
+            .build();
+        add_child(&mut world, canvas, slider).unwrap();
+        let scaling = ViewportScaling::identity(400, 300);
+        let mut ui = UiSystem::new();
+        let mut input = InputState::new();
+
+        // The handle travels between x = 110 and 210
+        touch(&mut input, &[TouchEvent::Start { x: 150.0, y: 110.0 }, TouchEvent::Move { x: 152.0, y: 130.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert_eq!(world.get::<UiSlider>(slider).unwrap().value, 4.0);
+        let kinds: Vec<_> = ui.events().iter().map(|event| event.kind).collect();
+        assert_eq!(kinds, vec![UiEventKind::ValueChanged(4.0)]);
+
+        touch(&mut input, &[TouchEvent::Move { x: 400.0, y: 0.0 }, TouchEvent::End { x: 400.0, y: 0.0 }]);
+        ui.update(&mut world, &input, &scaling);
+        assert_eq!(world.get::<UiSlider>(slider).unwrap().value, 10.0);
+        assert_eq!(ui.events().len(), 1);
+    }
+
+    #[test]
+    fn test_views_split_at_scroll_views_and_scale_with_the_screen() {
+        let mut world = World::new();
+        let (canvas, _, _) = menu(&mut world);
+        world.set(canvas, UiPanel::new([0.0, 0.0, 0.0, 0.5])).unwrap();
+        let mut assets = AssetManager::new(
+            longhorn_assets::FilesystemSource::new(std::env::temp_dir()),
+            std::env::temp_dir(),
+        );
+        let mut text = TextSystem::new();
+
+        // Design 400x300 on a 800x600 screen: everything doubles
+        let scaling = ViewportScaling::new(ScaleMode::Fit, Vec2::new(400.0, 300.0), 800, 600);
+        let views = UiSystem::new().collect(&world, &mut text, &mut assets, &scaling);
+        assert_eq!(views.len(), 2);
+        assert_eq!(views[0].viewport, ScreenRect::full(800, 600));
+        assert_eq!(views[0].camera.viewport_size, Vec2::new(400.0, 300.0));
+        assert_eq!(views[0].camera.position, Vec2::new(200.0, -150.0));
+        assert_eq!(views[1].viewport, ScreenRect { x: 0, y: 0, width: 400, height: 200 });
+        assert_eq!(views[1].sprites.len(), 4);
+
+        // A pixel canvas keeps one unit per screen pixel
+        world.set(canvas, Canvas::new().with_scale(longhorn_core::CanvasScale::Pixel)).unwrap();
+        let views = UiSystem::new().collect(&world, &mut text, &mut assets, &scaling);
+        assert_eq!(views[1].viewport, ScreenRect { x: 0, y: 0, width: 200, height: 100 });
+    }
+}
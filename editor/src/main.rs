@@ -25,6 +25,8 @@ struct EditorApp {
     editor: Editor,
     remote_server: Option<RemoteServer>,
     last_save_time: std::time::Instant,
+    /// When the last frame was rendered, for previewing particles outside play mode
+    last_frame_time: std::time::Instant,
 }
 
 struct GpuState {
@@ -108,6 +110,7 @@ impl EditorApp {
             editor,
             remote_server,
             last_save_time: std::time::Instant::now(),
+            last_frame_time: std::time::Instant::now(),
         }
     }
 
@@ -232,15 +235,39 @@ impl EditorApp {
 
         // Update game if in play mode and not paused
         let editor_state = self.editor.state();
-        if editor_state.mode == PlayMode::Play && !editor_state.paused {
-            let _ = self.engine.update();
+        let frame_time = self.last_frame_time.elapsed().as_secs_f32().min(0.1);
+        self.last_frame_time = std::time::Instant::now();
+        if editor_state.mode == PlayMode::Play {
+            if !editor_state.paused {
+                let _ = self.engine.update();
+            }
+        } else {
+            // Live preview of particle effects in the scene view
+            longhorn_engine::subsystems::particles::update(&mut self.engine, frame_time);
+            // Expire timed debug shapes outside of play mode too
+            self.engine.debug_draw_mut().advance(frame_time);
         }
 
-        // Propagate transforms before rendering
+        // Propagate transforms, lay out text and build tilemap geometry before rendering
         longhorn_core::propagate_transforms(self.engine.world_mut());
+        longhorn_engine::subsystems::text::update(&mut self.engine);
+        longhorn_engine::subsystems::tilemaps::update(&mut self.engine);
 
         // Render scene view (always) and game view (conditional on Play mode)
         if let Some(viewport_renderer) = &mut self.viewport_renderer {
+            // Re-upload textures whose import settings changed
+            for asset_id in self.editor.take_pending_texture_reloads() {
+                viewport_renderer.invalidate_texture(asset_id);
+            }
+
+            // Navigation overlay goes on a copy so the game view doesn't show it
+            let mut scene_debug = None;
+            if self.editor.viewport().show_navigation {
+                let mut debug = self.engine.debug_draw().clone();
+                longhorn_engine::subsystems::navigation::draw(&mut self.engine, &mut debug);
+                scene_debug = Some(debug);
+            }
+
             // Always render scene view with editor camera
             viewport_renderer.render_scene_view(
                 &gpu.device,
@@ -248,6 +275,7 @@ impl EditorApp {
                 self.engine.world(),
                 self.engine.assets(),
                 self.editor.editor_camera(),
+                Some(scene_debug.as_ref().unwrap_or(self.engine.debug_draw())),
             );
 
             // Conditionally render game view in Play mode
@@ -257,10 +285,17 @@ impl EditorApp {
                     &gpu.queue,
                     self.engine.world(),
                     self.engine.assets(),
+                    Some(self.engine.debug_draw()),
                     Some(&mut egui_state.renderer),
                 );
             }
 
+            // Report material shaders that failed to compile
+            for (asset_id, error) in viewport_renderer.take_material_errors() {
+                let path = self.engine.assets().material_path(asset_id).unwrap_or("<unknown>");
+                self.editor.console().error(format!("Material '{}' failed to compile: {}", path, error));
+            }
+
             // Handle pending screenshot requests
             if let Some(path) = self.editor.take_pending_screenshot() {
                 match viewport_renderer.capture_screenshot(&gpu.device, &gpu.queue, &path) {